@@ -1,24 +1,48 @@
 use eapp_utils::{
     borderless,
     codicons::{
-        ICON_DEBUG_START, ICON_DEBUG_STOP, ICON_LAYOUT_SIDEBAR_LEFT, ICON_NEW_FILE, ICON_SAVE,
-        ICON_SETTINGS, ICON_TERMINAL,
+        ICON_CLEAR_ALL, ICON_COPY, ICON_DEBUG_START, ICON_DEBUG_STOP, ICON_EXPORT,
+        ICON_LAYOUT_SIDEBAR_LEFT, ICON_LIST_FLAT, ICON_LIST_TREE, ICON_NEW_FILE, ICON_SAVE,
+        ICON_SETTINGS, ICON_TAG, ICON_TAG_ADD, ICON_TERMINAL, ICON_WAND,
     },
     get_body_font_id, get_button_height,
-    global_hotkey::{Code, GlobalHotkeyHandler, KeyMap, Modifiers},
+    global_hotkey::{ChordMap, Code, GlobalHotkeyHandler, KeyMap, Modifiers},
+    persistent_window::PersistentWindow,
     ui_font_selector::UiFontSelector,
-    widgets::simple_widgets::{
-        PlainButton, auto_selectable, frameless_btn, get_theme_button, theme_button,
+    widgets::{
+        command_palette::{Command, CommandPalette},
+        confirm::Confirm,
+        nav_list::NavList,
+        simple_widgets::{
+            PlainButton, auto_selectable, editable_label, frameless_btn, get_theme_button,
+            theme_button,
+        },
     },
 };
 use eframe::egui::{self, Align2, Color32, PopupCloseBehavior, UiBuilder, Vec2};
 use serde::{Deserialize, Serialize};
 
 use crate::auto_script::{
-    CONSOLE_SYSTEM_LOG_PREFIEX, script_editor::ScriptEditor, script_executor::ScriptExecutor,
+    console::{ConsoleView, LogLevel},
+    failsafe::{Corner, FailsafeOptions},
+    formatter::FormatOptions,
+    script_editor::ScriptEditor,
+    script_error::ScriptError,
+    script_executor::ScriptExecutor,
     script_manager::ScriptManager,
+    sound::SoundOptions,
 };
 
+/// Change to the script list, applied after the list is shown
+enum ListAction {
+    Delete(usize),
+    ToggleFailsafe(usize),
+    ToggleTag(usize, String),
+    AddTag(usize, String),
+    DeleteTag(String),
+    Move(usize, usize),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
 pub enum HotKeyAction {
     #[default]
@@ -30,28 +54,36 @@ pub struct App {
     editor: ScriptEditor,
     executor: ScriptExecutor,
     manager: ScriptManager,
+    format_options: FormatOptions,
     search_query: String,
+    tag_filter: Option<String>,
+    group_by_tag: bool,
+    new_tag: String,
     cur_sel: usize,
     cur_rename: Option<usize>,
-    check_error: Option<String>,
+    check_error: Option<ScriptError>,
     error: Option<String>,
     handler: GlobalHotkeyHandler<HotKeyAction>,
     script_changed: bool,
     selector: UiFontSelector,
-    show_confirm_modal: bool,
+    window: PersistentWindow,
+    confirm_close: Confirm<bool>,
+    allow_to_close: bool,
     show_console: bool,
+    console_view: ConsoleView,
     show_left_panel: bool,
+    palette: CommandPalette<App>,
 }
 
 impl App {
+    const GROUP_BY_TAG_KEY: &'static str = "group_by_tag";
+
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        let manager = match ScriptManager::load() {
-            Ok(manager) => manager,
-            Err(err) => {
-                log::error!("Error when load `ScriptManager`: {err}");
-                ScriptManager::default()
-            }
-        };
+        let manager = eapp_utils::capture_error_or!(
+            err => log::error!("Error when load `ScriptManager`: {err}"),
+            ScriptManager::default(),
+            ScriptManager::load()?
+        );
 
         let mut error = None;
 
@@ -70,13 +102,22 @@ impl App {
                 KeyMap::<HotKeyAction>::default()
             };
 
-            if  key_map.is_empty() {
+            let chord_map = if let Some(storage) = cc.storage {
+                eframe::get_value(storage, ChordMap::<HotKeyAction>::KEY).unwrap_or_default()
+            } else {
+                ChordMap::<HotKeyAction>::default()
+            };
+
+            if  key_map.is_empty() && chord_map.is_empty() {
                 handler.register_hotkey(HotKeyAction::RunScript, Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyB)?;
                 handler.register_hotkey(HotKeyAction::CancelScript, Some(Modifiers::CONTROL | Modifiers::SHIFT), Code::KeyE)?;
             } else {
                 for (hotkey, action) in key_map.values() {
                     handler.register_hotkey(*action, Some(hotkey.mods), hotkey.key)?;
                 }
+                for (chord, action) in chord_map.iter() {
+                    handler.register_chord(*action, chord)?;
+                }
             }
 
             handler
@@ -88,11 +129,39 @@ impl App {
             UiFontSelector::default()
         };
 
+        let format_options = if let Some(storage) = cc.storage {
+            eframe::get_value(storage, FormatOptions::KEY).unwrap_or_default()
+        } else {
+            FormatOptions::default()
+        };
+
+        let console_view = if let Some(storage) = cc.storage {
+            eframe::get_value(storage, ConsoleView::KEY).unwrap_or_default()
+        } else {
+            ConsoleView::default()
+        };
+
+        let group_by_tag = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, Self::GROUP_BY_TAG_KEY))
+            .unwrap_or(false);
+
+        let mut executor = ScriptExecutor::new();
+        if let Some(storage) = cc.storage {
+            executor.failsafe =
+                eframe::get_value(storage, FailsafeOptions::KEY).unwrap_or_default();
+            executor.sound = eframe::get_value(storage, SoundOptions::KEY).unwrap_or_default();
+        }
+
         let mut this = Self {
             editor: ScriptEditor::default(),
-            executor: ScriptExecutor::new(),
+            executor,
             manager,
+            format_options,
             search_query: String::new(),
+            tag_filter: None,
+            group_by_tag,
+            new_tag: String::new(),
             cur_sel: 0,
             cur_rename: None,
             check_error: None,
@@ -100,9 +169,13 @@ impl App {
             handler,
             script_changed: false,
             selector,
-            show_confirm_modal: false,
+            window: PersistentWindow::new(cc),
+            confirm_close: Confirm::new(),
+            allow_to_close: false,
             show_console: true,
+            console_view,
             show_left_panel: true,
+            palette: Self::command_palette(),
         };
 
         this.rebuild_fonts(&cc.egui_ctx);
@@ -155,16 +228,23 @@ impl App {
             }
 
             if frameless_btn(ui, ICON_SAVE.to_string()).clicked() {
-                match self.manager.save() {
-                    Ok(_) => self.script_changed = false,
-                    Err(err) => log::error!("Error when save `ScriptManager`: {err}"),
-                }
+                self.save_scripts(ui.ctx());
+            }
+
+            if frameless_btn(ui, ICON_WAND.to_string())
+                .on_hover_text("Format script")
+                .clicked()
+            {
+                self.format_cur_script(ui.ctx());
             }
 
             egui::Popup::menu(&frameless_btn(ui, ICON_SETTINGS.to_string()))
                 .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
                 .show(|ui| {
                     self.ui_show_global_hotkeys(ui);
+                    self.ui_show_format_options(ui);
+                    self.ui_show_failsafe_options(ui);
+                    self.ui_show_sound_options(ui);
                 });
 
             if ui
@@ -197,56 +277,395 @@ impl App {
         });
     }
 
+    fn command_palette() -> CommandPalette<App> {
+        let mut palette = CommandPalette::new();
+        palette.register(Command::new("run", "Run Script", |app: &mut App, _| {
+            app.run_cur_script()
+        }));
+        palette.register(Command::new("stop", "Stop Script", |app: &mut App, _| {
+            app.executor.cancel()
+        }));
+        palette.register(Command::new("new", "New Script", |app: &mut App, _| {
+            app.manager.new_script()
+        }));
+        palette.register(Command::new("save", "Save Scripts", App::save_scripts));
+        palette.register(Command::new(
+            "format",
+            "Format Script",
+            App::format_cur_script,
+        ));
+        palette.register(Command::new(
+            "toggle_console",
+            "Toggle Console",
+            |app: &mut App, _| app.show_console = !app.show_console,
+        ));
+        palette.register(Command::new(
+            "toggle_left_panel",
+            "Toggle Script List",
+            |app: &mut App, _| app.show_left_panel = !app.show_left_panel,
+        ));
+        palette
+    }
+
+    fn process_command_palette(&mut self, ctx: &egui::Context) {
+        let executing = self.executor.is_executing();
+        self.palette.set_enabled("run", !executing);
+        self.palette.set_enabled("stop", executing);
+
+        if let Some(action) = self.palette.show(ctx) {
+            action(self, ctx);
+        }
+    }
+
+    fn run_cur_script(&mut self) {
+        if let Some(script) = self.manager.scripts.get(self.cur_sel)
+            && !self.executor.is_executing()
+        {
+            self.executor
+                .execute_script(script.content.clone(), script.failsafe);
+        }
+    }
+
+    fn save_scripts(&mut self, ctx: &egui::Context) {
+        if self.format_options.format_on_save {
+            self.format_cur_script(ctx);
+        }
+
+        match self.manager.save() {
+            Ok(_) => self.script_changed = false,
+            Err(err) => log::error!("Error when save `ScriptManager`: {err}"),
+        }
+    }
+
     fn ui_left_panel(&mut self, ui: &mut egui::Ui) {
-        ui.add(
-            egui::TextEdit::singleline(&mut self.search_query)
-                .hint_text("Search Query")
-                .desired_width(f32::INFINITY),
-        );
+        ui.horizontal(|ui| {
+            let icon = if self.group_by_tag {
+                ICON_LIST_TREE
+            } else {
+                ICON_LIST_FLAT
+            };
+            if ui
+                .selectable_label(self.group_by_tag, icon.to_string())
+                .on_hover_text("Group by tag")
+                .clicked()
+            {
+                self.group_by_tag = !self.group_by_tag;
+            }
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.search_query)
+                    .hint_text("Search Query")
+                    .desired_width(f32::INFINITY),
+            );
+        });
+
+        let tags: Vec<String> = self.manager.tags().into_iter().map(str::to_owned).collect();
+        if self
+            .tag_filter
+            .as_ref()
+            .is_some_and(|tag| !tags.contains(tag))
+        {
+            self.tag_filter = None;
+        }
+
+        let mut action = None;
+
+        if !tags.is_empty() {
+            ui.horizontal(|ui| {
+                egui::ScrollArea::horizontal().show(ui, |ui| {
+                    auto_selectable(ui, &mut self.tag_filter, None, "ALL", false);
+                    for tag in &tags {
+                        auto_selectable(ui, &mut self.tag_filter, Some(tag.clone()), tag, false)
+                            .context_menu(|ui| {
+                                let text =
+                                    egui::RichText::new("Delete tag").color(Color32::LIGHT_RED);
+                                if frameless_btn(ui, text)
+                                    .on_hover_text("Remove the tag from all scripts")
+                                    .clicked()
+                                {
+                                    action = Some(ListAction::DeleteTag(tag.clone()));
+                                    ui.close();
+                                }
+                            });
+                    }
+                });
+            });
+        }
 
         ui.add_space(3.0);
 
+        let groups = self.script_groups(&tags);
+        let mut nav_order: Vec<usize> = Vec::new();
+        for idx in groups.iter().flat_map(|(_, scripts)| scripts) {
+            if !nav_order.contains(idx) {
+                nav_order.push(*idx);
+            }
+        }
+        let moved = self.process_list_keys(ui, &nav_order);
+
+        // Manual order is only arranged when every script is shown
+        let reorderable =
+            !self.group_by_tag && self.search_query.is_empty() && self.tag_filter.is_none();
+
         egui::ScrollArea::vertical().show(ui, |ui| {
             ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
-                if self.manager.is_empty() {
-                    return;
+                for (group, scripts) in &groups {
+                    match group {
+                        Some(group) => {
+                            egui::CollapsingHeader::new(group)
+                                .id_salt(("script_group", group))
+                                .default_open(true)
+                                .show(ui, |ui| {
+                                    for &idx in scripts {
+                                        self.ui_script_item(
+                                            ui,
+                                            idx,
+                                            &tags,
+                                            false,
+                                            moved,
+                                            &mut action,
+                                        );
+                                    }
+                                });
+                        }
+                        None => {
+                            for &idx in scripts {
+                                self.ui_script_item(
+                                    ui,
+                                    idx,
+                                    &tags,
+                                    reorderable,
+                                    moved,
+                                    &mut action,
+                                );
+                            }
+                        }
+                    }
                 }
+            })
+        });
 
-                let mut script_to_delete = None;
-                let query = self.search_query.to_ascii_lowercase();
+        if let Some(action) = action {
+            self.apply_list_action(action);
+        }
+    }
+
+    /// Scripts matching the search query and the tag filter, grouped by tag if needed,
+    /// scripts in more than one group appear in each of them
+    fn script_groups(&self, tags: &[String]) -> Vec<(Option<String>, Vec<usize>)> {
+        let query = self.search_query.to_lowercase();
+        let matched = |tag: Option<&String>| -> Vec<usize> {
+            self.manager
+                .iter()
+                .enumerate()
+                .filter(|(_, script)| script.matches(&query))
+                .filter(|(_, script)| match tag {
+                    Some(tag) => script.tags.contains(tag),
+                    None => true,
+                })
+                .map(|(idx, _)| idx)
+                .collect()
+        };
+
+        if !self.group_by_tag {
+            return vec![(None, matched(self.tag_filter.as_ref()))];
+        }
 
-                for (idx, script) in self.manager.iter().enumerate() {
-                    if !self.search_query.is_empty()
-                        && !script.name.to_ascii_lowercase().contains(&query)
+        let mut groups: Vec<_> = tags
+            .iter()
+            .filter(|tag| self.tag_filter.as_ref().is_none_or(|filter| filter == *tag))
+            .map(|tag| (Some(tag.clone()), matched(Some(tag))))
+            .filter(|(_, scripts)| !scripts.is_empty())
+            .collect();
+
+        if self.tag_filter.is_none() {
+            let untagged: Vec<_> = matched(None)
+                .into_iter()
+                .filter(|&idx| self.manager.scripts[idx].tags.is_empty())
+                .collect();
+            if !untagged.is_empty() {
+                groups.push((Some("Untagged".to_owned()), untagged));
+            }
+        }
+
+        groups
+    }
+
+    /// Keys of [`NavList`] move in the shown scripts, `Enter` focuses the editor,
+    /// returns whether the selection is moved
+    fn process_list_keys(&mut self, ui: &mut egui::Ui, nav_order: &[usize]) -> bool {
+        if nav_order.is_empty() || ui.memory(|mem| mem.focused().is_some()) {
+            return false;
+        }
+
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter))
+            && self.manager.scripts.get(self.cur_sel).is_some()
+        {
+            ScriptEditor::request_focus(ui.ctx());
+        }
+
+        let scripts = &self.manager.scripts;
+        let selected = nav_order
+            .iter()
+            .position(|&idx| idx == self.cur_sel)
+            .unwrap_or_default();
+        let Some(pos) = NavList::new("script_list", nav_order.len(), |i| {
+            scripts[nav_order[i]].name.clone()
+        })
+        .navigate(ui, selected) else {
+            return false;
+        };
+
+        let moved = nav_order[pos] != self.cur_sel;
+        self.cur_sel = nav_order[pos];
+        moved
+    }
+
+    fn ui_script_item(
+        &mut self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        tags: &[String],
+        reorderable: bool,
+        scroll: bool,
+        action: &mut Option<ListAction>,
+    ) {
+        if self.cur_rename == Some(idx) {
+            let mut editing = true;
+            if editable_label(ui, &mut self.manager.scripts[idx].name, &mut editing).changed() {
+                self.script_changed = true;
+            }
+            if !editing {
+                self.cur_rename = None;
+            }
+            return;
+        }
+
+        let script = &self.manager.scripts[idx];
+
+        let response = if reorderable {
+            let response = ui
+                .dnd_drag_source(egui::Id::new(("script_drag", idx)), idx, |ui| {
+                    auto_selectable(ui, &mut self.cur_sel, idx, &script.name, scroll)
+                })
+                .inner;
+
+            if let Some(from) = response.dnd_hover_payload::<usize>()
+                && *from != idx
+            {
+                let rect = response.rect;
+                let y = if *from > idx {
+                    rect.top()
+                } else {
+                    rect.bottom()
+                };
+                ui.painter()
+                    .hline(rect.x_range(), y, ui.visuals().selection.stroke);
+            }
+
+            if let Some(from) = response.dnd_release_payload::<usize>() {
+                *action = Some(ListAction::Move(*from, idx));
+            }
+
+            response
+        } else {
+            auto_selectable(ui, &mut self.cur_sel, idx, &script.name, scroll)
+        };
+
+        let response = if script.tags.is_empty() {
+            response
+        } else {
+            let tags = script.tags.iter().cloned().collect::<Vec<_>>().join(", ");
+            response.on_hover_text(format!("{ICON_TAG} {tags}"))
+        };
+
+        response.context_menu(|ui| {
+            if frameless_btn(ui, "Rename").clicked() {
+                self.cur_rename = Some(idx);
+                ui.close();
+            }
+
+            let mut failsafe = script.failsafe;
+            if ui
+                .checkbox(&mut failsafe, "Failsafe")
+                .on_hover_text("Allow the failsafe to abort this script")
+                .changed()
+            {
+                *action = Some(ListAction::ToggleFailsafe(idx));
+            }
+
+            ui.menu_button("Tags", |ui| {
+                for tag in tags {
+                    let mut has = script.tags.contains(tag);
+                    if ui.checkbox(&mut has, tag).changed() {
+                        *action = Some(ListAction::ToggleTag(idx, tag.clone()));
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    let edit = ui.add(
+                        egui::TextEdit::singleline(&mut self.new_tag)
+                            .hint_text("New tag")
+                            .desired_width(100.0),
+                    );
+                    let entered =
+                        edit.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    let tag = self.new_tag.trim();
+                    if (ui.button(ICON_TAG_ADD.to_string()).clicked() || entered) && !tag.is_empty()
                     {
-                        continue;
+                        *action = Some(ListAction::AddTag(idx, tag.to_owned()));
+                        self.new_tag.clear();
                     }
+                });
+            });
 
-                    auto_selectable(ui, &mut self.cur_sel, idx, &script.name, false).context_menu(
-                        |ui| {
-                            if frameless_btn(ui, "Rename").clicked() {
-                                self.cur_rename = Some(idx);
-                                ui.close();
-                            }
+            if frameless_btn(ui, egui::RichText::new("Delete").color(Color32::LIGHT_RED)).clicked()
+            {
+                *action = Some(ListAction::Delete(idx));
+                ui.close();
+            }
+        });
+    }
 
-                            if frameless_btn(
-                                ui,
-                                egui::RichText::new("Delete").color(Color32::LIGHT_RED),
-                            )
-                            .clicked()
-                            {
-                                script_to_delete = Some(idx);
-                                ui.close();
-                            }
-                        },
-                    );
+    fn apply_list_action(&mut self, action: ListAction) {
+        match action {
+            ListAction::Delete(idx) => self.manager.remove_script(idx),
+            ListAction::ToggleFailsafe(idx) => {
+                if let Some(script) = self.manager.scripts.get_mut(idx) {
+                    script.failsafe = !script.failsafe;
+                    self.script_changed = true;
                 }
-
-                if let Some(idx) = script_to_delete {
-                    self.manager.remove_script(idx);
+            }
+            ListAction::ToggleTag(idx, tag) => {
+                if let Some(script) = self.manager.scripts.get_mut(idx) {
+                    if !script.tags.remove(&tag) {
+                        script.tags.insert(tag);
+                    }
+                    self.script_changed = true;
                 }
-            })
-        });
+            }
+            ListAction::AddTag(idx, tag) => {
+                if let Some(script) = self.manager.scripts.get_mut(idx) {
+                    script.tags.insert(tag);
+                    self.script_changed = true;
+                }
+            }
+            ListAction::DeleteTag(tag) => {
+                self.manager.remove_tag(&tag);
+                self.script_changed = true;
+            }
+            ListAction::Move(from, to) => {
+                self.manager.move_script(from, to);
+                if self.cur_sel == from {
+                    self.cur_sel = to;
+                } else if from < self.cur_sel && self.cur_sel <= to {
+                    self.cur_sel -= 1;
+                } else if to <= self.cur_sel && self.cur_sel < from {
+                    self.cur_sel += 1;
+                }
+                self.script_changed = true;
+            }
+        }
     }
 
     fn ui_right_panel(&mut self, ui: &mut egui::Ui) {
@@ -261,7 +680,7 @@ impl App {
                 ui.with_layout(layout, |ui| {
                     let is_executing = self.executor.is_executing();
 
-                    let mut response = ui
+                    let response = ui
                         .add_enabled_ui(!is_executing, |ui| {
                             self.editor
                                 .ui(ui, &mut script.content, self.check_error.as_ref())
@@ -276,12 +695,6 @@ impl App {
                         }
                     }
 
-                    if let Some(err) = self.check_error.as_ref()
-                        && !self.editor.is_showing_completion()
-                    {
-                        response = response.on_hover_text_at_pointer(err);
-                    }
-
                     let rect = response.interact_rect;
                     let btn_size = egui::vec2(28.0, 28.0);
                     let btn_pos = rect.right_bottom() - btn_size - egui::vec2(4.0, 4.0);
@@ -304,7 +717,8 @@ impl App {
                                 if executing {
                                     self.executor.cancel();
                                 } else {
-                                    self.executor.execute_script(script.content.clone());
+                                    self.executor
+                                        .execute_script(script.content.clone(), script.failsafe);
                                 }
                             }
                         },
@@ -344,38 +758,97 @@ impl App {
             .inner_margin(8.0)
             .fill(get_bg_color(ui))
             .show(ui, |ui| {
-                egui::ScrollArea::vertical()
+                self.ui_console_toolbar(ui);
+                ui.separator();
+
+                let logs = self.executor.get_console_logs();
+                let visible: Vec<_> = logs.iter().filter(|e| self.console_view.shows(e)).collect();
+                let row_height = ui.text_style_height(&egui::TextStyle::Body);
+
+                // Only sticks while the view is at the bottom, scrolling up stays put
+                egui::ScrollArea::both()
                     .auto_shrink([false, false])
                     .stick_to_bottom(true)
                     .max_height(ui.available_height())
-                    .show(ui, |ui| {
-                        for log in self.executor.get_console_logs() {
-                            let color = if log.starts_with(CONSOLE_SYSTEM_LOG_PREFIEX) {
-                                ui.visuals().warn_fg_color
+                    .show_rows(ui, row_height, visible.len(), |ui, range| {
+                        for entry in &visible[range] {
+                            let color = match entry.level {
+                                LogLevel::System => ui.visuals().warn_fg_color,
+                                LogLevel::Print => ui.visuals().text_color(),
+                                LogLevel::Error => ui.visuals().error_fg_color,
+                            };
+
+                            let text = if self.console_view.show_time {
+                                format!("{}  {}", entry.time_string(), entry.text)
                             } else {
-                                ui.visuals().text_color()
+                                entry.text.clone()
                             };
-                            ui.label(egui::RichText::new(log).color(color));
+                            ui.add(
+                                egui::Label::new(egui::RichText::new(text).color(color)).extend(),
+                            );
                         }
                     });
             });
     }
 
-    fn ui_show_rename_modal(&mut self, ui: &mut egui::Ui) {
-        if let Some(idx) = self.cur_rename.take() {
-            egui::Modal::new(egui::Id::new("Rename")).show(ui.ctx(), |ui| {
-                let Some(script) = self.manager.scripts.get_mut(idx) else {
-                    return;
-                };
+    fn ui_console_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.console_view.filter)
+                    .hint_text("Filter")
+                    .desired_width(160.0),
+            );
 
-                ui.horizontal(|ui| {
-                    ui.text_edit_singleline(&mut script.name);
-                    if !ui.button("OK").clicked() {
-                        self.cur_rename = Some(idx);
-                    }
-                });
+            for level in LogLevel::ALL {
+                let show = self.console_view.level_mut(level);
+                if ui.selectable_label(*show, level.name()).clicked() {
+                    *show = !*show;
+                }
+            }
+
+            ui.checkbox(&mut self.console_view.show_time, "Time");
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.visuals_mut().button_frame = false;
+
+                if ui
+                    .button(ICON_CLEAR_ALL.to_string())
+                    .on_hover_text("Clear")
+                    .clicked()
+                {
+                    self.executor.console.clear();
+                }
+
+                if ui
+                    .button(ICON_EXPORT.to_string())
+                    .on_hover_text("Export the full log")
+                    .clicked()
+                    && let Some(path) = rfd::FileDialog::new()
+                        .add_filter("log", &["log", "txt"])
+                        .set_file_name("console.log")
+                        .save_file()
+                    && let Err(err) = self.executor.console.export(&path)
+                {
+                    self.error = Some(format!("Failed to export the console: {err}"));
+                }
+
+                if ui
+                    .button(ICON_COPY.to_string())
+                    .on_hover_text("Copy visible lines")
+                    .clicked()
+                {
+                    let text = self
+                        .executor
+                        .get_console_logs()
+                        .iter()
+                        .filter(|e| self.console_view.shows(e))
+                        .map(|e| e.to_line(self.console_view.show_time))
+                        .collect::<Vec<_>>()
+                        .join("\n");
+                    ui.ctx().copy_text(text);
+                }
             });
-        }
+        });
     }
 
     fn ui_show_error_modal(&mut self, ui: &mut egui::Ui) {
@@ -406,21 +879,80 @@ impl App {
         }
     }
 
-    fn ui_show_confirm_modal(&mut self, ui: &mut egui::Ui) {
-        if self.show_confirm_modal {
-            egui::Modal::new(egui::Id::new("confirm_close")).show(ui.ctx(), |ui| {
-                ui.label("There are unsaved changes, are you sure you want to exit?");
+    fn ui_show_format_options(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.vertical_centered(|ui| ui.heading("Formatter"));
 
-                ui.horizontal(|ui| {
-                    if ui.button("yes").clicked() {
-                        ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
-                    }
+        ui.horizontal(|ui| {
+            ui.label("Indent");
+            ui.selectable_value(&mut self.format_options.indent_width, 2, "2 spaces");
+            ui.selectable_value(&mut self.format_options.indent_width, 4, "4 spaces");
+        });
 
-                    if ui.button("no").clicked() {
-                        self.show_confirm_modal = false;
-                    }
-                });
+        ui.checkbox(&mut self.format_options.format_on_save, "Format on save");
+    }
+
+    fn ui_show_failsafe_options(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.vertical_centered(|ui| ui.heading("Failsafe"));
+
+        let failsafe = &mut self.executor.failsafe;
+        ui.checkbox(&mut failsafe.enabled, "Abort when cursor stays in corner")
+            .on_hover_text("Can be turned off for a single script in its context menu");
+
+        ui.add_enabled_ui(failsafe.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Corner");
+                egui::ComboBox::from_id_salt("failsafe_corner")
+                    .selected_text(failsafe.corner.name())
+                    .show_ui(ui, |ui| {
+                        for corner in Corner::ALL {
+                            ui.selectable_value(&mut failsafe.corner, corner, corner.name());
+                        }
+                    });
             });
+
+            ui.horizontal(|ui| {
+                ui.label("Dwell time");
+                ui.add(
+                    egui::DragValue::new(&mut failsafe.dwell_secs)
+                        .range(0.3..=5.0)
+                        .speed(0.05)
+                        .suffix("s"),
+                );
+            });
+        });
+    }
+
+    fn ui_show_sound_options(&mut self, ui: &mut egui::Ui) {
+        ui.separator();
+        ui.vertical_centered(|ui| ui.heading("Sound"));
+
+        ui.checkbox(
+            &mut self.executor.sound.chime_on_finish,
+            "Chime when a script finishes",
+        )
+        .on_hover_text("Also when it fails, but not when it's cancelled");
+    }
+
+    fn format_cur_script(&mut self, ctx: &egui::Context) {
+        let Some(script) = self.manager.scripts.get_mut(self.cur_sel) else {
+            return;
+        };
+
+        match ScriptEditor::format(ctx, &mut script.content, self.format_options.indent_width) {
+            Ok(changed) => self.script_changed |= changed,
+            Err(err) => self.executor.console.push(
+                LogLevel::System,
+                format!("Script is left unformatted: {err}"),
+            ),
+        }
+    }
+
+    fn ui_show_confirm_modal(&mut self, ui: &mut egui::Ui) {
+        if self.confirm_close.show(ui.ctx()) == Some(true) {
+            self.allow_to_close = true;
+            ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
         }
     }
 
@@ -448,13 +980,7 @@ impl App {
         ctx.request_repaint_after_secs(1.0);
         for action in self.handler.poll_events() {
             match action {
-                HotKeyAction::RunScript => {
-                    if let Some(script) = self.manager.scripts.get(self.cur_sel)
-                        && !self.executor.is_executing()
-                    {
-                        self.executor.execute_script(script.content.clone());
-                    }
-                }
+                HotKeyAction::RunScript => self.run_cur_script(),
                 HotKeyAction::CancelScript => self.executor.cancel(),
             }
         }
@@ -463,9 +989,14 @@ impl App {
     fn process_close_request(&mut self, ui: &mut egui::Ui) {
         if ui.ctx().input(|i| i.viewport().close_requested())
             && self.script_changed
-            && !self.show_confirm_modal
+            && !self.allow_to_close
         {
-            self.show_confirm_modal = true;
+            if !self.confirm_close.is_open() {
+                self.confirm_close.ask(
+                    "There are unsaved changes, are you sure you want to exit?",
+                    |yes| yes,
+                );
+            }
             ui.ctx()
                 .send_viewport_cmd(egui::ViewportCommand::CancelClose);
         }
@@ -484,7 +1015,18 @@ impl eframe::App for App {
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, UiFontSelector::KEY, &self.selector);
+        self.window.save(storage);
+        eframe::set_value(storage, FormatOptions::KEY, &self.format_options);
+        eframe::set_value(storage, FailsafeOptions::KEY, &self.executor.failsafe);
+        eframe::set_value(storage, SoundOptions::KEY, &self.executor.sound);
+        eframe::set_value(storage, ConsoleView::KEY, &self.console_view);
+        eframe::set_value(storage, Self::GROUP_BY_TAG_KEY, &self.group_by_tag);
         eframe::set_value(storage, eframe::APP_KEY, self.handler.get_key_map());
+        eframe::set_value(
+            storage,
+            ChordMap::<HotKeyAction>::KEY,
+            self.handler.get_chord_map(),
+        );
         if let Err(err) = self.manager.save() {
             log::error!("Error when save `ScriptManager`: {err}");
         }
@@ -522,8 +1064,8 @@ impl eframe::App for App {
             self.process_close_request(ui);
             self.ui_show_confirm_modal(ui);
 
-            self.ui_show_rename_modal(ui);
             self.ui_show_error_modal(ui);
+            self.process_command_palette(ctx);
             self.ui_contents(
                 &mut ui.new_child(UiBuilder::new().layout(*ui.layout()).max_rect(content_rect)),
             );
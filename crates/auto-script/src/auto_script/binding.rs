@@ -1,3 +1,4 @@
+use eapp_utils::platform::{self, WindowInfo};
 use mlua::{Error::RuntimeError, Lua, Result, Table, UserData, UserDataMethods, Value};
 use rustautogui::{MatchMode, MouseClick, RustAutoGui};
 use std::{
@@ -8,17 +9,26 @@ use std::{
     time::Duration,
 };
 
-use crate::auto_script::SCRIPT_EXECUTION_CANCELLED_MSG;
+use crate::auto_script::{
+    SCRIPT_EXECUTION_CANCELLED_MSG,
+    failsafe::{ScriptedMouse, to_pos},
+    sound,
+};
 
 pub struct AutoScript;
 
 impl AutoScript {
-    pub fn register_with_cancel_flag(lua: &Lua, cancel_flag: Arc<AtomicBool>) -> mlua::Result<()> {
+    pub fn register_with_cancel_flag(
+        lua: &Lua,
+        cancel_flag: Arc<AtomicBool>,
+        scripted_mouse: Arc<ScriptedMouse>,
+    ) -> mlua::Result<()> {
         let constructor = lua.create_function(move |_, debug: bool| {
             let inner = RustAutoGui::new(debug).map_err(|e| RuntimeError(e.to_string()))?;
             Ok(AutoGui {
                 inner,
                 cancel_flag: cancel_flag.clone(),
+                scripted_mouse: scripted_mouse.clone(),
             })
         })?;
         let table = lua.create_table()?;
@@ -32,12 +42,22 @@ impl UserData for AutoScript {}
 pub struct AutoGui {
     pub inner: RustAutoGui,
     pub cancel_flag: Arc<AtomicBool>,
+    pub scripted_mouse: Arc<ScriptedMouse>,
 }
 
 impl AutoGui {
     fn is_cancelled(&self) -> bool {
         self.cancel_flag.load(Ordering::SeqCst)
     }
+
+    /// Runs `f` as a scripted mouse move, so that the failsafe ignores it
+    fn scripted_move<T>(&self, f: impl FnOnce(&Self) -> T) -> T {
+        self.scripted_mouse.begin_move();
+        let result = f(self);
+        self.scripted_mouse
+            .end_move(self.inner.get_mouse_position().ok().map(to_pos));
+        result
+    }
 }
 
 impl UserData for AutoGui {
@@ -87,7 +107,7 @@ impl UserData for AutoGui {
             ($name:literal, $fn_call:ident, ($($arg:ident : $ty:ty),*)) => {
                 methods.add_method($name, |_, this, ($($arg),*): ($($ty),*)| {
                     cancelled!(this);
-                    this.inner.$fn_call($($arg),*)
+                    this.scripted_move(|this| this.inner.$fn_call($($arg),*))
                         .map_err(|e| RuntimeError(e.to_string()))
                 });
             };
@@ -102,8 +122,7 @@ impl UserData for AutoGui {
             "move_mouse_to",
             |_, this, (ox, oy, t): (Option<u32>, Option<u32>, f32)| {
                 cancelled!(this);
-                this.inner
-                    .move_mouse_to(ox, oy, t)
+                this.scripted_move(|this| this.inner.move_mouse_to(ox, oy, t))
                     .map_err(|e| RuntimeError(e.to_string()))
             },
         );
@@ -111,8 +130,7 @@ impl UserData for AutoGui {
             "drag_mouse_to",
             |_, this, (ox, oy, t): (Option<u32>, Option<u32>, f32)| {
                 cancelled!(this);
-                this.inner
-                    .drag_mouse_to(ox, oy, t)
+                this.scripted_move(|this| this.inner.drag_mouse_to(ox, oy, t))
                     .map_err(|e| RuntimeError(e.to_string()))
             },
         );
@@ -190,13 +208,97 @@ impl UserData for AutoGui {
                 })
         });
 
+        // ----- Window targeting -----
+        methods.add_method("find_window", |lua, this, title: String| {
+            cancelled!(this);
+            match platform::find_window(&title).map_err(|e| RuntimeError(e.to_string()))? {
+                Some(window) => parser::window_to_table(lua, window).map(Value::Table),
+                None => Ok(Value::Nil),
+            }
+        });
+        methods.add_method("focus_window", |_, this, title: String| {
+            cancelled!(this);
+            match platform::find_window(&title).map_err(|e| RuntimeError(e.to_string()))? {
+                Some(window) => {
+                    platform::focus_window(&window).map_err(|e| RuntimeError(e.to_string()))
+                }
+                None => Ok(false),
+            }
+        });
+        methods.add_method(
+            "move_mouse_in_window",
+            |_, this, (title, rx, ry, t): (String, f32, f32, f32)| {
+                cancelled!(this);
+                let (x, y) = parser::find_window(&title)?.to_screen(rx, ry);
+                this.scripted_move(|this| {
+                    this.inner
+                        .move_mouse_to_pos(x.max(0) as u32, y.max(0) as u32, t)
+                })
+                .map_err(|e| RuntimeError(e.to_string()))
+            },
+        );
+        methods.add_method(
+            "click_in_window",
+            |_, this, (title, rx, ry, button): (String, f32, f32, Option<String>)| {
+                cancelled!(this);
+                let btn = parser::parse_mouse_click(button.unwrap_or_else(|| "left".to_owned()))?;
+                let (x, y) = parser::find_window(&title)?.to_screen(rx, ry);
+                this.scripted_move(|this| {
+                    this.inner
+                        .move_mouse_to_pos(x.max(0) as u32, y.max(0) as u32, 0.0)
+                })
+                .map_err(|e| RuntimeError(e.to_string()))?;
+                this.inner
+                    .click(btn)
+                    .map_err(|e| RuntimeError(e.to_string()))
+            },
+        );
+
         // ----- Sleep binding -----
         methods.add_method("sleep", |_, this, secs: f32| {
+            const SLICE: Duration = Duration::from_millis(50);
+
+            let mut remaining = Duration::from_secs_f32(secs);
+            while !remaining.is_zero() {
+                cancelled!(this);
+                let slice = remaining.min(SLICE);
+                std::thread::sleep(slice);
+                remaining -= slice;
+            }
+
             cancelled!(this);
-            std::thread::sleep(Duration::from_secs_f32(secs));
             Ok(Value::Nil)
         });
 
+        // ----- Sound bindings -----
+        methods.add_method(
+            "beep",
+            |_, this, (frequency, secs): (Option<f32>, Option<f32>)| {
+                cancelled!(this);
+                let frequency = frequency.unwrap_or(sound::DEFAULT_FREQUENCY);
+                if !(20.0..=20000.0).contains(&frequency) {
+                    return Err(RuntimeError(format!(
+                        "Frequency {frequency} is out of 20 to 20000 Hz"
+                    )));
+                }
+                let duration = match secs {
+                    Some(secs) if secs.is_finite() && secs >= 0.0 => Duration::from_secs_f32(secs),
+                    Some(secs) => return Err(RuntimeError(format!("Invalid duration {secs}"))),
+                    None => sound::DEFAULT_DURATION,
+                };
+
+                sound::beep(frequency, duration, || this.is_cancelled()).map_err(RuntimeError)?;
+                cancelled!(this);
+                Ok(())
+            },
+        );
+        methods.add_method("play_sound", |_, this, path: String| {
+            cancelled!(this);
+            sound::play_sound(&path, || this.is_cancelled()).map_err(RuntimeError)?;
+            cancelled!(this);
+            Ok(())
+        });
+
         // ----- Image template methods -----
         methods.add_method_mut(
             "store_image",
@@ -227,10 +329,13 @@ impl UserData for AutoGui {
             "find_image_on_screen_and_move",
             |lua, this, (precision, time, alias): (f32, f32, String)| {
                 cancelled!(this);
+                this.scripted_mouse.begin_move();
                 let r = this
                     .inner
-                    .find_stored_image_on_screen_and_move_mouse(precision, time, &alias)
-                    .map_err(|e| RuntimeError(e.to_string()))?;
+                    .find_stored_image_on_screen_and_move_mouse(precision, time, &alias);
+                this.scripted_mouse
+                    .end_move(this.inner.get_mouse_position().ok().map(to_pos));
+                let r = r.map_err(|e| RuntimeError(e.to_string()))?;
                 parser::results_to_table(lua, r)
             },
         );
@@ -249,12 +354,13 @@ impl UserData for AutoGui {
             "loop_find_image_on_screen_and_move",
             |lua, this, (precision, time, timeout, alias): (f32, f32, u64, String)| {
                 cancelled!(this);
-                let r = this
-                    .inner
-                    .loop_find_stored_image_on_screen_and_move_mouse(
-                        precision, time, timeout, &alias,
-                    )
-                    .map_err(|e| RuntimeError(e.to_string()))?;
+                this.scripted_mouse.begin_move();
+                let r = this.inner.loop_find_stored_image_on_screen_and_move_mouse(
+                    precision, time, timeout, &alias,
+                );
+                this.scripted_mouse
+                    .end_move(this.inner.get_mouse_position().ok().map(to_pos));
+                let r = r.map_err(|e| RuntimeError(e.to_string()))?;
                 parser::results_to_table(lua, r)
             },
         );
@@ -293,6 +399,22 @@ mod parser {
         }
     }
 
+    pub fn find_window(title: &str) -> Result<WindowInfo> {
+        platform::find_window(title)
+            .map_err(|e| RuntimeError(e.to_string()))?
+            .ok_or_else(|| RuntimeError(format!("Unable to find window: {title}")))
+    }
+
+    pub fn window_to_table(lua: &Lua, window: WindowInfo) -> Result<Table> {
+        let tbl = lua.create_table()?;
+        tbl.set("x", window.x)?;
+        tbl.set("y", window.y)?;
+        tbl.set("w", window.width)?;
+        tbl.set("h", window.height)?;
+        tbl.set("title", window.title)?;
+        Ok(tbl)
+    }
+
     pub fn results_to_table(lua: &Lua, opt: Option<Vec<(u32, u32, f32)>>) -> Result<Value> {
         if let Some(vec) = opt {
             let tbl = lua.create_table()?;
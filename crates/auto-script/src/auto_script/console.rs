@@ -1,30 +1,167 @@
 use mlua::{Lua, Value};
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::mpsc::{Receiver, Sender};
+use time::{OffsetDateTime, UtcOffset};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    /// From the executor itself
+    System,
+
+    /// From `print` of scripts
+    Print,
+
+    /// Scripts failed
+    Error,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 3] = [LogLevel::System, LogLevel::Print, LogLevel::Error];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::System => "System",
+            LogLevel::Print => "Print",
+            LogLevel::Error => "Error",
+        }
+    }
+}
+
+pub struct LogEntry {
+    pub time: OffsetDateTime,
+    pub level: LogLevel,
+    pub text: String,
+}
+
+impl LogEntry {
+    /// e.g. `13:05:42`
+    pub fn time_string(&self) -> String {
+        format!(
+            "{:02}:{:02}:{:02}",
+            self.time.hour(),
+            self.time.minute(),
+            self.time.second()
+        )
+    }
+
+    /// How the entry is copied and exported
+    pub fn to_line(&self, with_time: bool) -> String {
+        if with_time {
+            format!(
+                "{} [{}] {}",
+                self.time_string(),
+                self.level.name(),
+                self.text
+            )
+        } else {
+            format!("[{}] {}", self.level.name(), self.text)
+        }
+    }
+}
+
+/// Which entries the console shows
+#[derive(Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct ConsoleView {
+    #[serde(skip)]
+    pub filter: String,
+    pub show_system: bool,
+    pub show_print: bool,
+    pub show_error: bool,
+    pub show_time: bool,
+}
+
+impl Default for ConsoleView {
+    fn default() -> Self {
+        Self {
+            filter: String::new(),
+            show_system: true,
+            show_print: true,
+            show_error: true,
+            show_time: false,
+        }
+    }
+}
+
+impl ConsoleView {
+    pub const KEY: &str = "console_view";
+
+    pub fn level_mut(&mut self, level: LogLevel) -> &mut bool {
+        match level {
+            LogLevel::System => &mut self.show_system,
+            LogLevel::Print => &mut self.show_print,
+            LogLevel::Error => &mut self.show_error,
+        }
+    }
+
+    pub fn shows(&self, entry: &LogEntry) -> bool {
+        let level = match entry.level {
+            LogLevel::System => self.show_system,
+            LogLevel::Print => self.show_print,
+            LogLevel::Error => self.show_error,
+        };
+        level && entry.text.contains(self.filter.as_str())
+    }
+}
 
 pub struct Console {
-    pub logs: VecDeque<String>,
-    max_lines: usize,
+    logs: VecDeque<LogEntry>,
     receiver: Receiver<String>,
+
+    /// Taken at startup, getting it later may fail on some platforms
+    offset: UtcOffset,
 }
 
 impl Console {
+    pub const MAX_LINES: usize = 10_000;
+
     pub fn new(receiver: Receiver<String>) -> Self {
         Self {
             logs: VecDeque::new(),
-            max_lines: 500,
             receiver,
+            offset: UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
+        }
+    }
+
+    pub fn logs(&self) -> &VecDeque<LogEntry> {
+        &self.logs
+    }
+
+    /// Adds an entry per line of `text`, the oldest entries are dropped past [`Console::MAX_LINES`]
+    pub fn push(&mut self, level: LogLevel, text: impl AsRef<str>) {
+        let time = OffsetDateTime::now_utc().to_offset(self.offset);
+        for line in text.as_ref().split('\n') {
+            self.logs.push_back(LogEntry {
+                time,
+                level,
+                text: line.strip_suffix('\r').unwrap_or(line).to_owned(),
+            });
         }
+
+        while self.logs.len() > Self::MAX_LINES {
+            self.logs.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.logs.clear();
     }
 
     pub fn update(&mut self) {
         while let Ok(log) = self.receiver.try_recv() {
-            self.logs.push_back(log);
+            self.push(LogLevel::Print, log);
+        }
+    }
 
-            if self.logs.len() > self.max_lines {
-                self.logs.pop_front();
-            }
+    /// Writes all the entries to `path`, one per line
+    pub fn export(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let mut content = String::new();
+        for entry in &self.logs {
+            content.push_str(&entry.to_line(true));
+            content.push('\n');
         }
+        std::fs::write(path, content)
     }
 }
 
@@ -61,3 +198,46 @@ pub fn inject_lua_console(lua: &Lua, sender: Sender<String>) -> mlua::Result<()>
     lua.globals().set("print", print_func)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::channel;
+
+    #[test]
+    fn push_splits_lines_and_drops_the_oldest() {
+        let (_sender, receiver) = channel();
+        let mut console = Console::new(receiver);
+
+        console.push(LogLevel::Print, "a\nb");
+        assert_eq!(console.logs().len(), 2);
+
+        for i in 0..Console::MAX_LINES {
+            console.push(LogLevel::System, i.to_string());
+        }
+        assert_eq!(console.logs().len(), Console::MAX_LINES);
+        assert_eq!(console.logs().front().unwrap().text, "0");
+    }
+
+    #[test]
+    fn view_filters_by_level_and_text() {
+        let (_sender, receiver) = channel();
+        let mut console = Console::new(receiver);
+        console.push(LogLevel::Print, "hello");
+        console.push(LogLevel::Error, "bad hello");
+
+        let mut view = ConsoleView {
+            filter: "hello".to_owned(),
+            ..Default::default()
+        };
+        *view.level_mut(LogLevel::Error) = false;
+
+        let shown: Vec<_> = console
+            .logs()
+            .iter()
+            .filter(|e| view.shows(e))
+            .map(|e| e.text.as_str())
+            .collect();
+        assert_eq!(shown, ["hello"]);
+    }
+}
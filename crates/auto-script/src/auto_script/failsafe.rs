@@ -0,0 +1,170 @@
+use eapp_utils::task::Task;
+use rustautogui::RustAutoGui;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc::{RecvTimeoutError, channel},
+    },
+    time::{Duration, Instant},
+};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Corner {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    pub const ALL: [Corner; 4] = [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Corner::TopLeft => "top-left",
+            Corner::TopRight => "top-right",
+            Corner::BottomLeft => "bottom-left",
+            Corner::BottomRight => "bottom-right",
+        }
+    }
+
+    fn contains(self, (x, y): (i64, i64), (width, height): (i64, i64)) -> bool {
+        const MARGIN: i64 = 2;
+
+        let left = x < MARGIN;
+        let top = y < MARGIN;
+        let right = x >= width - MARGIN;
+        let bottom = y >= height - MARGIN;
+
+        match self {
+            Corner::TopLeft => left && top,
+            Corner::TopRight => right && top,
+            Corner::BottomLeft => left && bottom,
+            Corner::BottomRight => right && bottom,
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(default)]
+pub struct FailsafeOptions {
+    pub enabled: bool,
+    pub corner: Corner,
+
+    /// How long the cursor has to stay in the corner before aborting
+    pub dwell_secs: f32,
+}
+
+impl Default for FailsafeOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            corner: Corner::default(),
+            dwell_secs: 0.5,
+        }
+    }
+}
+
+impl FailsafeOptions {
+    pub const KEY: &str = "failsafe_options";
+}
+
+pub fn to_pos<T: Into<i64>>((x, y): (T, T)) -> (i64, i64) {
+    (x.into(), y.into())
+}
+
+/// Mouse moves made by the script, so that the watcher does not take them for the user
+#[derive(Default)]
+pub struct ScriptedMouse {
+    moving: AtomicUsize,
+    last_pos: Mutex<Option<(i64, i64)>>,
+}
+
+impl ScriptedMouse {
+    pub fn begin_move(&self) {
+        self.moving.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// `pos` is where the script left the cursor
+    pub fn end_move(&self, pos: Option<(i64, i64)>) {
+        *self.last_pos.lock().unwrap() = pos;
+        self.moving.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    fn is_scripted(&self, pos: (i64, i64)) -> bool {
+        self.moving.load(Ordering::SeqCst) > 0 || *self.last_pos.lock().unwrap() == Some(pos)
+    }
+}
+
+/// Watches the cursor while a script runs, cancels it once the cursor dwells in the corner
+pub struct FailsafeWatcher {
+    task: Task<()>,
+}
+
+impl FailsafeWatcher {
+    const INTERVAL: Duration = Duration::from_millis(100);
+    const MIN_SAMPLES: u32 = 3;
+
+    pub fn spawn(
+        options: FailsafeOptions,
+        mouse: Arc<ScriptedMouse>,
+        cancel_flag: Arc<AtomicBool>,
+        triggered: Arc<AtomicBool>,
+    ) -> Self {
+        let (cancel_sender, cancel_receiver) = channel();
+
+        let task = Task::new(cancel_sender, move || {
+            let mut gui = match RustAutoGui::new(false) {
+                Ok(gui) => gui,
+                Err(err) => {
+                    log::error!("Failsafe is unavailable: {err}");
+                    return;
+                }
+            };
+
+            let screen_size = to_pos(gui.get_screen_size());
+            let dwell = Duration::from_secs_f32(options.dwell_secs.max(0.0));
+            let mut samples = 0;
+            let mut since = Instant::now();
+
+            while let Err(RecvTimeoutError::Timeout) = cancel_receiver.recv_timeout(Self::INTERVAL)
+            {
+                let Ok(pos) = gui.get_mouse_position().map(to_pos) else {
+                    continue;
+                };
+
+                if !options.corner.contains(pos, screen_size) || mouse.is_scripted(pos) {
+                    samples = 0;
+                    continue;
+                }
+
+                if samples == 0 {
+                    since = Instant::now();
+                }
+                samples += 1;
+
+                if samples >= Self::MIN_SAMPLES && since.elapsed() >= dwell {
+                    triggered.store(true, Ordering::SeqCst);
+                    cancel_flag.store(true, Ordering::SeqCst);
+                    break;
+                }
+            }
+        });
+
+        Self { task }
+    }
+}
+
+impl Drop for FailsafeWatcher {
+    fn drop(&mut self) {
+        self.task.cancel();
+    }
+}
@@ -0,0 +1,465 @@
+//! A conservative Lua formatter, only indentation and trailing whitespace are touched,
+//! strings and comments are always kept as they are
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Deserialize, Serialize, Clone, Copy)]
+#[serde(default)]
+pub struct FormatOptions {
+    pub indent_width: usize,
+    pub format_on_save: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            format_on_save: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    pub const KEY: &str = "format_options";
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Token {
+    Function,
+    Do,
+    Then,
+    Elseif,
+    Else,
+    Repeat,
+    Until,
+    End,
+    Open(u8),
+    Close(u8),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
+            Token::Function => "function",
+            Token::Do => "do",
+            Token::Then => "then",
+            Token::Elseif => "elseif",
+            Token::Else => "else",
+            Token::Repeat => "repeat",
+            Token::Until => "until",
+            Token::End => "end",
+            Token::Open(c) | Token::Close(c) => return write!(f, "`{}`", *c as char),
+        };
+        write!(f, "`{text}`")
+    }
+}
+
+struct ScannedToken {
+    token: Token,
+    line: usize,
+    /// Only closing tokens appear before it in its line
+    leading: bool,
+}
+
+#[derive(Default)]
+struct Scan {
+    tokens: Vec<ScannedToken>,
+    /// Line ranges of strings and comments spanning multiple lines
+    multiline: Vec<(usize, usize)>,
+}
+
+/// Re-indents `source` based on its block structure, trims trailing whitespaces
+/// and ensures a single trailing newline.
+///
+/// Returns why the structure could not be understood, the source should be kept
+/// untouched in such case.
+pub fn format_lua(source: &str, indent_width: usize) -> Result<String, String> {
+    let scan = scan(source)?;
+    let lines: Vec<&str> = source.split('\n').collect();
+
+    let mut starts_in_long = vec![false; lines.len()];
+    let mut ends_in_long = vec![false; lines.len()];
+    for &(start, end) in &scan.multiline {
+        starts_in_long[start + 1..=end].fill(true);
+        ends_in_long[start..end].fill(true);
+    }
+
+    let mut stack: Vec<(Token, usize)> = Vec::new();
+    let mut formatted = Vec::with_capacity(lines.len());
+    let mut tokens = scan.tokens.iter().peekable();
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        let mut line_tokens = Vec::new();
+        while let Some(token) = tokens.next_if(|t| t.line == line_idx) {
+            line_tokens.push(token);
+        }
+
+        let leading = line_tokens.iter().take_while(|t| t.leading).count();
+        let level = indent_level(&stack[..stack.len().saturating_sub(leading)]);
+
+        for token in line_tokens {
+            apply(&mut stack, token.token, line_idx)?;
+        }
+
+        if starts_in_long[line_idx] {
+            formatted.push(line.to_string());
+            continue;
+        }
+
+        let trimmed = if ends_in_long[line_idx] {
+            line.trim_start()
+        } else {
+            line.trim()
+        };
+
+        if trimmed.is_empty() {
+            formatted.push(String::new());
+        } else {
+            formatted.push(format!("{}{trimmed}", " ".repeat(level * indent_width)));
+        }
+    }
+
+    if let Some((token, line)) = stack.last() {
+        return Err(format!("{token} at line {} is never closed", line + 1));
+    }
+
+    let mut result = formatted.join("\n");
+    result.truncate(result.trim_end().len());
+    if !result.is_empty() {
+        result.push('\n');
+    }
+
+    Ok(result)
+}
+
+/// Maps a char index of `old` to the same line and (clamped) column of `new`
+pub fn map_cursor(old: &str, new: &str, char_idx: usize) -> usize {
+    let (mut line, mut column) = (0, 0);
+    for c in old.chars().take(char_idx) {
+        if c == '\n' {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+    }
+
+    let new_lines: Vec<&str> = new.split('\n').collect();
+    let line = line.min(new_lines.len() - 1);
+
+    let line_start: usize = new_lines[..line]
+        .iter()
+        .map(|l| l.chars().count() + 1)
+        .sum();
+    line_start + column.min(new_lines[line].chars().count())
+}
+
+/// Nested blocks opened in the same line only indent once
+fn indent_level(stack: &[(Token, usize)]) -> usize {
+    stack
+        .iter()
+        .enumerate()
+        .filter(|(idx, (_, line))| *idx == 0 || stack[idx - 1].1 != *line)
+        .count()
+}
+
+fn apply(stack: &mut Vec<(Token, usize)>, token: Token, line: usize) -> Result<(), String> {
+    let top = stack.last().map(|(t, _)| *t);
+    let matched = match token {
+        Token::Function | Token::Do | Token::Then | Token::Repeat | Token::Open(_) => {
+            stack.push((token, line));
+            return Ok(());
+        }
+        Token::Elseif => top == Some(Token::Then),
+        Token::Else => top == Some(Token::Then),
+        Token::Until => top == Some(Token::Repeat),
+        Token::End => matches!(
+            top,
+            Some(Token::Function | Token::Do | Token::Then | Token::Else)
+        ),
+        Token::Close(c) => top == Some(Token::Open(matching_bracket(c))),
+    };
+
+    if !matched {
+        return Err(match stack.last() {
+            Some((open, open_line)) => format!(
+                "{token} at line {} does not match {open} at line {}",
+                line + 1,
+                open_line + 1
+            ),
+            None => format!("{token} at line {} has nothing to close", line + 1),
+        });
+    }
+
+    stack.pop();
+    if token == Token::Else {
+        stack.push((Token::Else, line));
+    }
+
+    Ok(())
+}
+
+fn matching_bracket(close: u8) -> u8 {
+    match close {
+        b')' => b'(',
+        b']' => b'[',
+        _ => b'{',
+    }
+}
+
+fn scan(source: &str) -> Result<Scan, String> {
+    let bytes = source.as_bytes();
+    let mut scan = Scan::default();
+    let mut line = 0;
+    let mut leading = true;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b'\n' => {
+                line += 1;
+                leading = true;
+                i += 1;
+            }
+            b' ' | b'\t' | b'\r' => i += 1,
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i += 2;
+                if let Some(level) = long_bracket_level(bytes, i) {
+                    let start = line;
+                    i = skip_long_bracket(bytes, i, level, &mut line)
+                        .ok_or_else(|| format!("unfinished long comment at line {}", start + 1))?;
+                    if line > start {
+                        scan.multiline.push((start, line));
+                    }
+                } else {
+                    while i < bytes.len() && bytes[i] != b'\n' {
+                        i += 1;
+                    }
+                }
+                leading = false;
+            }
+            b'[' if long_bracket_level(bytes, i).is_some() => {
+                let level = long_bracket_level(bytes, i).unwrap();
+                let start = line;
+                i = skip_long_bracket(bytes, i, level, &mut line)
+                    .ok_or_else(|| format!("unfinished long string at line {}", start + 1))?;
+                if line > start {
+                    scan.multiline.push((start, line));
+                }
+                leading = false;
+            }
+            b'"' | b'\'' => {
+                let start = line;
+                i += 1;
+                loop {
+                    match bytes.get(i) {
+                        Some(b'\\') => {
+                            if bytes.get(i + 1) == Some(&b'z') {
+                                i += 2;
+                                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                                    line += (bytes[i] == b'\n') as usize;
+                                    i += 1;
+                                }
+                            } else {
+                                line += (bytes.get(i + 1) == Some(&b'\n')) as usize;
+                                i += 2;
+                            }
+                        }
+                        Some(&c) if c == b => {
+                            i += 1;
+                            break;
+                        }
+                        Some(b'\n') | None => {
+                            return Err(format!("unfinished string at line {}", line + 1));
+                        }
+                        Some(_) => i += 1,
+                    }
+                }
+                if line > start {
+                    scan.multiline.push((start, line));
+                }
+                leading = false;
+            }
+            b'(' | b'[' | b'{' => {
+                scan.tokens.push(ScannedToken {
+                    token: Token::Open(b),
+                    line,
+                    leading: false,
+                });
+                leading = false;
+                i += 1;
+            }
+            b')' | b']' | b'}' => {
+                scan.tokens.push(ScannedToken {
+                    token: Token::Close(b),
+                    line,
+                    leading,
+                });
+                i += 1;
+            }
+            _ if b.is_ascii_alphabetic() || b == b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+
+                let token = match &source[start..i] {
+                    "function" => Some(Token::Function),
+                    "do" => Some(Token::Do),
+                    "then" => Some(Token::Then),
+                    "elseif" => Some(Token::Elseif),
+                    "else" => Some(Token::Else),
+                    "repeat" => Some(Token::Repeat),
+                    "until" => Some(Token::Until),
+                    "end" => Some(Token::End),
+                    _ => None,
+                };
+
+                let is_closer = matches!(
+                    token,
+                    Some(Token::Elseif | Token::Else | Token::Until | Token::End)
+                );
+
+                if let Some(token) = token {
+                    scan.tokens.push(ScannedToken {
+                        token,
+                        line,
+                        leading: leading && is_closer,
+                    });
+                }
+
+                if !matches!(token, Some(Token::Until | Token::End)) {
+                    leading = false;
+                }
+            }
+            _ if b.is_ascii_digit() => {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                leading = false;
+            }
+            _ => {
+                leading = false;
+                i += 1;
+            }
+        }
+    }
+
+    Ok(scan)
+}
+
+/// Level of the long bracket (`[[`, `[==[`, ...) starting at `i`
+fn long_bracket_level(bytes: &[u8], i: usize) -> Option<usize> {
+    if bytes.get(i) != Some(&b'[') {
+        return None;
+    }
+
+    let level = bytes[i + 1..].iter().take_while(|&&b| b == b'=').count();
+    (bytes.get(i + 1 + level) == Some(&b'[')).then_some(level)
+}
+
+/// Returns the index right after the closing long bracket
+fn skip_long_bracket(bytes: &[u8], i: usize, level: usize, line: &mut usize) -> Option<usize> {
+    let mut i = i + level + 2;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\n' => *line += 1,
+            b']' if bytes[i + 1..].iter().take_while(|&&b| b == b'=').count() == level
+                && bytes.get(i + 1 + level) == Some(&b']') =>
+            {
+                return Some(i + level + 2);
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(before: &str, after: &str) {
+        assert_eq!(format_lua(before, 4).unwrap(), after);
+        assert_eq!(format_lua(after, 4).unwrap(), after);
+    }
+
+    #[test]
+    fn indents_blocks() {
+        check(
+            "local gui = AutoScript.new()\nfor i = 1, 10 do\nif i % 2 == 0 then\nprint(i)\nelseif i == 3 then\nprint(\"three\")\nelse\ngui:sleep(0.1)\nend\nend\n",
+            "local gui = AutoScript.new()\nfor i = 1, 10 do\n    if i % 2 == 0 then\n        print(i)\n    elseif i == 3 then\n        print(\"three\")\n    else\n        gui:sleep(0.1)\n    end\nend\n",
+        );
+    }
+
+    #[test]
+    fn indents_functions_repeat_and_tables() {
+        check(
+            "local function f(x)\n  repeat\n  x = x - 1\n  until x < 0\n  return x\nend\nlocal t = {\nkey = 1,\nnested = {\n2,\n},\n}\n",
+            "local function f(x)\n    repeat\n        x = x - 1\n    until x < 0\n    return x\nend\nlocal t = {\n    key = 1,\n    nested = {\n        2,\n    },\n}\n",
+        );
+    }
+
+    #[test]
+    fn blocks_opened_in_one_line_indent_once() {
+        check(
+            "local ok, result = pcall(function()\nreturn 1\nend)\n",
+            "local ok, result = pcall(function()\n    return 1\nend)\n",
+        );
+    }
+
+    #[test]
+    fn uses_configured_indent_width() {
+        assert_eq!(
+            format_lua("while true do\nbreak\nend", 2).unwrap(),
+            "while true do\n  break\nend\n"
+        );
+    }
+
+    #[test]
+    fn trims_trailing_whitespace_and_newlines() {
+        check(
+            "if a then   \n\n  b()\t\nend\n\n\n",
+            "if a then\n\n    b()\nend\n",
+        );
+    }
+
+    #[test]
+    fn keeps_strings_and_comments() {
+        check(
+            "local s = [[\n  if then   \nend ]]\n-- do function\nif a then --[==[\n   end\n]==] print(\"end\")\nend\n",
+            "local s = [[\n  if then   \nend ]]\n-- do function\nif a then --[==[\n   end\n]==] print(\"end\")\nend\n",
+        );
+    }
+
+    #[test]
+    fn keeps_keywords_longer_than_blocks() {
+        check(
+            "local endless = true\nif endless then\nlocal do_it = 1\nend\n",
+            "local endless = true\nif endless then\n    local do_it = 1\nend\n",
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_blocks() {
+        assert!(format_lua("if a then\nprint(a)\n", 4).is_err());
+        assert!(format_lua("print(a)\nend\n", 4).is_err());
+        assert!(format_lua("repeat\nprint(a)\nend\n", 4).is_err());
+        assert!(format_lua("local t = {\n)\n", 4).is_err());
+        assert!(format_lua("local s = [[\nnever closed\n", 4).is_err());
+    }
+
+    #[test]
+    fn maps_cursor_to_same_line() {
+        let old = "if a then\nb()\nend";
+        let new = "if a then\n    b()\nend\n";
+
+        assert_eq!(map_cursor(old, new, 11), 11);
+        assert_eq!(map_cursor(old, new, 100), 21);
+        assert_eq!(map_cursor("if a then   \nend", new, 12), 9);
+    }
+}
@@ -1,11 +1,14 @@
 pub(crate) mod binding;
 pub(crate) mod console;
+pub(crate) mod failsafe;
+pub(crate) mod formatter;
 pub(crate) mod script_editor;
+pub(crate) mod script_error;
 pub(crate) mod script_executor;
 pub(crate) mod script_manager;
+pub(crate) mod sound;
 
 pub const SCRIPT_EXECUTION_CANCELLED_MSG: &str = "Script cancelled";
-pub const CONSOLE_SYSTEM_LOG_PREFIEX: &str = "[SCRIPT_EXECUTOR]";
 
 pub const DEFAULT_SCRIPT_CONTENTS: &str = r#"local gui = AutoScript.new()
 "#;
@@ -121,11 +124,53 @@ pub const GUI_METHODS: &[(&str, &str, &str)] = &[
         "get_current_exe_dir() -> string",
         "Get the directory path of the current executable",
     ),
+    // ----- Window targeting -----
+    (
+        "find_window",
+        "find_window(title: string) -> table?",
+        "Find the first visible window whose title contains `title` (case-insensitive). \
+         Returns a table `{ x, y, w, h, title }` with the visible window rect in physical pixels, \
+         or nil if no window matches. Windows only.",
+    ),
+    (
+        "focus_window",
+        "focus_window(title: string) -> boolean",
+        "Restore and bring to front the first window whose title contains `title`. \
+         Returns false if no window matches or it cannot be focused. Windows only.",
+    ),
+    (
+        "move_mouse_in_window",
+        "move_mouse_in_window(title: string, rx: float, ry: float, t: float)",
+        "Move mouse over t seconds to (rx, ry) relative to the window rect, \
+         where (0, 0) is the top-left and (1, 1) the bottom-right corner. \
+         Accounts for DPI scaling. Windows only.",
+    ),
+    (
+        "click_in_window",
+        "click_in_window(title: string, rx: float, ry: float, button?: \"left\" | \"right\" | \"middle\")",
+        "Move mouse to (rx, ry) relative to the window rect and click, \
+         e.g. (0.5, 0.5) clicks the center of the window. \
+         `button` defaults to \"left\". Accounts for DPI scaling. Windows only.",
+    ),
     (
         "sleep",
         "sleep(seconds: float)",
         "Pause script execution for the given number of seconds",
     ),
+    // ----- Sound -----
+    (
+        "beep",
+        "beep(frequency?: float, duration?: float)",
+        "Play a tone of `frequency` Hz (20–20000, default 880) for `duration` seconds (default 0.2), \
+         e.g. to tell a long script is done. \
+         Plays the system alert sound instead if built without the `sound` feature.",
+    ),
+    (
+        "play_sound",
+        "play_sound(path: string)",
+        "Play a sound file (wav, mp3, ogg or flac) and wait until it ends. \
+         Requires the `sound` feature.",
+    ),
     // ----- Image templates -----
     (
         "store_image",
@@ -249,6 +294,12 @@ pub static SNIPPETS: &[(&str, &str, &str)] = &[
         "local M = {}\n\nfunction M.foo()\n    \nend\n\nreturn M",
         "Define a simple Lua module",
     ),
+    // === Automation ===
+    (
+        "window",
+        "local win = gui:find_window(\"Title\")\nif win and gui:focus_window(\"Title\") then\n    gui:sleep(0.2)\n    gui:click_in_window(\"Title\", 0.5, 0.5)\nend",
+        "Focus a window and click relative to it",
+    ),
     // === Error Handling ===
     (
         "pcall",
@@ -1,12 +1,17 @@
 use eframe::egui::{
-    self, Color32, Galley, Id, Response, TextEdit, Ui, text::LayoutJob, text_edit::TextEditOutput,
+    self, Color32, Galley, Id, Rect, Response, Stroke, TextEdit, Ui,
+    text::{CCursor, CCursorRange, LayoutJob},
+    text_edit::TextEditOutput,
     text_selection::text_cursor_state::byte_index_from_char_index,
 };
 use egui_extras::syntax_highlighting::{self, CodeTheme};
-use regex::Regex;
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
-use crate::auto_script::{GUI_METHODS, SNIPPETS};
+use crate::auto_script::{
+    GUI_METHODS, SNIPPETS,
+    formatter::{format_lua, map_cursor},
+    script_error::ScriptError,
+};
 
 enum CompletionKind {
     Gui((usize, usize)),
@@ -42,7 +47,7 @@ impl ScriptEditor {
         &mut self,
         ui: &mut Ui,
         content: &mut String,
-        check_error: Option<&String>,
+        check_error: Option<&ScriptError>,
     ) -> Response {
         let changed = self.input_completion(ui, content);
         let mut output = TextEdit::multiline(content)
@@ -52,17 +57,55 @@ impl ScriptEditor {
             .layouter(&mut |ui, code, wrap_width| {
                 Self::highlight(ui, code.as_str(), wrap_width, check_error)
             })
-            .id(Id::new("auto_script_editor"))
+            .id(Self::id())
             .show(ui);
 
         if changed {
             output.response.mark_changed();
         }
 
+        if let Some(error) = check_error {
+            self.show_error(ui, &mut output, error);
+        }
+
         self.show_completion(ui, &mut output, content);
         output.response
     }
 
+    /// Formats `content` in place and keeps the cursor at the same line and column,
+    /// returns whether `content` is changed
+    pub fn format(
+        ctx: &egui::Context,
+        content: &mut String,
+        indent_width: usize,
+    ) -> Result<bool, String> {
+        let formatted = format_lua(content, indent_width)?;
+        if formatted == *content {
+            return Ok(false);
+        }
+
+        if let Some(mut state) = TextEdit::load_state(ctx, Self::id())
+            && let Some(range) = state.cursor.char_range()
+        {
+            let idx = map_cursor(content, &formatted, range.primary.index);
+            state
+                .cursor
+                .set_char_range(Some(CCursorRange::one(CCursor::new(idx))));
+            state.store(ctx, Self::id());
+        }
+
+        *content = formatted;
+        Ok(true)
+    }
+
+    fn id() -> Id {
+        Id::new("auto_script_editor")
+    }
+
+    pub fn request_focus(ctx: &egui::Context) {
+        ctx.memory_mut(|mem| mem.request_focus(Self::id()));
+    }
+
     pub fn is_showing_completion(&self) -> bool {
         self.completion.is_some()
     }
@@ -228,48 +271,90 @@ impl ScriptEditor {
         });
     }
 
+    /// Marks the line of `error` in the gutter, its message is shown when hovering the line,
+    /// or anywhere in the editor if it's not located
+    fn show_error(&self, ui: &Ui, output: &mut TextEditOutput, error: &ScriptError) {
+        let galley = &output.galley;
+        let text = galley.text();
+        let line_rect = error.byte_range(text).map(|range| {
+            let line_start = text[..range.start].rfind('\n').map_or(0, |i| i + 1);
+            let line_end = text[range.end..]
+                .find('\n')
+                .map_or(text.len(), |i| range.end + i);
+            let cursor = |byte: usize| CCursor::new(text[..byte].chars().count());
+
+            let rect = galley
+                .pos_from_cursor(cursor(line_start))
+                .union(galley.pos_from_cursor(cursor(line_end)))
+                .translate(output.galley_pos.to_vec2());
+            Rect::from_x_y_ranges(output.response.rect.x_range(), rect.y_range())
+        });
+
+        if let Some(line_rect) = line_rect {
+            let marker = Rect::from_min_size(line_rect.min, egui::vec2(3.0, line_rect.height()));
+            ui.painter()
+                .rect_filled(marker, 1.0, ui.visuals().error_fg_color);
+        }
+
+        let hovered = ui
+            .input(|i| i.pointer.hover_pos())
+            .is_some_and(|pos| line_rect.is_none_or(|rect| rect.y_range().contains(pos.y)));
+        if hovered && !self.is_showing_completion() {
+            output.response = output
+                .response
+                .clone()
+                .on_hover_text_at_pointer(&error.message);
+        }
+    }
+
     fn highlight(
         ui: &egui::Ui,
         code: &str,
         wrap_width: f32,
-        error: Option<&String>,
+        error: Option<&ScriptError>,
     ) -> Arc<Galley> {
-        let line_number = error.map(|e| Self::extract_error_line(e));
-
-        let mut layout_job = if let Some(error_line) = line_number {
-            let mut layout_job = LayoutJob {
-                text: code.to_string(),
-                ..Default::default()
-            };
-
-            let mut byte_offset = 0;
-            let mut line_number = 1;
-
-            for line in Self::split_lines_including_newline(code) {
-                let mut line_job = Self::syntax_highlight(ui, line, "lua");
-
-                for section in line_job.sections.iter_mut() {
-                    section.byte_range.start += byte_offset;
-                    section.byte_range.end += byte_offset;
+        let mut layout_job = Self::syntax_highlight(ui, code, "lua");
+        if let Some(range) = error.and_then(|error| error.byte_range(code)) {
+            Self::underline_error(&mut layout_job, range, ui.visuals().error_fg_color);
+        }
 
-                    if Some(line_number) == error_line {
-                        section.format.background = Color32::DARK_RED;
-                    }
+        layout_job.wrap.max_width = wrap_width;
+        ui.fonts(|f| f.layout_job(layout_job))
+    }
 
-                    layout_job.sections.push(section.clone());
+    /// Splits the sections at the bounds of `range` to underline the text in it
+    fn underline_error(job: &mut LayoutJob, range: Range<usize>, color: Color32) {
+        let mut sections = Vec::with_capacity(job.sections.len() + 2);
+
+        for section in job.sections.drain(..) {
+            let bounds = section.byte_range.clone();
+            let cuts = [
+                bounds.start,
+                range.start.clamp(bounds.start, bounds.end),
+                range.end.clamp(bounds.start, bounds.end),
+                bounds.end,
+            ];
+
+            for (i, part_range) in cuts.windows(2).enumerate() {
+                let (start, end) = (part_range[0], part_range[1]);
+                if start >= end {
+                    continue;
                 }
 
-                byte_offset += line.len();
-                line_number += 1;
+                let mut part = section.clone();
+                part.byte_range = start..end;
+                if i > 0 {
+                    part.leading_space = 0.0;
+                }
+                if range.start <= start && end <= range.end {
+                    part.format.underline = Stroke::new(1.5, color);
+                    part.format.background = color.gamma_multiply(0.2);
+                }
+                sections.push(part);
             }
+        }
 
-            layout_job
-        } else {
-            Self::syntax_highlight(ui, code, "lua")
-        };
-
-        layout_job.wrap.max_width = wrap_width;
-        ui.fonts(|f| f.layout_job(layout_job))
+        job.sections = sections;
     }
 
     fn syntax_highlight(ui: &egui::Ui, code: &str, lang: &str) -> LayoutJob {
@@ -279,28 +364,6 @@ impl ScriptEditor {
         syntax_highlighting::highlight(ctx, style, &theme, code, lang)
     }
 
-    fn split_lines_including_newline(text: &str) -> Vec<&str> {
-        let mut result = Vec::new();
-        let mut start = 0;
-        for (i, c) in text.char_indices() {
-            if c == '\n' {
-                result.push(&text[start..=i]);
-                start = i + 1;
-            }
-        }
-        if start < text.len() {
-            result.push(&text[start..]);
-        }
-        result
-    }
-
-    fn extract_error_line(error: &str) -> Option<usize> {
-        let re = Regex::new(r#"\[string ".*?"\]:(\d+):"#).unwrap();
-        re.captures(error)
-            .and_then(|caps| caps.get(1))
-            .and_then(|m| m.as_str().parse::<usize>().ok())
-    }
-
     fn show_completion_area<R>(ui: &mut egui::Ui, add_contents: impl FnOnce(&mut egui::Ui) -> R) {
         egui::Frame::popup(ui.style())
             .multiply_with_opacity(0.6)
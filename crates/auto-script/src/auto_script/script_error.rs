@@ -0,0 +1,197 @@
+use regex::Regex;
+use std::{ops::Range, sync::LazyLock};
+
+/// Location of the chunk in a Lua error, e.g. `[string "script"]:3:`
+static LOCATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\[string ".*?"\]:(\d+):"#).unwrap());
+
+/// Token a syntax error is reported at, e.g. `near 'end'`
+static NEAR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"near '(.+)'$").unwrap());
+
+/// Error of checking a script, lines and columns are 1-based and counted in chars
+/// as the editor shows them
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScriptError {
+    pub message: String,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Line and column right after the error, on the same line as it starts
+    pub end: Option<(usize, usize)>,
+}
+
+impl ScriptError {
+    /// An error without a location
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            line: None,
+            column: None,
+            end: None,
+        }
+    }
+
+    /// Locates the mlua error `message` in `script` by the first line of the chunk in
+    /// the message, the column is known only if the token it's near is unique in the line
+    pub fn parse(message: impl Into<String>, script: &str) -> Self {
+        let mut error = Self::new(message);
+
+        let Some((lua_line, rest)) = LOCATION_RE.captures(&error.message).and_then(|caps| {
+            let lua_line = caps.get(1)?.as_str().parse().ok()?;
+            let rest = &error.message[caps.get(0)?.end()..];
+            Some((lua_line, rest.lines().next().unwrap_or_default()))
+        }) else {
+            return error;
+        };
+
+        let line = visual_line(script, lua_line);
+        let token = NEAR_RE
+            .captures(rest.trim_end())
+            .and_then(|caps| caps.get(1))
+            .map(|m| m.as_str().to_owned());
+
+        if let Some(token) = token
+            && let Some(line_text) = script.split('\n').nth(line - 1)
+            && let Some(byte_idx) = find_token(line_text, &token)
+        {
+            let column = line_text[..byte_idx].chars().count() + 1;
+            error.column = Some(column);
+            error.end = Some((line, column + token.chars().count()));
+        }
+        error.line = Some(line);
+        error
+    }
+
+    /// Byte range of the error in `script`, the whole line if the column is unknown,
+    /// `None` if it's not located
+    pub fn byte_range(&self, script: &str) -> Option<Range<usize>> {
+        let line = self.line?;
+        let mut start = 0;
+        for _ in 1..line {
+            start += script[start..].find('\n')? + 1;
+        }
+
+        let line_text = &script[start..];
+        let line_text = line_text.split('\n').next().unwrap_or(line_text);
+        let line_text = line_text.strip_suffix('\r').unwrap_or(line_text);
+        let byte_of_column = |column: usize| {
+            line_text
+                .char_indices()
+                .nth(column - 1)
+                .map_or(line_text.len(), |(idx, _)| idx)
+        };
+
+        match (self.column, self.end) {
+            (Some(column), Some((_, end))) => {
+                Some(start + byte_of_column(column)..start + byte_of_column(end))
+            }
+            _ => Some(start..start + line_text.len()),
+        }
+    }
+}
+
+/// Byte index of `token` in `line`, a name is not matched inside a longer one,
+/// `None` if it's not found or found more than once since Lua doesn't tell which one
+fn find_token(line: &str, token: &str) -> Option<usize> {
+    let is_name_char = |c: char| c.is_alphanumeric() || c == '_';
+    let is_name = token.chars().all(is_name_char);
+
+    let mut found = line
+        .match_indices(token)
+        .map(|(idx, _)| idx)
+        .filter(|&idx| {
+            let before = line[..idx].chars().next_back();
+            let after = line[idx + token.len()..].chars().next();
+            !is_name || (!before.is_some_and(is_name_char) && !after.is_some_and(is_name_char))
+        });
+
+    let idx = found.next()?;
+    found.next().is_none().then_some(idx)
+}
+
+/// Line shown in the editor of line `lua_line` counted by Lua, which takes a lone `\r`
+/// as a line break while the editor breaks lines at `\n` only
+fn visual_line(script: &str, lua_line: usize) -> usize {
+    let bytes = script.as_bytes();
+    let (mut lua, mut visual, mut i) = (1, 1, 0);
+
+    while i < bytes.len() && lua < lua_line {
+        let c = bytes[i];
+        if c == b'\n' || c == b'\r' {
+            // `\r\n` and `\n\r` are a single break
+            let pair = bytes
+                .get(i + 1)
+                .is_some_and(|&next| (next == b'\n' || next == b'\r') && next != c);
+            if c == b'\n' || pair {
+                visual += 1;
+            }
+            if pair {
+                i += 1;
+            }
+            lua += 1;
+        }
+        i += 1;
+    }
+
+    visual
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn syntax_error_near_a_token() {
+        let script = "local a = 1\nlocal b c = 2\n";
+        let error = ScriptError::parse(
+            r#"syntax error: [string "script"]:2: syntax error near 'c'"#,
+            script,
+        );
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.column, Some(9));
+        assert_eq!(error.end, Some((2, 10)));
+        assert_eq!(&script[error.byte_range(script).unwrap()], "c");
+    }
+
+    #[test]
+    fn error_near_eof_and_runtime_error() {
+        let script = "function f()\n  print(1)\n";
+        let error = ScriptError::parse(
+            r#"syntax error: [string "script"]:3: 'end' expected (to close 'function' at line 1) near <eof>"#,
+            script,
+        );
+        assert_eq!((error.line, error.column), (Some(3), None));
+        assert_eq!(error.byte_range(script), Some(script.len()..script.len()));
+
+        let script = "local x = nil\nx()\n";
+        let error = ScriptError::parse(
+            "runtime error: [string \"script\"]:2: attempt to call a nil value (local 'x')\n\
+             stack traceback:\n\t[C]: in ?\n\t[string \"script\"]:1: in main chunk",
+            script,
+        );
+        assert_eq!((error.line, error.column), (Some(2), None));
+        assert_eq!(&script[error.byte_range(script).unwrap()], "x()");
+    }
+
+    #[test]
+    fn line_endings_map_to_visual_lines() {
+        let script = "a = 1\r\nb = 2\r\nc = = 3\r\n";
+        let error = ScriptError::parse(
+            r#"syntax error: [string "script"]:3: unexpected symbol near '='"#,
+            script,
+        );
+        // either `=` may be the one Lua means
+        assert_eq!((error.line, error.column, error.end), (Some(3), None, None));
+        assert_eq!(&script[error.byte_range(script).unwrap()], "c = = 3");
+
+        // a lone `\r` breaks a line for Lua only
+        assert_eq!(visual_line("a\rb\nc", 3), 2);
+        assert_eq!(visual_line("a\n\rb\nc", 3), 3);
+    }
+
+    #[test]
+    fn error_without_location() {
+        let error = ScriptError::parse("callback error: something failed", "x = 1");
+        assert_eq!(error, ScriptError::new("callback error: something failed"));
+        assert_eq!(error.byte_range("x = 1"), None);
+    }
+}
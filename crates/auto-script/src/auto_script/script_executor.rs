@@ -1,4 +1,4 @@
-use mlua::Lua;
+use mlua::{HookTriggers, Lua, VmState};
 use std::{
     collections::VecDeque,
     sync::{
@@ -10,16 +10,23 @@ use std::{
 };
 
 use crate::auto_script::{
-    CONSOLE_SYSTEM_LOG_PREFIEX, SCRIPT_EXECUTION_CANCELLED_MSG,
+    SCRIPT_EXECUTION_CANCELLED_MSG,
     binding::AutoScript,
-    console::{Console, inject_lua_console},
+    console::{Console, LogEntry, LogLevel, inject_lua_console},
+    failsafe::{FailsafeOptions, FailsafeWatcher, ScriptedMouse},
+    script_error::ScriptError,
+    sound::{self, SoundOptions},
 };
 
 pub struct ScriptExecutor {
     pub console: Console,
+    pub failsafe: FailsafeOptions,
+    pub sound: SoundOptions,
     sender: Sender<String>,
     handle: Option<JoinHandle<Result<(), String>>>,
     cancel_flag: Arc<AtomicBool>,
+    watcher: Option<FailsafeWatcher>,
+    failsafe_triggered: Arc<AtomicBool>,
 }
 
 impl ScriptExecutor {
@@ -28,35 +35,69 @@ impl ScriptExecutor {
 
         ScriptExecutor {
             console: Console::new(receiver),
+            failsafe: FailsafeOptions::default(),
+            sound: SoundOptions::default(),
             sender,
             handle: None,
             cancel_flag: Arc::new(AtomicBool::new(false)),
+            watcher: None,
+            failsafe_triggered: Arc::new(AtomicBool::new(false)),
         }
     }
 
-    pub fn check_script(&self, script: &str) -> Result<(), String> {
+    pub fn check_script(&self, script: &str) -> Result<(), ScriptError> {
         let lua = Lua::new();
-        AutoScript::register_with_cancel_flag(&lua, self.cancel_flag.clone())
-            .map_err(|e| e.to_string())?;
+        AutoScript::register_with_cancel_flag(&lua, self.cancel_flag.clone(), Default::default())
+            .map_err(|e| ScriptError::new(e.to_string()))?;
         lua.load(script)
             .set_name("script")
             .into_function()
             .map(|_| ())
-            .map_err(|e| e.to_string())
+            .map_err(|e| ScriptError::parse(e.to_string(), script))
     }
 
-    pub fn execute_script(&mut self, script: String) {
+    /// Runs `script` on its own thread, `failsafe` is whether the script allows
+    /// the failsafe to abort it, which also requires [`FailsafeOptions::enabled`]
+    pub fn execute_script(&mut self, script: String, failsafe: bool) {
         assert!(!self.is_executing());
         self.cancel_flag.store(false, Ordering::SeqCst);
+        self.failsafe_triggered.store(false, Ordering::SeqCst);
 
         let flag = self.cancel_flag.clone();
         let code = script.clone();
         let sender = self.sender.clone();
+        let scripted_mouse = Arc::new(ScriptedMouse::default());
+
+        self.watcher = (failsafe && self.failsafe.enabled).then(|| {
+            FailsafeWatcher::spawn(
+                self.failsafe.clone(),
+                scripted_mouse.clone(),
+                self.cancel_flag.clone(),
+                self.failsafe_triggered.clone(),
+            )
+        });
 
         let handle = thread::spawn(move || {
             let lua = Lua::new();
+
+            // Checks the flag between instructions too, so that busy loops can be cancelled
+            let hook_flag = flag.clone();
+            lua.set_hook(
+                HookTriggers::new().every_nth_instruction(1000),
+                move |_, _| {
+                    if hook_flag.load(Ordering::SeqCst) {
+                        Err(mlua::Error::RuntimeError(
+                            SCRIPT_EXECUTION_CANCELLED_MSG.into(),
+                        ))
+                    } else {
+                        Ok(VmState::Continue)
+                    }
+                },
+            );
+
             inject_lua_console(&lua, sender).map_err(|e| e.to_string())?;
-            AutoScript::register_with_cancel_flag(&lua, flag).map_err(|e| e.to_string())?;
+            AutoScript::register_with_cancel_flag(&lua, flag, scripted_mouse)
+                .map_err(|e| e.to_string())?;
             lua.load(&code)
                 .set_name("script")
                 .exec()
@@ -66,8 +107,8 @@ impl ScriptExecutor {
         self.handle = Some(handle);
     }
 
-    pub fn get_console_logs(&self) -> &VecDeque<String> {
-        &self.console.logs
+    pub fn get_console_logs(&self) -> &VecDeque<LogEntry> {
+        self.console.logs()
     }
 
     pub fn update(&mut self) {
@@ -84,16 +125,40 @@ impl ScriptExecutor {
                 .unwrap()
                 .join()
                 .unwrap_or_else(|e| Err(format!("Script panicked: {e:?}")));
+            self.watcher = None;
 
             if let Err(err) = result.as_ref()
                 && err.contains(SCRIPT_EXECUTION_CANCELLED_MSG)
+                && self.failsafe_triggered.load(Ordering::SeqCst)
             {
-                self.console.logs.push_back(format!(
-                    "{CONSOLE_SYSTEM_LOG_PREFIEX} Script execution was cancelled by user"
-                ));
+                self.console.push(
+                    LogLevel::System,
+                    format!(
+                        "Script execution was aborted by failsafe, \
+                         the cursor stayed in the {} corner",
+                        self.failsafe.corner.name()
+                    ),
+                );
                 return Some(Ok(()));
             }
 
+            if let Err(err) = result.as_ref()
+                && err.contains(SCRIPT_EXECUTION_CANCELLED_MSG)
+            {
+                self.console
+                    .push(LogLevel::System, "Script execution was cancelled by user");
+                return Some(Ok(()));
+            }
+
+            if let Err(err) = result.as_ref() {
+                self.console.push(LogLevel::Error, err);
+            }
+
+            // cancelled ones are left quiet, the user is right there
+            if self.sound.chime_on_finish {
+                sound::chime();
+            }
+
             return Some(result);
         }
         None
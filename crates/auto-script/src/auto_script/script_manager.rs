@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{
-    VecDeque,
+    BTreeSet, VecDeque,
     vec_deque::{Iter, IterMut},
 };
 
@@ -11,6 +11,23 @@ use crate::auto_script::DEFAULT_SCRIPT_CONTENTS;
 pub struct Script {
     pub name: String,
     pub content: String,
+
+    /// Whether the failsafe may abort this script
+    pub failsafe: bool,
+
+    pub tags: BTreeSet<String>,
+}
+
+impl Script {
+    /// Whether the name or a tag contains `query`, which must be lowercase
+    pub fn matches(&self, query: &str) -> bool {
+        query.is_empty()
+            || self.name.to_lowercase().contains(query)
+            || self
+                .tags
+                .iter()
+                .any(|tag| tag.to_lowercase().contains(query))
+    }
 }
 
 impl Default for Script {
@@ -18,10 +35,13 @@ impl Default for Script {
         Self {
             name: "New Script".to_string(),
             content: DEFAULT_SCRIPT_CONTENTS.to_string(),
+            failsafe: true,
+            tags: BTreeSet::new(),
         }
     }
 }
 
+/// Scripts in the order arranged by user
 #[derive(Deserialize, Serialize, Default)]
 #[serde(default)]
 pub struct ScriptManager {
@@ -39,6 +59,31 @@ impl ScriptManager {
         self.scripts.remove(idx);
     }
 
+    /// Moves the script at `from` to be at `to`
+    pub fn move_script(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.scripts.len() {
+            return;
+        }
+
+        let script = self.scripts.remove(from).unwrap();
+        self.scripts.insert(to.min(self.scripts.len()), script);
+    }
+
+    /// Tags of all scripts
+    pub fn tags(&self) -> BTreeSet<&str> {
+        self.scripts
+            .iter()
+            .flat_map(|script| script.tags.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// Removes `tag` from the scripts having it
+    pub fn remove_tag(&mut self, tag: &str) {
+        for script in &mut self.scripts {
+            script.tags.remove(tag);
+        }
+    }
+
     pub fn iter(&self) -> Iter<'_, Script> {
         self.scripts.iter()
     }
@@ -65,3 +110,55 @@ impl ScriptManager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(names: &[&str]) -> ScriptManager {
+        let mut manager = ScriptManager::default();
+        for name in names {
+            manager.scripts.push_back(Script {
+                name: name.to_string(),
+                ..Default::default()
+            });
+        }
+        manager
+    }
+
+    fn names(manager: &ScriptManager) -> Vec<&str> {
+        manager.iter().map(|script| script.name.as_str()).collect()
+    }
+
+    #[test]
+    fn old_scripts_have_no_tags() {
+        let json = r#"{"scripts":[{"name":"a","content":"","failsafe":false}]}"#;
+        let manager = serde_json::from_str::<ScriptManager>(json).unwrap();
+        assert_eq!(names(&manager), ["a"]);
+        assert!(manager.scripts[0].tags.is_empty());
+    }
+
+    #[test]
+    fn move_keeps_the_others_in_order() {
+        let mut manager = manager(&["a", "b", "c", "d"]);
+        manager.move_script(0, 2);
+        assert_eq!(names(&manager), ["b", "c", "a", "d"]);
+        manager.move_script(3, 0);
+        assert_eq!(names(&manager), ["d", "b", "c", "a"]);
+        manager.move_script(9, 0);
+        assert_eq!(names(&manager), ["d", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn removed_tag_is_gone_from_all_scripts() {
+        let mut manager = manager(&["a", "b"]);
+        manager.scripts[0].tags = ["game".to_owned(), "work".to_owned()].into();
+        manager.scripts[1].tags = ["game".to_owned()].into();
+        assert_eq!(manager.tags(), ["game", "work"].into());
+        assert!(manager.scripts[1].matches("gam"));
+
+        manager.remove_tag("game");
+        assert_eq!(manager.tags(), ["work"].into());
+        assert!(manager.scripts[1].tags.is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Sounds that tell the user a script is done, for the long ones nobody watches
+//!
+//! Tones and sound files are played by `rodio` with the `sound` feature, without it
+//! only the system alert sound is available.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const DEFAULT_FREQUENCY: f32 = 880.0;
+pub const DEFAULT_DURATION: Duration = Duration::from_millis(200);
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(default)]
+pub struct SoundOptions {
+    /// Plays a chime when a script finishes or fails, but not when it's cancelled
+    pub chime_on_finish: bool,
+}
+
+impl Default for SoundOptions {
+    fn default() -> Self {
+        Self {
+            chime_on_finish: true,
+        }
+    }
+}
+
+impl SoundOptions {
+    pub const KEY: &str = "sound_options";
+}
+
+/// Plays a sine tone, blocks until it ends or `cancelled` returns true
+#[cfg(feature = "sound")]
+pub fn beep(
+    frequency: f32,
+    duration: Duration,
+    cancelled: impl Fn() -> bool,
+) -> Result<(), String> {
+    use rodio::Source;
+
+    let source = rodio::source::SineWave::new(frequency)
+        .take_duration(duration)
+        .amplify(0.2);
+    play(source, cancelled)
+}
+
+/// The system alert sound can't be tuned, so `frequency` and `duration` are ignored here
+#[cfg(not(feature = "sound"))]
+pub fn beep(
+    _frequency: f32,
+    _duration: Duration,
+    _cancelled: impl Fn() -> bool,
+) -> Result<(), String> {
+    eapp_utils::platform::beep();
+    Ok(())
+}
+
+/// Plays a sound file, e.g. `wav`, `mp3`, `ogg` or `flac`,
+/// blocks until it ends or `cancelled` returns true
+#[cfg(feature = "sound")]
+pub fn play_sound(path: &str, cancelled: impl Fn() -> bool) -> Result<(), String> {
+    use rodio::Source;
+
+    let file = std::fs::File::open(path).map_err(|e| format!("Cannot open '{path}': {e}"))?;
+    let source = rodio::Decoder::new(std::io::BufReader::new(file))
+        .map_err(|e| format!("Cannot decode '{path}': {e}"))?;
+    play(source.convert_samples(), cancelled)
+}
+
+#[cfg(not(feature = "sound"))]
+pub fn play_sound(_path: &str, _cancelled: impl Fn() -> bool) -> Result<(), String> {
+    Err("Playing sound files requires auto-script built with the `sound` feature".to_owned())
+}
+
+#[cfg(feature = "sound")]
+fn play(
+    source: impl rodio::Source<Item = f32> + Send + 'static,
+    cancelled: impl Fn() -> bool,
+) -> Result<(), String> {
+    const SLICE: Duration = Duration::from_millis(20);
+
+    // the stream stops playing once dropped, so it's kept until the end
+    let (_stream, handle) =
+        rodio::OutputStream::try_default().map_err(|e| format!("No audio output: {e}"))?;
+    let sink = rodio::Sink::try_new(&handle).map_err(|e| format!("No audio output: {e}"))?;
+    sink.append(source);
+
+    while !sink.empty() {
+        if cancelled() {
+            sink.stop();
+            break;
+        }
+        std::thread::sleep(SLICE);
+    }
+    Ok(())
+}
+
+/// Plays the completion chime on its own thread, falls back to the system alert sound
+pub fn chime() {
+    std::thread::spawn(|| {
+        for frequency in [660.0, 990.0] {
+            if let Err(err) = beep(frequency, Duration::from_millis(120), || false) {
+                log::warn!("play chime fails: {err}");
+                eapp_utils::platform::beep();
+                return;
+            }
+        }
+    });
+}
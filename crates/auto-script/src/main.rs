@@ -4,7 +4,7 @@ pub(crate) mod app;
 pub(crate) mod auto_script;
 
 fn main() {
-    eapp_utils::setup_loggers("auto-script.log").unwrap();
+    eapp_utils::setup_loggers("auto-script.log", eapp_utils::LogOptions::from_env()).unwrap();
 
     eapp_utils::capture_error!(err => log::error!("run native fails: {err}"), {
         let options = eframe::NativeOptions {
@@ -19,6 +19,7 @@ fn main() {
                     ))
                     .unwrap(),
                 ),
+            persist_window: false,
             ..Default::default()
         };
 
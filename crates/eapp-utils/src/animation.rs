@@ -1,6 +1,6 @@
 //! Contains animation related utils
 
-use eframe::egui::Color32;
+use eframe::egui::{self, Color32, Pos2, Vec2};
 
 pub fn color_lerp(color1: Color32, color2: Color32, factor: f32) -> Color32 {
     fn u8_lerp(v1: u8, v2: u8, factor: f32) -> u8 {
@@ -14,3 +14,251 @@ pub fn color_lerp(color1: Color32, color2: Color32, factor: f32) -> Color32 {
         u8_lerp(color1.a(), color2.a(), factor),
     )
 }
+
+/// Opacity that goes from `1.0` to `0.0` during the last `fade_secs` before `end_time`
+pub fn fade_out(now: f64, end_time: f64, fade_secs: f64) -> f32 {
+    if fade_secs <= 0.0 {
+        return if now < end_time { 1.0 } else { 0.0 };
+    }
+
+    ((end_time - now) / fade_secs).clamp(0.0, 1.0) as f32
+}
+
+/// Curve mapping the linear progress of an animation to the eased one
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    EaseInCubic,
+    EaseOutCubic,
+    EaseInOutCubic,
+
+    /// Overshoots the end a little and settles back
+    EaseOutBack,
+
+    /// Overshoots and oscillates around the end, like a damped spring
+    Spring,
+}
+
+impl Easing {
+    /// Eased progress of `t`, which is clamped to `0.0..=1.0`,
+    /// `0.0` and `1.0` are kept but the curves in between may leave that range
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        if t == 1.0 {
+            return 1.0;
+        }
+
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInCubic => t * t * t,
+            Easing::EaseOutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::EaseOutBack => {
+                const C1: f32 = 1.70158;
+                const C3: f32 = C1 + 1.0;
+                1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+            }
+            Easing::Spring => 1.0 - (-6.0 * t).exp() * (12.0 * t).cos(),
+        }
+    }
+}
+
+/// Values a [`Tween`] can interpolate
+pub trait Lerp: Clone {
+    /// `self` at `t == 0.0`, `to` at `t == 1.0`, `t` may leave that range for overshooting curves
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for f64 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t as f64
+    }
+}
+
+impl Lerp for Vec2 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        *self + (*to - *self) * t
+    }
+}
+
+impl Lerp for Pos2 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        *self + (*to - *self) * t
+    }
+}
+
+impl Lerp for Color32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        color_lerp(*self, *to, t.clamp(0.0, 1.0))
+    }
+}
+
+/// A value moving toward its target over a fixed duration along an [`Easing`] curve
+///
+/// # Example
+/// ```ignore
+/// // each frame
+/// let opacity = self.opacity.animate(ctx, if hovered { 1.0 } else { 0.0 });
+/// ```
+#[derive(Debug, Clone)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    start_time: f64,
+    duration: f64,
+    easing: Easing,
+}
+
+impl<T: Lerp> Tween<T> {
+    /// Starts at rest at `value`, later moves take `duration` seconds
+    pub fn new(value: T, duration: f64, easing: Easing) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            start_time: f64::NEG_INFINITY,
+            duration,
+            easing,
+        }
+    }
+
+    pub fn target(&self) -> &T {
+        &self.to
+    }
+
+    /// Moves from the current value at `now` toward `target`
+    pub fn set_target(&mut self, target: T, now: f64) {
+        self.from = self.value(now);
+        self.to = target;
+        self.start_time = now;
+    }
+
+    /// Jumps to `value` without animating
+    pub fn set_value(&mut self, value: T) {
+        self.from = value.clone();
+        self.to = value;
+        self.start_time = f64::NEG_INFINITY;
+    }
+
+    /// Linear progress in `0.0..=1.0` at `now`
+    fn progress(&self, now: f64) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+
+        ((now - self.start_time) / self.duration).clamp(0.0, 1.0) as f32
+    }
+
+    pub fn value(&self, now: f64) -> T {
+        let t = self.progress(now);
+        if t >= 1.0 {
+            return self.to.clone();
+        }
+
+        self.from.lerp(&self.to, self.easing.apply(t))
+    }
+
+    pub fn is_animating(&self, now: f64) -> bool {
+        self.progress(now) < 1.0
+    }
+
+    /// The value at `now` and whether it's still moving
+    pub fn update(&self, now: f64) -> (T, bool) {
+        (self.value(now), self.is_animating(now))
+    }
+}
+
+impl<T: Lerp + PartialEq> Tween<T> {
+    /// Retargets if `target` is changed and returns the current value,
+    /// repainting until it arrives
+    pub fn animate(&mut self, ctx: &egui::Context, target: T) -> T {
+        let now = ctx.input(|i| i.time);
+        if target != self.to {
+            self.set_target(target, now);
+        }
+
+        let (value, animating) = self.update(now);
+        if animating {
+            ctx.request_repaint();
+        }
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 6] = [
+        Easing::Linear,
+        Easing::EaseInCubic,
+        Easing::EaseOutCubic,
+        Easing::EaseInOutCubic,
+        Easing::EaseOutBack,
+        Easing::Spring,
+    ];
+
+    #[test]
+    fn easings_keep_endpoints() {
+        for easing in ALL {
+            assert_eq!(easing.apply(0.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(1.0), 1.0, "{easing:?}");
+            assert_eq!(easing.apply(-1.0), 0.0, "{easing:?}");
+            assert_eq!(easing.apply(2.0), 1.0, "{easing:?}");
+        }
+
+        assert!(Easing::EaseOutBack.apply(0.8) > 1.0);
+        assert!((Easing::EaseInOutCubic.apply(0.5) - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn tween_is_monotonic_between_endpoints() {
+        for easing in [
+            Easing::Linear,
+            Easing::EaseInCubic,
+            Easing::EaseOutCubic,
+            Easing::EaseInOutCubic,
+        ] {
+            let mut tween = Tween::new(10.0_f32, 0.5, easing);
+            assert_eq!(tween.update(0.0), (10.0, false));
+
+            tween.set_target(20.0, 1.0);
+            let samples: Vec<_> = (0..=10)
+                .map(|i| tween.value(1.0 + i as f64 * 0.05))
+                .collect();
+
+            assert_eq!(samples[0], 10.0, "{easing:?}");
+            assert_eq!(samples[10], 20.0, "{easing:?}");
+            assert!(
+                samples.windows(2).all(|w| w[0] <= w[1]),
+                "{easing:?} {samples:?}"
+            );
+
+            assert!(tween.is_animating(1.25));
+            assert_eq!(tween.update(1.5), (20.0, false));
+        }
+    }
+
+    #[test]
+    fn retargeting_starts_from_the_current_value() {
+        let mut tween = Tween::new(0.0_f64, 1.0, Easing::Linear);
+        tween.set_target(10.0, 0.0);
+        tween.set_target(0.0, 0.5);
+
+        assert_eq!(tween.value(0.5), 5.0);
+        assert_eq!(tween.value(1.0), 2.5);
+        assert_eq!(tween.value(1.5), 0.0);
+    }
+}
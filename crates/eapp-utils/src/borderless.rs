@@ -93,15 +93,337 @@ pub fn title_bar_behavior(ui: &egui::Ui, title_bar_rect: eframe::epaint::Rect) {
         Sense::click(),
     );
 
+    // Buttons of the title bar are added later, so they are hit first and this won't fire on them
     if title_bar_response.double_clicked() {
-        let is_maximized = ui.input(|i| i.viewport().maximized.unwrap_or(false));
-        ui.ctx()
-            .send_viewport_cmd(ViewportCommand::Maximized(!is_maximized));
+        toggle_maximized(ui.ctx());
     }
 
     if title_bar_response.is_pointer_button_down_on() {
+        begin_snap_drag(ui.ctx());
         ui.ctx().send_viewport_cmd(ViewportCommand::StartDrag);
     }
+
+    handle_snap_drag(ui.ctx());
+    handle_snap_shortcuts(ui.ctx());
+    apply_native_frame(ui.ctx());
+}
+
+/// Native decorations of the undecorated window, only supported on Windows 11
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NativeFrame {
+    /// System rounded corners, which are lost when the window is snapped otherwise
+    pub round_corners: bool,
+
+    /// System shadow, it may show a thin border around transparent windows
+    pub shadow: bool,
+}
+
+impl Default for NativeFrame {
+    fn default() -> Self {
+        Self {
+            round_corners: true,
+            shadow: false,
+        }
+    }
+}
+
+const NATIVE_FRAME_ID: &str = "borderless_native_frame";
+const APPLIED_NATIVE_FRAME_ID: &str = "borderless_applied_native_frame";
+
+/// Replaces the [`NativeFrame`] of the window, [`NativeFrame::default`] if never set
+pub fn set_native_frame(ctx: &egui::Context, frame: NativeFrame) {
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(NATIVE_FRAME_ID), frame));
+}
+
+/// Applies the [`NativeFrame`] again whenever the window is snapped, maximized or restored,
+/// as the OS may reset it then
+fn apply_native_frame(ctx: &egui::Context) {
+    let frame = ctx
+        .data(|d| d.get_temp::<NativeFrame>(egui::Id::new(NATIVE_FRAME_ID)))
+        .unwrap_or_default();
+    let fullscreen = ctx.input(|i| i.viewport().fullscreen.unwrap_or(false));
+    let state = (frame, current_snap(ctx), fullscreen);
+
+    let applied_id = egui::Id::new(APPLIED_NATIVE_FRAME_ID);
+    if ctx.data(|d| d.get_temp(applied_id)) == Some(state) {
+        return;
+    }
+
+    // Tried again next frame if the window isn't shown yet
+    if crate::platform::set_window_frame(frame.round_corners && !fullscreen, frame.shadow) {
+        ctx.data_mut(|d| d.insert_temp(applied_id, state));
+    }
+}
+
+/// Maximizes the window, or restores it to the size and position before maximized
+pub fn toggle_maximized(ctx: &egui::Context) {
+    let is_maximized = ctx.input(|i| i.viewport().maximized.unwrap_or(false));
+    apply_snap(ctx, (!is_maximized).then_some(Snap::Maximized));
+}
+
+/// Where a window is snapped to in the work area of a monitor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Snap {
+    Maximized,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Snap {
+    /// Distance to the edges of the work area to snap at
+    const EDGE_DIST: f32 = 8.0;
+
+    /// Height of the corners at the left and right edges to snap to a quarter
+    const CORNER_SIZE: f32 = 64.0;
+
+    /// The snap of a window dropped with the pointer at `pos`
+    pub fn at_pointer(work_area: egui::Rect, pos: egui::Pos2) -> Option<Self> {
+        let left = pos.x <= work_area.left() + Self::EDGE_DIST;
+        let right = pos.x >= work_area.right() - Self::EDGE_DIST;
+        let top = pos.y <= work_area.top() + Self::EDGE_DIST;
+
+        if left || right {
+            let upper = pos.y <= work_area.top() + Self::CORNER_SIZE;
+            let lower = pos.y >= work_area.bottom() - Self::CORNER_SIZE;
+            return Some(match (left, upper, lower) {
+                (true, true, _) => Snap::TopLeft,
+                (true, _, true) => Snap::BottomLeft,
+                (true, ..) => Snap::Left,
+                (false, true, _) => Snap::TopRight,
+                (false, _, true) => Snap::BottomRight,
+                (false, ..) => Snap::Right,
+            });
+        }
+
+        top.then_some(Snap::Maximized)
+    }
+
+    /// The snap after `key` is pressed with the snap shortcut modifiers, `None` to restore
+    pub fn by_key(current: Option<Self>, key: egui::Key) -> Option<Self> {
+        use Snap::*;
+        use egui::Key::*;
+
+        match (key, current) {
+            (ArrowLeft, Some(Right)) => None,
+            (ArrowLeft, Some(TopRight | TopLeft)) => Some(TopLeft),
+            (ArrowLeft, Some(BottomRight | BottomLeft)) => Some(BottomLeft),
+            (ArrowLeft, _) => Some(Left),
+
+            (ArrowRight, Some(Left)) => None,
+            (ArrowRight, Some(TopLeft | TopRight)) => Some(TopRight),
+            (ArrowRight, Some(BottomLeft | BottomRight)) => Some(BottomRight),
+            (ArrowRight, _) => Some(Right),
+
+            (ArrowUp, None | Some(Maximized)) => Some(Maximized),
+            (ArrowUp, Some(Left | TopLeft)) => Some(TopLeft),
+            (ArrowUp, Some(Right | TopRight)) => Some(TopRight),
+            (ArrowUp, Some(BottomLeft)) => Some(Left),
+            (ArrowUp, Some(BottomRight)) => Some(Right),
+
+            (ArrowDown, None | Some(Maximized)) => None,
+            (ArrowDown, Some(TopLeft)) => Some(Left),
+            (ArrowDown, Some(TopRight)) => Some(Right),
+            (ArrowDown, Some(Left | BottomLeft)) => Some(BottomLeft),
+            (ArrowDown, Some(Right | BottomRight)) => Some(BottomRight),
+
+            _ => current,
+        }
+    }
+
+    pub fn rect(self, work_area: egui::Rect) -> egui::Rect {
+        use egui::{Rect, pos2};
+
+        let (min, center, max) = (work_area.min, work_area.center(), work_area.max);
+        match self {
+            Snap::Maximized => work_area,
+            Snap::Left => Rect::from_min_max(min, pos2(center.x, max.y)),
+            Snap::Right => Rect::from_min_max(pos2(center.x, min.y), max),
+            Snap::TopLeft => Rect::from_min_max(min, center),
+            Snap::TopRight => Rect::from_min_max(pos2(center.x, min.y), pos2(max.x, center.y)),
+            Snap::BottomLeft => Rect::from_min_max(pos2(min.x, center.y), pos2(center.x, max.y)),
+            Snap::BottomRight => Rect::from_min_max(center, max),
+        }
+    }
+}
+
+const SNAP_ID: &str = "borderless_snap";
+const SNAP_DRAG_ID: &str = "borderless_snap_drag";
+const SNAP_DISABLED_ID: &str = "borderless_snap_disabled";
+const RESTORE_RECT_ID: &str = "borderless_restore_rect";
+
+/// Modifiers of the snap shortcuts (with arrow keys), `Super` is not reported by egui
+/// on every platform and is usually taken by the OS anyway
+pub const SNAP_MODIFIERS: egui::Modifiers = egui::Modifiers {
+    alt: true,
+    ctrl: false,
+    shift: false,
+    mac_cmd: false,
+    command: true,
+};
+
+/// Enables or disables snapping to the work area edges and the snap shortcuts, enabled by default
+pub fn set_snapping(ctx: &egui::Context, enabled: bool) {
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(SNAP_DISABLED_ID), !enabled));
+}
+
+fn snapping_enabled(ctx: &egui::Context) -> bool {
+    !ctx.data(|d| d.get_temp::<bool>(egui::Id::new(SNAP_DISABLED_ID)))
+        .unwrap_or(false)
+}
+
+fn current_snap(ctx: &egui::Context) -> Option<Snap> {
+    if ctx.input(|i| i.viewport().maximized.unwrap_or(false)) {
+        return Some(Snap::Maximized);
+    }
+
+    ctx.data(|d| d.get_temp::<Option<Snap>>(egui::Id::new(SNAP_ID)))
+        .flatten()
+}
+
+/// Work area of the monitor at `pos` (in points, relative to the screen), or the nearest one
+pub(crate) fn work_area_at(ctx: &egui::Context, pos: egui::Pos2) -> Option<egui::Rect> {
+    let ppp = ctx.pixels_per_point();
+    if let Some([left, top, right, bottom]) =
+        crate::platform::monitor_work_area((pos.x * ppp) as i32, (pos.y * ppp) as i32)
+    {
+        return Some(egui::Rect::from_min_max(
+            egui::pos2(left as f32 / ppp, top as f32 / ppp),
+            egui::pos2(right as f32 / ppp, bottom as f32 / ppp),
+        ));
+    }
+
+    // Without the monitor layout, assume monitors of the same size side by side
+    let size = ctx.input(|i| i.viewport().monitor_size)?;
+    let origin = egui::pos2(
+        (pos.x / size.x).floor() * size.x,
+        (pos.y / size.y).floor() * size.y,
+    );
+    Some(egui::Rect::from_min_size(origin, size))
+}
+
+/// Snaps the window, or restores it to the rect before snapped if `None`
+pub fn apply_snap(ctx: &egui::Context, snap: Option<Snap>) {
+    let current = current_snap(ctx);
+    if current == snap {
+        return;
+    }
+
+    let restore_id = egui::Id::new(RESTORE_RECT_ID);
+    if current.is_none()
+        && let Some((outer_rect, inner_rect)) =
+            ctx.input(|i| i.viewport().outer_rect.zip(i.viewport().inner_rect))
+    {
+        ctx.data_mut(|d| d.insert_temp(restore_id, (outer_rect.min, inner_rect.size())));
+    }
+
+    if current == Some(Snap::Maximized) {
+        ctx.send_viewport_cmd(ViewportCommand::Maximized(false));
+    }
+
+    match snap {
+        Some(Snap::Maximized) => ctx.send_viewport_cmd(ViewportCommand::Maximized(true)),
+        Some(snap) => {
+            let Some(outer_rect) = ctx.input(|i| i.viewport().outer_rect) else {
+                return;
+            };
+            let Some(work_area) = work_area_at(ctx, outer_rect.center()) else {
+                return;
+            };
+
+            let rect = snap.rect(work_area);
+            ctx.send_viewport_cmd(ViewportCommand::OuterPosition(rect.min));
+            ctx.send_viewport_cmd(ViewportCommand::InnerSize(rect.size()));
+        }
+        // Some platforms don't remember the size of undecorated windows
+        None => {
+            if let Some((pos, size)) =
+                ctx.data_mut(|d| d.remove_temp::<(egui::Pos2, egui::Vec2)>(restore_id))
+            {
+                ctx.send_viewport_cmd(ViewportCommand::InnerSize(size));
+                ctx.send_viewport_cmd(ViewportCommand::OuterPosition(pos));
+            }
+        }
+    }
+
+    let tile = snap.filter(|snap| *snap != Snap::Maximized);
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(SNAP_ID), tile));
+}
+
+/// Position and size of the window before it was maximized or snapped
+pub(crate) fn restore_rect(ctx: &egui::Context) -> Option<(egui::Pos2, egui::Vec2)> {
+    ctx.data(|d| d.get_temp(egui::Id::new(RESTORE_RECT_ID)))
+}
+
+fn begin_snap_drag(ctx: &egui::Context) {
+    let drag_id = egui::Id::new(SNAP_DRAG_ID);
+    if !snapping_enabled(ctx) || ctx.data(|d| d.get_temp::<bool>(drag_id)).unwrap_or(false) {
+        return;
+    }
+    ctx.data_mut(|d| d.insert_temp(drag_id, true));
+
+    // Dragging a tiled window out gives its size back, like the OS does
+    let snap_id = egui::Id::new(SNAP_ID);
+    if ctx
+        .data(|d| d.get_temp::<Option<Snap>>(snap_id))
+        .flatten()
+        .is_some()
+    {
+        ctx.data_mut(|d| d.insert_temp(snap_id, None::<Snap>));
+        if let Some((_, size)) = ctx
+            .data_mut(|d| d.remove_temp::<(egui::Pos2, egui::Vec2)>(egui::Id::new(RESTORE_RECT_ID)))
+        {
+            ctx.send_viewport_cmd(ViewportCommand::InnerSize(size));
+        }
+    }
+}
+
+/// Snaps the window when it is dropped with the pointer near the work area edges
+fn handle_snap_drag(ctx: &egui::Context) {
+    let drag_id = egui::Id::new(SNAP_DRAG_ID);
+    if !ctx.data(|d| d.get_temp::<bool>(drag_id)).unwrap_or(false) {
+        return;
+    }
+
+    let (down, pointer, outer_rect) = ctx.input(|i| {
+        (
+            i.pointer.any_down(),
+            i.pointer.latest_pos(),
+            i.viewport().outer_rect,
+        )
+    });
+    if down {
+        return;
+    }
+    ctx.data_mut(|d| d.remove::<bool>(drag_id));
+
+    let (Some(pointer), Some(outer_rect)) = (pointer, outer_rect) else {
+        return;
+    };
+
+    let pos = pointer + outer_rect.min.to_vec2();
+    if let Some(work_area) = work_area_at(ctx, pos)
+        && let Some(snap) = Snap::at_pointer(work_area, pos)
+    {
+        apply_snap(ctx, Some(snap));
+    }
+}
+
+fn handle_snap_shortcuts(ctx: &egui::Context) {
+    if !snapping_enabled(ctx) || ctx.input(|i| i.viewport().fullscreen.unwrap_or(false)) {
+        return;
+    }
+
+    use egui::Key::*;
+    for key in [ArrowLeft, ArrowRight, ArrowUp, ArrowDown] {
+        let shortcut = egui::KeyboardShortcut::new(SNAP_MODIFIERS, key);
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) {
+            apply_snap(ctx, Snap::by_key(current_snap(ctx), key));
+        }
+    }
 }
 
 pub fn title_bar(
@@ -170,6 +492,7 @@ pub fn title_bar_animated(ui: &mut egui::Ui, title_bar_rect: eframe::epaint::Rec
     );
 
     if opacity == 0.0 {
+        crate::platform::set_maximize_button_rect(None);
         return;
     }
 
@@ -189,6 +512,7 @@ pub fn close_maximize_minimize(
     fill_color: impl Into<Color32>,
 ) {
     if ui.input(|i| i.viewport().fullscreen.unwrap_or(false)) {
+        crate::platform::set_maximize_button_rect(None);
         return;
     }
 
@@ -239,11 +563,22 @@ pub fn close_maximize_minimize(
             codicons::ICON_CHROME_MAXIMIZE
         };
 
-        if ui.add(new_button(text.to_string())).clicked() {
-            ui.ctx()
-                .send_viewport_cmd(ViewportCommand::Maximized(!is_maximized));
+        // The OS takes the pointer over it to show the snap layouts, so it's highlighted here
+        let mut button = new_button(text.to_string());
+        if crate::platform::is_maximize_button_hovered() {
+            button = button.fill(ui.visuals().widgets.hovered.bg_fill);
         }
 
+        let response = ui.add(button);
+        if response.clicked() {
+            toggle_maximized(ui.ctx());
+        }
+
+        let ppp = ui.ctx().pixels_per_point();
+        let rect = response.rect * ppp;
+        let rect = [rect.left(), rect.top(), rect.right(), rect.bottom()].map(|v| v.round() as i32);
+        crate::platform::set_maximize_button_rect(Some(rect));
+
         if ui
             .add(new_button(codicons::ICON_CHROME_MINIMIZE.to_string()))
             .clicked()
@@ -252,3 +587,69 @@ pub fn close_maximize_minimize(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::{Key, Rect, pos2};
+
+    fn work_area() -> Rect {
+        Rect::from_min_max(pos2(1920.0, 0.0), pos2(3840.0, 1040.0))
+    }
+
+    #[test]
+    fn snap_at_edges_and_corners() {
+        let area = work_area();
+        assert_eq!(
+            Snap::at_pointer(area, pos2(1920.0, 500.0)),
+            Some(Snap::Left)
+        );
+        assert_eq!(
+            Snap::at_pointer(area, pos2(3839.0, 500.0)),
+            Some(Snap::Right)
+        );
+        assert_eq!(
+            Snap::at_pointer(area, pos2(1921.0, 10.0)),
+            Some(Snap::TopLeft)
+        );
+        assert_eq!(
+            Snap::at_pointer(area, pos2(3840.0, 1030.0)),
+            Some(Snap::BottomRight)
+        );
+        assert_eq!(
+            Snap::at_pointer(area, pos2(2800.0, 2.0)),
+            Some(Snap::Maximized)
+        );
+        assert_eq!(Snap::at_pointer(area, pos2(2800.0, 500.0)), None);
+    }
+
+    #[test]
+    fn snap_rects_tile_the_work_area() {
+        let area = work_area();
+        assert_eq!(
+            Snap::Left.rect(area),
+            Rect::from_min_max(pos2(1920.0, 0.0), pos2(2880.0, 1040.0))
+        );
+        assert_eq!(
+            Snap::BottomRight.rect(area),
+            Rect::from_min_max(pos2(2880.0, 520.0), pos2(3840.0, 1040.0))
+        );
+        assert_eq!(Snap::Maximized.rect(area), area);
+    }
+
+    #[test]
+    fn snap_by_key() {
+        assert_eq!(Snap::by_key(None, Key::ArrowLeft), Some(Snap::Left));
+        assert_eq!(
+            Snap::by_key(Some(Snap::Left), Key::ArrowUp),
+            Some(Snap::TopLeft)
+        );
+        assert_eq!(
+            Snap::by_key(Some(Snap::TopLeft), Key::ArrowRight),
+            Some(Snap::TopRight)
+        );
+        assert_eq!(Snap::by_key(Some(Snap::Right), Key::ArrowLeft), None);
+        assert_eq!(Snap::by_key(None, Key::ArrowUp), Some(Snap::Maximized));
+        assert_eq!(Snap::by_key(Some(Snap::Maximized), Key::ArrowDown), None);
+    }
+}
@@ -1,6 +1,8 @@
 //! Generated by https://github.com/juliettef/IconFontCppHeaders script GenerateIconFontCppHeaders.py for language Rust
 //! from https://raw.githubusercontent.com/microsoft/vscode-codicons/main/dist/codicon.css
 //! for use with https://github.com/microsoft/vscode-codicons/blob/main/dist/codicon.ttf
+use eframe::egui;
+
 pub const FONT_ICON_FILE_NAME_CI: &str = "codicon.ttf";
 
 pub const ICON_MIN: char = '\u{ea60}';
@@ -564,3 +566,691 @@ pub const ICON_RUN_COVERAGE: char = '\u{ec2c}';
 pub const ICON_RUN_ALL_COVERAGE: char = '\u{ec2d}';
 pub const ICON_COVERAGE: char = '\u{ec2e}';
 pub const ICON_GIT_FETCH: char = '\u{f101}';
+
+/// Name (as in `codicon.css`, e.g. `"debug-start"`) and glyph of every icon,
+/// icons with several names appear once per name
+pub const ALL: &[(&str, char)] = &[
+    ("add", ICON_ADD),
+    ("plus", ICON_PLUS),
+    ("gist-new", ICON_GIST_NEW),
+    ("repo-create", ICON_REPO_CREATE),
+    ("lightbulb", ICON_LIGHTBULB),
+    ("light-bulb", ICON_LIGHT_BULB),
+    ("repo", ICON_REPO),
+    ("repo-delete", ICON_REPO_DELETE),
+    ("gist-fork", ICON_GIST_FORK),
+    ("repo-forked", ICON_REPO_FORKED),
+    ("git-pull-request", ICON_GIT_PULL_REQUEST),
+    (
+        "git-pull-request-abandoned",
+        ICON_GIT_PULL_REQUEST_ABANDONED,
+    ),
+    ("record-keys", ICON_RECORD_KEYS),
+    ("keyboard", ICON_KEYBOARD),
+    ("tag", ICON_TAG),
+    ("git-pull-request-label", ICON_GIT_PULL_REQUEST_LABEL),
+    ("tag-add", ICON_TAG_ADD),
+    ("tag-remove", ICON_TAG_REMOVE),
+    ("person", ICON_PERSON),
+    ("person-follow", ICON_PERSON_FOLLOW),
+    ("person-outline", ICON_PERSON_OUTLINE),
+    ("person-filled", ICON_PERSON_FILLED),
+    ("git-branch", ICON_GIT_BRANCH),
+    ("git-branch-create", ICON_GIT_BRANCH_CREATE),
+    ("git-branch-delete", ICON_GIT_BRANCH_DELETE),
+    ("source-control", ICON_SOURCE_CONTROL),
+    ("mirror", ICON_MIRROR),
+    ("mirror-public", ICON_MIRROR_PUBLIC),
+    ("star", ICON_STAR),
+    ("star-add", ICON_STAR_ADD),
+    ("star-delete", ICON_STAR_DELETE),
+    ("star-empty", ICON_STAR_EMPTY),
+    ("comment", ICON_COMMENT),
+    ("comment-add", ICON_COMMENT_ADD),
+    ("alert", ICON_ALERT),
+    ("warning", ICON_WARNING),
+    ("search", ICON_SEARCH),
+    ("search-save", ICON_SEARCH_SAVE),
+    ("log-out", ICON_LOG_OUT),
+    ("sign-out", ICON_SIGN_OUT),
+    ("log-in", ICON_LOG_IN),
+    ("sign-in", ICON_SIGN_IN),
+    ("eye", ICON_EYE),
+    ("eye-unwatch", ICON_EYE_UNWATCH),
+    ("eye-watch", ICON_EYE_WATCH),
+    ("circle-filled", ICON_CIRCLE_FILLED),
+    ("primitive-dot", ICON_PRIMITIVE_DOT),
+    ("close-dirty", ICON_CLOSE_DIRTY),
+    ("debug-breakpoint", ICON_DEBUG_BREAKPOINT),
+    ("debug-breakpoint-disabled", ICON_DEBUG_BREAKPOINT_DISABLED),
+    ("debug-hint", ICON_DEBUG_HINT),
+    (
+        "terminal-decoration-success",
+        ICON_TERMINAL_DECORATION_SUCCESS,
+    ),
+    ("primitive-square", ICON_PRIMITIVE_SQUARE),
+    ("edit", ICON_EDIT),
+    ("pencil", ICON_PENCIL),
+    ("info", ICON_INFO),
+    ("issue-opened", ICON_ISSUE_OPENED),
+    ("gist-private", ICON_GIST_PRIVATE),
+    ("git-fork-private", ICON_GIT_FORK_PRIVATE),
+    ("lock", ICON_LOCK),
+    ("mirror-private", ICON_MIRROR_PRIVATE),
+    ("close", ICON_CLOSE),
+    ("remove-close", ICON_REMOVE_CLOSE),
+    ("x", ICON_X),
+    ("repo-sync", ICON_REPO_SYNC),
+    ("sync", ICON_SYNC),
+    ("clone", ICON_CLONE),
+    ("desktop-download", ICON_DESKTOP_DOWNLOAD),
+    ("beaker", ICON_BEAKER),
+    ("microscope", ICON_MICROSCOPE),
+    ("vm", ICON_VM),
+    ("device-desktop", ICON_DEVICE_DESKTOP),
+    ("file", ICON_FILE),
+    ("file-text", ICON_FILE_TEXT),
+    ("more", ICON_MORE),
+    ("ellipsis", ICON_ELLIPSIS),
+    ("kebab-horizontal", ICON_KEBAB_HORIZONTAL),
+    ("mail-reply", ICON_MAIL_REPLY),
+    ("reply", ICON_REPLY),
+    ("organization", ICON_ORGANIZATION),
+    ("organization-filled", ICON_ORGANIZATION_FILLED),
+    ("organization-outline", ICON_ORGANIZATION_OUTLINE),
+    ("new-file", ICON_NEW_FILE),
+    ("file-add", ICON_FILE_ADD),
+    ("new-folder", ICON_NEW_FOLDER),
+    ("file-directory-create", ICON_FILE_DIRECTORY_CREATE),
+    ("trash", ICON_TRASH),
+    ("trashcan", ICON_TRASHCAN),
+    ("history", ICON_HISTORY),
+    ("clock", ICON_CLOCK),
+    ("folder", ICON_FOLDER),
+    ("file-directory", ICON_FILE_DIRECTORY),
+    ("symbol-folder", ICON_SYMBOL_FOLDER),
+    ("logo-github", ICON_LOGO_GITHUB),
+    ("mark-github", ICON_MARK_GITHUB),
+    ("github", ICON_GITHUB),
+    ("terminal", ICON_TERMINAL),
+    ("console", ICON_CONSOLE),
+    ("repl", ICON_REPL),
+    ("zap", ICON_ZAP),
+    ("symbol-event", ICON_SYMBOL_EVENT),
+    ("error", ICON_ERROR),
+    ("stop", ICON_STOP),
+    ("variable", ICON_VARIABLE),
+    ("symbol-variable", ICON_SYMBOL_VARIABLE),
+    ("array", ICON_ARRAY),
+    ("symbol-array", ICON_SYMBOL_ARRAY),
+    ("symbol-module", ICON_SYMBOL_MODULE),
+    ("symbol-package", ICON_SYMBOL_PACKAGE),
+    ("symbol-namespace", ICON_SYMBOL_NAMESPACE),
+    ("symbol-object", ICON_SYMBOL_OBJECT),
+    ("symbol-method", ICON_SYMBOL_METHOD),
+    ("symbol-function", ICON_SYMBOL_FUNCTION),
+    ("symbol-constructor", ICON_SYMBOL_CONSTRUCTOR),
+    ("symbol-boolean", ICON_SYMBOL_BOOLEAN),
+    ("symbol-null", ICON_SYMBOL_NULL),
+    ("symbol-numeric", ICON_SYMBOL_NUMERIC),
+    ("symbol-number", ICON_SYMBOL_NUMBER),
+    ("symbol-structure", ICON_SYMBOL_STRUCTURE),
+    ("symbol-struct", ICON_SYMBOL_STRUCT),
+    ("symbol-parameter", ICON_SYMBOL_PARAMETER),
+    ("symbol-type-parameter", ICON_SYMBOL_TYPE_PARAMETER),
+    ("symbol-key", ICON_SYMBOL_KEY),
+    ("symbol-text", ICON_SYMBOL_TEXT),
+    ("symbol-reference", ICON_SYMBOL_REFERENCE),
+    ("go-to-file", ICON_GO_TO_FILE),
+    ("symbol-enum", ICON_SYMBOL_ENUM),
+    ("symbol-value", ICON_SYMBOL_VALUE),
+    ("symbol-ruler", ICON_SYMBOL_RULER),
+    ("symbol-unit", ICON_SYMBOL_UNIT),
+    ("activate-breakpoints", ICON_ACTIVATE_BREAKPOINTS),
+    ("archive", ICON_ARCHIVE),
+    ("arrow-both", ICON_ARROW_BOTH),
+    ("arrow-down", ICON_ARROW_DOWN),
+    ("arrow-left", ICON_ARROW_LEFT),
+    ("arrow-right", ICON_ARROW_RIGHT),
+    ("arrow-small-down", ICON_ARROW_SMALL_DOWN),
+    ("arrow-small-left", ICON_ARROW_SMALL_LEFT),
+    ("arrow-small-right", ICON_ARROW_SMALL_RIGHT),
+    ("arrow-small-up", ICON_ARROW_SMALL_UP),
+    ("arrow-up", ICON_ARROW_UP),
+    ("bell", ICON_BELL),
+    ("bold", ICON_BOLD),
+    ("book", ICON_BOOK),
+    ("bookmark", ICON_BOOKMARK),
+    (
+        "debug-breakpoint-conditional-unverified",
+        ICON_DEBUG_BREAKPOINT_CONDITIONAL_UNVERIFIED,
+    ),
+    (
+        "debug-breakpoint-conditional",
+        ICON_DEBUG_BREAKPOINT_CONDITIONAL,
+    ),
+    (
+        "debug-breakpoint-conditional-disabled",
+        ICON_DEBUG_BREAKPOINT_CONDITIONAL_DISABLED,
+    ),
+    (
+        "debug-breakpoint-data-unverified",
+        ICON_DEBUG_BREAKPOINT_DATA_UNVERIFIED,
+    ),
+    ("debug-breakpoint-data", ICON_DEBUG_BREAKPOINT_DATA),
+    (
+        "debug-breakpoint-data-disabled",
+        ICON_DEBUG_BREAKPOINT_DATA_DISABLED,
+    ),
+    (
+        "debug-breakpoint-log-unverified",
+        ICON_DEBUG_BREAKPOINT_LOG_UNVERIFIED,
+    ),
+    ("debug-breakpoint-log", ICON_DEBUG_BREAKPOINT_LOG),
+    (
+        "debug-breakpoint-log-disabled",
+        ICON_DEBUG_BREAKPOINT_LOG_DISABLED,
+    ),
+    ("briefcase", ICON_BRIEFCASE),
+    ("broadcast", ICON_BROADCAST),
+    ("browser", ICON_BROWSER),
+    ("bug", ICON_BUG),
+    ("calendar", ICON_CALENDAR),
+    ("case-sensitive", ICON_CASE_SENSITIVE),
+    ("check", ICON_CHECK),
+    ("checklist", ICON_CHECKLIST),
+    ("chevron-down", ICON_CHEVRON_DOWN),
+    ("chevron-left", ICON_CHEVRON_LEFT),
+    ("chevron-right", ICON_CHEVRON_RIGHT),
+    ("chevron-up", ICON_CHEVRON_UP),
+    ("chrome-close", ICON_CHROME_CLOSE),
+    ("chrome-maximize", ICON_CHROME_MAXIMIZE),
+    ("chrome-minimize", ICON_CHROME_MINIMIZE),
+    ("chrome-restore", ICON_CHROME_RESTORE),
+    ("circle-outline", ICON_CIRCLE_OUTLINE),
+    ("circle", ICON_CIRCLE),
+    (
+        "debug-breakpoint-unverified",
+        ICON_DEBUG_BREAKPOINT_UNVERIFIED,
+    ),
+    (
+        "terminal-decoration-incomplete",
+        ICON_TERMINAL_DECORATION_INCOMPLETE,
+    ),
+    ("circle-slash", ICON_CIRCLE_SLASH),
+    ("circuit-board", ICON_CIRCUIT_BOARD),
+    ("clear-all", ICON_CLEAR_ALL),
+    ("clippy", ICON_CLIPPY),
+    ("close-all", ICON_CLOSE_ALL),
+    ("cloud-download", ICON_CLOUD_DOWNLOAD),
+    ("cloud-upload", ICON_CLOUD_UPLOAD),
+    ("code", ICON_CODE),
+    ("collapse-all", ICON_COLLAPSE_ALL),
+    ("color-mode", ICON_COLOR_MODE),
+    ("comment-discussion", ICON_COMMENT_DISCUSSION),
+    ("credit-card", ICON_CREDIT_CARD),
+    ("dash", ICON_DASH),
+    ("dashboard", ICON_DASHBOARD),
+    ("database", ICON_DATABASE),
+    ("debug-continue", ICON_DEBUG_CONTINUE),
+    ("debug-disconnect", ICON_DEBUG_DISCONNECT),
+    ("debug-pause", ICON_DEBUG_PAUSE),
+    ("debug-restart", ICON_DEBUG_RESTART),
+    ("debug-start", ICON_DEBUG_START),
+    ("debug-step-into", ICON_DEBUG_STEP_INTO),
+    ("debug-step-out", ICON_DEBUG_STEP_OUT),
+    ("debug-step-over", ICON_DEBUG_STEP_OVER),
+    ("debug-stop", ICON_DEBUG_STOP),
+    ("debug", ICON_DEBUG),
+    ("device-camera-video", ICON_DEVICE_CAMERA_VIDEO),
+    ("device-camera", ICON_DEVICE_CAMERA),
+    ("device-mobile", ICON_DEVICE_MOBILE),
+    ("diff-added", ICON_DIFF_ADDED),
+    ("diff-ignored", ICON_DIFF_IGNORED),
+    ("diff-modified", ICON_DIFF_MODIFIED),
+    ("diff-removed", ICON_DIFF_REMOVED),
+    ("diff-renamed", ICON_DIFF_RENAMED),
+    ("diff", ICON_DIFF),
+    ("diff-sidebyside", ICON_DIFF_SIDEBYSIDE),
+    ("discard", ICON_DISCARD),
+    ("editor-layout", ICON_EDITOR_LAYOUT),
+    ("empty-window", ICON_EMPTY_WINDOW),
+    ("exclude", ICON_EXCLUDE),
+    ("extensions", ICON_EXTENSIONS),
+    ("eye-closed", ICON_EYE_CLOSED),
+    ("file-binary", ICON_FILE_BINARY),
+    ("file-code", ICON_FILE_CODE),
+    ("file-media", ICON_FILE_MEDIA),
+    ("file-pdf", ICON_FILE_PDF),
+    ("file-submodule", ICON_FILE_SUBMODULE),
+    ("file-symlink-directory", ICON_FILE_SYMLINK_DIRECTORY),
+    ("file-symlink-file", ICON_FILE_SYMLINK_FILE),
+    ("file-zip", ICON_FILE_ZIP),
+    ("files", ICON_FILES),
+    ("filter", ICON_FILTER),
+    ("flame", ICON_FLAME),
+    ("fold-down", ICON_FOLD_DOWN),
+    ("fold-up", ICON_FOLD_UP),
+    ("fold", ICON_FOLD),
+    ("folder-active", ICON_FOLDER_ACTIVE),
+    ("folder-opened", ICON_FOLDER_OPENED),
+    ("gear", ICON_GEAR),
+    ("gift", ICON_GIFT),
+    ("gist-secret", ICON_GIST_SECRET),
+    ("gist", ICON_GIST),
+    ("git-commit", ICON_GIT_COMMIT),
+    ("git-compare", ICON_GIT_COMPARE),
+    ("compare-changes", ICON_COMPARE_CHANGES),
+    ("git-merge", ICON_GIT_MERGE),
+    ("github-action", ICON_GITHUB_ACTION),
+    ("github-alt", ICON_GITHUB_ALT),
+    ("globe", ICON_GLOBE),
+    ("grabber", ICON_GRABBER),
+    ("graph", ICON_GRAPH),
+    ("gripper", ICON_GRIPPER),
+    ("heart", ICON_HEART),
+    ("home", ICON_HOME),
+    ("horizontal-rule", ICON_HORIZONTAL_RULE),
+    ("hubot", ICON_HUBOT),
+    ("inbox", ICON_INBOX),
+    ("issue-reopened", ICON_ISSUE_REOPENED),
+    ("issues", ICON_ISSUES),
+    ("italic", ICON_ITALIC),
+    ("jersey", ICON_JERSEY),
+    ("json", ICON_JSON),
+    ("kebab-vertical", ICON_KEBAB_VERTICAL),
+    ("key", ICON_KEY),
+    ("law", ICON_LAW),
+    ("lightbulb-autofix", ICON_LIGHTBULB_AUTOFIX),
+    ("link-external", ICON_LINK_EXTERNAL),
+    ("link", ICON_LINK),
+    ("list-ordered", ICON_LIST_ORDERED),
+    ("list-unordered", ICON_LIST_UNORDERED),
+    ("live-share", ICON_LIVE_SHARE),
+    ("loading", ICON_LOADING),
+    ("location", ICON_LOCATION),
+    ("mail-read", ICON_MAIL_READ),
+    ("mail", ICON_MAIL),
+    ("markdown", ICON_MARKDOWN),
+    ("megaphone", ICON_MEGAPHONE),
+    ("mention", ICON_MENTION),
+    ("milestone", ICON_MILESTONE),
+    (
+        "git-pull-request-milestone",
+        ICON_GIT_PULL_REQUEST_MILESTONE,
+    ),
+    ("mortar-board", ICON_MORTAR_BOARD),
+    ("move", ICON_MOVE),
+    ("multiple-windows", ICON_MULTIPLE_WINDOWS),
+    ("mute", ICON_MUTE),
+    ("no-newline", ICON_NO_NEWLINE),
+    ("note", ICON_NOTE),
+    ("octoface", ICON_OCTOFACE),
+    ("open-preview", ICON_OPEN_PREVIEW),
+    ("package", ICON_PACKAGE),
+    ("paintcan", ICON_PAINTCAN),
+    ("pin", ICON_PIN),
+    ("play", ICON_PLAY),
+    ("run", ICON_RUN),
+    ("plug", ICON_PLUG),
+    ("preserve-case", ICON_PRESERVE_CASE),
+    ("preview", ICON_PREVIEW),
+    ("project", ICON_PROJECT),
+    ("pulse", ICON_PULSE),
+    ("question", ICON_QUESTION),
+    ("quote", ICON_QUOTE),
+    ("radio-tower", ICON_RADIO_TOWER),
+    ("reactions", ICON_REACTIONS),
+    ("references", ICON_REFERENCES),
+    ("refresh", ICON_REFRESH),
+    ("regex", ICON_REGEX),
+    ("remote-explorer", ICON_REMOTE_EXPLORER),
+    ("remote", ICON_REMOTE),
+    ("remove", ICON_REMOVE),
+    ("replace-all", ICON_REPLACE_ALL),
+    ("replace", ICON_REPLACE),
+    ("repo-clone", ICON_REPO_CLONE),
+    ("repo-force-push", ICON_REPO_FORCE_PUSH),
+    ("repo-pull", ICON_REPO_PULL),
+    ("repo-push", ICON_REPO_PUSH),
+    ("report", ICON_REPORT),
+    ("request-changes", ICON_REQUEST_CHANGES),
+    ("rocket", ICON_ROCKET),
+    ("root-folder-opened", ICON_ROOT_FOLDER_OPENED),
+    ("root-folder", ICON_ROOT_FOLDER),
+    ("rss", ICON_RSS),
+    ("ruby", ICON_RUBY),
+    ("save-all", ICON_SAVE_ALL),
+    ("save-as", ICON_SAVE_AS),
+    ("save", ICON_SAVE),
+    ("screen-full", ICON_SCREEN_FULL),
+    ("screen-normal", ICON_SCREEN_NORMAL),
+    ("search-stop", ICON_SEARCH_STOP),
+    ("server", ICON_SERVER),
+    ("settings-gear", ICON_SETTINGS_GEAR),
+    ("settings", ICON_SETTINGS),
+    ("shield", ICON_SHIELD),
+    ("smiley", ICON_SMILEY),
+    ("sort-precedence", ICON_SORT_PRECEDENCE),
+    ("split-horizontal", ICON_SPLIT_HORIZONTAL),
+    ("split-vertical", ICON_SPLIT_VERTICAL),
+    ("squirrel", ICON_SQUIRREL),
+    ("star-full", ICON_STAR_FULL),
+    ("star-half", ICON_STAR_HALF),
+    ("symbol-class", ICON_SYMBOL_CLASS),
+    ("symbol-color", ICON_SYMBOL_COLOR),
+    ("symbol-constant", ICON_SYMBOL_CONSTANT),
+    ("symbol-enum-member", ICON_SYMBOL_ENUM_MEMBER),
+    ("symbol-field", ICON_SYMBOL_FIELD),
+    ("symbol-file", ICON_SYMBOL_FILE),
+    ("symbol-interface", ICON_SYMBOL_INTERFACE),
+    ("symbol-keyword", ICON_SYMBOL_KEYWORD),
+    ("symbol-misc", ICON_SYMBOL_MISC),
+    ("symbol-operator", ICON_SYMBOL_OPERATOR),
+    ("symbol-property", ICON_SYMBOL_PROPERTY),
+    ("wrench", ICON_WRENCH),
+    ("wrench-subaction", ICON_WRENCH_SUBACTION),
+    ("symbol-snippet", ICON_SYMBOL_SNIPPET),
+    ("tasklist", ICON_TASKLIST),
+    ("telescope", ICON_TELESCOPE),
+    ("text-size", ICON_TEXT_SIZE),
+    ("three-bars", ICON_THREE_BARS),
+    ("thumbsdown", ICON_THUMBSDOWN),
+    ("thumbsup", ICON_THUMBSUP),
+    ("tools", ICON_TOOLS),
+    ("triangle-down", ICON_TRIANGLE_DOWN),
+    ("triangle-left", ICON_TRIANGLE_LEFT),
+    ("triangle-right", ICON_TRIANGLE_RIGHT),
+    ("triangle-up", ICON_TRIANGLE_UP),
+    ("twitter", ICON_TWITTER),
+    ("unfold", ICON_UNFOLD),
+    ("unlock", ICON_UNLOCK),
+    ("unmute", ICON_UNMUTE),
+    ("unverified", ICON_UNVERIFIED),
+    ("verified", ICON_VERIFIED),
+    ("versions", ICON_VERSIONS),
+    ("vm-active", ICON_VM_ACTIVE),
+    ("vm-outline", ICON_VM_OUTLINE),
+    ("vm-running", ICON_VM_RUNNING),
+    ("watch", ICON_WATCH),
+    ("whitespace", ICON_WHITESPACE),
+    ("whole-word", ICON_WHOLE_WORD),
+    ("window", ICON_WINDOW),
+    ("word-wrap", ICON_WORD_WRAP),
+    ("zoom-in", ICON_ZOOM_IN),
+    ("zoom-out", ICON_ZOOM_OUT),
+    ("list-filter", ICON_LIST_FILTER),
+    ("list-flat", ICON_LIST_FLAT),
+    ("list-selection", ICON_LIST_SELECTION),
+    ("selection", ICON_SELECTION),
+    ("list-tree", ICON_LIST_TREE),
+    (
+        "debug-breakpoint-function-unverified",
+        ICON_DEBUG_BREAKPOINT_FUNCTION_UNVERIFIED,
+    ),
+    ("debug-breakpoint-function", ICON_DEBUG_BREAKPOINT_FUNCTION),
+    (
+        "debug-breakpoint-function-disabled",
+        ICON_DEBUG_BREAKPOINT_FUNCTION_DISABLED,
+    ),
+    ("debug-stackframe-active", ICON_DEBUG_STACKFRAME_ACTIVE),
+    ("circle-small-filled", ICON_CIRCLE_SMALL_FILLED),
+    ("debug-stackframe-dot", ICON_DEBUG_STACKFRAME_DOT),
+    ("terminal-decoration-mark", ICON_TERMINAL_DECORATION_MARK),
+    ("debug-stackframe", ICON_DEBUG_STACKFRAME),
+    ("debug-stackframe-focused", ICON_DEBUG_STACKFRAME_FOCUSED),
+    (
+        "debug-breakpoint-unsupported",
+        ICON_DEBUG_BREAKPOINT_UNSUPPORTED,
+    ),
+    ("symbol-string", ICON_SYMBOL_STRING),
+    ("debug-reverse-continue", ICON_DEBUG_REVERSE_CONTINUE),
+    ("debug-step-back", ICON_DEBUG_STEP_BACK),
+    ("debug-restart-frame", ICON_DEBUG_RESTART_FRAME),
+    ("debug-alt", ICON_DEBUG_ALT),
+    ("call-incoming", ICON_CALL_INCOMING),
+    ("call-outgoing", ICON_CALL_OUTGOING),
+    ("menu", ICON_MENU),
+    ("expand-all", ICON_EXPAND_ALL),
+    ("feedback", ICON_FEEDBACK),
+    ("git-pull-request-reviewer", ICON_GIT_PULL_REQUEST_REVIEWER),
+    ("group-by-ref-type", ICON_GROUP_BY_REF_TYPE),
+    ("ungroup-by-ref-type", ICON_UNGROUP_BY_REF_TYPE),
+    ("account", ICON_ACCOUNT),
+    ("git-pull-request-assignee", ICON_GIT_PULL_REQUEST_ASSIGNEE),
+    ("bell-dot", ICON_BELL_DOT),
+    ("debug-console", ICON_DEBUG_CONSOLE),
+    ("library", ICON_LIBRARY),
+    ("output", ICON_OUTPUT),
+    ("run-all", ICON_RUN_ALL),
+    ("sync-ignored", ICON_SYNC_IGNORED),
+    ("pinned", ICON_PINNED),
+    ("github-inverted", ICON_GITHUB_INVERTED),
+    ("server-process", ICON_SERVER_PROCESS),
+    ("server-environment", ICON_SERVER_ENVIRONMENT),
+    ("pass", ICON_PASS),
+    ("issue-closed", ICON_ISSUE_CLOSED),
+    ("stop-circle", ICON_STOP_CIRCLE),
+    ("play-circle", ICON_PLAY_CIRCLE),
+    ("record", ICON_RECORD),
+    ("debug-alt-small", ICON_DEBUG_ALT_SMALL),
+    ("vm-connect", ICON_VM_CONNECT),
+    ("cloud", ICON_CLOUD),
+    ("merge", ICON_MERGE),
+    ("export", ICON_EXPORT),
+    ("graph-left", ICON_GRAPH_LEFT),
+    ("magnet", ICON_MAGNET),
+    ("notebook", ICON_NOTEBOOK),
+    ("redo", ICON_REDO),
+    ("check-all", ICON_CHECK_ALL),
+    ("pinned-dirty", ICON_PINNED_DIRTY),
+    ("pass-filled", ICON_PASS_FILLED),
+    ("circle-large-filled", ICON_CIRCLE_LARGE_FILLED),
+    ("circle-large", ICON_CIRCLE_LARGE),
+    ("circle-large-outline", ICON_CIRCLE_LARGE_OUTLINE),
+    ("combine", ICON_COMBINE),
+    ("gather", ICON_GATHER),
+    ("table", ICON_TABLE),
+    ("variable-group", ICON_VARIABLE_GROUP),
+    ("type-hierarchy", ICON_TYPE_HIERARCHY),
+    ("type-hierarchy-sub", ICON_TYPE_HIERARCHY_SUB),
+    ("type-hierarchy-super", ICON_TYPE_HIERARCHY_SUPER),
+    ("git-pull-request-create", ICON_GIT_PULL_REQUEST_CREATE),
+    ("run-above", ICON_RUN_ABOVE),
+    ("run-below", ICON_RUN_BELOW),
+    ("notebook-template", ICON_NOTEBOOK_TEMPLATE),
+    ("debug-rerun", ICON_DEBUG_RERUN),
+    ("workspace-trusted", ICON_WORKSPACE_TRUSTED),
+    ("workspace-untrusted", ICON_WORKSPACE_UNTRUSTED),
+    ("workspace-unknown", ICON_WORKSPACE_UNKNOWN),
+    ("terminal-cmd", ICON_TERMINAL_CMD),
+    ("terminal-debian", ICON_TERMINAL_DEBIAN),
+    ("terminal-linux", ICON_TERMINAL_LINUX),
+    ("terminal-powershell", ICON_TERMINAL_POWERSHELL),
+    ("terminal-tmux", ICON_TERMINAL_TMUX),
+    ("terminal-ubuntu", ICON_TERMINAL_UBUNTU),
+    ("terminal-bash", ICON_TERMINAL_BASH),
+    ("arrow-swap", ICON_ARROW_SWAP),
+    ("copy", ICON_COPY),
+    ("person-add", ICON_PERSON_ADD),
+    ("filter-filled", ICON_FILTER_FILLED),
+    ("wand", ICON_WAND),
+    ("debug-line-by-line", ICON_DEBUG_LINE_BY_LINE),
+    ("inspect", ICON_INSPECT),
+    ("layers", ICON_LAYERS),
+    ("layers-dot", ICON_LAYERS_DOT),
+    ("layers-active", ICON_LAYERS_ACTIVE),
+    ("compass", ICON_COMPASS),
+    ("compass-dot", ICON_COMPASS_DOT),
+    ("compass-active", ICON_COMPASS_ACTIVE),
+    ("azure", ICON_AZURE),
+    ("issue-draft", ICON_ISSUE_DRAFT),
+    ("git-pull-request-closed", ICON_GIT_PULL_REQUEST_CLOSED),
+    ("git-pull-request-draft", ICON_GIT_PULL_REQUEST_DRAFT),
+    ("debug-all", ICON_DEBUG_ALL),
+    ("debug-coverage", ICON_DEBUG_COVERAGE),
+    ("run-errors", ICON_RUN_ERRORS),
+    ("folder-library", ICON_FOLDER_LIBRARY),
+    ("debug-continue-small", ICON_DEBUG_CONTINUE_SMALL),
+    ("beaker-stop", ICON_BEAKER_STOP),
+    ("graph-line", ICON_GRAPH_LINE),
+    ("graph-scatter", ICON_GRAPH_SCATTER),
+    ("pie-chart", ICON_PIE_CHART),
+    ("bracket", ICON_BRACKET),
+    ("bracket-dot", ICON_BRACKET_DOT),
+    ("bracket-error", ICON_BRACKET_ERROR),
+    ("lock-small", ICON_LOCK_SMALL),
+    ("azure-devops", ICON_AZURE_DEVOPS),
+    ("verified-filled", ICON_VERIFIED_FILLED),
+    ("newline", ICON_NEWLINE),
+    ("layout", ICON_LAYOUT),
+    ("layout-activitybar-left", ICON_LAYOUT_ACTIVITYBAR_LEFT),
+    ("layout-activitybar-right", ICON_LAYOUT_ACTIVITYBAR_RIGHT),
+    ("layout-panel-left", ICON_LAYOUT_PANEL_LEFT),
+    ("layout-panel-center", ICON_LAYOUT_PANEL_CENTER),
+    ("layout-panel-justify", ICON_LAYOUT_PANEL_JUSTIFY),
+    ("layout-panel-right", ICON_LAYOUT_PANEL_RIGHT),
+    ("layout-panel", ICON_LAYOUT_PANEL),
+    ("layout-sidebar-left", ICON_LAYOUT_SIDEBAR_LEFT),
+    ("layout-sidebar-right", ICON_LAYOUT_SIDEBAR_RIGHT),
+    ("layout-statusbar", ICON_LAYOUT_STATUSBAR),
+    ("layout-menubar", ICON_LAYOUT_MENUBAR),
+    ("layout-centered", ICON_LAYOUT_CENTERED),
+    ("target", ICON_TARGET),
+    ("indent", ICON_INDENT),
+    ("record-small", ICON_RECORD_SMALL),
+    ("error-small", ICON_ERROR_SMALL),
+    ("terminal-decoration-error", ICON_TERMINAL_DECORATION_ERROR),
+    ("arrow-circle-down", ICON_ARROW_CIRCLE_DOWN),
+    ("arrow-circle-left", ICON_ARROW_CIRCLE_LEFT),
+    ("arrow-circle-right", ICON_ARROW_CIRCLE_RIGHT),
+    ("arrow-circle-up", ICON_ARROW_CIRCLE_UP),
+    ("layout-sidebar-right-off", ICON_LAYOUT_SIDEBAR_RIGHT_OFF),
+    ("layout-panel-off", ICON_LAYOUT_PANEL_OFF),
+    ("layout-sidebar-left-off", ICON_LAYOUT_SIDEBAR_LEFT_OFF),
+    ("blank", ICON_BLANK),
+    ("heart-filled", ICON_HEART_FILLED),
+    ("map", ICON_MAP),
+    ("map-filled", ICON_MAP_FILLED),
+    ("circle-small", ICON_CIRCLE_SMALL),
+    ("bell-slash", ICON_BELL_SLASH),
+    ("bell-slash-dot", ICON_BELL_SLASH_DOT),
+    ("comment-unresolved", ICON_COMMENT_UNRESOLVED),
+    (
+        "git-pull-request-go-to-changes",
+        ICON_GIT_PULL_REQUEST_GO_TO_CHANGES,
+    ),
+    (
+        "git-pull-request-new-changes",
+        ICON_GIT_PULL_REQUEST_NEW_CHANGES,
+    ),
+    ("search-fuzzy", ICON_SEARCH_FUZZY),
+    ("comment-draft", ICON_COMMENT_DRAFT),
+    ("send", ICON_SEND),
+    ("sparkle", ICON_SPARKLE),
+    ("insert", ICON_INSERT),
+    ("mic", ICON_MIC),
+    ("thumbsdown-filled", ICON_THUMBSDOWN_FILLED),
+    ("thumbsup-filled", ICON_THUMBSUP_FILLED),
+    ("coffee", ICON_COFFEE),
+    ("snake", ICON_SNAKE),
+    ("game", ICON_GAME),
+    ("vr", ICON_VR),
+    ("chip", ICON_CHIP),
+    ("piano", ICON_PIANO),
+    ("music", ICON_MUSIC),
+    ("mic-filled", ICON_MIC_FILLED),
+    ("repo-fetch", ICON_REPO_FETCH),
+    ("copilot", ICON_COPILOT),
+    ("lightbulb-sparkle", ICON_LIGHTBULB_SPARKLE),
+    ("robot", ICON_ROBOT),
+    ("sparkle-filled", ICON_SPARKLE_FILLED),
+    ("diff-single", ICON_DIFF_SINGLE),
+    ("diff-multiple", ICON_DIFF_MULTIPLE),
+    ("surround-with", ICON_SURROUND_WITH),
+    ("share", ICON_SHARE),
+    ("git-stash", ICON_GIT_STASH),
+    ("git-stash-apply", ICON_GIT_STASH_APPLY),
+    ("git-stash-pop", ICON_GIT_STASH_POP),
+    ("vscode", ICON_VSCODE),
+    ("vscode-insiders", ICON_VSCODE_INSIDERS),
+    ("code-oss", ICON_CODE_OSS),
+    ("run-coverage", ICON_RUN_COVERAGE),
+    ("run-all-coverage", ICON_RUN_ALL_COVERAGE),
+    ("coverage", ICON_COVERAGE),
+    ("git-fetch", ICON_GIT_FETCH),
+];
+
+/// Iterates over `(name, glyph)` of every icon, see [`ALL`]
+pub fn iter() -> impl Iterator<Item = (&'static str, char)> {
+    ALL.iter().copied()
+}
+
+/// A searchable grid of icons, returns the glyph clicked
+///
+/// The filter is kept in the memory of `ui`, icons sharing a glyph are shown once
+pub fn picker(ui: &mut egui::Ui) -> Option<char> {
+    let id = ui.id().with("codicon_picker");
+    let mut filter = ui
+        .data(|data| data.get_temp::<String>(id))
+        .unwrap_or_default();
+
+    ui.add(
+        egui::TextEdit::singleline(&mut filter)
+            .hint_text("Search icons")
+            .desired_width(f32::INFINITY),
+    );
+
+    let needle = filter.to_lowercase().replace([' ', '_'], "-");
+    let mut seen = std::collections::HashSet::new();
+    let icons: Vec<_> = iter()
+        .filter(|(name, _)| name.contains(&needle))
+        .filter(|(_, icon)| seen.insert(*icon))
+        .collect();
+
+    ui.data_mut(|data| data.insert_temp(id, filter));
+
+    let cell = 32.0;
+    let spacing = ui.spacing().item_spacing.x;
+    let columns = (((ui.available_width() + spacing) / (cell + spacing)) as usize).max(1);
+    let rows = icons.len().div_ceil(columns);
+    let mut picked = None;
+
+    egui::ScrollArea::vertical()
+        .id_salt(id.with("scroll"))
+        .max_height(cell * 8.0)
+        .auto_shrink([false, true])
+        .show_rows(ui, cell, rows, |ui, rows| {
+            for row in rows {
+                ui.horizontal(|ui| {
+                    for &(name, icon) in icons.iter().skip(row * columns).take(columns) {
+                        let button =
+                            egui::Button::new(egui::RichText::new(icon.to_string()).size(20.0))
+                                .min_size(egui::vec2(cell, cell));
+                        if ui.add(button).on_hover_text(name).clicked() {
+                            picked = Some(icon);
+                        }
+                    }
+                });
+            }
+        });
+
+    if icons.is_empty() {
+        ui.weak("No matches");
+    }
+
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_unique_and_mapped() {
+        let mut names = std::collections::HashSet::new();
+        assert!(iter().all(|(name, _)| names.insert(name)));
+        assert!(iter().any(|(name, icon)| name == "debug-start" && icon == ICON_DEBUG_START));
+        assert!(!iter().any(|(name, _)| name == "min" || name == "max"));
+    }
+}
@@ -0,0 +1,82 @@
+use std::time::SystemTime;
+
+pub const SECS_PER_DAY: u64 = 86400;
+
+/// Date and time in UTC, of the proleptic Gregorian calendar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UtcDateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl UtcDateTime {
+    /// Times before the epoch are taken as the epoch
+    pub fn new(time: SystemTime) -> Self {
+        let secs = time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let (year, month, day) = civil_from_days(secs / SECS_PER_DAY);
+        let secs = (secs % SECS_PER_DAY) as u32;
+
+        Self {
+            year,
+            month,
+            day,
+            hour: secs / 3600,
+            minute: secs % 3600 / 60,
+            second: secs % 60,
+        }
+    }
+
+    pub fn now() -> Self {
+        Self::new(SystemTime::now())
+    }
+}
+
+/// Year, month and day of days since 1970-01-01,
+/// see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+pub fn civil_from_days(days: u64) -> (i64, u32, u32) {
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn days_map_to_civil_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn time_of_day_is_split() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(19_782 * SECS_PER_DAY + 45_296);
+        let date_time = UtcDateTime::new(time);
+
+        assert_eq!(
+            (date_time.year, date_time.month, date_time.day),
+            (2024, 2, 29)
+        );
+        assert_eq!(
+            (date_time.hour, date_time.minute, date_time.second),
+            (12, 34, 56)
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::time::Duration;
+
+/// A value that only takes a new value once it stays the same for `delay` seconds
+///
+/// # Example
+/// ```ignore
+/// // each frame
+/// self.filter.set(self.search_box.clone(), now);
+/// if self.filter.changed(now) {
+///     self.rebuild_matches(self.filter.get(now));
+/// }
+/// ```
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Debounced<T> {
+    value: T,
+
+    /// The value waiting to be taken and since when
+    pending: Option<(T, f64)>,
+
+    delay: f64,
+    changed: bool,
+}
+
+impl<T: PartialEq + Clone> Debounced<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            pending: None,
+            delay: 0.0,
+            changed: false,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.set_delay(delay);
+        self
+    }
+
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.delay = delay.as_secs_f64();
+    }
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_secs_f64(self.delay)
+    }
+
+    /// Waits for `value` to be stable, setting the waited value again doesn't restart the delay
+    pub fn set(&mut self, value: T, current_time: f64) {
+        if value == self.value {
+            self.pending = None;
+        } else if self
+            .pending
+            .as_ref()
+            .is_none_or(|(pending, _)| *pending != value)
+        {
+            self.pending = Some((value, current_time));
+        }
+    }
+
+    /// Takes `value` without waiting, the waited value if any is dropped
+    pub fn set_now(&mut self, value: T) {
+        self.pending = None;
+        self.take(value);
+    }
+
+    /// Takes the waited value if it's been stable for the delay
+    pub fn update(&mut self, current_time: f64) {
+        if let Some((_, since)) = &self.pending
+            && since + self.delay <= current_time
+        {
+            let (value, _) = self.pending.take().unwrap();
+            self.take(value);
+        }
+    }
+
+    /// The taken value at `current_time`
+    pub fn get(&mut self, current_time: f64) -> &T {
+        self.update(current_time);
+        &self.value
+    }
+
+    /// The taken value, without taking the waited one
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Whether the taken value changed since last call
+    pub fn changed(&mut self, current_time: f64) -> bool {
+        self.update(current_time);
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Seconds left before the waited value is taken, `None` if nothing is waited
+    pub fn remaining(&self, current_time: f64) -> Option<f64> {
+        self.pending
+            .as_ref()
+            .map(|(_, since)| (since + self.delay - current_time).max(0.0))
+    }
+
+    fn take(&mut self, value: T) {
+        if value != self.value {
+            self.value = value;
+            self.changed = true;
+        }
+    }
+
+    /// For [`crate::delayed_toggle`], which waits for a value already taken
+    pub(crate) fn wait_for(&mut self, value: T, current_time: f64) {
+        self.pending = Some((value, current_time));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn debounced() -> Debounced<&'static str> {
+        Debounced::new("").with_delay(Duration::from_millis(300))
+    }
+
+    #[test]
+    fn rapid_changes_restart_the_delay() {
+        let mut key = debounced();
+        key.set("a", 0.0);
+        key.set("ab", 0.2);
+        key.set("abc", 0.4);
+
+        assert_eq!(*key.get(0.6), "");
+        assert!(!key.changed(0.6));
+        assert!(
+            key.remaining(0.6)
+                .is_some_and(|secs| (secs - 0.1).abs() < 1e-9)
+        );
+
+        assert_eq!(*key.get(0.7), "abc");
+        assert!(key.changed(0.7));
+        assert!(!key.changed(0.8));
+        assert_eq!(key.remaining(0.8), None);
+    }
+
+    #[test]
+    fn same_value_does_not_restart_or_change() {
+        let mut key = debounced();
+        key.set("a", 0.0);
+        key.set("a", 0.2);
+        assert_eq!(*key.get(0.3), "a");
+        assert!(key.changed(0.3));
+
+        // typed and erased before the delay
+        key.set("ab", 0.4);
+        key.set("a", 0.5);
+        assert_eq!(key.remaining(0.5), None);
+        assert!(!key.changed(1.0));
+    }
+
+    #[test]
+    fn taken_exactly_at_the_delay() {
+        let mut key = debounced();
+        key.set("a", 1.0);
+        assert_eq!(*key.get(1.2999), "");
+        assert_eq!(*key.get(1.3), "a");
+
+        key.set("b", 2.0);
+        key.set_now("c");
+        assert_eq!(*key.get(5.0), "c");
+        assert!(key.changed(5.0));
+    }
+}
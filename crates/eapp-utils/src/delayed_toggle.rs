@@ -1,29 +1,56 @@
-#[derive(Default, Clone, Copy)]
-pub struct DelayedToggle {
-    active: bool,
-    last_toggle_time: f64,
-}
+use crate::debounced::Debounced;
 
-impl DelayedToggle {
-    pub fn new(initial: bool) -> Self {
-        Self {
-            active: initial,
-            last_toggle_time: 0.0,
-        }
-    }
+/// A flag that turns off by itself once `delay` seconds passed since it was last activated
+pub type DelayedToggle = Debounced<bool>;
 
+impl Debounced<bool> {
     pub fn active(&mut self, current_time: f64) {
-        self.active = true;
-        self.last_toggle_time = current_time;
+        self.set_now(true);
+        self.wait_for(false, current_time);
     }
 
-    pub fn update(&mut self, current_time: f64, delayed: f64) {
-        if self.last_toggle_time + delayed <= current_time {
-            self.active = false;
-        }
+    /// Turns off without waiting for the delay
+    pub fn cancel(&mut self) {
+        self.set_now(false);
     }
 
     pub fn is_active(&self) -> bool {
-        self.active
+        *self.value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn flips_exactly_at_the_delay() {
+        let mut toggle = DelayedToggle::new(false).with_delay(Duration::from_millis(500));
+        toggle.active(1.0);
+
+        toggle.update(1.25);
+        assert!(toggle.is_active());
+        assert_eq!(toggle.remaining(1.25), Some(0.25));
+
+        toggle.update(1.4999);
+        assert!(toggle.is_active());
+
+        toggle.update(1.5);
+        assert!(!toggle.is_active());
+        assert_eq!(toggle.remaining(1.5), None);
+    }
+
+    #[test]
+    fn active_again_restarts_the_delay() {
+        let mut toggle = DelayedToggle::new(false).with_delay(Duration::from_secs(1));
+        toggle.active(0.0);
+        toggle.active(0.8);
+
+        toggle.update(1.5);
+        assert!(toggle.is_active());
+
+        toggle.cancel();
+        assert!(!toggle.is_active());
     }
 }
@@ -0,0 +1,269 @@
+//! About popup showing the app version, system info and the tail of the log file,
+//! for reporting problems
+//!
+//! # Example
+//! ```ignore
+//! // once, to show the GPU in the system info
+//! eapp_utils::diagnostics::remember_renderer(cc);
+//!
+//! let response = ui.button(ICON_INFO.to_string());
+//! egui::Popup::menu(&response)
+//!     .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+//!     .show(|ui| about_popup(ui, "mychat", env!("CARGO_PKG_VERSION")));
+//! ```
+
+use eframe::egui::{self, Id};
+use log::Level;
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Lines shown of the log file
+const TAIL_LINES: usize = 200;
+
+/// Bytes read from the end of the log file, enough for [`TAIL_LINES`] lines
+const TAIL_BYTES: u64 = 64 * 1024;
+
+const REFRESH_SECS: f64 = 1.0;
+
+/// Shown lines of the log file, kept in the memory of egui while the popup is open
+#[derive(Clone)]
+struct LogTail {
+    /// Lines at least this severe are shown
+    level: Level,
+    lines: Vec<(Option<Level>, String)>,
+    /// Why the log file can't be read
+    error: Option<String>,
+    last_check: f64,
+    /// Modified time and length of the file when it's read, it's read again once they change
+    read_stamp: Option<(SystemTime, u64)>,
+}
+
+impl Default for LogTail {
+    fn default() -> Self {
+        Self {
+            level: Level::Trace,
+            lines: Vec::new(),
+            error: None,
+            last_check: f64::NEG_INFINITY,
+            read_stamp: None,
+        }
+    }
+}
+
+impl LogTail {
+    /// Reads the file again if it's changed since it's read
+    fn refresh(&mut self, path: std::io::Result<PathBuf>) {
+        let result = path.and_then(|path| {
+            let metadata = std::fs::metadata(&path)?;
+            let stamp = (metadata.modified()?, metadata.len());
+            if self.read_stamp == Some(stamp) {
+                return Ok(None);
+            }
+            Ok(Some((stamp, read_tail(&path)?)))
+        });
+
+        match result {
+            Ok(None) => (),
+            Ok(Some((stamp, text))) => {
+                self.lines = tail_lines(&text, TAIL_LINES);
+                self.error = None;
+                self.read_stamp = Some(stamp);
+            }
+            Err(err) => {
+                self.error = Some(err.to_string());
+                self.read_stamp = None;
+            }
+        }
+    }
+}
+
+fn renderer_id() -> Id {
+    Id::new("eapp_utils_diagnostics_renderer")
+}
+
+/// Keeps the OpenGL renderer string to show in [`about_popup`], does nothing without glow
+pub fn remember_renderer(cc: &eframe::CreationContext<'_>) {
+    use eframe::glow::HasContext as _;
+
+    if let Some(gl) = &cc.gl {
+        // SAFETY: the context is current while the app is created
+        let renderer = unsafe { gl.get_parameter_string(eframe::glow::RENDERER) };
+        cc.egui_ctx
+            .data_mut(|data| data.insert_temp(renderer_id(), renderer));
+    }
+}
+
+/// OS, GPU and toolkit of the running app, one per line
+pub fn system_info(ctx: &egui::Context, app_name: &str, version: &str) -> String {
+    let renderer = ctx
+        .data_mut(|data| data.get_temp::<String>(renderer_id()))
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    format!(
+        "{app_name} {version}\n\
+         OS: {} ({})\n\
+         GPU: {renderer}\n\
+         eapp-utils: {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        env!("CARGO_PKG_VERSION"),
+    )
+}
+
+/// Contents of the about popup of `app_name`, whose log file is `{app_name}.log`
+/// as passed to [`crate::setup_loggers`], `version` is usually `env!("CARGO_PKG_VERSION")`
+pub fn about_popup(ui: &mut egui::Ui, app_name: &str, version: &str) {
+    ui.heading(app_name);
+    ui.weak(format!("Version {version}"));
+    ui.separator();
+
+    let log_path = crate::log_path(&format!("{app_name}.log"));
+
+    ui.horizontal(|ui| {
+        if ui
+            .add_enabled(log_path.is_ok(), egui::Button::new("Open log location"))
+            .clicked()
+            && let Ok(path) = &log_path
+            && let Err(err) = crate::open_in_explorer(&path.to_string_lossy())
+        {
+            log::error!("open log location fails: {err}");
+        }
+
+        if ui.button("Copy system info").clicked() {
+            ui.ctx().copy_text(system_info(ui.ctx(), app_name, version));
+        }
+    });
+
+    let id = ui.id().with("log_tail");
+    let mut tail: LogTail = ui.data_mut(|data| data.get_temp(id)).unwrap_or_default();
+
+    let now = ui.input(|i| i.time);
+    if now - tail.last_check >= REFRESH_SECS {
+        tail.last_check = now;
+        tail.refresh(log_path);
+    }
+    ui.ctx().request_repaint_after_secs(REFRESH_SECS as f32);
+
+    ui.horizontal(|ui| {
+        ui.label("Log");
+        egui::ComboBox::from_id_salt(id.with("level"))
+            .selected_text(tail.level.as_str())
+            .show_ui(ui, |ui| {
+                for level in Level::iter() {
+                    ui.selectable_value(&mut tail.level, level, level.as_str());
+                }
+            });
+    });
+
+    egui::Frame::NONE
+        .fill(ui.visuals().extreme_bg_color)
+        .corner_radius(4)
+        .inner_margin(4)
+        .show(ui, |ui| {
+            egui::ScrollArea::both()
+                .id_salt(id.with("scroll"))
+                .max_height(240.0)
+                .max_width(480.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    if let Some(error) = &tail.error {
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            format!("Unable to read the log: {error}"),
+                        );
+                        return;
+                    }
+
+                    for (level, line) in &tail.lines {
+                        if level.is_some_and(|level| level > tail.level) {
+                            continue;
+                        }
+
+                        let text = egui::RichText::new(line).monospace();
+                        let text = match level {
+                            Some(Level::Error) => text.color(ui.visuals().error_fg_color),
+                            Some(Level::Warn) => text.color(ui.visuals().warn_fg_color),
+                            _ => text,
+                        };
+                        ui.add(egui::Label::new(text).wrap_mode(egui::TextWrapMode::Extend));
+                    }
+                });
+        });
+
+    ui.data_mut(|data| data.insert_temp(id, tail));
+}
+
+/// End of the log file from the start of a line, the file may be written or rotated meanwhile
+fn read_tail(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let start = file.metadata()?.len().saturating_sub(TAIL_BYTES);
+    file.seek(SeekFrom::Start(start))?;
+
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+
+    // the first line is cut if it's read from the middle
+    if start > 0 {
+        let line_start = bytes
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(0, |idx| idx + 1);
+        bytes.drain(..line_start);
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The last `count` complete lines of `text` and their levels, a line without a level
+/// continues the one before it
+fn tail_lines(text: &str, count: usize) -> Vec<(Option<Level>, String)> {
+    let mut lines: Vec<_> = text.lines().collect();
+    if lines.len() > count {
+        lines.drain(..lines.len() - count);
+    }
+
+    let mut level = None;
+    lines
+        .into_iter()
+        .map(|line| {
+            level = line_level(line).or(level);
+            (level, line.to_owned())
+        })
+        .collect()
+}
+
+/// Level of a line written by simplelog, e.g. `12:00:00 [WARN] ...`
+fn line_level(line: &str) -> Option<Level> {
+    let start = line.find('[')?;
+    let end = start + line[start..].find(']')?;
+    line[start + 1..end].trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_of_lines() {
+        assert_eq!(line_level("12:00:00 [WARN] low"), Some(Level::Warn));
+        assert_eq!(line_level("12:00:00 [ERROR] failed"), Some(Level::Error));
+        assert_eq!(line_level("  at main.rs:3"), None);
+        assert_eq!(line_level("value [not a level]"), None);
+    }
+
+    #[test]
+    fn tail_keeps_the_last_lines() {
+        let text = "0 [INFO] a\n1 [ERROR] b\n  detail\n2 [DEBUG] c\n";
+        let lines = tail_lines(text, 3);
+        assert_eq!(
+            lines,
+            vec![
+                (Some(Level::Error), "1 [ERROR] b".to_owned()),
+                (Some(Level::Error), "  detail".to_owned()),
+                (Some(Level::Debug), "2 [DEBUG] c".to_owned()),
+            ]
+        );
+    }
+}
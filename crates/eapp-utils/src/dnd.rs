@@ -0,0 +1,271 @@
+//! Drag-and-drop of files, with feedback while they are hovering the window
+
+use eframe::egui::{self, Align2, Color32, FontId, Id, LayerId, Order};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+pub const IMAGE_EXTS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "webp", "bmp", "tif", "tiff", "ico", "tga", "qoi", "avif", "pnm",
+];
+
+pub const MEDIA_EXTS: &[&str] = &[
+    "mp4", "mkv", "avi", "flv", "wmv", "webm", "vob", "mts", "ts", "m2ts", "mov", "rm", "rmvb",
+    "asf", "m4v", "mpg", "mp2", "mpeg", "mpe", "mpv", "m2v", "3gp", "f4v", "mp3", "wav", "ogg",
+    "flac", "aac", "ape", "ac3", "m4a", "mka", "opus",
+];
+
+pub const SUBTITLE_EXTS: &[&str] = &["srt", "ass", "ssa", "vtt", "sub", "sup"];
+
+pub const TEXT_EXTS: &[&str] = &[
+    "txt", "md", "json", "toml", "yaml", "yml", "ini", "log", "csv", "xml", "rs", "py", "js",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DropCategory {
+    Image,
+    /// Video or audio
+    Media,
+    Subtitle,
+    Text,
+    Directory,
+}
+
+impl DropCategory {
+    fn default_exts(self) -> &'static [&'static str] {
+        match self {
+            Self::Image => IMAGE_EXTS,
+            Self::Media => MEDIA_EXTS,
+            Self::Subtitle => SUBTITLE_EXTS,
+            Self::Text => TEXT_EXTS,
+            Self::Directory => &[],
+        }
+    }
+
+    fn noun(self, count: usize) -> &'static str {
+        match (self, count) {
+            (Self::Image, 1) => "image",
+            (Self::Image, _) => "images",
+            (Self::Media, 1) => "media file",
+            (Self::Media, _) => "media files",
+            (Self::Subtitle, 1) => "subtitle",
+            (Self::Subtitle, _) => "subtitles",
+            (Self::Text, 1) => "text file",
+            (Self::Text, _) => "text files",
+            (Self::Directory, 1) => "directory",
+            (Self::Directory, _) => "directories",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DroppedItem {
+    /// A file or directory of an accepted category
+    Path(DropCategory, PathBuf),
+
+    /// Given without a path, as some platforms do, the name may be empty
+    Bytes {
+        name: String,
+        bytes: Arc<[u8]>,
+    },
+
+    Unsupported(PathBuf),
+}
+
+/// Sorts the dropped files by the accepted categories, and shows what a drop would do
+/// over the whole window while files are hovering it
+///
+/// # Example
+/// ```ignore
+/// let handler = DropHandler::new(&[DropCategory::Image, DropCategory::Directory]);
+/// for item in handler.show(ctx) {
+///     match item {
+///         DroppedItem::Path(_, path) => open(path),
+///         _ => (),
+///     }
+/// }
+/// ```
+pub struct DropHandler {
+    accepted: Vec<(DropCategory, Vec<String>)>,
+}
+
+impl DropHandler {
+    pub fn new(accepted: &[DropCategory]) -> Self {
+        Self {
+            accepted: accepted
+                .iter()
+                .map(|category| {
+                    let exts = category.default_exts().iter().map(|ext| ext.to_string());
+                    (*category, exts.collect())
+                })
+                .collect(),
+        }
+    }
+
+    /// Replaces the extensions of an accepted `category`, which are lowercase
+    pub fn extensions<S: ToString>(mut self, category: DropCategory, exts: &[S]) -> Self {
+        if let Some((_, accepted)) = self.accepted.iter_mut().find(|(c, _)| *c == category) {
+            *accepted = exts.iter().map(ToString::to_string).collect();
+        }
+        self
+    }
+
+    /// Category of `path`, `None` if it's not accepted
+    pub fn categorize(&self, path: &Path) -> Option<DropCategory> {
+        if path.is_dir() {
+            return self
+                .accepted
+                .iter()
+                .any(|(category, _)| *category == DropCategory::Directory)
+                .then_some(DropCategory::Directory);
+        }
+
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        self.accepted
+            .iter()
+            .find(|(category, exts)| *category != DropCategory::Directory && exts.contains(&ext))
+            .map(|(category, _)| *category)
+    }
+
+    /// Paints the overlay while files are hovering, returns the files dropped in this frame
+    pub fn show(&self, ctx: &egui::Context) -> Vec<DroppedItem> {
+        let (hovered, dropped) =
+            ctx.input(|i| (i.raw.hovered_files.clone(), i.raw.dropped_files.clone()));
+
+        if !hovered.is_empty() {
+            let categories: Vec<_> = hovered
+                .iter()
+                .map(|file| match &file.path {
+                    Some(path) => Hovered::Known(self.categorize(path)),
+                    None => Hovered::Unknown,
+                })
+                .collect();
+            let (text, supported) = hover_text(&categories);
+            paint_overlay(ctx, &text, supported);
+        }
+
+        dropped
+            .into_iter()
+            .filter_map(|file| match (file.path, file.bytes) {
+                (Some(path), _) => Some(match self.categorize(&path) {
+                    Some(category) => DroppedItem::Path(category, path),
+                    None => DroppedItem::Unsupported(path),
+                }),
+                (None, Some(bytes)) => Some(DroppedItem::Bytes {
+                    name: file.name,
+                    bytes,
+                }),
+                (None, None) => None,
+            })
+            .collect()
+    }
+}
+
+/// A hovering file, whose path isn't known on some platforms
+enum Hovered {
+    Known(Option<DropCategory>),
+    Unknown,
+}
+
+/// e.g. `Drop to open 3 images, 1 directory`, and whether anything is supported
+fn hover_text(hovered: &[Hovered]) -> (String, bool) {
+    let mut counts: Vec<(DropCategory, usize)> = Vec::new();
+    let mut unknown = 0;
+    for item in hovered {
+        match item {
+            Hovered::Known(Some(category)) => {
+                match counts.iter_mut().find(|(c, _)| c == category) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((*category, 1)),
+                }
+            }
+            Hovered::Known(None) => (),
+            Hovered::Unknown => unknown += 1,
+        }
+    }
+
+    let mut parts: Vec<_> = counts
+        .iter()
+        .map(|(category, count)| format!("{count} {}", category.noun(*count)))
+        .collect();
+    if unknown > 0 {
+        parts.push(format!(
+            "{unknown} {}",
+            if unknown == 1 { "file" } else { "files" }
+        ));
+    }
+
+    if parts.is_empty() {
+        return ("Unsupported file type".to_owned(), false);
+    }
+
+    (format!("Drop to open {}", parts.join(", ")), true)
+}
+
+fn paint_overlay(ctx: &egui::Context, text: &str, supported: bool) {
+    let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("dnd_overlay")));
+    let rect = ctx.screen_rect();
+    painter.rect_filled(rect, 8, Color32::from_black_alpha(160));
+
+    let color = if supported {
+        Color32::WHITE
+    } else {
+        ctx.style().visuals.error_fg_color
+    };
+    painter.text(
+        rect.center(),
+        Align2::CENTER_CENTER,
+        text,
+        FontId::proportional(20.0),
+        color,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categorize_by_extension() {
+        let handler = DropHandler::new(&[DropCategory::Image, DropCategory::Subtitle])
+            .extensions(DropCategory::Subtitle, &["srt"]);
+
+        assert_eq!(
+            handler.categorize(Path::new("a/b.PNG")),
+            Some(DropCategory::Image)
+        );
+        assert_eq!(
+            handler.categorize(Path::new("b.srt")),
+            Some(DropCategory::Subtitle)
+        );
+        assert_eq!(handler.categorize(Path::new("b.ass")), None);
+        assert_eq!(handler.categorize(Path::new("b.mp4")), None);
+        assert_eq!(handler.categorize(Path::new("no_ext")), None);
+
+        let dir = std::env::temp_dir();
+        assert_eq!(handler.categorize(&dir), None);
+        let handler = DropHandler::new(&[DropCategory::Directory]);
+        assert_eq!(handler.categorize(&dir), Some(DropCategory::Directory));
+    }
+
+    #[test]
+    fn hover_text_counts_categories() {
+        let (text, supported) = hover_text(&[
+            Hovered::Known(Some(DropCategory::Image)),
+            Hovered::Known(Some(DropCategory::Directory)),
+            Hovered::Known(Some(DropCategory::Image)),
+            Hovered::Known(None),
+        ]);
+        assert!(supported);
+        assert_eq!(text, "Drop to open 2 images, 1 directory");
+
+        let (text, supported) = hover_text(&[Hovered::Unknown]);
+        assert!(supported);
+        assert_eq!(text, "Drop to open 1 file");
+
+        let (text, supported) = hover_text(&[Hovered::Known(None), Hovered::Known(None)]);
+        assert!(!supported);
+        assert_eq!(text, "Unsupported file type");
+    }
+}
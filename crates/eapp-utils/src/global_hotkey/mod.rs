@@ -1,10 +1,12 @@
 pub mod ui;
 
-use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, hotkey::HotKey};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::HotKey};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
+    fmt::Debug,
     ops::{Deref, DerefMut},
+    time::{Duration, Instant},
 };
 
 pub use global_hotkey::hotkey::{Code, Modifiers};
@@ -27,11 +29,80 @@ impl<Action: Default> DerefMut for KeyMap<Action> {
     }
 }
 
+/// Actions bound to sequences of hotkeys pressed one after another, e.g. `Ctrl+K` then `Ctrl+R`
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+pub struct ChordMap<Action: Default>(pub Vec<(Vec<HotKey>, Action)>);
+
+impl<Action: Default> ChordMap<Action> {
+    pub const KEY: &str = "global_hotkey_chords";
+}
+
+impl<Action: Default> Deref for ChordMap<Action> {
+    type Target = Vec<(Vec<HotKey>, Action)>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<Action: Default> DerefMut for ChordMap<Action> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Maximum number of hotkeys in a chord
+pub const MAX_CHORD_LEN: usize = 3;
+
+/// A partially pressed chord is forgotten after this long without the next hotkey
+pub const CHORD_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn same_keys(a: &[HotKey], b: &[HotKey]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.id() == b.id())
+}
+
+#[derive(Debug)]
+pub enum HotkeyError<Action> {
+    /// The hotkey is already bound to this action
+    Conflict(Action),
+
+    /// The OS refuses the hotkey, e.g. it is taken by another application
+    Os(global_hotkey::Error),
+}
+
+impl<Action: Debug> std::fmt::Display for HotkeyError<Action> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyError::Conflict(action) => write!(f, "Hotkey is already bound to {action:?}"),
+            HotkeyError::Os(err) => err.fmt(f),
+        }
+    }
+}
+
+impl<Action: Debug> std::error::Error for HotkeyError<Action> {}
+
+impl<Action> From<global_hotkey::Error> for HotkeyError<Action> {
+    fn from(err: global_hotkey::Error) -> Self {
+        HotkeyError::Os(err)
+    }
+}
+
 #[derive(Default)]
 pub struct GlobalHotkeyHandler<Action: Default> {
     key_map: KeyMap<Action>,
+    chord_map: ChordMap<Action>,
     manager: Option<GlobalHotKeyManager>,
     action_to_edit: Option<Action>,
+    captured: Vec<HotKey>,
+    conflict_msg: Option<String>,
+
+    /// Hotkeys of the chord pressed so far, and when the last one was pressed
+    pending: Vec<HotKey>,
+    pending_since: Option<Instant>,
+
+    /// Next hotkeys of the pending chord, registered only until it completes
+    temp_registered: Vec<HotKey>,
 }
 
 impl<Action> GlobalHotkeyHandler<Action>
@@ -47,10 +118,60 @@ where
         &self.key_map
     }
 
+    pub fn get_chord_map(&self) -> &ChordMap<Action> {
+        &self.chord_map
+    }
+
     pub fn is_ok(&self) -> bool {
         self.manager.is_some()
     }
 
+    /// Whether `hotkey` stays registered to the OS, i.e. it is bound alone or starts a chord
+    fn is_bound(&self, hotkey: &HotKey) -> bool {
+        self.key_map.contains_key(&hotkey.id())
+            || self
+                .chord_map
+                .iter()
+                .any(|(chord, _)| chord[0].id() == hotkey.id())
+    }
+
+    /// All bindings, hotkeys bound alone are chords of one hotkey
+    fn bindings(&self) -> impl Iterator<Item = (&[HotKey], &Action)> {
+        self.key_map
+            .values()
+            .map(|(hotkey, action)| (std::slice::from_ref(hotkey), action))
+            .chain(
+                self.chord_map
+                    .iter()
+                    .map(|(chord, action)| (chord.as_slice(), action)),
+            )
+    }
+
+    /// Registers `action` to the hotkeys pressed in order, a single hotkey is
+    /// the same as [`Self::register_hotkey`]
+    pub fn register_chord(
+        &mut self,
+        action: Action,
+        chord: &[HotKey],
+    ) -> global_hotkey::Result<()> {
+        assert!(self.is_ok(), "call `create_manager` first");
+        assert!(
+            (1..=MAX_CHORD_LEN).contains(&chord.len()),
+            "chord should have 1 to {MAX_CHORD_LEN} hotkeys"
+        );
+
+        if let [hotkey] = chord {
+            return self.register_hotkey(action, Some(hotkey.mods), hotkey.key);
+        }
+
+        self.reset_pending();
+        if !self.is_bound(&chord[0]) {
+            self.manager.as_ref().unwrap().register(chord[0])?;
+        }
+        self.chord_map.push((chord.to_vec(), action));
+        Ok(())
+    }
+
     pub fn register_hotkey(
         &mut self,
         action: Action,
@@ -58,14 +179,18 @@ where
         keycode: Code,
     ) -> global_hotkey::Result<()> {
         assert!(self.is_ok(), "call `create_manager` first");
+        self.reset_pending();
         let hotkey = HotKey::new(modifiers, keycode);
         self.manager.as_ref().unwrap().register(hotkey)?;
         self.key_map.insert(hotkey.id(), (hotkey, action));
         Ok(())
     }
 
+    /// Unregisters all hotkeys and chords of `action`
     pub fn unregister_hotkey(&mut self, action: &Action) {
         assert!(self.is_ok(), "call `create_manager` first");
+        self.reset_pending();
+
         let keys: Vec<_> = self
             .key_map
             .iter()
@@ -76,25 +201,172 @@ where
             let _ = self.manager.as_ref().unwrap().unregister(key);
             self.key_map.remove(&key.id());
         }
+
+        let first_keys: Vec<_> = self
+            .chord_map
+            .iter()
+            .filter(|(_, v)| v == action)
+            .map(|(chord, _)| chord[0])
+            .collect();
+        self.chord_map.retain(|(_, v)| v != action);
+
+        for key in first_keys {
+            if !self.is_bound(&key) {
+                let _ = self.manager.as_ref().unwrap().unregister(key);
+            }
+        }
+    }
+
+    /// The action already bound to the hotkey, if any
+    pub fn would_conflict(&self, modifiers: Option<Modifiers>, keycode: Code) -> Option<Action> {
+        self.would_conflict_chord(&[HotKey::new(modifiers, keycode)])
+    }
+
+    /// The action bound to the chord, or to one which starts with it or which
+    /// it starts with, if any
+    pub fn would_conflict_chord(&self, chord: &[HotKey]) -> Option<Action> {
+        self.conflicting_binding(chord, None)
     }
 
+    fn conflicting_binding(&self, chord: &[HotKey], except: Option<&Action>) -> Option<Action> {
+        self.bindings()
+            .filter(|(_, action)| except.is_none_or(|except| except != *action))
+            .find(|(keys, _)| keys.iter().zip(chord).all(|(a, b)| a.id() == b.id()))
+            .map(|(_, action)| action.clone())
+    }
+
+    /// Rebinds `action` to the hotkey, refuses if it is bound to a different action,
+    /// the old hotkey of `action` is kept if the OS refuses the new one
     pub fn update_hotkey(
         &mut self,
         action: Action,
         modifiers: Option<Modifiers>,
         keycode: Code,
-    ) -> global_hotkey::Result<()> {
+    ) -> Result<(), HotkeyError<Action>> {
+        self.update_chord(action, &[HotKey::new(modifiers, keycode)])
+    }
+
+    /// Same as [`Self::update_hotkey`] but binds a chord, which also conflicts with
+    /// chords sharing its beginning
+    pub fn update_chord(
+        &mut self,
+        action: Action,
+        chord: &[HotKey],
+    ) -> Result<(), HotkeyError<Action>> {
+        let old_chords: Vec<Vec<HotKey>> = self
+            .bindings()
+            .filter(|(_, v)| **v == action)
+            .map(|(keys, _)| keys.to_vec())
+            .collect();
+
+        if let [old_chord] = old_chords.as_slice()
+            && same_keys(old_chord, chord)
+        {
+            return Ok(());
+        }
+
+        if let Some(other) = self.conflicting_binding(chord, Some(&action)) {
+            return Err(HotkeyError::Conflict(other));
+        }
+
         self.unregister_hotkey(&action);
-        self.register_hotkey(action, modifiers, keycode)
+        if let Err(err) = self.register_chord(action.clone(), chord) {
+            for old_chord in old_chords {
+                let _ = self.register_chord(action.clone(), &old_chord);
+            }
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    /// Forgets the partially pressed chord and unregisters its next hotkeys
+    fn reset_pending(&mut self) {
+        self.pending.clear();
+        self.pending_since = None;
+
+        for key in std::mem::take(&mut self.temp_registered) {
+            if !self.is_bound(&key) {
+                let _ = self.manager.as_ref().unwrap().unregister(key);
+            }
+        }
+    }
+
+    /// Feeds the pressed hotkey `id` to the chords, returns whether a chord uses it
+    fn advance_chord(&mut self, id: u32, result: &mut Vec<Action>) -> bool {
+        let step = self.pending.len();
+        let candidates: Vec<_> = self
+            .chord_map
+            .iter()
+            .filter(|(chord, _)| {
+                chord.len() > step
+                    && same_keys(&chord[..step], &self.pending)
+                    && chord[step].id() == id
+            })
+            .cloned()
+            .collect();
+
+        if candidates.is_empty() {
+            if step == 0 {
+                return false;
+            }
+
+            // A wrong next hotkey cancels the chord, but it may still start another
+            self.reset_pending();
+            return self.advance_chord(id, result);
+        }
+
+        if let Some((_, action)) = candidates.iter().find(|(chord, _)| chord.len() == step + 1) {
+            result.push(action.clone());
+            self.reset_pending();
+            return true;
+        }
+
+        self.pending.push(candidates[0].0[step]);
+        self.pending_since = Some(Instant::now());
+
+        for (chord, _) in &candidates {
+            let next = chord[step + 1];
+            if !self.is_bound(&next)
+                && !self.temp_registered.iter().any(|key| key.id() == next.id())
+                && self.manager.as_ref().unwrap().register(next).is_ok()
+            {
+                self.temp_registered.push(next);
+            }
+        }
+
+        true
     }
 
     pub fn poll_events(&mut self) -> Vec<Action> {
         assert!(self.is_ok(), "call `create_manager` first");
+        if self
+            .pending_since
+            .is_some_and(|since| since.elapsed() > CHORD_TIMEOUT)
+        {
+            self.reset_pending();
+        }
+
         let mut result = Vec::new();
         for event in GlobalHotKeyEvent::receiver().try_iter() {
-            if let Some((_, action)) = self.key_map.get(&event.id())
-                && self.action_to_edit.is_none()
+            if self.action_to_edit.is_some() {
+                continue;
+            }
+
+            let in_chord = self
+                .chord_map
+                .iter()
+                .any(|(chord, _)| chord.iter().any(|key| key.id() == event.id()));
+            if in_chord && event.state() == HotKeyState::Released {
+                continue;
+            }
+
+            if event.state() == HotKeyState::Pressed && self.advance_chord(event.id(), &mut result)
             {
+                continue;
+            }
+
+            if let Some((_, action)) = self.key_map.get(&event.id()) {
                 result.push(action.clone());
             }
         }
@@ -1,4 +1,4 @@
-use crate::global_hotkey::GlobalHotkeyHandler;
+use crate::global_hotkey::{GlobalHotkeyHandler, HotkeyError, MAX_CHORD_LEN};
 use eframe::egui;
 use global_hotkey::{
     Result,
@@ -13,79 +13,175 @@ where
     pub fn ui(&mut self, ui: &mut egui::Ui) -> Result<()> {
         assert!(self.is_ok(), "call `create_manager` first");
 
+        let bindings: Vec<_> = self
+            .bindings()
+            .map(|(chord, action)| (chord_label(chord), action.clone()))
+            .collect();
+
         ui.add_enabled_ui(self.action_to_edit.is_none(), |ui| {
             ui.columns(2, |ui| {
-                for (_, (hotkey, action)) in self.key_map.iter() {
+                for (label, action) in bindings {
                     ui[0].vertical_centered(|ui| ui.label(format!("{action:?}")));
                     ui[1].vertical_centered(|ui| {
-                        if self.action_to_edit.as_ref().is_some_and(|a| a == action) {
-                            ui.label("Press new hotkey... (BACKSPACE to cancel)");
-                        } else {
-                            let label = hotkey_label(hotkey);
-                            if ui.button(label).clicked() {
-                                self.action_to_edit = Some(action.clone());
-                            }
+                        if self.action_to_edit.as_ref().is_some_and(|a| *a == action) {
+                            ui.label(format!("{}...", self.captured_label(ui)));
+                        } else if ui.button(label).clicked() {
+                            self.action_to_edit = Some(action);
+                            self.captured.clear();
+                            self.conflict_msg = None;
                         }
                     });
                 }
             });
         });
 
+        if let Some(action) = &self.action_to_edit {
+            ui.vertical_centered(|ui| {
+                ui.weak("ENTER to confirm, BACKSPACE to undo, ESC to cancel");
+                if !self.captured.is_empty()
+                    && let Some(other) = self.conflicting_binding(&self.captured, Some(action))
+                {
+                    let label = chord_label(&self.captured);
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!("{label} conflicts with the hotkey of {other:?}"),
+                    );
+                }
+            });
+        } else if let Some(msg) = &self.conflict_msg {
+            ui.vertical_centered(|ui| ui.colored_label(ui.visuals().error_fg_color, msg));
+        }
+
         if self.action_to_edit.is_none() {
             return Ok(());
         }
 
         let action = self.action_to_edit.clone().unwrap();
 
-        ui.input(|input| {
-            for event in &input.events {
-                let egui::Event::Key {
-                    key,
-                    modifiers,
-                    pressed,
-                    ..
-                } = event
-                else {
-                    continue;
-                };
-
-                if !pressed {
-                    continue;
-                }
+        let keys: Vec<_> = ui.input(|input| {
+            input
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        modifiers,
+                        pressed: true,
+                        ..
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+                .collect()
+        });
 
-                if *key == egui::Key::Backspace {
+        for (key, modifiers) in keys {
+            match capture_key(&mut self.captured, key, modifiers) {
+                Capture::Continue => (),
+                Capture::Cancel => {
                     self.action_to_edit = None;
+                    self.captured.clear();
                     return Ok(());
                 }
-
-                if let Some(code) = to_code(*key) {
-                    let mods = to_modifiers(*modifiers);
-
+                Capture::Confirm => {
+                    // Stays capturing so that the conflicting hotkeys can be undone
+                    if self
+                        .conflicting_binding(&self.captured, Some(&action))
+                        .is_some()
+                    {
+                        continue;
+                    }
                     self.action_to_edit = None;
-                    return self.update_hotkey(action, Some(mods), code);
+                    return self.commit_captured(action);
                 }
             }
-            Ok(())
-        })
+        }
+
+        Ok(())
+    }
+
+    /// The hotkeys captured so far, followed by the modifiers being held
+    fn captured_label(&self, ui: &egui::Ui) -> String {
+        let held = modifiers_label(to_modifiers(ui.input(|i| i.modifiers)));
+        match (self.captured.is_empty(), held.is_empty()) {
+            (true, true) => "Press new hotkeys".to_owned(),
+            (true, false) => held,
+            (false, true) => chord_label(&self.captured),
+            (false, false) => format!("{}, {held}", chord_label(&self.captured)),
+        }
+    }
+
+    fn commit_captured(&mut self, action: Action) -> Result<()> {
+        let chord = std::mem::take(&mut self.captured);
+        match self.update_chord(action, &chord) {
+            Ok(_) => Ok(()),
+            Err(HotkeyError::Conflict(other)) => {
+                let label = chord_label(&chord);
+                self.conflict_msg = Some(format!("{label} conflicts with the hotkey of {other:?}"));
+                Ok(())
+            }
+            Err(HotkeyError::Os(err)) => Err(err),
+        }
     }
 }
 
+/// What a key pressed while capturing does
+#[derive(Debug, PartialEq, Eq)]
+enum Capture {
+    Continue,
+    Confirm,
+    Cancel,
+}
+
+/// Feeds a pressed key to the hotkeys captured so far, `Enter`, `Backspace` and
+/// `Escape` are never captured
+fn capture_key(captured: &mut Vec<HotKey>, key: egui::Key, modifiers: egui::Modifiers) -> Capture {
+    match key {
+        egui::Key::Escape => Capture::Cancel,
+        egui::Key::Backspace => {
+            if captured.pop().is_some() {
+                Capture::Continue
+            } else {
+                Capture::Cancel
+            }
+        }
+        egui::Key::Enter if !captured.is_empty() => Capture::Confirm,
+        _ => {
+            if let Some(code) = to_code(key)
+                && captured.len() < MAX_CHORD_LEN
+            {
+                captured.push(HotKey::new(Some(to_modifiers(modifiers)), code));
+            }
+            Capture::Continue
+        }
+    }
+}
+
+fn chord_label(chord: &[HotKey]) -> String {
+    chord
+        .iter()
+        .map(hotkey_label)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 fn hotkey_label(hotkey: &HotKey) -> String {
+    format!("{}{:?}", modifiers_label(hotkey.mods), hotkey.key)
+}
+
+fn modifiers_label(mods: Modifiers) -> String {
     let mut label = String::new();
-    if hotkey.mods.ctrl() {
+    if mods.ctrl() {
         label.push_str("Ctrl+");
     }
-    if hotkey.mods.alt() {
+    if mods.alt() {
         label.push_str("Alt+");
     }
-    if hotkey.mods.shift() {
+    if mods.shift() {
         label.push_str("Shift+");
     }
-    if hotkey.mods.meta() {
+    if mods.meta() {
         label.push_str("Win+");
     }
-
-    label.push_str(&format!("{:?}", hotkey.key));
     label
 }
 
@@ -157,7 +253,54 @@ fn to_code(key: egui::Key) -> Option<Code> {
         F10 => Some(Code::F10),
         F11 => Some(Code::F11),
         F12 => Some(Code::F12),
-        Escape => Some(Code::Escape),
+        ArrowUp => Some(Code::ArrowUp),
+        ArrowDown => Some(Code::ArrowDown),
+        ArrowLeft => Some(Code::ArrowLeft),
+        ArrowRight => Some(Code::ArrowRight),
+        Space => Some(Code::Space),
+        Tab => Some(Code::Tab),
+        Insert => Some(Code::Insert),
+        Delete => Some(Code::Delete),
+        Home => Some(Code::Home),
+        End => Some(Code::End),
+        PageUp => Some(Code::PageUp),
+        PageDown => Some(Code::PageDown),
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_undo_and_cancel() {
+        let ctrl = egui::Modifiers::CTRL;
+        let mut captured = Vec::new();
+
+        assert_eq!(
+            capture_key(&mut captured, egui::Key::Enter, ctrl),
+            Capture::Continue
+        );
+        for key in [egui::Key::K, egui::Key::R, egui::Key::A, egui::Key::B] {
+            assert_eq!(capture_key(&mut captured, key, ctrl), Capture::Continue);
+        }
+        assert_eq!(captured.len(), MAX_CHORD_LEN);
+        assert_eq!(chord_label(&captured), "Ctrl+KeyK, Ctrl+KeyR, Ctrl+KeyA");
+
+        assert_eq!(
+            capture_key(&mut captured, egui::Key::Backspace, ctrl),
+            Capture::Continue
+        );
+        assert_eq!(
+            capture_key(&mut captured, egui::Key::Enter, ctrl),
+            Capture::Confirm
+        );
+        assert_eq!(chord_label(&captured), "Ctrl+KeyK, Ctrl+KeyR");
+
+        assert_eq!(
+            capture_key(&mut captured, egui::Key::Escape, ctrl),
+            Capture::Cancel
+        );
+    }
+}
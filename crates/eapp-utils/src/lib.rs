@@ -3,15 +3,24 @@ use eframe::egui;
 pub mod animation;
 pub mod borderless;
 pub mod codicons;
+pub mod date;
+pub mod debounced;
 pub mod delayed_toggle;
+pub mod diagnostics;
+pub mod dnd;
 pub mod global_hotkey;
+pub mod log_file;
 pub mod natordset;
+pub mod persistent_window;
 pub mod platform;
+pub mod shortcuts;
 pub mod task;
 pub mod ui_font_selector;
 pub mod waker;
 pub mod widgets;
 
+pub use log_file::LogOptions;
+
 #[macro_export]
 macro_rules! capture_error {
     ($i:ident => $handler:expr, $block_to_capture:expr) => {
@@ -25,6 +34,26 @@ macro_rules! capture_error {
     };
 }
 
+/// Same as [`capture_error`], but evaluates to `$fallback` after running `$handler` on error
+///
+/// # Example
+/// ```ignore
+/// let manager = eapp_utils::capture_error_or!(
+///     err => log::error!("Error when load `ScriptManager`: {err}"),
+///     ScriptManager::default(),
+///     ScriptManager::load()?
+/// );
+/// ```
+#[macro_export]
+macro_rules! capture_error_or {
+    ($i:ident => $handler:expr, $fallback:expr, $block_to_capture:expr) => {
+        $crate::capture_error!($i => {
+            $handler;
+            $fallback
+        }, $block_to_capture)
+    };
+}
+
 pub fn get_default_fonts() -> egui::FontDefinitions {
     let mut fonts = egui::FontDefinitions::default();
 
@@ -71,57 +100,59 @@ pub fn get_button_height(ui: &egui::Ui) -> f32 {
     ui.style().spacing.button_padding.y + get_text_size(ui, &egui::TextStyle::Button).unwrap()
 }
 
-pub fn setup_loggers(log_filename: &str) -> Result<(), Box<dyn std::error::Error>> {
-    use simplelog::{Config, LevelFilter, WriteLogger};
-    use std::fs::File;
+/// Where [`setup_loggers`] writes `log_filename`, next to the executable
+pub fn log_path(log_filename: &str) -> std::io::Result<std::path::PathBuf> {
+    Ok(std::env::current_exe()?
+        .parent()
+        .unwrap()
+        .join(log_filename))
+}
+
+/// Logs to `log_filename` next to the executable, see [`LogOptions`],
+/// debug builds also log to the console
+pub fn setup_loggers(
+    log_filename: &str,
+    options: LogOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use simplelog::{Config, WriteLogger};
+
+    let file =
+        log_file::RotatingFile::open(log_path(log_filename)?, options.max_size, options.keep)?;
 
     #[cfg(debug_assertions)]
     {
-        use simplelog::{CombinedLogger, SimpleLogger};
+        use simplelog::{CombinedLogger, LevelFilter, SimpleLogger};
         CombinedLogger::init(vec![
-            SimpleLogger::new(LevelFilter::Info, Config::default()),
-            WriteLogger::new(
-                LevelFilter::Warn,
-                Config::default(),
-                File::create(
-                    std::env::current_exe()?
-                        .parent()
-                        .unwrap()
-                        .join(log_filename),
-                )?,
-            ),
+            SimpleLogger::new(options.level.max(LevelFilter::Info), Config::default()),
+            WriteLogger::new(options.level, Config::default(), file),
         ])?;
     }
 
     #[cfg(not(debug_assertions))]
-    WriteLogger::init(
-        LevelFilter::Warn,
-        Config::default(),
-        File::create(
-            std::env::current_exe()?
-                .parent()
-                .unwrap()
-                .join(log_filename),
-        )?,
-    )?;
+    WriteLogger::init(options.level, Config::default(), file)?;
 
     Ok(())
 }
 
-pub fn open_in_explorer(path: &str) {
-    // https://github.com/tauri-apps/plugins-workspace/issues/999
-    #[allow(clippy::zombie_processes)]
+/// Shows `path` selected in the file manager, on Linux its directory is opened
+/// if the file manager can't select it
+// https://github.com/tauri-apps/plugins-workspace/issues/999
+#[allow(clippy::zombie_processes)]
+pub fn open_in_explorer(path: &str) -> std::io::Result<()> {
     #[cfg(target_os = "windows")]
     std::process::Command::new("explorer")
         .args(["/select,", path])
-        .spawn()
-        .unwrap();
+        .spawn()?;
 
     #[cfg(target_os = "macos")]
     std::process::Command::new("open")
         .args(["-R", path])
-        .spawn()
-        .unwrap();
+        .spawn()?;
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    platform::open_in_file_manager(path)?;
+
+    Ok(())
 }
 
 #[inline]
@@ -143,3 +174,81 @@ pub fn window_resize_by_fit_scale(ui: &mut egui::Ui, image_size: egui::Vec2) {
     window_resize(ui, image_size * fit_scale);
     ui.ctx().request_repaint();
 }
+
+/// Formats `bytes` in binary units, e.g. `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Moves `item` to the front of the most-recent-first `list`, removing the entries
+/// `same` as it and the oldest ones over `max`
+pub fn push_recent_by<T>(
+    list: &mut Vec<T>,
+    item: T,
+    max: usize,
+    mut same: impl FnMut(&T, &T) -> bool,
+) {
+    list.retain(|entry| !same(entry, &item));
+    list.insert(0, item);
+    list.truncate(max);
+}
+
+/// Same as [`push_recent_by`], with the equal entries being the same
+pub fn push_recent<T: PartialEq>(list: &mut Vec<T>, item: T, max: usize) {
+    push_recent_by(list, item, max, |a, b| a == b);
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn recent_list_is_deduplicated_and_bounded() {
+        let mut recent = Vec::new();
+        for i in 0..15 {
+            super::push_recent(&mut recent, i, 12);
+        }
+        assert_eq!(recent.len(), 12);
+        assert_eq!(recent[0], 14);
+
+        super::push_recent(&mut recent, 10, 12);
+        assert_eq!(recent[0], 10);
+        assert_eq!(recent.len(), 12);
+        assert_eq!(recent.iter().filter(|i| **i == 10).count(), 1);
+
+        super::push_recent_by(&mut recent, 17, 12, |a, b| a % 10 == b % 10);
+        assert_eq!(recent[0], 17);
+        assert!(!recent.contains(&7));
+    }
+
+    #[test]
+    fn sizes_are_in_binary_units() {
+        assert_eq!(super::format_size(1023), "1023 B");
+        assert_eq!(super::format_size(1536), "1.5 KiB");
+        assert_eq!(super::format_size(5 << 40), "5120.0 GiB");
+    }
+
+    #[test]
+    fn capture_error_or_yields_fallback() {
+        let mut handled = None;
+        let parsed: i32 = capture_error_or!(err => handled = Some(err.to_string()), -1, {
+            "12".parse::<i32>()? + "x".parse::<i32>()?
+        });
+        assert_eq!(parsed, -1);
+        assert!(handled.is_some());
+
+        let parsed: i32 = capture_error_or!(_err => (), -1, "12".parse::<i32>()?);
+        assert_eq!(parsed, 12);
+    }
+}
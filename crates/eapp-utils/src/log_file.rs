@@ -0,0 +1,203 @@
+//! Log file that is appended to and rotated by size
+
+use simplelog::LevelFilter;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// How [`crate::setup_loggers`] writes the log file
+#[derive(Debug, Clone, Copy)]
+pub struct LogOptions {
+    pub level: LevelFilter,
+
+    /// The file is rotated once it's bigger than this many bytes
+    pub max_size: u64,
+
+    /// How many rotated files are kept, `.log.1` is the newest
+    pub keep: usize,
+}
+
+impl Default for LogOptions {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::Warn,
+            max_size: 1024 * 1024,
+            keep: 3,
+        }
+    }
+}
+
+impl LogOptions {
+    /// Env var overriding the level, e.g. `EAPP_LOG_LEVEL=debug`
+    pub const LEVEL_ENV: &str = "EAPP_LOG_LEVEL";
+
+    /// Default options with the level from [`LogOptions::LEVEL_ENV`] if it's set
+    pub fn from_env() -> Self {
+        let mut options = Self::default();
+        if let Ok(level) = std::env::var(Self::LEVEL_ENV)
+            && let Ok(level) = level.parse()
+        {
+            options.level = level;
+        }
+        options
+    }
+
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+}
+
+/// Appends to `path`, which is moved to `path.1` (and `path.1` to `path.2`...)
+/// once it's bigger than `max_size`
+///
+/// Files are only rotated between lines, so a file can be bigger by a line
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_size: u64,
+    keep: usize,
+    line_start: bool,
+}
+
+impl RotatingFile {
+    pub fn open(path: impl Into<PathBuf>, max_size: u64, keep: usize) -> io::Result<Self> {
+        let path = path.into();
+
+        let mut size = fs::metadata(&path).map_or(0, |m| m.len());
+        if size >= max_size {
+            rotate(&path, keep)?;
+            size = 0;
+        }
+
+        Ok(Self {
+            file: open_append(&path)?,
+            path,
+            size,
+            max_size,
+            keep,
+            line_start: true,
+        })
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        rotate(&self.path, self.keep)?;
+        self.file = open_append(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.line_start && self.size >= self.max_size {
+            self.rotate()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        if written > 0 {
+            self.line_start = buf[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// e.g. `app.log.2`
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{n}"));
+    path.into()
+}
+
+fn rotate(path: &Path, keep: usize) -> io::Result<()> {
+    if keep == 0 {
+        return fs::remove_file(path);
+    }
+
+    let oldest = rotated_path(path, keep);
+    if oldest.exists() {
+        fs::remove_file(oldest)?;
+    }
+
+    for n in (1..keep).rev() {
+        let from = rotated_path(path, n);
+        if from.exists() {
+            fs::rename(from, rotated_path(path, n + 1))?;
+        }
+    }
+
+    fs::rename(path, rotated_path(path, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("eapp-utils-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("app.log")
+    }
+
+    #[test]
+    fn appends_and_rotates_between_lines() {
+        let path = temp_log("rotate");
+
+        let mut file = RotatingFile::open(&path, 8, 2).unwrap();
+        for line in ["one\n", "two\n", "three\n", "four\n", "five\n"] {
+            // A line written in pieces stays in one file
+            let (a, b) = line.split_at(2);
+            file.write_all(a.as_bytes()).unwrap();
+            file.write_all(b.as_bytes()).unwrap();
+        }
+        drop(file);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "five\n");
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+            "three\nfour\n"
+        );
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 2)).unwrap(),
+            "one\ntwo\n"
+        );
+        assert!(!rotated_path(&path, 3).exists());
+
+        // Reopening appends instead of truncating
+        let mut file = RotatingFile::open(&path, 64, 2).unwrap();
+        file.write_all(b"six\n").unwrap();
+        drop(file);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "five\nsix\n");
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn reopening_a_big_file_rotates_it() {
+        let path = temp_log("reopen");
+        fs::write(&path, "0123456789\n").unwrap();
+
+        drop(RotatingFile::open(&path, 8, 1).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 1)).unwrap(),
+            "0123456789\n"
+        );
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
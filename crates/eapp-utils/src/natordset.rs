@@ -1,8 +1,61 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
-#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+/// Natural order, e.g. `"img2"` is before `"img10"`
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    natord::compare(a, b)
+}
+
+/// Same as [`natural_cmp`] but ignoring case, so that `"Apple"` and `"apple"` are adjacent,
+/// ties are broken by [`natural_cmp`] to keep the order deterministic
+pub fn natural_cmp_ci(a: &str, b: &str) -> Ordering {
+    natord::compare(&a.to_lowercase(), &b.to_lowercase()).then_with(|| natural_cmp(a, b))
+}
+
+/// Strings in natural order, see [`natural_cmp`]
+///
+/// [`NatOrdSet::insert`] and [`NatOrdSet::remove`] keep the order, pushed strings must be
+/// sorted afterwards, deserialized ones are sorted and deduplicated
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "Unsorted")]
 pub struct NatOrdSet(pub Vec<String>);
 
+/// What is stored, which may come from an older version or be edited by hand
+#[derive(Deserialize)]
+#[serde(rename = "NatOrdSet")]
+struct Unsorted(Vec<String>);
+
+impl From<Unsorted> for NatOrdSet {
+    fn from(Unsorted(values): Unsorted) -> Self {
+        Self::from(values)
+    }
+}
+
+impl From<Vec<String>> for NatOrdSet {
+    /// Sorts and deduplicates `values`
+    fn from(values: Vec<String>) -> Self {
+        let mut set = Self(values);
+        set.sort();
+        set.0.dedup();
+        set
+    }
+}
+
+impl FromIterator<String> for NatOrdSet {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl<'a> IntoIterator for &'a NatOrdSet {
+    type Item = &'a String;
+    type IntoIter = std::slice::Iter<'a, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 impl NatOrdSet {
     pub fn new() -> Self {
         Default::default()
@@ -12,9 +65,49 @@ impl NatOrdSet {
         self.0.push(value);
     }
 
+    /// Inserts `value` at its place, returns `false` if it's already there
+    pub fn insert(&mut self, value: String) -> bool {
+        match self.search(&value) {
+            Ok(_) => false,
+            Err(idx) => {
+                self.0.insert(idx, value);
+                true
+            }
+        }
+    }
+
+    /// Returns `false` if `value` isn't there
+    pub fn remove(&mut self, value: &str) -> bool {
+        match self.search(value) {
+            Ok(idx) => {
+                self.0.remove(idx);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn contains(&self, value: &str) -> bool {
+        self.search(value).is_ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
     pub fn search(&self, value: &str) -> Result<usize, usize> {
         self.0
-            .binary_search_by(|item| natord::compare(item.as_str(), value))
+            .binary_search_by(|item| natural_cmp_total(item.as_str(), value))
+    }
+
+    /// Same as [`NatOrdSet::search`] for a set sorted by [`NatOrdSet::sort_ci`]
+    pub fn search_ci(&self, value: &str) -> Result<usize, usize> {
+        self.0
+            .binary_search_by(|item| natural_cmp_ci(item.as_str(), value))
     }
 
     pub fn iter(&self) -> std::slice::Iter<'_, String> {
@@ -22,6 +115,90 @@ impl NatOrdSet {
     }
 
     pub fn sort(&mut self) {
-        self.0.sort_by(|a, b| natord::compare(a, b));
+        self.0.sort_by(|a, b| natural_cmp_total(a, b));
+    }
+
+    /// Sorts ignoring case, search it with [`NatOrdSet::search_ci`]
+    pub fn sort_ci(&mut self) {
+        self.0.sort_by(|a, b| natural_cmp_ci(a, b));
+    }
+}
+
+/// [`natural_cmp`] with ties broken by the bytes, so that only equal strings are equal
+fn natural_cmp_total(a: &str, b: &str) -> Ordering {
+    natural_cmp(a, b).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_ci(values: &[&str]) -> Vec<String> {
+        let mut set = NatOrdSet(values.iter().map(|v| v.to_string()).collect());
+        set.sort_ci();
+        set.0
+    }
+
+    #[test]
+    fn mixed_case_is_adjacent() {
+        assert_eq!(
+            sorted_ci(&["banana", "apple", "Banana", "cherry", "Apple"]),
+            ["Apple", "apple", "Banana", "banana", "cherry"]
+        );
+    }
+
+    #[test]
+    fn numeric_runs() {
+        assert_eq!(natural_cmp("img2", "img10"), Ordering::Less);
+        assert_eq!(natural_cmp_ci("IMG2", "img10"), Ordering::Less);
+        assert_eq!(
+            sorted_ci(&["img10", "IMG2", "img1"]),
+            ["img1", "IMG2", "img10"]
+        );
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(natural_cmp_ci("Éclair", "éclair"), Ordering::Less);
+        assert_eq!(
+            sorted_ci(&["éclair", "Ωmega", "Éclair", "ωmega"]),
+            ["Éclair", "éclair", "Ωmega", "ωmega"]
+        );
+    }
+
+    #[test]
+    fn search_ci_finds_sorted_entries() {
+        let mut set = NatOrdSet(vec!["b".into(), "A".into(), "a10".into(), "a2".into()]);
+        set.sort_ci();
+
+        assert_eq!(set.search_ci("a2"), Ok(1));
+        assert_eq!(set.search_ci("a10"), Ok(2));
+        assert_eq!(set.search_ci("B"), Err(3));
+    }
+
+    #[test]
+    fn insert_and_remove_keep_the_order() {
+        let mut set: NatOrdSet = ["img10", "img2"].map(String::from).into_iter().collect();
+        assert!(set.insert("img1".to_owned()));
+        assert!(set.insert("img3".to_owned()));
+        assert!(!set.insert("img2".to_owned()));
+        assert_eq!(set.0, ["img1", "img2", "img3", "img10"]);
+
+        assert!(set.remove("img3"));
+        assert!(!set.remove("img3"));
+        assert!(set.contains("img10"));
+        assert_eq!(set.iter().collect::<Vec<_>>(), ["img1", "img2", "img10"]);
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn serde_round_trip_sorts() {
+        let set = NatOrdSet::from(vec!["b".to_owned(), "a10".to_owned(), "a2".to_owned()]);
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, r#"["a2","a10","b"]"#);
+        assert_eq!(serde_json::from_str::<NatOrdSet>(&json).unwrap(), set);
+
+        let edited: NatOrdSet = serde_json::from_str(r#"["b","a2","b","a10"]"#).unwrap();
+        assert_eq!(edited, set);
     }
 }
@@ -0,0 +1,121 @@
+//! Keeps the window position and size across restarts
+
+use crate::borderless;
+use eframe::egui::{self, Pos2, Rect, Vec2, ViewportCommand};
+use serde::{Deserialize, Serialize};
+
+/// Saves where the window is on `save` and moves it back there on startup
+///
+/// Set `persist_window` of `NativeOptions` to `false` so that eframe doesn't do it as well.
+pub struct PersistentWindow {
+    ctx: egui::Context,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy)]
+struct Geometry {
+    pos: Pos2,
+    size: Vec2,
+    maximized: bool,
+}
+
+impl PersistentWindow {
+    pub const KEY: &'static str = "window_geometry";
+
+    /// Restores the window saved by [`PersistentWindow::save`], kept inside the nearest monitor
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let ctx = cc.egui_ctx.clone();
+
+        if let Some(geometry) = cc
+            .storage
+            .and_then(|storage| eframe::get_value::<Geometry>(storage, Self::KEY))
+        {
+            let rect = Rect::from_min_size(geometry.pos, geometry.size);
+            match borderless::work_area_at(&ctx, rect.center()) {
+                Some(work_area) => {
+                    let rect = clamp_into(rect, work_area);
+                    ctx.send_viewport_cmd(ViewportCommand::InnerSize(rect.size()));
+                    ctx.send_viewport_cmd(ViewportCommand::OuterPosition(rect.min));
+                }
+                // The monitor is unknown, the position might be on a screen that is gone
+                None => ctx.send_viewport_cmd(ViewportCommand::InnerSize(rect.size())),
+            }
+
+            if geometry.maximized {
+                ctx.send_viewport_cmd(ViewportCommand::Maximized(true));
+            }
+        }
+
+        Self { ctx }
+    }
+
+    pub fn save(&self, storage: &mut dyn eframe::Storage) {
+        let (outer_rect, inner_rect, maximized, fullscreen, minimized) = self.ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.outer_rect,
+                viewport.inner_rect,
+                viewport.maximized.unwrap_or(false),
+                viewport.fullscreen.unwrap_or(false),
+                viewport.minimized.unwrap_or(false),
+            )
+        });
+
+        // Keep the last saved one, a fullscreen or minimized window says nothing useful
+        if fullscreen || minimized {
+            return;
+        }
+
+        let geometry = if maximized {
+            borderless::restore_rect(&self.ctx)
+        } else {
+            outer_rect
+                .zip(inner_rect)
+                .map(|(outer_rect, inner_rect)| (outer_rect.min, inner_rect.size()))
+        };
+
+        if let Some((pos, size)) = geometry {
+            eframe::set_value(
+                storage,
+                Self::KEY,
+                &Geometry {
+                    pos,
+                    size,
+                    maximized,
+                },
+            );
+        }
+    }
+}
+
+/// Shrinks and moves `rect` to be inside `area`
+fn clamp_into(rect: Rect, area: Rect) -> Rect {
+    let size = rect.size().min(area.size());
+    let min = rect.min.clamp(area.min, area.max - size);
+    Rect::from_min_size(min, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::{pos2, vec2};
+
+    #[test]
+    fn clamp_into_monitor() {
+        let area = Rect::from_min_size(pos2(0.0, 0.0), vec2(1920.0, 1040.0));
+
+        let inside = Rect::from_min_size(pos2(100.0, 100.0), vec2(720.0, 480.0));
+        assert_eq!(clamp_into(inside, area), inside);
+
+        let gone = Rect::from_min_size(pos2(2500.0, -300.0), vec2(720.0, 480.0));
+        assert_eq!(
+            clamp_into(gone, area),
+            Rect::from_min_size(pos2(1200.0, 0.0), vec2(720.0, 480.0))
+        );
+
+        let huge = Rect::from_min_size(pos2(-50.0, 10.0), vec2(4000.0, 480.0));
+        assert_eq!(
+            clamp_into(huge, area),
+            Rect::from_min_size(pos2(0.0, 10.0), vec2(1920.0, 480.0))
+        );
+    }
+}
@@ -1,3 +1,11 @@
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+
+/// Keeps the system and the display awake until [`allow_sleep`] is called,
+/// it's fine to call it repeatedly
+///
+/// On macOS and Linux it's held by a `caffeinate` or `systemd-inhibit` child process,
+/// which also exits with this process
 pub fn prevent_sleep() {
     #[cfg(windows)]
     unsafe {
@@ -6,4 +14,1007 @@ pub fn prevent_sleep() {
         };
         SetThreadExecutionState(ES_CONTINUOUS | ES_DISPLAY_REQUIRED | ES_SYSTEM_REQUIRED);
     }
+
+    #[cfg(unix)]
+    {
+        let mut inhibitor = SLEEP_INHIBITOR.lock().unwrap();
+        if let Some(child) = inhibitor.as_mut()
+            && matches!(child.try_wait(), Ok(None))
+        {
+            return;
+        }
+
+        let command = sleep_inhibitor_command(std::process::id());
+        *inhibitor = std::process::Command::new(&command[0])
+            .args(&command[1..])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .inspect_err(|err| log::warn!("prevent sleep by '{}' fails: {err}", command[0]))
+            .ok();
+    }
+}
+
+/// Releases what [`prevent_sleep`] holds
+pub fn allow_sleep() {
+    #[cfg(windows)]
+    unsafe {
+        use windows_sys::Win32::System::Power::{ES_CONTINUOUS, SetThreadExecutionState};
+        SetThreadExecutionState(ES_CONTINUOUS);
+    }
+
+    #[cfg(unix)]
+    {
+        if let Some(mut child) = SLEEP_INHIBITOR.lock().unwrap().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Plays the system alert sound, to give feedback while the app isn't visible
+///
+/// On macOS and Linux it's played by `osascript` or `canberra-gtk-play`,
+/// nothing is played if they are missing
+pub fn beep() {
+    #[cfg(windows)]
+    unsafe {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{MB_OK, MessageBeep};
+        MessageBeep(MB_OK);
+    }
+
+    #[cfg(unix)]
+    {
+        #[cfg(target_os = "macos")]
+        let command = ["osascript", "-e", "beep"];
+
+        #[cfg(not(target_os = "macos"))]
+        let command = [
+            "canberra-gtk-play",
+            "--id=bell",
+            "--description=lomekragow-eapp",
+        ];
+
+        match std::process::Command::new(command[0])
+            .args(&command[1..])
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+        {
+            Ok(mut child) => {
+                std::thread::spawn(move || child.wait());
+            }
+            Err(err) => {
+                log::info!("beep by '{}' fails: {err}", command[0]);
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+static SLEEP_INHIBITOR: std::sync::Mutex<Option<std::process::Child>> = std::sync::Mutex::new(None);
+
+/// A command that keeps the system awake while process `pid` is alive
+#[cfg(unix)]
+fn sleep_inhibitor_command(pid: u32) -> Vec<String> {
+    let pid = pid.to_string();
+
+    #[cfg(target_os = "macos")]
+    let command = vec!["caffeinate", "-d", "-i", "-w", pid.as_str()];
+
+    #[cfg(not(target_os = "macos"))]
+    let command = vec![
+        "systemd-inhibit",
+        "--what=idle:sleep",
+        "--who=lomekragow-eapp",
+        "--why=Playing media",
+        "--mode=block",
+        "tail",
+        "--pid",
+        pid.as_str(),
+        "-f",
+        "/dev/null",
+    ];
+
+    command.into_iter().map(str::to_owned).collect()
+}
+
+/// A visible top-level window, the rect is in physical screen pixels
+/// and excludes the invisible resize borders
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    pub handle: isize,
+    pub title: String,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl WindowInfo {
+    /// Converts `(rx, ry)` relative to the window rect (`0.0..=1.0`) to screen pixels
+    pub fn to_screen(&self, rx: f32, ry: f32) -> (i32, i32) {
+        (
+            self.x + (self.width as f32 * rx).round() as i32,
+            self.y + (self.height as f32 * ry).round() as i32,
+        )
+    }
+}
+
+/// Finds the first visible top-level window whose title contains `title` (case-insensitive)
+pub fn find_window(title: &str) -> std::io::Result<Option<WindowInfo>> {
+    #[cfg(windows)]
+    {
+        windows_impl::find_window(title)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = title;
+        Err(unsupported_window_targeting())
+    }
+}
+
+/// Restores `window` if minimized and brings it to the foreground, returns whether it succeeded
+pub fn focus_window(window: &WindowInfo) -> std::io::Result<bool> {
+    #[cfg(windows)]
+    {
+        Ok(windows_impl::focus_window(window))
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = window;
+        Err(unsupported_window_targeting())
+    }
+}
+
+/// Executable names of all running processes
+pub fn running_processes() -> std::io::Result<Vec<String>> {
+    #[cfg(windows)]
+    {
+        windows_impl::running_processes()
+    }
+
+    #[cfg(not(windows))]
+    {
+        let proc_dir = std::path::Path::new("/proc");
+        if !proc_dir.is_dir() {
+            // No procfs, e.g. macOS
+            let output = std::process::Command::new("ps")
+                .args(["-A", "-o", "comm="])
+                .output()?;
+
+            return Ok(String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.trim().rsplit('/').next())
+                .filter(|name| !name.is_empty())
+                .map(str::to_owned)
+                .collect());
+        }
+
+        let mut names = Vec::new();
+        for entry in std::fs::read_dir(proc_dir)? {
+            let path = entry?.path();
+            if !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().bytes().all(|b| b.is_ascii_digit()))
+            {
+                continue;
+            }
+
+            // `comm` is truncated to 15 bytes, prefer the first argument of the command line
+            let name = std::fs::read(path.join("cmdline"))
+                .ok()
+                .and_then(|cmdline| {
+                    let arg = cmdline.split(|b| *b == 0).next()?;
+                    let arg = String::from_utf8_lossy(arg);
+                    let name = arg.rsplit('/').next()?.to_owned();
+                    (!name.is_empty()).then_some(name)
+                })
+                .or_else(|| {
+                    std::fs::read_to_string(path.join("comm"))
+                        .ok()
+                        .map(|comm| comm.trim_end().to_owned())
+                });
+
+            names.extend(name);
+        }
+
+        Ok(names)
+    }
+}
+
+/// Whether a process named `exe_name` is running, the `.exe` extension and case are ignored
+pub fn is_process_running(exe_name: &str) -> std::io::Result<bool> {
+    fn normalize(name: &str) -> String {
+        let name = name.trim().to_lowercase();
+        match name.strip_suffix(".exe") {
+            Some(name) => name.to_owned(),
+            None => name,
+        }
+    }
+
+    let exe_name = normalize(exe_name);
+    Ok(running_processes()?
+        .iter()
+        .any(|name| normalize(name) == exe_name))
+}
+
+/// Work area (`[left, top, right, bottom]` in physical screen pixels, without the taskbar)
+/// of the monitor nearest to the point, `None` if unknown on this platform
+pub fn monitor_work_area(x: i32, y: i32) -> Option<[i32; 4]> {
+    #[cfg(windows)]
+    {
+        windows_impl::monitor_work_area(x, y)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (x, y);
+        None
+    }
+}
+
+/// Asks the OS to round the corners of the main window and whether to draw its shadow,
+/// which undecorated windows don't get by default, on Windows 11 only
+///
+/// The main window is the first visible top-level window of the calling thread,
+/// so call it from the UI thread. Returns `false` if it's not found yet, always on other platforms
+pub fn set_window_frame(round_corners: bool, shadow: bool) -> bool {
+    #[cfg(windows)]
+    {
+        windows_frame::set_window_frame(round_corners, shadow)
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = (round_corners, shadow);
+        false
+    }
+}
+
+/// Makes the OS treat `rect` (`[left, top, right, bottom]` in physical pixels of the client area)
+/// of the main window as its maximize button, `None` to stop
+///
+/// So hovering it shows the snap layouts flyout of Windows 11.
+/// Clicks on it toggle maximized natively, and the pointer is no longer reported to the app there,
+/// see [`is_maximize_button_hovered`]
+pub fn set_maximize_button_rect(rect: Option<[i32; 4]>) {
+    #[cfg(windows)]
+    windows_frame::set_maximize_button_rect(rect);
+
+    #[cfg(not(windows))]
+    let _ = rect;
+}
+
+/// Whether the pointer is over the rect given to [`set_maximize_button_rect`]
+pub fn is_maximize_button_hovered() -> bool {
+    #[cfg(windows)]
+    {
+        windows_frame::is_maximize_button_hovered()
+    }
+
+    #[cfg(not(windows))]
+    {
+        false
+    }
+}
+
+/// How the wallpaper is placed on the desktop
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum WallpaperFit {
+    /// Covers the desktop, may be cropped
+    #[default]
+    Fill,
+
+    /// Fits in the desktop, may be letterboxed
+    Fit,
+
+    Center,
+    Tile,
+}
+
+impl WallpaperFit {
+    pub const ALL: [Self; 4] = [Self::Fill, Self::Fit, Self::Center, Self::Tile];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Fill => "Fill",
+            Self::Fit => "Fit",
+            Self::Center => "Center",
+            Self::Tile => "Tile",
+        }
+    }
+}
+
+/// Sets the image at `path` as the desktop wallpaper
+///
+/// On Linux gsettings (GNOME-like desktops) is tried first, then feh. The fit is ignored on macOS
+pub fn set_wallpaper(path: &std::path::Path, fit: WallpaperFit) -> std::io::Result<()> {
+    let path = path.canonicalize()?;
+
+    #[cfg(windows)]
+    {
+        windows_impl::set_wallpaper(&path, fit)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = fit;
+        run_command(&macos_wallpaper_command(&path.to_string_lossy()))
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let path = path.to_string_lossy();
+        let gsettings = gsettings_wallpaper_commands(&path, fit)
+            .iter()
+            .try_for_each(|command| run_command(command));
+
+        gsettings.or_else(|err| {
+            log::info!("set wallpaper by gsettings fails: {err}, fall back to feh");
+            run_command(&feh_wallpaper_command(&path, fit))
+        })
+    }
+
+    #[cfg(not(any(windows, unix)))]
+    {
+        let _ = (path, fit);
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "Setting wallpaper is not supported on this platform",
+        ))
+    }
+}
+
+/// Runs `command` (program and arguments), fails if it exits unsuccessfully
+#[cfg(unix)]
+fn run_command(command: &[String]) -> std::io::Result<()> {
+    let output = std::process::Command::new(&command[0])
+        .args(&command[1..])
+        .output()?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "'{}' fails: {}",
+            command[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn macos_wallpaper_command(path: &str) -> Vec<String> {
+    let path = path.replace('\\', "\\\\").replace('"', "\\\"");
+    vec![
+        "osascript".to_owned(),
+        "-e".to_owned(),
+        format!(
+            "tell application \"System Events\" to tell every desktop to set picture to \"{path}\""
+        ),
+    ]
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn gsettings_wallpaper_commands(path: &str, fit: WallpaperFit) -> Vec<Vec<String>> {
+    let options = match fit {
+        WallpaperFit::Fill => "zoom",
+        WallpaperFit::Fit => "scaled",
+        WallpaperFit::Center => "centered",
+        WallpaperFit::Tile => "wallpaper",
+    };
+    let uri = file_uri(path);
+
+    [
+        ("picture-uri", uri.as_str()),
+        ("picture-uri-dark", uri.as_str()),
+        ("picture-options", options),
+    ]
+    .into_iter()
+    .map(|(key, value)| {
+        [
+            "gsettings",
+            "set",
+            "org.gnome.desktop.background",
+            key,
+            value,
+        ]
+        .map(str::to_owned)
+        .to_vec()
+    })
+    .collect()
+}
+
+/// Percent-encoded `file://` uri of an absolute path, e.g. spaces are not allowed in uri
+#[cfg(all(unix, not(target_os = "macos")))]
+fn file_uri(path: &str) -> String {
+    let mut uri = "file://".to_owned();
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-_.~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    uri
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn is_wsl() -> bool {
+    std::env::var_os("WSL_DISTRO_NAME").is_some()
+        || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .is_ok_and(|release| release.to_lowercase().contains("microsoft"))
+}
+
+/// Linux part of [`crate::open_in_explorer`]
+///
+/// Tries the `org.freedesktop.FileManager1` D-Bus interface, then `xdg-open` on the parent
+/// directory, in WSL the Windows explorer is used. The D-Bus reply is waited on a thread,
+/// which only logs if `xdg-open` fails after it
+#[cfg(all(unix, not(target_os = "macos")))]
+#[allow(clippy::zombie_processes)]
+pub(crate) fn open_in_file_manager(path: &str) -> std::io::Result<()> {
+    use std::process::{Command, Stdio};
+
+    let path = std::path::Path::new(path).canonicalize()?;
+
+    if is_wsl() {
+        let output = Command::new("wslpath").arg("-w").arg(&path).output()?;
+        if !output.status.success() {
+            return Err(std::io::Error::other(format!(
+                "'wslpath' fails: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let windows_path = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        Command::new("explorer.exe")
+            .arg(format!("/select,{windows_path}"))
+            .spawn()?;
+        return Ok(());
+    }
+
+    let dir = path.parent().unwrap_or(&path).to_owned();
+    let Ok(mut dbus_send) = Command::new("dbus-send")
+        .args(show_items_command(&file_uri(&path.to_string_lossy())))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        Command::new("xdg-open").arg(&dir).spawn()?;
+        return Ok(());
+    };
+
+    // the reply is waited off the UI thread, it may take until the dbus timeout
+    std::thread::spawn(move || {
+        if dbus_send.wait().is_ok_and(|status| status.success()) {
+            return;
+        }
+
+        if let Err(err) = Command::new("xdg-open")
+            .arg(&dir)
+            .spawn()
+            .and_then(|mut child| child.wait())
+        {
+            log::error!("open '{}' by 'xdg-open' fails: {err}", dir.display());
+        }
+    });
+
+    Ok(())
+}
+
+/// Arguments of `dbus-send` to select `uri` in the file manager
+#[cfg(all(unix, not(target_os = "macos")))]
+fn show_items_command(uri: &str) -> Vec<String> {
+    vec![
+        "--session".to_owned(),
+        "--print-reply".to_owned(),
+        "--dest=org.freedesktop.FileManager1".to_owned(),
+        "--type=method_call".to_owned(),
+        "/org/freedesktop/FileManager1".to_owned(),
+        "org.freedesktop.FileManager1.ShowItems".to_owned(),
+        format!("array:string:{uri}"),
+        "string:".to_owned(),
+    ]
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn feh_wallpaper_command(path: &str, fit: WallpaperFit) -> Vec<String> {
+    let option = match fit {
+        WallpaperFit::Fill => "--bg-fill",
+        WallpaperFit::Fit => "--bg-max",
+        WallpaperFit::Center => "--bg-center",
+        WallpaperFit::Tile => "--bg-tile",
+    };
+    ["feh", option, path].map(str::to_owned).to_vec()
+}
+
+#[cfg(not(windows))]
+fn unsupported_window_targeting() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "Window targeting is only supported on Windows",
+    )
+}
+
+#[cfg(windows)]
+mod windows_impl {
+    use super::{WallpaperFit, WindowInfo};
+    use windows_sys::Win32::{
+        Foundation::{BOOL, CloseHandle, HWND, INVALID_HANDLE_VALUE, LPARAM, POINT, RECT},
+        Graphics::{
+            Dwm::{DWMWA_EXTENDED_FRAME_BOUNDS, DwmGetWindowAttribute},
+            Gdi::{GetMonitorInfoW, MONITOR_DEFAULTTONEAREST, MONITORINFO, MonitorFromPoint},
+        },
+        System::{
+            Diagnostics::ToolHelp::{
+                CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW,
+                TH32CS_SNAPPROCESS,
+            },
+            Registry::{HKEY_CURRENT_USER, REG_SZ, RegSetKeyValueW},
+        },
+        UI::{
+            HiDpi::{DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, SetThreadDpiAwarenessContext},
+            WindowsAndMessaging::{
+                EnumWindows, GetWindowRect, GetWindowTextLengthW, GetWindowTextW, IsIconic,
+                IsWindowVisible, SPI_SETDESKWALLPAPER, SPIF_SENDCHANGE, SPIF_UPDATEINIFILE,
+                SW_RESTORE, SetForegroundWindow, ShowWindow, SystemParametersInfoW,
+            },
+        },
+    };
+
+    pub fn find_window(title: &str) -> std::io::Result<Option<WindowInfo>> {
+        unsafe extern "system" fn push_handle(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            let handles = unsafe { &mut *(lparam as *mut Vec<HWND>) };
+            handles.push(hwnd);
+            1
+        }
+
+        let mut handles: Vec<HWND> = Vec::new();
+        if unsafe { EnumWindows(Some(push_handle), &mut handles as *mut _ as LPARAM) } == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let title = title.to_lowercase();
+
+        // Query rects in physical pixels, whatever the awareness of the calling thread is,
+        // so that they match the coordinates used for mouse input
+        let old_context =
+            unsafe { SetThreadDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) };
+
+        let found = handles.into_iter().find_map(|hwnd| {
+            if unsafe { IsWindowVisible(hwnd) } == 0 {
+                return None;
+            }
+
+            let window_title = window_title(hwnd)?;
+            if !window_title.to_lowercase().contains(&title) {
+                return None;
+            }
+
+            let rect = window_rect(hwnd)?;
+            Some(WindowInfo {
+                handle: hwnd,
+                title: window_title,
+                x: rect.left,
+                y: rect.top,
+                width: rect.right - rect.left,
+                height: rect.bottom - rect.top,
+            })
+        });
+
+        if old_context != 0 {
+            unsafe { SetThreadDpiAwarenessContext(old_context) };
+        }
+
+        Ok(found)
+    }
+
+    pub fn running_processes() -> std::io::Result<Vec<String>> {
+        let snapshot = unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) };
+        if snapshot == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        let mut names = Vec::new();
+        let mut entry: PROCESSENTRY32W = unsafe { std::mem::zeroed() };
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+
+        let mut ok = unsafe { Process32FirstW(snapshot, &mut entry) };
+        while ok != 0 {
+            let len = entry
+                .szExeFile
+                .iter()
+                .position(|c| *c == 0)
+                .unwrap_or(entry.szExeFile.len());
+            names.push(String::from_utf16_lossy(&entry.szExeFile[..len]));
+            ok = unsafe { Process32NextW(snapshot, &mut entry) };
+        }
+
+        unsafe { CloseHandle(snapshot) };
+        Ok(names)
+    }
+
+    pub fn focus_window(window: &WindowInfo) -> bool {
+        unsafe {
+            if IsIconic(window.handle) != 0 {
+                ShowWindow(window.handle, SW_RESTORE);
+            }
+
+            SetForegroundWindow(window.handle) != 0
+        }
+    }
+
+    pub fn monitor_work_area(x: i32, y: i32) -> Option<[i32; 4]> {
+        let monitor = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST) };
+
+        let mut info: MONITORINFO = unsafe { std::mem::zeroed() };
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if unsafe { GetMonitorInfoW(monitor, &mut info) } == 0 {
+            return None;
+        }
+
+        let RECT {
+            left,
+            top,
+            right,
+            bottom,
+        } = info.rcWork;
+        Some([left, top, right, bottom])
+    }
+
+    /// `WallpaperStyle` and `TileWallpaper` values under `HKCU\Control Panel\Desktop`
+    pub fn wallpaper_style(fit: WallpaperFit) -> (&'static str, &'static str) {
+        match fit {
+            WallpaperFit::Fill => ("10", "0"),
+            WallpaperFit::Fit => ("6", "0"),
+            WallpaperFit::Center => ("0", "0"),
+            WallpaperFit::Tile => ("0", "1"),
+        }
+    }
+
+    pub fn set_wallpaper(path: &std::path::Path, fit: WallpaperFit) -> std::io::Result<()> {
+        fn wide(str: &str) -> Vec<u16> {
+            str.encode_utf16().chain(std::iter::once(0)).collect()
+        }
+
+        let (style, tile) = wallpaper_style(fit);
+        let key = wide("Control Panel\\Desktop");
+        for (name, value) in [("WallpaperStyle", style), ("TileWallpaper", tile)] {
+            let name = wide(name);
+            let value = wide(value);
+            let result = unsafe {
+                RegSetKeyValueW(
+                    HKEY_CURRENT_USER,
+                    key.as_ptr(),
+                    name.as_ptr(),
+                    REG_SZ,
+                    value.as_ptr() as *const _,
+                    (value.len() * 2) as u32,
+                )
+            };
+            if result != 0 {
+                return Err(std::io::Error::from_raw_os_error(result as i32));
+            }
+        }
+
+        // `canonicalize` gives a verbatim path, which the shell doesn't accept
+        let path = path.to_string_lossy();
+        let mut path = wide(path.strip_prefix(r"\\?\").unwrap_or(&path));
+        let ok = unsafe {
+            SystemParametersInfoW(
+                SPI_SETDESKWALLPAPER,
+                0,
+                path.as_mut_ptr() as *mut _,
+                SPIF_UPDATEINIFILE | SPIF_SENDCHANGE,
+            )
+        };
+        if ok == 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+
+        Ok(())
+    }
+
+    fn window_title(hwnd: HWND) -> Option<String> {
+        let len = unsafe { GetWindowTextLengthW(hwnd) };
+        if len <= 0 {
+            return None;
+        }
+
+        let mut buf = vec![0u16; len as usize + 1];
+        let len = unsafe { GetWindowTextW(hwnd, buf.as_mut_ptr(), buf.len() as i32) };
+        (len > 0).then(|| String::from_utf16_lossy(&buf[..len as usize]))
+    }
+
+    /// The visible bounds of the window, falls back to the rect including resize borders
+    fn window_rect(hwnd: HWND) -> Option<RECT> {
+        let mut rect: RECT = unsafe { std::mem::zeroed() };
+
+        let result = unsafe {
+            DwmGetWindowAttribute(
+                hwnd,
+                DWMWA_EXTENDED_FRAME_BOUNDS as _,
+                &mut rect as *mut RECT as *mut _,
+                std::mem::size_of::<RECT>() as u32,
+            )
+        };
+
+        if result >= 0 || unsafe { GetWindowRect(hwnd, &mut rect) } != 0 {
+            Some(rect)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows_frame {
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicIsize, Ordering},
+    };
+    use windows_sys::Win32::{
+        Foundation::{BOOL, HWND, LPARAM, LRESULT, POINT, WPARAM},
+        Graphics::{
+            Dwm::{
+                DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_DONOTROUND, DWMWCP_ROUND,
+                DwmExtendFrameIntoClientArea, DwmSetWindowAttribute,
+            },
+            Gdi::{InvalidateRect, ScreenToClient},
+        },
+        System::Threading::GetCurrentThreadId,
+        UI::{
+            Controls::MARGINS,
+            Input::KeyboardAndMouse::{TME_LEAVE, TME_NONCLIENT, TRACKMOUSEEVENT, TrackMouseEvent},
+            Shell::{DefSubclassProc, SetWindowSubclass},
+            WindowsAndMessaging::{
+                EnumThreadWindows, GetParent, HTMAXBUTTON, IsWindow, IsWindowVisible, IsZoomed,
+                SW_MAXIMIZE, SW_RESTORE, ShowWindow, WM_MOUSEMOVE, WM_NCHITTEST, WM_NCLBUTTONDOWN,
+                WM_NCLBUTTONUP, WM_NCMOUSELEAVE,
+            },
+        },
+    };
+
+    /// The window found by [`main_window`], `0` if not found yet
+    static MAIN_WINDOW: AtomicIsize = AtomicIsize::new(0);
+
+    /// The window the subclass is installed on
+    static SUBCLASSED: AtomicIsize = AtomicIsize::new(0);
+
+    static MAXIMIZE_BUTTON: Mutex<Option<[i32; 4]>> = Mutex::new(None);
+    static HOVERED: AtomicBool = AtomicBool::new(false);
+
+    const SUBCLASS_ID: usize = 0xea77;
+
+    pub fn set_window_frame(round_corners: bool, shadow: bool) -> bool {
+        let Some(hwnd) = main_window() else {
+            return false;
+        };
+
+        let preference = if round_corners {
+            DWMWCP_ROUND
+        } else {
+            DWMWCP_DONOTROUND
+        };
+        let margin = if shadow { 1 } else { 0 };
+        let margins = MARGINS {
+            cxLeftWidth: margin,
+            cxRightWidth: margin,
+            cyTopHeight: margin,
+            cyBottomHeight: margin,
+        };
+
+        // Fails before Windows 11, where there is nothing to do
+        unsafe {
+            DwmSetWindowAttribute(
+                hwnd,
+                DWMWA_WINDOW_CORNER_PREFERENCE as _,
+                &preference as *const _ as *const _,
+                std::mem::size_of_val(&preference) as u32,
+            );
+            DwmExtendFrameIntoClientArea(hwnd, &margins);
+        }
+        true
+    }
+
+    pub fn set_maximize_button_rect(rect: Option<[i32; 4]>) {
+        *MAXIMIZE_BUTTON.lock().unwrap() = rect;
+        if rect.is_none() {
+            HOVERED.store(false, Ordering::Relaxed);
+            return;
+        }
+
+        let Some(hwnd) = main_window() else {
+            return;
+        };
+
+        if SUBCLASSED.swap(hwnd, Ordering::Relaxed) != hwnd
+            && unsafe { SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0) } == 0
+        {
+            SUBCLASSED.store(0, Ordering::Relaxed);
+            log::warn!(
+                "subclass the main window fails: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+
+    pub fn is_maximize_button_hovered() -> bool {
+        HOVERED.load(Ordering::Relaxed)
+    }
+
+    fn main_window() -> Option<HWND> {
+        unsafe extern "system" fn find_top_level(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            if unsafe { IsWindowVisible(hwnd) == 0 || GetParent(hwnd) != 0 } {
+                return 1;
+            }
+
+            unsafe { *(lparam as *mut HWND) = hwnd };
+            0
+        }
+
+        let hwnd = MAIN_WINDOW.load(Ordering::Relaxed);
+        if hwnd != 0 && unsafe { IsWindow(hwnd) } != 0 {
+            return Some(hwnd);
+        }
+
+        let mut found: HWND = 0;
+        unsafe {
+            EnumThreadWindows(
+                GetCurrentThreadId(),
+                Some(find_top_level),
+                &mut found as *mut HWND as LPARAM,
+            )
+        };
+
+        MAIN_WINDOW.store(found, Ordering::Relaxed);
+        (found != 0).then_some(found)
+    }
+
+    fn set_hovered(hwnd: HWND, hovered: bool) {
+        if HOVERED.swap(hovered, Ordering::Relaxed) == hovered {
+            return;
+        }
+
+        if hovered {
+            let mut event = TRACKMOUSEEVENT {
+                cbSize: std::mem::size_of::<TRACKMOUSEEVENT>() as u32,
+                dwFlags: TME_LEAVE | TME_NONCLIENT,
+                hwndTrack: hwnd,
+                dwHoverTime: 0,
+            };
+            unsafe { TrackMouseEvent(&mut event) };
+        }
+
+        // Nothing is reported to the app from the non-client area, make it repaint the button
+        unsafe { InvalidateRect(hwnd, std::ptr::null(), 0) };
+    }
+
+    fn in_maximize_button(hwnd: HWND, lparam: LPARAM) -> bool {
+        let Some([left, top, right, bottom]) = *MAXIMIZE_BUTTON.lock().unwrap() else {
+            return false;
+        };
+
+        let mut point = POINT {
+            x: (lparam & 0xffff) as i16 as i32,
+            y: ((lparam >> 16) & 0xffff) as i16 as i32,
+        };
+        if unsafe { ScreenToClient(hwnd, &mut point) } == 0 {
+            return false;
+        }
+
+        (left..right).contains(&point.x) && (top..bottom).contains(&point.y)
+    }
+
+    unsafe extern "system" fn subclass_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        _data: usize,
+    ) -> LRESULT {
+        match msg {
+            WM_NCHITTEST => {
+                let hit = in_maximize_button(hwnd, lparam);
+                set_hovered(hwnd, hit);
+                if hit {
+                    return HTMAXBUTTON as _;
+                }
+            }
+            // Swallowed, otherwise the default handling tracks the button itself
+            WM_NCLBUTTONDOWN if wparam == HTMAXBUTTON as usize => return 0,
+            WM_NCLBUTTONUP if wparam == HTMAXBUTTON as usize => {
+                let show = if unsafe { IsZoomed(hwnd) } != 0 {
+                    SW_RESTORE
+                } else {
+                    SW_MAXIMIZE
+                };
+                unsafe { ShowWindow(hwnd, show) };
+                return 0;
+            }
+            WM_NCMOUSELEAVE | WM_MOUSEMOVE => set_hovered(hwnd, false),
+            _ => {}
+        }
+
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_wallpaper_style() {
+        assert_eq!(
+            windows_impl::wallpaper_style(WallpaperFit::Fill),
+            ("10", "0")
+        );
+        assert_eq!(
+            windows_impl::wallpaper_style(WallpaperFit::Tile),
+            ("0", "1")
+        );
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn macos_sleep_inhibitor_args() {
+        assert_eq!(
+            sleep_inhibitor_command(42),
+            ["caffeinate", "-d", "-i", "-w", "42"]
+        );
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn linux_sleep_inhibitor_args() {
+        let command = sleep_inhibitor_command(42);
+        assert_eq!(command[0], "systemd-inhibit");
+        assert!(command.ends_with(&["tail", "--pid", "42", "-f", "/dev/null"].map(str::to_owned)));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn macos_wallpaper_args() {
+        let command = macos_wallpaper_command(r#"/tmp/a "b".png"#);
+        assert_eq!(command[..2], ["osascript", "-e"]);
+        assert!(command[2].ends_with(r#"set picture to "/tmp/a \"b\".png""#));
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn linux_wallpaper_args() {
+        let commands = gsettings_wallpaper_commands("/tmp/a b.png", WallpaperFit::Fit);
+        assert_eq!(
+            commands[0],
+            [
+                "gsettings",
+                "set",
+                "org.gnome.desktop.background",
+                "picture-uri",
+                "file:///tmp/a%20b.png"
+            ]
+        );
+        assert_eq!(commands[2][4], "scaled");
+        assert_eq!(
+            feh_wallpaper_command("/tmp/a b.png", WallpaperFit::Tile),
+            ["feh", "--bg-tile", "/tmp/a b.png"]
+        );
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    #[test]
+    fn linux_show_items_args() {
+        let command = show_items_command(&file_uri("/tmp/a,b.png"));
+        assert_eq!(command[6], "array:string:file:///tmp/a%2Cb.png");
+        assert_eq!(command[7], "string:");
+    }
 }
@@ -0,0 +1,101 @@
+//! Image clipboard, which egui doesn't provide
+//!
+//! Raw pixels are unmultiplied RGBA, as the OS clipboard expects,
+//! the [`ColorImage`] helpers convert from and to the premultiplied pixels of egui
+
+use eframe::egui::{Color32, ColorImage};
+use std::{cell::RefCell, io};
+
+thread_local! {
+    /// Kept alive as on Linux the image is served only as long as its owner lives
+    static CLIPBOARD: RefCell<Option<arboard::Clipboard>> = const { RefCell::new(None) };
+}
+
+fn with_clipboard<T>(
+    f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+) -> Result<T, arboard::Error> {
+    CLIPBOARD.with_borrow_mut(|clipboard| {
+        let clipboard = match clipboard {
+            Some(clipboard) => clipboard,
+            None => clipboard.insert(arboard::Clipboard::new()?),
+        };
+        f(clipboard)
+    })
+}
+
+/// Puts a `width` x `height` image of unmultiplied RGBA pixels into the clipboard
+pub fn set_image(width: usize, height: usize, rgba: &[u8]) -> io::Result<()> {
+    if rgba.len() != width * height * 4 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} bytes is not an image of {width}x{height}", rgba.len()),
+        ));
+    }
+
+    with_clipboard(|clipboard| {
+        clipboard.set_image(arboard::ImageData {
+            width,
+            height,
+            bytes: rgba.into(),
+        })
+    })
+    .map_err(io::Error::other)
+}
+
+/// The image in the clipboard as width, height and unmultiplied RGBA pixels,
+/// `None` if there is no image or the clipboard is unavailable
+pub fn get_image() -> Option<(usize, usize, Vec<u8>)> {
+    let result = with_clipboard(|clipboard| clipboard.get_image());
+
+    match result {
+        Ok(image) => Some((image.width, image.height, image.bytes.into_owned())),
+        Err(arboard::Error::ContentNotAvailable) => None,
+        Err(err) => {
+            log::warn!("get image from clipboard fails: {err}");
+            None
+        }
+    }
+}
+
+/// Same as [`set_image`] with the pixels of an egui image
+pub fn set_color_image(image: &ColorImage) -> io::Result<()> {
+    let [width, height] = image.size;
+    set_image(width, height, &to_unmultiplied_rgba(&image.pixels))
+}
+
+/// Same as [`get_image`], ready to be loaded as a texture
+pub fn get_color_image() -> Option<ColorImage> {
+    let (width, height, rgba) = get_image()?;
+    Some(ColorImage::from_rgba_unmultiplied([width, height], &rgba))
+}
+
+fn to_unmultiplied_rgba(pixels: &[Color32]) -> Vec<u8> {
+    pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixels_are_unmultiplied_for_the_clipboard() {
+        let rgba = [255, 0, 0, 255, 0, 255, 0, 128, 0, 0, 0, 0];
+        let image = ColorImage::from_rgba_unmultiplied([3, 1], &rgba);
+        assert_ne!(image.pixels[1].g(), 255);
+
+        let back = to_unmultiplied_rgba(&image.pixels);
+        assert_eq!(back[..4], rgba[..4]);
+        assert_eq!(back[7], 128);
+        assert!(back[5].abs_diff(255) <= 2);
+        assert_eq!(back[8..], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn wrong_size_is_refused() {
+        let err = set_image(2, 2, &[0; 4]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,451 @@
+//! Keyboard shortcuts the user can rebind, apps register the default one of each action
+//! and keep the map to save the rebound ones
+
+use eframe::egui::{self, Event, Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::{codicons::ICON_DISCARD, widgets::simple_widgets::frameless_btn};
+
+struct Binding<A> {
+    action: A,
+    desc: &'static str,
+    default: KeyboardShortcut,
+    /// The default is pressed with any modifiers, see [`ShortcutMap::register_any_modifiers`]
+    any_modifiers: bool,
+}
+
+/// Shortcuts of the actions `A`, only the rebound ones are saved so that a changed
+/// default takes effect for those who kept it
+///
+/// # Example
+/// ```ignore
+/// let mut shortcuts: ShortcutMap<Action> = eframe::get_value(storage, ShortcutMap::<Action>::KEY)
+///     .unwrap_or_default();
+/// shortcuts.register(Action::Save, "Save", KeyboardShortcut::new(Modifiers::COMMAND, Key::S));
+///
+/// if shortcuts.consume(ui, Action::Save) {
+///     save();
+/// }
+/// ```
+#[derive(Serialize, Deserialize)]
+#[serde(
+    default,
+    bound(serialize = "A: Serialize", deserialize = "A: Deserialize<'de>")
+)]
+pub struct ShortcutMap<A> {
+    overrides: Vec<(A, KeyboardShortcut)>,
+
+    #[serde(skip)]
+    bindings: Vec<Binding<A>>,
+
+    /// The action waiting for a new shortcut in [`ShortcutMap::ui`]
+    #[serde(skip)]
+    rebinding: Option<A>,
+
+    /// The shortcut just pressed for `rebinding`, already used by another action
+    #[serde(skip)]
+    conflict: Option<(KeyboardShortcut, A)>,
+
+    /// Pass [`ShortcutMap::ui`] was last shown in, `rebinding` is dropped once it's not shown,
+    /// e.g. its window is closed while waiting for a shortcut
+    #[serde(skip)]
+    shown_pass: u64,
+}
+
+impl<A> Default for ShortcutMap<A> {
+    fn default() -> Self {
+        Self {
+            overrides: Vec::new(),
+            bindings: Vec::new(),
+            rebinding: None,
+            conflict: None,
+            shown_pass: 0,
+        }
+    }
+}
+
+impl<A: Copy + PartialEq> ShortcutMap<A> {
+    pub const KEY: &str = "shortcut_map";
+
+    /// Adds `action` with its default shortcut, actions are listed in the order they're added
+    pub fn register(
+        &mut self,
+        action: A,
+        desc: &'static str,
+        default: KeyboardShortcut,
+    ) -> &mut Self {
+        self.insert(action, desc, default, false)
+    }
+
+    /// Same as [`ShortcutMap::register`] with a plain `key` as the default, which is pressed
+    /// whatever modifiers are held, as long as it's not rebound
+    pub fn register_any_modifiers(&mut self, action: A, desc: &'static str, key: Key) -> &mut Self {
+        self.insert(
+            action,
+            desc,
+            KeyboardShortcut::new(Modifiers::NONE, key),
+            true,
+        )
+    }
+
+    fn insert(
+        &mut self,
+        action: A,
+        desc: &'static str,
+        default: KeyboardShortcut,
+        any_modifiers: bool,
+    ) -> &mut Self {
+        match self.bindings.iter_mut().find(|b| b.action == action) {
+            Some(binding) => {
+                binding.desc = desc;
+                binding.default = default;
+                binding.any_modifiers = any_modifiers;
+            }
+            None => self.bindings.push(Binding {
+                action,
+                desc,
+                default,
+                any_modifiers,
+            }),
+        }
+        self
+    }
+
+    /// Whether the shortcut of `action` is pressed with any modifiers
+    fn is_any_modifiers(&self, action: A) -> bool {
+        !self.is_rebound(action)
+            && self
+                .bindings
+                .iter()
+                .any(|b| b.action == action && b.any_modifiers)
+    }
+
+    fn default_of(&self, action: A) -> Option<KeyboardShortcut> {
+        self.bindings
+            .iter()
+            .find(|b| b.action == action)
+            .map(|b| b.default)
+    }
+
+    /// The shortcut of `action`, `None` if it's not registered
+    pub fn get(&self, action: A) -> Option<KeyboardShortcut> {
+        self.overrides
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, shortcut)| *shortcut)
+            .or_else(|| self.default_of(action))
+    }
+
+    /// e.g. `Ctrl+S`, empty if `action` is not registered
+    pub fn format(&self, ctx: &egui::Context, action: A) -> String {
+        self.get(action)
+            .map(|shortcut| ctx.format_shortcut(&shortcut))
+            .unwrap_or_default()
+    }
+
+    /// Another action whose shortcut is pressed along with `shortcut`, e.g. `Ctrl+S` along
+    /// with `Ctrl+Shift+S` as the extra modifiers are not checked
+    pub fn conflict(&self, action: A, shortcut: KeyboardShortcut) -> Option<A> {
+        self.bindings.iter().map(|b| b.action).find(|&other| {
+            other != action
+                && self.get(other).is_some_and(|theirs| {
+                    theirs.logical_key == shortcut.logical_key
+                        && (self.is_any_modifiers(other)
+                            || theirs.modifiers.matches_logically(shortcut.modifiers)
+                            || shortcut.modifiers.matches_logically(theirs.modifiers))
+                })
+        })
+    }
+
+    /// Rebinds `action`, the override is dropped if `shortcut` is the default one
+    pub fn set(&mut self, action: A, shortcut: KeyboardShortcut) {
+        self.overrides.retain(|(a, _)| *a != action);
+        if self.default_of(action) != Some(shortcut) {
+            self.overrides.push((action, shortcut));
+        }
+    }
+
+    pub fn reset(&mut self, action: A) {
+        self.overrides.retain(|(a, _)| *a != action);
+    }
+
+    pub fn is_rebound(&self, action: A) -> bool {
+        self.overrides.iter().any(|(a, _)| *a == action)
+    }
+
+    /// Whether the shortcut of `action` is pressed, the key press is taken so that nothing
+    /// else acts on it, nothing is pressed while waiting for a new shortcut
+    pub fn consume(&self, ui: &egui::Ui, action: A) -> bool {
+        if self.is_capturing(ui.ctx()) {
+            return false;
+        }
+
+        let Some(shortcut) = self.get(action) else {
+            return false;
+        };
+        if self.is_any_modifiers(action) {
+            return ui.input_mut(|i| {
+                let modifiers = i.modifiers;
+                i.consume_key(modifiers, shortcut.logical_key)
+            });
+        }
+        ui.input_mut(|i| i.consume_shortcut(&shortcut))
+    }
+
+    /// Whether a new shortcut is waited for, only while [`ShortcutMap::ui`] is shown,
+    /// which may be shown after the shortcuts are consumed in a pass
+    fn is_capturing(&self, ctx: &egui::Context) -> bool {
+        self.rebinding.is_some() && ctx.cumulative_pass_nr() <= self.shown_pass + 1
+    }
+
+    /// Lists the actions with their shortcuts, a shortcut is rebound by clicking it and
+    /// pressing the new one, `Escape` cancels, returns whether any shortcut is changed
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        if !self.is_capturing(ui.ctx()) {
+            self.rebinding = None;
+            self.conflict = None;
+        }
+        self.shown_pass = ui.ctx().cumulative_pass_nr();
+
+        let mut changed = false;
+        if let Some(action) = self.rebinding {
+            changed = self.capture(ui, action);
+        }
+
+        let mut to_rebind = None;
+        let mut to_reset = None;
+        egui::Grid::new("shortcut_map_grid")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                for binding in &self.bindings {
+                    let action = binding.action;
+                    ui.label(binding.desc);
+
+                    let text = if self.rebinding == Some(action) {
+                        "Press a shortcut...".to_owned()
+                    } else {
+                        self.format(ui.ctx(), action)
+                    };
+                    if ui
+                        .selectable_label(self.rebinding == Some(action), text)
+                        .on_hover_text("Click to rebind")
+                        .clicked()
+                    {
+                        to_rebind = Some(action);
+                    }
+
+                    if self.is_rebound(action) {
+                        if frameless_btn(ui, ICON_DISCARD.to_string())
+                            .on_hover_text(format!(
+                                "Reset to {}",
+                                ui.ctx().format_shortcut(&binding.default)
+                            ))
+                            .clicked()
+                        {
+                            to_reset = Some(action);
+                        }
+                    } else {
+                        ui.label("");
+                    }
+                    ui.end_row();
+                }
+            });
+
+        if let Some((shortcut, other)) = self.conflict
+            && let Some(binding) = self.bindings.iter().find(|b| b.action == other)
+        {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!(
+                    "{} is used by '{}'",
+                    ui.ctx().format_shortcut(&shortcut),
+                    binding.desc
+                ),
+            );
+        }
+
+        if let Some(action) = to_rebind {
+            self.rebinding = (self.rebinding != Some(action)).then_some(action);
+            self.conflict = None;
+        }
+        if let Some(action) = to_reset {
+            self.reset(action);
+            changed = true;
+        }
+
+        changed
+    }
+
+    /// Takes the shortcut pressed for `action`, returns whether it's rebound
+    fn capture(&mut self, ui: &egui::Ui, action: A) -> bool {
+        let pressed = ui.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                Event::Key {
+                    key,
+                    pressed: true,
+                    repeat: false,
+                    modifiers,
+                    ..
+                } => Some((*modifiers, *key)),
+                _ => None,
+            })
+        });
+        let Some((modifiers, key)) = pressed else {
+            return false;
+        };
+        ui.input_mut(|i| i.consume_key(modifiers, key));
+
+        if key == Key::Escape && modifiers == Modifiers::NONE {
+            self.rebinding = None;
+            self.conflict = None;
+            return false;
+        }
+
+        let shortcut = KeyboardShortcut::new(modifiers, key);
+        match self.conflict(action, shortcut) {
+            Some(other) => {
+                self.conflict = Some((shortcut, other));
+                false
+            }
+            None => {
+                self.set(action, shortcut);
+                self.rebinding = None;
+                self.conflict = None;
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+    enum Action {
+        Save,
+        Open,
+    }
+
+    fn map() -> ShortcutMap<Action> {
+        let mut map = ShortcutMap::default();
+        map.register(
+            Action::Save,
+            "Save",
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::S),
+        )
+        .register(
+            Action::Open,
+            "Open",
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::O),
+        );
+        map
+    }
+
+    #[test]
+    fn rebound_shortcuts_and_conflicts() {
+        let mut map = map();
+        let ctrl_o = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
+        let alt_s = KeyboardShortcut::new(Modifiers::ALT, Key::S);
+
+        assert_eq!(map.conflict(Action::Save, ctrl_o), Some(Action::Open));
+        assert_eq!(map.conflict(Action::Open, ctrl_o), None);
+
+        map.set(Action::Save, alt_s);
+        assert_eq!(map.get(Action::Save), Some(alt_s));
+        assert!(map.is_rebound(Action::Save));
+
+        // rebinding to the default is the same as resetting
+        map.set(
+            Action::Save,
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::S),
+        );
+        assert!(!map.is_rebound(Action::Save));
+    }
+
+    #[test]
+    fn shortcuts_pressed_along_with_each_other_conflict() {
+        let mut map = map();
+        let ctrl_shift_o = KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::O);
+        let alt_o = KeyboardShortcut::new(Modifiers::ALT, Key::O);
+        assert_eq!(map.conflict(Action::Save, ctrl_shift_o), Some(Action::Open));
+        assert_eq!(map.conflict(Action::Save, alt_o), None);
+
+        map.register_any_modifiers(Action::Open, "Open", Key::O);
+        assert_eq!(map.conflict(Action::Save, alt_o), Some(Action::Open));
+
+        // a rebound one is pressed with its own modifiers only
+        map.set(Action::Open, KeyboardShortcut::new(Modifiers::NONE, Key::P));
+        assert_eq!(map.conflict(Action::Save, alt_o), None);
+    }
+
+    #[test]
+    fn plain_defaults_are_pressed_with_any_modifiers() {
+        let mut map = map();
+        map.register_any_modifiers(Action::Open, "Open", Key::O);
+
+        let ctx = egui::Context::default();
+        let input = egui::RawInput {
+            modifiers: Modifiers::CTRL,
+            events: vec![Event::Key {
+                key: Key::O,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: Modifiers::CTRL,
+            }],
+            ..Default::default()
+        };
+
+        let mut pressed = (false, false);
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                pressed = (map.consume(ui, Action::Save), map.consume(ui, Action::Open));
+            });
+        });
+        assert_eq!(pressed, (false, true));
+    }
+
+    #[test]
+    fn rebinding_ends_once_the_editor_is_not_shown() {
+        let ctx = egui::Context::default();
+        let mut map = map();
+        map.rebinding = Some(Action::Save);
+        assert!(map.is_capturing(&ctx));
+
+        for _ in 0..2 {
+            let _ = ctx.run(Default::default(), |_| {});
+        }
+        assert!(!map.is_capturing(&ctx));
+    }
+
+    #[test]
+    fn only_rebound_shortcuts_are_saved() {
+        let mut map = map();
+        let alt_o = KeyboardShortcut::new(Modifiers::ALT, Key::O);
+        map.set(Action::Open, alt_o);
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert!(!json.contains("Save"));
+
+        // a loaded map gets its defaults registered again
+        let mut loaded: ShortcutMap<Action> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get(Action::Save), None);
+        loaded.register(
+            Action::Save,
+            "Save",
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::S),
+        );
+        loaded.register(
+            Action::Open,
+            "Open",
+            KeyboardShortcut::new(Modifiers::COMMAND, Key::O),
+        );
+        assert_eq!(loaded.get(Action::Open), Some(alt_o));
+        assert_eq!(
+            loaded.get(Action::Save),
+            Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::S))
+        );
+    }
+}
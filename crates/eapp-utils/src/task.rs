@@ -1,11 +1,52 @@
 use std::{
-    sync::mpsc::Sender,
+    sync::mpsc::{Receiver, Sender},
     thread::{JoinHandle, Result},
 };
 
+#[cfg(feature = "tokio")]
+pub mod async_task;
+
 pub struct Task<T> {
     thread_handle: JoinHandle<T>,
     cancel_sender: Sender<()>,
+    progress_receiver: Option<Receiver<Progress>>,
+    progress: Option<Progress>,
+}
+
+/// Incremental progress reported by the worker of [`Task::with_progress`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Progress {
+    pub done: u64,
+
+    /// `None` if the amount of work is unknown
+    pub total: Option<u64>,
+
+    /// What is being done now
+    pub msg: String,
+}
+
+impl Progress {
+    pub fn new(done: u64, total: Option<u64>) -> Self {
+        Self {
+            done,
+            total,
+            msg: String::new(),
+        }
+    }
+
+    pub fn msg(mut self, msg: impl Into<String>) -> Self {
+        self.msg = msg.into();
+        self
+    }
+
+    /// Fraction in `0.0..=1.0`, `None` if the total is unknown
+    pub fn fraction(&self) -> Option<f32> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.done as f64 / total as f64).clamp(0.0, 1.0) as f32),
+            None => None,
+        }
+    }
 }
 
 impl<T> Task<T> {
@@ -18,6 +59,24 @@ impl<T> Task<T> {
         Self {
             thread_handle: std::thread::spawn(f),
             cancel_sender,
+            progress_receiver: None,
+            progress: None,
+        }
+    }
+
+    /// Same as [`Task::new`] but `f` is given a sender to report its [`Progress`]
+    pub fn with_progress<F>(cancel_sender: Sender<()>, f: F) -> Self
+    where
+        F: FnOnce(Sender<Progress>) -> T,
+        F: Send + 'static,
+        T: Send + 'static,
+    {
+        let (progress_sender, progress_receiver) = std::sync::mpsc::channel();
+        Self {
+            thread_handle: std::thread::spawn(move || f(progress_sender)),
+            cancel_sender,
+            progress_receiver: Some(progress_receiver),
+            progress: None,
         }
     }
 
@@ -29,6 +88,17 @@ impl<T> Task<T> {
         let _ = self.cancel_sender.send(());
     }
 
+    /// The latest reported progress without blocking, `None` if nothing is reported yet
+    pub fn progress(&mut self) -> Option<&Progress> {
+        if let Some(receiver) = &self.progress_receiver
+            && let Some(progress) = receiver.try_iter().last()
+        {
+            self.progress = Some(progress);
+        }
+
+        self.progress.as_ref()
+    }
+
     pub fn get_result(self) -> Result<T> {
         self.thread_handle.join()
     }
@@ -0,0 +1,241 @@
+//! Background jobs on the tokio runtime, polled from the ui without blocking
+
+use eframe::egui;
+use std::{
+    future::Future,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender, error::SendError},
+    oneshot,
+};
+
+/// Asks a job to stop, the job checks it by itself
+#[derive(Default, Clone)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Reports progress of a job, each progress requests a repaint
+pub struct ProgressSender<P> {
+    sender: UnboundedSender<P>,
+    ctx: egui::Context,
+}
+
+impl<P> ProgressSender<P> {
+    /// Fails if the [`JobHandle`] is dropped
+    pub fn send(&self, progress: P) -> Result<(), SendError<P>> {
+        self.sender.send(progress)?;
+        self.ctx.request_repaint();
+        Ok(())
+    }
+}
+
+/// What [`JobHandle::poll`] gets
+#[derive(Debug, PartialEq)]
+pub enum JobEvent<P, T> {
+    Progress(P),
+
+    /// The output, after all the progress
+    Done(T),
+}
+
+/// A job spawned on the tokio runtime
+pub struct JobHandle<P, T> {
+    token: CancellationToken,
+    progress_receiver: UnboundedReceiver<P>,
+    output_receiver: Option<oneshot::Receiver<T>>,
+    output: Option<T>,
+}
+
+impl<P, T> JobHandle<P, T>
+where
+    P: Send + 'static,
+    T: Send + 'static,
+{
+    /// Spawns `f` on the current tokio runtime, a repaint is requested when it's done
+    pub fn spawn<F, Fut>(ctx: &egui::Context, f: F) -> Self
+    where
+        F: FnOnce(CancellationToken, ProgressSender<P>) -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        Self::spawn_with_token(ctx, CancellationToken::new(), f)
+    }
+
+    /// Same as [`JobHandle::spawn`], cancelled together with other jobs sharing `token`
+    pub fn spawn_with_token<F, Fut>(ctx: &egui::Context, token: CancellationToken, f: F) -> Self
+    where
+        F: FnOnce(CancellationToken, ProgressSender<P>) -> Fut,
+        Fut: Future<Output = T> + Send + 'static,
+    {
+        let (progress_sender, progress_receiver) = mpsc::unbounded_channel();
+        let (output_sender, output_receiver) = oneshot::channel();
+
+        let future = f(
+            token.clone(),
+            ProgressSender {
+                sender: progress_sender,
+                ctx: ctx.clone(),
+            },
+        );
+
+        let ctx = ctx.clone();
+        tokio::spawn(async move {
+            let _ = output_sender.send(future.await);
+            ctx.request_repaint();
+        });
+
+        Self {
+            token,
+            progress_receiver,
+            output_receiver: Some(output_receiver),
+            output: None,
+        }
+    }
+}
+
+impl<P, T> JobHandle<P, T> {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// Whether [`JobEvent::Done`] is got by [`JobHandle::poll`]
+    pub fn is_finished(&self) -> bool {
+        self.output_receiver.is_none() && self.output.is_none()
+    }
+
+    /// Gets the next event without blocking, `None` if nothing happened yet or it's finished
+    pub fn poll(&mut self) -> Option<JobEvent<P, T>> {
+        if let Ok(progress) = self.progress_receiver.try_recv() {
+            return Some(JobEvent::Progress(progress));
+        }
+
+        if let Some(receiver) = &mut self.output_receiver {
+            match receiver.try_recv() {
+                Ok(output) => {
+                    self.output = Some(output);
+                    self.output_receiver = None;
+
+                    // Progress sent right before the output may arrive after the check above
+                    if let Ok(progress) = self.progress_receiver.try_recv() {
+                        return Some(JobEvent::Progress(progress));
+                    }
+                }
+                Err(oneshot::error::TryRecvError::Empty) => return None,
+                // The job panicked
+                Err(oneshot::error::TryRecvError::Closed) => self.output_receiver = None,
+            }
+        }
+
+        if let Ok(progress) = self.progress_receiver.try_recv() {
+            return Some(JobEvent::Progress(progress));
+        }
+
+        self.output.take().map(JobEvent::Done)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    async fn poll_until_done<P, T>(job: &mut JobHandle<P, T>) -> Vec<JobEvent<P, T>> {
+        let mut events = Vec::new();
+        while !job.is_finished() {
+            match job.poll() {
+                Some(event) => events.push(event),
+                None => tokio::time::sleep(Duration::from_millis(1)).await,
+            }
+        }
+        events
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn progress_comes_before_output() {
+        let ctx = egui::Context::default();
+        let mut job = JobHandle::spawn(&ctx, |_, progress| async move {
+            for i in 0..100 {
+                progress.send(i).unwrap();
+            }
+            "done"
+        });
+
+        let events = poll_until_done(&mut job).await;
+        let mut expected: Vec<_> = (0..100).map(JobEvent::Progress).collect();
+        expected.push(JobEvent::Done("done"));
+        assert_eq!(events, expected);
+        assert!(job.poll().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn cancel_mid_stream() {
+        let ctx = egui::Context::default();
+        let (started_sender, started_receiver) = oneshot::channel();
+        let mut job = JobHandle::spawn(&ctx, |token, progress| async move {
+            let mut started_sender = Some(started_sender);
+            let mut sent = 0;
+            while !token.is_cancelled() {
+                progress.send(sent).unwrap();
+                sent += 1;
+                if let Some(sender) = started_sender.take() {
+                    let _ = sender.send(());
+                }
+                tokio::time::sleep(Duration::from_millis(1)).await;
+            }
+            sent
+        });
+
+        started_receiver.await.unwrap();
+        job.cancel();
+
+        let events = poll_until_done(&mut job).await;
+        let Some((JobEvent::Done(sent), progress)) = events.split_last() else {
+            panic!("job is not done");
+        };
+        assert!(*sent >= 1);
+        assert_eq!(progress.len(), *sent);
+        assert!(
+            progress
+                .iter()
+                .enumerate()
+                .all(|(i, event)| *event == JobEvent::Progress(i))
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shared_token_cancels_later_jobs() {
+        let ctx = egui::Context::default();
+        let first = JobHandle::spawn(&ctx, |_, _: ProgressSender<()>| async {});
+        first.cancel();
+
+        let mut second = JobHandle::spawn_with_token(
+            &ctx,
+            first.token().clone(),
+            |token, _: ProgressSender<()>| async move { token.is_cancelled() },
+        );
+
+        assert_eq!(poll_until_done(&mut second).await, [JobEvent::Done(true)]);
+    }
+}
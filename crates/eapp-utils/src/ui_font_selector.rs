@@ -14,25 +14,52 @@ use crate::{
 };
 
 #[derive(Serialize, Deserialize)]
-#[serde(default)]
+#[serde(from = "SavedUiFontSelector")]
 pub struct UiFontSelector {
     pub font_path: String,
+
+    /// Scale of the default size of all text styles
+    pub scale: f32,
+
+    /// Sizes that override the scaled default size
     pub text_style: BTreeMap<TextStyle, f32>,
 }
 
 impl Default for UiFontSelector {
     fn default() -> Self {
-        use crate::egui::TextStyle::*;
-
         Self {
             font_path: String::default(),
-            text_style: BTreeMap::from([
-                (Heading, 18.0),
-                (Body, 16.0),
-                (Monospace, 16.0),
-                (Button, 16.0),
-                (Small, 12.0),
-            ]),
+            scale: 1.0,
+            text_style: BTreeMap::new(),
+        }
+    }
+}
+
+/// What's saved, older versions have no scale and save every size
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SavedUiFontSelector {
+    font_path: String,
+    scale: Option<f32>,
+    text_style: BTreeMap<TextStyle, f32>,
+}
+
+impl From<SavedUiFontSelector> for UiFontSelector {
+    fn from(saved: SavedUiFontSelector) -> Self {
+        let mut text_style = saved.text_style;
+        // sizes saved by older versions are overrides only if they were changed
+        if saved.scale.is_none() {
+            text_style.retain(|style, size| {
+                !UiFontSelector::TEXT_STYLES
+                    .iter()
+                    .any(|(s, default)| s == style && default == size)
+            });
+        }
+
+        Self {
+            font_path: saved.font_path,
+            scale: saved.scale.unwrap_or(1.0),
+            text_style,
         }
     }
 }
@@ -40,6 +67,28 @@ impl Default for UiFontSelector {
 impl UiFontSelector {
     pub const KEY: &str = "ui_font_selector_state";
 
+    const TEXT_STYLES: [(TextStyle, f32); 5] = [
+        (TextStyle::Heading, 18.0),
+        (TextStyle::Body, 16.0),
+        (TextStyle::Monospace, 16.0),
+        (TextStyle::Button, 16.0),
+        (TextStyle::Small, 12.0),
+    ];
+
+    /// The size `style` is shown in, `None` for styles not handled by the selector
+    pub fn size_of(&self, style: &TextStyle) -> Option<f32> {
+        let default = Self::TEXT_STYLES
+            .iter()
+            .find(|(s, _)| s == style)
+            .map(|(_, size)| *size)?;
+        Some(
+            self.text_style
+                .get(style)
+                .copied()
+                .unwrap_or(default * self.scale),
+        )
+    }
+
     pub fn insert_font(&self, mut fonts: FontDefinitions) -> FontDefinitions {
         if let Ok(data) = std::fs::read(&self.font_path) {
             let name = "ui_font_selector_font".to_string();
@@ -61,7 +110,7 @@ impl UiFontSelector {
     pub fn apply_text_style(&self, ctx: &egui::Context) {
         ctx.style_mut(|style| {
             for (style, font_id) in style.text_styles.iter_mut() {
-                if let Some(&size) = self.text_style.get(style) {
+                if let Some(size) = self.size_of(style) {
                     *font_id = FontId::proportional(size);
                 }
             }
@@ -81,9 +130,36 @@ impl UiFontSelector {
                     .spacing([20.0, 4.0])
                     .striped(true)
                     .show(ui, |ui| {
-                        for (style, size) in &mut self.text_style {
+                        ui.label("Scale");
+                        ui.add(egui::Slider::new(&mut self.scale, 0.5..=2.0));
+                        ui.end_row();
+
+                        for (style, _) in Self::TEXT_STYLES {
                             ui.label(style.to_string());
-                            ui.add(egui::Slider::new(size, 8.0..=36.0));
+                            ui.horizontal(|ui| {
+                                let scaled = self.size_of(&style).unwrap_or_default();
+                                let mut overridden = self.text_style.contains_key(&style);
+                                if ui
+                                    .checkbox(&mut overridden, "")
+                                    .on_hover_text("Override the scaled size")
+                                    .changed()
+                                {
+                                    if overridden {
+                                        self.text_style.insert(style.clone(), scaled);
+                                    } else {
+                                        self.text_style.remove(&style);
+                                    }
+                                }
+
+                                match self.text_style.get_mut(&style) {
+                                    Some(size) => {
+                                        ui.add(egui::Slider::new(size, 8.0..=36.0));
+                                    }
+                                    None => {
+                                        ui.weak(format!("{scaled:.1}"));
+                                    }
+                                }
+                            });
                             ui.end_row();
                         }
                     });
@@ -130,7 +206,8 @@ impl UiFontSelector {
                         .on_hover_text("Reset text style")
                         .clicked()
                     {
-                        self.text_style = Self::default().text_style;
+                        self.scale = 1.0;
+                        self.text_style.clear();
                     }
                 });
             });
@@ -138,3 +215,36 @@ impl UiFontSelector {
         should_rebuild_fonts
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_take_precedence_over_scale() {
+        let mut selector = UiFontSelector {
+            scale: 1.5,
+            ..Default::default()
+        };
+        selector.text_style.insert(TextStyle::Button, 13.0);
+
+        assert_eq!(selector.size_of(&TextStyle::Body), Some(24.0));
+        assert_eq!(selector.size_of(&TextStyle::Button), Some(13.0));
+        assert_eq!(selector.size_of(&TextStyle::Name("custom".into())), None);
+    }
+
+    #[test]
+    fn unchanged_sizes_of_older_versions_are_not_overrides() {
+        let saved = SavedUiFontSelector {
+            text_style: BTreeMap::from([(TextStyle::Body, 16.0), (TextStyle::Small, 10.0)]),
+            ..Default::default()
+        };
+        let selector = UiFontSelector::from(saved);
+
+        assert_eq!(selector.scale, 1.0);
+        assert_eq!(
+            selector.text_style,
+            BTreeMap::from([(TextStyle::Small, 10.0)])
+        );
+    }
+}
@@ -7,105 +7,171 @@ use std::{
 };
 
 pub struct Waker {
-    sender: Sender<f64>,
+    sender: Sender<Instant>,
 }
 
+/// How requested deadlines are turned into repaints
 pub enum WakeType {
+    /// Repaints at every deadline
     Independent,
+
+    /// Only repaints once at the latest pending deadline, each request postpones it
     WakeOnLongestDeadLine,
+
+    /// Only repaints once at the earliest pending deadline, later pending deadlines
+    /// are dropped with it
+    WakeOnEarliestDeadLine,
 }
 
-impl Waker {
-    pub fn new(ctx: egui::Context, wake_type: WakeType) -> Self {
-        let (sender, receiver) = std::sync::mpsc::channel();
+/// Pending deadlines of a [`Waker`]
+enum Schedule {
+    Independent(BinaryHeap<Reverse<Instant>>),
+    Longest(Option<Instant>),
+    Earliest(Option<Instant>),
+}
 
+impl Schedule {
+    fn new(wake_type: WakeType) -> Self {
         match wake_type {
-            WakeType::Independent => std::thread::spawn(move || {
-                let mut heap = BinaryHeap::<Reverse<Instant>>::new();
+            WakeType::Independent => Schedule::Independent(BinaryHeap::new()),
+            WakeType::WakeOnLongestDeadLine => Schedule::Longest(None),
+            WakeType::WakeOnEarliestDeadLine => Schedule::Earliest(None),
+        }
+    }
 
-                loop {
-                    while let Ok(secs) = receiver.try_recv() {
-                        let deadline = Instant::now() + Duration::from_secs_f64(secs);
-                        heap.push(Reverse(deadline));
-                    }
+    fn push(&mut self, deadline: Instant) {
+        match self {
+            Schedule::Independent(heap) => heap.push(Reverse(deadline)),
+            Schedule::Longest(longest) => {
+                if longest.is_none_or(|d| deadline > d) {
+                    *longest = Some(deadline);
+                }
+            }
+            Schedule::Earliest(earliest) => {
+                if earliest.is_none_or(|d| deadline < d) {
+                    *earliest = Some(deadline);
+                }
+            }
+        }
+    }
 
-                    let now = Instant::now();
-                    let mut need_repaint = false;
+    /// When the next repaint is due
+    fn next_deadline(&self) -> Option<Instant> {
+        match self {
+            Schedule::Independent(heap) => heap.peek().map(|Reverse(deadline)| *deadline),
+            Schedule::Longest(deadline) | Schedule::Earliest(deadline) => *deadline,
+        }
+    }
 
-                    while let Some(Reverse(deadline)) = heap.peek() {
-                        if *deadline <= now {
-                            heap.pop();
-                            need_repaint = true;
-                        } else {
-                            break;
-                        }
-                    }
+    /// Removes the deadlines reached at `now`, returns whether to repaint
+    fn take_due(&mut self, now: Instant) -> bool {
+        match self {
+            Schedule::Independent(heap) => {
+                let mut due = false;
+                while heap
+                    .peek()
+                    .is_some_and(|Reverse(deadline)| *deadline <= now)
+                {
+                    heap.pop();
+                    due = true;
+                }
+                due
+            }
+            Schedule::Longest(deadline) | Schedule::Earliest(deadline) => {
+                deadline.take_if(|deadline| *deadline <= now).is_some()
+            }
+        }
+    }
+}
 
-                    if need_repaint {
-                        ctx.request_repaint();
-                    }
+impl Waker {
+    pub fn new(ctx: egui::Context, wake_type: WakeType) -> Self {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut schedule = Schedule::new(wake_type);
 
-                    let sleep_duration = heap
-                        .peek()
-                        .map(|Reverse(deadline)| deadline.saturating_duration_since(now));
-
-                    match sleep_duration {
-                        Some(duration) => match receiver.recv_timeout(duration) {
-                            Ok(secs) => {
-                                let deadline = Instant::now() + Duration::from_secs_f64(secs);
-                                heap.push(Reverse(deadline));
-                            }
-                            Err(RecvTimeoutError::Disconnected) => break,
-                            Err(RecvTimeoutError::Timeout) => {}
-                        },
-                        None => match receiver.recv() {
-                            Ok(secs) => {
-                                let deadline = Instant::now() + Duration::from_secs_f64(secs);
-                                heap.push(Reverse(deadline));
-                            }
-                            Err(_) => break,
-                        },
+        std::thread::spawn(move || {
+            loop {
+                let received = match schedule.next_deadline() {
+                    Some(deadline) => {
+                        receiver.recv_timeout(deadline.saturating_duration_since(Instant::now()))
                     }
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+
+                match received {
+                    Ok(deadline) => schedule.push(deadline),
+                    Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => {}
                 }
-            }),
-            WakeType::WakeOnLongestDeadLine => std::thread::spawn(move || {
-                let mut longest_deadline: Option<Instant> = None;
-
-                loop {
-                    while let Ok(secs) = receiver.try_recv() {
-                        let deadline = Instant::now() + Duration::from_secs_f64(secs);
-                        if longest_deadline.is_none_or(|d| deadline > d) {
-                            longest_deadline = Some(deadline);
-                        }
-                    }
 
-                    let sleep_duration = longest_deadline
-                        .map(|d| d.saturating_duration_since(Instant::now()))
-                        .unwrap_or(Duration::from_secs(3600));
-
-                    match receiver.recv_timeout(sleep_duration) {
-                        Ok(secs) => {
-                            let deadline = Instant::now() + Duration::from_secs_f64(secs);
-                            if longest_deadline.is_none_or(|d| deadline > d) {
-                                longest_deadline = Some(deadline);
-                            }
-                        }
-                        Err(RecvTimeoutError::Disconnected) => break,
-                        Err(RecvTimeoutError::Timeout) => {
-                            if longest_deadline.is_some() {
-                                ctx.request_repaint();
-                                longest_deadline = None;
-                            }
-                        }
-                    }
+                if schedule.take_due(Instant::now()) {
+                    ctx.request_repaint();
                 }
-            }),
-        };
+            }
+        });
 
         Self { sender }
     }
 
     pub fn request_repaint_after_secs(&self, secs: f64) {
-        let _ = self.sender.send(secs);
+        self.wake_at(Instant::now() + Duration::from_secs_f64(secs));
+    }
+
+    /// Requests a repaint at `deadline`, coalesced with other pending requests
+    /// according to the [`WakeType`]
+    pub fn wake_at(&self, deadline: Instant) {
+        let _ = self.sender.send(deadline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn earliest_coalesces_to_earliest_deadline() {
+        let now = Instant::now();
+        let mut schedule = Schedule::new(WakeType::WakeOnEarliestDeadLine);
+        schedule.push(now + secs(3));
+        schedule.push(now + secs(1));
+        schedule.push(now + secs(2));
+
+        assert_eq!(schedule.next_deadline(), Some(now + secs(1)));
+        assert!(!schedule.take_due(now));
+        assert!(schedule.take_due(now + secs(1)));
+        assert_eq!(schedule.next_deadline(), None);
+    }
+
+    #[test]
+    fn longest_coalesces_to_latest_deadline() {
+        let now = Instant::now();
+        let mut schedule = Schedule::new(WakeType::WakeOnLongestDeadLine);
+        schedule.push(now + secs(1));
+        schedule.push(now + secs(3));
+        schedule.push(now + secs(2));
+
+        assert_eq!(schedule.next_deadline(), Some(now + secs(3)));
+        assert!(!schedule.take_due(now + secs(2)));
+        assert!(schedule.take_due(now + secs(3)));
+        assert_eq!(schedule.next_deadline(), None);
+    }
+
+    #[test]
+    fn independent_repaints_at_every_deadline() {
+        let now = Instant::now();
+        let mut schedule = Schedule::new(WakeType::Independent);
+        schedule.push(now + secs(2));
+        schedule.push(now + secs(1));
+        schedule.push(now + secs(3));
+
+        assert_eq!(schedule.next_deadline(), Some(now + secs(1)));
+        assert!(schedule.take_due(now + secs(2)));
+        assert_eq!(schedule.next_deadline(), Some(now + secs(3)));
+        assert!(schedule.take_due(now + secs(3)));
+        assert_eq!(schedule.next_deadline(), None);
     }
 }
@@ -0,0 +1,302 @@
+//! ColorButton widget impl
+
+use eframe::egui::{
+    self, Color32, Id, Key, Popup, PopupCloseBehavior, Response, Sense, Stroke, TextEdit, Ui,
+    Widget,
+    color_picker::{Alpha, color_picker_color32, show_color_at},
+};
+use std::path::PathBuf;
+
+/// Most colors kept in the recent colors
+pub const MAX_RECENT: usize = 12;
+
+/// A swatch button opening a color picker popup
+///
+/// Features:
+/// - Hue/saturation square, with an alpha slider unless disabled
+/// - Hex input of `#RGB`, `#RRGGBB` or `#RRGGBBAA`, an invalid one keeps the color
+/// - Row of recent colors, shared by all apps and kept across restarts
+///
+/// The response is changed when the color changes
+///
+/// # Example
+/// ```ignore
+/// ui.add(ColorButton::new("sub_color", &mut color).alpha(false));
+/// ```
+pub struct ColorButton<'a> {
+    id_salt: Id,
+    color: &'a mut Color32,
+    alpha: bool,
+}
+
+#[derive(Clone, Default)]
+struct PickerState {
+    /// Color when the popup is opened, the color is added to the recent ones if it's changed
+    open_color: Option<Color32>,
+    hex: String,
+    /// The hex input can't be parsed, the color is kept as it was
+    hex_invalid: bool,
+}
+
+impl<'a> ColorButton<'a> {
+    pub fn new(id_salt: impl std::hash::Hash, color: &'a mut Color32) -> Self {
+        Self {
+            id_salt: Id::new(id_salt),
+            color,
+            alpha: true,
+        }
+    }
+
+    /// Whether the alpha can be changed, it's opaque if not
+    #[inline]
+    pub fn alpha(mut self, alpha: bool) -> Self {
+        self.alpha = alpha;
+        self
+    }
+}
+
+impl Widget for ColorButton<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self {
+            id_salt,
+            color,
+            alpha,
+        } = self;
+
+        let id = ui.make_persistent_id(id_salt);
+        let popup_id = id.with("popup");
+
+        let size = egui::vec2(
+            ui.spacing().interact_size.y * 1.5,
+            ui.spacing().interact_size.y,
+        );
+        let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+        let visuals = ui.style().interact(&response);
+        show_color_at(ui.painter(), *color, rect);
+        ui.painter().rect_stroke(
+            rect,
+            visuals.corner_radius,
+            visuals.fg_stroke,
+            egui::StrokeKind::Inside,
+        );
+        let mut response = response.on_hover_text(to_hex(*color));
+
+        let mut state = ui
+            .data(|data| data.get_temp::<PickerState>(id))
+            .unwrap_or_default();
+        let old_color = *color;
+
+        let inner = Popup::from_toggle_button_response(&response)
+            .id(popup_id)
+            .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+            .show(|ui| {
+                if state.open_color.is_none() {
+                    state.open_color = Some(*color);
+                }
+
+                let alpha_mode = if alpha {
+                    Alpha::OnlyBlend
+                } else {
+                    Alpha::Opaque
+                };
+                color_picker_color32(ui, color, alpha_mode);
+
+                ui.horizontal(|ui| {
+                    ui.label("Hex");
+                    ui.scope(|ui| {
+                        if state.hex_invalid {
+                            let stroke = Stroke::new(1.0, ui.visuals().error_fg_color);
+                            let visuals = ui.visuals_mut();
+                            visuals.widgets.inactive.bg_stroke = stroke;
+                            visuals.widgets.hovered.bg_stroke = stroke;
+                            visuals.selection.stroke = stroke;
+                        }
+
+                        let edit = ui.add(TextEdit::singleline(&mut state.hex).desired_width(90.0));
+                        if edit.changed() {
+                            match parse_hex(&state.hex) {
+                                Some(parsed) => {
+                                    *color = if alpha { parsed } else { opaque(parsed) };
+                                    state.hex_invalid = false;
+                                }
+                                None => state.hex_invalid = true,
+                            }
+                        } else if !edit.has_focus() {
+                            state.hex = to_hex(*color);
+                            state.hex_invalid = false;
+                        }
+
+                        if edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                            Popup::close_id(ui.ctx(), popup_id);
+                        }
+                    });
+                });
+
+                let recent = recent_colors(ui.ctx());
+                if !recent.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 2.0;
+                        let size = egui::Vec2::splat(ui.spacing().interact_size.y * 0.8);
+                        for recent in recent {
+                            let (rect, res) = ui.allocate_exact_size(size, Sense::click());
+                            show_color_at(ui.painter(), recent, rect);
+                            if res.on_hover_text(to_hex(recent)).clicked() {
+                                *color = if alpha { recent } else { opaque(recent) };
+                            }
+                        }
+                    });
+                }
+            });
+
+        if *color != old_color {
+            response.mark_changed();
+        }
+
+        if inner.is_none() {
+            if let Some(open_color) = state.open_color
+                && open_color != *color
+            {
+                add_recent_color(ui.ctx(), *color);
+            }
+            state = PickerState::default();
+        }
+        ui.data_mut(|data| data.insert_temp(id, state));
+
+        response
+    }
+}
+
+fn opaque(color: Color32) -> Color32 {
+    let [r, g, b, _] = color.to_srgba_unmultiplied();
+    Color32::from_rgb(r, g, b)
+}
+
+/// Parses `#RGB`, `#RRGGBB` or `#RRGGBBAA`, the `#` is optional
+pub fn parse_hex(text: &str) -> Option<Color32> {
+    let hex = text.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if !hex.is_ascii() {
+        return None;
+    }
+
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let [r, g, b, a] = match hex.len() {
+        3 => {
+            let mut rgb = [0; 3];
+            for (i, c) in hex.chars().enumerate() {
+                rgb[i] = c.to_digit(16)? as u8 * 17;
+            }
+            [rgb[0], rgb[1], rgb[2], 255]
+        }
+        6 => [byte(0)?, byte(2)?, byte(4)?, 255],
+        8 => [byte(0)?, byte(2)?, byte(4)?, byte(6)?],
+        _ => return None,
+    };
+
+    Some(Color32::from_rgba_unmultiplied(r, g, b, a))
+}
+
+/// `#RRGGBB`, or `#RRGGBBAA` if it's not opaque
+pub fn to_hex(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!("#{r:02X}{g:02X}{b:02X}")
+    } else {
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+/// The file of the recent colors, which is shared by all apps
+fn recent_colors_path() -> Option<PathBuf> {
+    Some(eframe::storage_dir("eapp-utils")?.join("recent_colors.txt"))
+}
+
+fn recent_colors_id() -> Id {
+    Id::new("eapp_utils_recent_colors")
+}
+
+/// The recent colors, the latest first, read from the disk once
+pub fn recent_colors(ctx: &egui::Context) -> Vec<Color32> {
+    if let Some(recent) = ctx.data(|data| data.get_temp(recent_colors_id())) {
+        return recent;
+    }
+
+    let recent: Vec<Color32> = recent_colors_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map(|text| {
+            text.lines()
+                .filter_map(parse_hex)
+                .take(MAX_RECENT)
+                .collect()
+        })
+        .unwrap_or_default();
+    ctx.data_mut(|data| data.insert_temp(recent_colors_id(), recent.clone()));
+    recent
+}
+
+/// Adds `color` to the recent colors and writes them to the disk
+pub fn add_recent_color(ctx: &egui::Context, color: Color32) {
+    let mut recent = recent_colors(ctx);
+    crate::push_recent(&mut recent, color, MAX_RECENT);
+    ctx.data_mut(|data| data.insert_temp(recent_colors_id(), recent.clone()));
+
+    let Some(path) = recent_colors_path() else {
+        return;
+    };
+    let text: Vec<_> = recent.into_iter().map(to_hex).collect();
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(&path, text.join("\n")));
+    if let Err(err) = result {
+        log::warn!("save recent colors to '{}' fails: {err}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_forms() {
+        assert_eq!(parse_hex("#fff"), Some(Color32::WHITE));
+        assert_eq!(
+            parse_hex("#1a2B3c"),
+            Some(Color32::from_rgb(0x1a, 0x2b, 0x3c))
+        );
+        assert_eq!(
+            parse_hex(" 1a2b3c80 "),
+            Some(Color32::from_rgba_unmultiplied(0x1a, 0x2b, 0x3c, 0x80))
+        );
+
+        for invalid in ["", "#", "#ff", "#fffff", "#ggg", "#12345g", "#ééé"] {
+            assert_eq!(parse_hex(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        let color = Color32::from_rgb(1, 2, 254);
+        assert_eq!(to_hex(color), "#0102FE");
+        assert_eq!(parse_hex(&to_hex(color)), Some(color));
+
+        let color = Color32::from_rgba_unmultiplied(255, 0, 0, 128);
+        assert_eq!(to_hex(color).len(), 9);
+        assert_eq!(parse_hex(&to_hex(color)), Some(color));
+    }
+
+    #[test]
+    fn recent_colors_are_deduplicated_and_bounded() {
+        let mut recent = Vec::new();
+        for i in 0..MAX_RECENT as u8 + 3 {
+            crate::push_recent(&mut recent, Color32::from_gray(i), MAX_RECENT);
+        }
+        assert_eq!(recent.len(), MAX_RECENT);
+        assert_eq!(recent[0], Color32::from_gray(MAX_RECENT as u8 + 2));
+
+        crate::push_recent(&mut recent, Color32::from_gray(10), MAX_RECENT);
+        assert_eq!(recent[0], Color32::from_gray(10));
+        assert_eq!(recent.len(), MAX_RECENT);
+        assert_eq!(recent.iter().filter(|c| **c == recent[0]).count(), 1);
+    }
+}
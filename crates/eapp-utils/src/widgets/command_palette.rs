@@ -0,0 +1,338 @@
+//! CommandPalette widget impl
+
+use eframe::egui::{
+    self, Id, Key, KeyboardShortcut, Modifiers, ScrollArea, TextEdit, TextFormat, text::LayoutJob,
+};
+use std::rc::Rc;
+
+/// What a command does, called with the app and the context
+pub type CommandAction<T> = Rc<dyn Fn(&mut T, &egui::Context)>;
+
+/// A command shown in [`CommandPalette`]
+pub struct Command<T> {
+    id: String,
+    title: String,
+    shortcut: Option<String>,
+    enabled: bool,
+    action: CommandAction<T>,
+}
+
+impl<T> Command<T> {
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        action: impl Fn(&mut T, &egui::Context) + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            shortcut: None,
+            enabled: true,
+            action: Rc::new(action),
+        }
+    }
+
+    /// Text shown on the right, e.g. from [`egui::Context::format_shortcut`]
+    pub fn shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// Disabled commands are not listed
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+}
+
+/// A searchable list of commands, opened by a shortcut
+///
+/// Features:
+/// - Commands are filtered by a fuzzy match of their title, best matches first
+/// - `Up`/`Down` to move, `Enter` to run, `Escape` to close
+///
+/// # Example
+/// ```ignore
+/// // once
+/// palette.register(Command::new("save", "Save", |app: &mut App, _| app.save()));
+///
+/// // each frame
+/// palette.set_enabled("save", !self.read_only);
+/// if let Some(action) = self.palette.show(ctx) {
+///     action(self, ctx);
+/// }
+/// ```
+pub struct CommandPalette<T> {
+    commands: Vec<Command<T>>,
+    shortcut: KeyboardShortcut,
+    open: bool,
+    filter: String,
+
+    /// Row of the filtered list run by `Enter`
+    cursor: usize,
+}
+
+impl<T> Default for CommandPalette<T> {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            shortcut: Self::DEFAULT_SHORTCUT,
+            open: false,
+            filter: String::new(),
+            cursor: 0,
+        }
+    }
+}
+
+impl<T> CommandPalette<T> {
+    pub const DEFAULT_SHORTCUT: KeyboardShortcut =
+        KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::P);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shortcut to open or close the palette
+    pub fn shortcut(mut self, shortcut: KeyboardShortcut) -> Self {
+        self.shortcut = shortcut;
+        self
+    }
+
+    /// Adds `command`, or replaces the one with the same id
+    pub fn register(&mut self, command: Command<T>) {
+        match self.commands.iter_mut().find(|c| c.id == command.id) {
+            Some(old) => *old = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    pub fn set_enabled(&mut self, id: &str, enabled: bool) {
+        if let Some(command) = self.commands.iter_mut().find(|c| c.id == id) {
+            command.enabled = enabled;
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+        self.filter.clear();
+        self.cursor = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Enabled commands matching the filter, best matches first,
+    /// with the char indices of the title that matched
+    fn matches(&self) -> Vec<(usize, Vec<usize>)> {
+        let mut matches: Vec<_> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| command.enabled)
+            .filter_map(|(idx, command)| {
+                fuzzy_match(&self.filter, &command.title)
+                    .map(|(score, positions)| (score, idx, positions))
+            })
+            .collect();
+
+        // Stable, so commands with the same score keep the registered order
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches
+            .into_iter()
+            .map(|(_, idx, positions)| (idx, positions))
+            .collect()
+    }
+
+    /// Shows the palette if it's open, returns the action of the chosen command
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<CommandAction<T>> {
+        if ctx.input_mut(|i| i.consume_shortcut(&self.shortcut)) {
+            if self.open {
+                self.close();
+            } else {
+                self.open();
+            }
+        }
+
+        if !self.open {
+            return None;
+        }
+
+        let matches = self.matches();
+        self.cursor = self.cursor.min(matches.len().saturating_sub(1));
+
+        let mut chosen = None;
+
+        let modal = egui::Modal::new(Id::new("eapp_utils_command_palette")).show(ctx, |ui| {
+            ui.set_width(420.0);
+
+            // Keys are taken before the text edit, which would move its cursor instead
+            let (up, down, enter) = ui.input_mut(|i| {
+                (
+                    i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                    i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                    i.consume_key(Modifiers::NONE, Key::Enter),
+                )
+            });
+
+            let mut moved = false;
+            if up && self.cursor > 0 {
+                self.cursor -= 1;
+                moved = true;
+            }
+            if down && self.cursor + 1 < matches.len() {
+                self.cursor += 1;
+                moved = true;
+            }
+
+            let edit = ui.add(
+                TextEdit::singleline(&mut self.filter)
+                    .hint_text("Type a command")
+                    .desired_width(f32::INFINITY),
+            );
+            edit.request_focus();
+            if edit.changed() {
+                self.cursor = 0;
+            }
+
+            if enter && let Some((idx, _)) = matches.get(self.cursor) {
+                chosen = Some(*idx);
+            }
+
+            ScrollArea::vertical()
+                .max_height(320.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
+                        for (row, (idx, positions)) in matches.iter().enumerate() {
+                            let command = &self.commands[*idx];
+                            let job = highlight(ui, &command.title, positions);
+
+                            let mut button = egui::Button::selectable(row == self.cursor, job);
+                            if let Some(shortcut) = &command.shortcut {
+                                button = button.right_text(egui::RichText::new(shortcut).weak());
+                            }
+
+                            let response = ui.add(button);
+                            if moved && row == self.cursor {
+                                response.scroll_to_me(None);
+                            }
+                            if response.clicked() {
+                                chosen = Some(*idx);
+                            }
+                        }
+
+                        if matches.is_empty() {
+                            ui.weak("No matches");
+                        }
+                    });
+                });
+        });
+
+        if modal.should_close() || chosen.is_some() {
+            self.close();
+        }
+
+        chosen.map(|idx| self.commands[idx].action.clone())
+    }
+}
+
+/// Title with the matched chars highlighted
+fn highlight(ui: &egui::Ui, title: &str, positions: &[usize]) -> LayoutJob {
+    let font_id = crate::get_body_font_id(ui);
+    let normal = TextFormat::simple(font_id.clone(), ui.visuals().text_color());
+    let matched = TextFormat {
+        color: ui.visuals().strong_text_color(),
+        underline: egui::Stroke::new(1.0, ui.visuals().strong_text_color()),
+        ..TextFormat::simple(font_id, ui.visuals().strong_text_color())
+    };
+
+    let mut job = LayoutJob::default();
+    let mut buf = [0; 4];
+    for (i, c) in title.chars().enumerate() {
+        let format = if positions.contains(&i) {
+            matched.clone()
+        } else {
+            normal.clone()
+        };
+        job.append(c.encode_utf8(&mut buf), 0.0, format);
+    }
+    job
+}
+
+/// Matches the chars of `pattern` in order in `text` ignoring case,
+/// returns the score (higher is better) and the char indices of `text` that matched
+///
+/// Consecutive matches and matches at the start of words score higher,
+/// an empty pattern matches everything
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<(i32, Vec<usize>)> {
+    let text: Vec<char> = text.chars().collect();
+    let mut positions = Vec::new();
+    let mut score = 0;
+    let mut next = 0;
+
+    for p in pattern.chars().filter(|c| !c.is_whitespace()) {
+        let p = p.to_lowercase().next().unwrap_or(p);
+        let offset = text[next..]
+            .iter()
+            .position(|c| c.to_lowercase().next().unwrap_or(*c) == p)?;
+        let idx = next + offset;
+
+        score += 1;
+        if positions.last().is_some_and(|&last| last + 1 == idx) {
+            score += 5;
+        }
+        if idx == 0 || !text[idx - 1].is_alphanumeric() {
+            score += 8;
+        }
+        score -= offset.min(3) as i32;
+
+        positions.push(idx);
+        next = idx + 1;
+    }
+
+    Some((score, positions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_match_is_ordered_and_case_insensitive() {
+        assert_eq!(fuzzy_match("sa", "Save As").unwrap().1, [0, 1]);
+        assert_eq!(fuzzy_match("SA", "save as").unwrap().1, [0, 1]);
+        assert!(fuzzy_match("as", "sa").is_none());
+        assert_eq!(fuzzy_match("", "anything"), Some((0, vec![])));
+    }
+
+    #[test]
+    fn word_starts_score_higher() {
+        let (word_starts, _) = fuzzy_match("nf", "New File").unwrap();
+        let (inner, _) = fuzzy_match("nf", "Open Info").unwrap();
+        assert!(word_starts > inner);
+    }
+
+    #[test]
+    fn best_match_first() {
+        let mut palette = CommandPalette::<()>::new();
+        palette.register(Command::new("reopen", "Reopen", |_, _| ()));
+        palette.register(Command::new("open", "Open", |_, _| ()));
+        palette.register(Command::new("save", "Save", |_, _| ()).enabled(false));
+        palette.filter = "ope".to_owned();
+
+        let ids: Vec<_> = palette
+            .matches()
+            .into_iter()
+            .map(|(idx, _)| palette.commands[idx].id.as_str())
+            .collect();
+        assert_eq!(ids, ["open", "reopen"]);
+
+        palette.filter.clear();
+        assert_eq!(palette.matches().len(), 2);
+    }
+}
@@ -0,0 +1,148 @@
+//! Confirm widget impl
+
+use eframe::egui::{self, Color32, Id, RichText};
+
+/// A question for [`Confirm`], answered by either of two buttons
+pub struct ConfirmRequest<T> {
+    message: String,
+    no: String,
+    yes: String,
+    dangerous: bool,
+    cancellable: bool,
+    callback: Box<dyn FnOnce(bool) -> T>,
+}
+
+impl<T> ConfirmRequest<T> {
+    /// Asks `message` with `No` and `Yes`, the callback gets whether `Yes` is clicked
+    pub fn new(message: impl Into<String>, callback: impl FnOnce(bool) -> T + 'static) -> Self {
+        Self {
+            message: message.into(),
+            no: "No".to_owned(),
+            yes: "Yes".to_owned(),
+            dangerous: false,
+            cancellable: false,
+            callback: Box::new(callback),
+        }
+    }
+
+    /// Replaces the text of `No` and `Yes`
+    pub fn buttons(mut self, no: impl Into<String>, yes: impl Into<String>) -> Self {
+        self.no = no.into();
+        self.yes = yes.into();
+        self
+    }
+
+    /// Paints `Yes` red, for deletes and such
+    pub fn dangerous(mut self) -> Self {
+        self.dangerous = true;
+        self
+    }
+
+    /// `Escape` or clicking outside answers `No`, otherwise a button must be clicked
+    pub fn cancellable(mut self) -> Self {
+        self.cancellable = true;
+        self
+    }
+}
+
+/// A modal asking one question at a time
+///
+/// # Example
+/// ```ignore
+/// // when asking
+/// self.confirm.ask("Discard changes?", move |yes| yes);
+///
+/// // each frame
+/// if self.confirm.show(ctx) == Some(true) {
+///     self.discard();
+/// }
+/// ```
+pub struct Confirm<T = ()> {
+    request: Option<ConfirmRequest<T>>,
+}
+
+impl<T> Default for Confirm<T> {
+    fn default() -> Self {
+        Self { request: None }
+    }
+}
+
+impl<T> Confirm<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.request.is_some()
+    }
+
+    /// Asks `request`, replacing the unanswered one if any
+    pub fn open(&mut self, request: ConfirmRequest<T>) {
+        self.request = Some(request);
+    }
+
+    /// Same as [`Confirm::open`] with [`ConfirmRequest::new`]
+    pub fn ask(&mut self, message: impl Into<String>, callback: impl FnOnce(bool) -> T + 'static) {
+        self.open(ConfirmRequest::new(message, callback));
+    }
+
+    /// Drops the question without calling its callback
+    pub fn close(&mut self) {
+        self.request = None;
+    }
+
+    /// Shows the question if any, returns what the callback returns once it's answered
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<T> {
+        let request = self.request.as_ref()?;
+
+        let mut answer = None;
+        let modal = egui::Modal::new(Id::new("eapp_utils_confirm")).show(ctx, |ui| {
+            ui.label(&request.message);
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                if ui.button(&request.no).clicked() {
+                    answer = Some(false);
+                }
+
+                let yes = if request.dangerous {
+                    egui::Button::new(RichText::new(&request.yes).color(Color32::WHITE))
+                        .fill(ui.visuals().error_fg_color)
+                } else {
+                    egui::Button::new(&request.yes)
+                };
+                if ui.add(yes).clicked() {
+                    answer = Some(true);
+                }
+            });
+        });
+
+        if answer.is_none() && request.cancellable && modal.should_close() {
+            answer = Some(false);
+        }
+
+        let answer = answer?;
+        let request = self.request.take().unwrap();
+        Some((request.callback)(answer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unanswered_question_stays_open() {
+        let ctx = egui::Context::default();
+        let mut confirm = Confirm::new();
+        confirm.open(ConfirmRequest::new("Delete?", |yes| yes).dangerous());
+
+        let _ = ctx.run(Default::default(), |ctx| {
+            assert_eq!(confirm.show(ctx), None);
+        });
+        assert!(confirm.is_open());
+
+        confirm.close();
+        assert!(!confirm.is_open());
+    }
+}
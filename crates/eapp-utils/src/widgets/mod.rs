@@ -1,2 +1,9 @@
+pub mod color_picker;
+pub mod command_palette;
+pub mod confirm;
+pub mod nav_list;
 pub mod progress_bar;
+pub mod searchable_combo;
 pub mod simple_widgets;
+pub mod toast;
+pub mod unit_drag;
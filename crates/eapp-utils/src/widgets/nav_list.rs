@@ -0,0 +1,201 @@
+//! NavList widget impl
+
+use eframe::egui::{self, Event, Id, Key, Modifiers, ScrollArea, Ui};
+
+/// A list of selectable rows, which can be navigated by keyboard
+///
+/// Keys are only handled while no widget has the focus:
+/// - `Up`/`Down` to move, wrapping around the ends
+/// - `Home`/`End` to go to the first/last row
+/// - A letter jumps to the next row starting with it
+///
+/// # Example
+/// ```ignore
+/// let output = NavList::new("scripts", names.len(), |i| names[i].clone()).show(ui, &mut cur);
+/// if output.double_clicked.is_some() {
+///     self.run(cur);
+/// }
+/// ```
+pub struct NavList<'a> {
+    id_salt: Id,
+
+    /// Number of rows
+    len: usize,
+
+    /// Text of row `i`
+    get: RowText<'a>,
+
+    keyboard: bool,
+}
+
+type RowText<'a> = Box<dyn Fn(usize) -> String + 'a>;
+
+/// What happened to a [`NavList`] this frame
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NavListOutput {
+    /// Selection is moved by either the keyboard or a click
+    pub changed: bool,
+
+    pub clicked: Option<usize>,
+    pub double_clicked: Option<usize>,
+    pub secondary_clicked: Option<usize>,
+}
+
+impl<'a> NavList<'a> {
+    pub fn new(
+        id_salt: impl std::hash::Hash,
+        len: usize,
+        get: impl Fn(usize) -> String + 'a,
+    ) -> Self {
+        Self {
+            id_salt: Id::new(id_salt),
+            len,
+            get: Box::new(get),
+            keyboard: true,
+        }
+    }
+
+    /// Whether keys are handled, `true` by default
+    ///
+    /// Turn it off when another list of the window takes the keys
+    pub fn keyboard(mut self, keyboard: bool) -> Self {
+        self.keyboard = keyboard;
+        self
+    }
+
+    /// Shows the rows in a vertical scroll area, `selected` is clamped to the rows
+    pub fn show(self, ui: &mut Ui, selected: &mut usize) -> NavListOutput {
+        let mut output = NavListOutput::default();
+
+        if self.len == 0 {
+            *selected = 0;
+            return output;
+        }
+        *selected = (*selected).min(self.len - 1);
+
+        if let Some(idx) = self.navigate(ui, *selected)
+            && idx != *selected
+        {
+            *selected = idx;
+            output.changed = true;
+        }
+        let scroll_to_selected = output.changed;
+
+        ScrollArea::vertical()
+            .id_salt(ui.make_persistent_id(self.id_salt))
+            .show(ui, |ui| {
+                ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
+                    for idx in 0..self.len {
+                        let response = ui.selectable_label(idx == *selected, (self.get)(idx));
+
+                        if idx == *selected && scroll_to_selected {
+                            response.scroll_to_me(None);
+                        }
+
+                        if response.clicked() || response.secondary_clicked() {
+                            output.changed |= idx != *selected;
+                            *selected = idx;
+                        }
+                        if response.clicked() {
+                            output.clicked = Some(idx);
+                        }
+                        if response.double_clicked() {
+                            output.double_clicked = Some(idx);
+                        }
+                        if response.secondary_clicked() {
+                            output.secondary_clicked = Some(idx);
+                        }
+                    }
+                });
+            });
+
+        output
+    }
+
+    /// The row to select by the keys pressed this frame, `None` if keys are not handled now,
+    /// for lists which lay out their rows themselves
+    pub fn navigate(&self, ui: &mut Ui, selected: usize) -> Option<usize> {
+        if !self.keyboard || self.len == 0 || ui.memory(|mem| mem.focused().is_some()) {
+            return None;
+        }
+
+        self.handle_keys(ui, selected.min(self.len - 1))
+    }
+
+    /// The row to select by the keys pressed this frame
+    fn handle_keys(&self, ui: &mut Ui, selected: usize) -> Option<usize> {
+        let (up, down, home, end, letter) = ui.input_mut(|i| {
+            let letter = i.events.iter().find_map(|event| match event {
+                Event::Text(text) => text.chars().next().filter(|c| c.is_alphanumeric()),
+                _ => None,
+            });
+
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::NONE, Key::Home),
+                i.consume_key(Modifiers::NONE, Key::End),
+                letter,
+            )
+        });
+
+        if up {
+            Some(step(selected, self.len, -1))
+        } else if down {
+            Some(step(selected, self.len, 1))
+        } else if home {
+            Some(0)
+        } else if end {
+            Some(self.len - 1)
+        } else {
+            letter.and_then(|letter| find_by_letter(selected, self.len, letter, &self.get))
+        }
+    }
+}
+
+/// Moves `selected` by `delta` rows, wrapping around the ends
+fn step(selected: usize, len: usize, delta: isize) -> usize {
+    (selected as isize + delta).rem_euclid(len as isize) as usize
+}
+
+/// The next row after `selected` whose text starts with `letter`, ignoring case
+fn find_by_letter(
+    selected: usize,
+    len: usize,
+    letter: char,
+    get: impl Fn(usize) -> String,
+) -> Option<usize> {
+    let letter = letter.to_lowercase().next()?;
+
+    (1..=len).map(|i| (selected + i) % len).find(|&idx| {
+        get(idx)
+            .chars()
+            .next()
+            .and_then(|c| c.to_lowercase().next())
+            == Some(letter)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn step_wraps_around() {
+        assert_eq!(step(0, 3, -1), 2);
+        assert_eq!(step(2, 3, 1), 0);
+        assert_eq!(step(1, 3, 1), 2);
+    }
+
+    #[test]
+    fn letter_jumps_to_the_next_match() {
+        let names = ["apple", "Banana", "avocado", "cherry"];
+        let get = |i: usize| names[i].to_owned();
+
+        assert_eq!(find_by_letter(0, names.len(), 'a', get), Some(2));
+        assert_eq!(find_by_letter(2, names.len(), 'A', get), Some(0));
+        assert_eq!(find_by_letter(0, names.len(), 'b', get), Some(1));
+        assert_eq!(find_by_letter(3, names.len(), 'c', get), Some(3));
+        assert_eq!(find_by_letter(0, names.len(), 'z', get), None);
+    }
+}
@@ -1,10 +1,32 @@
 //! ProgressBar widget impl
 
 use eframe::egui::{
-    Align2, Area, Color32, CornerRadius, Order, Rect, Response, Sense, Stroke, Ui, Widget, pos2,
-    vec2,
+    Align2, Area, Color32, CornerRadius, Order, Pos2, Rect, Response, Sense, Stroke, Ui, Widget,
+    pos2, vec2,
 };
 
+/// Direction the progress bar grows in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Orientation {
+    /// Left to right
+    #[default]
+    Horizontal,
+
+    /// Bottom to top
+    Vertical,
+}
+
+impl Orientation {
+    /// Converts a position within the progress bar to a value,
+    /// see [`value_from_x`] and [`value_from_y`]
+    pub fn value_from_pos(self, max: f64, rect: Rect, pos: Pos2) -> f64 {
+        match self {
+            Orientation::Horizontal => value_from_x(max, rect, pos.x as _),
+            Orientation::Vertical => value_from_y(max, rect, pos.y as _),
+        }
+    }
+}
+
 /// A customizable progress bar widget for egui
 ///
 /// Features:
@@ -12,6 +34,7 @@ use eframe::egui::{
 /// - Interactive dragging for value adjustment
 /// - Hover preview with custom content
 /// - Customizable colors and styling
+/// - Horizontal or vertical, see [`ProgressBar::orientation`]
 ///
 /// # Example
 /// ```
@@ -32,9 +55,11 @@ pub struct ProgressBar<'a> {
     /// Maximum value (represents 100% progress)
     max: f64,
 
-    /// Height of the progress bar track
+    /// Height of the progress bar track, or its width if vertical
     height: Option<f32>,
 
+    orientation: Orientation,
+
     /// Background color of the track
     background_color: Option<Color32>,
 
@@ -67,6 +92,7 @@ impl<'a> ProgressBar<'a> {
             value,
             max,
             height: None,
+            orientation: Orientation::Horizontal,
             background_color: None,
             fill_color: None,
             active_color: None,
@@ -82,6 +108,13 @@ impl<'a> ProgressBar<'a> {
         self
     }
 
+    /// Vertical bars take the available height and fill from the bottom,
+    /// the preview is shown on their left
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
     /// Sets the background color of the track
     pub fn background_color(mut self, color: impl Into<Color32>) -> Self {
         self.background_color = Some(color.into());
@@ -127,7 +160,10 @@ impl<'a> Widget for ProgressBar<'a> {
     fn ui(mut self, ui: &mut Ui) -> Response {
         // Allocate space for the progress bar
         let height = self.height.unwrap_or(crate::get_body_text_size(ui));
-        let desired_size = vec2(ui.available_width(), height);
+        let desired_size = match self.orientation {
+            Orientation::Horizontal => vec2(ui.available_width(), height),
+            Orientation::Vertical => vec2(height, ui.available_height()),
+        };
         let (_, response) = ui.allocate_exact_size(desired_size, Sense::drag());
         let rect = response.rect;
 
@@ -143,31 +179,28 @@ impl<'a> Widget for ProgressBar<'a> {
                 .background_color
                 .unwrap_or(ui.visuals().widgets.active.bg_fill);
 
+            let (start, end) = match self.orientation {
+                Orientation::Horizontal => (rect.left_center(), rect.right_center()),
+                Orientation::Vertical => (rect.center_bottom(), rect.center_top()),
+            };
+
             // Draw background track
-            ui.painter().line_segment(
-                [rect.left_center(), rect.right_center()],
-                Stroke::new(3.0, background_color),
-            );
+            ui.painter()
+                .line_segment([start, end], Stroke::new(3.0, background_color));
 
             // Draw filled portion if we have valid values
             if self.max > 0.0 {
                 let progress_fraction = (self.value / self.max) as f32;
-                let fill_width = progress_fraction * rect.width();
-                let fill_end = rect.left() + fill_width;
+                let fill_end = start.lerp(end, progress_fraction);
 
                 // Draw filled progress
-                ui.painter().line_segment(
-                    [rect.left_center(), pos2(fill_end, rect.center().y)],
-                    Stroke::new(3.0, fill_color),
-                );
+                ui.painter()
+                    .line_segment([start, fill_end], Stroke::new(3.0, fill_color));
 
                 // Draw draggable knob
                 if self.show_knob {
-                    ui.painter().circle_filled(
-                        pos2(fill_end, rect.center().y),
-                        self.knob_radius,
-                        fill_color,
-                    );
+                    ui.painter()
+                        .circle_filled(fill_end, self.knob_radius, fill_color);
                 }
             }
 
@@ -175,13 +208,20 @@ impl<'a> Widget for ProgressBar<'a> {
             if let Some(ref mut preview_callback) = self.preview
                 && let Some(pointer) = response.hover_pos()
             {
-                let value = value_from_x(self.max, rect, pointer.x as _);
-                let preview_pos = pos2(pointer.x, rect.top() - 10.0);
+                let value = self.orientation.value_from_pos(self.max, rect, pointer);
+                let (preview_pos, pivot) = match self.orientation {
+                    Orientation::Horizontal => {
+                        (pos2(pointer.x, rect.top() - 10.0), Align2::CENTER_BOTTOM)
+                    }
+                    Orientation::Vertical => {
+                        (pos2(rect.left() - 10.0, pointer.y), Align2::RIGHT_CENTER)
+                    }
+                };
 
                 Area::new("progress_bar_preview_area".into())
                     .order(Order::Tooltip)
                     .fixed_pos(preview_pos)
-                    .pivot(Align2::CENTER_BOTTOM)
+                    .pivot(pivot)
                     .show(ui.ctx(), |ui| {
                         preview_callback(ui, value);
                     });
@@ -205,6 +245,17 @@ pub fn value_from_x(max: f64, rect: Rect, x: f64) -> f64 {
     fraction * max
 }
 
+/// Converts a vertical position within the progress bar to a value,
+/// the bottom is `0` and the top is `max`
+pub fn value_from_y(max: f64, rect: Rect, y: f64) -> f64 {
+    if rect.height() <= 0.0 {
+        return 0.0;
+    }
+
+    let fraction = ((rect.bottom() as f64 - y) / rect.height() as f64).clamp(0.0, 1.0);
+    fraction * max
+}
+
 /// Draws the progress bar background with gradient effect
 ///
 /// This includes:
@@ -222,38 +273,79 @@ pub fn draw_progress_bar_background(
     background_color: Color32,
     corner_radius: CornerRadius,
 ) {
-    let size = crate::get_body_text_size(ui);
-
-    // Draw bottom background panel
-    let painter = ui.painter();
-    painter.rect_filled(
-        {
-            let mut rect = rect;
-            rect.set_top(rect.bottom() - size);
-            rect
-        },
-        corner_radius,
+    draw_oriented_progress_bar_background(
+        ui,
+        rect,
         background_color,
+        corner_radius,
+        Orientation::Horizontal,
     );
+}
 
-    // Prepare gradient area
-    let mesh_rect = {
-        let mut r = rect;
-        r.set_bottom(r.bottom() - size);
-        r
-    };
+/// Same as [`draw_progress_bar_background`], a vertical one has the panel
+/// on the right side and the gradient fading out to the left
+pub fn draw_oriented_progress_bar_background(
+    ui: &mut Ui,
+    rect: Rect,
+    background_color: Color32,
+    corner_radius: CornerRadius,
+    orientation: Orientation,
+) {
+    let size = crate::get_body_text_size(ui);
+
+    // Split into the gradient area and the panel
+    let mut panel_rect = rect;
+    let mut mesh_rect = rect;
+    match orientation {
+        Orientation::Horizontal => {
+            panel_rect.set_top(rect.bottom() - size);
+            mesh_rect.set_bottom(rect.bottom() - size);
+        }
+        Orientation::Vertical => {
+            panel_rect.set_left(rect.right() - size);
+            mesh_rect.set_right(rect.right() - size);
+        }
+    }
 
-    // Define gradient colors
-    let mesh_top_color = Color32::TRANSPARENT;
-    let mesh_bottom_color = background_color;
+    // Draw background panel
+    let painter = ui.painter();
+    painter.rect_filled(panel_rect, corner_radius, background_color);
+
+    // Define gradient colors, transparent on the side away from the panel
+    let far_color = Color32::TRANSPARENT;
+    let near_color = background_color;
+    let [left_top, right_top, left_bottom, right_bottom] = match orientation {
+        Orientation::Horizontal => [far_color, far_color, near_color, near_color],
+        Orientation::Vertical => [far_color, near_color, far_color, near_color],
+    };
 
     // Create and add gradient mesh
     let mut mesh = eframe::egui::Mesh::default();
-    mesh.colored_vertex(mesh_rect.left_top(), mesh_top_color);
-    mesh.colored_vertex(mesh_rect.right_top(), mesh_top_color);
-    mesh.colored_vertex(mesh_rect.left_bottom(), mesh_bottom_color);
-    mesh.colored_vertex(mesh_rect.right_bottom(), mesh_bottom_color);
+    mesh.colored_vertex(mesh_rect.left_top(), left_top);
+    mesh.colored_vertex(mesh_rect.right_top(), right_top);
+    mesh.colored_vertex(mesh_rect.left_bottom(), left_bottom);
+    mesh.colored_vertex(mesh_rect.right_bottom(), right_bottom);
     mesh.add_triangle(0, 1, 2);
     mesh.add_triangle(1, 2, 3);
     painter.add(mesh);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_from_both_orientations() {
+        let rect = Rect::from_min_max(pos2(10.0, 20.0), pos2(110.0, 220.0));
+
+        assert_eq!(value_from_x(10.0, rect, 60.0), 5.0);
+        assert_eq!(value_from_x(10.0, rect, 0.0), 0.0);
+        assert_eq!(value_from_y(10.0, rect, 220.0), 0.0);
+        assert_eq!(value_from_y(10.0, rect, 70.0), 7.5);
+        assert_eq!(value_from_y(10.0, rect, 0.0), 10.0);
+
+        let pos = pos2(35.0, 170.0);
+        assert_eq!(Orientation::default().value_from_pos(4.0, rect, pos), 1.0);
+        assert_eq!(Orientation::Vertical.value_from_pos(4.0, rect, pos), 1.0);
+    }
+}
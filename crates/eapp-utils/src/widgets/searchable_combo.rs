@@ -0,0 +1,211 @@
+//! SearchableCombo widget impl
+
+use crate::codicons::ICON_CHEVRON_DOWN;
+use eframe::egui::{
+    self, Id, Key, Modifiers, Popup, PopupCloseBehavior, Response, ScrollArea, TextEdit, Ui,
+};
+
+/// A combo box whose popup has a filter and can be navigated by keyboard
+///
+/// Features:
+/// - Filter text edit, focused when the popup opens
+/// - Only the visible rows of the filtered list are laid out
+/// - `Up`/`Down` to move, `Enter` to choose, `Escape` to close
+/// - Can be placed in other popups
+///
+/// # Example
+/// ```ignore
+/// SearchableCombo::new("codec", encodings.len(), |i| encodings[i].name().to_owned())
+///     .show(ui, &mut codec_idx);
+/// ```
+pub struct SearchableCombo<'a> {
+    id_salt: Id,
+
+    /// Number of entries
+    len: usize,
+
+    /// Text of entry `i`
+    get: EntryText<'a>,
+
+    /// Text shown on the button instead of the text of the selected entry
+    selected_text: Option<String>,
+
+    /// Minimum width of the button and the popup
+    width: Option<f32>,
+}
+
+type EntryText<'a> = Box<dyn Fn(usize) -> String + 'a>;
+
+#[derive(Clone, Default)]
+struct ComboState {
+    open: bool,
+    filter: String,
+
+    /// Row of the filtered list chosen by `Enter`
+    cursor: usize,
+
+    scroll_offset: f32,
+}
+
+impl<'a> SearchableCombo<'a> {
+    pub fn new(
+        id_salt: impl std::hash::Hash,
+        len: usize,
+        get: impl Fn(usize) -> String + 'a,
+    ) -> Self {
+        Self {
+            id_salt: Id::new(id_salt),
+            len,
+            get: Box::new(get),
+            selected_text: None,
+            width: None,
+        }
+    }
+
+    pub fn selected_text(mut self, selected_text: impl Into<String>) -> Self {
+        self.selected_text = Some(selected_text.into());
+        self
+    }
+
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Shows the combo box, the response is changed if an entry is chosen
+    pub fn show(self, ui: &mut Ui, selected: &mut usize) -> Response {
+        let id = ui.make_persistent_id(self.id_salt);
+        let popup_id = id.with("popup");
+
+        let selected_text = match self.selected_text {
+            Some(text) => text,
+            None if *selected < self.len => (self.get)(*selected),
+            None => String::new(),
+        };
+
+        let mut button = egui::Button::new(format!("{selected_text} {ICON_CHEVRON_DOWN}"));
+        if let Some(width) = self.width {
+            button = button.min_size(egui::vec2(width, 0.0));
+        }
+        let mut response = ui.add(button);
+
+        let mut state = ui
+            .data(|data| data.get_temp::<ComboState>(id))
+            .unwrap_or_default();
+        let mut chosen = None;
+
+        let inner = Popup::from_toggle_button_response(&response)
+            .id(popup_id)
+            .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+            .width(response.rect.width().max(self.width.unwrap_or(0.0)))
+            .show(|ui| {
+                let just_opened = !state.open;
+                state.open = true;
+
+                let filter = state.filter.to_lowercase();
+                let matches: Vec<usize> = (0..self.len)
+                    .filter(|&i| {
+                        filter.is_empty() || (self.get)(i).to_lowercase().contains(&filter)
+                    })
+                    .collect();
+
+                if just_opened {
+                    state.cursor = matches.iter().position(|&i| i == *selected).unwrap_or(0);
+                }
+
+                // Keys are taken before the text edit, which would move its cursor instead
+                let (up, down, enter, escape) = ui.input_mut(|i| {
+                    (
+                        i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                        i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                        i.consume_key(Modifiers::NONE, Key::Enter),
+                        i.consume_key(Modifiers::NONE, Key::Escape),
+                    )
+                });
+
+                let mut moved = just_opened;
+                if up && state.cursor > 0 {
+                    state.cursor -= 1;
+                    moved = true;
+                }
+                if down && state.cursor + 1 < matches.len() {
+                    state.cursor += 1;
+                    moved = true;
+                }
+
+                let edit = TextEdit::singleline(&mut state.filter)
+                    .hint_text("Filter")
+                    .desired_width(f32::INFINITY)
+                    .show(ui)
+                    .response;
+                if just_opened {
+                    edit.request_focus();
+                }
+                if edit.changed() {
+                    state.cursor = 0;
+                    moved = true;
+                }
+
+                if enter && let Some(&idx) = matches.get(state.cursor) {
+                    chosen = Some(idx);
+                }
+                if escape {
+                    Popup::close_id(ui.ctx(), popup_id);
+                }
+
+                let row_height = ui.spacing().interact_size.y;
+                let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+                let max_height = ui.spacing().combo_height;
+
+                let mut scroll_area = ScrollArea::vertical()
+                    .id_salt(id.with("scroll"))
+                    .max_height(max_height)
+                    .auto_shrink([false, true]);
+
+                if moved {
+                    let top = state.cursor as f32 * row_height_with_spacing;
+                    let bottom = top + row_height_with_spacing;
+                    if top < state.scroll_offset {
+                        state.scroll_offset = top;
+                    } else if bottom > state.scroll_offset + max_height {
+                        state.scroll_offset = bottom - max_height;
+                    }
+                    scroll_area = scroll_area.vertical_scroll_offset(state.scroll_offset);
+                }
+
+                let output = scroll_area.show_rows(ui, row_height, matches.len(), |ui, rows| {
+                    ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
+                        for row in rows {
+                            let idx = matches[row];
+                            let mut response =
+                                ui.selectable_label(idx == *selected, (self.get)(idx));
+                            if row == state.cursor {
+                                response = response.highlight();
+                            }
+                            if response.clicked() {
+                                chosen = Some(idx);
+                            }
+                        }
+                    });
+                });
+                state.scroll_offset = output.state.offset.y;
+
+                if matches.is_empty() {
+                    ui.weak("No matches");
+                }
+            });
+
+        if let Some(idx) = chosen {
+            *selected = idx;
+            response.mark_changed();
+            Popup::close_id(ui.ctx(), popup_id);
+        }
+
+        if inner.is_none() || chosen.is_some() {
+            state = ComboState::default();
+        }
+        ui.data_mut(|data| data.insert_temp(id, state));
+
+        response
+    }
+}
@@ -1,12 +1,13 @@
 //! Contains widgets related utils
 
 use crate::{
-    animation::color_lerp,
+    animation::{Easing, Tween, color_lerp},
     codicons::{ICON_GITHUB, ICON_GITHUB_INVERTED},
 };
 use eframe::egui::{
     self, Align2, Color32, CornerRadius, FontId, IntoAtoms, Rect, Sense, Vec2, Widget, WidgetText,
     pos2,
+    text::{CCursor, CCursorRange},
 };
 
 /// Just a button, with plain style
@@ -108,6 +109,7 @@ pub fn popup_animated(
     add_contents: impl FnOnce(&mut egui::Ui),
 ) -> bool {
     let opacity = ui.ctx().animate_bool(popup_id, open).min(parent_opacity);
+    let nested_id = popup_id.with("nested_popup");
 
     if opacity != 0.0 {
         let pos = match above_or_below {
@@ -123,16 +125,31 @@ pub fn popup_animated(
             .show(ui.ctx(), |ui| {
                 ui.set_opacity(opacity);
                 let frame = egui::Frame::popup(ui.style());
+                let was_any_open = egui::Popup::is_any_open(ui.ctx());
                 frame.show(ui, |ui| {
                     ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
                         add_contents(ui)
                     })
-                })
+                });
+
+                // only one popup is open at a time, so a popup opened by the contents is
+                // the open one until it's closed
+                if !was_any_open && egui::Popup::is_any_open(ui.ctx()) {
+                    ui.data_mut(|d| d.insert_temp(nested_id, true));
+                }
             })
             .response;
 
-        if ui.input(|i| i.key_pressed(egui::Key::Escape))
-            || (widget_response.clicked_elsewhere() && area.clicked_elsewhere())
+        // Popups opened inside, e.g. by a combo box, are outside of the area
+        let nested_popup_open = egui::Popup::is_any_open(ui.ctx())
+            && ui.data(|d| d.get_temp(nested_id).unwrap_or(false));
+        if !nested_popup_open {
+            ui.data_mut(|d| d.remove::<bool>(nested_id));
+        }
+
+        if !nested_popup_open
+            && (ui.input(|i| i.key_pressed(egui::Key::Escape))
+                || (widget_response.clicked_elsewhere() && area.clicked_elsewhere()))
         {
             open = false;
         }
@@ -141,38 +158,102 @@ pub fn popup_animated(
     open
 }
 
+/// A switch for a boolean, whose knob slides between off and on
 pub fn toggle_ui(ui: &mut egui::Ui, on: &mut bool) -> egui::Response {
-    let desired_size = ui.spacing().interact_size.y * egui::vec2(2.0, 1.0);
+    toggle_impl(ui, on, None)
+}
+
+/// Same as [`toggle_ui`] with a label on the right, which can be clicked as well
+pub fn toggle_ui_with_label(
+    ui: &mut egui::Ui,
+    on: &mut bool,
+    text: impl Into<WidgetText>,
+) -> egui::Response {
+    toggle_impl(ui, on, Some(text.into()))
+}
+
+fn toggle_impl(ui: &mut egui::Ui, on: &mut bool, text: Option<WidgetText>) -> egui::Response {
+    let switch_size = ui.spacing().interact_size.y * egui::vec2(2.0, 1.0);
+    let galley = text.map(|text| {
+        text.into_galley(
+            ui,
+            Some(egui::TextWrapMode::Extend),
+            f32::INFINITY,
+            egui::TextStyle::Button,
+        )
+    });
+
+    let mut desired_size = switch_size;
+    if let Some(galley) = &galley {
+        desired_size.x += ui.spacing().item_spacing.x + galley.size().x;
+        desired_size.y = desired_size.y.max(galley.size().y);
+    }
+
     let (rect, mut response) = ui.allocate_exact_size(desired_size, egui::Sense::click());
     if response.clicked() {
         *on = !*on;
         response.mark_changed();
     }
     response.widget_info(|| {
-        egui::WidgetInfo::selected(egui::WidgetType::Checkbox, ui.is_enabled(), *on, "")
+        let label = galley.as_ref().map_or("", |galley| galley.text());
+        egui::WidgetInfo::selected(egui::WidgetType::Checkbox, ui.is_enabled(), *on, label)
     });
 
+    let how_on = animate_toggle(ui, response.id, *on);
+
     if ui.is_rect_visible(rect) {
-        let how_on = ui.ctx().animate_bool_responsive(response.id, *on);
         let visuals = ui.style().interact_selectable(&response, *on);
-        let rect = rect.expand(visuals.expansion);
-        let radius = 0.5 * rect.height();
+        let off_fill = ui.visuals().widgets.inactive.bg_fill;
+        let on_fill = ui.visuals().selection.bg_fill;
+
+        let switch_rect = Rect::from_min_size(
+            pos2(rect.left(), rect.center().y - switch_size.y / 2.0),
+            switch_size,
+        )
+        .expand(visuals.expansion);
+        let radius = 0.5 * switch_rect.height();
         ui.painter().rect(
-            rect,
+            switch_rect,
             radius,
-            visuals.bg_fill,
+            color_lerp(off_fill, on_fill, how_on.clamp(0.0, 1.0)),
             visuals.bg_stroke,
             egui::StrokeKind::Inside,
         );
-        let circle_x = egui::lerp((rect.left() + radius)..=(rect.right() - radius), how_on);
-        let center = egui::pos2(circle_x, rect.center().y);
+
+        let circle_x = egui::lerp(
+            (switch_rect.left() + radius)..=(switch_rect.right() - radius),
+            how_on,
+        );
+        let center = egui::pos2(circle_x, switch_rect.center().y);
         ui.painter()
             .circle(center, 0.75 * radius, visuals.bg_fill, visuals.fg_stroke);
+
+        if let Some(galley) = galley {
+            let pos = pos2(
+                switch_rect.right() + ui.spacing().item_spacing.x,
+                rect.center().y - galley.size().y / 2.0,
+            );
+            ui.painter().galley(pos, galley, visuals.text_color());
+        }
     }
 
     response
 }
 
+/// Position of the knob of a toggle, `0.0` is off and `1.0` is on
+fn animate_toggle(ui: &egui::Ui, id: egui::Id, on: bool) -> f32 {
+    let target = if on { 1.0 } else { 0.0 };
+    let duration = 2.0 * ui.style().animation_time as f64;
+
+    let mut tween = ui
+        .data(|data| data.get_temp::<Tween<f32>>(id))
+        .unwrap_or_else(|| Tween::new(target, duration, Easing::EaseOutCubic));
+    let how_on = tween.animate(ui.ctx(), target);
+    ui.data_mut(|data| data.insert_temp(id, tween));
+
+    how_on
+}
+
 pub fn text_in_center_bottom_of_rect(ui: &egui::Ui, text: String, rect: &Rect) {
     let color = ui.visuals().strong_text_color();
     let font_size = crate::get_body_text_size(ui);
@@ -236,3 +317,69 @@ where
 pub fn frameless_btn<'a>(ui: &mut egui::Ui, text: impl IntoAtoms<'a>) -> egui::Response {
     ui.selectable_label(false, text)
 }
+
+/// A label that turns into a single-line text edit when `editing` is set or it's double clicked.
+///
+/// The edit starts with the whole text selected, and it's committed on `Enter` or losing focus,
+/// reverted on `Escape` or if it ends up empty. The returned response is `changed` when
+/// `text` is replaced.
+pub fn editable_label(ui: &mut egui::Ui, text: &mut String, editing: &mut bool) -> egui::Response {
+    let id = ui.next_auto_id();
+    let buffer_id = id.with("editable_label_buffer");
+
+    if !*editing {
+        let response = ui.add(
+            egui::Label::new(text.as_str())
+                .truncate()
+                .sense(Sense::click()),
+        );
+        if response.double_clicked() {
+            *editing = true;
+            ui.ctx().request_repaint();
+        }
+        return response;
+    }
+
+    // edit a copy, so `text` is never left with half of the edit
+    let buffer = ui.data_mut(|data| data.remove_temp::<String>(buffer_id));
+    let entering = buffer.is_none();
+    let mut buffer = buffer.unwrap_or_else(|| text.clone());
+
+    let mut output = egui::TextEdit::singleline(&mut buffer)
+        .id(id)
+        .desired_width(ui.available_width())
+        .show(ui);
+
+    if entering {
+        output.response.request_focus();
+        let len = buffer.chars().count();
+        output
+            .state
+            .cursor
+            .set_char_range(Some(CCursorRange::two(CCursor::new(0), CCursor::new(len))));
+        output.state.store(ui.ctx(), id);
+    }
+
+    let mut response = ui.interact(
+        output.response.rect,
+        id.with("editable_label"),
+        Sense::hover(),
+    );
+
+    // focus can be taken by others as well, which ends the edit like clicking outside
+    let done = output.response.lost_focus() || (!entering && !output.response.has_focus());
+    if !done {
+        ui.data_mut(|data| data.insert_temp(buffer_id, buffer));
+        return response;
+    }
+
+    *editing = false;
+    let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+    let new_text = buffer.trim();
+    if !cancelled && !new_text.is_empty() && new_text != text {
+        *text = new_text.to_owned();
+        response.mark_changed();
+    }
+
+    response
+}
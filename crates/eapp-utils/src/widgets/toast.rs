@@ -0,0 +1,244 @@
+//! Toasts impl
+
+use crate::{
+    animation::fade_out,
+    codicons::{ICON_ERROR, ICON_INFO, ICON_WARNING},
+};
+use eframe::egui::{self, Align2, Color32, Frame, Id, Order, RichText};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Warn,
+    Error,
+}
+
+struct Toast {
+    kind: ToastKind,
+    text: String,
+
+    /// Set when it's shown for the first time, so that toasts added before the first frame
+    /// don't expire early
+    end_time: Option<f64>,
+}
+
+/// Timed notifications stacked in a corner, fading out before they are dismissed
+///
+/// Clicking a toast dismisses it, error toasts stay twice as long
+///
+/// # Example
+/// ```ignore
+/// self.toasts.info("Saved");
+///
+/// // once per frame
+/// self.toasts.show(ctx);
+/// ```
+pub struct Toasts {
+    toasts: Vec<Toast>,
+    anchor: Align2,
+    duration: f64,
+    max_count: usize,
+}
+
+impl Default for Toasts {
+    fn default() -> Self {
+        Self {
+            toasts: Vec::new(),
+            anchor: Align2::RIGHT_BOTTOM,
+            duration: 3.0,
+            max_count: 5,
+        }
+    }
+}
+
+impl Toasts {
+    const FADE_SECS: f64 = 0.5;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Corner of the window to stack toasts in
+    pub fn anchor(mut self, anchor: Align2) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Seconds a toast is shown
+    pub fn duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Older toasts are dismissed when there are more
+    pub fn max_count(mut self, max_count: usize) -> Self {
+        self.max_count = max_count.max(1);
+        self
+    }
+
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.add(ToastKind::Info, text);
+    }
+
+    pub fn warn(&mut self, text: impl Into<String>) {
+        self.add(ToastKind::Warn, text);
+    }
+
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.add(ToastKind::Error, text);
+    }
+
+    pub fn add(&mut self, kind: ToastKind, text: impl Into<String>) {
+        self.toasts.push(Toast {
+            kind,
+            text: text.into(),
+            end_time: None,
+        });
+
+        let overflow = self.toasts.len().saturating_sub(self.max_count);
+        self.toasts.drain(..overflow);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    /// Starts the timer of new toasts and removes expired ones
+    fn update(&mut self, now: f64) {
+        for toast in &mut self.toasts {
+            let duration = match toast.kind {
+                ToastKind::Error => self.duration * 2.0,
+                _ => self.duration,
+            };
+            toast.end_time.get_or_insert(now + duration);
+        }
+
+        self.toasts
+            .retain(|toast| toast.end_time.is_some_and(|end_time| now < end_time));
+    }
+
+    /// Draws the toasts, should be called once per frame
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        self.update(now);
+
+        if self.toasts.is_empty() {
+            return;
+        }
+
+        let margin = 12.0;
+        let offset = egui::vec2(
+            -self.anchor.x().to_sign() * margin,
+            -self.anchor.y().to_sign() * margin,
+        );
+
+        // The newest toast is the nearest to the corner
+        let from_bottom = self.anchor.y() == egui::Align::Max;
+        let mut dismissed = None;
+
+        egui::Area::new(Id::new("eapp_utils_toasts"))
+            .anchor(self.anchor, offset)
+            .order(Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(320.0);
+
+                let mut show_toast = |ui: &mut egui::Ui, idx: usize, toast: &Toast| {
+                    let end_time = toast.end_time.unwrap_or(now);
+                    ui.set_opacity(fade_out(now, end_time, Self::FADE_SECS));
+
+                    let (icon, color) = match toast.kind {
+                        ToastKind::Info => (ICON_INFO, ui.visuals().text_color()),
+                        ToastKind::Warn => (ICON_WARNING, ui.visuals().warn_fg_color),
+                        ToastKind::Error => (ICON_ERROR, ui.visuals().error_fg_color),
+                    };
+
+                    let response = Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new(icon.to_string()).color(color));
+                                ui.label(&toast.text);
+                            });
+                        })
+                        .response
+                        .interact(egui::Sense::click())
+                        .on_hover_cursor(egui::CursorIcon::PointingHand);
+
+                    if response.clicked() {
+                        dismissed = Some(idx);
+                    }
+                };
+
+                let count = self.toasts.len();
+                for i in 0..count {
+                    let idx = if from_bottom { i } else { count - 1 - i };
+                    ui.scope(|ui| show_toast(ui, idx, &self.toasts[idx]));
+                }
+            });
+
+        if let Some(idx) = dismissed {
+            self.toasts.remove(idx);
+        }
+
+        let next_change = self
+            .toasts
+            .iter()
+            .filter_map(|toast| toast.end_time)
+            .map(|end_time| {
+                let fade_start = end_time - Self::FADE_SECS;
+                if now < fade_start {
+                    fade_start - now
+                } else {
+                    0.0
+                }
+            })
+            .fold(f64::INFINITY, f64::min);
+        if next_change.is_finite() {
+            ctx.request_repaint_after_secs(next_change as f32);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_expire_after_shown() {
+        let mut toasts = Toasts::new().duration(1.0);
+        toasts.info("saved");
+        toasts.error("failed");
+
+        // Added before the first frame, the timer starts when shown
+        toasts.update(10.0);
+        assert_eq!(toasts.toasts.len(), 2);
+
+        toasts.update(11.5);
+        assert_eq!(toasts.toasts.len(), 1);
+        assert_eq!(toasts.toasts[0].kind, ToastKind::Error);
+
+        toasts.update(12.0);
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn oldest_are_dropped() {
+        let mut toasts = Toasts::new().max_count(2);
+        toasts.info("1");
+        toasts.warn("2");
+        toasts.error("3");
+
+        let texts: Vec<_> = toasts.toasts.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, ["2", "3"]);
+    }
+
+    #[test]
+    fn fade_out_opacity() {
+        assert_eq!(fade_out(0.0, 2.0, 0.5), 1.0);
+        assert_eq!(fade_out(1.75, 2.0, 0.5), 0.5);
+        assert_eq!(fade_out(3.0, 2.0, 0.5), 0.0);
+    }
+}
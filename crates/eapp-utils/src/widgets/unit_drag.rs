@@ -0,0 +1,133 @@
+//! UnitDrag widget impl
+
+use eframe::egui::{DragValue, Response, Ui, Widget, emath::Numeric};
+use std::ops::RangeInclusive;
+
+/// A [`DragValue`] with a unit suffix, whose shown value can be scaled from the stored one
+///
+/// The response is changed when the value changes, as for [`DragValue`]
+///
+/// # Example
+/// ```ignore
+/// // stored in bytes, shown in MB
+/// ui.add(UnitDrag::new(&mut self.cache_bytes, "MB").scale(1024.0 * 1024.0).range(0..=1 << 32));
+/// ```
+pub struct UnitDrag<'a, T: Numeric> {
+    value: &'a mut T,
+    unit: String,
+
+    /// Stored units per shown unit
+    scale: f64,
+
+    /// In stored units
+    range: RangeInclusive<f64>,
+
+    speed: f64,
+    max_decimals: Option<usize>,
+}
+
+impl<'a, T: Numeric> UnitDrag<'a, T> {
+    pub fn new(value: &'a mut T, unit: impl Into<String>) -> Self {
+        Self {
+            value,
+            unit: unit.into(),
+            scale: 1.0,
+            range: T::MIN.to_f64()..=T::MAX.to_f64(),
+            speed: 1.0,
+            max_decimals: None,
+        }
+    }
+
+    /// Shows the value divided by `scale`, which must be positive
+    #[inline]
+    pub fn scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Clamps the stored value into `range`
+    #[inline]
+    pub fn range(mut self, range: RangeInclusive<T>) -> Self {
+        self.range = range.start().to_f64()..=range.end().to_f64();
+        self
+    }
+
+    /// Change of the shown value per point dragged
+    #[inline]
+    pub fn speed(mut self, speed: impl Into<f64>) -> Self {
+        self.speed = speed.into();
+        self
+    }
+
+    #[inline]
+    pub fn max_decimals(mut self, max_decimals: usize) -> Self {
+        self.max_decimals = Some(max_decimals);
+        self
+    }
+}
+
+impl<T: Numeric> Widget for UnitDrag<'_, T> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let Self {
+            value,
+            unit,
+            scale,
+            range,
+            speed,
+            max_decimals,
+        } = self;
+
+        let shown_range = (range.start() / scale)..=(range.end() / scale);
+        let mut drag = DragValue::from_get_set(move |shown| {
+            if let Some(shown) = shown {
+                *value = to_stored(shown, scale, &range);
+            }
+            value.to_f64() / scale
+        })
+        .range(shown_range)
+        .speed(speed)
+        .suffix(format!(" {unit}"));
+
+        if let Some(max_decimals) = max_decimals {
+            drag = drag.max_decimals(max_decimals);
+        } else if T::INTEGRAL && scale == 1.0 {
+            drag = drag.max_decimals(0);
+        }
+
+        ui.add(drag)
+    }
+}
+
+/// Same as [`Ui::add`] with an unscaled [`UnitDrag`], returns whether the value changed
+pub fn unit_drag<T: Numeric>(
+    ui: &mut Ui,
+    value: &mut T,
+    unit: &str,
+    range: RangeInclusive<T>,
+) -> bool {
+    ui.add(UnitDrag::new(value, unit).range(range)).changed()
+}
+
+fn to_stored<T: Numeric>(shown: f64, scale: f64, range: &RangeInclusive<f64>) -> T {
+    let mut stored = (shown * scale).clamp(*range.start(), *range.end());
+    if T::INTEGRAL {
+        stored = stored.round();
+    }
+    T::from_f64(stored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shown_value_is_scaled_and_clamped() {
+        let mb = 1024.0 * 1024.0;
+        let range = 0.0..=(4.0 * mb);
+
+        assert_eq!(to_stored::<u64>(1.5, mb, &range), 1_572_864);
+        assert_eq!(to_stored::<u64>(10.0, mb, &range), 4 * 1024 * 1024);
+        assert_eq!(to_stored::<u64>(-1.0, mb, &range), 0);
+        assert_eq!(to_stored::<f32>(0.25, 2.0, &(0.0..=1.0)), 0.5);
+    }
+}
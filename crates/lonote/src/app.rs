@@ -2,20 +2,29 @@ use crate::codec;
 use chardetng::EncodingDetector;
 use eapp_utils::{
     borderless,
-    codicons::{ICON_TRIANGLE_DOWN, ICON_TRIANGLE_UP},
+    codicons::{ICON_ADD, ICON_CLOSE, ICON_TRIANGLE_DOWN, ICON_TRIANGLE_UP},
     get_body_font_id, get_button_height,
+    persistent_window::PersistentWindow,
+    shortcuts::ShortcutMap,
     ui_font_selector::UiFontSelector,
-    widgets::simple_widgets::{get_theme_button, theme_button},
+    widgets::{
+        command_palette::{Command, CommandPalette},
+        confirm::{Confirm, ConfirmRequest},
+        searchable_combo::SearchableCombo,
+        simple_widgets::{get_theme_button, theme_button},
+    },
 };
 use eframe::egui::{
-    self, Color32, Margin, Rect, UiBuilder, Vec2,
+    self, Color32, Key, KeyboardShortcut, Margin, Modifiers, Rect, UiBuilder, Vec2,
     text::{CCursor, CCursorRange},
     text_edit::TextEditOutput,
     text_selection::text_cursor_state::{byte_index_from_char_index, cursor_rect},
 };
+use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
     cell::RefCell,
+    ops::Range,
     path::{Path, PathBuf},
     rc::Rc,
     time::{Duration, UNIX_EPOCH},
@@ -23,19 +32,49 @@ use std::{
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
-type DialogCb = Option<(String, Box<dyn FnOnce(bool) -> Result<()>>)>;
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+enum Action {
+    New,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    Open,
+    Reopen,
+    Save,
+    SaveAs,
+    Search,
+    SearchDown,
+    SearchUp,
+    GotoLine,
+}
 
 pub struct App {
-    note: Rc<RefCell<Note>>,
-    dialog_cb: DialogCb,
+    /// Open tabs, there is always at least one
+    notes: Vec<Rc<RefCell<Note>>>,
+    active: usize,
+    next_tab_id: u64,
+    confirm: Confirm<Result<()>>,
     show_search_box: bool,
     case_sense: bool,
     search_words: String,
     search_down: Option<bool>,
+    show_goto_box: bool,
+    goto_words: String,
+    goto_line: Option<usize>,
+
+    /// Files larger than this (in MB) are offered to open in large-file mode
+    large_file_threshold: u64,
+    palette: CommandPalette<App>,
+    shortcuts: ShortcutMap<Action>,
+    show_shortcuts: bool,
     selector: UiFontSelector,
+    window: PersistentWindow,
 }
 
 struct Note {
+    /// Tells the tabs apart for the state of their editors
+    pub id: u64,
     pub codec_idx: usize,
     pub contents: String,
     pub state_msg: String,
@@ -43,17 +82,111 @@ struct Note {
     pub modified: bool,
     pub cur_file: Option<File>,
     pub allow_to_close: bool,
+
+    /// The tab is closed on the next frame
+    pub closing: bool,
+
+    /// Set if the contents are shown read-only in large-file mode
+    pub large: Option<LargeFile>,
+
+    /// A file over the threshold waiting for the user to choose how to open it
+    pub pending_large_open: Option<PathBuf>,
+}
+
+/// Read-only view of a large file, only the visible lines are laid out
+struct LargeFile {
+    /// Byte offset of each line in the contents
+    pub line_starts: Vec<usize>,
+
+    /// Byte range of the last search match
+    pub selection: Option<Range<usize>>,
+}
+
+impl LargeFile {
+    pub fn new(contents: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(contents.match_indices('\n').map(|(i, _)| i + 1))
+            .collect();
+
+        Self {
+            line_starts,
+            selection: None,
+        }
+    }
+
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.line_starts
+            .partition_point(|&start| start <= offset)
+            .saturating_sub(1)
+    }
+
+    pub fn line_range(&self, line: usize, contents: &str) -> Range<usize> {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(contents.len());
+        let text = contents[start..end].trim_end_matches(['\n', '\r']);
+        start..start + text.len()
+    }
 }
 
 impl Note {
-    pub fn update_title(&mut self) {
-        let modified = self.modified;
-        let modified = if modified { "* " } else { "" };
-        let name = match &self.cur_file {
-            Some(f) => f.path.file_name().unwrap().to_string_lossy().to_string(),
+    /// The file name, or the one waiting to be opened
+    pub fn name(&self) -> String {
+        match self.get_path().or(self.pending_large_open.as_deref()) {
+            Some(path) => path.file_name().unwrap().to_string_lossy().to_string(),
             None => "(Untitled)".to_string(),
+        }
+    }
+
+    pub fn tab_name(&self) -> String {
+        let modified = if self.modified { "* " } else { "" };
+        format!("{modified}{}", self.name())
+    }
+
+    /// An untitled one never typed in, which is taken by the next opened file
+    pub fn is_blank(&self) -> bool {
+        self.cur_file.is_none()
+            && self.pending_large_open.is_none()
+            && !self.modified
+            && self.contents.is_empty()
+    }
+
+    pub fn update_title(&mut self) {
+        let read_only = if self.large.is_some() {
+            " [Read-only]"
+        } else {
+            ""
         };
-        self.title = format!("{modified}{name} - lonote");
+        self.title = format!("{}{read_only} - lonote", self.tab_name());
+    }
+
+    /// Opens `path`, in large-file mode if `read_only`
+    pub fn load(&mut self, path: PathBuf, read_only: bool) -> Result<()> {
+        let last_modified_time = Note::get_modified_time(&path)?;
+        let (contents, codec_idx) = Note::read_from_file(&path, None)?;
+
+        self.large = read_only.then(|| LargeFile::new(&contents));
+        self.contents = contents;
+        self.codec_idx = codec_idx;
+        self.cur_file = Some(File {
+            path,
+            last_modified_time,
+        });
+        self.modified = false;
+        self.update_title();
+
+        let encoding = codec::supported_encodings()[codec_idx].name();
+        self.state_msg = match &self.large {
+            Some(large) => format!(
+                "Open read-only, Save is disabled in large-file mode (Encoding: {encoding}, {} lines)",
+                large.line_starts.len()
+            ),
+            None => format!("Open successfully (Encoding: {encoding})"),
+        };
+        Ok(())
     }
 
     pub fn read_from_file<P>(path: P, codec_idx: Option<usize>) -> Result<(String, usize)>
@@ -115,6 +248,7 @@ impl Note {
 impl Default for Note {
     fn default() -> Self {
         Self {
+            id: 0,
             codec_idx: 0,
             contents: Default::default(),
             state_msg: Default::default(),
@@ -122,6 +256,9 @@ impl Default for Note {
             modified: false,
             cur_file: None,
             allow_to_close: false,
+            closing: false,
+            large: None,
+            pending_large_open: None,
         }
     }
 }
@@ -139,17 +276,56 @@ impl App {
             UiFontSelector::default()
         };
 
+        let large_file_threshold = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, Self::LARGE_FILE_THRESHOLD_KEY))
+            .unwrap_or(Self::DEFAULT_LARGE_FILE_THRESHOLD);
+
+        let (session_files, session_active): (Vec<String>, usize) = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, Self::SESSION_KEY))
+            .unwrap_or_default();
+
+        let shortcuts = Self::shortcut_map(
+            cc.storage
+                .and_then(|storage| eframe::get_value(storage, ShortcutMap::<Action>::KEY))
+                .unwrap_or_default(),
+        );
+
         let mut this = Self {
-            note: Rc::new(RefCell::new(Note::default())),
-            dialog_cb: None,
+            notes: vec![Rc::new(RefCell::new(Note::default()))],
+            active: 0,
+            next_tab_id: 1,
+            confirm: Confirm::new(),
             show_search_box: false,
             case_sense: true,
             search_words: String::default(),
             search_down: None,
+            show_goto_box: false,
+            goto_words: String::default(),
+            goto_line: None,
+            large_file_threshold,
+            palette: Self::command_palette(&cc.egui_ctx, &shortcuts),
+            shortcuts,
+            show_shortcuts: false,
             selector,
+            window: PersistentWindow::new(cc),
         };
 
-        if let Some(file) = std::env::args().nth(1) {
+        let mut active = None;
+        for (idx, file) in session_files.into_iter().enumerate() {
+            if Path::new(&file).is_file() {
+                this.open(Some(file.into()));
+                if idx == session_active {
+                    active = Some(this.active);
+                }
+            }
+        }
+        if let Some(active) = active {
+            this.active = active;
+        }
+
+        for file in std::env::args().skip(1) {
             this.open(Some(file.into()));
         }
 
@@ -158,183 +334,476 @@ impl App {
         this
     }
 
-    const NEW: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::N);
+    const LARGE_FILE_THRESHOLD_KEY: &'static str = "large_file_threshold";
+
+    /// Files of the open tabs and the index of the active one among them
+    const SESSION_KEY: &'static str = "session";
+    const DEFAULT_LARGE_FILE_THRESHOLD: u64 = 20;
+
+    /// Registers the default shortcuts to `shortcuts` loaded with the rebound ones
+    fn shortcut_map(mut shortcuts: ShortcutMap<Action>) -> ShortcutMap<Action> {
+        let shortcut = KeyboardShortcut::new;
+        shortcuts
+            .register(Action::New, "New", shortcut(Modifiers::COMMAND, Key::N))
+            .register(
+                Action::NewTab,
+                "New tab",
+                shortcut(Modifiers::COMMAND, Key::T),
+            )
+            .register(
+                Action::CloseTab,
+                "Close tab",
+                shortcut(Modifiers::COMMAND, Key::W),
+            )
+            .register(
+                Action::NextTab,
+                "Next tab",
+                shortcut(Modifiers::CTRL, Key::Tab),
+            )
+            .register(
+                Action::PrevTab,
+                "Previous tab",
+                shortcut(Modifiers::CTRL.plus(Modifiers::SHIFT), Key::Tab),
+            )
+            .register(Action::Open, "Open", shortcut(Modifiers::COMMAND, Key::O))
+            .register(
+                Action::Reopen,
+                "Reopen",
+                shortcut(Modifiers::COMMAND, Key::R),
+            )
+            .register(Action::Save, "Save", shortcut(Modifiers::COMMAND, Key::S))
+            .register(Action::SaveAs, "Save as", shortcut(Modifiers::ALT, Key::S))
+            .register(
+                Action::Search,
+                "Search",
+                shortcut(Modifiers::COMMAND, Key::F),
+            )
+            .register(
+                Action::SearchDown,
+                "Search down",
+                shortcut(Modifiers::COMMAND, Key::Num1),
+            )
+            .register(
+                Action::SearchUp,
+                "Search up",
+                shortcut(Modifiers::COMMAND, Key::Num2),
+            )
+            .register(
+                Action::GotoLine,
+                "Go to line",
+                shortcut(Modifiers::COMMAND, Key::G),
+            );
+        shortcuts
+    }
 
-    const OPEN: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::O);
+    /// Built again once a shortcut is rebound, to show the new one
+    fn command_palette(
+        ctx: &egui::Context,
+        shortcuts: &ShortcutMap<Action>,
+    ) -> CommandPalette<App> {
+        let mut palette = CommandPalette::new();
+        let mut register = |id: &str, title: &str, shortcut: Action, action: fn(&mut App)| {
+            palette.register(
+                Command::new(id, title, move |app: &mut App, _| action(app))
+                    .shortcut(shortcuts.format(ctx, shortcut)),
+            );
+        };
 
-    const REOPEN: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::R);
+        register("new", "New", Action::New, |app| app.new_note());
+        register("new_tab", "New Tab", Action::NewTab, |app| app.new_tab());
+        register("close_tab", "Close Tab", Action::CloseTab, |app| {
+            app.close_tab(app.active)
+        });
+        register("next_tab", "Next Tab", Action::NextTab, |app| {
+            app.cycle_tab(1)
+        });
+        register("prev_tab", "Previous Tab", Action::PrevTab, |app| {
+            app.cycle_tab(-1)
+        });
+        register("open", "Open", Action::Open, |app| app.open(None));
+        register("reopen", "Reopen", Action::Reopen, |app| app.reopen());
+        register("save", "Save", Action::Save, |app| app.save());
+        register("save_as", "Save As", Action::SaveAs, |app| {
+            if let Err(err) = app.save_as() {
+                app.note().borrow_mut().state_msg = err.to_string();
+            }
+        });
+        register("search", "Search", Action::Search, |app| {
+            app.show_search_box = true
+        });
+        register("goto_line", "Go to Line", Action::GotoLine, |app| {
+            app.show_goto_box = true
+        });
 
-    const SAVE: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::S);
+        for (idx, encoding) in codec::supported_encodings().iter().enumerate() {
+            palette.register(Command::new(
+                format!("encoding_{}", encoding.name()),
+                format!("Encoding: {}", encoding.name()),
+                move |app: &mut App, _| app.note().borrow_mut().codec_idx = idx,
+            ));
+        }
 
-    const SAVE_AS: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::ALT, egui::Key::S);
+        palette
+    }
 
-    const SEARCH: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::F);
+    fn process_command_palette(&mut self, ctx: &egui::Context) {
+        if self.confirm.is_open() {
+            return;
+        }
 
-    const SEARCH_DOWN: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Num1);
+        let (has_file, large) = {
+            let note = self.note().borrow();
+            (note.cur_file.is_some(), note.large.is_some())
+        };
+        self.palette.set_enabled("reopen", has_file);
+        self.palette.set_enabled("save", !large);
+        self.palette.set_enabled("save_as", !large);
 
-    const SEARCH_UP: egui::KeyboardShortcut =
-        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Num2);
+        if let Some(action) = self.palette.show(ctx) {
+            action(self, ctx);
+        }
+    }
 
     fn process_inputs(&mut self, ui: &mut egui::Ui) {
-        if self.dialog_cb.is_none() {
-            if ui.input_mut(|i| i.consume_shortcut(&Self::NEW)) {
+        let dropped: Vec<PathBuf> = ui.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .filter_map(|file| file.path.clone())
+                .collect()
+        });
+        for path in dropped {
+            self.open(Some(path));
+        }
+
+        if !self.confirm.is_open() {
+            if self.shortcuts.consume(ui, Action::New) {
                 self.new_note();
             }
 
-            if ui.input_mut(|i| i.consume_shortcut(&Self::OPEN)) {
+            if self.shortcuts.consume(ui, Action::NewTab) {
+                self.new_tab();
+            }
+
+            if self.shortcuts.consume(ui, Action::CloseTab) {
+                self.close_tab(self.active);
+            }
+
+            // before the editor takes `Tab`, and the shift one first since it matches both
+            if self.shortcuts.consume(ui, Action::PrevTab) {
+                self.cycle_tab(-1);
+            }
+
+            if self.shortcuts.consume(ui, Action::NextTab) {
+                self.cycle_tab(1);
+            }
+
+            if self.shortcuts.consume(ui, Action::Open) {
                 self.open(None);
             }
 
-            if ui.input_mut(|i| i.consume_shortcut(&Self::REOPEN)) {
+            if self.shortcuts.consume(ui, Action::Reopen) {
                 self.reopen();
             }
 
-            if ui.input_mut(|i| i.consume_shortcut(&Self::SAVE)) {
+            if self.shortcuts.consume(ui, Action::Save) {
                 self.save();
             }
 
-            if ui.input_mut(|i| i.consume_shortcut(&Self::SAVE_AS))
+            if self.shortcuts.consume(ui, Action::SaveAs)
                 && let Err(err) = self.save_as()
             {
-                self.note.borrow_mut().state_msg = err.to_string();
+                self.note().borrow_mut().state_msg = err.to_string();
             }
 
-            if ui.input_mut(|i| i.consume_shortcut(&Self::SEARCH)) {
+            if self.shortcuts.consume(ui, Action::Search) {
                 self.show_search_box = true;
             }
 
-            if ui.input_mut(|i| i.consume_shortcut(&Self::SEARCH_DOWN)) {
+            if self.shortcuts.consume(ui, Action::SearchDown) {
                 self.search_down = Some(true);
             }
 
-            if ui.input_mut(|i| i.consume_shortcut(&Self::SEARCH_UP)) {
+            if self.shortcuts.consume(ui, Action::SearchUp) {
                 self.search_down = Some(false);
             }
+
+            if self.shortcuts.consume(ui, Action::GotoLine) {
+                self.show_goto_box = true;
+            }
         }
     }
 
+    /// Asks once for all the unsaved tabs, listing them
     fn process_close_request(&mut self, ui: &mut egui::Ui) {
         let ctx = ui.ctx();
-        if ctx.input(|i| i.viewport().close_requested())
-            && self.note.borrow().modified
-            && !self.note.borrow().allow_to_close
-        {
-            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
-            self.set_confirm_dialog(Self::FILE_UNSAVED.to_owned(), {
-                let note = self.note.clone();
-                let ctx = ctx.clone();
-                move |yes| {
-                    if yes {
+        if !ctx.input(|i| i.viewport().close_requested()) {
+            return;
+        }
+
+        let unsaved: Vec<_> = self
+            .notes
+            .iter()
+            .filter(|note| {
+                let note = note.borrow();
+                note.modified && !note.allow_to_close
+            })
+            .cloned()
+            .collect();
+        if unsaved.is_empty() {
+            return;
+        }
+
+        ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        let names: Vec<_> = unsaved
+            .iter()
+            .map(|note| format!("  {}", note.borrow().name()))
+            .collect();
+        let msg = format!(
+            "{} files unsaved:\n{}\nDo you wish to close anyway?",
+            unsaved.len(),
+            names.join("\n")
+        );
+
+        self.confirm.ask(msg, {
+            let ctx = ctx.clone();
+            move |yes| {
+                if yes {
+                    for note in &unsaved {
                         note.borrow_mut().allow_to_close = true;
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
-                    Ok(())
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                 }
-            });
+                Ok(())
+            }
+        });
+    }
+
+    fn process_large_open(&mut self) {
+        if self.confirm.is_open() {
+            return;
         }
+
+        let Some(path) = self.note().borrow_mut().pending_large_open.take() else {
+            return;
+        };
+
+        let msg = format!(
+            "'{}' is larger than {} MB, editing it may be slow.",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            self.large_file_threshold
+        );
+        let note = self.note().clone();
+        self.confirm.open(
+            ConfirmRequest::new(msg, move |read_only| {
+                note.borrow_mut().load(path, read_only)
+            })
+            .buttons(
+                "Open for editing anyway (may be slow)",
+                "Open read-only (fast)",
+            ),
+        );
     }
 
-    fn set_confirm_dialog<F: FnOnce(bool) -> Result<()> + 'static>(&mut self, msg: String, cb: F) {
-        assert!(self.dialog_cb.is_none());
-        self.dialog_cb = Some((msg, Box::new(cb)));
+    fn note(&self) -> &Rc<RefCell<Note>> {
+        &self.notes[self.active]
     }
 
-    fn try_search(&mut self, ui: &mut egui::Ui, id: egui::Id, mut output: TextEditOutput) {
-        if let Some(down) = self.search_down.take()
-            && !self.search_words.is_empty()
-        {
-            let range = output
-                .cursor_range
-                .unwrap_or_default()
-                .as_sorted_char_range();
-
-            let search_result = {
-                let contents = &self.note.borrow().contents;
-                let down_offset = byte_index_from_char_index(contents, range.end);
-                let contents = if down {
-                    &contents[down_offset..]
-                } else {
-                    &contents[..byte_index_from_char_index(contents, range.start)]
-                };
-                let contents = if self.case_sense {
-                    Cow::Borrowed(contents)
-                } else {
-                    Cow::Owned(contents.to_ascii_lowercase())
-                };
-                if down {
-                    contents.find(&self.search_words).map(|v| v + down_offset)
-                } else {
-                    contents.rfind(&self.search_words)
-                }
-            };
+    /// Adds an empty tab after the active one and switches to it
+    fn new_tab(&mut self) {
+        let note = Note {
+            id: self.next_tab_id,
+            ..Default::default()
+        };
+        self.next_tab_id += 1;
 
-            match search_result {
-                Some(new_bi) => {
-                    let mut new_ci = None;
-                    for (ci, (bi, _)) in self.note.borrow().contents.char_indices().enumerate() {
-                        if new_bi == bi {
-                            new_ci = Some(ci);
-                            break;
-                        }
-                    }
+        self.active = (self.active + 1).min(self.notes.len());
+        self.notes.insert(self.active, Rc::new(RefCell::new(note)));
+    }
 
-                    if let Some(new_ci_start) = new_ci {
-                        let new_ci_end = new_ci_start + self.search_words.chars().count();
-                        output.state.cursor.set_char_range(Some(CCursorRange::two(
-                            CCursor::new(new_ci_start),
-                            CCursor::new(new_ci_end),
-                        )));
-                        let primary_cursor_rect = cursor_rect(
-                            &output.galley,
-                            &output.state.cursor.range(&output.galley).unwrap().primary,
-                            ui.fonts(|f| f.row_height(&get_body_font_id(ui))),
-                        );
+    /// Closes the tab at `idx` once it's confirmed if unsaved
+    fn close_tab(&mut self, idx: usize) {
+        let note = self.notes[idx].clone();
+        if !note.borrow().modified {
+            note.borrow_mut().closing = true;
+            return;
+        }
 
-                        ui.scroll_to_rect(
-                            egui::Rect::from_center_size(
-                                primary_cursor_rect.center() + output.galley_pos.to_vec2(),
-                                primary_cursor_rect.size(),
-                            ),
-                            None,
-                        );
-                        ui.ctx().request_repaint();
-                        output.state.store(ui.ctx(), id);
+        // shows the one asked about
+        self.active = idx;
+        self.confirm.ask(Self::FILE_UNSAVED, move |yes| {
+            if yes {
+                note.borrow_mut().closing = true;
+            }
+            Ok(())
+        });
+    }
 
-                        self.note.borrow_mut().state_msg = "Found".to_owned();
-                    }
-                }
-                None => {
-                    self.note.borrow_mut().state_msg = "Search finished".to_owned();
+    fn cycle_tab(&mut self, delta: isize) {
+        let len = self.notes.len() as isize;
+        self.active = (self.active as isize + delta).rem_euclid(len) as usize;
+    }
+
+    fn remove_closed_tabs(&mut self) {
+        if !self.notes.iter().any(|note| note.borrow().closing) {
+            return;
+        }
+
+        let active = self.note().clone();
+        let mut idx = 0;
+        let mut new_active = None;
+        self.notes.retain(|note| {
+            let keep = !note.borrow().closing;
+            if keep {
+                if Rc::ptr_eq(note, &active) {
+                    new_active = Some(idx);
                 }
+                idx += 1;
+            }
+            keep
+        });
+
+        if self.notes.is_empty() {
+            self.active = 0;
+            self.new_tab();
+            self.active = 0;
+            return;
+        }
+
+        // the next one takes the place of the closed active one
+        self.active = new_active.unwrap_or(self.active.min(self.notes.len() - 1));
+    }
+
+    /// Byte range of the next match after `selection`, or before it if not `down`
+    fn find_words(
+        &self,
+        contents: &str,
+        selection: Range<usize>,
+        down: bool,
+    ) -> Option<Range<usize>> {
+        let haystack = if down {
+            &contents[selection.end..]
+        } else {
+            &contents[..selection.start]
+        };
+
+        let (haystack, words) = if self.case_sense {
+            (
+                Cow::Borrowed(haystack),
+                Cow::Borrowed(self.search_words.as_str()),
+            )
+        } else {
+            (
+                Cow::Owned(haystack.to_ascii_lowercase()),
+                Cow::Owned(self.search_words.to_ascii_lowercase()),
+            )
+        };
+
+        let start = if down {
+            haystack.find(words.as_ref())? + selection.end
+        } else {
+            haystack.rfind(words.as_ref())?
+        };
+
+        Some(start..start + self.search_words.len())
+    }
+
+    /// Takes the pending go-to-line or search request, returns the byte range to select
+    fn take_target(
+        &mut self,
+        contents: &str,
+        selection: Range<usize>,
+        state_msg: &mut String,
+    ) -> Option<Range<usize>> {
+        if let Some(line) = self.goto_line.take() {
+            let start = match line.checked_sub(2) {
+                Some(n) => contents
+                    .match_indices('\n')
+                    .nth(n)
+                    .or_else(|| contents.match_indices('\n').last())
+                    .map_or(0, |(i, _)| i + 1),
+                None => 0,
+            };
+            *state_msg = format!("Line {line}");
+            return Some(start..start);
+        }
+
+        if let Some(down) = self.search_down.take()
+            && !self.search_words.is_empty()
+        {
+            let found = self.find_words(contents, selection, down);
+            *state_msg = if found.is_some() {
+                "Found"
+            } else {
+                "Search finished"
             }
+            .to_owned();
+            return found;
         }
+
+        None
+    }
+
+    fn try_select(&mut self, ui: &mut egui::Ui, id: egui::Id, mut output: TextEditOutput) {
+        if self.goto_line.is_none() && self.search_down.is_none() {
+            return;
+        }
+
+        let note = self.note().clone();
+        let note = &mut *note.borrow_mut();
+
+        let range = output
+            .cursor_range
+            .unwrap_or_default()
+            .as_sorted_char_range();
+        let selection = byte_index_from_char_index(&note.contents, range.start)
+            ..byte_index_from_char_index(&note.contents, range.end);
+
+        let Some(target) = self.take_target(&note.contents, selection, &mut note.state_msg) else {
+            return;
+        };
+
+        let new_ci_start = note.contents[..target.start].chars().count();
+        let new_ci_end = new_ci_start + note.contents[target].chars().count();
+        output.state.cursor.set_char_range(Some(CCursorRange::two(
+            CCursor::new(new_ci_start),
+            CCursor::new(new_ci_end),
+        )));
+        let primary_cursor_rect = cursor_rect(
+            &output.galley,
+            &output.state.cursor.range(&output.galley).unwrap().primary,
+            ui.fonts(|f| f.row_height(&get_body_font_id(ui))),
+        );
+
+        ui.scroll_to_rect(
+            egui::Rect::from_center_size(
+                primary_cursor_rect.center() + output.galley_pos.to_vec2(),
+                primary_cursor_rect.size(),
+            ),
+            None,
+        );
+        ui.ctx().request_repaint();
+        output.state.store(ui.ctx(), id);
+        output.response.request_focus();
     }
 }
 
 impl App {
-    fn ui_show_confirm_dialog(&mut self, ui: &mut egui::Ui) {
-        if self.dialog_cb.is_some() {
-            egui::Modal::new(egui::Id::new("Warning")).show(ui.ctx(), |ui| {
-                let str = &self.dialog_cb.as_ref().unwrap().0;
-                ui.label(str);
-                ui.horizontal(|ui| {
-                    let no_res = ui.button("No");
-                    let yes_res = ui.button("Yes");
-                    if no_res.clicked() || yes_res.clicked() {
-                        let (.., cb) = self.dialog_cb.take().unwrap();
-                        if let Err(err) = cb(yes_res.clicked()) {
-                            self.note.borrow_mut().state_msg = err.to_string();
-                        }
-                    }
-                });
+    fn ui_show_shortcuts(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        egui::Window::new("Keyboard shortcuts")
+            .open(&mut self.show_shortcuts)
+            .resizable(false)
+            .show(&ctx, |ui| {
+                if self.shortcuts.ui(ui) {
+                    self.palette = Self::command_palette(ui.ctx(), &self.shortcuts);
+                }
             });
+    }
+
+    fn ui_show_confirm_dialog(&mut self, ui: &mut egui::Ui) {
+        if let Some(Err(err)) = self.confirm.show(ui.ctx()) {
+            self.note().borrow_mut().state_msg = err.to_string();
         }
     }
 
@@ -345,9 +814,9 @@ impl App {
 
             ui.menu_button("File", |ui| {
                 macro_rules! btn {
-                    ($name:literal, $shortcut:expr, $stmt:stmt) => {
+                    ($name:literal, $action:expr, $stmt:stmt) => {
                         let btn = egui::Button::new($name)
-                            .shortcut_text(ui.ctx().format_shortcut($shortcut));
+                            .shortcut_text(self.shortcuts.format(ui.ctx(), $action));
                         if ui.add(btn).clicked() {
                             $stmt
                             ui.close();
@@ -355,24 +824,39 @@ impl App {
                     };
                 }
 
-                btn!("New...", &Self::NEW, self.new_note());
-                btn!("Open...", &Self::OPEN, self.open(None));
-                btn!("ReOpen", &Self::REOPEN, self.reopen());
-                btn!("Save", &Self::SAVE, self.save());
+                btn!("New...", Action::New, self.new_note());
+                btn!("New tab", Action::NewTab, self.new_tab());
+                btn!("Close tab", Action::CloseTab, self.close_tab(self.active));
+                btn!("Open...", Action::Open, self.open(None));
+                btn!("ReOpen", Action::Reopen, self.reopen());
+                btn!("Save", Action::Save, self.save());
                 btn!(
                     "Save as...",
-                    &Self::SAVE_AS,
+                    Action::SaveAs,
                     if let Err(err) = self.save_as() {
-                        self.note.borrow_mut().state_msg = err.to_string();
+                        self.note().borrow_mut().state_msg = err.to_string();
                     }
                 );
-                btn!("Search", &Self::SEARCH, self.show_search_box = true);
+                btn!("Search", Action::Search, self.show_search_box = true);
+                btn!("Go to line...", Action::GotoLine, self.show_goto_box = true);
+
+                if ui.button("Keyboard shortcuts...").clicked() {
+                    self.show_shortcuts = true;
+                    ui.close();
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Large file (MB)");
+                    ui.add(egui::DragValue::new(&mut self.large_file_threshold).range(1..=4096))
+                        .on_hover_text("Files larger than this are offered to open read-only");
+                });
             });
 
             ui.painter().text(
                 title_bar_rect.center(),
                 egui::Align2::CENTER_CENTER,
-                &self.note.borrow().title,
+                &self.note().borrow().title,
                 get_body_font_id(ui),
                 ui.style().visuals.text_color(),
             );
@@ -382,6 +866,10 @@ impl App {
     fn ui_contents(&mut self, ui: &mut egui::Ui) {
         ui.set_clip_rect(ui.max_rect());
 
+        egui::TopBottomPanel::top("tab_bar")
+            .frame(egui::Frame::side_top_panel(ui.style()).fill(Color32::TRANSPARENT))
+            .show_inside(ui, |ui| self.ui_tab_bar(ui));
+
         egui::TopBottomPanel::bottom("bottom_panel")
             .exact_height(get_button_height(ui) + 16.0)
             .frame(egui::Frame::side_top_panel(ui.style()).fill(Color32::TRANSPARENT))
@@ -390,52 +878,179 @@ impl App {
         egui::CentralPanel::default()
             .frame(egui::Frame::central_panel(ui.style()).fill(ui.style().visuals.extreme_bg_color))
             .show_inside(ui, |ui| {
-                egui::ScrollArea::vertical().show(ui, |ui| {
-                    let rect = ui.max_rect();
-                    ui.scope_builder(UiBuilder::new().max_rect(rect), |ui| {
-                        ui.with_layout(
-                            egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                            |ui| {
-                                let id = ui.make_persistent_id("text_edit");
-                                let output =
-                                    egui::TextEdit::multiline(&mut self.note.borrow_mut().contents)
-                                        .frame(false)
-                                        .margin(Margin::ZERO)
-                                        .code_editor()
-                                        .id(id)
-                                        .show(ui);
-
-                                if output.response.changed() && !self.note.borrow().modified {
-                                    self.note.borrow_mut().modified = true;
-                                    self.note.borrow_mut().update_title();
-                                }
+                if self.note().borrow().large.is_some() {
+                    self.ui_large_contents(ui);
+                    return;
+                }
 
-                                if output.response.dragged() {
-                                    let pointer = ui.input(|i| i.pointer.clone());
-                                    if let Some(mouse_pos) = pointer.interact_pos() {
-                                        ui.scroll_to_rect(
-                                            Rect::from_min_max(mouse_pos, mouse_pos),
-                                            None,
-                                        );
+                let tab_id = self.note().borrow().id;
+                egui::ScrollArea::vertical()
+                    .id_salt(("contents", tab_id))
+                    .show(ui, |ui| {
+                        let rect = ui.max_rect();
+                        ui.scope_builder(UiBuilder::new().max_rect(rect), |ui| {
+                            ui.with_layout(
+                                egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
+                                |ui| {
+                                    let id = ui.make_persistent_id(("text_edit", tab_id));
+                                    let output = egui::TextEdit::multiline(
+                                        &mut self.note().borrow_mut().contents,
+                                    )
+                                    .frame(false)
+                                    .margin(Margin::ZERO)
+                                    .code_editor()
+                                    .id(id)
+                                    .show(ui);
+
+                                    if output.response.changed() && !self.note().borrow().modified {
+                                        self.note().borrow_mut().modified = true;
+                                        self.note().borrow_mut().update_title();
                                     }
-                                }
 
-                                self.try_search(ui, id, output);
-                            },
-                        );
+                                    if output.response.dragged() {
+                                        let pointer = ui.input(|i| i.pointer.clone());
+                                        if let Some(mouse_pos) = pointer.interact_pos() {
+                                            ui.scroll_to_rect(
+                                                Rect::from_min_max(mouse_pos, mouse_pos),
+                                                None,
+                                            );
+                                        }
+                                    }
+
+                                    self.try_select(ui, id, output);
+                                },
+                            );
+                        });
                     });
+            });
+    }
+
+    fn ui_tab_bar(&mut self, ui: &mut egui::Ui) {
+        let mut close = None;
+        let mut new_tab = false;
+        let close_shortcut = self.shortcuts.format(ui.ctx(), Action::CloseTab);
+
+        egui::ScrollArea::horizontal()
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.visuals_mut().button_frame = false;
+
+                    for (idx, note) in self.notes.iter().enumerate() {
+                        let (name, path) = {
+                            let note = note.borrow();
+                            let path = note.get_path().map(|path| path.display().to_string());
+                            (note.tab_name(), path)
+                        };
+
+                        let res = ui
+                            .selectable_label(idx == self.active, name)
+                            .on_hover_text(path.unwrap_or_else(|| "(Untitled)".to_owned()));
+                        if res.clicked() {
+                            self.active = idx;
+                        }
+                        if res.middle_clicked() {
+                            close = Some(idx);
+                        }
+
+                        if ui
+                            .small_button(ICON_CLOSE.to_string())
+                            .on_hover_text(format!("Close ({close_shortcut})"))
+                            .clicked()
+                        {
+                            close = Some(idx);
+                        }
+                    }
+
+                    if ui
+                        .small_button(ICON_ADD.to_string())
+                        .on_hover_text(format!(
+                            "New tab ({})",
+                            self.shortcuts.format(ui.ctx(), Action::NewTab)
+                        ))
+                        .clicked()
+                    {
+                        new_tab = true;
+                    }
                 });
             });
+
+        if let Some(idx) = close {
+            self.close_tab(idx);
+        }
+        if new_tab {
+            self.new_tab();
+        }
+    }
+
+    fn ui_large_contents(&mut self, ui: &mut egui::Ui) {
+        let note = self.note().clone();
+        let note = &mut *note.borrow_mut();
+        let Note {
+            id,
+            contents,
+            state_msg,
+            large,
+            ..
+        } = note;
+        let large = large.as_mut().unwrap();
+
+        let selection = large.selection.clone().unwrap_or_default();
+        let target = self.take_target(contents, selection, state_msg);
+
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        let row_height = ui.fonts(|f| f.row_height(&font_id));
+        ui.spacing_mut().item_spacing.y = 0.0;
+
+        let mut scroll_area = egui::ScrollArea::both()
+            .id_salt(("large_contents", *id))
+            .auto_shrink(false);
+        if let Some(target) = target {
+            let line = large.line_of(target.start);
+            let offset = line as f32 * row_height - ui.available_height() / 2.0;
+            scroll_area = scroll_area.vertical_scroll_offset(offset.max(0.0));
+            large.selection = Some(target);
+            ui.ctx().request_repaint();
+        }
+
+        let color = ui.visuals().text_color();
+        let selection_color = ui.visuals().selection.bg_fill;
+        scroll_area.show_rows(ui, row_height, large.line_starts.len(), |ui, rows| {
+            for line in rows {
+                let range = large.line_range(line, contents);
+                let text = &contents[range.clone()];
+                let format = egui::TextFormat::simple(font_id.clone(), color);
+
+                let mut job = egui::text::LayoutJob::default();
+                match &large.selection {
+                    Some(sel) if sel.start <= range.end && sel.end >= range.start => {
+                        let start = sel.start.max(range.start) - range.start;
+                        let end = sel.end.min(range.end) - range.start;
+                        job.append(&text[..start], 0.0, format.clone());
+                        job.append(
+                            &text[start..end],
+                            0.0,
+                            egui::TextFormat {
+                                background: selection_color,
+                                ..format.clone()
+                            },
+                        );
+                        job.append(&text[end..], 0.0, format);
+                    }
+                    _ => job.append(text, 0.0, format),
+                }
+
+                ui.add(egui::Label::new(job).extend());
+            }
+        });
     }
 
     fn ui_bottom_panel(&mut self, ui: &mut egui::Ui) {
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-            egui::ComboBox::from_id_salt("codec").show_index(
-                ui,
-                &mut self.note.borrow_mut().codec_idx,
-                codec::supported_encodings().len(),
-                |i| codec::supported_encodings()[i].name(),
-            );
+            SearchableCombo::new("codec", codec::supported_encodings().len(), |i| {
+                codec::supported_encodings()[i].name().to_owned()
+            })
+            .show(ui, &mut self.note().borrow_mut().codec_idx);
 
             ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
                 if theme_button(ui, get_theme_button(ui)).clicked() {
@@ -447,7 +1062,7 @@ impl App {
                 }
 
                 ui.set_clip_rect(ui.max_rect());
-                ui.label(&self.note.borrow().state_msg);
+                ui.label(&self.note().borrow().state_msg);
             });
         });
     }
@@ -457,7 +1072,7 @@ impl App {
             .auto_sized()
             .open(&mut self.show_search_box)
             .show(ui.ctx(), |ui| {
-                ui.add_enabled_ui(self.dialog_cb.is_none(), |ui| {
+                ui.add_enabled_ui(!self.confirm.is_open(), |ui| {
                     ui.text_edit_singleline(&mut self.search_words);
                     ui.horizontal(|ui| {
                         ui.checkbox(&mut self.case_sense, "case sense");
@@ -465,21 +1080,50 @@ impl App {
                         ui.label(format!(
                             " {}[{}] {}[{}]",
                             ICON_TRIANGLE_DOWN,
-                            ui.ctx().format_shortcut(&Self::SEARCH_DOWN),
+                            self.shortcuts.format(ui.ctx(), Action::SearchDown),
                             ICON_TRIANGLE_UP,
-                            ui.ctx().format_shortcut(&Self::SEARCH_UP)
+                            self.shortcuts.format(ui.ctx(), Action::SearchUp)
                         ));
                     });
                 });
             });
     }
+
+    fn ui_show_goto_box(&mut self, ui: &mut egui::Ui) {
+        egui::Window::new("Go to Line")
+            .auto_sized()
+            .open(&mut self.show_goto_box)
+            .show(ui.ctx(), |ui| {
+                ui.add_enabled_ui(!self.confirm.is_open(), |ui| {
+                    ui.horizontal(|ui| {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(&mut self.goto_words)
+                                .hint_text("Line number")
+                                .desired_width(120.0),
+                        );
+                        let entered =
+                            response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+                        if ui.button("Go").clicked() || entered {
+                            match self.goto_words.trim().parse::<usize>() {
+                                Ok(line) if line > 0 => self.goto_line = Some(line),
+                                _ => {
+                                    self.note().borrow_mut().state_msg =
+                                        "Invalid line number".to_owned()
+                                }
+                            }
+                        }
+                    });
+                });
+            });
+    }
 }
 
 macro_rules! confirm_dialog_or_calling {
     ($self:expr, $note:ident, $block:block) => {
         #[allow(unused_mut)]
         let mut cb = {
-            let $note = $self.note.clone();
+            let $note = $self.note().clone();
             move |yes: bool| {
                 if yes {
                     $block
@@ -488,13 +1132,13 @@ macro_rules! confirm_dialog_or_calling {
             }
         };
 
-        if $self.note.borrow().modified {
-            $self.set_confirm_dialog(Self::FILE_UNSAVED.to_owned(), cb);
+        if $self.note().borrow().modified {
+            $self.confirm.ask(Self::FILE_UNSAVED, cb);
             return;
         }
 
         if let Err(err) = cb(true) {
-            $self.note.borrow_mut().state_msg = err.to_string();
+            $self.note().borrow_mut().state_msg = err.to_string();
         }
     };
 }
@@ -503,11 +1147,14 @@ impl App {
     const FILE_UNSAVED: &'static str = "File unsaved, Do you wish to continue?";
     const FILE_HAS_MODIFIED: &'static str =
         "File has been modified since the last access, Do you wish to continue?";
+    const SAVE_DISABLED: &'static str =
+        "Save is disabled in large-file mode, open the file for editing to save it";
 
     fn new_note(&mut self) {
         confirm_dialog_or_calling!(self, note, {
             let note = &mut *note.borrow_mut();
             note.contents.clear();
+            note.large = None;
             note.cur_file = None;
             note.modified = false;
             note.update_title();
@@ -515,38 +1162,41 @@ impl App {
         });
     }
 
-    fn open(&mut self, mut path: Option<std::path::PathBuf>) {
-        confirm_dialog_or_calling!(self, note, {
-            if path.is_none()
-                && let Some(open_path) =
-                    rfd::FileDialog::new().add_filter("*", &["txt"]).pick_file()
-            {
-                path = Some(open_path);
-            }
+    /// Opens a file in a new tab, or switches to the tab it's already opened in
+    fn open(&mut self, path: Option<std::path::PathBuf>) {
+        let Some(path) =
+            path.or_else(|| rfd::FileDialog::new().add_filter("*", &["txt"]).pick_file())
+        else {
+            return;
+        };
 
-            if let Some(path) = path {
-                let last_modified_time = Note::get_modified_time(&path)?;
-                let (contents, codec_idx) = Note::read_from_file(&path, None)?;
+        if let Some(idx) = self.notes.iter().position(|note| {
+            let note = note.borrow();
+            note.get_path().or(note.pending_large_open.as_deref()) == Some(path.as_path())
+        }) {
+            self.active = idx;
+            return;
+        }
 
+        if !self.note().borrow().is_blank() {
+            self.new_tab();
+        }
+
+        let threshold = self.large_file_threshold * 1024 * 1024;
+        let note = self.note().clone();
+        eapp_utils::capture_error!(err => note.borrow_mut().state_msg = err.to_string(), {
+            if std::fs::metadata(&path)?.len() > threshold {
                 let note = &mut *note.borrow_mut();
-                note.contents = contents;
-                note.codec_idx = codec_idx;
-                note.cur_file = Some(File {
-                    path,
-                    last_modified_time,
-                });
-                note.modified = false;
+                note.pending_large_open = Some(path);
                 note.update_title();
-                note.state_msg = format!(
-                    "Open successfully (Encoding: {})",
-                    codec::supported_encodings()[codec_idx].name()
-                );
+            } else {
+                note.borrow_mut().load(path, false)?;
             }
         });
     }
 
     fn reopen(&mut self) {
-        if self.note.borrow().cur_file.is_none() {
+        if self.note().borrow().cur_file.is_none() {
             return;
         }
 
@@ -556,6 +1206,9 @@ impl App {
             let last_modified_time = Note::get_modified_time(path)?;
             let (contents, codec_idx) = Note::read_from_file(path, Some(note.codec_idx))?;
 
+            if let Some(large) = &mut note.large {
+                *large = LargeFile::new(&contents);
+            }
             note.contents = contents;
             note.codec_idx = codec_idx;
             note.cur_file.as_mut().unwrap().last_modified_time = last_modified_time;
@@ -569,10 +1222,15 @@ impl App {
     }
 
     fn save(&mut self) {
-        if self.note.borrow().cur_file.is_none() {
-            eapp_utils::capture_error!(err => self.note.borrow_mut().state_msg = err.to_string(), {
+        if self.note().borrow().large.is_some() {
+            self.note().borrow_mut().state_msg = Self::SAVE_DISABLED.to_owned();
+            return;
+        }
+
+        if self.note().borrow().cur_file.is_none() {
+            eapp_utils::capture_error!(err => self.note().borrow_mut().state_msg = err.to_string(), {
                 let path = self.save_as()?;
-                let note = &mut *self.note.borrow_mut();
+                let note = &mut *self.note().borrow_mut();
                 let last_modified_time = Note::get_modified_time(&path)?;
                 note.cur_file = Some(File {
                     path,
@@ -586,7 +1244,7 @@ impl App {
         }
 
         let cb = {
-            let note = self.note.clone();
+            let note = self.note().clone();
             move |yes: bool| {
                 if yes {
                     let note = &mut *note.borrow_mut();
@@ -604,7 +1262,7 @@ impl App {
         };
 
         let show_dialog = {
-            let cur_file = &self.note.borrow().cur_file;
+            let cur_file = &self.note().borrow().cur_file;
             let File {
                 path,
                 last_modified_time,
@@ -618,18 +1276,22 @@ impl App {
         };
 
         if show_dialog {
-            self.set_confirm_dialog(Self::FILE_HAS_MODIFIED.to_owned(), cb);
+            self.confirm.ask(Self::FILE_HAS_MODIFIED, cb);
             return;
         }
 
         if let Err(err) = cb(true) {
-            self.note.borrow_mut().state_msg = err.to_string();
+            self.note().borrow_mut().state_msg = err.to_string();
         }
     }
 
     fn save_as(&self) -> Result<std::path::PathBuf> {
+        if self.note().borrow().large.is_some() {
+            return Err(Self::SAVE_DISABLED.into());
+        }
+
         if let Some(save_path) = rfd::FileDialog::new().save_file() {
-            self.note.borrow().write_to_file(&save_path)?;
+            self.note().borrow().write_to_file(&save_path)?;
             return Ok(save_path);
         }
 
@@ -649,6 +1311,26 @@ impl eframe::App for App {
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, UiFontSelector::KEY, &self.selector);
+        eframe::set_value(storage, ShortcutMap::<Action>::KEY, &self.shortcuts);
+        self.window.save(storage);
+        eframe::set_value(
+            storage,
+            Self::LARGE_FILE_THRESHOLD_KEY,
+            &self.large_file_threshold,
+        );
+
+        let mut active = 0;
+        let mut files = Vec::new();
+        for (idx, note) in self.notes.iter().enumerate() {
+            let note = note.borrow();
+            if let Some(path) = note.get_path().or(note.pending_large_open.as_deref()) {
+                if idx == self.active {
+                    active = files.len();
+                }
+                files.push(path.to_string_lossy().into_owned());
+            }
+        }
+        eframe::set_value(storage, Self::SESSION_KEY, &(files, active));
     }
 
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
@@ -671,8 +1353,11 @@ impl eframe::App for App {
             }
             .shrink2(Vec2::new(1.5, 1.0));
 
+            self.remove_closed_tabs();
             self.process_close_request(ui);
+            self.process_large_open();
             self.process_inputs(ui);
+            self.process_command_palette(ctx);
 
             self.ui_title_bar(ui, title_bar_rect);
             self.ui_contents(
@@ -680,6 +1365,8 @@ impl eframe::App for App {
             );
 
             self.ui_show_search_box(ui);
+            self.ui_show_goto_box(ui);
+            self.ui_show_shortcuts(ui);
             self.ui_show_confirm_dialog(ui);
         });
     }
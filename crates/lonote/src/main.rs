@@ -4,7 +4,7 @@ pub(crate) mod app;
 pub(crate) mod codec;
 
 fn main() {
-    eapp_utils::setup_loggers("lonote.log").unwrap();
+    eapp_utils::setup_loggers("lonote.log", eapp_utils::LogOptions::from_env()).unwrap();
 
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
@@ -18,6 +18,7 @@ fn main() {
                 ))
                 .unwrap(),
             ),
+        persist_window: false,
         ..Default::default()
     };
 
@@ -1,19 +1,33 @@
 use crate::{
-    img_finder::ImgFinder,
-    img_utils::{ImgTranslation, InitialScalingMode, LastImageInfo},
+    img_finder::{ImageFilter, ImgFinder},
+    img_utils::{
+        ColorAdjust, DisplayFilter, FileInfo, ImgTranslation, InitialScalingMode, LastImageInfo,
+        spread_partner, spread_start,
+    },
+    pdf_export::{self, ExportReport},
+    recent_dirs::{self, RecentDirs},
     tex_loader::TexLoader,
 };
 use eapp_utils::{
     borderless,
     codicons::{
-        ICON_COFFEE, ICON_FOLDER, ICON_GO_TO_FILE, ICON_INSPECT, ICON_NEW_FILE, ICON_REFRESH,
-        ICON_SCREEN_FULL, ICON_SCREEN_NORMAL, ICON_TRIANGLE_LEFT, ICON_TRIANGLE_RIGHT,
+        ICON_ARROW_BOTH, ICON_ARROW_LEFT, ICON_ARROW_RIGHT, ICON_ARROW_SWAP, ICON_BOOK,
+        ICON_COFFEE, ICON_COLOR_MODE, ICON_DEVICE_DESKTOP, ICON_FILE_PDF, ICON_FILTER,
+        ICON_FILTER_FILLED, ICON_FOLDER, ICON_GO_TO_FILE, ICON_INFO, ICON_INSPECT,
+        ICON_LAYOUT_PANEL_JUSTIFY, ICON_LIGHTBULB, ICON_NEW_FILE, ICON_REFRESH, ICON_SCREEN_FULL,
+        ICON_SCREEN_NORMAL, ICON_TRASH, ICON_TRIANGLE_LEFT, ICON_TRIANGLE_RIGHT, ICON_UNFOLD,
     },
+    debounced::Debounced,
+    dnd::{DropCategory, DropHandler, DroppedItem},
     get_body_font_id, get_body_text_size, get_button_height,
+    persistent_window::PersistentWindow,
+    platform::WallpaperFit,
     task::Task,
     ui_font_selector::UiFontSelector,
     waker::{WakeType, Waker},
     widgets::{
+        color_picker::ColorButton,
+        confirm::Confirm,
         progress_bar::{ProgressBar, draw_progress_bar_background, value_from_x},
         simple_widgets::{
             PlainButton, get_theme_button, text_in_center_bottom_of_rect, theme_button,
@@ -25,14 +39,44 @@ use eframe::egui::{
     vec2,
 };
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 #[derive(Deserialize, Serialize)]
 #[serde(default)]
 struct State {
     search_key: String,
+    /// `search_key` once typing pauses
+    #[serde(skip)]
+    search_filter: Debounced<String>,
     left_panel_open: bool,
     initial_scaling_mode: InitialScalingMode,
+    image_filter: ImageFilter,
+    wallpaper_fit: WallpaperFit,
+    display_filter: DisplayFilter,
+    /// Colors of the pages out of night mode
+    color_adjust: ColorAdjust,
+    /// Colors of the pages in night mode
+    night_adjust: ColorAdjust,
+    night_mode: bool,
+    /// Pages loaded ahead on each side of the current one
+    preload_radius: usize,
+    /// Two pages side by side, as printed manga is laid out
+    spread: bool,
+    /// Pages are read from right to left, the earlier page of a spread is on the right
+    right_to_left: bool,
+    /// Images flipped horizontally, by path, the ones gone are dropped on start
+    mirrored_images: HashSet<String>,
+    recent_dirs: RecentDirs,
+    /// Color around the image, the theme one if `None`
+    background: Option<Color32>,
+    /// The wheel turns pages instead of zooming while the image fits the view
+    wheel_turns_pages: bool,
+    /// Time of the last wheel event, a page is turned only after the wheel rests
+    #[serde(skip)]
+    last_wheel_time: f64,
+    /// Message shown in the info bar and the time to hide it
+    #[serde(skip)]
+    info_message: Option<(String, f64)>,
     #[serde(skip)]
     last_image_info: Option<LastImageInfo>,
     #[serde(skip)]
@@ -49,14 +93,33 @@ struct State {
     last_time_pointer_in_info_rect: f64,
     #[serde(skip)]
     scroll_to_current: bool,
+    /// Input of the jump-to-page modal, which is shown if some
+    #[serde(skip)]
+    goto_page: Option<String>,
 }
 
 impl Default for State {
     fn default() -> Self {
         Self {
             search_key: String::default(),
+            search_filter: Debounced::new(String::default()).with_delay(App::FILTER_DELAY),
             left_panel_open: true,
             initial_scaling_mode: InitialScalingMode::default(),
+            image_filter: ImageFilter::default(),
+            wallpaper_fit: WallpaperFit::default(),
+            display_filter: DisplayFilter::default(),
+            color_adjust: ColorAdjust::default(),
+            night_adjust: ColorAdjust::NIGHT,
+            night_mode: false,
+            preload_radius: 3,
+            spread: false,
+            right_to_left: false,
+            mirrored_images: HashSet::new(),
+            recent_dirs: RecentDirs::default(),
+            background: None,
+            wheel_turns_pages: false,
+            last_wheel_time: 0.0,
+            info_message: None,
             last_image_info: None,
             is_cur_image_loading: true,
             last_cur_dir: None,
@@ -65,6 +128,7 @@ impl Default for State {
             pointer_in_info_rect: false,
             last_time_pointer_in_info_rect: 0.0,
             scroll_to_current: false,
+            goto_page: None,
         }
     }
 }
@@ -76,22 +140,61 @@ pub struct App {
     tex_loader: TexLoader,
     translation: ImgTranslation,
     search_task: Option<Task<Option<ImgFinder>>>,
+    /// Reads the dimensions the image filter needs, see [`ImgFinder::unprobed`]
+    probe_task: Option<Task<Vec<(String, Option<(u32, u32)>)>>>,
+    /// Sizes the pages to export before asking, answered with them and their total size
+    export_estimate_task: Option<Task<(Vec<String>, u64)>>,
+    export_task: Option<Task<std::io::Result<ExportReport>>>,
+    /// Answered with the pages to export
+    export_confirm: Confirm<Option<Vec<String>>>,
+    /// Pages failed in the last export, shown until dismissed
+    export_failures: Vec<(String, String)>,
     search_list: VecDeque<String>,
     selector: UiFontSelector,
+    window: PersistentWindow,
+    drop_handler: DropHandler,
+    /// Of the current image, shown in the info bar
+    file_info: Option<FileInfo>,
+    /// Indices of the directories matching the search key, found again once the key
+    /// or the directories change
+    matched_dirs: Option<Vec<usize>>,
 }
 
 impl App {
+    const FILTER_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
+    /// Textures kept besides the preloaded ones, for the pages previewed on the progress bar
+    const EXTRA_TEXTURES: usize = 16;
+
+    const MAX_PRELOAD_RADIUS: usize = 32;
+
+    /// Rest of the wheel between two page turns, so that one notch turns one page
+    const WHEEL_TURN_INTERVAL: f64 = 0.15;
+
+    const GOTO_PAGE: egui::KeyboardShortcut =
+        egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::G);
+
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         cc.egui_ctx.style_mut(|style| style.animation_time = 0.11);
 
-        let state = if let Some(storage) = cc.storage {
+        let mut state: State = if let Some(storage) = cc.storage {
             eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default()
         } else {
             State::default()
         };
+        state.search_filter.set_now(state.search_key.clone());
+        state
+            .mirrored_images
+            .retain(|path| std::path::Path::new(path).is_file());
         let waker = Waker::new(cc.egui_ctx.clone(), WakeType::WakeOnLongestDeadLine);
         let img_finder = ImgFinder::new();
-        let tex_loader = TexLoader::new(&cc.egui_ctx);
+        let mut tex_loader = TexLoader::new(&cc.egui_ctx);
+        tex_loader.set_filter(state.display_filter);
+        tex_loader.set_adjust(if state.night_mode {
+            state.night_adjust
+        } else {
+            state.color_adjust
+        });
         let translation = ImgTranslation::default();
         let search_task = None;
         let search_list: VecDeque<_> = std::env::args().skip(1).collect();
@@ -109,8 +212,18 @@ impl App {
             tex_loader,
             translation,
             search_task,
+            probe_task: None,
+            export_estimate_task: None,
+            export_task: None,
+            export_confirm: Confirm::new(),
+            export_failures: Vec::new(),
             search_list,
             selector,
+            window: PersistentWindow::new(cc),
+            drop_handler: DropHandler::new(&[DropCategory::Image, DropCategory::Directory])
+                .extensions(DropCategory::Image, &ImgFinder::supported_exts()),
+            file_info: None,
+            matched_dirs: None,
         };
 
         this.rebuild_fonts(&cc.egui_ctx);
@@ -130,8 +243,9 @@ impl App {
             }
 
             let (cancel_sender, cancel_receiver) = std::sync::mpsc::channel();
-            let task = Task::new(cancel_sender, move || {
-                match ImgFinder::from_search(&canonicalized_path, cancel_receiver) {
+            let task = Task::with_progress(cancel_sender, move |progress_sender| {
+                match ImgFinder::from_search(&canonicalized_path, cancel_receiver, progress_sender)
+                {
                     Ok(finder) => Some(finder),
                     Err(err) => {
                         log::error!("load from path '{path}' fails: {err}");
@@ -144,6 +258,42 @@ impl App {
         }
     }
 
+    fn try_get_probe_result(&mut self, ctx: &egui::Context) {
+        if self.probe_task.as_ref().is_some_and(Task::is_finished) {
+            match self.probe_task.take().unwrap().get_result() {
+                Ok(dimensions) => self.img_finder.set_dimensions(dimensions),
+                Err(_) => log::error!("Probe thread panicked"),
+            }
+        }
+
+        if self.probe_task.is_some() {
+            return;
+        }
+
+        let unprobed = self.img_finder.unprobed();
+        if unprobed.is_empty() {
+            return;
+        }
+
+        let ctx = ctx.clone();
+        let (cancel_sender, cancel_receiver) = std::sync::mpsc::channel();
+        self.probe_task = Some(Task::new(cancel_sender, move || {
+            let mut dimensions = Vec::with_capacity(unprobed.len());
+            for image in unprobed {
+                if cancel_receiver.try_recv().is_ok() {
+                    break;
+                }
+
+                // Only the header is read, images are not decoded
+                let size = image::image_dimensions(&image).ok();
+                dimensions.push((image, size));
+            }
+
+            ctx.request_repaint();
+            dimensions
+        }));
+    }
+
     fn is_searching(&self) -> bool {
         self.search_task.is_some()
     }
@@ -159,7 +309,11 @@ impl App {
         }
 
         match self.search_task.take().unwrap().get_result() {
-            Ok(Some(finder)) => self.img_finder = finder,
+            Ok(Some(mut finder)) => {
+                finder.set_filter(self.state.image_filter);
+                self.img_finder = finder;
+                self.matched_dirs = None;
+            }
             Err(_) => log::error!("Search thread panicked"),
             _ => (),
         }
@@ -188,16 +342,459 @@ impl App {
         });
     }
 
+    fn set_wallpaper(&mut self, ctx: &egui::Context) {
+        let Some(cur_image_name) = self.img_finder.cur_image_name() else {
+            return;
+        };
+
+        let message = match eapp_utils::platform::set_wallpaper(
+            std::path::Path::new(cur_image_name),
+            self.state.wallpaper_fit,
+        ) {
+            Ok(_) => "Wallpaper set".to_owned(),
+            Err(err) => {
+                log::error!("set wallpaper fails: {err}");
+                format!("Set wallpaper fails: {err}")
+            }
+        };
+
+        self.show_info_message(ctx, message);
+    }
+
+    /// Keeps the info bar shown with the message for a while
+    fn show_info_message(&mut self, ctx: &egui::Context, message: String) {
+        let current_time = ctx.input(|i| i.time);
+        self.state.info_message = Some((message, current_time + 4.0));
+        self.state.pointer_in_info_rect = true;
+        self.state.last_time_pointer_in_info_rect = current_time + 2.0;
+        self.waker.request_repaint_after_secs(4.5);
+    }
+
+    /// Asks to export the pages of the current directory, in reading order,
+    /// once they are sized on a thread
+    fn ask_export_pdf(&mut self, ctx: &egui::Context) {
+        let pages: Vec<String> = self.img_finder.image_iter().cloned().collect();
+        if pages.is_empty() || self.export_estimate_task.is_some() {
+            return;
+        }
+
+        let ctx = ctx.clone();
+        let (cancel_sender, _) = std::sync::mpsc::channel();
+        self.export_estimate_task = Some(Task::new(cancel_sender, move || {
+            let size = pdf_export::estimate_size(&pages);
+            ctx.request_repaint();
+            (pages, size)
+        }));
+    }
+
+    fn try_get_export_estimate(&mut self) {
+        if !self
+            .export_estimate_task
+            .as_ref()
+            .is_some_and(|task| task.is_finished())
+        {
+            return;
+        }
+
+        let Ok((pages, size)) = self.export_estimate_task.take().unwrap().get_result() else {
+            log::error!("Export estimate thread panicked");
+            return;
+        };
+
+        let message = format!(
+            "Export {} pages to PDF, about {}?",
+            pages.len(),
+            eapp_utils::format_size(size)
+        );
+        self.export_confirm
+            .ask(message, move |yes| yes.then_some(pages));
+    }
+
+    fn start_export_pdf(&mut self, pages: Vec<String>) {
+        let file_name = self
+            .img_finder
+            .cur_dir_name()
+            .and_then(|dir| std::path::Path::new(dir).file_name())
+            .map(|name| format!("{}.pdf", name.to_string_lossy()))
+            .unwrap_or_else(|| "pages.pdf".to_owned());
+
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("PDF", &["pdf"])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+
+        let (cancel_sender, cancel_receiver) = std::sync::mpsc::channel();
+        let task = Task::with_progress(cancel_sender, move |progress_sender| {
+            pdf_export::export(&pages, &path, cancel_receiver, progress_sender)
+        });
+        self.export_task = Some(task);
+    }
+
+    fn try_get_export_result(&mut self, ctx: &egui::Context) {
+        if !self
+            .export_task
+            .as_ref()
+            .is_some_and(|task| task.is_finished())
+        {
+            return;
+        }
+
+        let message = match self.export_task.take().unwrap().get_result() {
+            Ok(Ok(report)) if report.cancelled => "PDF export cancelled".to_owned(),
+            Ok(Ok(report)) if report.written == 0 => "No page can be exported".to_owned(),
+            Ok(Ok(report)) => {
+                let message = if report.failed.is_empty() {
+                    format!("Exported {} pages to PDF", report.written)
+                } else {
+                    format!(
+                        "Exported {} pages to PDF, {} failed",
+                        report.written,
+                        report.failed.len()
+                    )
+                };
+                self.export_failures = report.failed;
+                message
+            }
+            Ok(Err(err)) => {
+                log::error!("export pdf fails: {err}");
+                format!("Export to PDF fails: {err}")
+            }
+            Err(_) => {
+                log::error!("Export thread panicked");
+                "Export to PDF fails".to_owned()
+            }
+        };
+        self.show_info_message(ctx, message);
+    }
+
+    fn ui_show_export_modals(&mut self, ui: &mut egui::Ui) {
+        if let Some(Some(pages)) = self.export_confirm.show(ui.ctx()) {
+            self.start_export_pdf(pages);
+        }
+
+        if let Some(task) = self.export_task.as_mut() {
+            egui::Modal::new(egui::Id::new("Exporting")).show(ui.ctx(), |ui| {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Exporting to PDF...");
+                    if ui.button("Cancel").clicked() {
+                        task.cancel();
+                    }
+                });
+
+                if let Some(progress) = task.progress() {
+                    ui.add(
+                        egui::ProgressBar::new(progress.fraction().unwrap_or_default())
+                            .desired_width(240.0)
+                            .text(format!(
+                                "{} / {}",
+                                progress.done,
+                                progress.total.unwrap_or_default()
+                            )),
+                    );
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(&progress.msg).weak())
+                            .wrap_mode(egui::TextWrapMode::Truncate),
+                    );
+                }
+            });
+        }
+
+        if !self.export_failures.is_empty() {
+            let modal = egui::Modal::new(egui::Id::new("Export failures")).show(ui.ctx(), |ui| {
+                ui.set_max_width(400.0);
+                ui.label(format!(
+                    "{} pages are skipped since they can't be read:",
+                    self.export_failures.len()
+                ));
+
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        for (page, err) in &self.export_failures {
+                            let name = std::path::Path::new(page)
+                                .file_name()
+                                .map(|name| name.to_string_lossy())
+                                .unwrap_or_default();
+                            ui.add(
+                                egui::Label::new(format!("{name}: {err}"))
+                                    .wrap_mode(egui::TextWrapMode::Truncate),
+                            )
+                            .on_hover_text(page);
+                        }
+                    });
+
+                ui.vertical_centered(|ui| ui.button("OK").clicked()).inner
+            });
+
+            if modal.inner || modal.should_close() {
+                self.export_failures.clear();
+            }
+        }
+    }
+
+    /// Shows the page at `page`, clamped to the pages, and preloads the ones around it
+    fn go_to_page(&mut self, page: usize) {
+        let total_pages = self.img_finder.cur_image_set().0.len();
+        if total_pages == 0 {
+            return;
+        }
+
+        let page = page.min(total_pages - 1);
+        self.img_finder.set_cur_image_idx(page);
+
+        let radius = self.state.preload_radius;
+        for page in page.saturating_sub(radius)..=page.saturating_add(radius) {
+            if page < total_pages
+                && let Some(img_name) = self.img_finder.image_at(page)
+            {
+                self.tex_loader.load(img_name);
+            }
+        }
+    }
+
+    /// Moves back by a page, or by a spread in the spread mode
+    fn prev_page(&mut self) {
+        if self.state.spread {
+            if let Some(cur_image) = self.img_finder.cur_image()
+                && let Some(prev) = spread_start(cur_image).checked_sub(1)
+            {
+                self.go_to_page(spread_start(prev));
+            }
+            return;
+        }
+
+        self.img_finder.prev_image();
+        if let Some(cur_image) = self.img_finder.cur_image() {
+            let radius = self.state.preload_radius;
+            for item in self
+                .img_finder
+                .image_iter()
+                .skip(cur_image.saturating_sub(radius))
+                .take(radius.min(cur_image))
+            {
+                self.tex_loader.load(item);
+            }
+        }
+    }
+
+    fn next_page(&mut self) {
+        if self.state.spread {
+            let total_pages = self.img_finder.cur_image_set().0.len();
+            if let Some(cur_image) = self.img_finder.cur_image() {
+                let start = spread_start(cur_image);
+                let next = spread_partner(start, total_pages).unwrap_or(start) + 1;
+                if next < total_pages {
+                    self.go_to_page(next);
+                }
+            }
+            return;
+        }
+
+        self.img_finder.next_image();
+        if let Some(cur_image) = self.img_finder.cur_image() {
+            for item in self
+                .img_finder
+                .image_iter()
+                .skip(cur_image + 1)
+                .take(self.state.preload_radius)
+                .rev()
+            {
+                self.tex_loader.load(item);
+            }
+        }
+    }
+
+    /// The page shown beside the current one in the spread mode
+    fn cur_spread_partner(&self) -> Option<usize> {
+        if !self.state.spread {
+            return None;
+        }
+
+        let total_pages = self.img_finder.cur_image_set().0.len();
+        spread_partner(self.img_finder.cur_image()?, total_pages)
+    }
+
+    /// Flips the current image horizontally, which is kept for the image
+    fn toggle_mirror(&mut self) {
+        let Some(cur_image) = self.img_finder.cur_image_name() else {
+            return;
+        };
+
+        self.translation.mirrored = !self.translation.mirrored;
+        if self.translation.mirrored {
+            self.state.mirrored_images.insert(cur_image.to_owned());
+        } else {
+            self.state.mirrored_images.remove(cur_image);
+        }
+    }
+
+    /// The color adjustments in use, the night preset in night mode
+    fn color_adjust(&self) -> ColorAdjust {
+        if self.state.night_mode {
+            self.state.night_adjust
+        } else {
+            self.state.color_adjust
+        }
+    }
+
+    fn toggle_night_mode(&mut self, ctx: &egui::Context) {
+        self.state.night_mode = !self.state.night_mode;
+        let message = if self.state.night_mode {
+            "Night mode on"
+        } else {
+            "Night mode off"
+        };
+        self.show_info_message(ctx, message.to_owned());
+    }
+
+    fn ui_color_adjust_menu(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.state.night_mode, "Night mode (N)");
+        ui.weak(if self.state.night_mode {
+            "Adjusting the night preset"
+        } else {
+            "Adjusting the normal colors"
+        });
+
+        let adjust = if self.state.night_mode {
+            &mut self.state.night_adjust
+        } else {
+            &mut self.state.color_adjust
+        };
+
+        egui::Grid::new("color_adjust_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Brightness");
+                ui.add(egui::Slider::new(&mut adjust.brightness, 0.2..=1.0).fixed_decimals(2));
+                ui.end_row();
+
+                ui.label("Contrast");
+                ui.add(egui::Slider::new(&mut adjust.contrast, 0.5..=1.5).fixed_decimals(2))
+                    .on_hover_text("The pages are loaded again once the slider is released");
+                ui.end_row();
+
+                // the same as the display filter, which is kept in both modes
+                ui.label("Invert");
+                let mut invert = self.state.display_filter == DisplayFilter::Invert;
+                if ui
+                    .checkbox(&mut invert, "")
+                    .on_hover_text("Same as the Invert display filter")
+                    .changed()
+                {
+                    self.state.display_filter = if invert {
+                        DisplayFilter::Invert
+                    } else {
+                        DisplayFilter::None
+                    };
+                }
+                ui.end_row();
+
+                ui.label("Paper");
+                ui.checkbox(&mut adjust.warm, "")
+                    .on_hover_text("Tint the pages warm like paper");
+                ui.end_row();
+            });
+
+        let preset = if self.state.night_mode {
+            ColorAdjust::NIGHT
+        } else {
+            ColorAdjust::default()
+        };
+        if ui
+            .add_enabled(*adjust != preset, egui::Button::new("Reset"))
+            .clicked()
+        {
+            *adjust = preset;
+        }
+    }
+
+    fn open_goto_page(&mut self) {
+        if let Some(page) = self.img_finder.cur_image() {
+            self.state.goto_page = Some((page + 1).to_string());
+        }
+    }
+
+    fn ui_show_goto_page_modal(&mut self, ui: &mut egui::Ui) {
+        let Some(input) = &mut self.state.goto_page else {
+            return;
+        };
+
+        let total_pages = self.img_finder.cur_image_set().0.len();
+        let mut page = None;
+
+        let modal = egui::Modal::new(egui::Id::new("Go to page")).show(ui.ctx(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("Go to page (1 - {total_pages}):"));
+
+                let res = ui.add(egui::TextEdit::singleline(input).desired_width(64.0));
+                if ui.memory(|mem| mem.focused().is_none()) {
+                    res.request_focus();
+                }
+
+                let entered = res.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let parsed = input.trim().parse::<usize>().ok();
+                if ui
+                    .add_enabled(parsed.is_some(), egui::Button::new("Go"))
+                    .clicked()
+                    || entered
+                {
+                    page = parsed;
+                }
+            });
+        });
+
+        if let Some(page) = page {
+            self.state.goto_page = None;
+            self.go_to_page(page.clamp(1, total_pages.max(1)) - 1);
+        } else if modal.should_close() {
+            self.state.goto_page = None;
+        }
+    }
+
+    fn ui_wallpaper_menu(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for fit in WallpaperFit::ALL {
+                ui.selectable_value(&mut self.state.wallpaper_fit, fit, fit.name());
+            }
+        });
+
+        if ui
+            .button(format!("{ICON_DEVICE_DESKTOP} Set as wallpaper"))
+            .clicked()
+        {
+            self.set_wallpaper(ui.ctx());
+            ui.close();
+        }
+    }
+
     fn ui_show_searching_modal(&mut self, ui: &mut egui::Ui) {
         if self.is_searching() {
             egui::Modal::new(egui::Id::new("Searching")).show(ui.ctx(), |ui| {
+                let task = self.search_task.as_mut().unwrap();
                 ui.horizontal(|ui| {
                     ui.spinner();
-                    ui.label("Searching directory...");
+                    match task.progress() {
+                        Some(progress) => ui.label(format!(
+                            "Searching directory... ({} scanned)",
+                            progress.done
+                        )),
+                        None => ui.label("Searching directory..."),
+                    };
                     if ui.button("Cancel").clicked() {
-                        self.search_task.as_ref().unwrap().cancel();
+                        task.cancel();
                     }
                 });
+
+                if let Some(progress) = task.progress() {
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(&progress.msg).weak())
+                            .wrap_mode(egui::TextWrapMode::Truncate),
+                    );
+                }
             });
         }
     }
@@ -255,13 +852,81 @@ impl App {
                         ICON_SCREEN_FULL.to_string(),
                     )
                     .on_hover_text("Fit the image size with the available space size");
+                    ui.selectable_value(
+                        &mut self.state.initial_scaling_mode,
+                        InitialScalingMode::FitWidth,
+                        ICON_LAYOUT_PANEL_JUSTIFY.to_string(),
+                    )
+                    .on_hover_text("Fit the image width, drag to see the rest of a tall page");
+                    ui.selectable_value(
+                        &mut self.state.initial_scaling_mode,
+                        InitialScalingMode::FitHeight,
+                        ICON_UNFOLD.to_string(),
+                    )
+                    .on_hover_text("Fit the image height");
+
+                    let filter_icon = if self.state.image_filter.enabled {
+                        ICON_FILTER_FILLED
+                    } else {
+                        ICON_FILTER
+                    };
+                    ui.toggle_value(
+                        &mut self.state.image_filter.enabled,
+                        filter_icon.to_string(),
+                    )
+                    .on_hover_text("Hide images by their size");
+
+                    ui.toggle_value(&mut self.state.spread, ICON_BOOK.to_string())
+                        .on_hover_text("Show two pages side by side, the cover alone");
+
+                    let (icon, hover_text) = if self.state.right_to_left {
+                        (ICON_ARROW_LEFT, "Reading from right to left")
+                    } else {
+                        (ICON_ARROW_RIGHT, "Reading from left to right")
+                    };
+                    if ui
+                        .button(icon.to_string())
+                        .on_hover_text(hover_text)
+                        .clicked()
+                    {
+                        self.state.right_to_left = !self.state.right_to_left;
+                    }
+
+                    ui.toggle_value(
+                        &mut self.state.wheel_turns_pages,
+                        ICON_ARROW_SWAP.to_string(),
+                    )
+                    .on_hover_text("Wheel turns pages when fit, zooms when the image is larger");
+
+                    ui.add(
+                        egui::DragValue::new(&mut self.state.preload_radius)
+                            .range(0..=Self::MAX_PRELOAD_RADIUS)
+                            .prefix("±"),
+                    )
+                    .on_hover_text("Pages preloaded on each side of the current one");
                 });
 
-                ui.add(
-                    egui::TextEdit::singleline(&mut self.state.search_key)
-                        .desired_width(f32::INFINITY)
-                        .hint_text("Search keywords"),
-                );
+                let now = ui.input(|i| i.time);
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.state.search_key)
+                            .desired_width(f32::INFINITY)
+                            .hint_text("Search keywords"),
+                    )
+                    .changed()
+                {
+                    self.state
+                        .search_filter
+                        .set(self.state.search_key.clone(), now);
+                }
+                if let Some(secs) = self.state.search_filter.remaining(now) {
+                    ui.ctx().request_repaint_after_secs(secs as f32);
+                }
+
+                if self.state.image_filter.enabled {
+                    self.ui_image_filter(ui);
+                }
+                self.img_finder.set_filter(self.state.image_filter);
 
                 egui::ScrollArea::both()
                     .auto_shrink([false, true])
@@ -276,24 +941,40 @@ impl App {
                         let mut cur_dir = self.img_finder.cur_dir();
 
                         self.state.last_cur_dir = cur_dir;
+                        if self.state.search_filter.changed(now) {
+                            self.matched_dirs = None;
+                        }
 
-                        for (dir, dir_name) in self.img_finder.cur_dir_set().iter().enumerate() {
-                            let dir_str = if dir_name.len() != dir_prefix - 1 {
+                        fn dir_str(dir_name: &str, dir_prefix: usize) -> &str {
+                            if dir_name.len() != dir_prefix - 1 {
                                 &dir_name[dir_prefix..]
                             } else {
                                 "current directory"
-                            };
-
-                            if !self.state.search_key.is_empty()
-                                && !dir_str
-                                    .to_ascii_lowercase()
-                                    .contains(&self.state.search_key)
-                            {
-                                continue;
                             }
+                        }
+
+                        let dir_set = self.img_finder.cur_dir_set();
+                        let matched = self.matched_dirs.get_or_insert_with(|| {
+                            let search_key = self.state.search_filter.value().to_lowercase();
+                            (0..dir_set.len())
+                                .filter(|&dir| {
+                                    search_key.is_empty()
+                                        || dir_str(&dir_set.0[dir], dir_prefix)
+                                            .to_lowercase()
+                                            .contains(&search_key)
+                                })
+                                .collect()
+                        });
+
+                        for &dir in matched.iter() {
+                            let dir_str = dir_str(&dir_set.0[dir], dir_prefix);
 
                             let is_cur_dir = cur_dir == Some(dir);
-                            let str = if is_cur_dir {
+                            let filtered_out = self.img_finder.filtered_out_count();
+                            let str = if is_cur_dir && filtered_out > 0 {
+                                egui::RichText::new(format!("{dir_str} ({filtered_out} hidden)"))
+                                    .color(ui.visuals().strong_text_color())
+                            } else if is_cur_dir {
                                 egui::RichText::new(dir_str).color(ui.visuals().strong_text_color())
                             } else {
                                 egui::RichText::new(dir_str)
@@ -318,6 +999,38 @@ impl App {
             });
     }
 
+    fn ui_image_filter(&mut self, ui: &mut egui::Ui) {
+        let filter = &mut self.state.image_filter;
+
+        egui::Grid::new("image_filter_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("min size");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut filter.min_width).suffix(" w"));
+                    ui.add(egui::DragValue::new(&mut filter.min_height).suffix(" h"));
+                });
+                ui.end_row();
+
+                ui.label("aspect")
+                    .on_hover_text("Width divided by height, e.g. 0.5 - 1.0 for portrait pages");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut filter.min_aspect)
+                            .speed(0.01)
+                            .range(0.0..=filter.max_aspect),
+                    );
+                    ui.label("-");
+                    ui.add(
+                        egui::DragValue::new(&mut filter.max_aspect)
+                            .speed(0.01)
+                            .range(filter.min_aspect..=10.0),
+                    );
+                });
+                ui.end_row();
+            });
+    }
+
     fn ui_contents(&mut self, ui: &mut egui::Ui) {
         egui::CentralPanel::default()
             .frame(Frame::NONE)
@@ -331,6 +1044,7 @@ impl App {
                 }
 
                 self.process_inputs(ui);
+                self.ui_background(ui, app_rect);
                 self.ui_show_cur_image(ui, app_rect);
 
                 let title_bar_height = get_button_height(ui) + 12.0;
@@ -374,6 +1088,18 @@ impl App {
             });
     }
 
+    /// Paints the custom background over the window one, in the same corners
+    fn ui_background(&self, ui: &egui::Ui, rect: egui::Rect) {
+        let Some(background) = self.state.background else {
+            return;
+        };
+
+        let fullscreen = ui.input(|i| i.viewport().fullscreen.unwrap_or(false));
+        let corner_radius = if fullscreen { 0 } else { 8 };
+        let corner_radius = self.adjust_corner_radius_match_left_panel(corner_radius.into());
+        ui.painter().rect_filled(rect, corner_radius, background);
+    }
+
     fn ui_show_cur_image(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
         let show_center_text = |text| {
             ui.painter().text(
@@ -392,11 +1118,33 @@ impl App {
         if let Some(cur_image_name) = self.img_finder.cur_image_name() {
             self.tex_loader.load(cur_image_name);
 
+            let partner = self
+                .cur_spread_partner()
+                .and_then(|page| self.img_finder.image_at(page))
+                .cloned();
+            if let Some(partner) = &partner {
+                self.tex_loader.load(partner);
+            }
+
             if let Some(texture) = self.tex_loader.textures().get(cur_image_name).unwrap() {
                 self.state.is_cur_image_loading = false;
 
                 let handle = texture.get_cur_handle();
-                let image_size = handle.size_vec2();
+
+                // the partner is scaled to the height of the current page, and the spread is
+                // scaled as one image, it's shown alone until the partner is loaded
+                let partner_handle = partner
+                    .and_then(|partner| self.tex_loader.textures().get(&partner)?.as_ref())
+                    .map(|partner| partner.get_cur_handle());
+                let page_size = handle.size_vec2();
+                let partner_size = partner_handle.map(|partner| {
+                    let size = partner.size_vec2();
+                    size * (page_size.y / size.y.max(1.0))
+                });
+                let image_size = match partner_size {
+                    Some(size) => vec2(page_size.x + size.x, page_size.y),
+                    None => page_size,
+                };
                 let available_size = rect.size();
 
                 let keep_min_scale = matches!(
@@ -414,7 +1162,13 @@ impl App {
 
                 if self.translation.image_fit_space_size {
                     self.translation.image_fit_space_size = false;
-                    self.translation.scale = fit_scale;
+                    let mode = self.state.initial_scaling_mode;
+                    self.translation.scale = mode.fit_scale(available_size, image_size);
+
+                    // starts from the top of a tall page, the offset is clamped below
+                    if mode == InitialScalingMode::FitWidth {
+                        self.translation.image_offset = vec2(0.0, f32::MAX);
+                    }
                 }
 
                 let scaled_size = image_size * self.translation.scale;
@@ -444,8 +1198,6 @@ impl App {
                 self.translation.image_offset =
                     self.translation.clamp_offset(self.translation.image_offset);
 
-                let tex = egui::Image::from_texture(handle).show_loading_spinner(false);
-
                 let image_pos = rect.center() - scaled_size * 0.5 + self.translation.image_offset;
                 let image_rect = Rect::from_min_size(image_pos, scaled_size);
 
@@ -462,9 +1214,50 @@ impl App {
                     0
                 };
 
-                tex.corner_radius(self.adjust_corner_radius_match_left_panel(corner_radius.into()))
-                    .tint(Color32::WHITE.gamma_multiply(opacity))
-                    .paint_at(ui, image_rect);
+                let corner_radius =
+                    self.adjust_corner_radius_match_left_panel(corner_radius.into());
+                let tint = self.color_adjust().tint().gamma_multiply(opacity);
+                // flipping the uv keeps the rect, so the offset and zooming are not changed
+                let mirrored = self.translation.mirrored;
+                let uv = if mirrored {
+                    Rect::from_min_max(pos2(1.0, 0.0), pos2(0.0, 1.0))
+                } else {
+                    Rect::from_min_max(pos2(0.0, 0.0), pos2(1.0, 1.0))
+                };
+                let paint = |handle, rect| {
+                    egui::Image::from_texture(handle)
+                        .uv(uv)
+                        .show_loading_spinner(false)
+                        .corner_radius(corner_radius)
+                        .tint(tint)
+                        .paint_at(ui, rect);
+                };
+
+                match partner_handle.zip(partner_size) {
+                    Some((partner_handle, partner_size)) => {
+                        // the spread is mirrored as a whole, so the pages swap sides too
+                        let page_on_right = self.state.right_to_left != mirrored;
+                        let split =
+                            image_rect.width() * page_size.x / (page_size.x + partner_size.x);
+                        let (left, right) = if page_on_right {
+                            image_rect.split_left_right_at_x(image_rect.right() - split)
+                        } else {
+                            image_rect.split_left_right_at_x(image_rect.left() + split)
+                        };
+                        let (page_rect, partner_rect) = if page_on_right {
+                            (right, left)
+                        } else {
+                            (left, right)
+                        };
+                        paint(handle, page_rect);
+                        paint(partner_handle, partner_rect);
+                    }
+                    None => paint(handle, image_rect),
+                }
+
+                // Dragging is handled by `handle_scroll_and_drag`, only clicks are sensed here
+                ui.interact(image_rect, Id::new("cur_image"), egui::Sense::click())
+                    .context_menu(|ui| self.ui_wallpaper_menu(ui));
             } else {
                 self.state.is_cur_image_loading = true;
                 if let Some(info) = self.state.last_image_info.as_ref() {
@@ -480,12 +1273,133 @@ impl App {
         } else if self.img_finder.cur_dir_set().0.is_empty()
             && self.img_finder.cur_image_set().0.is_empty()
         {
-            show_center_text("Drop file or directory here");
+            if self.state.recent_dirs.is_empty() || self.is_searching() {
+                show_center_text("Drop file or directory here");
+            } else {
+                self.ui_recent_dirs(ui, rect);
+            }
         } else {
             show_center_text("manga-reader :)");
         }
     }
 
+    /// Cards of the recent directories, shown when nothing is opened
+    fn ui_recent_dirs(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        const CARD_WIDTH: f32 = 120.0;
+        const THUMBNAIL_HEIGHT: f32 = 150.0;
+
+        let mut dir_to_open = None;
+        let mut dir_to_remove = None;
+        let mut clear = false;
+
+        let rect = rect.shrink2(vec2(40.0, 48.0));
+        ui.scope_builder(UiBuilder::new().max_rect(rect), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Drop file or directory here, or continue reading");
+                if ui.link("clear history").clicked() {
+                    clear = true;
+                }
+            });
+            ui.add_space(4.0);
+
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        let exists = self.state.recent_dirs.exists().to_vec();
+                        let entries = self.state.recent_dirs.entries();
+                        for (entry, exists) in entries.iter().zip(exists) {
+                            let res = Frame::group(ui.style()).show(ui, |ui| {
+                                ui.set_width(CARD_WIDTH);
+                                if !exists {
+                                    ui.multiply_opacity(0.4);
+                                }
+
+                                let (thumbnail_rect, _) = ui.allocate_exact_size(
+                                    vec2(CARD_WIDTH, THUMBNAIL_HEIGHT),
+                                    egui::Sense::hover(),
+                                );
+                                if exists && let Some(thumbnail) = &entry.thumbnail {
+                                    self.tex_loader.load(thumbnail);
+                                }
+                                let texture = entry.thumbnail.as_ref().filter(|_| exists).and_then(
+                                    |thumbnail| self.tex_loader.textures().get(thumbnail)?.as_ref(),
+                                );
+                                match texture {
+                                    Some(texture) => {
+                                        let handle = texture.get_cur_handle();
+                                        let size = handle.size_vec2()
+                                            * eapp_utils::calculate_fit_scale(
+                                                thumbnail_rect.size(),
+                                                handle.size_vec2(),
+                                            );
+                                        egui::Image::from_texture(handle)
+                                            .show_loading_spinner(false)
+                                            .tint(self.color_adjust().tint())
+                                            .paint_at(
+                                                ui,
+                                                Rect::from_center_size(
+                                                    thumbnail_rect.center(),
+                                                    size,
+                                                ),
+                                            );
+                                    }
+                                    None => {
+                                        ui.painter().rect_filled(
+                                            thumbnail_rect,
+                                            CornerRadius::same(4),
+                                            ui.visuals().faint_bg_color,
+                                        );
+                                        ui.painter().text(
+                                            thumbnail_rect.center(),
+                                            Align2::CENTER_CENTER,
+                                            ICON_FOLDER,
+                                            egui::FontId::proportional(32.0),
+                                            ui.visuals().weak_text_color(),
+                                        );
+                                    }
+                                }
+
+                                ui.add(egui::Label::new(entry.dir_name()).truncate());
+                                ui.horizontal(|ui| {
+                                    ui.weak(format!(
+                                        "p.{} {}",
+                                        entry.page_idx + 1,
+                                        recent_dirs::format_read_time(entry.read_time)
+                                    ));
+                                    if !exists
+                                        && ui
+                                            .small_button(ICON_TRASH.to_string())
+                                            .on_hover_text("Remove, the directory is gone")
+                                            .clicked()
+                                    {
+                                        dir_to_remove = Some(entry.dir.clone());
+                                    }
+                                });
+                            });
+
+                            let res = res.response.interact(egui::Sense::click());
+                            if exists && res.on_hover_text(&entry.dir).clicked() {
+                                dir_to_open = Some(entry.resume_path().to_owned());
+                            }
+                        }
+                    });
+                });
+        });
+
+        if clear {
+            self.state.recent_dirs.clear();
+        }
+
+        if let Some(dir) = dir_to_remove {
+            self.state.recent_dirs.remove(&dir);
+        }
+
+        if let Some(path) = dir_to_open {
+            self.start_search(path);
+        }
+    }
+
     fn ui_left_panel_button(
         &mut self,
         ui: &mut egui::Ui,
@@ -569,15 +1483,38 @@ impl App {
         let mut name = "None".to_owned();
         let mut page_info = "None".to_owned();
         let mut size_info = "? x ?".to_owned();
+        let mut file_info = String::new();
         let total_pages = self.img_finder.cur_image_set().0.len();
-        let current_page = self.img_finder.cur_image().unwrap_or(0);
+        // the last page shown, so that the progress moves by spreads
+        let current_page = self
+            .cur_spread_partner()
+            .or(self.img_finder.cur_image())
+            .unwrap_or(0);
+
+        if let Some((_, hide_time)) = &self.state.info_message
+            && current_time >= *hide_time
+        {
+            self.state.info_message = None;
+        }
 
         if let Some(img) = self.img_finder.cur_image() {
             let prefix = self.img_finder.search_dir().unwrap().len() + 1;
             let img_name = self.img_finder.cur_image_name().unwrap();
             name = img_name[prefix..].to_owned();
 
-            page_info = format!("PAGE ({} / {})", img + 1, total_pages);
+            if self
+                .file_info
+                .as_ref()
+                .is_none_or(|info| info.path != *img_name)
+            {
+                self.file_info = Some(FileInfo::read(img_name));
+            }
+            file_info = self.file_info.as_ref().unwrap().text();
+
+            page_info = match self.cur_spread_partner() {
+                Some(partner) => format!("PAGE ({} - {} / {})", img + 1, partner + 1, total_pages),
+                None => format!("PAGE ({} / {})", img + 1, total_pages),
+            };
 
             if let Some(texture) = self.tex_loader.textures().get(img_name).unwrap() {
                 let size = texture.get_cur_handle().size();
@@ -600,7 +1537,17 @@ impl App {
 
             ui.style_mut().spacing.item_spacing = vec2(0.0, 12.0);
 
-            ui.add(egui::Label::new(name).wrap_mode(egui::TextWrapMode::Truncate));
+            ui.horizontal(|ui| {
+                ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.style_mut().spacing.item_spacing.x = 12.0;
+                    if !file_info.is_empty() {
+                        ui.label(file_info);
+                    }
+                    ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
+                        ui.add(egui::Label::new(name).wrap_mode(egui::TextWrapMode::Truncate));
+                    });
+                });
+            });
 
             let response = ProgressBar::new((current_page + 1) as f64, total_pages as f64)
                 .preview(|ui, hover_img| {
@@ -614,7 +1561,8 @@ impl App {
 
                             let image = egui::Image::from_texture(handle)
                                 .max_size(vec2(256.0, 256.0))
-                                .corner_radius(4);
+                                .corner_radius(4)
+                                .tint(self.color_adjust().tint());
                             let image_size = image.calc_size(size, image.size());
                             let center = pos2(
                                 rect.center().x,
@@ -634,8 +1582,23 @@ impl App {
             let progress_bar_rect = response.rect;
 
             ui.horizontal(|ui| {
-                ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| {
-                    ui.label(page_info);
+                ui.with_layout(Layout::left_to_right(egui::Align::Center), |ui| match &self
+                    .state
+                    .info_message
+                {
+                    Some((message, _)) => {
+                        ui.label(message);
+                    }
+                    None => {
+                        let shortcut = ui.ctx().format_shortcut(&Self::GOTO_PAGE);
+                        if ui
+                            .add(egui::Label::new(page_info).sense(egui::Sense::click()))
+                            .on_hover_text(format!("Click to go to a page ({shortcut})"))
+                            .clicked()
+                        {
+                            self.open_goto_page();
+                        }
+                    }
                 });
 
                 ui.with_layout(Layout::right_to_left(egui::Align::Center), |ui| {
@@ -643,8 +1606,15 @@ impl App {
                 });
             });
 
+            let exif = self
+                .img_finder
+                .cur_image_name()
+                .and_then(|img_name| self.tex_loader.exif(img_name))
+                .cloned();
+
             let btn_size = vec2(32.0, 32.0);
-            let rect_size = vec2(btn_size.x * 5.0, btn_size.y);
+            let btn_count = if exif.is_some() { 11.0 } else { 10.0 };
+            let rect_size = vec2(btn_size.x * btn_count, btn_size.y);
 
             let rect =
                 Rect::from_center_size(pos2(rect.center().x, rect.bottom() - 22.0), rect_size);
@@ -676,6 +1646,10 @@ impl App {
                         eapp_utils::window_resize_by_fit_scale(ui, size);
                     }
 
+                    if btn_clicked!(ICON_ARROW_BOTH, "Mirror horizontally (H)") {
+                        self.toggle_mirror();
+                    }
+
                     if btn_clicked!(ICON_REFRESH, "Reset image translation") {
                         self.translation.scale = 1.0;
                         self.translation.image_offset = egui::Vec2::ZERO;
@@ -693,7 +1667,89 @@ impl App {
                     if btn_clicked!(ICON_GO_TO_FILE, "Open in explorer")
                         && let Some(cur_img) = self.img_finder.cur_image_name()
                     {
-                        eapp_utils::open_in_explorer(cur_img);
+                        if let Err(err) = eapp_utils::open_in_explorer(cur_img) {
+                            log::error!("open in explorer fails: {err}");
+                        }
+                    }
+
+                    let response = PlainButton::new(btn_size, ICON_DEVICE_DESKTOP.to_string())
+                        .corner_radius(CornerRadius::same(2))
+                        .hover(hover_color)
+                        .ui(ui)
+                        .on_hover_text("Set as wallpaper");
+                    egui::Popup::menu(&response).show(|ui| self.ui_wallpaper_menu(ui));
+
+                    let response = PlainButton::new(btn_size, ICON_COLOR_MODE.to_string())
+                        .corner_radius(CornerRadius::same(2))
+                        .hover(hover_color)
+                        .ui(ui)
+                        .on_hover_text("Display filter");
+                    egui::Popup::menu(&response).show(|ui| {
+                        for filter in DisplayFilter::ALL {
+                            ui.selectable_value(
+                                &mut self.state.display_filter,
+                                filter,
+                                filter.name(),
+                            );
+                        }
+                    });
+
+                    let response = PlainButton::new(btn_size, ICON_LIGHTBULB.to_string())
+                        .corner_radius(CornerRadius::same(2))
+                        .hover(hover_color)
+                        .ui(ui)
+                        .on_hover_text("Color adjustments");
+                    egui::Popup::menu(&response)
+                        .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                        .show(|ui| self.ui_color_adjust_menu(ui));
+
+                    let mut background = self
+                        .state
+                        .background
+                        .unwrap_or(ui.visuals().extreme_bg_color);
+                    let response = ui
+                        .add_sized(
+                            btn_size,
+                            ColorButton::new("background_color", &mut background).alpha(false),
+                        )
+                        .on_hover_text("Background, right-click for presets");
+                    if response.changed() {
+                        self.state.background = Some(background);
+                    }
+                    response.context_menu(|ui| {
+                        for (name, color) in [
+                            ("Theme", None),
+                            ("Black", Some(Color32::BLACK)),
+                            ("Gray", Some(Color32::from_gray(128))),
+                            ("White", Some(Color32::WHITE)),
+                        ] {
+                            ui.selectable_value(&mut self.state.background, color, name);
+                        }
+                    });
+
+                    if btn_clicked!(ICON_FILE_PDF, "Export this directory to PDF") {
+                        self.ask_export_pdf(ui.ctx());
+                    }
+
+                    // only for the photos which have it
+                    if let Some(exif) = &exif {
+                        let response = PlainButton::new(btn_size, ICON_INFO.to_string())
+                            .corner_radius(CornerRadius::same(2))
+                            .hover(hover_color)
+                            .ui(ui)
+                            .on_hover_text("EXIF");
+                        egui::Popup::menu(&response).show(|ui| {
+                            egui::Grid::new("exif_grid")
+                                .num_columns(2)
+                                .spacing([12.0, 4.0])
+                                .show(ui, |ui| {
+                                    for (name, value) in exif {
+                                        ui.weak(*name);
+                                        ui.label(value);
+                                        ui.end_row();
+                                    }
+                                });
+                        });
                     }
                 });
             });
@@ -704,24 +1760,28 @@ impl App {
                 let new_page =
                     value_from_x(total_pages as f64, progress_bar_rect, pointer.x as f64) as usize;
 
-                let new_page = new_page.min(total_pages.saturating_sub(1));
-                self.img_finder.set_cur_image_idx(new_page);
-
-                for page in new_page.saturating_sub(3)..=new_page.saturating_add(3) {
-                    if page < total_pages
-                        && let Some(img_name) = self.img_finder.image_at(page)
-                    {
-                        self.tex_loader.load(img_name);
-                    }
-                }
+                self.go_to_page(new_page);
             }
         });
     }
 
     fn handle_scroll_and_drag(&mut self, ui: &mut egui::Ui) {
-        let scroll_delta = ui.input(|i| i.smooth_scroll_delta.y);
+        let (scroll_delta, wheel_delta, now) =
+            ui.input(|i| (i.smooth_scroll_delta.y, i.raw_scroll_delta.y, i.time));
+
+        let turns_pages = self.state.wheel_turns_pages && self.translation.image_fully_contained();
+        if turns_pages && wheel_delta != 0.0 {
+            if now - self.state.last_wheel_time >= Self::WHEEL_TURN_INTERVAL {
+                if wheel_delta < 0.0 {
+                    self.next_page();
+                } else {
+                    self.prev_page();
+                }
+            }
+            self.state.last_wheel_time = now;
+        }
 
-        let zoom_delta = if scroll_delta != 0.0 {
+        let zoom_delta = if scroll_delta != 0.0 && !turns_pages {
             scroll_delta * 0.005
         } else {
             0.0
@@ -779,6 +1839,18 @@ impl App {
 
     fn process_inputs(&mut self, ui: &mut egui::Ui) {
         if ui.memory(|mem| mem.focused().is_none()) {
+            if ui.input_mut(|i| i.consume_shortcut(&Self::GOTO_PAGE)) {
+                self.open_goto_page();
+            }
+
+            if ui.input(|i| i.key_pressed(egui::Key::H)) {
+                self.toggle_mirror();
+            }
+
+            if ui.input(|i| i.key_pressed(egui::Key::N)) {
+                self.toggle_night_mode(ui.ctx());
+            }
+
             if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
                 self.img_finder.prev_dir();
             }
@@ -787,55 +1859,52 @@ impl App {
                 self.img_finder.next_dir();
             }
 
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
-                self.img_finder.prev_image();
+            let (prev_key, next_key) = if self.state.right_to_left {
+                (egui::Key::ArrowRight, egui::Key::ArrowLeft)
+            } else {
+                (egui::Key::ArrowLeft, egui::Key::ArrowRight)
+            };
 
-                if let Some(cur_image) = self.img_finder.cur_image() {
-                    for item in self
-                        .img_finder
-                        .image_iter()
-                        .skip(cur_image.saturating_sub(3))
-                        .take(3)
-                    {
-                        self.tex_loader.load(item);
-                    }
-                }
+            if ui.input(|i| i.key_pressed(prev_key)) {
+                self.prev_page();
             }
 
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowRight)) {
-                self.img_finder.next_image();
-                if let Some(cur_image) = self.img_finder.cur_image() {
-                    for item in self
-                        .img_finder
-                        .image_iter()
-                        .skip(cur_image + 1)
-                        .take(3)
-                        .rev()
-                    {
-                        self.tex_loader.load(item);
-                    }
-                }
+            if ui.input(|i| i.key_pressed(next_key)) {
+                self.next_page();
             }
         }
 
-        ui.ctx().input(|i| {
-            if !i.raw.dropped_files.is_empty()
-                && let Some(path) = &i.raw.dropped_files.first().unwrap().path
-            {
-                let cwd = if path.is_dir() {
-                    path
-                } else {
-                    path.parent().unwrap()
-                };
+        if self.state.spread
+            && let Some(cur_image) = self.img_finder.cur_image()
+            && spread_start(cur_image) != cur_image
+        {
+            // the current page is always the first of its spread
+            self.img_finder.set_cur_image_idx(spread_start(cur_image));
+        }
 
-                if let Err(err) = std::env::set_current_dir(cwd) {
-                    log::error!("set current dir '{cwd:?}' fails: {err}");
-                }
+        // only the first is opened, a search replaces the images of the last one
+        let path = self
+            .drop_handler
+            .show(ui.ctx())
+            .into_iter()
+            .find_map(|item| match item {
+                DroppedItem::Path(_, path) => Some(path),
+                _ => None,
+            });
+        if let Some(path) = path {
+            let cwd = if path.is_dir() {
+                path.as_path()
+            } else {
+                path.parent().unwrap()
+            };
 
-                self.search_list
-                    .push_back(path.to_string_lossy().into_owned());
+            if let Err(err) = std::env::set_current_dir(cwd) {
+                log::error!("set current dir '{cwd:?}' fails: {err}");
             }
-        });
+
+            self.search_list
+                .push_back(path.to_string_lossy().into_owned());
+        }
 
         if let Some(current_rect) = ui.ctx().input(|i| i.viewport().inner_rect) {
             let current_size = current_rect.size();
@@ -847,9 +1916,14 @@ impl App {
         }
 
         if self.img_finder.consume_dir_changed_flag() {
+            // the new directory is probed next
+            if let Some(task) = &self.probe_task {
+                task.cancel();
+            }
             self.state.scroll_to_current = true;
             self.tex_loader.forget_all();
-            for item in self.img_finder.image_iter().take(3).rev() {
+            let count = self.state.preload_radius + 1;
+            for item in self.img_finder.image_iter().take(count).rev() {
                 self.tex_loader.load(item);
             }
         }
@@ -861,6 +1935,16 @@ impl App {
                     .reset_translation(self.state.initial_scaling_mode);
                 self.translation
                     .fit_space_if_need(self.state.initial_scaling_mode);
+                self.translation.mirrored = self.state.mirrored_images.contains(cur_image);
+
+                if let Some(cur_dir) = self.img_finder.cur_dir_name() {
+                    self.state.recent_dirs.update(
+                        cur_dir,
+                        cur_image,
+                        self.img_finder.cur_image().unwrap_or_default(),
+                        self.img_finder.image_at(0),
+                    );
+                }
             }
         } else {
             self.state.last_image_name = None;
@@ -876,6 +1960,7 @@ impl App {
 impl eframe::App for App {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, UiFontSelector::KEY, &self.selector);
+        self.window.save(storage);
         eframe::set_value(storage, eframe::APP_KEY, &self.state);
     }
 
@@ -888,10 +1973,25 @@ impl eframe::App for App {
             borderless::handle_resize(ui);
 
             self.try_get_search_result();
+            self.try_get_probe_result(ctx);
+            self.try_get_export_estimate();
+            self.try_get_export_result(ctx);
+            if self.tex_loader.set_filter(self.state.display_filter) {
+                // the placeholder is of the page without the new filter
+                self.state.last_image_info = None;
+            }
+            // not while a slider is dragged, as each change loads all the pages again
+            if !ctx.is_using_pointer() && self.tex_loader.set_adjust(self.color_adjust()) {
+                self.state.last_image_info = None;
+            }
+            self.tex_loader
+                .set_capacity(self.state.preload_radius * 2 + 1 + Self::EXTRA_TEXTURES);
             self.tex_loader
                 .update(ctx, self.img_finder.cur_image_name());
 
             self.ui_show_searching_modal(ui);
+            self.ui_show_export_modals(ui);
+            self.ui_show_goto_page_modal(ui);
 
             ui.add_enabled_ui(!self.is_searching(), |ui| {
                 self.ui_left_panel(ui);
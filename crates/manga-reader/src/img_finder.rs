@@ -1,15 +1,66 @@
-use eapp_utils::natordset::NatOrdSet;
-use std::{path::Path, slice::Iter, sync::mpsc::Receiver};
+use eapp_utils::{natordset::NatOrdSet, task::Progress};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::Path,
+    slice::Iter,
+    sync::mpsc::{Receiver, Sender},
+};
 use walkdir::WalkDir;
 
+/// Hides images of the current directory by their dimensions
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ImageFilter {
+    pub enabled: bool,
+    pub min_width: u32,
+    pub min_height: u32,
+
+    /// Band of `width / height`
+    pub min_aspect: f32,
+    pub max_aspect: f32,
+}
+
+impl Default for ImageFilter {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_width: 0,
+            min_height: 0,
+            min_aspect: 0.0,
+            max_aspect: 10.0,
+        }
+    }
+}
+
+impl ImageFilter {
+    /// Images whose dimensions are unknown are always shown
+    fn matches(&self, dimensions: Option<(u32, u32)>) -> bool {
+        let Some((width, height)) = dimensions else {
+            return true;
+        };
+
+        let aspect = width as f32 / height.max(1) as f32;
+        width >= self.min_width
+            && height >= self.min_height
+            && (self.min_aspect..=self.max_aspect).contains(&aspect)
+    }
+}
+
 #[derive(Default, Clone, Debug)]
 pub struct ImgFinder {
     search_dir: Option<String>,
     cur_image: Option<usize>,
     cur_dir: Option<usize>,
+    /// Images of the current directory that pass the filter
     cur_image_set: NatOrdSet,
+    /// All images of the current directory
+    all_image_set: NatOrdSet,
     cur_dir_set: NatOrdSet,
     dir_changed: bool,
+    filter: ImageFilter,
+    /// Dimensions read from image headers, `None` if it can't be read
+    dimensions: HashMap<String, Option<(u32, u32)>>,
 }
 
 impl ImgFinder {
@@ -22,6 +73,19 @@ impl ImgFinder {
         image::ImageFormat::from_extension(ext).is_some_and(|fmt| fmt.can_read())
     }
 
+    /// Extensions of the images which can be read, for the files dropped
+    pub fn supported_exts() -> Vec<&'static str> {
+        const MORE_EXTS: [&str; 9] = [
+            "jfif", "exr", "hdr", "dds", "ff", "pbm", "pgm", "ppm", "pam",
+        ];
+        eapp_utils::dnd::IMAGE_EXTS
+            .iter()
+            .chain(&MORE_EXTS)
+            .copied()
+            .filter(|ext| Self::is_supported_ext(ext))
+            .collect()
+    }
+
     fn is_dir_has_supported_image(dir: &Path) -> std::io::Result<bool> {
         for item in std::fs::read_dir(dir)? {
             let item = item?.path();
@@ -62,6 +126,7 @@ impl ImgFinder {
     pub fn from_search(
         canonicalized_path: &Path,
         cancel_receiver: Receiver<()>,
+        progress_sender: Sender<Progress>,
     ) -> std::io::Result<Self> {
         let search_dir = if canonicalized_path.is_file() {
             canonicalized_path.parent().unwrap()
@@ -84,11 +149,17 @@ impl ImgFinder {
             .filter(|e| e.file_type().is_dir())
             .enumerate()
         {
-            if i % 50 == 0 && cancel_receiver.try_recv().is_ok() {
-                return Err(std::io::Error::other("Search canceled"));
+            let entry_path = entry.path();
+
+            if i % 50 == 0 {
+                if cancel_receiver.try_recv().is_ok() {
+                    return Err(std::io::Error::other("Search canceled"));
+                }
+
+                let progress = Progress::new(i as u64, None).msg(entry_path.to_string_lossy());
+                let _ = progress_sender.send(progress);
             }
 
-            let entry_path = entry.path();
             if Self::is_dir_has_supported_image(entry_path)? {
                 finder
                     .cur_dir_set
@@ -96,7 +167,7 @@ impl ImgFinder {
             }
         }
 
-        finder.cur_dir_set.sort();
+        finder.cur_dir_set.sort_ci();
         finder.set_cur_dir(&search_dir_str);
 
         if canonicalized_path.is_file() {
@@ -105,6 +176,64 @@ impl ImgFinder {
 
         Ok(finder)
     }
+
+    pub fn set_filter(&mut self, filter: ImageFilter) {
+        if self.filter != filter {
+            self.filter = filter;
+            self.apply_filter();
+        }
+    }
+
+    /// Number of images in the current directory hidden by the filter
+    pub fn filtered_out_count(&self) -> usize {
+        self.all_image_set.0.len() - self.cur_image_set.0.len()
+    }
+
+    /// Images of the current directory whose dimensions the filter needs but are not read yet,
+    /// see [`ImgFinder::set_dimensions`]
+    pub fn unprobed(&self) -> Vec<String> {
+        if !self.filter.enabled {
+            return Vec::new();
+        }
+
+        self.all_image_set
+            .iter()
+            .filter(|image| !self.dimensions.contains_key(*image))
+            .cloned()
+            .collect()
+    }
+
+    /// Takes the dimensions read off the UI thread, `None` for the images that can't be read
+    pub fn set_dimensions(&mut self, dimensions: Vec<(String, Option<(u32, u32)>)>) {
+        if dimensions.is_empty() {
+            return;
+        }
+
+        self.dimensions.extend(dimensions);
+        self.apply_filter();
+    }
+
+    /// Rebuilds the visible images, the current image is kept if it's still visible,
+    /// otherwise its index is clamped. Images not probed yet are shown until they are
+    fn apply_filter(&mut self) {
+        let cur_image_name = self.cur_image_name().map(str::to_owned);
+        let mut visible = Vec::with_capacity(self.all_image_set.0.len());
+
+        for image in self.all_image_set.iter() {
+            let dimensions = self.dimensions.get(image).copied().flatten();
+            if self.filter.enabled && !self.filter.matches(dimensions) {
+                continue;
+            }
+            visible.push(image.clone());
+        }
+
+        self.cur_image_set = NatOrdSet(visible);
+        let len = self.cur_image_set.0.len();
+        self.cur_image = cur_image_name
+            .and_then(|name| self.cur_image_set.search_ci(&name).ok())
+            .or_else(|| self.cur_image.filter(|_| len > 0).map(|i| i.min(len - 1)));
+    }
+
     pub fn consume_dir_changed_flag(&mut self) -> bool {
         let mut flag = false;
         std::mem::swap(&mut flag, &mut self.dir_changed);
@@ -117,7 +246,7 @@ impl ImgFinder {
     }
 
     pub fn set_cur_image(&mut self, image_name: &str) {
-        if let Ok(image) = self.cur_image_set.search(image_name) {
+        if let Ok(image) = self.cur_image_set.search_ci(image_name) {
             self.cur_image = Some(image);
         }
     }
@@ -177,7 +306,7 @@ impl ImgFinder {
     }
 
     pub fn set_cur_dir(&mut self, dir_name: &str) {
-        if let Ok(dir) = self.cur_dir_set.search(dir_name) {
+        if let Ok(dir) = self.cur_dir_set.search_ci(dir_name) {
             self.set_cur_dir_idx(dir);
         }
     }
@@ -186,7 +315,7 @@ impl ImgFinder {
         if self.cur_dir != Some(dir) && dir < self.cur_dir_set.0.len() {
             self.cur_dir = Some(dir);
             self.cur_image = None;
-            self.cur_image_set.0.clear();
+            self.all_image_set.0.clear();
             self.dir_changed = true;
 
             let dir_path = &self.cur_dir_set.0[dir];
@@ -204,7 +333,7 @@ impl ImgFinder {
                                 .extension()
                                 .is_some_and(|ext| Self::is_supported_ext(ext.to_str().unwrap()))
                         {
-                            self.cur_image_set.push(item.to_string_lossy().into_owned());
+                            self.all_image_set.push(item.to_string_lossy().into_owned());
                         }
                     }
                 }
@@ -215,7 +344,8 @@ impl ImgFinder {
                 }
             }
 
-            self.cur_image_set.sort();
+            self.all_image_set.sort_ci();
+            self.apply_filter();
             self.next_image();
         }
     }
@@ -224,7 +354,6 @@ impl ImgFinder {
         self.cur_dir
     }
 
-    #[allow(unused)]
     pub fn cur_dir_name(&self) -> Option<&str> {
         if let Some(dir) = self.cur_dir {
             return Some(&self.cur_dir_set.0[dir]);
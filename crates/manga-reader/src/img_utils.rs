@@ -11,6 +11,8 @@ pub struct ImgTranslation {
     pub max_offset: egui::Vec2,
     pub image_fit_space_size: bool,
     pub image_exceeds_space: (bool, bool),
+    /// The image is flipped horizontally, it's kept by image in [`crate::app`]
+    pub mirrored: bool,
 }
 
 impl ImgTranslation {
@@ -35,7 +37,9 @@ impl ImgTranslation {
         self.image_fit_space_size = match mode {
             InitialScalingMode::KeepScale => false,
             InitialScalingMode::OriginalSize => false,
-            InitialScalingMode::FitToSpace => true,
+            InitialScalingMode::FitToSpace
+            | InitialScalingMode::FitWidth
+            | InitialScalingMode::FitHeight => true,
         };
     }
 }
@@ -52,6 +56,7 @@ impl Default for ImgTranslation {
             image_exceeds_space: (false, false),
             max_offset: egui::Vec2::ZERO,
             min_scale: 1.0,
+            mirrored: false,
         }
     }
 }
@@ -67,6 +72,33 @@ pub enum InitialScalingMode {
 
     /// The image will automatically adapt to the available space size
     FitToSpace,
+
+    /// The width of the image fits the space, the rest is dragged to, for tall pages
+    FitWidth,
+
+    /// The height of the image fits the space
+    FitHeight,
+}
+
+impl InitialScalingMode {
+    /// Scale fitting the image to the space, only in one dimension for `FitWidth` or `FitHeight`
+    pub fn fit_scale(self, available_size: egui::Vec2, image_size: egui::Vec2) -> f32 {
+        match self {
+            Self::FitWidth => available_size.x / image_size.x,
+            Self::FitHeight => available_size.y / image_size.y,
+            _ => eapp_utils::calculate_fit_scale(available_size, image_size),
+        }
+    }
+}
+
+/// First page of the spread `page` is in, the cover is alone and the others are paired
+pub fn spread_start(page: usize) -> usize {
+    if page == 0 { 0 } else { page - (page - 1) % 2 }
+}
+
+/// The page shown beside `start`, which is the first page of a spread
+pub fn spread_partner(start: usize, total_pages: usize) -> Option<usize> {
+    (start > 0 && start + 1 < total_pages).then_some(start + 1)
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -74,3 +106,247 @@ pub struct LastImageInfo {
     pub average_color: egui::Color32,
     pub rect: egui::Rect,
 }
+
+/// Filter applied to the pages before they are shown, for reading in the dark and such
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum DisplayFilter {
+    #[default]
+    None,
+    Invert,
+    Grayscale,
+    HighContrast,
+}
+
+impl DisplayFilter {
+    pub const ALL: [Self; 4] = [
+        Self::None,
+        Self::Invert,
+        Self::Grayscale,
+        Self::HighContrast,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Invert => "Invert",
+            Self::Grayscale => "Grayscale",
+            Self::HighContrast => "High contrast",
+        }
+    }
+
+    /// Filters the pixels in place, the alpha is kept
+    pub fn apply(self, pixels: &mut [egui::Color32]) {
+        if self == Self::None {
+            return;
+        }
+
+        for pixel in pixels {
+            let [r, g, b, a] = pixel.to_srgba_unmultiplied();
+            let luma = || (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) as u8;
+            let [r, g, b] = match self {
+                Self::None => unreachable!(),
+                Self::Invert => [255 - r, 255 - g, 255 - b],
+                Self::Grayscale => [luma(); 3],
+                Self::HighContrast => {
+                    [r, g, b].map(|c| ((c as f32 - 128.0) * 2.0 + 128.0).clamp(0.0, 255.0) as u8)
+                }
+            };
+            *pixel = egui::Color32::from_rgba_unmultiplied(r, g, b, a);
+        }
+    }
+}
+
+/// Adjustments of the page colors for reading at night, the default one keeps them as they are
+#[derive(Deserialize, Serialize, PartialEq, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct ColorAdjust {
+    /// Multiplies the colors when drawn, 1 keeps them
+    pub brightness: f32,
+    /// Scales the colors around the middle gray when loaded, 1 keeps them,
+    /// inverting is left to [`DisplayFilter::Invert`]
+    pub contrast: f32,
+    /// Tints towards the color of paper when drawn
+    pub warm: bool,
+}
+
+impl Default for ColorAdjust {
+    fn default() -> Self {
+        Self {
+            brightness: 1.0,
+            contrast: 1.0,
+            warm: false,
+        }
+    }
+}
+
+impl ColorAdjust {
+    /// The night preset before it's changed
+    pub const NIGHT: Self = Self {
+        brightness: 0.7,
+        contrast: 0.9,
+        warm: true,
+    };
+
+    const PAPER: egui::Color32 = egui::Color32::from_rgb(255, 236, 204);
+
+    /// Whether the pixels are adjusted the same as by `other`, as only those need
+    /// the pages loaded again
+    pub fn same_pixels(self, other: Self) -> bool {
+        self.contrast == other.contrast
+    }
+
+    /// Adjusts the pixels in place, the alpha is kept
+    pub fn apply(self, pixels: &mut [egui::Color32]) {
+        if self.contrast == 1.0 {
+            return;
+        }
+
+        for pixel in pixels {
+            let [r, g, b, a] = pixel.to_srgba_unmultiplied();
+            let [r, g, b] = [r, g, b]
+                .map(|c| ((c as f32 - 128.0) * self.contrast + 128.0).clamp(0.0, 255.0) as u8);
+            *pixel = egui::Color32::from_rgba_unmultiplied(r, g, b, a);
+        }
+    }
+
+    /// Tint of the drawn pages, white if they are not dimmed or tinted
+    pub fn tint(self) -> egui::Color32 {
+        let base = if self.warm {
+            Self::PAPER
+        } else {
+            egui::Color32::WHITE
+        };
+        let [r, g, b] = [base.r(), base.g(), base.b()]
+            .map(|c| (c as f32 * self.brightness.clamp(0.0, 1.0)).round() as u8);
+        egui::Color32::from_rgb(r, g, b)
+    }
+}
+
+/// Capture settings of a photo, name and value of each field it has
+pub type ExifFields = Vec<(&'static str, String)>;
+
+/// Reads the EXIF fields shown in the info bar, empty if there is no EXIF
+pub fn read_exif(bytes: &[u8]) -> ExifFields {
+    use exif::{In, Tag, Value};
+
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut std::io::Cursor::new(bytes)) else {
+        return ExifFields::new();
+    };
+
+    let text = |tag| {
+        let field = exif.get_field(tag, In::PRIMARY)?;
+        let text = match &field.value {
+            // shown without the quotes
+            Value::Ascii(values) => String::from_utf8_lossy(values.first()?).trim().to_owned(),
+            _ => field.display_value().with_unit(&exif).to_string(),
+        };
+        (!text.is_empty()).then_some(text)
+    };
+
+    let camera = match (text(Tag::Make), text(Tag::Model)) {
+        (Some(make), Some(model)) if !model.starts_with(&make) => Some(format!("{make} {model}")),
+        (make, model) => model.or(make),
+    };
+
+    let fields = [
+        ("Camera", camera),
+        ("Lens", text(Tag::LensModel)),
+        ("ISO", text(Tag::PhotographicSensitivity)),
+        ("Aperture", text(Tag::FNumber)),
+        ("Shutter speed", text(Tag::ExposureTime)),
+        ("Focal length", text(Tag::FocalLength)),
+        (
+            "Date",
+            text(Tag::DateTimeOriginal).or_else(|| text(Tag::DateTime)),
+        ),
+    ];
+
+    fields
+        .into_iter()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect()
+}
+
+/// Size and modified time of an image file, read once per image instead of every frame
+#[derive(Clone, Debug)]
+pub struct FileInfo {
+    pub path: String,
+    pub size: Option<u64>,
+    pub modified: Option<std::time::SystemTime>,
+}
+
+impl FileInfo {
+    pub fn read(path: &str) -> Self {
+        let metadata = std::fs::metadata(path)
+            .inspect_err(|err| log::warn!("read metadata of '{path}' fails: {err}"))
+            .ok();
+
+        Self {
+            path: path.to_owned(),
+            size: metadata.as_ref().map(|metadata| metadata.len()),
+            modified: metadata.and_then(|metadata| metadata.modified().ok()),
+        }
+    }
+
+    /// e.g. `1.2 MiB, 2024-05-01 12:30 UTC`, empty if nothing is known
+    pub fn text(&self) -> String {
+        let size = self.size.map(eapp_utils::format_size);
+        let modified = self.modified.map(format_utc_time);
+        [size, modified]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Formats `time` as `YYYY-MM-DD HH:MM UTC`
+fn format_utc_time(time: std::time::SystemTime) -> String {
+    let t = eapp_utils::date::UtcDateTime::new(time);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        t.year, t.month, t.day, t.hour, t.minute
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_adjust_keeps_the_pages() {
+        let pixels: Vec<_> = (0..=255u8)
+            .map(|c| egui::Color32::from_rgba_unmultiplied(c, 255 - c, c / 2, c))
+            .collect();
+
+        let mut adjusted = pixels.clone();
+        ColorAdjust::default().apply(&mut adjusted);
+        assert_eq!(adjusted, pixels);
+        assert_eq!(ColorAdjust::default().tint(), egui::Color32::WHITE);
+    }
+
+    #[test]
+    fn spreads_pair_the_pages_after_the_cover() {
+        // (total pages, page, first page of its spread, page beside it)
+        let cases = [
+            (1, 0, 0, None),
+            (2, 0, 0, None),
+            (2, 1, 1, None),
+            (4, 0, 0, None),
+            (4, 1, 1, Some(2)),
+            (4, 2, 1, Some(2)),
+            (4, 3, 3, None),
+            (5, 3, 3, Some(4)),
+            (5, 4, 3, Some(4)),
+        ];
+
+        for (total, page, start, partner) in cases {
+            assert_eq!(spread_start(page), start, "page {page} of {total}");
+            assert_eq!(
+                spread_partner(start, total),
+                partner,
+                "page {page} of {total}"
+            );
+        }
+    }
+}
@@ -4,10 +4,12 @@ pub(crate) mod app;
 pub(crate) mod img_finder;
 pub(crate) mod img_utils;
 pub(crate) mod lifo;
+pub(crate) mod pdf_export;
+pub(crate) mod recent_dirs;
 pub(crate) mod tex_loader;
 
 fn main() {
-    eapp_utils::setup_loggers("manga-reader.log").unwrap();
+    eapp_utils::setup_loggers("manga-reader.log", eapp_utils::LogOptions::from_env()).unwrap();
 
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
@@ -21,6 +23,7 @@ fn main() {
                 ))
                 .unwrap(),
             ),
+        persist_window: false,
         ..Default::default()
     };
 
@@ -0,0 +1,437 @@
+//! Exports the pages of a directory to a PDF, one image per page
+//!
+//! JPEGs are embedded as they are, other images are decoded and stored deflated,
+//! since PDF can't embed them directly.
+
+use eapp_utils::task::Progress;
+use flate2::{Compression, write::ZlibEncoder};
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::mpsc::{Receiver, Sender},
+};
+
+/// Images have no physical size, they are laid out as if shown on a 96 dpi screen
+const POINTS_PER_PIXEL: f32 = 72.0 / 96.0;
+
+#[derive(Default)]
+pub struct ExportReport {
+    pub written: usize,
+
+    /// Pages that can't be read, with the reason
+    pub failed: Vec<(String, String)>,
+
+    /// Nothing is written if cancelled
+    pub cancelled: bool,
+}
+
+/// Total size of the page files, which is about the size of the PDF
+pub fn estimate_size(pages: &[String]) -> u64 {
+    pages
+        .iter()
+        .filter_map(|page| std::fs::metadata(page).ok())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Writes `pages` in order to `path`, a page that can't be read is skipped and reported.
+/// Pages are written as they are read, into a partial file which replaces `path` once done
+pub fn export(
+    pages: &[String],
+    path: &Path,
+    cancel_receiver: Receiver<()>,
+    progress_sender: Sender<Progress>,
+) -> io::Result<ExportReport> {
+    let part_path = path.with_extension("pdf.part");
+    let result = write_pages(pages, &part_path, cancel_receiver, progress_sender);
+
+    match &result {
+        Ok(report) if !report.cancelled && report.written > 0 => std::fs::rename(&part_path, path)?,
+        _ => {
+            let _ = std::fs::remove_file(&part_path);
+        }
+    }
+    result
+}
+
+fn write_pages(
+    pages: &[String],
+    path: &Path,
+    cancel_receiver: Receiver<()>,
+    progress_sender: Sender<Progress>,
+) -> io::Result<ExportReport> {
+    let mut report = ExportReport::default();
+    let mut pdf = PdfWriter::new(BufWriter::new(File::create(path)?))?;
+    let catalog_id = pdf.reserve();
+    let pages_id = pdf.reserve();
+    let mut kids = Vec::with_capacity(pages.len());
+
+    for (i, page) in pages.iter().enumerate() {
+        if cancel_receiver.try_recv().is_ok() {
+            report.cancelled = true;
+            return Ok(report);
+        }
+
+        let name = Path::new(page)
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+        let progress = Progress::new(i as u64, Some(pages.len() as u64)).msg(name);
+        let _ = progress_sender.send(progress);
+
+        let image = match page_image(page) {
+            Ok(image) => image,
+            Err(err) => {
+                log::warn!("export page '{page}' fails: {err}");
+                report.failed.push((page.clone(), err));
+                continue;
+            }
+        };
+
+        let image_id = pdf.reserve();
+        let dict = format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{} \
+             /BitsPerComponent 8 /Filter /{}",
+            image.width, image.height, image.color_space, image.filter
+        );
+        pdf.stream(image_id, &dict, &image.data)?;
+
+        let (width, height) = (
+            image.width as f32 * POINTS_PER_PIXEL,
+            image.height as f32 * POINTS_PER_PIXEL,
+        );
+
+        // Draws the image, which is a unit square, scaled to the whole page
+        let content_id = pdf.reserve();
+        let content = format!("q {width:.2} 0 0 {height:.2} 0 0 cm /Im0 Do Q");
+        pdf.stream(content_id, "", content.as_bytes())?;
+
+        let page_id = pdf.reserve();
+        pdf.object(
+            page_id,
+            &format!(
+                "<< /Type /Page /Parent {pages_id} 0 R /Contents {content_id} 0 R \
+                 /MediaBox [0 0 {width:.2} {height:.2}] \
+                 /Resources << /XObject << /Im0 {image_id} 0 R >> >> >>"
+            ),
+        )?;
+        kids.push(format!("{page_id} 0 R"));
+        report.written += 1;
+    }
+
+    if report.written == 0 {
+        return Ok(report);
+    }
+
+    let count = kids.len();
+    pdf.object(
+        pages_id,
+        &format!(
+            "<< /Type /Pages /Kids [{}] /Count {count} >>",
+            kids.join(" ")
+        ),
+    )?;
+    pdf.object(
+        catalog_id,
+        &format!("<< /Type /Catalog /Pages {pages_id} 0 R >>"),
+    )?;
+
+    let _ = progress_sender.send(Progress::new(count as u64, Some(count as u64)).msg("Writing"));
+    pdf.finish(catalog_id)?;
+    Ok(report)
+}
+
+/// Writes the objects of a PDF as they come, so that only one page is kept in memory
+struct PdfWriter<W: Write> {
+    out: W,
+
+    /// Bytes written so far
+    pos: u64,
+
+    /// Offset of each object, the id of an object is its index plus one
+    offsets: Vec<u64>,
+}
+
+impl<W: Write> PdfWriter<W> {
+    fn new(out: W) -> io::Result<Self> {
+        let mut this = Self {
+            out,
+            pos: 0,
+            offsets: Vec::new(),
+        };
+
+        // the comment of non ASCII bytes tells that the file is binary
+        this.write(b"%PDF-1.5\n%\xE2\xE3\xCF\xD3\n")?;
+        Ok(this)
+    }
+
+    /// The id of an object written later, which may be referred to before
+    fn reserve(&mut self) -> usize {
+        self.offsets.push(0);
+        self.offsets.len()
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.out.write_all(bytes)?;
+        self.pos += bytes.len() as u64;
+        Ok(())
+    }
+
+    fn object(&mut self, id: usize, value: &str) -> io::Result<()> {
+        self.offsets[id - 1] = self.pos;
+        self.write(format!("{id} 0 obj\n{value}\nendobj\n").as_bytes())
+    }
+
+    /// `dict` is the entries of the stream dictionary besides its length
+    fn stream(&mut self, id: usize, dict: &str, data: &[u8]) -> io::Result<()> {
+        self.offsets[id - 1] = self.pos;
+        let header = format!("{id} 0 obj\n<< {dict} /Length {} >>\nstream\n", data.len());
+        self.write(header.as_bytes())?;
+        self.write(data)?;
+        self.write(b"\nendstream\nendobj\n")
+    }
+
+    /// Writes the cross reference table and the trailer
+    fn finish(mut self, root_id: usize) -> io::Result<()> {
+        let xref_pos = self.pos;
+        let size = self.offsets.len() + 1;
+
+        let mut tail = format!("xref\n0 {size}\n0000000000 65535 f \n");
+        for offset in &self.offsets {
+            let _ = writeln!(tail, "{offset:010} 00000 n ");
+        }
+        let _ = write!(
+            tail,
+            "trailer\n<< /Size {size} /Root {root_id} 0 R >>\nstartxref\n{xref_pos}\n%%EOF\n"
+        );
+
+        self.write(tail.as_bytes())?;
+        self.out.flush()
+    }
+}
+
+/// An image as a PDF image XObject takes it
+struct PageImage {
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+    filter: &'static str,
+    data: Vec<u8>,
+}
+
+/// The image of the page at `path`
+fn page_image(path: &str) -> Result<PageImage, String> {
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+
+    if let Some((width, height, color_space)) = jpeg_info(&bytes) {
+        return Ok(PageImage {
+            width,
+            height,
+            color_space,
+            filter: "DCTDecode",
+            data: bytes,
+        });
+    }
+
+    let image = image::load_from_memory(&bytes)
+        .map_err(|err| err.to_string())?
+        .to_rgba8();
+    let (width, height) = image.dimensions();
+
+    // PDF images have no alpha without a soft mask, transparent parts are shown on white
+    let rgb: Vec<u8> = image
+        .pixels()
+        .flat_map(|p| {
+            let alpha = p[3] as u16;
+            [0, 1, 2].map(|i| ((p[i] as u16 * alpha + 255 * (255 - alpha)) / 255) as u8)
+        })
+        .collect();
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let data = encoder
+        .write_all(&rgb)
+        .and_then(|_| encoder.finish())
+        .map_err(|err| err.to_string())?;
+
+    Ok(PageImage {
+        width,
+        height,
+        color_space: "DeviceRGB",
+        filter: "FlateDecode",
+        data,
+    })
+}
+
+/// Size and color space of a JPEG that PDF readers can show as it is,
+/// which is a baseline or progressive one in gray or YCbCr
+fn jpeg_info(bytes: &[u8]) -> Option<(u32, u32, &'static str)> {
+    if !bytes.starts_with(&[0xFF, 0xD8]) {
+        return None;
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= bytes.len() {
+        if bytes[pos] != 0xFF {
+            return None;
+        }
+
+        let marker = bytes[pos + 1];
+        if marker == 0xFF {
+            // fill byte
+            pos += 1;
+            continue;
+        }
+
+        let len = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        match marker {
+            // SOF0, SOF1 and SOF2
+            0xC0..=0xC2 => {
+                let frame = bytes.get(pos + 4..pos + 2 + len)?;
+                if frame.len() < 6 || frame[0] != 8 {
+                    return None;
+                }
+
+                let height = u16::from_be_bytes([frame[1], frame[2]]) as u32;
+                let width = u16::from_be_bytes([frame[3], frame[4]]) as u32;
+                let color_space = match frame[5] {
+                    1 => "DeviceGray",
+                    3 => "DeviceRGB",
+                    _ => return None,
+                };
+
+                return (width > 0 && height > 0).then_some((width, height, color_space));
+            }
+            // other SOFs, lossless or arithmetic coding, which are rarely supported
+            0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF => return None,
+            // start of scan, the frame header must come before it
+            0xDA => return None,
+            _ => pos += 2 + len,
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A JPEG up to its start of scan, of 200x300 pixels
+    fn jpeg(sof: u8, precision: u8, components: u8) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00];
+        let len = 8 + 3 * components as u16;
+        bytes.extend([0xFF, sof]);
+        bytes.extend(len.to_be_bytes());
+        bytes.extend([precision, 0x01, 0x2C, 0x00, 0xC8, components]);
+        bytes.extend(std::iter::repeat_n(0, 3 * components as usize));
+        bytes.extend([0xFF, 0xDA, 0x00, 0x02]);
+        bytes
+    }
+
+    #[test]
+    fn baseline_and_progressive_jpegs_are_embedded() {
+        assert_eq!(jpeg_info(&jpeg(0xC0, 8, 3)), Some((200, 300, "DeviceRGB")));
+        assert_eq!(jpeg_info(&jpeg(0xC2, 8, 1)), Some((200, 300, "DeviceGray")));
+    }
+
+    #[test]
+    fn other_jpegs_are_decoded() {
+        // CMYK, 12 bits and lossless
+        assert_eq!(jpeg_info(&jpeg(0xC0, 8, 4)), None);
+        assert_eq!(jpeg_info(&jpeg(0xC1, 12, 3)), None);
+        assert_eq!(jpeg_info(&jpeg(0xC3, 8, 3)), None);
+
+        assert_eq!(jpeg_info(&jpeg(0xC0, 8, 3)[..12]), None);
+        assert_eq!(jpeg_info(b"\x89PNG\r\n\x1a\n"), None);
+    }
+
+    fn find(bytes: &[u8], pattern: &[u8]) -> Option<usize> {
+        bytes.windows(pattern.len()).position(|w| w == pattern)
+    }
+
+    /// The number right after the first `prefix` in `bytes`
+    fn number_after(bytes: &[u8], prefix: &[u8]) -> usize {
+        let start = find(bytes, prefix).unwrap() + prefix.len();
+        let len = bytes[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        std::str::from_utf8(&bytes[start..start + len])
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn exported_pdf_is_well_formed() {
+        let dir = std::env::temp_dir().join(format!("manga-reader-pdf-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let jpeg_page = dir.join("1.jpg");
+        std::fs::write(&jpeg_page, jpeg(0xC0, 8, 3)).unwrap();
+        let png_page = dir.join("2.png");
+        image::RgbaImage::from_pixel(3, 2, image::Rgba([255, 0, 0, 128]))
+            .save(&png_page)
+            .unwrap();
+
+        let pages = [&jpeg_page, &png_page].map(|page| page.to_string_lossy().into_owned());
+        let path = dir.join("out.pdf");
+        let (_cancel_sender, cancel_receiver) = std::sync::mpsc::channel();
+        let (progress_sender, _progress_receiver) = std::sync::mpsc::channel();
+        let report = export(&pages, &path, cancel_receiver, progress_sender).unwrap();
+        assert_eq!((report.written, report.failed.len()), (2, 0));
+        assert!(!path.with_extension("pdf.part").exists());
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"%PDF-1.5\n"));
+        assert!(bytes.ends_with(b"%%EOF\n"));
+
+        // the offset after `startxref` points at the table, which is the last section
+        let xref_pos = number_after(&bytes, b"\nstartxref\n");
+        assert!(bytes[xref_pos..].starts_with(b"xref\n0 "));
+        let tail = &bytes[xref_pos..];
+
+        let size = number_after(tail, b"xref\n0 ");
+        assert_eq!(number_after(tail, b"/Size "), size);
+        assert_eq!(size, 1 + 2 + 2 * 3);
+
+        // entries are 20 bytes each, following the subsection line
+        let entries = &tail[format!("xref\n0 {size}\n").len()..];
+        assert!(entries.starts_with(b"0000000000 65535 f \n"));
+        for id in 1..size {
+            let entry = std::str::from_utf8(&entries[20 * id..20 * (id + 1)]).unwrap();
+            assert!(entry.ends_with(" 00000 n \n"), "{entry:?}");
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(
+                bytes[offset..].starts_with(format!("{id} 0 obj\n").as_bytes()),
+                "object {id}"
+            );
+        }
+
+        let pages_dict = find(&bytes, b"/Type /Pages /Kids [").unwrap();
+        let kids_start = pages_dict + b"/Type /Pages /Kids [".len();
+        let kids_end = kids_start + find(&bytes[kids_start..], b"]").unwrap();
+        let kids: Vec<usize> = std::str::from_utf8(&bytes[kids_start..kids_end])
+            .unwrap()
+            .split(" 0 R")
+            .map(str::trim)
+            .filter(|kid| !kid.is_empty())
+            .map(|kid| kid.parse().unwrap())
+            .collect();
+        assert_eq!(kids.len(), 2);
+        assert_eq!(number_after(&bytes[kids_end..], b"/Count "), 2);
+        for kid in kids {
+            let start = find(&bytes, format!("\n{kid} 0 obj\n").as_bytes()).unwrap();
+            assert!(
+                bytes[start..].starts_with(format!("\n{kid} 0 obj\n<< /Type /Page ").as_bytes())
+            );
+        }
+
+        assert!(find(&bytes, b"/Filter /DCTDecode").is_some());
+        assert!(find(&bytes, b"/Filter /FlateDecode").is_some());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most directories kept in the history
+pub const MAX_RECENT_DIRS: usize = 12;
+
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct RecentDir {
+    pub dir: String,
+    /// Image read last, the search starts from it to resume
+    pub page: String,
+    pub page_idx: usize,
+    /// Seconds since the unix epoch
+    pub read_time: u64,
+    /// First image of the directory
+    pub thumbnail: Option<String>,
+}
+
+impl RecentDir {
+    pub fn dir_name(&self) -> String {
+        std::path::Path::new(&self.dir)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.dir.clone())
+    }
+
+    pub fn exists(&self) -> bool {
+        std::path::Path::new(&self.dir).is_dir()
+    }
+
+    /// Path to search to open it, the page if it's still there
+    pub fn resume_path(&self) -> &str {
+        if std::path::Path::new(&self.page).is_file() {
+            &self.page
+        } else {
+            &self.dir
+        }
+    }
+}
+
+/// Directories read recently, the latest first
+#[derive(Deserialize, Serialize, Default, Debug)]
+#[serde(default)]
+pub struct RecentDirs {
+    entries: Vec<RecentDir>,
+    /// Whether each of the entries exists, checked once after they change
+    #[serde(skip)]
+    exists: Option<Vec<bool>>,
+}
+
+impl RecentDirs {
+    pub fn entries(&self) -> &[RecentDir] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether each of the entries exists
+    pub fn exists(&mut self) -> &[bool] {
+        self.exists
+            .get_or_insert_with(|| self.entries.iter().map(RecentDir::exists).collect())
+    }
+
+    /// Records `page` of `dir` is being read, moving `dir` to the front
+    pub fn update(&mut self, dir: &str, page: &str, page_idx: usize, thumbnail: Option<&String>) {
+        let entry = RecentDir {
+            dir: dir.to_owned(),
+            page: page.to_owned(),
+            page_idx,
+            read_time: now_secs(),
+            thumbnail: thumbnail.cloned(),
+        };
+        eapp_utils::push_recent_by(&mut self.entries, entry, MAX_RECENT_DIRS, |a, b| {
+            a.dir == b.dir
+        });
+        self.exists = None;
+    }
+
+    pub fn remove(&mut self, dir: &str) {
+        self.entries.retain(|entry| entry.dir != dir);
+        self.exists = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.exists = None;
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Rough time since `secs`, e.g. `3 days ago`
+pub fn format_read_time(secs: u64) -> String {
+    let elapsed = now_secs().saturating_sub(secs);
+    match elapsed {
+        0..60 => "just now".to_owned(),
+        60..3600 => format!("{} min ago", elapsed / 60),
+        3600..86400 => format!("{} h ago", elapsed / 3600),
+        _ => format!("{} days ago", elapsed / 86400),
+    }
+}
@@ -7,11 +7,15 @@ use image::{
     codecs::{gif::GifDecoder, webp::WebPDecoder},
 };
 use std::{
+    collections::VecDeque,
     io::Cursor,
     time::{Duration, Instant},
 };
 
-use crate::lifo;
+use crate::{
+    img_utils::{self, ColorAdjust, DisplayFilter, ExifFields},
+    lifo,
+};
 
 enum Image {
     Static(egui::ColorImage),
@@ -19,7 +23,7 @@ enum Image {
 }
 
 enum LoadCommand {
-    Load(String),
+    Load(String, DisplayFilter, ColorAdjust),
 }
 
 pub enum Texture {
@@ -55,9 +59,19 @@ impl Texture {
 
 pub struct TexLoader {
     textures: HashMap<String, Option<Texture>>,
+    /// Of the filtered pages, so that the placeholder looks the same as the page
     average_colors: HashMap<String, egui::Color32>,
+    /// Of the loaded pages which have EXIF
+    exif: HashMap<String, ExifFields>,
+    filter: DisplayFilter,
+    /// Only its pixel part is applied here, see [`ColorAdjust::same_pixels`]
+    adjust: ColorAdjust,
+    /// Paths of `textures`, the least recently used first
+    recent: VecDeque<String>,
+    /// Most textures kept, the least recently used ones are forgotten over it
+    capacity: usize,
     sender: lifo::Sender<LoadCommand>,
-    receiver: std::sync::mpsc::Receiver<(String, Image)>,
+    receiver: std::sync::mpsc::Receiver<(String, DisplayFilter, ColorAdjust, Image, ExifFields)>,
 }
 
 fn calculate_average_color(pixels: &[egui::Color32]) -> egui::Color32 {
@@ -98,16 +112,18 @@ impl TexLoader {
                 };
 
                 match cmd {
-                    LoadCommand::Load(image_path) => {
-                        let image = match Self::load_image(&image_path) {
-                            Ok(image) => image,
+                    LoadCommand::Load(image_path, filter, adjust) => {
+                        let (image, exif) = match Self::load_image(&image_path, filter, adjust) {
+                            Ok(loaded) => loaded,
                             Err(error) => {
                                 log::warn!("error when load image '{image_path}': {error}");
                                 continue;
                             }
                         };
 
-                        image_sender.send((image_path, image)).unwrap();
+                        image_sender
+                            .send((image_path, filter, adjust, image, exif))
+                            .unwrap();
                         ctx.request_repaint();
                     }
                 };
@@ -117,17 +133,84 @@ impl TexLoader {
         Self {
             textures,
             average_colors,
+            exif: HashMap::new(),
+            filter: DisplayFilter::default(),
+            adjust: ColorAdjust::default(),
+            recent: VecDeque::new(),
+            capacity: usize::MAX,
             sender,
             receiver,
         }
     }
 
+    /// Loads all the pages again if `filter` is changed, returns whether it's changed
+    pub fn set_filter(&mut self, filter: DisplayFilter) -> bool {
+        if self.filter == filter {
+            return false;
+        }
+
+        self.filter = filter;
+        self.reload_all();
+        true
+    }
+
+    /// Loads all the pages again if the pixels are adjusted differently by `adjust`,
+    /// returns whether they are
+    pub fn set_adjust(&mut self, adjust: ColorAdjust) -> bool {
+        let same_pixels = self.adjust.same_pixels(adjust);
+        self.adjust = adjust;
+        if same_pixels {
+            return false;
+        }
+
+        self.reload_all();
+        true
+    }
+
+    fn reload_all(&mut self) {
+        self.textures.clear();
+        self.recent.clear();
+        self.exif.clear();
+        self.average_colors.clear();
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+    }
+
     pub fn load(&mut self, image_path: &str) {
-        if !self.textures.contains_key(image_path) {
-            self.textures.insert(image_path.to_owned(), None);
-            self.sender
-                .send(LoadCommand::Load(image_path.to_owned()))
-                .unwrap();
+        if self.textures.contains_key(image_path) {
+            self.touch(image_path);
+            return;
+        }
+
+        self.textures.insert(image_path.to_owned(), None);
+        self.recent.push_back(image_path.to_owned());
+        self.sender
+            .send(LoadCommand::Load(
+                image_path.to_owned(),
+                self.filter,
+                self.adjust,
+            ))
+            .unwrap();
+
+        // the one just asked for is the last to forget
+        while self.textures.len() > self.capacity
+            && let Some(oldest) = self.recent.pop_front()
+        {
+            self.textures.remove(&oldest);
+            self.exif.remove(&oldest);
+        }
+    }
+
+    fn touch(&mut self, image_path: &str) {
+        if self.recent.back().is_some_and(|path| path == image_path) {
+            return;
+        }
+
+        if let Some(idx) = self.recent.iter().position(|path| path == image_path) {
+            let path = self.recent.remove(idx).unwrap();
+            self.recent.push_back(path);
         }
     }
 
@@ -178,7 +261,16 @@ impl TexLoader {
             }
 
             match self.receiver.try_recv() {
-                Ok((image_path, image)) => {
+                Ok((image_path, filter, adjust, image, exif)) => {
+                    // loaded before the filter is changed
+                    if filter != self.filter || !adjust.same_pixels(self.adjust) {
+                        continue;
+                    }
+
+                    if !exif.is_empty() && self.textures.contains_key(&image_path) {
+                        self.exif.insert(image_path.clone(), exif);
+                    }
+
                     if let Some(opt_texture) = self.textures.get_mut(&image_path) {
                         if opt_texture.is_some() {
                             continue;
@@ -243,59 +335,86 @@ impl TexLoader {
         &self.textures
     }
 
+    /// EXIF fields of a loaded page, `None` if it has no EXIF
+    pub fn exif(&self, image_path: &str) -> Option<&ExifFields> {
+        self.exif.get(image_path)
+    }
+
     pub fn forget_all(&mut self) {
         self.textures.clear();
+        self.recent.clear();
+        self.exif.clear();
     }
 
-    fn dynamic_image_to_image(img: DynamicImage) -> Image {
+    fn dynamic_image_to_image(
+        img: DynamicImage,
+        filter: DisplayFilter,
+        adjust: ColorAdjust,
+    ) -> Image {
         let size = [img.width() as _, img.height() as _];
         let image_buffer = img.to_rgba8();
         let pixels = image_buffer.as_flat_samples();
-        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
+        let mut color_image = egui::ColorImage::from_rgba_unmultiplied(size, pixels.as_slice());
+        filter.apply(&mut color_image.pixels);
+        adjust.apply(&mut color_image.pixels);
 
         Image::Static(color_image)
     }
 
-    fn frames_to_image(frames: Vec<Frame>) -> Image {
+    fn frames_to_image(frames: Vec<Frame>, filter: DisplayFilter, adjust: ColorAdjust) -> Image {
         let frames = frames
             .into_iter()
             .map(|frame| {
                 let (num, den) = frame.delay().numer_denom_ms();
                 let delay_ms = num as f32 / den as f32;
-                (
-                    egui::ColorImage::from_rgba_unmultiplied(
-                        [frame.buffer().width() as _, frame.buffer().height() as _],
-                        frame.buffer(),
-                    ),
-                    delay_ms as u64,
-                )
+                let mut color_image = egui::ColorImage::from_rgba_unmultiplied(
+                    [frame.buffer().width() as _, frame.buffer().height() as _],
+                    frame.buffer(),
+                );
+                filter.apply(&mut color_image.pixels);
+                adjust.apply(&mut color_image.pixels);
+                (color_image, delay_ms as u64)
             })
             .collect();
 
         Image::Animated(frames)
     }
 
-    fn load_image(image_path: &str) -> Result<Image, Box<dyn std::error::Error>> {
+    /// Decodes the page, and reads its EXIF here as well to keep it off the UI thread
+    fn load_image(
+        image_path: &str,
+        filter: DisplayFilter,
+        adjust: ColorAdjust,
+    ) -> Result<(Image, ExifFields), Box<dyn std::error::Error>> {
         let content = std::fs::read(image_path)?;
+        let exif = img_utils::read_exif(&content);
         let image = match image::guess_format(&content)? {
             image::ImageFormat::Gif => Self::frames_to_image(
                 GifDecoder::new(Cursor::new(content))?
                     .into_frames()
                     .collect_frames()?,
+                filter,
+                adjust,
             ),
             image::ImageFormat::WebP => {
                 let decoder = WebPDecoder::new(Cursor::new(&content))?;
                 if decoder.has_animation() {
-                    Self::frames_to_image(decoder.into_frames().collect_frames()?)
+                    Self::frames_to_image(decoder.into_frames().collect_frames()?, filter, adjust)
                 } else {
-                    Self::dynamic_image_to_image(DynamicImage::from_decoder(decoder)?)
+                    Self::dynamic_image_to_image(
+                        DynamicImage::from_decoder(decoder)?,
+                        filter,
+                        adjust,
+                    )
                 }
             }
-            fmt => {
-                Self::dynamic_image_to_image(image::load_from_memory_with_format(&content, fmt)?)
-            }
+            fmt => Self::dynamic_image_to_image(
+                image::load_from_memory_with_format(&content, fmt)?,
+                filter,
+                adjust,
+            ),
         };
 
-        Ok(image)
+        Ok((image, exif))
     }
 }
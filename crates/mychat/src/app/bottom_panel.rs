@@ -1,15 +1,17 @@
 use eapp_utils::{
     codicons::{
         ICON_ARROW_CIRCLE_DOWN, ICON_ARROW_CIRCLE_UP, ICON_ARROW_UP, ICON_CLOUD_UPLOAD,
-        ICON_OPEN_PREVIEW, ICON_PREVIEW, ICON_STOP_CIRCLE,
+        ICON_HISTORY, ICON_OPEN_PREVIEW, ICON_PREVIEW, ICON_STOP_CIRCLE,
     },
     widgets::simple_widgets::frameless_btn,
 };
 use eframe::egui::{self, Button, TextEdit, Widget};
 
-use crate::chat::{Message, Role};
+use crate::chat::Role;
 
 impl super::App {
+    const DEFAULT_CONTEXT_WINDOW: usize = 10;
+
     pub fn ui_bottom_panel(&mut self, ui: &mut egui::Ui) {
         ui.separator();
 
@@ -47,6 +49,23 @@ impl super::App {
             {
                 self.scroll_to_summary = true;
             }
+
+            let dialogue = self.manager.cur_dialogue_mut();
+            let windowed = dialogue.context_window.is_some();
+            if ui
+                .selectable_label(windowed, ICON_HISTORY.to_string())
+                .on_hover_text("Send only the last messages of this chat, instead of summarizing")
+                .clicked()
+            {
+                dialogue.context_window = (!windowed).then_some(Self::DEFAULT_CONTEXT_WINDOW);
+            }
+            if let Some(len) = &mut dialogue.context_window {
+                egui::DragValue::new(len)
+                    .range(1..=usize::MAX)
+                    .suffix(" messages")
+                    .ui(ui);
+            }
+
             ui.selectable_value(&mut self.role, Role::System, "System");
             ui.selectable_value(&mut self.role, Role::Assistant, "Assistant");
             ui.selectable_value(&mut self.role, Role::User, "User");
@@ -65,6 +84,7 @@ impl super::App {
                     .show(ui, |ui| {
                         ui.centered_and_justified(|ui| {
                             TextEdit::multiline(&mut self.input)
+                                .id(egui::Id::new(Self::INPUT_ID))
                                 .hint_text("Type a message")
                                 .desired_width(scroll_width - 8.0)
                                 .ui(ui);
@@ -81,39 +101,10 @@ impl super::App {
             let icon = egui::RichText::new(icon).size(24.0);
 
             if ui.add_sized(button_size, Button::new(icon)).clicked() {
-                if self.manager.is_empty() {
-                    self.manager.new_dialogue();
-                }
-
-                match (is_idle, self.edit_summary) {
-                    (true, true) => {
-                        let input = self.input.trim();
-                        self.manager.cur_dialogue_mut().summary.message.content = input.to_owned();
-                        self.edit_summary = false;
-                        self.input.clear();
-                    }
-                    (true, false) => {
-                        let input = self.input.trim();
-                        if !input.is_empty() {
-                            let thinking_content = self.thinking_content.take();
-                            self.manager.push_message(Message {
-                                role: self.role,
-                                content: input.to_owned(),
-                                thinking_content,
-                            });
-
-                            self.input.clear();
-                        }
-
-                        if self.state.trigger_request {
-                            self.last_summary.0 =
-                                self.manager.cur_dialogue().amount_of_message_summarized;
-                            self.last_summary.1 =
-                                self.manager.cur_dialogue().summary.message.clone();
-                            self.manager.trigger_request();
-                        }
-                    }
-                    (false, _) => self.manager.cancel(),
+                if is_idle {
+                    self.submit_input();
+                } else {
+                    self.manager.cancel();
                 }
             }
         });
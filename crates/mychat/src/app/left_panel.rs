@@ -1,65 +1,289 @@
-use eapp_utils::widgets::simple_widgets::frameless_btn;
-use eframe::egui::{self};
+use eapp_utils::{
+    codicons::{ICON_ARCHIVE, ICON_CHEVRON_DOWN, ICON_CHEVRON_RIGHT, ICON_NEW_FOLDER, ICON_PINNED},
+    widgets::simple_widgets::frameless_btn,
+};
+use eframe::egui::{self, collapsing_header::CollapsingState};
+use std::collections::HashMap;
+
+/// A row of the list, which are laid out only when in view
+enum Row {
+    /// Header of the folder at the index, which is the ungrouped ones past the folders
+    Folder(usize),
+
+    /// Dialogue at the index and its indent
+    Dialogue(usize, usize),
+}
+
+/// Applied once the list is shown, as the dialogues are borrowed while showing it
+enum ListAction {
+    Remove(usize),
+    MoveTo(usize, Option<String>),
+    MoveToNewFolder(usize),
+    RenameFolder(String, String),
+    RemoveFolder(String),
+}
 
 impl super::App {
     pub fn ui_left_panel(&mut self, ui: &mut egui::Ui) {
         ui.add_space(4.0);
 
-        if ui
-            .add_sized([ui.available_width(), 26.0], egui::Button::new("New Chat"))
-            .clicked()
-        {
-            self.manager.new_dialogue();
-        }
+        ui.horizontal(|ui| {
+            let buttons_width = (ui.spacing().interact_size.y + ui.spacing().item_spacing.x) * 2.0;
+            if ui
+                .add_sized(
+                    [ui.available_width() - buttons_width, 26.0],
+                    egui::Button::new("New Chat"),
+                )
+                .clicked()
+            {
+                self.manager.new_dialogue();
+            }
+
+            if frameless_btn(ui, ICON_NEW_FOLDER.to_string())
+                .on_hover_text("New folder")
+                .clicked()
+            {
+                self.manager.data.add_folder();
+            }
+
+            if ui
+                .selectable_label(self.state.show_archived, ICON_ARCHIVE.to_string())
+                .on_hover_text("Show archived chats")
+                .clicked()
+            {
+                self.state.show_archived = !self.state.show_archived;
+            }
+        });
 
         ui.add_space(4.0);
 
-        let row_height = ui.spacing().interact_size.y;
-        let total_rows = self.manager.len();
+        let listed = self.manager.data.listed(self.state.show_archived);
+        let folders = self.manager.data.folders.clone();
+
+        // the last group is of the ungrouped ones, and of those in a folder that's gone
+        let folder_pos: HashMap<&str, usize> = folders
+            .iter()
+            .enumerate()
+            .map(|(pos, name)| (name.as_str(), pos))
+            .collect();
+        let mut groups = vec![Vec::new(); folders.len() + 1];
+        for &idx in &listed {
+            let group = self
+                .manager
+                .dialogue(idx)
+                .folder
+                .as_deref()
+                .and_then(|folder| folder_pos.get(folder).copied())
+                .unwrap_or(folders.len());
+            groups[group].push(idx);
+        }
+
+        // headers are only shown once there is a folder
+        let mut rows = Vec::with_capacity(listed.len() + groups.len());
+        for (group, dialogues) in groups.iter().enumerate() {
+            if folders.is_empty() {
+                rows.extend(dialogues.iter().map(|&idx| Row::Dialogue(idx, 0)));
+                continue;
+            }
 
-        let mut idx_to_remove = None;
+            rows.push(Row::Folder(group));
+            if Self::folder_state(ui.ctx(), folders.get(group)).is_open() {
+                rows.extend(dialogues.iter().map(|&idx| Row::Dialogue(idx, 1)));
+            }
+        }
+
+        let row_height = ui.spacing().interact_size.y;
+        let mut action = None;
+        let mut folder_menu_shown = false;
         egui::ScrollArea::both()
             .auto_shrink([false, true])
-            .show_rows(ui, row_height, total_rows, |ui, row_range| {
+            .show_rows(ui, row_height, rows.len(), |ui, row_range| {
                 ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
                     ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
 
-                    for idx in row_range {
-                        let is_current = idx == self.manager.cur_dialogue_idx;
-                        let dialogue = self.manager.dialogue_mut(idx);
-                        let title = if dialogue.messages.is_empty() {
-                            "New Chat".to_string()
-                        } else {
-                            dialogue
-                                .messages
-                                .front()
-                                .map(|m| m.message.content.chars().take(20).collect())
-                                .unwrap()
-                        };
-
-                        let response = ui.selectable_label(is_current, &title).on_hover_text(title);
-                        if response.clicked() {
-                            self.manager.cur_dialogue_idx = idx;
-                        }
+                    for row in &rows[row_range] {
+                        match *row {
+                            Row::Folder(group) => {
+                                let folder = folders.get(group);
+                                let mut state = Self::folder_state(ui.ctx(), folder);
+                                let icon = if state.is_open() {
+                                    ICON_CHEVRON_DOWN
+                                } else {
+                                    ICON_CHEVRON_RIGHT
+                                };
+                                let name = folder.map_or("Ungrouped", String::as_str);
 
-                        response.context_menu(|ui| {
-                            if ui
-                                .add_enabled_ui(self.manager.is_dialogue_idle(idx), |ui| {
-                                    frameless_btn(ui, "Delete this chat")
-                                })
-                                .inner
-                                .clicked()
-                            {
-                                idx_to_remove = Some(idx);
-                                ui.close();
+                                let response = ui.selectable_label(false, format!("{icon} {name}"));
+                                if response.clicked() {
+                                    state.toggle(ui);
+                                    state.store(ui.ctx());
+                                }
+
+                                if let Some(folder) = folder {
+                                    response.context_menu(|ui| {
+                                        folder_menu_shown = true;
+                                        self.ui_folder_menu(ui, folder, &mut action);
+                                    });
+                                }
+                            }
+                            Row::Dialogue(idx, indent) => {
+                                self.ui_dialogue_row(ui, idx, indent, &folders, &mut action);
                             }
-                        });
+                        }
                     }
                 })
             });
 
-        if let Some(idx) = idx_to_remove {
-            self.manager.remove_dialogue(idx);
+        // what's typed is dropped once the menu is closed without Enter
+        if !folder_menu_shown {
+            self.folder_rename = None;
+        }
+
+        match action {
+            Some(ListAction::Remove(idx)) => self.ask_delete_dialogue(idx),
+            Some(ListAction::MoveTo(idx, folder)) => self.manager.dialogue_mut(idx).folder = folder,
+            Some(ListAction::MoveToNewFolder(idx)) => {
+                let folder = self.manager.data.add_folder();
+                self.manager.dialogue_mut(idx).folder = Some(folder);
+            }
+            Some(ListAction::RenameFolder(old, new)) => {
+                self.folder_rename = None;
+                if !self.manager.data.rename_folder(&old, &new) {
+                    self.toasts
+                        .warn(format!("Folder '{}' can't be used", new.trim()));
+                }
+            }
+            Some(ListAction::RemoveFolder(name)) => self.manager.data.remove_folder(&name),
+            None => (),
+        }
+    }
+
+    /// Whether the folder, or the ungrouped ones if `None`, is expanded
+    fn folder_state(ctx: &egui::Context, folder: Option<&String>) -> CollapsingState {
+        let id = egui::Id::new(("dialogue_folder", folder));
+        CollapsingState::load_with_default_open(ctx, id, true)
+    }
+
+    /// `indent` is in levels, besides the depth of the branch
+    fn ui_dialogue_row(
+        &mut self,
+        ui: &mut egui::Ui,
+        idx: usize,
+        indent: usize,
+        folders: &[String],
+        action: &mut Option<ListAction>,
+    ) {
+        let is_current = idx == self.manager.cur_dialogue_idx;
+        let dialogue = self.manager.dialogue(idx);
+        let (pinned, archived) = (dialogue.pinned, dialogue.archived);
+        let folder = dialogue.folder.clone();
+        let title = dialogue.title();
+        let depth = self.manager.branch_depth(idx) + indent;
+
+        let mut text = egui::RichText::new(match (pinned, archived) {
+            (true, _) => format!("{ICON_PINNED} {title}"),
+            (false, true) => format!("{ICON_ARCHIVE} {title}"),
+            (false, false) => title.clone(),
+        });
+        if archived {
+            text = text.weak();
+        }
+
+        let response = if depth > 0 {
+            ui.horizontal(|ui| {
+                ui.add_space(depth as f32 * ui.spacing().indent);
+                ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
+                    ui.selectable_label(is_current, text)
+                })
+                .inner
+            })
+            .inner
+        } else {
+            ui.selectable_label(is_current, text)
+        }
+        .on_hover_text(title);
+        if response.clicked() {
+            self.manager.cur_dialogue_idx = idx;
+        }
+
+        response.context_menu(|ui| {
+            let dialogue = self.manager.dialogue_mut(idx);
+            if frameless_btn(ui, if pinned { "Unpin" } else { "Pin" }).clicked() {
+                dialogue.pinned = !pinned;
+                ui.close();
+            }
+
+            if frameless_btn(ui, if archived { "Unarchive" } else { "Archive" }).clicked() {
+                dialogue.archived = !archived;
+                ui.close();
+            }
+
+            ui.menu_button("Move to", |ui| {
+                for name in folders {
+                    if ui
+                        .selectable_label(folder.as_ref() == Some(name), name)
+                        .clicked()
+                    {
+                        *action = Some(ListAction::MoveTo(idx, Some(name.clone())));
+                        ui.close();
+                    }
+                }
+
+                if ui.selectable_label(folder.is_none(), "Ungrouped").clicked() {
+                    *action = Some(ListAction::MoveTo(idx, None));
+                    ui.close();
+                }
+
+                ui.separator();
+
+                if frameless_btn(ui, "New folder").clicked() {
+                    *action = Some(ListAction::MoveToNewFolder(idx));
+                    ui.close();
+                }
+            });
+
+            if ui
+                .add_enabled_ui(self.manager.is_dialogue_idle(idx), |ui| {
+                    frameless_btn(ui, "Delete this chat")
+                })
+                .inner
+                .clicked()
+            {
+                *action = Some(ListAction::Remove(idx));
+                ui.close();
+            }
+        });
+    }
+
+    fn ui_folder_menu(&mut self, ui: &mut egui::Ui, folder: &str, action: &mut Option<ListAction>) {
+        // the name being edited is kept while the menu of the same folder is open
+        if self
+            .folder_rename
+            .as_ref()
+            .is_none_or(|(old, _)| old != folder)
+        {
+            self.folder_rename = Some((folder.to_owned(), folder.to_owned()));
+        }
+        let (_, new_name) = self.folder_rename.as_mut().unwrap();
+
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            let response = ui.text_edit_singleline(new_name);
+            if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                *action = Some(ListAction::RenameFolder(
+                    folder.to_owned(),
+                    new_name.clone(),
+                ));
+                ui.close();
+            }
+        });
+
+        if frameless_btn(ui, "Delete this folder")
+            .on_hover_text("The chats in it become ungrouped")
+            .clicked()
+        {
+            *action = Some(ListAction::RemoveFolder(folder.to_owned()));
+            ui.close();
         }
     }
 }
@@ -1,18 +1,27 @@
 mod bottom_panel;
 mod left_panel;
 mod right_panel;
+mod security;
 mod setting;
+mod shortcut;
+mod usage;
 
 use eapp_utils::{
     borderless,
-    codicons::{ICON_LAYOUT_SIDEBAR_LEFT, ICON_SETTINGS_GEAR, ICON_TERMINAL},
+    codicons::{ICON_INFO, ICON_LAYOUT_SIDEBAR_LEFT, ICON_SETTINGS_GEAR, ICON_TERMINAL},
     delayed_toggle::DelayedToggle,
     get_body_font_id, get_button_height,
+    persistent_window::PersistentWindow,
     ui_font_selector::UiFontSelector,
-    widgets::simple_widgets::{frameless_btn, get_theme_button, theme_button},
+    widgets::{
+        confirm::Confirm,
+        simple_widgets::{frameless_btn, get_theme_button, theme_button},
+        toast::Toasts,
+    },
 };
 use eframe::egui::{self, Color32, UiBuilder, Vec2};
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 use crate::chat::{Message, Role, config::ChatConfig, dialogue_manager::DialogueManager};
 
@@ -24,6 +33,15 @@ pub struct State {
     pub show_bottom_panel: bool,
     pub show_summarized: bool,
     pub trigger_request: bool,
+
+    /// Send with `Enter` instead of `Ctrl+Enter`
+    pub enter_sends: bool,
+
+    /// Lists the archived dialogues along with the others
+    pub show_archived: bool,
+
+    /// Types streaming replies out at a steady rate instead of showing them as they arrive
+    pub typing_animation: bool,
 }
 
 impl Default for State {
@@ -34,6 +52,9 @@ impl Default for State {
             show_bottom_panel: true,
             show_summarized: true,
             trigger_request: true,
+            enter_sends: false,
+            show_archived: false,
+            typing_animation: false,
         }
     }
 }
@@ -45,14 +66,32 @@ pub struct App {
     thinking_content: Option<String>,
     role: Role,
     config: ChatConfig,
-    status_msg: String,
-    edit_summary: bool,
+    toasts: Toasts,
+    /// Summary being edited and the id of its dialogue
+    summary_edit: Option<(u64, String)>,
     last_summary: (usize, Message),
     scroll_to_top: bool,
     scroll_to_bottom: bool,
     scroll_to_summary: bool,
     toggle: DelayedToggle,
     selector: UiFontSelector,
+    window: PersistentWindow,
+    confirm_delete: Confirm<Option<u64>>,
+    confirm_clear_usage: Confirm<bool>,
+    show_shortcuts: bool,
+    /// The folder being renamed and its new name
+    folder_rename: Option<(String, String)>,
+    setting_tab: SettingTab,
+    passphrase: String,
+    passphrase_confirm: String,
+    unlock_msg: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SettingTab {
+    Config,
+    Usage,
+    Security,
 }
 
 impl App {
@@ -78,8 +117,8 @@ impl App {
             thinking_content: None,
             role: Role::User,
             config,
-            status_msg: String::new(),
-            edit_summary: false,
+            toasts: Toasts::new(),
+            summary_edit: None,
             last_summary: (
                 0,
                 Message {
@@ -90,10 +129,20 @@ impl App {
             scroll_to_top: false,
             scroll_to_bottom: false,
             scroll_to_summary: false,
-            toggle: Default::default(),
+            toggle: DelayedToggle::new(false).with_delay(Duration::from_millis(100)),
             selector,
+            window: PersistentWindow::new(cc),
+            confirm_delete: Confirm::new(),
+            confirm_clear_usage: Confirm::new(),
+            show_shortcuts: false,
+            folder_rename: None,
+            setting_tab: SettingTab::Config,
+            passphrase: String::new(),
+            passphrase_confirm: String::new(),
+            unlock_msg: None,
         };
 
+        eapp_utils::diagnostics::remember_renderer(cc);
         this.rebuild_fonts(&cc.egui_ctx);
         this.selector.apply_text_style(&cc.egui_ctx);
         this
@@ -131,6 +180,13 @@ impl App {
                 self.rebuild_fonts(ui.ctx());
             }
 
+            let response = frameless_btn(ui, ICON_INFO.to_string()).on_hover_text("About");
+            egui::Popup::menu(&response)
+                .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                .show(|ui| {
+                    eapp_utils::diagnostics::about_popup(ui, "mychat", env!("CARGO_PKG_VERSION"))
+                });
+
             if frameless_btn(ui, ICON_SETTINGS_GEAR.to_string()).clicked() {
                 self.state.show_setting_window = !self.state.show_setting_window;
             }
@@ -152,7 +208,18 @@ impl App {
             .open(&mut show_setting_window)
             .max_height(600.0)
             .show(ui.ctx(), |ui| {
-                self.ui_setting(ui);
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.setting_tab, SettingTab::Config, "Config");
+                    ui.selectable_value(&mut self.setting_tab, SettingTab::Usage, "Usage");
+                    ui.selectable_value(&mut self.setting_tab, SettingTab::Security, "Security");
+                });
+                ui.separator();
+
+                match self.setting_tab {
+                    SettingTab::Config => self.ui_setting(ui),
+                    SettingTab::Usage => self.ui_usage(ui),
+                    SettingTab::Security => self.ui_security(ui),
+                }
             });
 
         self.state.show_setting_window = show_setting_window;
@@ -162,22 +229,6 @@ impl App {
         let max_width = ui.available_width() * 0.65;
         let max_height = ui.available_height() * 0.65;
 
-        egui::TopBottomPanel::bottom("msg_panel")
-            .exact_height(get_button_height(ui) + 16.0)
-            .frame(egui::Frame::side_top_panel(ui.style()).fill(Color32::TRANSPARENT))
-            .show_animated_inside(ui, !self.status_msg.is_empty(), |ui| {
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("Clear").clicked() {
-                        self.status_msg.clear();
-                    }
-
-                    ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                        ui.set_clip_rect(ui.max_rect());
-                        ui.label(&self.status_msg);
-                    });
-                });
-            });
-
         egui::SidePanel::left("left_panel")
             .frame(egui::Frame::side_top_panel(ui.style()).fill(Color32::TRANSPARENT))
             .default_width(200.0)
@@ -212,6 +263,7 @@ impl eframe::App for App {
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, UiFontSelector::KEY, &self.selector);
+        self.window.save(storage);
         eframe::set_value(storage, eframe::APP_KEY, &self.state);
         self.manager.save();
     }
@@ -236,14 +288,27 @@ impl eframe::App for App {
             }
             .shrink2(Vec2::new(0.5, 0.5));
 
-            self.manager.update(&mut self.status_msg);
+            if self.manager.is_locked() {
+                self.ui_title_bar(ui, title_bar_rect);
+                self.ui_unlock(ui);
+                return;
+            }
+
+            self.manager.update(&mut self.toasts);
+
+            self.process_shortcuts(ctx);
 
             self.show_setting_window(ui);
+            self.show_shortcuts_window(ui);
+            self.ui_confirm_delete(ui);
+            self.ui_confirm_clear_usage(ui);
 
             self.ui_title_bar(ui, title_bar_rect);
             self.ui_contents(
                 &mut ui.new_child(UiBuilder::new().layout(*ui.layout()).max_rect(content_rect)),
             );
         });
+
+        self.toasts.show(ctx);
     }
 }
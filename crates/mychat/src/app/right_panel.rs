@@ -1,18 +1,25 @@
 use eapp_utils::{
-    codicons::{ICON_CLEAR_ALL, ICON_COPY, ICON_EDIT, ICON_REDO},
+    codicons::{
+        ICON_CHEVRON_LEFT, ICON_CHEVRON_RIGHT, ICON_CLEAR_ALL, ICON_COPY, ICON_EDIT, ICON_FOLD,
+        ICON_REDO, ICON_REFRESH, ICON_REPO_FORKED, ICON_UNFOLD, ICON_WAND,
+    },
     get_body_font_id, get_body_text_size,
     widgets::simple_widgets::frameless_btn,
 };
 use eframe::egui::{self, CollapsingHeader, Color32, Response, vec2};
-use egui_commonmark::CommonMarkViewer;
+use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
 
 use crate::chat::{
     Message, Role,
     dialogue::{DialogueState, MessageWithUiData},
+    math,
 };
 
 impl super::App {
     pub fn ui_right_panel(&mut self, ui: &mut egui::Ui) {
+        self.ui_branch_origin(ui);
+        self.ui_summary_panel(ui);
+
         let show_summarized =
             self.state.show_summarized || self.manager.cur_dialogue().is_summary_empty();
         let scroll_offset = if !self.manager.is_empty() {
@@ -29,7 +36,7 @@ impl super::App {
             self.toggle.active(cur_time);
         }
 
-        self.toggle.update(cur_time, 0.1);
+        self.toggle.update(cur_time);
 
         let output = egui::ScrollArea::vertical()
             .max_height(f32::INFINITY)
@@ -58,19 +65,191 @@ impl super::App {
         }
     }
 
+    /// Where the current dialogue was branched from, the original one is selected by clicking it
+    fn ui_branch_origin(&mut self, ui: &mut egui::Ui) {
+        if self.manager.is_empty() {
+            return;
+        }
+
+        let Some(origin_idx) = self
+            .manager
+            .cur_dialogue()
+            .branch_of
+            .and_then(|id| self.manager.data.index_of(id))
+        else {
+            return;
+        };
+
+        let title = self.manager.dialogue(origin_idx).title();
+        ui.horizontal(|ui| {
+            ui.weak(format!("{ICON_REPO_FORKED} Branched from"));
+            if ui
+                .link(title)
+                .on_hover_text("Go to the original chat")
+                .clicked()
+            {
+                self.manager.cur_dialogue_idx = origin_idx;
+            }
+        });
+        ui.separator();
+    }
+
+    /// The summary sent in place of the summarized messages, which can be corrected here
+    fn ui_summary_panel(&mut self, ui: &mut egui::Ui) {
+        if self.manager.is_empty() {
+            return;
+        }
+
+        let dialogue = self.manager.cur_dialogue();
+        if dialogue.is_summary_empty() && dialogue.state != DialogueState::Summarizing {
+            if self
+                .summary_edit
+                .as_ref()
+                .is_some_and(|(id, _)| *id == dialogue.id)
+            {
+                self.summary_edit = None;
+            }
+            return;
+        }
+
+        let id = dialogue.id;
+        let editing = self
+            .summary_edit
+            .as_ref()
+            .is_some_and(|(edit_id, _)| *edit_id == id);
+        let amount = dialogue.amount_of_message_summarized;
+        let title = match amount {
+            0 | 1 => format!("Summary of message {amount}"),
+            _ => format!("Summary of messages 1 - {amount}"),
+        };
+
+        let mut save = false;
+        let mut cancel = false;
+        let mut start_edit = false;
+        CollapsingHeader::new(egui::RichText::new(title).strong())
+            .id_salt(("summary_panel", id))
+            .open(editing.then_some(true))
+            .show(ui, |ui| {
+                let dialogue = self.manager.cur_dialogue_mut();
+                if dialogue.state == DialogueState::Summarizing {
+                    ui.horizontal(|ui| {
+                        ui.weak("Summarizing...");
+                        ui.spinner();
+                    });
+                    return;
+                }
+
+                ui.weak(if dialogue.context_window.is_some() {
+                    "Not sent, this chat only sends its last messages"
+                } else {
+                    "Sent in place of the summarized messages"
+                });
+
+                if let Some((_, text)) = self.summary_edit.as_mut().filter(|_| editing) {
+                    egui::ScrollArea::vertical()
+                        .id_salt("summary_edit")
+                        .max_height(240.0)
+                        .show(ui, |ui| {
+                            egui::TextEdit::multiline(text)
+                                .desired_width(f32::INFINITY)
+                                .show(ui);
+                        });
+
+                    ui.horizontal(|ui| {
+                        save = ui
+                            .button("Save")
+                            .on_hover_text(
+                                "An empty summary clears it, the summarized messages are sent again",
+                            )
+                            .clicked();
+                        cancel = ui.button("Cancel").clicked();
+                    });
+                    return;
+                }
+
+                egui::ScrollArea::vertical()
+                    .id_salt("summary_view")
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        show_markdown(ui, &mut dialogue.summary.cache, &dialogue.summary.message.content);
+                    });
+
+                start_edit = ui
+                    .add_enabled_ui(dialogue.is_idle(), |ui| {
+                        frameless_btn(ui, format!("{ICON_EDIT} Edit"))
+                    })
+                    .inner
+                    .clicked();
+            });
+        ui.separator();
+
+        if start_edit {
+            self.start_summary_edit();
+        }
+        if cancel {
+            self.summary_edit = None;
+        }
+        if save {
+            self.apply_summary_edit();
+        }
+    }
+
+    fn start_summary_edit(&mut self) {
+        let dialogue = self.manager.cur_dialogue();
+        self.summary_edit = Some((dialogue.id, dialogue.summary.message.content.clone()));
+    }
+
+    /// Replaces the summary with the edited one, which is used by the next request
+    fn apply_summary_edit(&mut self) {
+        let Some((id, text)) = self.summary_edit.take() else {
+            return;
+        };
+        let Some(idx) = self.manager.data.index_of(id) else {
+            return;
+        };
+
+        let dialogue = self.manager.dialogue_mut(idx);
+        match text.trim() {
+            "" => dialogue.clear_summary(),
+            text => dialogue.summary.message.content = text.to_owned(),
+        }
+        self.manager.save();
+    }
+
     fn ui_show_dialogues(&mut self, ui: &mut egui::Ui, show_summarized: bool) {
         if self.manager.is_empty() {
             return;
         }
 
+        let can_branch = self.manager.is_idle();
+        let profiles: Vec<String> = self
+            .manager
+            .data
+            .manager
+            .read()
+            .unwrap()
+            .profiles
+            .iter()
+            .map(|p| p.name.clone())
+            .collect();
         let dialogue = self.manager.cur_dialogue_mut();
 
         let is_idle = dialogue.is_idle();
         let mut idx_to_edit = None;
+        let mut idx_to_branch = None;
+        let mut to_regenerate = None;
         let mut clear_summary = false;
+        let mut toggle_summary_edit = false;
+        let editing_summary = self
+            .summary_edit
+            .as_ref()
+            .is_some_and(|(id, _)| *id == dialogue.id);
 
         let start_index = dialogue.start_idx(show_summarized);
         let is_summarizing = dialogue.state == DialogueState::Summarizing;
+        let streaming_idx =
+            (dialogue.state == DialogueState::Sending).then(|| dialogue.reply_idx());
+        let typing_time = self.state.typing_animation.then(|| ui.input(|i| i.time));
 
         macro_rules! show_summary {
             () => {
@@ -79,8 +258,8 @@ impl super::App {
                     &mut dialogue.summary,
                     &mut clear_summary,
                     &mut dialogue.amount_of_message_summarized,
-                    &mut self.edit_summary,
-                    &mut self.input,
+                    editing_summary,
+                    &mut toggle_summary_edit,
                     &mut self.last_summary,
                 )
             };
@@ -109,8 +288,20 @@ impl super::App {
                 }
             }
 
+            let can_regenerate = dialogue.can_regenerate(idx);
             let msg = &mut dialogue.messages[idx];
-            ui_show_message(ui, msg, is_idle, idx, &mut idx_to_edit);
+            ui_show_message(
+                ui,
+                msg,
+                typing_time.filter(|_| streaming_idx == Some(idx)),
+                is_idle,
+                can_branch,
+                idx,
+                &mut idx_to_edit,
+                &mut idx_to_branch,
+                can_regenerate.then_some(profiles.as_slice()),
+                &mut to_regenerate,
+            );
         }
 
         if is_summarizing {
@@ -133,19 +324,52 @@ impl super::App {
             self.role = message.role;
             dialogue.back_to(idx as isize - 1);
         }
+
+        if let Some(idx) = idx_to_branch {
+            self.manager.branch_from(idx);
+        }
+
+        if let Some((idx, profile_idx)) = to_regenerate {
+            self.manager.regenerate(idx, profile_idx);
+        }
+
+        if toggle_summary_edit {
+            if editing_summary {
+                self.summary_edit = None;
+            } else {
+                self.start_summary_edit();
+            }
+        }
     }
 }
 
 fn ui_show_message(
     ui: &mut egui::Ui,
     message_with_ui_data: &mut MessageWithUiData,
+    typing_time: Option<f64>,
     is_idle: bool,
+    can_branch: bool,
     idx: usize,
     idx_to_edit: &mut Option<usize>,
+    idx_to_branch: &mut Option<usize>,
+    regenerate_profiles: Option<&[String]>,
+    to_regenerate: &mut Option<(usize, usize)>,
 ) -> Response {
     let max_width = ui.available_width() * 0.85;
 
-    let MessageWithUiData { cache, message } = message_with_ui_data;
+    let variant_count = message_with_ui_data.variant_count();
+    let variant_profile = message_with_ui_data.variant_profile().map(str::to_owned);
+    let mut variant_to_show = None;
+
+    let MessageWithUiData {
+        cache,
+        message,
+        show_full,
+        typewriter,
+        variant_idx,
+        ..
+    } = message_with_ui_data;
+    let variant_idx = *variant_idx;
     let is_user = message.role == Role::User;
     let is_system = message.role == Role::System;
 
@@ -174,13 +398,35 @@ fn ui_show_message(
         ui.heading("System");
     }
 
+    let content = match &message.full_content {
+        Some(full_content) if *show_full => full_content.clone(),
+        _ => message.content.clone(),
+    };
+
+    // typed out from the start of the stream only, a flushed reply stays shown
+    if typing_time.is_none() {
+        *typewriter = None;
+    } else if message.content.is_empty() {
+        typewriter.get_or_insert_default();
+    }
+    let content = match (typing_time, typewriter.as_mut()) {
+        (Some(time), Some(typewriter)) => {
+            let revealed = typewriter.advance(&content, time);
+            if revealed.len() < content.len() {
+                ui.ctx().request_repaint();
+            }
+            revealed.to_owned()
+        }
+        _ => content,
+    };
+
     let inner = ui.with_layout(layout, |ui| {
-        let width = if message.content.len() >= 200 {
+        let width = if content.len() >= 200 {
             max_width
         } else {
             ui.painter()
                 .layout(
-                    message.content.clone(),
+                    content.clone(),
                     get_body_font_id(ui),
                     Color32::TRANSPARENT,
                     max_width,
@@ -196,7 +442,7 @@ fn ui_show_message(
             .corner_radius(8)
             .inner_margin(egui::Margin::symmetric(12, 8))
             .show(ui, |ui| {
-                CommonMarkViewer::new().show(ui, cache, &message.content);
+                show_markdown(ui, cache, &content);
             })
     });
 
@@ -206,18 +452,98 @@ fn ui_show_message(
             if ui.button(ICON_COPY.to_string()).clicked() {
                 ui.output_mut(|o| {
                     o.commands
-                        .push(egui::OutputCommand::CopyText(message.content.clone()))
+                        .push(egui::OutputCommand::CopyText(content.clone()))
                 });
             }
 
+            if message.full_content.is_some() {
+                let (icon, hover_text) = if *show_full {
+                    (ICON_FOLD, "Show the trimmed reply")
+                } else {
+                    (ICON_UNFOLD, "Show the full reply, it was cut by Max Tokens")
+                };
+                if ui
+                    .button(icon.to_string())
+                    .on_hover_text(hover_text)
+                    .clicked()
+                {
+                    *show_full = !*show_full;
+                }
+            }
+
             ui.add_enabled_ui(is_idle, |ui| {
                 if ui.button(ICON_EDIT.to_string()).clicked() {
                     *idx_to_edit = Some(idx);
                 }
+
+                if ui
+                    .button(ICON_WAND.to_string())
+                    .on_hover_text("Re-split thinking content")
+                    .clicked()
+                {
+                    message.resplit_thinking_content();
+                }
             });
+
+            ui.add_enabled_ui(can_branch, |ui| {
+                if ui
+                    .button(ICON_REPO_FORKED.to_string())
+                    .on_hover_text("Branch from here")
+                    .clicked()
+                {
+                    *idx_to_branch = Some(idx);
+                }
+            });
+
+            if let Some(profiles) = regenerate_profiles {
+                ui.menu_button(ICON_REFRESH.to_string(), |ui| {
+                    ui.label("Regenerate with");
+                    for (profile_idx, name) in profiles.iter().enumerate() {
+                        if frameless_btn(ui, name).clicked() {
+                            *to_regenerate = Some((idx, profile_idx));
+                            ui.close();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Regenerate with another profile, keeping this reply");
+            }
+
+            if variant_count > 1 {
+                ui.add_enabled_ui(is_idle, |ui| {
+                    if ui
+                        .add_enabled(
+                            variant_idx > 0,
+                            egui::Button::new(ICON_CHEVRON_LEFT.to_string()),
+                        )
+                        .clicked()
+                    {
+                        variant_to_show = Some(variant_idx - 1);
+                    }
+
+                    let response = ui.label(format!("{}/{variant_count}", variant_idx + 1));
+                    if let Some(profile) = &variant_profile {
+                        response.on_hover_text(format!("Generated with '{profile}'"));
+                    }
+
+                    if ui
+                        .add_enabled(
+                            variant_idx + 1 < variant_count,
+                            egui::Button::new(ICON_CHEVRON_RIGHT.to_string()),
+                        )
+                        .clicked()
+                    {
+                        variant_to_show = Some(variant_idx + 1);
+                    }
+                });
+            }
         });
     });
 
+    if let Some(variant) = variant_to_show {
+        message_with_ui_data.select_variant(variant);
+    }
+
     ui.add_space(get_body_text_size(ui));
 
     inner.response
@@ -228,8 +554,8 @@ fn ui_show_summary(
     summary: &mut MessageWithUiData,
     clear_summary: &mut bool,
     amount_of_message_summarized: &mut usize,
-    edit_summary: &mut bool,
-    input: &mut String,
+    editing: bool,
+    toggle_edit: &mut bool,
     last_summary: &mut (usize, Message),
 ) -> Response {
     let response = egui::Frame::NONE
@@ -252,7 +578,7 @@ fn ui_show_summary(
                     });
             }
 
-            CommonMarkViewer::new().show(ui, &mut summary.cache, &summary.message.content);
+            show_markdown(ui, &mut summary.cache, &summary.message.content);
 
             ui.horizontal(|ui| {
                 if frameless_btn(ui, ICON_CLEAR_ALL.to_string()).clicked() {
@@ -268,16 +594,11 @@ fn ui_show_summary(
                 }
 
                 if ui
-                    .selectable_label(*edit_summary, ICON_EDIT.to_string())
+                    .selectable_label(editing, ICON_EDIT.to_string())
+                    .on_hover_text("Edit in the summary panel above")
                     .clicked()
                 {
-                    *edit_summary = !*edit_summary;
-
-                    if *edit_summary {
-                        *input = summary.message.content.clone();
-                    } else {
-                        input.clear();
-                    }
+                    *toggle_edit = true;
                 }
 
                 if ui
@@ -298,3 +619,9 @@ fn ui_show_summary(
 
     response
 }
+
+/// Markdown with LaTeX math, which is typeset in place, or shown as LaTeX in code if it
+/// can't be
+fn show_markdown(ui: &mut egui::Ui, cache: &mut CommonMarkCache, text: &str) {
+    CommonMarkViewer::new().show(ui, cache, &math::to_markdown(text));
+}
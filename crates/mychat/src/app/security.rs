@@ -0,0 +1,156 @@
+use eframe::egui::{self, TextEdit};
+use std::time::Duration;
+
+impl super::App {
+    /// Asks for the passphrase of the encrypted store, before anything else can be done
+    pub fn ui_unlock(&mut self, ui: &mut egui::Ui) {
+        egui::Modal::new(egui::Id::new("unlock_dialogues")).show(ui.ctx(), |ui| {
+            ui.set_width(300.0);
+            ui.vertical_centered(|ui| ui.heading("Dialogues are encrypted"));
+            ui.add_space(4.0);
+
+            self.poll_unlock();
+            let wait = self.manager.unlock_wait();
+            let unlocking = self.manager.is_unlocking();
+            let can_unlock = !unlocking && wait.is_none();
+
+            ui.add_enabled_ui(can_unlock, |ui| {
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        TextEdit::singleline(&mut self.passphrase)
+                            .password(true)
+                            .hint_text("Passphrase")
+                            .desired_width(200.0),
+                    );
+                    if can_unlock && ui.memory(|mem| mem.focused().is_none()) {
+                        response.request_focus();
+                    }
+
+                    let entered =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button("Unlock").clicked() || entered {
+                        let passphrase = std::mem::take(&mut self.passphrase);
+                        self.manager.unlock(passphrase);
+                    }
+                });
+            });
+
+            if unlocking {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Unlocking...");
+                });
+            } else if let Some(wait) = wait {
+                ui.weak(format!("Try again in {}s", wait.as_secs() + 1));
+                ui.ctx().request_repaint_after(Duration::from_secs(1));
+            }
+
+            if let Some(msg) = &self.unlock_msg {
+                ui.colored_label(ui.visuals().error_fg_color, msg);
+            }
+
+            ui.separator();
+
+            if ui
+                .button("Start Fresh")
+                .on_hover_text("Move the encrypted dialogues aside and start with none")
+                .clicked()
+            {
+                match self.manager.start_fresh() {
+                    Ok(path) => {
+                        self.passphrase.clear();
+                        self.unlock_msg = None;
+                        self.toasts.info(format!(
+                            "Encrypted dialogues are moved to '{}'",
+                            path.display()
+                        ));
+                    }
+                    Err(err) => self.unlock_msg = Some(format!("Cannot move the store: {err}")),
+                }
+            }
+        });
+    }
+
+    fn poll_unlock(&mut self) {
+        match self.manager.poll_unlock() {
+            Some(Ok(())) => {
+                self.unlock_msg = None;
+                self.config = self
+                    .manager
+                    .data
+                    .manager
+                    .read()
+                    .unwrap()
+                    .cur_config()
+                    .clone();
+            }
+            Some(Err(err)) => self.unlock_msg = Some(err.to_string()),
+            None => (),
+        }
+    }
+
+    pub fn ui_security(&mut self, ui: &mut egui::Ui) {
+        let encrypted = self.manager.data.is_encrypted();
+        if encrypted {
+            ui.label("Dialogues and API keys are encrypted on disk.");
+        } else {
+            ui.label("Dialogues and API keys are stored as plain text.");
+        }
+        ui.weak("A forgotten passphrase can't be recovered.");
+        ui.add_space(4.0);
+
+        egui::Grid::new("passphrase_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("New Passphrase:");
+                ui.add(TextEdit::singleline(&mut self.passphrase).password(true));
+                ui.end_row();
+
+                ui.label("Confirm:");
+                ui.add(TextEdit::singleline(&mut self.passphrase_confirm).password(true));
+                ui.end_row();
+            });
+
+        let matched = self.passphrase == self.passphrase_confirm;
+        if !matched {
+            ui.colored_label(ui.visuals().warn_fg_color, "Passphrases don't match");
+        }
+
+        ui.horizontal(|ui| {
+            let text = if encrypted {
+                "Change Passphrase"
+            } else {
+                "Encrypt"
+            };
+            let can_set = matched && !self.passphrase.is_empty() && self.manager.is_idle();
+            if ui.add_enabled(can_set, egui::Button::new(text)).clicked() {
+                let passphrase = std::mem::take(&mut self.passphrase);
+                self.passphrase_confirm.clear();
+                self.set_passphrase(Some(&passphrase));
+            }
+
+            if encrypted
+                && ui
+                    .add_enabled(
+                        self.manager.is_idle(),
+                        egui::Button::new("Remove Encryption"),
+                    )
+                    .clicked()
+            {
+                self.set_passphrase(None);
+            }
+        });
+    }
+
+    fn set_passphrase(&mut self, passphrase: Option<&str>) {
+        match self.manager.data.set_passphrase(passphrase) {
+            Ok(()) if passphrase.is_some() => self.toasts.info("Dialogues are encrypted"),
+            Ok(()) => self.toasts.info("Dialogues are stored as plain text"),
+            Err(err) => {
+                log::error!("Error when set the passphrase: {err}");
+                self.toasts
+                    .error(format!("Cannot set the passphrase: {err}"));
+            }
+        }
+    }
+}
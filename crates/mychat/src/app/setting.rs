@@ -1,10 +1,17 @@
 use eapp_utils::{get_body_text_size, get_button_height};
 use eframe::egui::{self, Color32, TextEdit};
 
-use crate::chat::config::ChatParam;
+use crate::chat::config::{ChatConfig, ChatParam};
 
 impl super::App {
     pub fn ui_setting(&mut self, ui: &mut egui::Ui) {
+        if ui.button("Keyboard Shortcuts").clicked() {
+            self.show_shortcuts = true;
+        }
+
+        ui.checkbox(&mut self.state.typing_animation, "Typing Animation")
+            .on_hover_text("Streaming replies are typed out at a steady rate");
+
         ui.horizontal(|ui| {
             let mut manager = self.manager.data.manager.write().unwrap();
             let current_index = manager.current_profile_index;
@@ -63,18 +70,64 @@ impl super::App {
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
                     ui.label("Context Window:");
-                    ui.add(egui::DragValue::new(&mut self.config.n_ctx).speed(1));
+                    ui.add(
+                        egui::Slider::new(&mut self.config.n_ctx, 512..=1 << 20)
+                            .logarithmic(true)
+                            .suffix(" tokens"),
+                    );
                 });
 
                 ui.horizontal(|ui| {
                     ui.label("Compression Threshold:");
                     ui.add(
-                        egui::DragValue::new(&mut self.config.compression_threshold)
-                            .speed(0.01)
-                            .range(0.1..=1.0),
-                    );
+                        egui::Slider::new(
+                            &mut self.config.compression_threshold,
+                            ChatConfig::THRESHOLD_RANGE,
+                        )
+                        .fixed_decimals(2),
+                    )
+                    .on_hover_text("Share of the context window over which the chat is summarized");
                 });
 
+                ui.horizontal(|ui| {
+                    ui.label("Summary Ratio:");
+                    ui.add(
+                        egui::Slider::new(
+                            &mut self.config.summary_ratio,
+                            ChatConfig::SUMMARY_RATIO_RANGE,
+                        )
+                        .fixed_decimals(2),
+                    )
+                    .on_hover_text("Share of the threshold left unsummarized after summarizing");
+                });
+
+                self.ui_summarize_preview(ui);
+
+                ui.vertical(|ui| {
+                    ui.label("Stop Sequences:")
+                        .on_hover_text("One per line, for the replies only");
+                    let mut stop = self.config.stop.join("\n");
+                    if ui
+                        .add(
+                            TextEdit::multiline(&mut stop)
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(2),
+                        )
+                        .changed()
+                    {
+                        self.config.stop = stop.split('\n').map(str::to_owned).collect();
+                    }
+                });
+
+                ui.checkbox(
+                    &mut self.config.trim_incomplete,
+                    "Trim Incomplete Sentence",
+                )
+                .on_hover_text(
+                    "Replies cut by Max Tokens are trimmed back to their last complete sentence, \
+                     the full reply can still be shown",
+                );
+
                 egui::CollapsingHeader::new("Assistant Parameters")
                     .default_open(true)
                     .show(ui, |ui| {
@@ -115,6 +168,40 @@ impl super::App {
         });
     }
 
+    /// When the current chat would be summarized with the edited config
+    fn ui_summarize_preview(&self, ui: &mut egui::Ui) {
+        let (at, target) = (self.config.summarize_at(), self.config.summary_target());
+        ui.weak(format!(
+            "Summarizes over {at} chars, keeping the last {target} of them"
+        ))
+        .on_hover_text("Chars are counted as tokens, which is only an approximation");
+
+        if self.manager.is_empty() {
+            return;
+        }
+
+        let dialogue = self.manager.cur_dialogue();
+        if dialogue.context_window.is_some() {
+            ui.weak("This chat sends only its last messages and is not summarized");
+            return;
+        }
+
+        let chars = dialogue.token_count();
+        // the same messages as counted, which end at the one replied to
+        let end = (dialogue.reply_idx() + 1).min(dialogue.messages.len());
+        let messages = end.saturating_sub(dialogue.amount_of_message_summarized);
+        let text = if chars > at {
+            format!("This chat has {chars} chars and is summarized by the next request")
+        } else {
+            let per_message = (chars / messages.max(1)).max(1);
+            format!(
+                "This chat has {chars} chars, summarized in ~{} messages",
+                (at - chars).div_ceil(per_message)
+            )
+        };
+        ui.weak(text);
+    }
+
     fn ui_param(ui: &mut egui::Ui, param: &mut ChatParam) {
         ui.horizontal(|ui| {
             ui.label("API URL:");
@@ -0,0 +1,214 @@
+use eapp_utils::widgets::confirm::ConfirmRequest;
+use eframe::egui::{self, Key, KeyboardShortcut, Modifiers};
+
+use crate::chat::Message;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    Send,
+    NewDialogue,
+    DeleteDialogue,
+    NextDialogue,
+    PrevDialogue,
+    Cancel,
+    FocusInput,
+}
+
+pub struct Binding {
+    pub command: Command,
+    pub shortcut: KeyboardShortcut,
+    pub desc: &'static str,
+}
+
+/// All shortcuts, [`Command::Send`] is swapped with plain `Enter` if `enter_sends` is set
+pub const BINDINGS: &[Binding] = &[
+    Binding {
+        command: Command::Send,
+        shortcut: KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter),
+        desc: "Send the input",
+    },
+    Binding {
+        command: Command::NewDialogue,
+        shortcut: KeyboardShortcut::new(Modifiers::COMMAND, Key::N),
+        desc: "New chat",
+    },
+    Binding {
+        command: Command::DeleteDialogue,
+        shortcut: KeyboardShortcut::new(Modifiers::COMMAND, Key::W),
+        desc: "Delete the current chat",
+    },
+    Binding {
+        command: Command::NextDialogue,
+        shortcut: KeyboardShortcut::new(Modifiers::COMMAND, Key::Tab),
+        desc: "Next chat",
+    },
+    Binding {
+        command: Command::PrevDialogue,
+        shortcut: KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Tab),
+        desc: "Previous chat",
+    },
+    Binding {
+        command: Command::Cancel,
+        shortcut: KeyboardShortcut::new(Modifiers::NONE, Key::Escape),
+        desc: "Cancel the request",
+    },
+    Binding {
+        command: Command::FocusInput,
+        shortcut: KeyboardShortcut::new(Modifiers::COMMAND, Key::L),
+        desc: "Focus the input",
+    },
+];
+
+impl Binding {
+    pub fn shortcut(&self, enter_sends: bool) -> KeyboardShortcut {
+        match self.command {
+            Command::Send if enter_sends => KeyboardShortcut::new(Modifiers::NONE, Key::Enter),
+            _ => self.shortcut,
+        }
+    }
+}
+
+impl super::App {
+    pub const INPUT_ID: &str = "chat_input";
+
+    /// Runs the pressed shortcuts, must be called before the input is shown so that
+    /// it does not take the keys
+    pub fn process_shortcuts(&mut self, ctx: &egui::Context) {
+        let input_id = egui::Id::new(Self::INPUT_ID);
+        let other_focused = ctx
+            .memory(|mem| mem.focused())
+            .is_some_and(|id| id != input_id);
+
+        if self.confirm_delete.is_open() || self.confirm_clear_usage.is_open() || other_focused {
+            return;
+        }
+
+        for binding in BINDINGS {
+            if !self.can_run(binding.command) {
+                continue;
+            }
+
+            let shortcut = binding.shortcut(self.state.enter_sends);
+            if ctx.input_mut(|i| {
+                i.modifiers.matches_exact(shortcut.modifiers) && i.consume_shortcut(&shortcut)
+            }) {
+                self.run_command(ctx, binding.command);
+            }
+        }
+    }
+
+    fn can_run(&self, command: Command) -> bool {
+        match command {
+            Command::Send => self.manager.is_empty() || self.manager.is_cur_dialogue_idle(),
+            Command::DeleteDialogue => !self.manager.is_empty() && self.manager.is_idle(),
+            Command::NextDialogue | Command::PrevDialogue => self.manager.len() > 1,
+            Command::Cancel => !self.manager.is_empty() && !self.manager.is_cur_dialogue_idle(),
+            Command::NewDialogue | Command::FocusInput => true,
+        }
+    }
+
+    fn run_command(&mut self, ctx: &egui::Context, command: Command) {
+        match command {
+            Command::Send => self.submit_input(),
+            Command::NewDialogue => self.manager.new_dialogue(),
+            Command::DeleteDialogue => self.ask_delete_dialogue(self.manager.cur_dialogue_idx),
+            Command::NextDialogue => self.step_dialogue(true),
+            Command::PrevDialogue => self.step_dialogue(false),
+            Command::Cancel => self.manager.cancel(),
+            Command::FocusInput => {
+                self.state.show_bottom_panel = true;
+                ctx.memory_mut(|mem| mem.request_focus(egui::Id::new(Self::INPUT_ID)));
+            }
+        }
+    }
+
+    /// Pushes the input to the current dialogue and triggers a request
+    pub fn submit_input(&mut self) {
+        if self.manager.is_empty() {
+            self.manager.new_dialogue();
+        }
+
+        let input = self.input.trim();
+        if !input.is_empty() {
+            let thinking_content = self.thinking_content.take();
+            self.manager.push_message(Message {
+                role: self.role,
+                content: input.to_owned(),
+                thinking_content,
+                ..Default::default()
+            });
+
+            self.input.clear();
+        }
+
+        if self.state.trigger_request {
+            self.last_summary.0 = self.manager.cur_dialogue().amount_of_message_summarized;
+            self.last_summary.1 = self.manager.cur_dialogue().summary.message.clone();
+            self.manager.trigger_request();
+        }
+    }
+
+    /// Selects the next or previous dialogue in the order they're listed
+    fn step_dialogue(&mut self, forward: bool) {
+        let listed = self.manager.data.listed(self.state.show_archived);
+        if listed.is_empty() {
+            return;
+        }
+
+        let len = listed.len();
+        let pos = listed
+            .iter()
+            .position(|&idx| idx == self.manager.cur_dialogue_idx);
+        let pos = match (pos, forward) {
+            (Some(pos), true) => (pos + 1) % len,
+            (Some(pos), false) => (pos + len - 1) % len,
+            (None, _) => 0,
+        };
+        self.manager.cur_dialogue_idx = listed[pos];
+    }
+
+    pub fn ask_delete_dialogue(&mut self, idx: usize) {
+        let dialogue = self.manager.dialogue(idx);
+        let (id, title) = (dialogue.id, dialogue.title());
+        self.confirm_delete.open(
+            ConfirmRequest::new(format!("Delete chat '{title}'?"), move |yes| {
+                yes.then_some(id)
+            })
+            .dangerous()
+            .cancellable(),
+        );
+    }
+
+    pub fn ui_confirm_delete(&mut self, ui: &mut egui::Ui) {
+        // by id, the chat may have moved while asking
+        if let Some(Some(id)) = self.confirm_delete.show(ui.ctx())
+            && self.manager.is_idle()
+            && let Some(idx) = self.manager.data.index_of(id)
+        {
+            self.manager.remove_dialogue(idx);
+        }
+    }
+
+    pub fn show_shortcuts_window(&mut self, ui: &mut egui::Ui) {
+        let ctx = ui.ctx().clone();
+        egui::Window::new("Shortcuts")
+            .open(&mut self.show_shortcuts)
+            .resizable(false)
+            .show(&ctx, |ui| {
+                egui::Grid::new("shortcuts_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for binding in BINDINGS {
+                            let shortcut = binding.shortcut(self.state.enter_sends);
+                            ui.label(ctx.format_shortcut(&shortcut));
+                            ui.label(binding.desc);
+                            ui.end_row();
+                        }
+                    });
+
+                ui.checkbox(&mut self.state.enter_sends, "Enter sends")
+                    .on_hover_text("Send with Enter instead, Shift+Enter inserts a newline");
+            });
+    }
+}
@@ -0,0 +1,120 @@
+use eapp_utils::widgets::confirm::ConfirmRequest;
+use eframe::egui::{self, Sense, vec2};
+
+use crate::chat::usage::{format_day, today};
+
+impl super::App {
+    const SPARKLINE_DAYS: usize = 14;
+
+    pub fn ui_usage(&mut self, ui: &mut egui::Ui) {
+        let usage = &self.manager.usage;
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} requests recorded", usage.entries().len()));
+            ui.weak("(tokens are estimated, days are in UTC)");
+        });
+
+        let counts = usage.daily_requests(today(), Self::SPARKLINE_DAYS);
+        ui_sparkline(ui, &counts).on_hover_text(format!(
+            "Requests of the last {} days",
+            Self::SPARKLINE_DAYS
+        ));
+
+        ui.separator();
+
+        let summaries = usage.summarize();
+        if summaries.is_empty() {
+            ui.weak("No requests yet");
+        } else {
+            egui::ScrollArea::vertical()
+                .max_height(360.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("usage_grid")
+                        .num_columns(7)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for header in [
+                                "Day",
+                                "Profile",
+                                "Requests",
+                                "Tokens In",
+                                "Tokens Out",
+                                "Avg Latency",
+                                "Errors",
+                            ] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+
+                            for summary in &summaries {
+                                ui.label(format_day(summary.day));
+                                ui.label(&summary.profile);
+                                ui.label(summary.requests.to_string());
+                                ui.label(summary.prompt_tokens.to_string());
+                                ui.label(summary.completion_tokens.to_string());
+                                ui.label(format!("{:.1}s", summary.avg_latency().as_secs_f32()));
+
+                                let error_rate = format!("{:.0}%", summary.error_rate() * 100.0);
+                                if summary.errors > 0 {
+                                    ui.colored_label(ui.visuals().warn_fg_color, error_rate);
+                                } else {
+                                    ui.label(error_rate);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+
+        ui.separator();
+
+        if ui
+            .add_enabled(
+                !usage.entries().is_empty(),
+                egui::Button::new("Clear Statistics"),
+            )
+            .clicked()
+        {
+            self.confirm_clear_usage.open(
+                ConfirmRequest::new("Clear all usage statistics?", |yes| yes)
+                    .dangerous()
+                    .cancellable(),
+            );
+        }
+    }
+
+    pub fn ui_confirm_clear_usage(&mut self, ui: &mut egui::Ui) {
+        if self.confirm_clear_usage.show(ui.ctx()) == Some(true) {
+            self.manager.usage.clear();
+        }
+    }
+}
+
+/// Bars of `counts`, scaled to the largest one
+fn ui_sparkline(ui: &mut egui::Ui, counts: &[usize]) -> egui::Response {
+    let (rect, response) =
+        ui.allocate_exact_size(vec2(ui.available_width().min(360.0), 40.0), Sense::hover());
+
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+    let max = counts.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let slot = rect.width() / counts.len().max(1) as f32;
+    let fill = ui.visuals().selection.bg_fill;
+
+    for (i, &count) in counts.iter().enumerate() {
+        let height = (count as f32 / max * (rect.height() - 4.0)).max(1.0);
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(rect.left() + i as f32 * slot + 1.0, rect.bottom() - height),
+            egui::pos2(rect.left() + (i + 1) as f32 * slot - 1.0, rect.bottom()),
+        );
+        let color = if count == 0 {
+            ui.visuals().weak_text_color()
+        } else {
+            fill
+        };
+        painter.rect_filled(bar, 1.0, color);
+    }
+
+    response
+}
@@ -1,10 +1,19 @@
 use serde::{Deserialize, Serialize};
+use std::ops::RangeInclusive;
 
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct ChatConfig {
+    /// Share of `n_ctx` over which the dialogue is summarized
     pub compression_threshold: f32,
     pub n_ctx: usize,
+    /// Share of the threshold left unsummarized after summarizing
+    pub summary_ratio: f32,
+
+    /// Sequences ending the replies, not the summaries, empty ones are ignored
+    pub stop: Vec<String>,
+    /// Replies cut by `max_tokens` are trimmed back to their last complete sentence
+    pub trim_incomplete: bool,
 
     pub summary_param: ChatParam,
     pub assistant_param: ChatParam,
@@ -16,6 +25,9 @@ impl Default for ChatConfig {
         Self {
             compression_threshold: 0.7,
             n_ctx: 4096,
+            summary_ratio: 0.5,
+            stop: Vec::new(),
+            trim_incomplete: false,
             summary_param: ChatParam::summary_param(),
             assistant_param: ChatParam::param(),
             user_param: ChatParam::param(),
@@ -23,6 +35,28 @@ impl Default for ChatConfig {
     }
 }
 
+impl ChatConfig {
+    pub const THRESHOLD_RANGE: RangeInclusive<f32> = 0.1..=0.95;
+    pub const SUMMARY_RATIO_RANGE: RangeInclusive<f32> = 0.1..=0.9;
+
+    /// Tokens over which the dialogue is summarized, compared with the chars of the dialogue
+    pub fn summarize_at(&self) -> usize {
+        let threshold = self
+            .compression_threshold
+            .clamp(*Self::THRESHOLD_RANGE.start(), *Self::THRESHOLD_RANGE.end());
+        (threshold * self.n_ctx as f32) as usize
+    }
+
+    /// Tokens left unsummarized after summarizing
+    pub fn summary_target(&self) -> usize {
+        let ratio = self.summary_ratio.clamp(
+            *Self::SUMMARY_RATIO_RANGE.start(),
+            *Self::SUMMARY_RATIO_RANGE.end(),
+        );
+        (self.summarize_at() as f32 * ratio) as usize
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub struct ChatParam {
     pub api_key: String,
@@ -143,3 +177,26 @@ impl ChatConfigManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_are_kept_in_range() {
+        let mut config = ChatConfig {
+            n_ctx: 1000,
+            compression_threshold: 0.5,
+            summary_ratio: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(config.summarize_at(), 500);
+        assert_eq!(config.summary_target(), 250);
+
+        // from a config file edited by hand
+        config.compression_threshold = 1.5;
+        config.summary_ratio = 0.0;
+        assert_eq!(config.summarize_at(), 950);
+        assert_eq!(config.summary_target(), 95);
+    }
+}
@@ -1,8 +1,16 @@
-use crate::chat::{Message, Role, config::ChatConfigManager};
+use crate::chat::{
+    Message, Role,
+    config::ChatConfigManager,
+    typing::Typewriter,
+    vault::{self, Vault},
+};
+use eapp_utils::date::UtcDateTime;
 use egui_commonmark::CommonMarkCache;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::VecDeque,
+    ops::Range,
+    path::PathBuf,
     sync::{Arc, RwLock},
 };
 
@@ -20,6 +28,18 @@ pub struct MessageWithUiData {
     pub message: Message,
     #[serde(skip)]
     pub cache: CommonMarkCache,
+    /// Shows the untrimmed content, see [`Message::full_content`]
+    #[serde(skip)]
+    pub show_full: bool,
+    /// Reveals the content while it's streaming, `None` once it's all shown
+    #[serde(skip)]
+    pub typewriter: Option<Typewriter>,
+    /// Replies to the same messages, including the shown one whose content is in `message`
+    /// instead, empty if it's never regenerated
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub variants: Vec<Variant>,
+    /// Index of the shown one in `variants`
+    pub variant_idx: usize,
 }
 
 impl From<Message> for MessageWithUiData {
@@ -27,6 +47,87 @@ impl From<Message> for MessageWithUiData {
         Self {
             message,
             cache: CommonMarkCache::default(),
+            show_full: false,
+            typewriter: None,
+            variants: Vec::new(),
+            variant_idx: 0,
+        }
+    }
+}
+
+/// Another reply to the same messages, e.g. by another model
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct Variant {
+    pub message: Message,
+    /// Name of the profile it's generated with, `None` for the reply before regenerating
+    pub profile: Option<String>,
+}
+
+impl MessageWithUiData {
+    pub fn variant_count(&self) -> usize {
+        self.variants.len().max(1)
+    }
+
+    /// Profile the shown variant is generated with
+    pub fn variant_profile(&self) -> Option<&str> {
+        self.variants
+            .get(self.variant_idx)
+            .and_then(|v| v.profile.as_deref())
+    }
+
+    /// Shows the variant at `idx`, it's what is sent afterwards
+    pub fn select_variant(&mut self, idx: usize) {
+        if idx == self.variant_idx || idx >= self.variants.len() {
+            return;
+        }
+
+        std::mem::swap(
+            &mut self.message,
+            &mut self.variants[self.variant_idx].message,
+        );
+        std::mem::swap(&mut self.message, &mut self.variants[idx].message);
+        self.variant_idx = idx;
+        self.show_full = false;
+        self.typewriter = None;
+    }
+
+    /// Adds an empty variant generated with `profile` and shows it, to receive a new reply
+    pub fn push_variant(&mut self, profile: String) {
+        if self.variants.is_empty() {
+            self.variants.push(Variant::default());
+        }
+
+        self.variants.push(Variant {
+            message: Message {
+                role: self.message.role,
+                ..Default::default()
+            },
+            profile: Some(profile),
+        });
+        self.select_variant(self.variants.len() - 1);
+    }
+
+    /// Removes the shown variant if nothing is received for it and shows the one before,
+    /// e.g. once regenerating fails
+    pub fn discard_empty_variant(&mut self) {
+        if self.variants.len() < 2
+            || !self.message.content.is_empty()
+            || self.message.thinking_content.is_some()
+        {
+            return;
+        }
+
+        let idx = self.variant_idx;
+        self.select_variant(if idx > 0 { idx - 1 } else { 1 });
+        self.variants.remove(idx);
+        if self.variant_idx > idx {
+            self.variant_idx -= 1;
+        }
+
+        // the reply before regenerating alone is not a variant
+        if self.variants.len() == 1 {
+            self.variants.clear();
         }
     }
 }
@@ -41,11 +142,27 @@ struct ScrollState {
 #[derive(Serialize, Deserialize)]
 #[serde(default)]
 pub struct Dialogue {
+    pub id: u64,
+    pub title: String,
+    /// Id of the dialogue this one was branched from
+    pub branch_of: Option<u64>,
+    /// Listed before the others
+    pub pinned: bool,
+    /// Only listed if archived dialogues are shown
+    pub archived: bool,
+    /// Name of the folder it's listed in, one of [`DialoguesData::folders`]
+    pub folder: Option<String>,
     pub messages: VecDeque<MessageWithUiData>,
     pub summary: MessageWithUiData,
     pub amount_of_message_summarized: usize,
+    /// Only the last this many messages are sent, instead of summarizing the older ones
+    pub context_window: Option<usize>,
     #[serde(skip)]
     pub generate_user_input: bool,
+    /// The message receiving the reply instead of the last one while it's regenerated,
+    /// and the index of the profile it's regenerated with
+    #[serde(skip)]
+    pub regenerating: Option<(usize, usize)>,
     #[serde(skip)]
     pub state: DialogueState,
     #[serde(skip)]
@@ -57,10 +174,18 @@ impl Default for Dialogue {
         let mut summary = MessageWithUiData::default();
         summary.message.role = Role::System;
         Self {
+            id: Default::default(),
+            title: Default::default(),
+            branch_of: Default::default(),
+            pinned: Default::default(),
+            archived: Default::default(),
+            folder: Default::default(),
             messages: Default::default(),
             summary,
             amount_of_message_summarized: Default::default(),
+            context_window: Default::default(),
             generate_user_input: Default::default(),
+            regenerating: Default::default(),
             state: Default::default(),
             scroll_state: Default::default(),
         }
@@ -77,6 +202,63 @@ impl Dialogue {
         self.summary.message.content.is_empty()
     }
 
+    /// Back to idle once the request fails or is cancelled, the variant being regenerated
+    /// is dropped if nothing is received for it
+    pub fn stop_on_error(&mut self) {
+        if let Some((idx, _)) = self.regenerating.take()
+            && let Some(message) = self.messages.get_mut(idx)
+        {
+            message.discard_empty_variant();
+        }
+        self.state = DialogueState::Idle;
+        self.generate_user_input = false;
+    }
+
+    /// The message receiving the reply, the last one unless another one is regenerated
+    pub fn reply_idx(&self) -> usize {
+        self.regenerating
+            .map(|(idx, _)| idx)
+            .unwrap_or(self.messages.len().saturating_sub(1))
+    }
+
+    /// Whether the message at `idx` can be replied again, the messages before it must not be
+    /// summarized as the summary covers the ones after it
+    pub fn can_regenerate(&self, idx: usize) -> bool {
+        self.is_idle()
+            && idx >= self.amount_of_message_summarized
+            && self
+                .messages
+                .get(idx)
+                .is_some_and(|m| m.message.role == Role::Assistant)
+    }
+
+    /// The leading system messages, sent before the others even once the context window
+    /// or the summary has passed them
+    pub fn system_prompt_range(&self) -> Range<usize> {
+        let len = self
+            .messages
+            .range(..self.reply_idx())
+            .take_while(|m| m.message.role == Role::System)
+            .count();
+        0..len
+    }
+
+    /// Messages sent after the system prompt and the summary, the ones before the one that
+    /// receives the reply
+    pub fn sent_range(&self) -> Range<usize> {
+        let end = self.reply_idx();
+        let start = match self.context_window {
+            Some(len) => end.saturating_sub(len),
+            None => self.amount_of_message_summarized.min(end),
+        };
+        start.max(self.system_prompt_range().end)..end
+    }
+
+    /// Whether the summary is sent, it's not used with a context window
+    pub fn sends_summary(&self) -> bool {
+        self.context_window.is_none() && !self.is_summary_empty()
+    }
+
     pub fn start_idx(&self, show_summarized: bool) -> usize {
         if show_summarized {
             0
@@ -109,13 +291,18 @@ impl Dialogue {
         self.scroll_state.all_messages_scroll = new_offset + self.height_offset(show_summarized);
     }
 
+    /// Chars of the messages sent, taken as tokens to compare with `n_ctx`, which is only an
+    /// approximation without a tokenizer
     pub fn token_count(&self) -> usize {
         // TODO: use Tiktoken?
         let mut total = 0;
 
         total += self.summary.message.content.chars().count();
 
-        for msg in self.messages.iter().skip(self.amount_of_message_summarized) {
+        // the messages after a regenerated one are not sent
+        let end = (self.reply_idx() + 1).min(self.messages.len());
+        let start = self.amount_of_message_summarized.min(end);
+        for msg in self.messages.range(start..end) {
             total += msg.message.content.chars().count();
         }
 
@@ -140,27 +327,470 @@ impl Dialogue {
     pub fn is_idle(&self) -> bool {
         self.state == DialogueState::Idle
     }
+
+    pub fn title(&self) -> String {
+        if !self.title.is_empty() {
+            return self.title.clone();
+        }
+
+        match self.messages.front() {
+            Some(m) => m.message.content.chars().take(20).collect(),
+            None => "New Chat".to_string(),
+        }
+    }
+
+    /// Creates a new dialogue containing the messages up to and including `idx`,
+    /// the summary is only kept if a message after the summarized ones is included
+    pub fn branch(&self, idx: usize) -> Self {
+        assert!(self.is_idle());
+
+        let new_len = (idx + 1).min(self.messages.len());
+        let mut branch = Self {
+            title: format!("{} (branch)", self.title()),
+            branch_of: Some(self.id),
+            context_window: self.context_window,
+            messages: self
+                .messages
+                .range(..new_len)
+                .map(|m| m.message.clone().into())
+                .collect(),
+            ..Default::default()
+        };
+
+        if new_len > self.amount_of_message_summarized {
+            branch.summary = self.summary.message.clone().into();
+            branch.amount_of_message_summarized = self.amount_of_message_summarized;
+        }
+
+        branch
+    }
 }
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct DialoguesData {
     pub dialogues: VecDeque<Dialogue>,
+
+    /// Names of the folders in the order they're listed, kept even if empty
+    #[serde(default)]
+    pub folders: Vec<String>,
+
+    /// Encrypted along with the dialogues, as it holds the API keys
     pub manager: Arc<RwLock<ChatConfigManager>>,
+    #[serde(default)]
+    next_id: u64,
+
+    /// Encrypts the store when saving, if a passphrase is set
+    #[serde(skip)]
+    vault: Option<Vault>,
+}
+
+/// The store read from the disk
+pub enum StoredData {
+    Plain(DialoguesData),
+
+    /// Encrypted, see [`DialoguesData::unlock`]
+    Locked(Vec<u8>),
 }
 
 impl DialoguesData {
     const FILENAME: &'static str = "dialogues_data.json";
 
-    pub fn load() -> std::io::Result<Self> {
-        let path = std::env::current_exe()?.join(format!("../{}", Self::FILENAME));
-        Ok(serde_json::from_str::<DialoguesData>(
-            &std::fs::read_to_string(path)?,
-        )?)
+    fn path() -> std::io::Result<PathBuf> {
+        Ok(std::env::current_exe()?.join(format!("../{}", Self::FILENAME)))
+    }
+
+    pub fn load() -> std::io::Result<StoredData> {
+        let bytes = std::fs::read(Self::path()?)?;
+        if vault::is_encrypted(&bytes) {
+            return Ok(StoredData::Locked(bytes));
+        }
+
+        Ok(StoredData::Plain(Self::from_json(&bytes)?))
+    }
+
+    /// Decrypts the store read by [`Self::load`], the passphrase is kept to encrypt it when saving
+    pub fn unlock(bytes: &[u8], passphrase: &str) -> anyhow::Result<Self> {
+        let (vault, plain) = Vault::open(bytes, passphrase)?;
+        Ok(Self::opened(vault, &plain)?)
+    }
+
+    /// The store decrypted by [`Vault::open`], as [`Self::unlock`] does
+    pub fn opened(vault: Vault, plain: &[u8]) -> std::io::Result<Self> {
+        let mut data = Self::from_json(plain)?;
+        data.vault = Some(vault);
+        Ok(data)
+    }
+
+    fn from_json(bytes: &[u8]) -> std::io::Result<Self> {
+        let mut data = serde_json::from_slice::<DialoguesData>(bytes)?;
+
+        // Dialogues saved by older versions have no id yet
+        let max_id = data.dialogues.iter().map(|d| d.id).max().unwrap_or(0);
+        data.next_id = data.next_id.max(max_id + 1);
+        for idx in 0..data.dialogues.len() {
+            if data.dialogues[idx].id == 0 {
+                data.dialogues[idx].id = data.alloc_id();
+            }
+        }
+
+        // A folder is never left out of the list while a dialogue is in it
+        for idx in 0..data.dialogues.len() {
+            if let Some(folder) = &data.dialogues[idx].folder
+                && !data.folders.contains(folder)
+            {
+                data.folders.push(folder.clone());
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Indices of the dialogues in the order they're listed, the pinned ones come first,
+    /// the archived ones are left out unless `show_archived`
+    pub fn listed(&self, show_archived: bool) -> Vec<usize> {
+        let mut listed: Vec<_> = (0..self.dialogues.len())
+            .filter(|&idx| show_archived || !self.dialogues[idx].archived)
+            .collect();
+        listed.sort_by_key(|&idx| !self.dialogues[idx].pinned);
+        listed
+    }
+
+    /// Adds an empty folder with a name not taken yet, returns the name
+    pub fn add_folder(&mut self) -> String {
+        let name = (1..)
+            .map(|n| match n {
+                1 => "New Folder".to_owned(),
+                n => format!("New Folder {n}"),
+            })
+            .find(|name| !self.folders.contains(name))
+            .unwrap();
+        self.folders.push(name.clone());
+        name
+    }
+
+    /// Renames the folder `old` along with the dialogues in it, returns `false` if `new`
+    /// is empty or taken by another folder
+    pub fn rename_folder(&mut self, old: &str, new: &str) -> bool {
+        let new = new.trim();
+        if new.is_empty() || (new != old && self.folders.iter().any(|f| f == new)) {
+            return false;
+        }
+
+        let Some(folder) = self.folders.iter_mut().find(|f| *f == old) else {
+            return false;
+        };
+        *folder = new.to_owned();
+        for dialogue in self.dialogues.iter_mut() {
+            if dialogue.folder.as_deref() == Some(old) {
+                dialogue.folder = Some(new.to_owned());
+            }
+        }
+        true
+    }
+
+    /// Removes the folder `name`, the dialogues in it become ungrouped
+    pub fn remove_folder(&mut self, name: &str) {
+        self.folders.retain(|f| f != name);
+        for dialogue in self.dialogues.iter_mut() {
+            if dialogue.folder.as_deref() == Some(name) {
+                dialogue.folder = None;
+            }
+        }
+    }
+
+    /// Index of the dialogue with `id`, which stays the same while others are added or removed
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        self.dialogues.iter().position(|d| d.id == id)
+    }
+
+    pub fn alloc_id(&mut self) -> u64 {
+        self.next_id = self.next_id.max(1);
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.vault.is_some()
+    }
+
+    /// Encrypts the store with `passphrase` from now on, or stores it as plain text if `None`,
+    /// the store is saved at once
+    pub fn set_passphrase(&mut self, passphrase: Option<&str>) -> anyhow::Result<()> {
+        let vault = passphrase.map(Vault::new).transpose()?;
+        let old_vault = std::mem::replace(&mut self.vault, vault);
+        if let Err(err) = self.save() {
+            self.vault = old_vault;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    fn to_bytes(&self) -> std::io::Result<Vec<u8>> {
+        let json = serde_json::to_vec(self)?;
+        Ok(match &self.vault {
+            Some(vault) => vault.encrypt(&json),
+            None => json,
+        })
     }
 
     pub fn save(&self) -> std::io::Result<()> {
-        let path = std::env::current_exe()?.join(format!("../{}", Self::FILENAME));
-        std::fs::write(path, serde_json::to_vec(self)?)?;
+        let path = Self::path()?;
+
+        // Written aside first so that a failed write never leaves a damaged store
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, self.to_bytes()?)?;
+        std::fs::rename(temp_path, path)?;
         Ok(())
     }
+
+    /// Renames the store to an unused name with the time of it so that a new one can be
+    /// started, returns the new path of it
+    pub fn move_aside() -> std::io::Result<PathBuf> {
+        let path = Self::path()?;
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let now = UtcDateTime::now();
+        let stamp = format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}",
+            now.year, now.month, now.day, now.hour, now.minute, now.second
+        );
+        let new_path = (1..)
+            .map(|i| match i {
+                1 => path.with_file_name(format!("{stem}.locked-{stamp}.json")),
+                i => path.with_file_name(format!("{stem}.locked-{stamp}-{i}.json")),
+            })
+            .find(|new_path| !new_path.exists())
+            .unwrap();
+        std::fs::rename(path, &new_path)?;
+        Ok(new_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dialogue_with_summary(len: usize, amount_of_message_summarized: usize) -> Dialogue {
+        let mut dialogue = Dialogue {
+            id: 1,
+            amount_of_message_summarized,
+            ..Default::default()
+        };
+        dialogue.summary.message.content = "summary".to_owned();
+
+        for i in 0..len {
+            dialogue.messages.push_back(
+                Message {
+                    role: Role::User,
+                    content: format!("message {i}"),
+                    thinking_content: None,
+                    ..Default::default()
+                }
+                .into(),
+            );
+        }
+
+        dialogue
+    }
+
+    #[test]
+    fn branch_after_summarized_region_keeps_summary() {
+        let dialogue = dialogue_with_summary(6, 3);
+        let branch = dialogue.branch(4);
+
+        assert_eq!(branch.messages.len(), 5);
+        assert_eq!(branch.amount_of_message_summarized, 3);
+        assert_eq!(branch.summary.message.content, "summary");
+        assert_eq!(branch.branch_of, Some(1));
+        assert_eq!(branch.title, "message 0 (branch)");
+    }
+
+    #[test]
+    fn branch_inside_summarized_region_drops_summary() {
+        let dialogue = dialogue_with_summary(6, 3);
+        let branch = dialogue.branch(1);
+
+        assert_eq!(branch.messages.len(), 2);
+        assert_eq!(branch.amount_of_message_summarized, 0);
+        assert!(branch.is_summary_empty());
+        assert_eq!(branch.summary.message.role, Role::System);
+    }
+
+    #[test]
+    fn branch_at_end_of_summarized_region_drops_summary() {
+        let dialogue = dialogue_with_summary(6, 3);
+        let branch = dialogue.branch(2);
+
+        assert_eq!(branch.messages.len(), 3);
+        assert_eq!(branch.amount_of_message_summarized, 0);
+        assert!(branch.is_summary_empty());
+    }
+
+    #[test]
+    fn context_window_replaces_summary() {
+        let mut dialogue = dialogue_with_summary(6, 3);
+        assert_eq!(dialogue.sent_range(), 3..5);
+        assert!(dialogue.sends_summary());
+
+        dialogue.context_window = Some(2);
+        assert_eq!(dialogue.sent_range(), 3..5);
+        assert!(!dialogue.sends_summary());
+
+        // the summarized messages are sent again if the window reaches them
+        dialogue.context_window = Some(4);
+        assert_eq!(dialogue.sent_range(), 1..5);
+        dialogue.context_window = Some(10);
+        assert_eq!(dialogue.sent_range(), 0..5);
+    }
+
+    #[test]
+    fn system_prompt_is_kept_out_of_the_window() {
+        let mut dialogue = dialogue_with_summary(8, 0);
+        dialogue.messages[0].message.role = Role::System;
+        dialogue.messages[1].message.role = Role::System;
+        assert_eq!(dialogue.system_prompt_range(), 0..2);
+        assert_eq!(dialogue.sent_range(), 2..7);
+
+        dialogue.context_window = Some(3);
+        assert_eq!(dialogue.system_prompt_range(), 0..2);
+        assert_eq!(dialogue.sent_range(), 4..7);
+
+        // the window reaching the system prompt doesn't send it twice
+        dialogue.context_window = Some(6);
+        assert_eq!(dialogue.sent_range(), 2..7);
+    }
+
+    #[test]
+    fn regenerated_variants_are_sent_when_shown() {
+        let mut dialogue = dialogue_with_summary(6, 3);
+        let msg = &mut dialogue.messages[4];
+        msg.message.role = Role::Assistant;
+        assert_eq!(msg.variant_count(), 1);
+
+        msg.push_variant("other".to_owned());
+        assert_eq!(msg.variant_count(), 2);
+        assert_eq!(msg.variant_idx, 1);
+        assert_eq!(msg.message.role, Role::Assistant);
+        assert!(msg.message.content.is_empty());
+        assert_eq!(msg.variant_profile(), Some("other"));
+        msg.message.content = "reply".to_owned();
+
+        msg.select_variant(0);
+        assert_eq!(msg.message.content, "message 4");
+        assert_eq!(msg.variant_profile(), None);
+        msg.select_variant(1);
+        assert_eq!(msg.message.content, "reply");
+
+        // the reply of a regenerated message is given the messages before it only
+        assert!(dialogue.can_regenerate(4));
+        assert!(!dialogue.can_regenerate(2));
+        dialogue.regenerating = Some((4, 0));
+        assert_eq!(dialogue.sent_range(), 3..4);
+    }
+
+    #[test]
+    fn pinned_are_listed_first_and_archived_are_hidden() {
+        let mut data = DialoguesData::default();
+        for i in 0..5 {
+            let id = data.alloc_id();
+            data.dialogues.push_back(Dialogue {
+                id,
+                pinned: i == 1 || i == 3,
+                archived: i == 2 || i == 3,
+                ..Default::default()
+            });
+        }
+
+        assert_eq!(data.listed(false), [1, 0, 4]);
+        assert_eq!(data.listed(true), [1, 3, 0, 2, 4]);
+
+        let id = data.dialogues[4].id;
+        data.dialogues.remove(0);
+        assert_eq!(data.index_of(id), Some(3));
+        assert_eq!(data.index_of(0), None);
+    }
+
+    #[test]
+    fn folders_are_renamed_and_removed_with_their_dialogues() {
+        let mut data = DialoguesData::default();
+        assert_eq!(data.add_folder(), "New Folder");
+        assert_eq!(data.add_folder(), "New Folder 2");
+        data.dialogues.push_back(Dialogue {
+            folder: Some("New Folder".to_owned()),
+            ..Default::default()
+        });
+
+        assert!(!data.rename_folder("New Folder", "New Folder 2"));
+        assert!(!data.rename_folder("New Folder", "  "));
+        assert!(data.rename_folder("New Folder", " Work "));
+        assert_eq!(data.folders, ["Work", "New Folder 2"]);
+        assert_eq!(data.dialogues[0].folder.as_deref(), Some("Work"));
+
+        data.remove_folder("Work");
+        assert_eq!(data.folders, ["New Folder 2"]);
+        assert_eq!(data.dialogues[0].folder, None);
+
+        // folders of dialogues are listed even if the list misses them
+        data.dialogues[0].folder = Some("Lost".to_owned());
+        let json = serde_json::to_vec(&data).unwrap();
+        let data = DialoguesData::from_json(&json).unwrap();
+        assert_eq!(data.folders, ["New Folder 2", "Lost"]);
+    }
+
+    #[test]
+    fn encrypted_store_round_trip() {
+        let mut data = DialoguesData::default();
+        data.manager
+            .write()
+            .unwrap()
+            .cur_config_mut()
+            .assistant_param
+            .api_key = "sk-secret".to_owned();
+        data.dialogues.push_back(dialogue_with_summary(2, 0));
+        data.vault = Some(Vault::new("passphrase").unwrap());
+
+        let bytes = data.to_bytes().unwrap();
+        assert!(vault::is_encrypted(&bytes));
+        assert!(!bytes.windows(9).any(|w| w == b"sk-secret"));
+
+        assert!(DialoguesData::unlock(&bytes, "wrong").is_err());
+        let data = DialoguesData::unlock(&bytes, "passphrase").unwrap();
+        assert!(data.is_encrypted());
+        assert_eq!(data.dialogues.len(), 1);
+        assert_eq!(
+            data.manager
+                .read()
+                .unwrap()
+                .cur_config()
+                .assistant_param
+                .api_key,
+            "sk-secret"
+        );
+    }
+
+    #[test]
+    fn failed_regeneration_shows_the_reply_before() {
+        let mut dialogue = dialogue_with_summary(6, 3);
+        dialogue.messages[4].message.role = Role::Assistant;
+        dialogue.messages[4].push_variant("other".to_owned());
+        dialogue.regenerating = Some((4, 0));
+        dialogue.state = DialogueState::Sending;
+
+        dialogue.stop_on_error();
+        assert!(dialogue.is_idle());
+        assert_eq!(dialogue.regenerating, None);
+        let msg = &dialogue.messages[4];
+        assert_eq!(msg.message.content, "message 4");
+        assert_eq!((msg.variant_count(), msg.variant_idx), (1, 0));
+        assert!(msg.variants.is_empty());
+
+        // a partial reply is kept
+        dialogue.messages[4].push_variant("other".to_owned());
+        dialogue.messages[4].message.content = "partial".to_owned();
+        dialogue.regenerating = Some((4, 0));
+        dialogue.stop_on_error();
+        let msg = &dialogue.messages[4];
+        assert_eq!(msg.message.content, "partial");
+        assert_eq!((msg.variant_count(), msg.variant_idx), (2, 1));
+    }
 }
@@ -1,20 +1,28 @@
 use crate::chat::{
     Message, Role,
-    dialogue::{Dialogue, DialogueState, DialoguesData},
-    dialogue_task::dialogue_task,
+    config::ChatConfigProfile,
+    dialogue::{Dialogue, DialogueState, DialoguesData, StoredData},
+    dialogue_task::stream_from_api,
+    thinking::ThinkingSplitter,
+    usage::{RequestStats, UsageEntry, UsageLog},
+    vault::{Vault, VaultError},
 };
 
+use eapp_utils::{
+    task::{
+        Task,
+        async_task::{CancellationToken, JobEvent, JobHandle},
+    },
+    widgets::toast::Toasts,
+};
 use eframe::egui;
 
 use std::{
     collections::HashMap,
-    sync::{Arc, atomic::AtomicBool},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use tokio::sync::mpsc::{self, Receiver, Sender};
-
-pub enum Request {
-    Send((usize, SendType, Vec<Message>, CancellationToken)),
-}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SendType {
@@ -27,80 +35,190 @@ pub enum SendType {
 pub enum StreamType {
     Content,
     Reasoning,
+    /// Given once the reply ends, see [`Message::finish_reason`]
+    FinishReason,
 }
 
-pub enum Result {
-    Streaming((usize, StreamType, String)),
-    Done(usize),
-    Error((usize, String)),
+/// A request streaming parts of the reply, its usage is given with the result
+type SendJob = JobHandle<(StreamType, String), (UsageEntry, anyhow::Result<()>)>;
+
+/// The encrypted store waiting for the passphrase
+struct Locked {
+    bytes: Arc<Vec<u8>>,
+    /// Wrong attempts so far
+    failures: u32,
+    /// No attempt is taken before it, after too many wrong ones
+    retry_at: Option<Instant>,
+    /// Decrypts the store with the passphrase being tried, off the UI thread
+    /// as deriving the key takes a while
+    task: Option<Task<Result<(Vault, Vec<u8>), VaultError>>>,
 }
 
-#[derive(Default, Clone)]
-pub struct CancellationToken {
-    cancelled: Arc<AtomicBool>,
-}
+pub struct DialogueManager {
+    pub cur_dialogue_idx: usize,
+    pub data: DialoguesData,
+    ctx: egui::Context,
 
-impl CancellationToken {
-    pub fn new() -> Self {
-        Self {
-            cancelled: Arc::new(AtomicBool::new(false)),
-        }
-    }
+    /// Keyed by the dialogue id, as indices change while replies are streaming
+    jobs: HashMap<u64, SendJob>,
 
-    pub fn is_cancelled(&self) -> bool {
-        self.cancelled.load(std::sync::atomic::Ordering::Relaxed)
-    }
+    /// Splits the content streamed by the job of the same dialogue
+    splitters: HashMap<u64, ThinkingSplitter>,
 
-    pub fn cancel(&self) {
-        self.cancelled
-            .store(true, std::sync::atomic::Ordering::Relaxed);
-    }
-}
+    pub usage: UsageLog,
 
-pub struct DialogueManager {
-    pub cur_dialogue_idx: usize,
-    pub data: DialoguesData,
-    request_tx: Sender<Request>,
-    result_rx: Receiver<Result>,
-    cancellation_tokens: HashMap<usize, CancellationToken>,
+    locked: Option<Locked>,
 }
 
 impl DialogueManager {
+    /// Wrong attempts to unlock taken without waiting
+    pub const FREE_UNLOCK_ATTEMPTS: u32 = 3;
+
     pub fn new(ctx: egui::Context) -> Self {
-        let data = match DialoguesData::load() {
-            Ok(data) => data,
-            Err(err) => {
-                log::error!("Error when load `DialogueData`: {err}");
-                DialoguesData::default()
+        let mut locked = None;
+        let data = eapp_utils::capture_error_or!(
+            err => log::error!("Error when load `DialogueData`: {err}"),
+            DialoguesData::default(),
+            match DialoguesData::load()? {
+                StoredData::Plain(data) => data,
+                StoredData::Locked(bytes) => {
+                    locked = Some(Locked {
+                        bytes: Arc::new(bytes),
+                        failures: 0,
+                        retry_at: None,
+                        task: None,
+                    });
+                    DialoguesData::default()
+                }
             }
-        };
-        let (request_tx, request_rx) = mpsc::channel::<Request>(100);
-        let (result_tx, result_rx) = mpsc::channel::<Result>(100);
-
-        tokio::spawn(dialogue_task(
-            request_rx,
-            result_tx,
-            data.manager.clone(),
-            ctx,
-        ));
-
+        );
         let cur_dialogue_idx = 0;
-        let cancellation_tokens = HashMap::new();
+        let jobs = HashMap::new();
 
         Self {
             cur_dialogue_idx,
             data,
-            request_tx,
-            result_rx,
-            cancellation_tokens,
+            ctx,
+            jobs,
+            splitters: HashMap::new(),
+            usage: UsageLog::load(),
+            locked,
         }
     }
 
+    /// Whether the store is encrypted and not unlocked yet, nothing should be done with it
+    pub fn is_locked(&self) -> bool {
+        self.locked.is_some()
+    }
+
+    /// Whether the passphrase is being tried
+    pub fn is_unlocking(&self) -> bool {
+        self.locked
+            .as_ref()
+            .is_some_and(|locked| locked.task.is_some())
+    }
+
+    /// How long to wait before the next attempt, after too many wrong ones
+    pub fn unlock_wait(&self) -> Option<Duration> {
+        let retry_at = self.locked.as_ref()?.retry_at?;
+        Some(retry_at.saturating_duration_since(Instant::now())).filter(|wait| !wait.is_zero())
+    }
+
+    /// Starts trying `passphrase`, see [`Self::poll_unlock`] for the result,
+    /// nothing is done while an attempt is running or has to wait
+    pub fn unlock(&mut self, passphrase: String) {
+        if self.is_unlocking() || self.unlock_wait().is_some() {
+            return;
+        }
+        let Some(locked) = &mut self.locked else {
+            return;
+        };
+
+        let bytes = locked.bytes.clone();
+        let ctx = self.ctx.clone();
+        let (cancel_sender, _) = std::sync::mpsc::channel();
+        locked.task = Some(Task::new(cancel_sender, move || {
+            let result = Vault::open(&bytes, &passphrase);
+            ctx.request_repaint();
+            result
+        }));
+    }
+
+    /// The result of the attempt started by [`Self::unlock`] once it's done
+    pub fn poll_unlock(&mut self) -> Option<anyhow::Result<()>> {
+        let locked = self.locked.as_mut()?;
+        if !locked.task.as_ref()?.is_finished() {
+            return None;
+        }
+
+        let result = match locked.task.take().unwrap().get_result() {
+            Ok(Ok((vault, plain))) => {
+                DialoguesData::opened(vault, &plain).map_err(anyhow::Error::from)
+            }
+            Ok(Err(err)) => {
+                locked.failures += 1;
+                locked.retry_at = Some(Instant::now() + unlock_backoff(locked.failures));
+                Err(err.into())
+            }
+            Err(_) => Err(anyhow::anyhow!("Cannot unlock, the key derivation panics")),
+        };
+
+        Some(result.map(|data| {
+            self.data = data;
+            self.cur_dialogue_idx = 0;
+            self.locked = None;
+        }))
+    }
+
+    /// Gives up the encrypted store to start with an empty one, the store is moved aside
+    /// rather than removed, returns where it is moved to
+    pub fn start_fresh(&mut self) -> std::io::Result<PathBuf> {
+        let path = DialoguesData::move_aside()?;
+        self.locked = None;
+        Ok(path)
+    }
+
     pub fn new_dialogue(&mut self) {
-        self.data.dialogues.push_front(Dialogue::default());
+        let dialogue = Dialogue {
+            id: self.data.alloc_id(),
+            ..Default::default()
+        };
+        self.data.dialogues.push_front(dialogue);
+        self.cur_dialogue_idx = 0;
+    }
+
+    /// Branches the current dialogue at `msg_idx`, the branch is placed at the front
+    /// as a new dialogue is, and becomes the current dialogue
+    pub fn branch_from(&mut self, msg_idx: usize) {
+        assert!(self.is_idle());
+
+        let mut branch = self.cur_dialogue().branch(msg_idx);
+        branch.id = self.data.alloc_id();
+
+        self.data.dialogues.push_front(branch);
         self.cur_dialogue_idx = 0;
     }
 
+    /// How many existing ancestors the dialogue at `idx` has
+    pub fn branch_depth(&self, idx: usize) -> usize {
+        let mut depth = 0;
+        let mut origin = self.data.dialogues[idx].branch_of;
+
+        while let Some(id) = origin
+            && depth < self.data.dialogues.len()
+        {
+            match self.data.dialogues.iter().find(|d| d.id == id) {
+                Some(dialogue) => {
+                    depth += 1;
+                    origin = dialogue.branch_of;
+                }
+                None => break,
+            }
+        }
+
+        depth
+    }
+
     pub fn remove_dialogue(&mut self, dialogue_idx: usize) {
         assert!(self.is_idle());
         if dialogue_idx >= self.data.dialogues.len() {
@@ -115,11 +233,11 @@ impl DialogueManager {
     }
 
     pub fn is_idle(&self) -> bool {
-        self.cancellation_tokens.is_empty()
+        self.jobs.is_empty()
     }
 
     pub fn is_dialogue_idle(&self, idx: usize) -> bool {
-        !self.cancellation_tokens.contains_key(&idx)
+        !self.jobs.contains_key(&self.data.dialogues[idx].id)
     }
 
     pub fn is_cur_dialogue_idle(&self) -> bool {
@@ -162,10 +280,6 @@ impl DialogueManager {
     pub fn trigger_request(&mut self) {
         assert!(self.is_cur_dialogue_idle());
 
-        let token = CancellationToken::new();
-        self.cancellation_tokens
-            .insert(self.cur_dialogue_idx, token.clone());
-
         let dialogue = &mut self.data.dialogues[self.cur_dialogue_idx];
 
         dialogue.generate_user_input = dialogue.messages.is_empty()
@@ -178,27 +292,40 @@ impl DialogueManager {
                 role: Role::Assistant.reversed_if(dialogue.generate_user_input),
                 content: String::new(),
                 thinking_content: None,
+                ..Default::default()
             }
             .into(),
         );
 
+        self.summarize_or_send();
+    }
+
+    /// Replies to the current dialogue, summarizing it first if it's too long for the profile
+    /// of the reply
+    fn summarize_or_send(&mut self) {
+        let profile_idx = self.reply_profile(self.cur_dialogue_idx);
+        let dialogue = &mut self.data.dialogues[self.cur_dialogue_idx];
+
         let manager = self.data.manager.read().unwrap();
-        let config = manager.cur_config();
+        let config = &manager.profiles[profile_idx].config;
         let current_tokens = dialogue.token_count();
-        let threshold = (config.compression_threshold * config.n_ctx as f32) as usize;
+        let threshold = config.summarize_at();
+        let target = config.summary_target();
 
-        if current_tokens > threshold {
+        if dialogue.context_window.is_none() && current_tokens > threshold {
             dialogue.state = DialogueState::Summarizing;
 
             let mut accumulated_tokens = 0;
             let mut start_idx = dialogue.amount_of_message_summarized;
-            let end_idx = dialogue.messages.len().saturating_sub(2).max(start_idx);
+            // the message before the reply is kept for it to reply to
+            let end_idx = dialogue.reply_idx().saturating_sub(1).max(start_idx);
 
             for idx in dialogue.amount_of_message_summarized..end_idx {
                 if let Some(msg) = dialogue.messages.get(idx) {
-                    accumulated_tokens += msg.message.content.len();
+                    // counted the same way as `token_count`
+                    accumulated_tokens += msg.message.content.chars().count();
 
-                    if (current_tokens - accumulated_tokens) <= threshold / 2 {
+                    if (current_tokens - accumulated_tokens) <= target {
                         start_idx = idx + 1;
                         break;
                     }
@@ -223,122 +350,202 @@ impl DialogueManager {
             messages_to_summarize.insert(0, summary_message);
             dialogue.summary.message.clear();
 
-            tokio::spawn({
-                let idx = self.cur_dialogue_idx;
-                let tx = self.request_tx.clone();
-                async move {
-                    let _ = tx
-                        .send(Request::Send((
-                            idx,
-                            SendType::Summary,
-                            messages_to_summarize,
-                            token,
-                        )))
-                        .await;
-                }
-            });
+            drop(manager);
+            self.send(
+                self.cur_dialogue_idx,
+                SendType::Summary,
+                messages_to_summarize,
+                CancellationToken::new(),
+            );
         } else {
             dialogue.state = DialogueState::Sending;
+            drop(manager);
 
             let (messages_to_send, send_type) =
                 self.prepare_messages_for_sending(self.cur_dialogue_idx);
+            self.send(
+                self.cur_dialogue_idx,
+                send_type,
+                messages_to_send,
+                CancellationToken::new(),
+            );
+        }
+    }
 
-            tokio::spawn({
-                let idx = self.cur_dialogue_idx;
-                let tx = self.request_tx.clone();
-                async move {
-                    let _ = tx
-                        .send(Request::Send((idx, send_type, messages_to_send, token)))
-                        .await;
-                }
-            });
+    /// Replies again to the messages before the assistant message at `msg_idx` of the current
+    /// dialogue with the profile at `profile_idx`, the reply is kept as another variant of it
+    pub fn regenerate(&mut self, msg_idx: usize, profile_idx: usize) {
+        if !self.cur_dialogue().can_regenerate(msg_idx) {
+            return;
         }
+
+        let manager = self.data.manager.read().unwrap();
+        let Some(profile) = manager.profiles.get(profile_idx) else {
+            return;
+        };
+        let profile = profile.name.clone();
+        drop(manager);
+
+        let dialogue = &mut self.data.dialogues[self.cur_dialogue_idx];
+        dialogue.messages[msg_idx].push_variant(profile);
+        dialogue.regenerating = Some((msg_idx, profile_idx));
+        self.summarize_or_send();
+    }
+
+    /// Index of the profile the dialogue at `idx` is replied with
+    fn reply_profile(&self, idx: usize) -> usize {
+        let manager = self.data.manager.read().unwrap();
+        self.data.dialogues[idx]
+            .regenerating
+            .map(|(_, profile_idx)| profile_idx)
+            .filter(|&profile_idx| profile_idx < manager.profiles.len())
+            .unwrap_or(manager.current_profile_index)
+    }
+
+    /// Streams the reply of `messages` into the dialogue at `idx`, which may move meanwhile
+    fn send(
+        &mut self,
+        idx: usize,
+        send_type: SendType,
+        messages: Vec<Message>,
+        token: CancellationToken,
+    ) {
+        let profile_idx = self.reply_profile(idx);
+        let manager = self.data.manager.read().unwrap();
+        let ChatConfigProfile { name, config } = &manager.profiles[profile_idx];
+        let (config, profile) = (config.clone(), name.clone());
+        drop(manager);
+
+        let job = JobHandle::spawn_with_token(&self.ctx, token, move |token, tx| async move {
+            let start = Instant::now();
+            let mut stats = RequestStats::default();
+            let result =
+                stream_from_api(token, &config, send_type, messages, &tx, &mut stats).await;
+
+            let entry = UsageEntry::new(profile, stats, start.elapsed(), result.is_ok());
+            (entry, result)
+        });
+        let id = self.data.dialogues[idx].id;
+        self.jobs.insert(id, job);
+        self.splitters.insert(id, ThinkingSplitter::default());
     }
 
     pub fn cancel(&mut self) {
         assert!(!self.is_cur_dialogue_idle());
 
-        if let Some(token) = self.cancellation_tokens.get(&self.cur_dialogue_idx) {
-            token.cancel();
+        if let Some(job) = self.jobs.get(&self.cur_dialogue().id) {
+            job.cancel();
         }
-    }
 
-    pub fn update(&mut self, status_msg: &mut String) {
-        while let Ok(result) = self.result_rx.try_recv() {
-            match result {
-                Result::Streaming((idx, stream_type, content)) => {
-                    if let Some(dialogue) = self.data.dialogues.get_mut(idx) {
-                        let message = match dialogue.state {
-                            DialogueState::Summarizing => &mut dialogue.summary.message,
-                            DialogueState::Sending => {
-                                &mut dialogue.messages.back_mut().unwrap().message
-                            }
-                            _ => unreachable!(),
-                        };
+        // what's streamed so far is shown at once rather than typed out
+        let dialogue = self.cur_dialogue_mut();
+        let reply_idx = dialogue.reply_idx();
+        if let Some(reply) = dialogue.messages.get_mut(reply_idx) {
+            reply.typewriter = None;
+        }
+    }
 
-                        match stream_type {
-                            StreamType::Content => {
-                                message.content.push_str(&content);
-                                message.split_thinking_content();
+    pub fn update(&mut self, toasts: &mut Toasts) {
+        let mut finished = Vec::new();
+
+        for (&id, job) in self.jobs.iter_mut() {
+            while let Some(event) = job.poll() {
+                match event {
+                    JobEvent::Progress((stream_type, content)) => {
+                        if let Some(dialogue) = self.data.dialogues.iter_mut().find(|d| d.id == id)
+                        {
+                            let message = match dialogue.state {
+                                DialogueState::Summarizing => &mut dialogue.summary.message,
+                                DialogueState::Sending => {
+                                    let reply_idx = dialogue.reply_idx();
+                                    &mut dialogue.messages[reply_idx].message
+                                }
+                                _ => unreachable!(),
+                            };
+
+                            match stream_type {
+                                StreamType::Content => self
+                                    .splitters
+                                    .entry(id)
+                                    .or_default()
+                                    .push(message, &content),
+                                StreamType::Reasoning => message
+                                    .thinking_content
+                                    .get_or_insert_default()
+                                    .push_str(&content),
+                                StreamType::FinishReason => message.finish_reason = Some(content),
                             }
-                            StreamType::Reasoning => message
-                                .thinking_content
-                                .get_or_insert_default()
-                                .push_str(&content),
                         }
                     }
+                    JobEvent::Done((entry, result)) => {
+                        self.usage.record(entry);
+                        finished.push((id, result));
+                    }
                 }
-                Result::Done(idx) => {
-                    if let Some(dialogue) = self.data.dialogues.get_mut(idx) {
-                        match dialogue.state {
-                            DialogueState::Summarizing => {
-                                dialogue.summary.message.split_thinking_content();
-
-                                dialogue.state = DialogueState::Sending;
-                                let (messages_to_send, send_type) =
-                                    self.prepare_messages_for_sending(idx);
-
-                                let token = self.cancellation_tokens.get(&idx).unwrap().clone();
-
-                                let tx = self.request_tx.clone();
-                                tokio::spawn(async move {
-                                    let _ = tx
-                                        .send(Request::Send((
-                                            idx,
-                                            send_type,
-                                            messages_to_send,
-                                            token,
-                                        )))
-                                        .await;
-                                });
-                            }
-                            DialogueState::Sending => {
-                                let last_msg = &mut dialogue.messages.back_mut().unwrap().message;
-                                last_msg.split_thinking_content();
+            }
+        }
 
-                                dialogue.state = DialogueState::Idle;
-                                dialogue.generate_user_input = false;
-                                self.cancellation_tokens.remove(&idx);
-                            }
-                            _ => {}
+        for (id, result) in finished {
+            let token = self.jobs.remove(&id).unwrap().token().clone();
+            let mut splitter = self.splitters.remove(&id).unwrap_or_default();
+            let Some(idx) = self.data.index_of(id) else {
+                continue;
+            };
+
+            let profile_idx = self.reply_profile(idx);
+            let trim_incomplete = self.data.manager.read().unwrap().profiles[profile_idx]
+                .config
+                .trim_incomplete;
+
+            if let Some(dialogue) = self.data.dialogues.get_mut(idx) {
+                match dialogue.state {
+                    DialogueState::Summarizing => splitter.finish(&mut dialogue.summary.message),
+                    DialogueState::Sending => {
+                        let reply_idx = dialogue.reply_idx();
+                        let message = &mut dialogue.messages[reply_idx].message;
+                        splitter.finish(message);
+                        if trim_incomplete {
+                            message.trim_incomplete_sentence();
                         }
                     }
+                    _ => {}
+                }
+            }
+
+            if let Err(err) = result {
+                let error_msg = format!("Dialogue error: {err}");
+                log::error!("{error_msg}");
+                toasts.error(error_msg);
+                if let Some(dialogue) = self.data.dialogues.get_mut(idx) {
+                    dialogue.stop_on_error();
                 }
-                Result::Error((idx, err)) => {
-                    let error_msg = format!("Dialogue error: {err}");
-                    log::error!("{error_msg}");
-                    *status_msg = error_msg;
-                    if let Some(dialogue) = self.data.dialogues.get_mut(idx) {
+                continue;
+            }
+
+            if let Some(dialogue) = self.data.dialogues.get_mut(idx) {
+                match dialogue.state {
+                    DialogueState::Summarizing => {
+                        dialogue.state = DialogueState::Sending;
+                        let (messages_to_send, send_type) = self.prepare_messages_for_sending(idx);
+                        self.send(idx, send_type, messages_to_send, token);
+                    }
+                    DialogueState::Sending => {
                         dialogue.state = DialogueState::Idle;
                         dialogue.generate_user_input = false;
+                        dialogue.regenerating = None;
                     }
-                    self.cancellation_tokens.remove(&idx);
+                    _ => {}
                 }
             }
         }
     }
 
     pub fn save(&self) {
+        // The store which is not unlocked must not be overwritten
+        if self.is_locked() {
+            return;
+        }
+
         if let Err(err) = self.data.save() {
             log::error!("Error when save `DialogueData`: {err}");
         }
@@ -348,20 +555,30 @@ impl DialogueManager {
         let mut messages = Vec::new();
         let dialogue = &self.data.dialogues[dialogue_idx];
 
-        if !dialogue.is_summary_empty() {
+        messages.extend(
+            dialogue
+                .messages
+                .range(dialogue.system_prompt_range())
+                .map(|m| Message {
+                    role: Role::System,
+                    content: m.message.content.clone(),
+                    ..Default::default()
+                }),
+        );
+
+        if dialogue.sends_summary() {
             messages.push(dialogue.summary.message.clone());
         }
 
-        let start_idx = dialogue.amount_of_message_summarized;
-        let end_idx = dialogue.messages.len().saturating_sub(1);
         messages.extend(
             dialogue
                 .messages
-                .range(start_idx..end_idx)
+                .range(dialogue.sent_range())
                 .map(|m| Message {
                     role: m.message.role.reversed_if(dialogue.generate_user_input),
                     content: m.message.content.clone(),
                     thinking_content: None,
+                    ..Default::default()
                 }),
         );
 
@@ -373,3 +590,75 @@ impl DialogueManager {
         (messages, send_type)
     }
 }
+
+/// Wait before the next attempt to unlock after `failures` wrong ones, which doubles
+/// after [`DialogueManager::FREE_UNLOCK_ATTEMPTS`] up to 5 minutes
+fn unlock_backoff(failures: u32) -> Duration {
+    match failures.checked_sub(DialogueManager::FREE_UNLOCK_ATTEMPTS + 1) {
+        None => Duration::ZERO,
+        Some(doublings) => Duration::from_secs(5 << doublings.min(6)).min(Duration::from_secs(300)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dialogue(id: u64, len: usize) -> Dialogue {
+        let mut dialogue = Dialogue {
+            id,
+            ..Default::default()
+        };
+        for i in 0..len {
+            dialogue.messages.push_back(
+                Message {
+                    role: Role::User,
+                    content: format!("message {i} of {id}"),
+                    ..Default::default()
+                }
+                .into(),
+            );
+        }
+        dialogue
+    }
+
+    /// A manager of `dialogues` which doesn't touch the disk
+    fn manager(dialogues: Vec<Dialogue>) -> DialogueManager {
+        let mut data = DialoguesData {
+            dialogues: dialogues.into(),
+            ..Default::default()
+        };
+        let max_id = data.dialogues.iter().map(|d| d.id).max().unwrap_or(0);
+        while data.alloc_id() <= max_id {}
+
+        DialogueManager {
+            cur_dialogue_idx: 0,
+            data,
+            ctx: egui::Context::default(),
+            jobs: HashMap::new(),
+            splitters: HashMap::new(),
+            usage: UsageLog::in_memory(),
+            locked: None,
+        }
+    }
+
+    #[test]
+    fn branch_is_placed_at_the_front_and_selected() {
+        let mut manager = manager(vec![dialogue(1, 1), dialogue(2, 4)]);
+        manager.cur_dialogue_idx = 1;
+        manager.branch_from(2);
+
+        assert_eq!(manager.cur_dialogue_idx, 0);
+        assert_eq!(manager.data.dialogues.len(), 3);
+
+        let branch = manager.cur_dialogue();
+        assert_eq!(branch.branch_of, Some(2));
+        assert!(branch.id > 2);
+        assert_eq!(branch.messages.len(), 3);
+        assert_eq!(branch.messages[2].message.content, "message 2 of 2");
+
+        let ids: Vec<_> = manager.data.dialogues.iter().map(|d| d.id).collect();
+        assert_eq!(ids[1..], [1, 2]);
+        assert_eq!(manager.branch_depth(0), 1);
+    }
+}
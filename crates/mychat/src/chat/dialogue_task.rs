@@ -1,63 +1,23 @@
 use crate::chat::{
     Message, Role,
-    config::{ChatConfig, ChatConfigManager},
-    dialogue_manager::{CancellationToken, Request, Result, SendType, StreamType},
+    config::ChatConfig,
+    dialogue_manager::{SendType, StreamType},
+    usage::RequestStats,
 };
 use anyhow::anyhow;
-use eframe::egui;
+use eapp_utils::task::async_task::{CancellationToken, ProgressSender};
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde_json::json;
 use std::fmt::Write as _;
-use std::sync::{Arc, RwLock};
-use tokio::sync::mpsc::{Receiver, Sender};
-
-pub async fn dialogue_task(
-    mut request_rx: Receiver<Request>,
-    result_tx: Sender<Result>,
-    manager: Arc<RwLock<ChatConfigManager>>,
-    ctx: egui::Context,
-) {
-    loop {
-        let request = match request_rx.recv().await {
-            Some(request) => request,
-            None => return,
-        };
-
-        match request {
-            Request::Send((idx, send_type, messages, token)) => {
-                tokio::spawn({
-                    let config = manager.read().unwrap().cur_config().clone();
-                    let tx = result_tx.clone();
-                    let ctx = ctx.clone();
-
-                    async move {
-                        match stream_from_api(&ctx, token, &config, send_type, messages, &tx, idx)
-                            .await
-                        {
-                            Ok(_) => {
-                                let _ = tx.send(Result::Done(idx)).await;
-                            }
-                            Err(err) => {
-                                let _ = tx.send(Result::Error((idx, err.to_string()))).await;
-                            }
-                        }
-                        ctx.request_repaint();
-                    }
-                });
-            }
-        }
-    }
-}
 
-async fn stream_from_api(
-    ctx: &egui::Context,
+pub async fn stream_from_api(
     token: CancellationToken,
     config: &ChatConfig,
     send_type: SendType,
     messages: Vec<Message>,
-    tx: &Sender<Result>,
-    dialogue_idx: usize,
+    tx: &ProgressSender<(StreamType, String)>,
+    stats: &mut RequestStats,
 ) -> anyhow::Result<()> {
     let (param, all_messages) = match send_type {
         SendType::Assistant => {
@@ -65,6 +25,7 @@ async fn stream_from_api(
                 role: Role::System,
                 content: config.assistant_param.system_message.clone(),
                 thinking_content: None,
+                ..Default::default()
             }];
             all_messages.extend(messages);
 
@@ -75,6 +36,7 @@ async fn stream_from_api(
                 role: Role::System,
                 content: config.user_param.system_message.clone(),
                 thinking_content: None,
+                ..Default::default()
             }];
             all_messages.extend(messages);
 
@@ -91,13 +53,20 @@ async fn stream_from_api(
                 role: Role::System,
                 content,
                 thinking_content: None,
+                ..Default::default()
             }];
 
             (&config.summary_param, all_messages)
         }
     };
 
-    let request_body = json!({
+    stats.model = param.model.clone();
+    stats.prompt_tokens = all_messages
+        .iter()
+        .map(|message| message.content.chars().count())
+        .sum();
+
+    let mut request_body = json!({
         "model": param.model,
         "messages": all_messages,
         "max_tokens": param.max_tokens,
@@ -111,6 +80,11 @@ async fn stream_from_api(
         "include_reasoning": true,
     });
 
+    let stop: Vec<_> = config.stop.iter().filter(|stop| !stop.is_empty()).collect();
+    if send_type != SendType::Summary && !stop.is_empty() {
+        request_body["stop"] = json!(stop);
+    }
+
     // TODO: DEBUG
     log::warn!("\n{send_type:?}: {request_body}\n\n");
 
@@ -146,21 +120,21 @@ async fn stream_from_api(
                 break;
             }
 
-            if let Some(data) = line.strip_prefix("data: ")
-                && let Ok(json) = serde_json::from_str::<serde_json::Value>(data)
-                && let Some(delta) = json["choices"][0]["delta"].as_object()
-            {
+            let Some(json) = line
+                .strip_prefix("data: ")
+                .and_then(|data| serde_json::from_str::<serde_json::Value>(data).ok())
+            else {
+                continue;
+            };
+            let choice = &json["choices"][0];
+
+            if let Some(delta) = choice["delta"].as_object() {
                 macro_rules! send_streaming_if_has {
                     ($name: expr, $stream_type: expr) => {
                         if let Some(part) = delta.get($name).and_then(|v| v.as_str()) {
-                            ctx.request_repaint();
-                            tx.send(Result::Streaming((
-                                dialogue_idx,
-                                $stream_type,
-                                part.to_string(),
-                            )))
-                            .await
-                            .map_err(|e| anyhow!("Failed to send streaming: {}", e))?;
+                            stats.completion_tokens += part.chars().count();
+                            tx.send(($stream_type, part.to_string()))
+                                .map_err(|e| anyhow!("Failed to send streaming: {}", e))?;
                         }
                     };
                 }
@@ -168,6 +142,11 @@ async fn stream_from_api(
                 send_streaming_if_has!("content", StreamType::Content);
                 send_streaming_if_has!("reasoning", StreamType::Reasoning);
             }
+
+            if let Some(finish_reason) = choice["finish_reason"].as_str() {
+                tx.send((StreamType::FinishReason, finish_reason.to_owned()))
+                    .map_err(|e| anyhow!("Failed to send streaming: {}", e))?;
+            }
         }
     }
 
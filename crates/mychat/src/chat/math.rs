@@ -0,0 +1,745 @@
+//! LaTeX math in replies, `$...$` and `\(...\)` inline, `$$...$$` and `\[...\]` on their own,
+//! which are typeset in unicode as far as it goes and put back into the markdown
+
+/// Markdown of `text` with its math typeset, math in code is kept as text
+///
+/// Display math on a line of its own stays in the list or quote it's in, as a line of
+/// its own, or as a LaTeX code block if it can't be typeset. Elsewhere it's taken as inline.
+pub fn to_markdown(text: &str) -> String {
+    let mut markdown = String::with_capacity(text.len());
+
+    for segment in split_math(text) {
+        let latex = match segment {
+            Segment::Text(text) => {
+                markdown.push_str(text);
+                continue;
+            }
+            Segment::Inline(latex) => {
+                markdown.push_str(&inline_markdown(latex));
+                continue;
+            }
+            Segment::Display(latex) => latex,
+        };
+
+        // both `$$` and `\[` are two bytes long
+        let start = latex.as_ptr() as usize - text.as_ptr() as usize - 2;
+        let end = start + latex.len() + 4;
+        let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let prefix = &text[line_start..start];
+        let rest = text[end..].split('\n').next().unwrap_or_default();
+        if !is_container_prefix(prefix) || !rest.trim().is_empty() {
+            markdown.push_str(&inline_markdown(latex));
+            continue;
+        }
+
+        // the lines after the first one are indented as the content of the list item
+        let indent: String = prefix
+            .chars()
+            .map(|c| {
+                if c == '>' || c.is_whitespace() {
+                    c
+                } else {
+                    ' '
+                }
+            })
+            .collect();
+
+        match to_unicode(latex) {
+            Some(typeset) if !typeset.trim().is_empty() => {
+                // hard breaks around it, which are dropped at the end of a paragraph
+                let before = markdown.len() - prefix.len();
+                if indent == prefix && markdown[..before].ends_with('\n') {
+                    markdown.insert_str(before - 1, "  ");
+                }
+                markdown.push_str(&emphasis(&typeset));
+                markdown.push_str("  ");
+            }
+            _ => {
+                markdown.push_str("```latex\n");
+                for line in latex.trim().lines() {
+                    markdown.push_str(&indent);
+                    markdown.push_str(line.trim());
+                    markdown.push('\n');
+                }
+                markdown.push_str(&indent);
+                markdown.push_str("```");
+            }
+        }
+    }
+
+    markdown
+}
+
+/// Whether `prefix` of a line is only the markers of the lists and quotes it's in
+fn is_container_prefix(prefix: &str) -> bool {
+    let is_marker = |c: char| c == '>' || c.is_whitespace();
+    let is_list_marker = |c: char| c.is_ascii_digit() || ".)-*+".contains(c);
+
+    prefix.chars().all(|c| is_marker(c) || is_list_marker(c))
+        && prefix.chars().next_back().is_none_or(is_marker)
+}
+
+/// Inline math as markdown, in italic if it's typeset, or as code if not
+fn inline_markdown(latex: &str) -> String {
+    match to_unicode(latex) {
+        Some(text) if !text.trim().is_empty() => emphasis(&text),
+        _ if latex.contains('`') => format!("`` {} ``", latex.trim()),
+        _ => format!("`{}`", latex.trim()),
+    }
+}
+
+/// `text` in italic, escaped so that none of it is taken as markdown
+fn emphasis(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('*');
+    for c in text.trim().chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped.push('*');
+    escaped
+}
+
+#[derive(PartialEq, Debug)]
+enum Segment<'a> {
+    Text(&'a str),
+    Inline(&'a str),
+    Display(&'a str),
+}
+
+/// Finds the math out of code blocks and code spans, an escaped `\$` and
+/// an amount like `$5 and $10` are not math
+fn split_math(text: &str) -> Vec<Segment<'_>> {
+    let bytes = text.as_bytes();
+    let mut segments = Vec::new();
+    let mut seg_start = 0;
+    let mut i = 0;
+    let mut at_line_start = true;
+    // char and length of the opening fence of the code block it's in
+    let mut fence: Option<(u8, usize)> = None;
+
+    while i < bytes.len() {
+        if at_line_start {
+            let line_end = text[i..].find('\n').map_or(text.len(), |idx| i + idx);
+            let line = text[i..line_end].trim_start();
+            let run = |c: u8| line.bytes().take_while(|b| *b == c).count();
+
+            let fence_run = [b'`', b'~']
+                .into_iter()
+                .map(|c| (c, run(c)))
+                .find(|(_, len)| *len >= 3);
+            match (fence, fence_run) {
+                (None, Some(run)) => fence = Some(run),
+                (Some((c, len)), Some((run_c, run_len)))
+                    if c == run_c && run_len >= len && line[run_len..].trim().is_empty() =>
+                {
+                    fence = None;
+                }
+                (None, None) => at_line_start = false,
+                _ => (),
+            }
+
+            if at_line_start {
+                i = line_end + 1;
+                continue;
+            }
+        }
+
+        match bytes[i] {
+            b'\n' => {
+                at_line_start = true;
+                i += 1;
+            }
+            b'\\' => match bytes.get(i + 1) {
+                Some(b'(') | Some(b'[') => {
+                    let display = bytes[i + 1] == b'[';
+                    let close = if display { "\\]" } else { "\\)" };
+                    match text[i + 2..].find(close) {
+                        Some(len) => {
+                            let latex = &text[i + 2..i + 2 + len];
+                            let end = i + 2 + len + 2;
+                            let math = if display {
+                                Segment::Display(latex)
+                            } else {
+                                Segment::Inline(latex)
+                            };
+                            push_math(&mut segments, text, &mut seg_start, i, end, math);
+                            i = end;
+                        }
+                        None => i += 2,
+                    }
+                }
+                Some(next) if next.is_ascii() => i += 2,
+                _ => i += 1,
+            },
+            b'`' => {
+                let len = bytes[i..].iter().take_while(|b| **b == b'`').count();
+                let ticks = &text[i..i + len];
+                let mut j = i + len;
+                // the closing run has the same length exactly
+                i = loop {
+                    match text[j..].find(ticks) {
+                        Some(idx) => {
+                            let start = j + idx;
+                            let run = bytes[start..].iter().take_while(|b| **b == b'`').count();
+                            if run == len {
+                                break start + len;
+                            }
+                            j = start + run;
+                        }
+                        None => break i + len,
+                    }
+                };
+            }
+            b'$' if bytes.get(i + 1) == Some(&b'$') => match text[i + 2..].find("$$") {
+                Some(len) if !text[i + 2..i + 2 + len].trim().is_empty() => {
+                    let end = i + 2 + len + 2;
+                    let latex = &text[i + 2..i + 2 + len];
+                    push_math(
+                        &mut segments,
+                        text,
+                        &mut seg_start,
+                        i,
+                        end,
+                        Segment::Display(latex),
+                    );
+                    i = end;
+                }
+                _ => i += 2,
+            },
+            b'$' => match find_inline_close(text, i + 1) {
+                Some(close) => {
+                    let latex = &text[i + 1..close];
+                    push_math(
+                        &mut segments,
+                        text,
+                        &mut seg_start,
+                        i,
+                        close + 1,
+                        Segment::Inline(latex),
+                    );
+                    i = close + 1;
+                }
+                None => i += 1,
+            },
+            _ => i += 1,
+        }
+    }
+
+    if seg_start < text.len() {
+        segments.push(Segment::Text(&text[seg_start..]));
+    }
+    segments
+}
+
+/// Pushes the text from `seg_start` to `start` and the math after it, which ends at `end`
+fn push_math<'a>(
+    segments: &mut Vec<Segment<'a>>,
+    text: &'a str,
+    seg_start: &mut usize,
+    start: usize,
+    end: usize,
+    math: Segment<'a>,
+) {
+    if *seg_start < start {
+        segments.push(Segment::Text(&text[*seg_start..start]));
+    }
+    segments.push(math);
+    *seg_start = end;
+}
+
+/// Index of the `$` closing inline math which starts at `start`, the math doesn't start
+/// or end with a space, the closing `$` is not followed by a digit and no blank line is in it
+fn find_inline_close(text: &str, start: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if bytes
+        .get(start)
+        .is_none_or(|b| b.is_ascii_whitespace() || *b == b'$')
+    {
+        return None;
+    }
+
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'\n' if bytes.get(i + 1) == Some(&b'\n') => return None,
+            b'$' => {
+                let closes = !bytes[i - 1].is_ascii_whitespace()
+                    && !bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+                if closes {
+                    return Some(i);
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Typesets `latex` in unicode, `None` if it has something not supported
+pub fn to_unicode(latex: &str) -> Option<String> {
+    let mut parser = Parser {
+        chars: latex.chars().collect(),
+        pos: 0,
+    };
+    let text = parser.sequence(false)?;
+    (parser.pos == parser.chars.len()).then_some(text)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses until the end, or the closing `}` of a group which is consumed
+    fn sequence(&mut self, in_group: bool) -> Option<String> {
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None => return (!in_group).then_some(out),
+                Some('}') => {
+                    if !in_group {
+                        return None;
+                    }
+                    self.pos += 1;
+                    return Some(out);
+                }
+                Some(c) if c.is_whitespace() => {
+                    self.skip_spaces();
+                    if !out.is_empty() && !out.ends_with(' ') {
+                        out.push(' ');
+                    }
+                }
+                Some('^') | Some('_') => {
+                    let sup = self.next() == Some('^');
+                    let arg = self.argument()?;
+                    out.push_str(&script(&arg, sup));
+                }
+                Some('&') => return None,
+                Some('~') => {
+                    self.pos += 1;
+                    out.push(' ');
+                }
+                Some(_) => out.push_str(&self.atom()?),
+            }
+        }
+    }
+
+    /// Argument of a command or a script, a group or a single atom
+    fn argument(&mut self) -> Option<String> {
+        self.skip_spaces();
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Option<String> {
+        match self.next()? {
+            '{' => self.sequence(true),
+            '\\' => self.command(),
+            '}' | '^' | '_' => None,
+            '-' => Some("−".to_owned()),
+            c => Some(c.to_string()),
+        }
+    }
+
+    /// Raw text of a group, for `\text{...}` and such
+    fn raw_group(&mut self) -> Option<String> {
+        self.skip_spaces();
+        if self.next()? != '{' {
+            return None;
+        }
+
+        let mut depth = 1;
+        let mut out = String::new();
+        while let Some(c) = self.next() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(out);
+                    }
+                }
+                _ => (),
+            }
+            out.push(c);
+        }
+        None
+    }
+
+    fn command(&mut self) -> Option<String> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            // a single symbol, like `\{` or `\,`
+            return match self.next()? {
+                ',' | ':' | ';' | ' ' => Some(" ".to_owned()),
+                '!' => Some(String::new()),
+                '\\' => Some("\n".to_owned()),
+                '|' => Some("‖".to_owned()),
+                c @ ('{' | '}' | '$' | '%' | '&' | '#' | '_') => Some(c.to_string()),
+                _ => None,
+            };
+        }
+
+        let name: String = self.chars[start..self.pos].iter().collect();
+        if let Some(symbol) = symbol(&name) {
+            return Some(symbol.to_owned());
+        }
+
+        match name.as_str() {
+            "frac" | "dfrac" | "tfrac" => {
+                let num = self.argument()?;
+                let den = self.argument()?;
+                Some(format!("{}/{}", wrap(&num), wrap(&den)))
+            }
+            "sqrt" => {
+                self.skip_spaces();
+                let index = if self.peek() == Some('[') {
+                    self.pos += 1;
+                    let end = self.chars[self.pos..].iter().position(|c| *c == ']')?;
+                    let index: String = self.chars[self.pos..self.pos + end].iter().collect();
+                    self.pos += end + 1;
+                    let index = to_unicode(&index)?;
+                    superscript(&index).unwrap_or(index)
+                } else {
+                    String::new()
+                };
+                let arg = self.argument()?;
+                Some(format!("{index}√{}", wrap(&arg)))
+            }
+            "text" | "textrm" | "textit" | "textbf" | "mathrm" | "operatorname" | "mbox" => {
+                self.raw_group()
+            }
+            "mathbf" | "mathit" | "boldsymbol" | "mathsf" | "mathtt" => self.argument(),
+            "mathbb" => {
+                let arg = self.argument()?;
+                Some(arg.chars().map(double_struck).collect())
+            }
+            "hat" | "bar" | "overline" | "vec" | "dot" | "ddot" | "tilde" => {
+                let mark = match name.as_str() {
+                    "hat" => '\u{302}',
+                    "bar" | "overline" => '\u{305}',
+                    "vec" => '\u{20d7}',
+                    "dot" => '\u{307}',
+                    "ddot" => '\u{308}',
+                    _ => '\u{303}',
+                };
+                let arg = self.argument()?;
+                let mut out = String::with_capacity(arg.len() * 2);
+                for c in arg.chars() {
+                    out.push(c);
+                    out.push(mark);
+                }
+                Some(out)
+            }
+            "left" | "right" | "bigl" | "bigr" | "Bigl" | "Bigr" | "big" | "Big" => {
+                self.skip_spaces();
+                match self.next()? {
+                    '.' => Some(String::new()),
+                    '\\' => self.command(),
+                    c => Some(c.to_string()),
+                }
+            }
+            "displaystyle" | "textstyle" | "limits" | "nolimits" => Some(String::new()),
+            "quad" | "qquad" => Some("  ".to_owned()),
+            _ => None,
+        }
+    }
+}
+
+/// `arg` in a superscript or a subscript, in script characters if all of them have one
+fn script(arg: &str, sup: bool) -> String {
+    let scripted = if sup {
+        superscript(arg)
+    } else {
+        subscript(arg)
+    };
+    scripted.unwrap_or_else(|| format!("{}{}", if sup { '^' } else { '_' }, wrap(arg)))
+}
+
+/// `text` in parentheses unless it's a single char or already one
+fn wrap(text: &str) -> String {
+    let already = text.starts_with('(') && text.ends_with(')');
+    if text.chars().count() <= 1 || already || text.chars().all(|c| c.is_ascii_digit()) {
+        text.to_owned()
+    } else {
+        format!("({text})")
+    }
+}
+
+fn superscript(text: &str) -> Option<String> {
+    const FROM: &str = "0123456789+−=()niabcdefghjklmoprstuvwxyzT′∘*";
+    const TO: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹⁺⁻⁼⁽⁾ⁿⁱᵃᵇᶜᵈᵉᶠᵍʰʲᵏˡᵐᵒᵖʳˢᵗᵘᵛʷˣʸᶻᵀ′°*";
+    map_chars(text, FROM, TO)
+}
+
+fn subscript(text: &str) -> Option<String> {
+    const FROM: &str = "0123456789+−=()aehijklmnoprstuvx";
+    const TO: &str = "₀₁₂₃₄₅₆₇₈₉₊₋₌₍₎ₐₑₕᵢⱼₖₗₘₙₒₚᵣₛₜᵤᵥₓ";
+    map_chars(text, FROM, TO)
+}
+
+fn map_chars(text: &str, from: &str, to: &str) -> Option<String> {
+    text.chars()
+        .map(|c| {
+            let idx = from.chars().position(|v| v == c)?;
+            to.chars().nth(idx)
+        })
+        .collect()
+}
+
+fn double_struck(c: char) -> char {
+    match c {
+        'C' => 'ℂ',
+        'H' => 'ℍ',
+        'N' => 'ℕ',
+        'P' => 'ℙ',
+        'Q' => 'ℚ',
+        'R' => 'ℝ',
+        'Z' => 'ℤ',
+        c => c,
+    }
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "rho" => "ρ",
+        "sigma" => "σ",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "iint" => "∬",
+        "oint" => "∮",
+        "pm" => "±",
+        "mp" => "∓",
+        "times" => "×",
+        "div" => "÷",
+        "cdot" => "·",
+        "ast" => "∗",
+        "circ" => "∘",
+        "le" | "leq" => "≤",
+        "ge" | "geq" => "≥",
+        "ne" | "neq" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "leftrightarrow" => "↔",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "emptyset" | "varnothing" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" | "lnot" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "perp" => "⊥",
+        "parallel" => "∥",
+        "angle" => "∠",
+        "degree" => "°",
+        "prime" => "′",
+        "ldots" | "dots" => "…",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "aleph" => "ℵ",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "lvert" | "rvert" | "vert" | "mid" => "|",
+        "lVert" | "rVert" | "Vert" => "‖",
+        "sin" => "sin",
+        "cos" => "cos",
+        "tan" => "tan",
+        "cot" => "cot",
+        "sec" => "sec",
+        "csc" => "csc",
+        "arcsin" => "arcsin",
+        "arccos" => "arccos",
+        "arctan" => "arctan",
+        "sinh" => "sinh",
+        "cosh" => "cosh",
+        "tanh" => "tanh",
+        "log" => "log",
+        "ln" => "ln",
+        "lg" => "lg",
+        "exp" => "exp",
+        "lim" => "lim",
+        "max" => "max",
+        "min" => "min",
+        "sup" => "sup",
+        "inf" => "inf",
+        "det" => "det",
+        "gcd" => "gcd",
+        "deg" => "deg",
+        "dim" => "dim",
+        "ker" => "ker",
+        "arg" => "arg",
+        "mod" | "bmod" => "mod",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_is_found_out_of_code() {
+        assert_eq!(
+            split_math("Area $\\pi r^2$ and\n$$E = mc^2$$\nend"),
+            [
+                Segment::Text("Area "),
+                Segment::Inline("\\pi r^2"),
+                Segment::Text(" and\n"),
+                Segment::Display("E = mc^2"),
+                Segment::Text("\nend"),
+            ]
+        );
+        assert_eq!(
+            split_math("\\(a\\) or \\[b\\]"),
+            [
+                Segment::Inline("a"),
+                Segment::Text(" or "),
+                Segment::Display("b"),
+            ]
+        );
+
+        for text in [
+            "It costs $5 and $10.",
+            "Escaped \\$x\\$ dollars",
+            "Code `$x$` span",
+            "```sh\necho $HOME$\n```",
+            "$ x $ has spaces",
+        ] {
+            assert_eq!(split_math(text), [Segment::Text(text)], "{text}");
+        }
+    }
+
+    #[test]
+    fn latex_to_unicode() {
+        assert_eq!(to_unicode("x^2 + y_i").unwrap(), "x² + yᵢ");
+        assert_eq!(to_unicode("e^{i\\pi} = -1").unwrap(), "e^(iπ) = −1");
+        assert_eq!(to_unicode("x^{-1}").unwrap(), "x⁻¹");
+        assert_eq!(to_unicode("\\frac{a+b}{2}").unwrap(), "(a+b)/2");
+        assert_eq!(to_unicode("\\sqrt{x}\\leq\\sqrt[3]{y}").unwrap(), "√x≤³√y");
+        assert_eq!(to_unicode("\\mathbb{R}^n").unwrap(), "ℝⁿ");
+        assert_eq!(to_unicode("\\text{if } x > 0").unwrap(), "if x > 0");
+        assert_eq!(to_unicode("\\left( a \\right)").unwrap(), "( a )");
+
+        for invalid in ["\\frac{a}", "x^", "{a", "a}", "\\unknown", "a & b"] {
+            assert_eq!(to_unicode(invalid), None, "{invalid}");
+        }
+    }
+
+    #[test]
+    fn inline_math_falls_back_to_code() {
+        assert_eq!(
+            to_markdown("Let $\\alpha_1$ and $\\bad{x}$:"),
+            "Let *α₁* and `\\bad{x}`:"
+        );
+        assert_eq!(to_markdown("plain"), "plain");
+        assert_eq!(to_markdown("$a*b$"), "*a\\*b*");
+        assert_eq!(to_markdown("where $$x^2$$ holds"), "where *x²* holds");
+    }
+
+    #[test]
+    fn display_math_stays_in_its_list_item() {
+        assert_eq!(
+            to_markdown("1. Square\n   $$x^2$$\n2. Next"),
+            "1. Square  \n   *x²*  \n2. Next"
+        );
+        assert_eq!(to_markdown("- $$x^2$$\n- Next"), "- *x²*  \n- Next");
+        assert_eq!(
+            to_markdown("1. Matrix\n\n   $$\n   a & b \\\\\n   c & d\n   $$\n2. Next"),
+            "1. Matrix\n\n   ```latex\n   a & b \\\\\n   c & d\n   ```\n2. Next"
+        );
+        assert_eq!(to_markdown("> $$a & b$$"), "> ```latex\n> a & b\n> ```");
+    }
+}
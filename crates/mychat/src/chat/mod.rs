@@ -2,10 +2,18 @@ pub mod config;
 pub mod dialogue;
 pub mod dialogue_manager;
 pub mod dialogue_task;
+pub mod math;
+pub mod thinking;
+pub mod trim;
+pub mod typing;
+pub mod usage;
+pub mod vault;
 
 use std::fmt::Display;
 
 use serde::{Deserialize, Serialize};
+use thinking::{THINK_END, THINK_START, ThinkingSplitter};
+use trim::TRIMMED_MARKER;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -46,36 +54,51 @@ pub struct Message {
     pub role: Role,
     pub content: String,
     pub thinking_content: Option<String>,
+
+    /// Why the reply stopped, as the API gives it, e.g. `stop` or `length`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_reason: Option<String>,
+
+    /// The untrimmed content, if it's trimmed by [`Message::trim_incomplete_sentence`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_content: Option<String>,
 }
 
 impl Message {
-    fn parse_thinking_content(response: &str) -> (Option<String>, String) {
-        let think_start = "<think>";
-        let think_end = "</think>";
+    pub fn clear(&mut self) {
+        self.content.clear();
+        self.thinking_content = None;
+        self.finish_reason = None;
+        self.full_content = None;
+    }
 
-        if let Some(start_idx) = response.find(think_start) {
-            let content_start = start_idx + think_start.len();
-            if let Some(end_idx) = response[content_start..].find(think_end) {
-                let content_end = content_start + end_idx;
-                let thinking = response[content_start..content_end].to_string();
-                let content = response[content_end + think_end.len()..].to_string();
-                return (Some(thinking), content);
-            }
+    /// Trims the content cut by the token limit back to its last complete sentence,
+    /// see [`trim::trim_incomplete_sentence`]
+    pub fn trim_incomplete_sentence(&mut self) {
+        if self.finish_reason.as_deref() != Some("length") {
+            return;
         }
 
-        (None, response.to_string())
+        if let Some(kept) = trim::trim_incomplete_sentence(&self.content) {
+            let kept = format!("{kept}{TRIMMED_MARKER}");
+            self.full_content = Some(std::mem::replace(&mut self.content, kept));
+        }
     }
 
-    pub fn clear(&mut self) {
-        self.content.clear();
-        self.thinking_content = None;
+    /// Moves `<think>` blocks of the content into the thinking content, see [`ThinkingSplitter`]
+    pub fn split_thinking_content(&mut self) {
+        let content = std::mem::take(&mut self.content);
+        let mut splitter = ThinkingSplitter::default();
+        splitter.push(self, &content);
+        splitter.finish(self);
     }
 
-    pub fn split_thinking_content(&mut self) {
-        if self.thinking_content.is_none() {
-            let (thinking, content) = Message::parse_thinking_content(&self.content);
-            self.content = content;
-            self.thinking_content = thinking;
+    /// Same as [`Message::split_thinking_content`], the thinking content is put back first
+    /// so that edits to either are kept
+    pub fn resplit_thinking_content(&mut self) {
+        if let Some(thinking) = self.thinking_content.take() {
+            self.content = format!("{THINK_START}{thinking}{THINK_END}{}", self.content);
         }
+        self.split_thinking_content();
     }
 }
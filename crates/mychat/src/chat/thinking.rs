@@ -0,0 +1,219 @@
+//! Splits `<think>` blocks out of the message content
+
+use super::Message;
+
+pub const THINK_START: &str = "<think>";
+pub const THINK_END: &str = "</think>";
+
+/// Moves `<think>` blocks of content arriving in chunks into the thinking content
+///
+/// - Several blocks are concatenated, one per line
+/// - An unterminated block takes everything after it, until its close tag arrives
+/// - A tail that may be the start of a tag is held back, so partial tags never show
+/// - A stray close tag moves the content before it into thinking if there is no thinking yet,
+///   as some models don't emit the open tag, otherwise it's dropped
+/// - A nested open tag is dropped
+#[derive(Default)]
+pub struct ThinkingSplitter {
+    in_think: bool,
+    seen_think: bool,
+
+    /// Whitespace right after a close tag isn't content
+    trim_content: bool,
+
+    /// Possibly the start of a tag
+    pending: String,
+}
+
+impl ThinkingSplitter {
+    pub fn push(&mut self, message: &mut Message, chunk: &str) {
+        let mut buf = std::mem::take(&mut self.pending);
+        buf.push_str(chunk);
+        let mut rest = buf.as_str();
+
+        while let Some((idx, tag)) = find_tag(rest) {
+            self.emit(message, &rest[..idx]);
+            rest = &rest[idx + tag.len()..];
+
+            if tag == THINK_START {
+                self.open(message);
+            } else {
+                self.close(message);
+            }
+        }
+
+        let (text, pending) = rest.split_at(rest.len() - partial_tag_len(rest));
+        self.emit(message, text);
+        self.pending = pending.to_owned();
+    }
+
+    /// Flushes the held back tail and resets, call it when the stream ends
+    pub fn finish(&mut self, message: &mut Message) {
+        let pending = std::mem::take(&mut self.pending);
+        self.emit(message, &pending);
+        *self = Self::default();
+    }
+
+    fn open(&mut self, message: &mut Message) {
+        if self.in_think {
+            return;
+        }
+
+        self.in_think = true;
+        if let Some(thinking) = &mut message.thinking_content
+            && !thinking.is_empty()
+            && !thinking.ends_with('\n')
+        {
+            thinking.push('\n');
+        }
+    }
+
+    fn close(&mut self, message: &mut Message) {
+        let no_thinking = message
+            .thinking_content
+            .as_deref()
+            .is_none_or(str::is_empty);
+
+        if self.in_think {
+            self.in_think = false;
+        } else if !self.seen_think && no_thinking {
+            let content = std::mem::take(&mut message.content);
+            push_thinking(message, &content);
+        } else {
+            return;
+        }
+
+        self.seen_think = true;
+        self.trim_content = true;
+    }
+
+    fn emit(&mut self, message: &mut Message, mut text: &str) {
+        if self.in_think {
+            push_thinking(message, text);
+            return;
+        }
+
+        if self.trim_content {
+            text = text.trim_start();
+            self.trim_content = text.is_empty();
+        }
+        message.content.push_str(text);
+    }
+}
+
+fn push_thinking(message: &mut Message, text: &str) {
+    if !text.is_empty() {
+        message
+            .thinking_content
+            .get_or_insert_default()
+            .push_str(text);
+    }
+}
+
+/// The first tag in `text` and its byte index
+fn find_tag(text: &str) -> Option<(usize, &'static str)> {
+    [THINK_START, THINK_END]
+        .into_iter()
+        .filter_map(|tag| text.find(tag).map(|idx| (idx, tag)))
+        .min_by_key(|(idx, _)| *idx)
+}
+
+/// Length of the tail of `text` that may be the start of a tag
+fn partial_tag_len(text: &str) -> usize {
+    text.rfind('<')
+        .map(|idx| &text[idx..])
+        .filter(|tail| THINK_START.starts_with(tail) || THINK_END.starts_with(tail))
+        .map_or(0, str::len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASES: &[(&str, Option<&str>, &str)] = &[
+        ("no tags", None, "no tags"),
+        ("", None, ""),
+        ("<think>a</think>\n\nb", Some("a"), "b"),
+        ("<think>a</think>x<think>b</think>y", Some("a\nb"), "xy"),
+        ("<think>unterminated", Some("unterminated"), ""),
+        ("<think></think>x", None, "x"),
+        ("a</think>b", Some("a"), "b"),
+        ("</think>", None, ""),
+        ("<think>a</think>b</think>c", Some("a"), "bc"),
+        ("<think>a<think>b</think>c", Some("ab"), "c"),
+        ("1 < 2 <thin", None, "1 < 2 <thin"),
+        ("<think>思考</think>回答", Some("思考"), "回答"),
+    ];
+
+    fn split(chunks: &[&str]) -> Message {
+        let mut message = Message::default();
+        let mut splitter = ThinkingSplitter::default();
+        for chunk in chunks {
+            splitter.push(&mut message, chunk);
+        }
+        splitter.finish(&mut message);
+        message
+    }
+
+    #[test]
+    fn whole_input() {
+        for &(input, thinking, content) in CASES {
+            let message = split(&[input]);
+            assert_eq!(message.thinking_content.as_deref(), thinking, "{input:?}");
+            assert_eq!(message.content, content, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn any_chunking_gives_the_same_result() {
+        for &(input, thinking, content) in CASES {
+            let chars: Vec<String> = input.chars().map(String::from).collect();
+            let chars: Vec<&str> = chars.iter().map(String::as_str).collect();
+            let mut chunkings = vec![chars];
+            chunkings.extend(
+                input
+                    .char_indices()
+                    .map(|(idx, _)| vec![&input[..idx], &input[idx..]]),
+            );
+
+            for chunks in chunkings {
+                let message = split(&chunks);
+                assert_eq!(message.thinking_content.as_deref(), thinking, "{chunks:?}");
+                assert_eq!(message.content, content, "{chunks:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn partial_tags_are_held_back() {
+        let mut message = Message::default();
+        let mut splitter = ThinkingSplitter::default();
+
+        splitter.push(&mut message, "hello <thi");
+        assert_eq!(message.content, "hello ");
+
+        splitter.push(&mut message, "nk>hmm</th");
+        assert_eq!(message.content, "hello ");
+        assert_eq!(message.thinking_content.as_deref(), Some("hmm"));
+
+        splitter.push(&mut message, "ink> world");
+        assert_eq!(message.content, "hello world");
+    }
+
+    #[test]
+    fn resplit_keeps_existing_thinking() {
+        let mut message = Message {
+            thinking_content: Some("first".to_owned()),
+            content: "<think>second</think>answer".to_owned(),
+            ..Default::default()
+        };
+
+        message.resplit_thinking_content();
+        assert_eq!(message.thinking_content.as_deref(), Some("first\nsecond"));
+        assert_eq!(message.content, "answer");
+
+        message.resplit_thinking_content();
+        assert_eq!(message.thinking_content.as_deref(), Some("first\nsecond"));
+        assert_eq!(message.content, "answer");
+    }
+}
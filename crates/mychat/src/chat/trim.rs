@@ -0,0 +1,148 @@
+//! Trims a reply cut by the token limit back to its last complete sentence
+
+/// Appended to a trimmed reply
+pub const TRIMMED_MARKER: &str = " …";
+
+/// Ends a sentence if it's followed by whitespace, or ends the text
+const LATIN_TERMINALS: [char; 3] = ['.', '!', '?'];
+
+/// Ends a sentence wherever it is
+const CJK_TERMINALS: [char; 5] = ['。', '！', '？', '…', '；'];
+
+/// Closes a sentence, such as quotes after the terminal
+const CLOSERS: [char; 12] = [
+    '"', '\'', ')', ']', '*', '”', '’', '」', '』', '）', '】', '》',
+];
+
+/// The text up to its last complete sentence if it ends without a terminal,
+/// `None` if it's complete or no sentence is complete
+///
+/// - A sentence ends at a terminal with optional closers after it, or at a line break
+/// - A cut inside a code block is kept as it is, code has no sentences
+pub fn trim_incomplete_sentence(text: &str) -> Option<&str> {
+    let text = text.trim_end();
+    if text.is_empty() || ends_sentence(text) {
+        return None;
+    }
+
+    let end = last_boundary(text)?;
+    let kept = text[..end].trim_end();
+    if kept.is_empty() || kept.matches("```").count() % 2 == 1 {
+        return None;
+    }
+
+    Some(kept)
+}
+
+fn ends_sentence(text: &str) -> bool {
+    let text = text.trim_end_matches(CLOSERS);
+    text.ends_with(LATIN_TERMINALS) || text.ends_with(CJK_TERMINALS)
+}
+
+/// Byte index right after the last sentence in `text`
+fn last_boundary(text: &str) -> Option<usize> {
+    let chars: Vec<_> = text.char_indices().collect();
+    let mut boundary = None;
+
+    for (i, &(idx, c)) in chars.iter().enumerate() {
+        if c == '\n' {
+            boundary = Some(idx);
+            continue;
+        }
+
+        let is_latin = LATIN_TERMINALS.contains(&c);
+        if !is_latin && !CJK_TERMINALS.contains(&c) {
+            continue;
+        }
+
+        // closers right after the terminal belong to the sentence
+        let mut next = i + 1;
+        while chars.get(next).is_some_and(|(_, c)| CLOSERS.contains(c)) {
+            next += 1;
+        }
+
+        let end = chars.get(next).map_or(text.len(), |(idx, _)| *idx);
+        // `3.14` or `example.com` is not the end of a sentence
+        let followed_by_space = chars.get(next).is_none_or(|(_, c)| c.is_whitespace());
+        if !is_latin || followed_by_space {
+            boundary = Some(end);
+        }
+    }
+
+    boundary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_replies_are_kept() {
+        for text in [
+            "",
+            "Done.",
+            "Really?  ",
+            "He said \"stop.\"",
+            "好的。",
+            "真的吗？」",
+            "（完。）",
+            "**Bold.**",
+            "Wait…",
+        ] {
+            assert_eq!(trim_incomplete_sentence(text), None, "{text}");
+        }
+    }
+
+    #[test]
+    fn latin_is_trimmed_to_the_last_sentence() {
+        assert_eq!(
+            trim_incomplete_sentence("First one. Second one is cut"),
+            Some("First one.")
+        );
+        assert_eq!(
+            trim_incomplete_sentence("Is it? \"Yes!\" And then the"),
+            Some("Is it? \"Yes!\"")
+        );
+        assert_eq!(
+            trim_incomplete_sentence("Pi is 3.14 and e is 2.71 which"),
+            None
+        );
+        assert_eq!(
+            trim_incomplete_sentence("See example.com. Then open"),
+            Some("See example.com.")
+        );
+    }
+
+    #[test]
+    fn cjk_is_trimmed_without_spaces() {
+        assert_eq!(
+            trim_incomplete_sentence("今天天气很好。我们去公园散"),
+            Some("今天天气很好。")
+        );
+        assert_eq!(
+            trim_incomplete_sentence("「走吧！」她说着，转身"),
+            Some("「走吧！」")
+        );
+        assert_eq!(
+            trim_incomplete_sentence("それは本当ですか？はい、そう"),
+            Some("それは本当ですか？")
+        );
+        assert_eq!(trim_incomplete_sentence("没有任何标点的句子"), None);
+    }
+
+    #[test]
+    fn line_breaks_and_code_blocks() {
+        assert_eq!(
+            trim_incomplete_sentence("- first item\n- second it"),
+            Some("- first item")
+        );
+        assert_eq!(
+            trim_incomplete_sentence("Code:\n```rust\nlet a = 1;\nlet b"),
+            None
+        );
+        assert_eq!(
+            trim_incomplete_sentence("```\ncode\n```\nAfter the code. And th"),
+            Some("```\ncode\n```\nAfter the code.")
+        );
+    }
+}
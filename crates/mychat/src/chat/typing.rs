@@ -0,0 +1,65 @@
+//! Reveals a streaming reply at a steady rate instead of in the bursts it arrives in
+
+/// Chars revealed per second while the reply keeps up
+pub const CHARS_PER_SEC: f64 = 60.0;
+
+/// The revealed text is never further behind than this, a long backlog is drained faster
+pub const MAX_LAG_SECS: f64 = 2.0;
+
+/// How much of a streaming text is revealed, the text itself is not changed
+#[derive(Default, Clone, Copy)]
+pub struct Typewriter {
+    revealed: f64,
+    last_time: Option<f64>,
+}
+
+impl Typewriter {
+    /// Reveals more of `text` streamed so far as of `time` in seconds, returns the revealed part
+    pub fn advance<'a>(&mut self, text: &'a str, time: f64) -> &'a str {
+        let total = text.chars().count() as f64;
+        let dt = self.last_time.map_or(0.0, |last| (time - last).max(0.0));
+        self.last_time = Some(time);
+
+        let backlog = (total - self.revealed).max(0.0);
+        let rate = CHARS_PER_SEC.max(backlog / MAX_LAG_SECS);
+        self.revealed = (self.revealed + rate * dt).min(total);
+
+        let end = text
+            .char_indices()
+            .nth(self.revealed as usize)
+            .map_or(text.len(), |(idx, _)| idx);
+        &text[..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reveals_at_a_steady_rate() {
+        let mut typewriter = Typewriter::default();
+        let text = "a".repeat(30);
+
+        assert_eq!(typewriter.advance(&text, 1.0), "");
+        assert_eq!(typewriter.advance(&text, 1.125).len(), 7);
+        assert_eq!(typewriter.advance(&text, 1.25).len(), 15);
+        assert_eq!(typewriter.advance(&text, 5.0), text);
+
+        // the clock going back reveals nothing
+        let text = "a".repeat(60);
+        assert_eq!(typewriter.advance(&text, 4.0).len(), 30);
+    }
+
+    #[test]
+    fn long_backlog_is_drained_faster() {
+        let mut typewriter = Typewriter::default();
+        let text = "字".repeat(600);
+
+        typewriter.advance(&text, 0.0);
+        // 600 chars behind are drained within `MAX_LAG_SECS`
+        let revealed = typewriter.advance(&text, 0.5);
+        assert_eq!(revealed.chars().count(), 150);
+        assert!(text.starts_with(revealed));
+    }
+}
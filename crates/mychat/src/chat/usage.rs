@@ -0,0 +1,310 @@
+//! Statistics of the requests sent to the APIs
+
+use eapp_utils::date::SECS_PER_DAY;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::Write,
+    sync::mpsc::{Receiver, Sender, channel},
+    time::{Duration, SystemTime},
+};
+
+/// Filled by the request while it streams
+#[derive(Default)]
+pub struct RequestStats {
+    pub model: String,
+
+    /// Estimated the same way as [`super::dialogue::Dialogue::token_count`]
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UsageEntry {
+    /// Seconds since the epoch when the request finished
+    pub time: u64,
+    pub profile: String,
+    pub model: String,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub duration_ms: u64,
+    pub success: bool,
+}
+
+impl UsageEntry {
+    pub fn new(profile: String, stats: RequestStats, duration: Duration, success: bool) -> Self {
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+
+        Self {
+            time,
+            profile,
+            model: stats.model,
+            prompt_tokens: stats.prompt_tokens,
+            completion_tokens: stats.completion_tokens,
+            duration_ms: duration.as_millis() as u64,
+            success,
+        }
+    }
+
+    /// Days since the epoch, in UTC
+    pub fn day(&self) -> u64 {
+        self.time / SECS_PER_DAY
+    }
+}
+
+/// Requests of a profile in a day
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UsageSummary {
+    pub day: u64,
+    pub profile: String,
+    pub requests: usize,
+    pub errors: usize,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub total_ms: u64,
+}
+
+impl UsageSummary {
+    pub fn avg_latency(&self) -> Duration {
+        Duration::from_millis(self.total_ms / self.requests.max(1) as u64)
+    }
+
+    pub fn error_rate(&self) -> f32 {
+        self.errors as f32 / self.requests.max(1) as f32
+    }
+}
+
+enum WriteCmd {
+    Append(UsageEntry),
+    Clear,
+}
+
+/// The latest [`UsageLog::MAX_ENTRIES`] requests, appended to a file one JSON per line
+///
+/// The file is written on its own thread, so recording never waits for the disk
+pub struct UsageLog {
+    entries: VecDeque<UsageEntry>,
+    writer: Sender<WriteCmd>,
+}
+
+impl UsageLog {
+    const FILENAME: &'static str = "usage_stats.jsonl";
+    pub const MAX_ENTRIES: usize = 10_000;
+
+    pub fn load() -> Self {
+        let path = std::env::current_exe()
+            .map(|exe| exe.join(format!("../{}", Self::FILENAME)))
+            .unwrap_or_else(|_| Self::FILENAME.into());
+
+        let mut entries = VecDeque::new();
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                entries.extend(content.lines().filter_map(|line| {
+                    serde_json::from_str::<UsageEntry>(line)
+                        .inspect_err(|err| log::warn!("Skip bad usage entry: {err}"))
+                        .ok()
+                }));
+                let excess = entries.len().saturating_sub(Self::MAX_ENTRIES);
+                entries.drain(..excess);
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+            Err(err) => log::error!("Error when load usage statistics: {err}"),
+        }
+
+        let (writer, receiver) = channel();
+        let written = entries.clone();
+        std::thread::spawn(move || write_entries(path, written, receiver));
+
+        Self { entries, writer }
+    }
+
+    /// A log which is neither read from nor written to the disk
+    #[cfg(test)]
+    pub fn in_memory() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            writer: channel().0,
+        }
+    }
+
+    pub fn entries(&self) -> &VecDeque<UsageEntry> {
+        &self.entries
+    }
+
+    pub fn record(&mut self, entry: UsageEntry) {
+        if self.entries.len() >= Self::MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry.clone());
+        let _ = self.writer.send(WriteCmd::Append(entry));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        let _ = self.writer.send(WriteCmd::Clear);
+    }
+
+    /// Requests grouped by day and profile, the latest day first
+    pub fn summarize(&self) -> Vec<UsageSummary> {
+        let mut summaries = BTreeMap::<(u64, &str), UsageSummary>::new();
+        for entry in &self.entries {
+            let summary = summaries
+                .entry((entry.day(), &entry.profile))
+                .or_insert_with(|| UsageSummary {
+                    day: entry.day(),
+                    profile: entry.profile.clone(),
+                    ..Default::default()
+                });
+
+            summary.requests += 1;
+            summary.errors += usize::from(!entry.success);
+            summary.prompt_tokens += entry.prompt_tokens;
+            summary.completion_tokens += entry.completion_tokens;
+            summary.total_ms += entry.duration_ms;
+        }
+
+        let mut summaries: Vec<_> = summaries.into_values().collect();
+        summaries.sort_by(|a, b| b.day.cmp(&a.day).then_with(|| a.profile.cmp(&b.profile)));
+        summaries
+    }
+
+    /// Requests of each of the `days` days up to `today`, the oldest first
+    pub fn daily_requests(&self, today: u64, days: usize) -> Vec<usize> {
+        let mut counts = vec![0; days];
+        for entry in &self.entries {
+            if let Some(ago) = today.checked_sub(entry.day())
+                && (ago as usize) < days
+            {
+                counts[days - 1 - ago as usize] += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Days since the epoch of now, in UTC
+pub fn today() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / SECS_PER_DAY)
+}
+
+/// `YYYY-MM-DD` of days since the epoch
+pub fn format_day(day: u64) -> String {
+    let (y, m, d) = eapp_utils::date::civil_from_days(day);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+fn write_entries(
+    path: std::path::PathBuf,
+    mut entries: VecDeque<UsageEntry>,
+    receiver: Receiver<WriteCmd>,
+) {
+    let rewrite = |entries: &VecDeque<UsageEntry>| -> std::io::Result<()> {
+        let mut content = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut content, entry)?;
+            content.push(b'\n');
+        }
+        std::fs::write(&path, content)
+    };
+
+    // Lines in the file, which is compacted once it has a quarter more than kept
+    let mut lines = entries.len();
+
+    for cmd in receiver {
+        let result = match cmd {
+            WriteCmd::Append(entry) => {
+                if entries.len() >= UsageLog::MAX_ENTRIES {
+                    entries.pop_front();
+                }
+                entries.push_back(entry);
+                lines += 1;
+
+                if lines > UsageLog::MAX_ENTRIES + UsageLog::MAX_ENTRIES / 4 {
+                    lines = entries.len();
+                    rewrite(&entries)
+                } else {
+                    std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(&path)
+                        .and_then(|mut file| {
+                            let mut line = serde_json::to_vec(entries.back().unwrap())?;
+                            line.push(b'\n');
+                            file.write_all(&line)
+                        })
+                }
+            }
+            WriteCmd::Clear => {
+                entries.clear();
+                lines = 0;
+                rewrite(&entries)
+            }
+        };
+
+        if let Err(err) = result {
+            log::error!("Error when save usage statistics: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(day: u64, profile: &str, duration_ms: u64, success: bool) -> UsageEntry {
+        UsageEntry {
+            time: day * SECS_PER_DAY + 60,
+            profile: profile.to_owned(),
+            model: "model".to_owned(),
+            prompt_tokens: 100,
+            completion_tokens: 10,
+            duration_ms,
+            success,
+        }
+    }
+
+    fn usage_log(entries: Vec<UsageEntry>) -> UsageLog {
+        UsageLog {
+            entries: entries.into(),
+            writer: channel().0,
+        }
+    }
+
+    #[test]
+    fn summarize_by_day_and_profile() {
+        let log = usage_log(vec![
+            entry(1, "paid", 100, true),
+            entry(2, "paid", 300, false),
+            entry(2, "local", 50, true),
+            entry(2, "paid", 500, true),
+        ]);
+
+        let summaries = log.summarize();
+        let keys: Vec<_> = summaries
+            .iter()
+            .map(|s| (s.day, s.profile.as_str()))
+            .collect();
+        assert_eq!(keys, [(2, "local"), (2, "paid"), (1, "paid")]);
+
+        let paid = &summaries[1];
+        assert_eq!(paid.requests, 2);
+        assert_eq!(paid.prompt_tokens, 200);
+        assert_eq!(paid.completion_tokens, 20);
+        assert_eq!(paid.avg_latency(), Duration::from_millis(400));
+        assert_eq!(paid.error_rate(), 0.5);
+
+        assert_eq!(log.daily_requests(3, 3), [1, 3, 0]);
+        assert_eq!(log.daily_requests(2, 1), [3]);
+    }
+
+    #[test]
+    fn format_days() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(59), "1970-03-01");
+        assert_eq!(format_day(19_782), "2024-02-29");
+    }
+}
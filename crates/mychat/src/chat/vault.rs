@@ -0,0 +1,180 @@
+//! Encryption of the dialogues store at rest
+//!
+//! The encrypted store is `MAGIC | version | salt | nonce | ciphertext`, the key is derived
+//! from the passphrase and the salt with Argon2id, and the header is authenticated along
+//! with the payload by ChaCha20-Poly1305.
+
+use argon2::Argon2;
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng, Payload, rand_core::RngCore},
+};
+
+const MAGIC: &[u8] = b"MYCHAT-VAULT";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+#[derive(Debug)]
+pub enum VaultError {
+    /// The header is cut short
+    Malformed,
+
+    /// Written by a newer version
+    UnsupportedVersion(u8),
+
+    /// Either the passphrase is wrong or the data is tampered with, AEAD can't tell apart
+    Rejected,
+
+    Kdf(argon2::Error),
+}
+
+impl std::fmt::Display for VaultError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultError::Malformed => write!(f, "Encrypted data is malformed"),
+            VaultError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported encryption version {version}")
+            }
+            VaultError::Rejected => write!(f, "Wrong passphrase or damaged data"),
+            VaultError::Kdf(err) => write!(f, "Cannot derive the key: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for VaultError {}
+
+/// Whether `data` is written by [`Vault::encrypt`]
+pub fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(MAGIC)
+}
+
+/// The key derived from a passphrase, kept so that each save doesn't derive it again
+pub struct Vault {
+    salt: [u8; SALT_LEN],
+    key: Key,
+}
+
+impl Vault {
+    /// A vault with a new random salt
+    pub fn new(passphrase: &str) -> Result<Self, VaultError> {
+        let mut salt = [0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self::derive(passphrase, salt)
+    }
+
+    fn derive(passphrase: &str, salt: [u8; SALT_LEN]) -> Result<Self, VaultError> {
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(VaultError::Kdf)?;
+        Ok(Self { salt, key })
+    }
+
+    /// Encrypts `plain` with a new nonce
+    pub fn encrypt(&self, plain: &[u8]) -> Vec<u8> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut data = Vec::with_capacity(HEADER_LEN + plain.len() + 16);
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        data.extend_from_slice(&self.salt);
+        data.extend_from_slice(&nonce);
+
+        let ciphertext = ChaCha20Poly1305::new(&self.key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: plain,
+                    aad: &data,
+                },
+            )
+            .expect("payload should be smaller than the cipher limit");
+        data.extend_from_slice(&ciphertext);
+        data
+    }
+
+    /// Decrypts `data` with `passphrase`, returns the vault to encrypt it again as well
+    pub fn open(data: &[u8], passphrase: &str) -> Result<(Self, Vec<u8>), VaultError> {
+        if data.len() < HEADER_LEN || !is_encrypted(data) {
+            return Err(VaultError::Malformed);
+        }
+
+        let (header, ciphertext) = data.split_at(HEADER_LEN);
+        let version = header[MAGIC.len()];
+        if version != VERSION {
+            return Err(VaultError::UnsupportedVersion(version));
+        }
+
+        let salt_start = MAGIC.len() + 1;
+        let nonce_start = salt_start + SALT_LEN;
+        let salt = header[salt_start..nonce_start].try_into().unwrap();
+        let nonce = Nonce::from_slice(&header[nonce_start..]);
+
+        let vault = Self::derive(passphrase, salt)?;
+        let plain = ChaCha20Poly1305::new(&vault.key)
+            .decrypt(
+                nonce,
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| VaultError::Rejected)?;
+
+        Ok((vault, plain))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let vault = Vault::new("passphrase").unwrap();
+        let data = vault.encrypt(b"secret");
+        assert!(is_encrypted(&data));
+        assert!(!data.windows(6).any(|w| w == b"secret"));
+
+        let (vault, plain) = Vault::open(&data, "passphrase").unwrap();
+        assert_eq!(plain, b"secret");
+
+        // Saved again with the same key, but never the same nonce
+        let again = vault.encrypt(b"secret");
+        assert_ne!(again, data);
+        assert_eq!(Vault::open(&again, "passphrase").unwrap().1, b"secret");
+    }
+
+    #[test]
+    fn wrong_passphrase_and_tampering_are_rejected() {
+        let data = Vault::new("passphrase").unwrap().encrypt(b"secret");
+
+        assert!(matches!(
+            Vault::open(&data, "wrong"),
+            Err(VaultError::Rejected)
+        ));
+
+        for idx in [MAGIC.len() + 1, HEADER_LEN - 1, data.len() - 1] {
+            let mut tampered = data.clone();
+            tampered[idx] ^= 1;
+            assert!(matches!(
+                Vault::open(&tampered, "passphrase"),
+                Err(VaultError::Rejected)
+            ));
+        }
+
+        let mut newer = data.clone();
+        newer[MAGIC.len()] = VERSION + 1;
+        assert!(matches!(
+            Vault::open(&newer, "passphrase"),
+            Err(VaultError::UnsupportedVersion(_))
+        ));
+
+        assert!(matches!(
+            Vault::open(&data[..HEADER_LEN - 1], "passphrase"),
+            Err(VaultError::Malformed)
+        ));
+    }
+}
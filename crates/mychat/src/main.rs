@@ -7,7 +7,7 @@ fn main() {
     let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
     let _guard = rt.enter();
 
-    eapp_utils::setup_loggers("mychat.log").unwrap();
+    eapp_utils::setup_loggers("mychat.log", eapp_utils::LogOptions::from_env()).unwrap();
 
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
@@ -21,6 +21,7 @@ fn main() {
                 ))
                 .unwrap(),
             ),
+        persist_window: false,
         ..Default::default()
     };
 
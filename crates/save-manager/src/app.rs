@@ -1,20 +1,47 @@
-use crate::save_manager::SaveManager;
+use crate::{
+    change_watcher::{ChangeEvent, ChangeWatcher},
+    process_watcher::{ProcessWatcher, WatchEvent},
+    save_manager::{BackupFilter, Change, Conflict, FileChange, Import, Resolution, SaveManager},
+};
 use eapp_utils::{
     borderless,
-    codicons::ICON_FOLDER,
+    codicons::{
+        ICON_CLEAR_ALL, ICON_FOLDER, ICON_PINNED, ICON_SEARCH, ICON_SETTINGS, ICON_TAG, ICON_TARGET,
+    },
     get_body_font_id, get_button_height,
+    global_hotkey::{ChordMap, Code, GlobalHotkeyHandler, KeyMap, Modifiers},
+    persistent_window::PersistentWindow,
+    task::Task,
     ui_font_selector::UiFontSelector,
-    widgets::simple_widgets::{get_theme_button, theme_button},
+    widgets::simple_widgets::{frameless_btn, get_theme_button, theme_button},
+};
+use eframe::egui::{
+    self, Color32, PopupCloseBehavior, UiBuilder, Vec2, collapsing_header::CollapsingState,
 };
-use eframe::egui::{self, Color32, UiBuilder, Vec2, collapsing_header::CollapsingState};
 use serde::{Deserialize, Serialize};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Deserialize, Serialize)]
+pub enum HotKeyAction {
+    /// Back up the save into the active backup
+    #[default]
+    BackupActive,
+
+    /// Restore the active backup
+    RestoreActive,
+}
+
 #[derive(Deserialize, Serialize, Default)]
 #[serde(default)]
 pub struct App {
     manager: SaveManager,
     selector: UiFontSelector,
 
+    /// Back up the current save automatically before restoring
+    backup_before_restore: bool,
+
+    /// Let the restore hotkey run while the watched process is running
+    force_restore: bool,
+
     #[serde(skip)]
     msg: String,
 
@@ -23,6 +50,45 @@ pub struct App {
 
     #[serde(skip)]
     input_dir: String,
+
+    /// Backup to restore and the changes it brings, waiting for confirmation
+    #[serde(skip)]
+    restore_preview: Option<(String, Vec<FileChange>)>,
+
+    #[serde(skip)]
+    filter: BackupFilter,
+
+    /// Backup whose tags are being edited, and the comma separated tags
+    #[serde(skip)]
+    tag_edit: Option<(String, String)>,
+
+    #[serde(skip)]
+    watcher: Option<ProcessWatcher>,
+
+    #[serde(skip)]
+    change_watcher: Option<ChangeWatcher>,
+
+    /// When the save was last seen changing
+    #[serde(skip)]
+    last_change: Option<std::time::SystemTime>,
+
+    /// A change settled without being backed up, asking whether to back it up
+    #[serde(skip)]
+    change_prompt: bool,
+
+    /// Import waiting for conflicts to be resolved
+    #[serde(skip)]
+    pending_import: Option<(Import, Vec<Conflict>)>,
+
+    #[serde(skip)]
+    window: Option<PersistentWindow>,
+
+    #[serde(skip)]
+    handler: GlobalHotkeyHandler<HotKeyAction>,
+
+    /// Backup or restore started by a hotkey, gives the message to show
+    #[serde(skip)]
+    hotkey_task: Option<Task<std::io::Result<String>>>,
 }
 
 impl App {
@@ -39,6 +105,45 @@ impl App {
             this.msg = err.to_string();
         }
 
+        this.handler = eapp_utils::capture_error!(err => {
+            log::error!("Error when load `GlobalHotkeyHandler`: {err}");
+            this.msg = err.to_string();
+            Default::default()
+        },
+        {
+            let mut handler = GlobalHotkeyHandler::<HotKeyAction>::default();
+            handler.create_manager()?;
+
+            let key_map = if let Some(storage) = cc.storage {
+                eframe::get_value(storage, Self::HOTKEYS_KEY).unwrap_or_default()
+            } else {
+                KeyMap::<HotKeyAction>::default()
+            };
+
+            let chord_map = if let Some(storage) = cc.storage {
+                eframe::get_value(storage, ChordMap::<HotKeyAction>::KEY).unwrap_or_default()
+            } else {
+                ChordMap::<HotKeyAction>::default()
+            };
+
+            if key_map.is_empty() && chord_map.is_empty() {
+                let mods = Some(Modifiers::CONTROL | Modifiers::SHIFT);
+                handler.register_hotkey(HotKeyAction::BackupActive, mods, Code::F5)?;
+                handler.register_hotkey(HotKeyAction::RestoreActive, mods, Code::F9)?;
+            } else {
+                for (hotkey, action) in key_map.values() {
+                    handler.register_hotkey(*action, Some(hotkey.mods), hotkey.key)?;
+                }
+                for (chord, action) in chord_map.iter() {
+                    handler.register_chord(*action, chord)?;
+                }
+            }
+
+            handler
+        });
+
+        this.window = Some(PersistentWindow::new(cc));
+        this.restart_watcher(&cc.egui_ctx);
         this.rebuild_fonts(&cc.egui_ctx);
         this.selector.apply_text_style(&cc.egui_ctx);
         this
@@ -46,6 +151,8 @@ impl App {
 }
 
 impl App {
+    const HOTKEYS_KEY: &str = "hotkeys";
+
     fn ui_title_bar(&mut self, ui: &mut egui::Ui, title_bar_rect: egui::Rect) {
         borderless::title_bar(ui, title_bar_rect, |ui| {
             ui.add_space(8.0);
@@ -58,6 +165,10 @@ impl App {
                 self.rebuild_fonts(ui.ctx());
             }
 
+            egui::Popup::menu(&frameless_btn(ui, ICON_SETTINGS.to_string()))
+                .close_behavior(PopupCloseBehavior::CloseOnClickOutside)
+                .show(|ui| self.ui_hotkey_settings(ui));
+
             ui.painter().text(
                 title_bar_rect.center(),
                 egui::Align2::CENTER_CENTER,
@@ -87,6 +198,8 @@ impl App {
                 });
             });
 
+        self.ui_change_prompt(ui);
+
         egui::CentralPanel::default()
             .frame(egui::Frame::central_panel(ui.style()).fill(Color32::TRANSPARENT))
             .show_inside(ui, |ui| {
@@ -104,7 +217,11 @@ impl App {
                         );
                     }
 
-                    res.changed().then(|| self.manager.build_regex_from_str());
+                    // The watchers only look at the files matched by the regex
+                    if res.changed() {
+                        self.manager.build_regex_from_str();
+                        self.restart_watcher(ui.ctx());
+                    }
                 });
 
                 ui.horizontal(|ui| {
@@ -121,6 +238,7 @@ impl App {
                         .show(ui);
                 });
 
+                let ctx = ui.ctx().clone();
                 ui.columns(4, |ui| {
                     macro_rules! btn {
                         ($i:literal, $name:literal, $expr:expr) => {
@@ -136,17 +254,25 @@ impl App {
                         if let Err(err) = self.manager.load_main_save_dir() {
                             self.msg = err.to_string();
                         }
+                        self.restart_watcher(&ctx);
                     });
 
                     btn!(1, "backup", {
-                        if let Err(err) = self.manager.backup(&self.cur_sel_dir) {
-                            self.msg = err.to_string();
+                        match self.manager.backup(&self.cur_sel_dir) {
+                            Ok(pruned) if !pruned.is_empty() => {
+                                self.msg = format!("Moved to trash: {}", pruned.join(", "));
+                            }
+                            Ok(_) => {}
+                            Err(err) => self.msg = err.to_string(),
                         }
                     });
 
                     btn!(2, "restore", {
-                        if let Err(err) = self.manager.restore(&self.cur_sel_dir) {
-                            self.msg = err.to_string();
+                        match self.manager.diff(&self.cur_sel_dir) {
+                            Ok(changes) => {
+                                self.restore_preview = Some((self.cur_sel_dir.clone(), changes))
+                            }
+                            Err(err) => self.msg = err.to_string(),
                         }
                     });
 
@@ -154,12 +280,139 @@ impl App {
                         if let Err(err) = self.manager.save_regex() {
                             self.msg = err.to_string();
                         }
+                        self.restart_watcher(&ctx);
                     });
                 });
 
                 ui.separator();
 
+                let to_prune = self.manager.backups_to_prune();
+
+                ui.horizontal(|ui| {
+                    let mut changed = false;
+                    ui.label("keep last");
+                    changed |= ui_optional_value(ui, &mut self.manager.profile.keep_last, 10);
+                    ui.label("keep days");
+                    changed |= ui_optional_value(ui, &mut self.manager.profile.keep_days, 30);
+
+                    if changed && let Err(err) = self.manager.save_profile() {
+                        self.msg = err.to_string();
+                    }
+
+                    ui.label(format!(
+                        "{} backups, {} to prune",
+                        self.manager.save_dirs.len(),
+                        to_prune.len()
+                    ));
+
+                    if ui
+                        .add_enabled(!to_prune.is_empty(), egui::Button::new("prune"))
+                        .on_hover_text("Move backups exceeding the limits to trash")
+                        .clicked()
+                        && let Err(err) = self.manager.prune()
+                    {
+                        self.msg = err.to_string();
+                    }
+                });
+
                 ui.horizontal(|ui| {
+                    if ui
+                        .button("export…")
+                        .on_hover_text("Pack settings and all backups into a zip archive")
+                        .clicked()
+                        && let Some(path) = rfd::FileDialog::new()
+                            .add_filter("zip", &["zip"])
+                            .set_file_name("save-manager-export.zip")
+                            .save_file()
+                    {
+                        self.msg = match self.manager.export(&path) {
+                            Ok(_) => format!("Exported to '{}'", path.to_string_lossy()),
+                            Err(err) => err.to_string(),
+                        };
+                    }
+
+                    if ui
+                        .button("import…")
+                        .on_hover_text("Merge an exported archive into this save directory")
+                        .clicked()
+                        && let Some(path) = rfd::FileDialog::new()
+                            .add_filter("zip", &["zip"])
+                            .pick_file()
+                    {
+                        self.start_import(ui.ctx(), &path);
+                    }
+
+                    ui.separator();
+
+                    ui.label("backup on exit of");
+
+                    let response =
+                        egui::TextEdit::singleline(&mut self.manager.profile.watch_process)
+                            .hint_text("game.exe")
+                            .desired_width(160.0)
+                            .show(ui)
+                            .response
+                            .on_hover_text("Back up automatically when this process exits");
+
+                    if response.lost_focus() {
+                        if let Err(err) = self.manager.save_profile() {
+                            self.msg = err.to_string();
+                        }
+                        self.restart_watcher(ui.ctx());
+                    }
+
+                    if let Some(watcher) = &self.watcher {
+                        ui.weak(if watcher.is_finished() {
+                            "stopped"
+                        } else if watcher.is_running() {
+                            "running"
+                        } else {
+                            "not running"
+                        });
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    let profile = &mut self.manager.profile;
+                    let mut changed = ui
+                        .checkbox(&mut profile.watch_changes, "watch changes")
+                        .on_hover_text("Offer a backup once the game writes the save")
+                        .changed();
+                    changed |= ui
+                        .add_enabled(
+                            profile.watch_changes,
+                            egui::Checkbox::new(&mut profile.backup_on_change, "back up on change"),
+                        )
+                        .on_hover_text("Back up into a new backup without asking")
+                        .changed();
+
+                    if changed {
+                        if let Err(err) = self.manager.save_profile() {
+                            self.msg = err.to_string();
+                        }
+                        self.restart_watcher(ui.ctx());
+                    }
+
+                    if let Some(watcher) = &self.change_watcher {
+                        if watcher.is_finished() {
+                            ui.weak("stopped");
+                        } else if let Some(time) = self.last_change {
+                            ui.weak(format!("last change detected {}", format_age(time)));
+                        } else {
+                            ui.weak("no change detected");
+                        }
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .checkbox(&mut self.manager.compress, "zip")
+                        .on_hover_text("Store backups as a compressed zip archive")
+                        .changed()
+                    {
+                        self.restart_watcher(ui.ctx());
+                    }
+
                     if ui.button("add").clicked()
                         && !self.input_dir.is_empty()
                         && let Err(err) = self.manager.add(self.input_dir.clone())
@@ -177,20 +430,140 @@ impl App {
                         .show(ui);
                 });
 
+                ui.horizontal(|ui| {
+                    ui.label(ICON_SEARCH.to_string());
+                    ui.label("newer than");
+                    ui_optional_value(ui, &mut self.filter.newer_than_days, 7);
+                    ui.label("older than");
+                    ui_optional_value(ui, &mut self.filter.older_than_days, 7);
+                    ui.label("days");
+
+                    if ui
+                        .add_enabled(
+                            !self.filter.is_empty(),
+                            egui::Button::new(ICON_CLEAR_ALL.to_string()).frame(false),
+                        )
+                        .on_hover_text("Clear the filter")
+                        .clicked()
+                    {
+                        self.filter = BackupFilter::default();
+                    }
+
+                    egui::TextEdit::singleline(&mut self.filter.query)
+                        .hint_text("name or #tag")
+                        .desired_width(f32::INFINITY)
+                        .show(ui);
+                });
+
                 ui.columns(2, |ui| {
                     egui::ScrollArea::both()
                         .id_salt("scroll_left")
                         .auto_shrink([false, false])
                         .show(&mut ui[0], |ui| {
-                            for (dir, items) in self.manager.save_dirs.iter() {
-                                let id = ui.make_persistent_id(dir);
+                            let mut pin_to_toggle = None;
+                            let mut target_to_toggle = None;
+                            let mut tags_to_set = None;
+
+                            for dir in self.manager.filtered_backups(&self.filter) {
+                                let id = ui.make_persistent_id(&dir);
+                                let backup = &self.manager.save_dirs[&dir];
+                                let items = &backup.items;
                                 CollapsingState::load_with_default_open(ui.ctx(), id, false)
                                     .show_header(ui, |ui| {
+                                        let pinned = self.manager.profile.pinned.contains(&dir);
+                                        if ui
+                                            .selectable_label(pinned, ICON_PINNED.to_string())
+                                            .on_hover_text("Exempt from pruning")
+                                            .clicked()
+                                        {
+                                            pin_to_toggle = Some(dir.clone());
+                                        }
+
+                                        let will_prune = to_prune.contains(&dir);
+                                        let mut text = egui::RichText::new(&dir);
+                                        if will_prune {
+                                            text = text.strikethrough();
+                                        }
+
+                                        let mut response =
+                                            ui.selectable_label(self.cur_sel_dir == dir, text);
+                                        if will_prune {
+                                            response = response.on_hover_text("Will be pruned");
+                                        }
+
+                                        if response.clicked() {
+                                            self.cur_sel_dir = dir.clone();
+                                        }
+
+                                        let targeted = self
+                                            .manager
+                                            .profile
+                                            .hotkey_target
+                                            .as_ref()
+                                            .is_some_and(|target| *target == dir);
                                         if ui
-                                            .selectable_label(self.cur_sel_dir == *dir, dir)
+                                            .selectable_label(targeted, ICON_TARGET.to_string())
+                                            .on_hover_text("Target of the global hotkeys")
                                             .clicked()
                                         {
-                                            self.cur_sel_dir = dir.to_string();
+                                            target_to_toggle = Some(dir.clone());
+                                        }
+
+                                        if let Some(modified) = backup.modified {
+                                            ui.weak(format_age(modified));
+                                        }
+
+                                        if let Some(size) = backup.archive_size {
+                                            ui.weak(format!(
+                                                "zip {}",
+                                                eapp_utils::format_size(size)
+                                            ));
+                                        }
+
+                                        let editing = self
+                                            .tag_edit
+                                            .as_ref()
+                                            .is_some_and(|(name, _)| *name == dir);
+
+                                        if ui
+                                            .selectable_label(editing, ICON_TAG.to_string())
+                                            .on_hover_text("Edit tags")
+                                            .clicked()
+                                        {
+                                            if editing {
+                                                tags_to_set = self.tag_edit.take();
+                                            } else {
+                                                let tags: Vec<_> =
+                                                    self.manager.tags(&dir).cloned().collect();
+                                                self.tag_edit =
+                                                    Some((dir.clone(), tags.join(", ")));
+                                            }
+                                        }
+
+                                        if let Some((_, text)) = &mut self.tag_edit
+                                            && editing
+                                        {
+                                            let response = ui.add(
+                                                egui::TextEdit::singleline(text)
+                                                    .hint_text("tags, comma separated")
+                                                    .desired_width(160.0),
+                                            );
+
+                                            if response.lost_focus() {
+                                                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                                    self.tag_edit = None;
+                                                } else {
+                                                    tags_to_set = self.tag_edit.take();
+                                                }
+                                            }
+                                        } else {
+                                            for tag in self.manager.tags(&dir) {
+                                                ui.label(
+                                                    egui::RichText::new(format!("#{tag}"))
+                                                        .small()
+                                                        .weak(),
+                                                );
+                                            }
                                         }
                                     })
                                     .body(|ui| {
@@ -204,6 +577,32 @@ impl App {
                                             })
                                     });
                             }
+
+                            if let Some((dir, tags)) = tags_to_set
+                                && let Err(err) = self.manager.set_tags(&dir, &tags)
+                            {
+                                self.msg = err.to_string();
+                            }
+
+                            if let Some(dir) = pin_to_toggle {
+                                let pinned = &mut self.manager.profile.pinned;
+                                if !pinned.remove(&dir) {
+                                    pinned.insert(dir);
+                                }
+
+                                if let Err(err) = self.manager.save_profile() {
+                                    self.msg = err.to_string();
+                                }
+                            }
+
+                            if let Some(dir) = target_to_toggle {
+                                let target = &mut self.manager.profile.hotkey_target;
+                                *target = (target.as_ref() != Some(&dir)).then_some(dir);
+
+                                if let Err(err) = self.manager.save_profile() {
+                                    self.msg = err.to_string();
+                                }
+                            }
                         });
 
                     let row_height = ui[1].text_style_height(&egui::TextStyle::Body);
@@ -235,12 +634,494 @@ impl App {
             });
     }
 
+    fn restart_watcher(&mut self, ctx: &egui::Context) {
+        self.watcher = None;
+        self.change_watcher = None;
+        self.last_change = None;
+        self.change_prompt = false;
+
+        if self.manager.profile.watch_changes && !self.manager.main_save_dir.is_empty() {
+            self.change_watcher = Some(ChangeWatcher::spawn(ctx.clone(), self.manager.detached()));
+        }
+
+        let exe_name = self.manager.profile.watch_process.trim();
+        if exe_name.is_empty() || self.manager.main_save_dir.is_empty() {
+            return;
+        }
+
+        self.watcher = Some(ProcessWatcher::spawn(ctx.clone(), exe_name.to_owned()));
+    }
+
+    fn poll_watcher_events(&mut self) {
+        self.poll_change_events();
+
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+
+        let mut exited = false;
+        for event in watcher.poll_events() {
+            match event {
+                WatchEvent::Exited => exited = true,
+                WatchEvent::Error(err) => self.msg = err,
+            }
+        }
+
+        // Same as settled changes, the watcher only reports it
+        if exited {
+            let msg = format!("'{}' exited, backed up into", watcher.exe_name());
+            self.backup_new_with_msg("exit", &msg);
+        }
+    }
+
+    fn poll_change_events(&mut self) {
+        let Some(watcher) = &self.change_watcher else {
+            return;
+        };
+
+        // A restore in the background rewrites the save, which is not a change by the game
+        watcher.set_suspended(self.hotkey_task.is_some());
+
+        let mut settled = false;
+        for event in watcher.poll_events() {
+            match event {
+                ChangeEvent::Changed(time) => self.last_change = Some(time),
+                ChangeEvent::Settled => settled = true,
+                ChangeEvent::Error(err) => self.msg = err,
+            }
+        }
+
+        // Backed up here rather than by the watcher, only this manager writes the profile
+        if settled {
+            if self.manager.profile.backup_on_change {
+                self.backup_new_with_msg("change", "Save changed, backed up into");
+            } else {
+                self.change_prompt = true;
+            }
+        }
+    }
+
+    /// Backs up into a new backup named after `prefix`, the result is shown after `msg`
+    fn backup_new_with_msg(&mut self, prefix: &str, msg: &str) {
+        match self.manager.backup_new(prefix) {
+            Ok((name, pruned)) => {
+                self.msg = format!("{msg} '{name}'");
+                if !pruned.is_empty() {
+                    self.msg += &format!(", moved to trash: {}", pruned.join(", "));
+                }
+            }
+            Err(err) => self.msg = err.to_string(),
+        }
+    }
+
+    fn ui_change_prompt(&mut self, ui: &mut egui::Ui) {
+        egui::TopBottomPanel::bottom("change_prompt_panel")
+            .exact_height(get_button_height(ui) + 16.0)
+            .frame(egui::Frame::side_top_panel(ui.style()).fill(Color32::TRANSPARENT))
+            .show_animated_inside(ui, self.change_prompt, |ui| {
+                ui.horizontal_centered(|ui| {
+                    ui.label("Save data changed — back up now?");
+
+                    if ui.button("back up").clicked() {
+                        self.change_prompt = false;
+                        self.backup_new_with_msg("change", "Backed up into");
+                    }
+
+                    if ui.button("dismiss").clicked() {
+                        self.change_prompt = false;
+                    }
+                });
+            });
+    }
+
+    fn ui_hotkey_settings(&mut self, ui: &mut egui::Ui) {
+        if !self.handler.is_ok() {
+            ui.label("HotKeys unable to work");
+            return;
+        }
+
+        ui.vertical_centered(|ui| ui.heading("HotKeys"));
+        if let Err(err) = self.handler.ui(ui) {
+            self.msg = err.to_string();
+        }
+
+        ui.separator();
+        ui.checkbox(&mut self.force_restore, "Restore while the game is running")
+            .on_hover_text(
+                "Otherwise the restore hotkey is refused while the watched process is running",
+            );
+    }
+
+    /// Backup the hotkeys act on
+    fn active_backup(&self) -> Option<String> {
+        self.manager
+            .profile
+            .hotkey_target
+            .as_ref()
+            .filter(|target| self.manager.save_dirs.contains_key(*target))
+            .or(Some(&self.cur_sel_dir).filter(|dir| !dir.is_empty()))
+            .cloned()
+    }
+
+    fn poll_global_hotkey_events(&mut self, ctx: &egui::Context) {
+        if let Some(task) = &self.hotkey_task
+            && task.is_finished()
+        {
+            let result = self.hotkey_task.take().unwrap().get_result();
+            match result {
+                Ok(Ok(msg)) => {
+                    log::info!("{msg}");
+                    self.msg = msg;
+                    eapp_utils::platform::beep();
+                }
+                Ok(Err(err)) => {
+                    log::error!("hotkey action fails: {err}");
+                    self.msg = err.to_string();
+                }
+                Err(_) => self.msg = "Hotkey action panicked".to_owned(),
+            }
+
+            if let Err(err) = self.manager.load_main_save_dir() {
+                self.msg = err.to_string();
+            }
+        }
+
+        if !self.handler.is_ok() {
+            return;
+        }
+
+        ctx.request_repaint_after_secs(1.0);
+        for action in self.handler.poll_events() {
+            self.run_hotkey_action(ctx, action);
+        }
+    }
+
+    /// Runs `action` on the active backup in the background
+    fn run_hotkey_action(&mut self, ctx: &egui::Context, action: HotKeyAction) {
+        if self.hotkey_task.is_some() {
+            log::warn!("{action:?} is ignored, the last one is still running");
+            return;
+        }
+
+        let Some(name) = self.active_backup() else {
+            log::warn!("{action:?} is ignored, no backup is selected or targeted");
+            self.msg = "Select a backup or mark one as the hotkey target first".to_owned();
+            return;
+        };
+
+        let exe_name = self.manager.profile.watch_process.trim();
+        if action == HotKeyAction::RestoreActive && !self.force_restore && !exe_name.is_empty() {
+            // The game may be running if it can't be told, its saves must not be overwritten
+            match eapp_utils::platform::is_process_running(exe_name) {
+                Ok(false) => (),
+                Ok(true) => {
+                    log::warn!("restore of '{name}' is refused, '{exe_name}' is running");
+                    self.msg = format!("Refused to restore '{name}' while '{exe_name}' is running");
+                    return;
+                }
+                Err(err) => {
+                    log::warn!(
+                        "restore of '{name}' is refused, unable to check '{exe_name}': {err}"
+                    );
+                    self.msg = format!(
+                        "Refused to restore '{name}', unable to check whether '{exe_name}' is running: {err}"
+                    );
+                    return;
+                }
+            }
+        }
+
+        let mut manager = self.manager.detached();
+        let backup_first = self.backup_before_restore;
+        let ctx = ctx.clone();
+        let (cancel_sender, cancel_receiver) = std::sync::mpsc::channel();
+
+        self.hotkey_task = Some(Task::new(cancel_sender, move || {
+            // Reload first, the backup may be changed since the last time
+            let result = manager.load_main_save_dir().and_then(|_| match action {
+                HotKeyAction::BackupActive => {
+                    let pruned = manager.backup(&name)?;
+                    let mut msg = format!("Backed up into '{name}'");
+                    if !pruned.is_empty() {
+                        msg += &format!(", moved to trash: {}", pruned.join(", "));
+                    }
+                    Ok(msg)
+                }
+                HotKeyAction::RestoreActive => {
+                    // Nothing is written yet if it's cancelled meanwhile, e.g. the app exits
+                    if cancel_receiver.try_recv().is_ok() {
+                        return Ok(format!("Restore of '{name}' is cancelled"));
+                    }
+
+                    let pruned = manager.restore_with_backup(&name, backup_first)?;
+                    let mut msg = format!("Restored '{name}'");
+                    if !pruned.is_empty() {
+                        msg += &format!(", moved to trash: {}", pruned.join(", "));
+                    }
+                    Ok(msg)
+                }
+            });
+
+            ctx.request_repaint();
+            result
+        }));
+    }
+
+    fn start_import(&mut self, ctx: &egui::Context, path: &std::path::Path) {
+        let mut import = match SaveManager::read_import(path) {
+            Ok(import) => import,
+            Err(err) => {
+                self.msg = err.to_string();
+                return;
+            }
+        };
+
+        if import.save_dir.is_none() {
+            import.save_dir = rfd::FileDialog::new()
+                .set_title(format!(
+                    "'{}' does not exist, pick the save directory to import into",
+                    import.source_dir()
+                ))
+                .pick_folder()
+                .map(|dir| dir.to_string_lossy().into_owned());
+        }
+
+        let Some(save_dir) = import.save_dir.clone() else {
+            return;
+        };
+
+        // Conflicts are found on a copy, the directory is only switched once it's imported
+        let mut target = self.manager.detached();
+        target.main_save_dir = save_dir;
+        let result = target
+            .load_main_save_dir()
+            .and_then(|_| target.import_conflicts(&import));
+
+        match result {
+            Ok(conflicts) if conflicts.is_empty() => self.finish_import(ctx, &import, &conflicts),
+            Ok(conflicts) => self.pending_import = Some((import, conflicts)),
+            Err(err) => self.msg = err.to_string(),
+        }
+    }
+
+    /// Switches to the save directory of `import` and imports into it
+    fn finish_import(&mut self, ctx: &egui::Context, import: &Import, conflicts: &[Conflict]) {
+        let Some(save_dir) = import.save_dir.clone() else {
+            return;
+        };
+
+        if save_dir != self.manager.main_save_dir {
+            self.manager.main_save_dir = save_dir;
+            let result = self.manager.load_main_save_dir();
+            self.restart_watcher(ctx);
+            if let Err(err) = result {
+                self.msg = err.to_string();
+                return;
+            }
+        }
+
+        self.msg = match self.manager.import(import, conflicts) {
+            Ok(summary) => format!(
+                "Imported {} of {} backups, {} skipped",
+                summary.added.len(),
+                import.backup_count(),
+                summary.skipped
+            ),
+            Err(err) => err.to_string(),
+        };
+    }
+
+    fn ui_import_modal(&mut self, ui: &mut egui::Ui) {
+        let Some((_, conflicts)) = &mut self.pending_import else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut closed = false;
+
+        let response = egui::Modal::new(egui::Id::new("confirm_import")).show(ui.ctx(), |ui| {
+            ui.heading("Backups with conflicting names");
+            ui.label("These backups differ from local ones with the same name");
+
+            egui::ScrollArea::vertical()
+                .max_height(240.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for Conflict { name, resolution } in conflicts.iter_mut() {
+                        ui.horizontal(|ui| {
+                            ui.label(name.as_str());
+
+                            let renaming = matches!(resolution, Resolution::Rename(_));
+                            if ui.radio(renaming, "rename").clicked() && !renaming {
+                                *resolution = Resolution::Rename(format!("{name}-imported"));
+                            }
+
+                            if ui
+                                .radio(!renaming, "merge")
+                                .on_hover_text("Keep whichever is newer")
+                                .clicked()
+                            {
+                                *resolution = Resolution::Merge;
+                            }
+
+                            if let Resolution::Rename(new_name) = resolution {
+                                egui::TextEdit::singleline(new_name)
+                                    .desired_width(f32::INFINITY)
+                                    .show(ui);
+                            }
+                        });
+                    }
+                });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                confirmed = ui.button("import").clicked();
+                closed = ui.button("cancel").clicked();
+            });
+        });
+        closed |= response.should_close();
+
+        if confirmed {
+            let (import, conflicts) = self.pending_import.take().unwrap();
+            self.finish_import(ui.ctx(), &import, &conflicts);
+        } else if closed {
+            self.pending_import = None;
+        }
+    }
+
+    fn ui_restore_modal(&mut self, ui: &mut egui::Ui) {
+        let Some((name, changes)) = &self.restore_preview else {
+            return;
+        };
+
+        let mut confirmed = false;
+        let mut closed = false;
+
+        let response = egui::Modal::new(egui::Id::new("confirm_restore")).show(ui.ctx(), |ui| {
+            ui.heading(format!("Restore '{name}'?"));
+
+            if changes.is_empty() {
+                ui.label("The current save is identical to this backup");
+            } else {
+                ui.label(format!("{} files will be changed", changes.len()));
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        for FileChange { name, change } in changes {
+                            let (mark, color, hint) = match change {
+                                Change::Added => ("+", Color32::GREEN, "Restored from backup"),
+                                Change::Removed => ("-", Color32::RED, "Removed from current save"),
+                                Change::Modified => {
+                                    ("~", Color32::YELLOW, "Reverted to the backup version")
+                                }
+                            };
+
+                            ui.horizontal(|ui| {
+                                ui.colored_label(color, mark);
+                                ui.label(name);
+                            })
+                            .response
+                            .on_hover_text(hint);
+                        }
+                    });
+            }
+
+            ui.separator();
+
+            let is_auto_backup = name == SaveManager::AUTO_BACKUP_NAME;
+            ui.add_enabled(
+                !is_auto_backup,
+                egui::Checkbox::new(
+                    &mut self.backup_before_restore,
+                    format!(
+                        "Back up current save to '{}' first",
+                        SaveManager::AUTO_BACKUP_NAME
+                    ),
+                ),
+            );
+
+            ui.horizontal(|ui| {
+                confirmed = ui.button("restore").clicked();
+                closed = ui.button("cancel").clicked();
+            });
+        });
+        closed |= response.should_close();
+
+        if confirmed {
+            let (name, _) = self.restore_preview.take().unwrap();
+            if let Err(err) = self.restore(&name) {
+                self.msg = err.to_string();
+            }
+        } else if closed {
+            self.restore_preview = None;
+        }
+    }
+
+    fn restore(&mut self, name: &str) -> std::io::Result<()> {
+        // The restore rewrites the save, which is not a change by the game
+        if let Some(watcher) = &self.change_watcher {
+            watcher.set_suspended(true);
+        }
+
+        let result = self
+            .manager
+            .restore_with_backup(name, self.backup_before_restore);
+
+        if let Some(watcher) = &self.change_watcher {
+            watcher.set_suspended(false);
+            for event in watcher.poll_events() {
+                if let ChangeEvent::Error(err) = event {
+                    self.msg = err;
+                }
+            }
+        }
+        self.change_prompt = false;
+
+        let pruned = result?;
+        if !pruned.is_empty() {
+            self.msg = format!("Moved to trash: {}", pruned.join(", "));
+        }
+        Ok(())
+    }
+
     fn rebuild_fonts(&mut self, ctx: &egui::Context) {
         let fonts = self.selector.insert_font(eapp_utils::get_default_fonts());
         ctx.set_fonts(fonts);
     }
 }
 
+fn ui_optional_value<T: egui::emath::Numeric>(
+    ui: &mut egui::Ui,
+    value: &mut Option<T>,
+    default: T,
+) -> bool {
+    let mut enabled = value.is_some();
+    let mut changed = ui.checkbox(&mut enabled, "").changed();
+    if changed {
+        *value = enabled.then_some(default);
+    }
+
+    if let Some(value) = value {
+        changed |= ui
+            .add(egui::DragValue::new(value).range(T::from_f64(1.0)..=T::MAX))
+            .changed();
+    }
+
+    changed
+}
+
+fn format_age(time: std::time::SystemTime) -> String {
+    let secs = time.elapsed().map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+    match secs {
+        0..60 => "just now".to_owned(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
 impl eframe::App for App {
     fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
         egui::Rgba::TRANSPARENT.to_array()
@@ -249,6 +1130,8 @@ impl eframe::App for App {
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
         borderless::window_frame(ctx, Some(ctx.style().visuals.window_fill)).show(ctx, |ui| {
             borderless::handle_resize(ui);
+            self.poll_watcher_events();
+            self.poll_global_hotkey_events(ui.ctx());
 
             let app_rect = ui.max_rect();
 
@@ -267,6 +1150,8 @@ impl eframe::App for App {
             .shrink2(Vec2::new(1.5, 1.0));
 
             self.ui_title_bar(ui, title_bar_rect);
+            self.ui_restore_modal(ui);
+            self.ui_import_modal(ui);
 
             self.ui_contents(
                 &mut ui.new_child(UiBuilder::new().layout(*ui.layout()).max_rect(content_rect)),
@@ -274,7 +1159,22 @@ impl eframe::App for App {
         });
     }
 
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if let Some(task) = &self.hotkey_task {
+            task.cancel();
+        }
+    }
+
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, self);
+        eframe::set_value(storage, Self::HOTKEYS_KEY, self.handler.get_key_map());
+        eframe::set_value(
+            storage,
+            ChordMap::<HotKeyAction>::KEY,
+            self.handler.get_chord_map(),
+        );
+        if let Some(window) = &self.window {
+            window.save(storage);
+        }
     }
 }
@@ -0,0 +1,130 @@
+use crate::save_manager::{DirState, SaveManager};
+use eapp_utils::task::Task;
+use eframe::egui;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, RecvTimeoutError, channel},
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+pub enum ChangeEvent {
+    /// The save is being written, more changes may follow
+    Changed(SystemTime),
+
+    /// No change for a while since the last one, the save is written completely
+    Settled,
+
+    Error(String),
+}
+
+/// Polls the state of the save on its own thread, so that the game writing a save is noticed,
+/// a backup is only offered or made by the app once the writes settle to not take
+/// a half written save
+pub struct ChangeWatcher {
+    suspended: Arc<AtomicBool>,
+    rebaseline: Arc<AtomicBool>,
+    receiver: Receiver<ChangeEvent>,
+    task: Task<()>,
+}
+
+impl ChangeWatcher {
+    const INTERVAL: Duration = Duration::from_secs(3);
+
+    /// Time without changes for a change to settle
+    const SETTLE: Duration = Duration::from_secs(10);
+
+    /// `manager` should be a copy made by [`SaveManager::detached`], it's only used to read
+    /// the state of the save, restart the watcher if the save files are changed
+    pub fn spawn(ctx: egui::Context, manager: SaveManager) -> Self {
+        let (cancel_sender, cancel_receiver) = channel();
+        let (sender, receiver) = channel();
+        let suspended = Arc::new(AtomicBool::new(false));
+        let rebaseline = Arc::new(AtomicBool::new(false));
+
+        let task = {
+            let suspended = suspended.clone();
+            let rebaseline = rebaseline.clone();
+            Task::new(cancel_sender, move || {
+                let send = |event: ChangeEvent| {
+                    let _ = sender.send(event);
+                    ctx.request_repaint();
+                };
+
+                let mut baseline: Option<DirState> = None;
+                let mut last_change: Option<Instant> = None;
+
+                while let Err(RecvTimeoutError::Timeout) =
+                    cancel_receiver.recv_timeout(Self::INTERVAL)
+                {
+                    // Changes made by our own restore are not the game's
+                    if rebaseline.swap(false, Ordering::SeqCst) || suspended.load(Ordering::SeqCst)
+                    {
+                        baseline = None;
+                        last_change = None;
+                        continue;
+                    }
+
+                    let state = match manager.dir_state() {
+                        Ok(state) => state,
+                        Err(err) => {
+                            send(ChangeEvent::Error(err.to_string()));
+                            break;
+                        }
+                    };
+
+                    match baseline {
+                        None => baseline = Some(state),
+                        Some(old) if old != state => {
+                            baseline = Some(state);
+                            last_change = Some(Instant::now());
+                            send(ChangeEvent::Changed(SystemTime::now()));
+                        }
+                        Some(_) => (),
+                    }
+
+                    if last_change.is_some_and(|time| time.elapsed() >= Self::SETTLE) {
+                        last_change = None;
+                        send(ChangeEvent::Settled);
+                    }
+                }
+            })
+        };
+
+        Self {
+            suspended,
+            rebaseline,
+            receiver,
+            task,
+        }
+    }
+
+    /// Stops watching while the save is restored, the state after it is taken as unchanged
+    pub fn set_suspended(&self, suspended: bool) {
+        let was_suspended = self.suspended.swap(suspended, Ordering::SeqCst);
+        if was_suspended && !suspended {
+            self.rebaseline();
+        }
+    }
+
+    /// Takes the current state as unchanged, after the save is restored by the app
+    pub fn rebaseline(&self) {
+        self.rebaseline.store(true, Ordering::SeqCst);
+    }
+
+    pub fn poll_events(&self) -> impl Iterator<Item = ChangeEvent> + '_ {
+        self.receiver.try_iter()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for ChangeWatcher {
+    fn drop(&mut self) {
+        self.task.cancel();
+    }
+}
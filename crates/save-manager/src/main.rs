@@ -1,10 +1,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 pub(crate) mod app;
+pub(crate) mod change_watcher;
+pub(crate) mod process_watcher;
 pub(crate) mod save_manager;
 
 fn main() {
-    eapp_utils::setup_loggers("save-manager.log").unwrap();
+    eapp_utils::setup_loggers("save-manager.log", eapp_utils::LogOptions::from_env()).unwrap();
 
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
@@ -18,6 +20,7 @@ fn main() {
                 ))
                 .unwrap(),
             ),
+        persist_window: false,
         ..Default::default()
     };
 
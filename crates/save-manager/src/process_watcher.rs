@@ -0,0 +1,96 @@
+use eapp_utils::task::Task;
+use eframe::egui;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, RecvTimeoutError, channel},
+    },
+    time::Duration,
+};
+
+pub enum WatchEvent {
+    /// The watched process exited, the save is to be backed up
+    Exited,
+    Error(String),
+}
+
+/// Polls the process list on its own thread, so that it keeps working while the app is minimized,
+/// the app backs up the save once the watched process exits
+pub struct ProcessWatcher {
+    exe_name: String,
+    running: Arc<AtomicBool>,
+    receiver: Receiver<WatchEvent>,
+    task: Task<()>,
+}
+
+impl ProcessWatcher {
+    const INTERVAL: Duration = Duration::from_secs(2);
+
+    pub fn spawn(ctx: egui::Context, exe_name: String) -> Self {
+        let (cancel_sender, cancel_receiver) = channel();
+        let (sender, receiver) = channel();
+        let running = Arc::new(AtomicBool::new(false));
+
+        let task = {
+            let exe_name = exe_name.clone();
+            let running = running.clone();
+            Task::new(cancel_sender, move || {
+                let send = |event: WatchEvent| {
+                    let _ = sender.send(event);
+                    ctx.request_repaint();
+                };
+
+                while let Err(RecvTimeoutError::Timeout) =
+                    cancel_receiver.recv_timeout(Self::INTERVAL)
+                {
+                    let is_running = match eapp_utils::platform::is_process_running(&exe_name) {
+                        Ok(is_running) => is_running,
+                        Err(err) => {
+                            send(WatchEvent::Error(err.to_string()));
+                            break;
+                        }
+                    };
+
+                    let was_running = running.swap(is_running, Ordering::SeqCst);
+                    if was_running != is_running {
+                        ctx.request_repaint();
+                    }
+
+                    if was_running && !is_running {
+                        send(WatchEvent::Exited);
+                    }
+                }
+            })
+        };
+
+        Self {
+            exe_name,
+            running,
+            receiver,
+            task,
+        }
+    }
+
+    pub fn exe_name(&self) -> &str {
+        &self.exe_name
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    pub fn poll_events(&self) -> impl Iterator<Item = WatchEvent> + '_ {
+        self.receiver.try_iter()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+}
+
+impl Drop for ProcessWatcher {
+    fn drop(&mut self) {
+        self.task.cancel();
+    }
+}
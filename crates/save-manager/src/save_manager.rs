@@ -1,5 +1,141 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+mod portable;
+
+pub use portable::{Conflict, Import, ImportSummary, Resolution};
+
+#[derive(Default, Debug)]
+pub struct Backup {
+    pub items: Vec<String>,
+
+    /// Size of the archive on disk, only `Some` for compressed backups
+    pub archive_size: Option<u64>,
+
+    pub modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Change {
+    /// Only exists in the backup
+    Added,
+
+    /// Only exists in the current save
+    Removed,
+
+    /// Exists in both but with different content
+    Modified,
+}
+
+/// A file which would be changed by restoring a backup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub name: String,
+    pub change: Change,
+}
+
+/// Settings stored alongside the backups of a main save directory
+#[derive(Deserialize, Serialize, Default, Debug)]
+#[serde(default)]
+pub struct Profile {
+    /// Keep at most this many unpinned backups
+    pub keep_last: Option<usize>,
+
+    /// Keep unpinned backups no older than this many days
+    pub keep_days: Option<u64>,
+
+    /// Backups exempt from pruning
+    pub pinned: BTreeSet<String>,
+
+    /// Free-form labels of each backup
+    pub tags: BTreeMap<String, BTreeSet<String>>,
+
+    /// Executable whose exit triggers a backup, empty to disable
+    pub watch_process: String,
+
+    /// Watch the save for changes and offer a backup once they settle
+    pub watch_changes: bool,
+
+    /// Back up without asking when a watched change settles
+    pub backup_on_change: bool,
+
+    /// Backup the global hotkeys act on, the selected one if `None`
+    pub hotkey_target: Option<String>,
+
+    /// Modification times of imported backups in seconds since the epoch,
+    /// preferred over the time of their directories
+    pub modified: BTreeMap<String, u64>,
+}
+
+/// Aggregate of the matching files in the main save directory, cheap to take as only
+/// the metadata is read, any write of the save changes it
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct DirState {
+    pub latest_modified: Option<SystemTime>,
+    pub file_count: usize,
+    pub total_size: u64,
+}
+
+/// Narrows down the backup list
+#[derive(Default, Debug)]
+pub struct BackupFilter {
+    /// Whitespace separated terms, all of them must match the name or a tag,
+    /// terms starting with `#` only match tags
+    pub query: String,
+
+    /// Only backups modified within this many days
+    pub newer_than_days: Option<u64>,
+
+    /// Only backups modified before this many days
+    pub older_than_days: Option<u64>,
+}
+
+impl BackupFilter {
+    pub fn is_empty(&self) -> bool {
+        self.query.trim().is_empty()
+            && self.newer_than_days.is_none()
+            && self.older_than_days.is_none()
+    }
+
+    fn matches(&self, name: &str, backup: &Backup, tags: Option<&BTreeSet<String>>) -> bool {
+        let days_ago =
+            |days: u64| SystemTime::now().checked_sub(Duration::from_secs(days * 24 * 3600));
+
+        if let Some(since) = self.newer_than_days.and_then(days_ago)
+            && backup.modified.is_none_or(|modified| modified < since)
+        {
+            return false;
+        }
+
+        if let Some(until) = self.older_than_days.and_then(days_ago)
+            && backup.modified.is_none_or(|modified| modified > until)
+        {
+            return false;
+        }
+
+        let name = name.to_lowercase();
+        let tags: Vec<String> = tags
+            .into_iter()
+            .flatten()
+            .map(|tag| tag.to_lowercase())
+            .collect();
+
+        self.query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|term| match term.strip_prefix('#') {
+                Some(term) => tags.iter().any(|tag| tag.contains(term)),
+                None => name.contains(term) || tags.iter().any(|tag| tag.contains(term)),
+            })
+    }
+}
 
 #[derive(Deserialize, Serialize, Default, Debug)]
 #[serde(default)]
@@ -9,11 +145,17 @@ pub struct SaveManager {
 
     pub main_save_dir: String,
 
+    /// Store new backups as a single zip archive instead of plain copies
+    pub compress: bool,
+
     #[serde(skip)]
     pub main_save_dir_items: Vec<String>,
 
     #[serde(skip)]
-    pub save_dirs: HashMap<String, Vec<String>>,
+    pub save_dirs: HashMap<String, Backup>,
+
+    #[serde(skip)]
+    pub profile: Profile,
 
     #[serde(skip)]
     pub regex: Option<regex::Regex>,
@@ -28,6 +170,12 @@ enum RemoveCmd {
 }
 
 impl SaveManager {
+    const ARCHIVE_NAME: &str = "backup.zip";
+    const PROFILE_NAME: &str = "profile.json";
+
+    /// Backup that receives the current save before restoring, when requested
+    pub const AUTO_BACKUP_NAME: &str = "auto-before-restore";
+
     pub fn load_main_save_dir(&mut self) -> std::io::Result<()> {
         self.verify_main_save_dir()?;
 
@@ -39,6 +187,7 @@ impl SaveManager {
         let info_path = Path::new(&self.main_save_dir).with_file_name("save_manager");
 
         let mut regex_str = None;
+        let mut profile = None;
         let mut save_dirs = HashMap::new();
 
         std::fs::create_dir_all(&info_path)?;
@@ -50,13 +199,25 @@ impl SaveManager {
                 regex_str = Some(std::fs::read_to_string(&path)?);
             }
 
+            if path.is_file() && filename == Self::PROFILE_NAME {
+                profile = Some(serde_json::from_str(&std::fs::read_to_string(&path)?)?);
+            }
+
             if path.is_dir() {
-                save_dirs.insert(filename.into_owned(), Self::search_dir_items(path)?);
+                save_dirs.insert(filename.into_owned(), Self::search_backup(&path)?);
+            }
+        }
+
+        let profile: Profile = profile.unwrap_or_default();
+        for (name, secs) in &profile.modified {
+            if let Some(backup) = save_dirs.get_mut(name) {
+                backup.modified = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(*secs));
             }
         }
 
         self.main_save_dir_items = items;
         self.save_dirs = save_dirs;
+        self.profile = profile;
 
         if let Some(regex_str) = regex_str {
             self.regex_str = regex_str;
@@ -66,6 +227,19 @@ impl SaveManager {
         Ok(())
     }
 
+    /// A copy of the settings only, for working on another thread,
+    /// [`Self::load_main_save_dir`] should be called before use
+    pub fn detached(&self) -> Self {
+        let mut manager = Self {
+            regex_str: self.regex_str.clone(),
+            main_save_dir: self.main_save_dir.clone(),
+            compress: self.compress,
+            ..Default::default()
+        };
+        manager.build_regex_from_str();
+        manager
+    }
+
     pub fn build_regex_from_str(&mut self) {
         if self.regex_str.is_empty() {
             self.regex = None;
@@ -93,6 +267,101 @@ impl SaveManager {
         Ok(items)
     }
 
+    fn search_backup(path: &Path) -> std::io::Result<Backup> {
+        let archive_path = path.join(Self::ARCHIVE_NAME);
+        if !archive_path.is_file() {
+            return Ok(Backup {
+                items: Self::search_dir_items(path)?,
+                archive_size: None,
+                modified: path.metadata()?.modified().ok(),
+            });
+        }
+
+        let archive = ZipArchive::new(File::open(&archive_path)?)?;
+        let items = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .map(str::to_owned)
+            .collect();
+
+        Ok(Backup {
+            items,
+            archive_size: Some(archive_path.metadata()?.len()),
+            modified: path.metadata()?.modified().ok(),
+        })
+    }
+
+    /// State of the files a backup would take
+    pub fn dir_state(&self) -> std::io::Result<DirState> {
+        self.verify_main_save_dir()?;
+
+        let mut state = DirState::default();
+        for item in std::fs::read_dir(&self.main_save_dir)? {
+            let item = item?;
+            let metadata = item.metadata()?;
+            if !metadata.is_file() || !self.is_match(&item.file_name().to_string_lossy()) {
+                continue;
+            }
+
+            state.file_count += 1;
+            state.total_size += metadata.len();
+            state.latest_modified = state.latest_modified.max(metadata.modified().ok());
+        }
+
+        Ok(state)
+    }
+
+    fn is_match(&self, filename: &str) -> bool {
+        self.regex.as_ref().is_none_or(|reg| reg.is_match(filename))
+    }
+
+    fn backup_dir(&self, name: &str) -> PathBuf {
+        Path::new(&self.main_save_dir)
+            .parent()
+            .unwrap()
+            .join(format!("save_manager/{name}"))
+    }
+
+    /// Hash and total size of the files of backup `name`, equal for identical plain
+    /// and compressed backups, `None` if it has no file
+    fn backup_fingerprint(&self, name: &str) -> std::io::Result<Option<(u32, u64)>> {
+        let dir = self.backup_dir(name);
+        let mut files = Vec::new();
+
+        let archive_path = dir.join(Self::ARCHIVE_NAME);
+        if archive_path.is_file() {
+            let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+            for i in 0..archive.len() {
+                let entry = archive.by_index(i)?;
+                if !entry.is_dir() {
+                    files.push((entry.name().to_owned(), entry.crc32(), entry.size()));
+                }
+            }
+        } else {
+            for item in Self::search_dir_items(&dir)? {
+                let path = dir.join(&item);
+                let checksum = Self::checksum(File::open(&path)?)?;
+                files.push((item, checksum, path.metadata()?.len()));
+            }
+        }
+
+        if files.is_empty() {
+            return Ok(None);
+        }
+        files.sort();
+
+        let mut hasher = crc32fast::Hasher::new();
+        let mut total_size = 0;
+        for (name, checksum, size) in files {
+            hasher.update(name.as_bytes());
+            hasher.update(&[0]);
+            hasher.update(&checksum.to_le_bytes());
+            total_size += size;
+        }
+
+        Ok(Some((hasher.finalize(), total_size)))
+    }
+
     fn verify_main_save_dir(&self) -> std::io::Result<()> {
         let main_dir = Path::new(&self.main_save_dir);
         if !main_dir.is_dir() {
@@ -122,7 +391,130 @@ impl SaveManager {
         Ok(())
     }
 
-    pub fn backup(&mut self, name: &str) -> std::io::Result<()> {
+    pub fn save_profile(&self) -> std::io::Result<()> {
+        self.verify_main_save_dir()?;
+
+        let file_path = Path::new(&self.main_save_dir)
+            .parent()
+            .unwrap()
+            .join(format!("save_manager/{}", Self::PROFILE_NAME));
+
+        std::fs::create_dir_all(file_path.parent().unwrap())?;
+        std::fs::write(file_path, serde_json::to_vec_pretty(&self.profile)?)?;
+
+        Ok(())
+    }
+
+    /// Names of all backups, the most recent first
+    pub fn sorted_backups(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.save_dirs.keys().cloned().collect();
+        names.sort_by(|a, b| {
+            self.save_dirs[b]
+                .modified
+                .cmp(&self.save_dirs[a].modified)
+                .then_with(|| a.cmp(b))
+        });
+        names
+    }
+
+    /// Names of backups passing `filter`, the most recent first
+    pub fn filtered_backups(&self, filter: &BackupFilter) -> Vec<String> {
+        self.sorted_backups()
+            .into_iter()
+            .filter(|name| filter.matches(name, &self.save_dirs[name], self.profile.tags.get(name)))
+            .collect()
+    }
+
+    pub fn tags(&self, name: &str) -> impl Iterator<Item = &String> {
+        self.profile.tags.get(name).into_iter().flatten()
+    }
+
+    /// Replaces the tags of backup `name` with the comma separated `tags`
+    pub fn set_tags(&mut self, name: &str, tags: &str) -> std::io::Result<()> {
+        let tags: BTreeSet<String> = tags
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(str::to_owned)
+            .collect();
+
+        if tags.is_empty() {
+            self.profile.tags.remove(name);
+        } else {
+            self.profile.tags.insert(name.to_owned(), tags);
+        }
+
+        self.save_profile()
+    }
+
+    /// Drops the pin and tags of backup `name`, returns whether the profile is changed
+    fn forget(&mut self, name: &str) -> bool {
+        let pinned = self.profile.pinned.remove(name);
+        let tagged = self.profile.tags.remove(name).is_some();
+        let imported = self.profile.modified.remove(name).is_some();
+        let targeted = self
+            .profile
+            .hotkey_target
+            .take_if(|target| target == name)
+            .is_some();
+        pinned || tagged || imported || targeted
+    }
+
+    /// Backups which exceed the retention policy of the profile
+    pub fn backups_to_prune(&self) -> Vec<String> {
+        let keep_since = self
+            .profile
+            .keep_days
+            .and_then(|days| SystemTime::now().checked_sub(Duration::from_secs(days * 24 * 3600)));
+
+        self.sorted_backups()
+            .into_iter()
+            .filter(|name| !self.profile.pinned.contains(name))
+            .enumerate()
+            .filter(|(idx, name)| {
+                self.profile.keep_last.is_some_and(|n| *idx >= n)
+                    || keep_since
+                        .zip(self.save_dirs[name].modified)
+                        .is_some_and(|(since, modified)| modified < since)
+            })
+            .map(|(_, name)| name)
+            .collect()
+    }
+
+    /// Moves the backups exceeding the retention policy to the trash,
+    /// returns their names
+    pub fn prune(&mut self) -> std::io::Result<Vec<String>> {
+        self.prune_keeping(&[])
+    }
+
+    /// Same as [`Self::prune`] but never prunes the backups in `keep`
+    fn prune_keeping(&mut self, keep: &[&str]) -> std::io::Result<Vec<String>> {
+        self.verify_main_save_dir()?;
+
+        let mut names = self.backups_to_prune();
+        names.retain(|name| !keep.contains(&name.as_str()));
+        let mut profile_changed = false;
+        for name in &names {
+            trash::delete(self.backup_dir(name)).map_err(std::io::Error::other)?;
+            self.save_dirs.remove(name);
+            profile_changed |= self.forget(name);
+        }
+
+        if profile_changed {
+            self.save_profile()?;
+        }
+
+        Ok(names)
+    }
+
+    /// Backs up into `name` then prunes, returns names of pruned backups
+    pub fn backup(&mut self, name: &str) -> std::io::Result<Vec<String>> {
+        self.copy_into(name)?;
+        self.prune()
+    }
+
+    /// Backs up into `name` without pruning
+    fn copy_into(&mut self, name: &str) -> std::io::Result<()> {
         self.verify_main_save_dir()?;
 
         if !self.save_dirs.contains_key(name) {
@@ -132,13 +524,18 @@ impl SaveManager {
         }
 
         let main_dir = Path::new(&self.main_save_dir);
-        let to_dir = Path::new(&self.main_save_dir)
-            .parent()
-            .unwrap()
-            .join(format!("save_manager/{name}"));
+        let to_dir = self.backup_dir(name);
 
-        self.replace(main_dir, to_dir.as_path(), RemoveCmd::RemoveAll)?;
-        *self.save_dirs.get_mut(name).unwrap() = Self::search_dir_items(to_dir)?;
+        if self.compress {
+            self.compress(main_dir, to_dir.as_path())?;
+        } else {
+            self.replace(main_dir, to_dir.as_path(), RemoveCmd::RemoveAll)?;
+        }
+        *self.save_dirs.get_mut(name).unwrap() = Self::search_backup(&to_dir)?;
+
+        if self.profile.modified.remove(name).is_some() {
+            self.save_profile()?;
+        }
 
         Ok(())
     }
@@ -153,28 +550,172 @@ impl SaveManager {
         }
 
         let main_dir = Path::new(&self.main_save_dir);
-        let from_dir = Path::new(&self.main_save_dir)
-            .parent()
-            .unwrap()
-            .join(format!("save_manager/{name}"));
-
-        self.replace(from_dir.as_path(), main_dir, RemoveCmd::RemoveByRegex)?;
+        let from_dir = self.backup_dir(name);
+
+        let archive_path = from_dir.join(Self::ARCHIVE_NAME);
+        if archive_path.is_file() {
+            self.remove_items(main_dir, RemoveCmd::RemoveByRegex)?;
+            ZipArchive::new(File::open(archive_path)?)?.extract(main_dir)?;
+        } else {
+            self.replace(from_dir.as_path(), main_dir, RemoveCmd::RemoveByRegex)?;
+        }
         self.main_save_dir_items = Self::search_dir_items(main_dir)?;
 
         Ok(())
     }
 
+    /// Backs up the current save into [`Self::AUTO_BACKUP_NAME`], creating it if needed
+    fn copy_into_auto(&mut self) -> std::io::Result<()> {
+        let name = Self::AUTO_BACKUP_NAME;
+        if !self.save_dirs.contains_key(name) {
+            self.add(name.to_owned())?;
+        }
+
+        self.copy_into(name)
+    }
+
+    /// Restores backup `name`, backing up the current save into [`Self::AUTO_BACKUP_NAME`]
+    /// first if `backup_first`, returns names of pruned backups
+    ///
+    /// Backups are only pruned once it's restored, and neither `name` nor the one just
+    /// backed up into is pruned
+    pub fn restore_with_backup(
+        &mut self,
+        name: &str,
+        backup_first: bool,
+    ) -> std::io::Result<Vec<String>> {
+        let backup_first = backup_first && name != Self::AUTO_BACKUP_NAME;
+        if backup_first {
+            self.copy_into_auto()?;
+        }
+
+        self.restore(name)?;
+
+        if backup_first {
+            self.prune_keeping(&[name, Self::AUTO_BACKUP_NAME])
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    /// Backs up the current save into a new backup named `{prefix}-{UTC time}`,
+    /// returns its name and names of pruned backups
+    pub fn backup_new(&mut self, prefix: &str) -> std::io::Result<(String, Vec<String>)> {
+        let name = format!("{prefix}-{}", utc_timestamp(SystemTime::now()));
+        self.add(name.clone())?;
+        let pruned = self.backup(&name)?;
+
+        Ok((name, pruned))
+    }
+
+    /// Compares the backup `name` with the current save, returns files changed by restoring it
+    pub fn diff(&self, name: &str) -> std::io::Result<Vec<FileChange>> {
+        self.verify_main_save_dir()?;
+
+        if !self.save_dirs.contains_key(name) {
+            return Err(std::io::Error::other(
+                "Unable to find the directory specified for diff",
+            ));
+        }
+
+        self.diff_dirs(&self.backup_dir(name), Path::new(&self.main_save_dir))
+    }
+
+    fn diff_dirs(&self, backup: &Path, current: &Path) -> std::io::Result<Vec<FileChange>> {
+        let mut changes = Vec::new();
+        let mut in_backup = BTreeSet::new();
+
+        let archive_path = backup.join(Self::ARCHIVE_NAME);
+        if archive_path.is_file() {
+            let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+            for i in 0..archive.len() {
+                let entry = archive.by_index(i)?;
+                if entry.is_dir() {
+                    continue;
+                }
+
+                let name = entry.name().to_owned();
+                let path = current.join(&name);
+                let change = if !path.is_file() {
+                    Some(Change::Added)
+                } else if path.metadata()?.len() != entry.size()
+                    || Self::checksum(File::open(&path)?)? != entry.crc32()
+                {
+                    Some(Change::Modified)
+                } else {
+                    None
+                };
+
+                if let Some(change) = change {
+                    changes.push(FileChange {
+                        name: name.clone(),
+                        change,
+                    });
+                }
+                in_backup.insert(name);
+            }
+        } else {
+            for name in Self::search_dir_items(backup)? {
+                if !self.is_match(&name) {
+                    continue;
+                }
+
+                let from = backup.join(&name);
+                let path = current.join(&name);
+                let change = if !path.is_file() {
+                    Some(Change::Added)
+                } else if path.metadata()?.len() != from.metadata()?.len()
+                    || Self::checksum(File::open(&path)?)? != Self::checksum(File::open(&from)?)?
+                {
+                    Some(Change::Modified)
+                } else {
+                    None
+                };
+
+                if let Some(change) = change {
+                    changes.push(FileChange {
+                        name: name.clone(),
+                        change,
+                    });
+                }
+                in_backup.insert(name);
+            }
+        }
+
+        for name in Self::search_dir_items(current)? {
+            if self.is_match(&name) && !in_backup.contains(&name) {
+                changes.push(FileChange {
+                    name,
+                    change: Change::Removed,
+                });
+            }
+        }
+
+        changes.sort_by(|a, b| a.change.cmp(&b.change).then_with(|| a.name.cmp(&b.name)));
+        Ok(changes)
+    }
+
+    fn checksum(mut reader: impl Read) -> std::io::Result<u32> {
+        let mut hasher = crc32fast::Hasher::new();
+        let mut buf = [0; 8192];
+        loop {
+            let len = reader.read(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+            hasher.update(&buf[..len]);
+        }
+
+        Ok(hasher.finalize())
+    }
+
     pub fn add(&mut self, name: String) -> std::io::Result<()> {
         self.verify_main_save_dir()?;
 
-        let dir_path = Path::new(&self.main_save_dir)
-            .parent()
-            .unwrap()
-            .join(format!("save_manager/{name}"));
+        let dir_path = self.backup_dir(&name);
         std::fs::create_dir_all(&dir_path)?;
 
-        self.save_dirs
-            .insert(name, Self::search_dir_items(dir_path)?);
+        self.save_dirs.insert(name, Self::search_backup(&dir_path)?);
 
         Ok(())
     }
@@ -182,13 +723,12 @@ impl SaveManager {
     pub fn remove(&mut self, name: &str) -> std::io::Result<()> {
         self.verify_main_save_dir()?;
 
-        let dir_path = Path::new(&self.main_save_dir)
-            .parent()
-            .unwrap()
-            .join(format!("save_manager/{name}"));
-        std::fs::remove_dir_all(dir_path)?;
+        std::fs::remove_dir_all(self.backup_dir(name))?;
 
         self.save_dirs.remove(name);
+        if self.forget(name) {
+            self.save_profile()?;
+        }
 
         Ok(())
     }
@@ -202,22 +742,75 @@ impl SaveManager {
         let from_dir = from_dir.as_ref();
         let to_dir = to_dir.as_ref();
 
-        if to_dir.exists() {
+        self.remove_items(to_dir, cmd)?;
+
+        for item in std::fs::read_dir(from_dir)? {
+            let path = item?.path();
+            if !path.is_file() {
+                continue;
+            }
+
+            if self.is_match(&path.file_name().unwrap().to_string_lossy()) {
+                let to = to_dir.join(path.file_name().unwrap());
+                std::fs::copy(path, to)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Packs every matched file under `from_dir` (recursively) into the archive of `to_dir`,
+    /// keeping paths relative to `from_dir`
+    fn compress<P: AsRef<Path>>(&self, from_dir: P, to_dir: P) -> std::io::Result<()> {
+        let to_dir = to_dir.as_ref();
+
+        self.remove_items(to_dir, RemoveCmd::RemoveAll)?;
+
+        let mut writer = ZipWriter::new(File::create(to_dir.join(Self::ARCHIVE_NAME))?);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        self.compress_dir(&mut writer, options, from_dir.as_ref(), "")?;
+        writer.finish()?;
+
+        Ok(())
+    }
+
+    fn compress_dir(
+        &self,
+        writer: &mut ZipWriter<File>,
+        options: SimpleFileOptions,
+        dir: &Path,
+        prefix: &str,
+    ) -> std::io::Result<()> {
+        for item in std::fs::read_dir(dir)? {
+            let path = item?.path();
+            let filename = path.file_name().unwrap().to_string_lossy();
+            let name = format!("{prefix}{filename}");
+
+            if path.is_dir() {
+                self.compress_dir(writer, options, &path, &format!("{name}/"))?;
+            } else if self.is_match(&filename) {
+                writer.start_file(name, options)?;
+                std::io::copy(&mut File::open(&path)?, writer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn remove_items(&self, dir: &Path, cmd: RemoveCmd) -> std::io::Result<()> {
+        if dir.exists() {
             match cmd {
-                RemoveCmd::RemoveAll => std::fs::remove_dir_all(to_dir)?,
+                RemoveCmd::RemoveAll => std::fs::remove_dir_all(dir)?,
                 RemoveCmd::RemoveByRegex => {
-                    for item in std::fs::read_dir(to_dir)? {
+                    for item in std::fs::read_dir(dir)? {
                         let path = item?.path();
                         if !path.is_file() {
                             continue;
                         }
 
-                        if let Some(reg) = self.regex.as_ref() {
-                            let filename = path.file_name().unwrap().to_string_lossy();
-                            if reg.is_match(&filename) {
-                                std::fs::remove_file(path)?;
-                            }
-                        } else {
+                        if self.is_match(&path.file_name().unwrap().to_string_lossy()) {
                             std::fs::remove_file(path)?;
                         }
                     }
@@ -225,32 +818,19 @@ impl SaveManager {
             }
         }
 
-        if !to_dir.exists() {
-            std::fs::create_dir_all(to_dir)?;
-        }
-
-        for item in std::fs::read_dir(from_dir)? {
-            let path = item?.path();
-            if !path.is_file() {
-                continue;
-            }
-
-            let from = if let Some(reg) = self.regex.as_ref() {
-                if reg.is_match(&path.file_name().unwrap().to_string_lossy()) {
-                    Some(path)
-                } else {
-                    None
-                }
-            } else {
-                Some(path)
-            };
-
-            if let Some(from) = from {
-                let to = to_dir.join(from.file_name().unwrap());
-                std::fs::copy(from, to)?;
-            }
+        if !dir.exists() {
+            std::fs::create_dir_all(dir)?;
         }
 
         Ok(())
     }
 }
+
+/// Formats `time` as `YYYYMMDD-HHMMSS` in UTC
+fn utc_timestamp(time: SystemTime) -> String {
+    let t = eapp_utils::date::UtcDateTime::new(time);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        t.year, t.month, t.day, t.hour, t.minute, t.second
+    )
+}
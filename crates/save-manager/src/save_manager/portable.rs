@@ -0,0 +1,373 @@
+//! Export and import of a main save directory with all its backups,
+//! so that the history can be shared through a synced folder
+
+use super::{Profile, RemoveCmd, SaveManager};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
+};
+use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+
+const MANIFEST_NAME: &str = "manifest.json";
+const BACKUPS_PREFIX: &str = "backups/";
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+struct Manifest {
+    /// Main save directory on the exporting machine
+    source_dir: String,
+
+    /// Same as `source_dir` but relative to the home directory, if it is inside it
+    home_relative_dir: Option<String>,
+
+    backups: Vec<ManifestBackup>,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(default)]
+struct ManifestBackup {
+    name: String,
+
+    /// Seconds since the epoch
+    modified: Option<u64>,
+
+    /// `None` if it has no file, an empty backup is never taken as identical to another
+    hash: Option<u32>,
+
+    /// Total size of its files, `None` in exports made before it's recorded
+    size: Option<u64>,
+}
+
+impl ManifestBackup {
+    /// Whether it's identical to one of the `local` fingerprints
+    fn is_in(&self, local: &[(u32, u64)]) -> bool {
+        self.hash.is_some_and(|hash| {
+            local
+                .iter()
+                .any(|&(h, size)| h == hash && self.size.is_none_or(|s| s == size))
+        })
+    }
+}
+
+/// An export read from disk, waiting to be merged into the current main save directory
+pub struct Import {
+    path: PathBuf,
+    manifest: Manifest,
+
+    /// Main save directory to import into, resolved on this machine
+    pub save_dir: Option<String>,
+}
+
+impl Import {
+    /// Main save directory on the exporting machine
+    pub fn source_dir(&self) -> &str {
+        &self.manifest.source_dir
+    }
+
+    pub fn backup_count(&self) -> usize {
+        self.manifest.backups.len()
+    }
+}
+
+/// How to import a backup whose name is taken by a different local backup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// Import it under another name
+    Rename(String),
+
+    /// Keep whichever of the two is newer
+    Merge,
+}
+
+pub struct Conflict {
+    pub name: String,
+    pub resolution: Resolution,
+}
+
+#[derive(Default)]
+pub struct ImportSummary {
+    pub added: Vec<String>,
+    pub skipped: usize,
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from)
+}
+
+/// Fails if `name` is not a single plain entry of the backup directory,
+/// the names in an export are not trusted as it may come from anywhere
+fn check_backup_name(name: &str) -> std::io::Result<()> {
+    let mut components = Path::new(name).components();
+    let single =
+        matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+
+    if name.trim().is_empty() || name.contains(['/', '\\']) || !single {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid backup name '{name}'"),
+        ));
+    }
+    Ok(())
+}
+
+fn to_secs(time: Option<SystemTime>) -> Option<u64> {
+    time?
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+impl SaveManager {
+    /// Packs the settings and all backups into the zip archive `path`
+    pub fn export(&self, path: &Path) -> std::io::Result<()> {
+        self.verify_main_save_dir()?;
+
+        let mut writer = ZipWriter::new(File::create(path)?);
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+        let mut manifest = Manifest {
+            source_dir: self.main_save_dir.clone(),
+            home_relative_dir: home_dir().and_then(|home| {
+                let relative = Path::new(&self.main_save_dir).strip_prefix(home).ok()?;
+                let parts: Vec<_> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect();
+                Some(parts.join("/"))
+            }),
+            backups: Vec::new(),
+        };
+
+        for name in self.sorted_backups() {
+            Self::pack_dir(
+                &mut writer,
+                options,
+                &self.backup_dir(&name),
+                &format!("{BACKUPS_PREFIX}{name}/"),
+            )?;
+
+            let fingerprint = self.backup_fingerprint(&name)?;
+            manifest.backups.push(ManifestBackup {
+                hash: fingerprint.map(|(hash, _)| hash),
+                size: fingerprint.map(|(_, size)| size),
+                modified: to_secs(self.save_dirs[&name].modified),
+                name,
+            });
+        }
+
+        writer.start_file("regex.txt", options)?;
+        writer.write_all(self.regex_str.as_bytes())?;
+
+        writer.start_file(Self::PROFILE_NAME, options)?;
+        writer.write_all(&serde_json::to_vec_pretty(&self.profile)?)?;
+
+        writer.start_file(MANIFEST_NAME, options)?;
+        writer.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+
+        writer.finish()?;
+        Ok(())
+    }
+
+    fn pack_dir(
+        writer: &mut ZipWriter<File>,
+        options: SimpleFileOptions,
+        dir: &Path,
+        prefix: &str,
+    ) -> std::io::Result<()> {
+        writer.add_directory(prefix, options)?;
+
+        for item in std::fs::read_dir(dir)? {
+            let path = item?.path();
+            let name = format!("{prefix}{}", path.file_name().unwrap().to_string_lossy());
+
+            if path.is_dir() {
+                Self::pack_dir(writer, options, &path, &format!("{name}/"))?;
+            } else {
+                writer.start_file(name, options)?;
+                std::io::copy(&mut File::open(&path)?, writer)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the export `path`, [`Import::save_dir`] is `None` if the exported
+    /// main save directory does not exist on this machine
+    pub fn read_import(path: &Path) -> std::io::Result<Import> {
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        let manifest: Manifest = serde_json::from_reader(archive.by_name(MANIFEST_NAME)?)?;
+        for backup in &manifest.backups {
+            check_backup_name(&backup.name)?;
+        }
+
+        let from_home = manifest
+            .home_relative_dir
+            .as_ref()
+            .and_then(|relative| Some(home_dir()?.join(relative)));
+
+        let save_dir = from_home
+            .into_iter()
+            .chain([PathBuf::from(&manifest.source_dir)])
+            .find(|dir| dir.is_dir())
+            .map(|dir| dir.to_string_lossy().into_owned());
+
+        Ok(Import {
+            path: path.to_owned(),
+            manifest,
+            save_dir,
+        })
+    }
+
+    /// Backups of `import` whose names are taken by different local backups
+    pub fn import_conflicts(&self, import: &Import) -> std::io::Result<Vec<Conflict>> {
+        let local = self.local_fingerprints()?;
+
+        Ok(import
+            .manifest
+            .backups
+            .iter()
+            .filter(|b| self.save_dirs.contains_key(&b.name) && !b.is_in(&local))
+            .map(|b| Conflict {
+                name: b.name.clone(),
+                resolution: Resolution::Rename(format!("{}-imported", b.name)),
+            })
+            .collect())
+    }
+
+    /// Merges `import` into the loaded main save directory, backups identical to
+    /// a local one are skipped
+    pub fn import(
+        &mut self,
+        import: &Import,
+        conflicts: &[Conflict],
+    ) -> std::io::Result<ImportSummary> {
+        self.verify_main_save_dir()?;
+
+        let mut archive = ZipArchive::new(File::open(&import.path)?)?;
+        let imported_profile: Profile = match archive.by_name(Self::PROFILE_NAME) {
+            Ok(file) => serde_json::from_reader(file)?,
+            Err(_) => Profile::default(),
+        };
+
+        // Checked before anything is written, so that it doesn't fail halfway
+        let backups = &import.manifest.backups;
+        for backup in backups {
+            check_backup_name(&backup.name)?;
+        }
+        let mut renamed = Vec::new();
+        for conflict in conflicts {
+            let Resolution::Rename(name) = &conflict.resolution else {
+                continue;
+            };
+            let name = name.trim();
+            check_backup_name(name)?;
+            if self.save_dirs.contains_key(name)
+                || backups.iter().any(|b| b.name == name)
+                || renamed.contains(&name)
+            {
+                return Err(std::io::Error::other(format!(
+                    "Unable to rename '{}' to '{name}'",
+                    conflict.name
+                )));
+            }
+            renamed.push(name);
+        }
+
+        let local = self.local_fingerprints()?;
+        let mut summary = ImportSummary::default();
+
+        for backup in backups {
+            if backup.is_in(&local) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            let resolution = conflicts
+                .iter()
+                .find(|c| c.name == backup.name)
+                .map(|c| &c.resolution);
+
+            let target = match resolution {
+                Some(Resolution::Rename(name)) => name.trim().to_owned(),
+                Some(Resolution::Merge) => {
+                    let local = to_secs(self.save_dirs[&backup.name].modified);
+                    if backup.modified <= local {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    backup.name.clone()
+                }
+                None if self.save_dirs.contains_key(&backup.name) => {
+                    summary.skipped += 1;
+                    continue;
+                }
+                None => backup.name.clone(),
+            };
+
+            let to_dir = self.backup_dir(&target);
+            self.remove_items(&to_dir, RemoveCmd::RemoveAll)?;
+
+            let prefix = format!("{BACKUPS_PREFIX}{}/", backup.name);
+            for i in 0..archive.len() {
+                let mut entry = archive.by_index(i)?;
+                let Some(relative) = entry
+                    .enclosed_name()
+                    .and_then(|path| path.strip_prefix(&prefix).ok().map(Path::to_owned))
+                else {
+                    continue;
+                };
+
+                let path = to_dir.join(relative);
+                if entry.is_dir() {
+                    std::fs::create_dir_all(path)?;
+                } else {
+                    std::fs::create_dir_all(path.parent().unwrap())?;
+                    std::io::copy(&mut entry, &mut File::create(path)?)?;
+                }
+            }
+
+            if let Some(secs) = backup.modified {
+                self.profile.modified.insert(target.clone(), secs);
+            }
+            if imported_profile.pinned.contains(&backup.name) {
+                self.profile.pinned.insert(target.clone());
+            }
+            if let Some(tags) = imported_profile.tags.get(&backup.name) {
+                self.profile.tags.insert(target.clone(), tags.clone());
+            }
+
+            summary.added.push(target);
+        }
+
+        if self.profile.keep_last.is_none() && self.profile.keep_days.is_none() {
+            self.profile.keep_last = imported_profile.keep_last;
+            self.profile.keep_days = imported_profile.keep_days;
+        }
+
+        if self.regex_str.is_empty()
+            && let Ok(mut file) = archive.by_name("regex.txt")
+        {
+            let mut regex_str = String::new();
+            file.read_to_string(&mut regex_str)?;
+            self.regex_str = regex_str;
+            self.save_regex()?;
+        }
+
+        self.save_profile()?;
+        self.load_main_save_dir()?;
+
+        Ok(summary)
+    }
+
+    /// Fingerprints of local backups which are not empty
+    fn local_fingerprints(&self) -> std::io::Result<Vec<(u32, u64)>> {
+        self.save_dirs
+            .keys()
+            .filter_map(|name| self.backup_fingerprint(name).transpose())
+            .collect()
+    }
+}
@@ -3,10 +3,14 @@ use eapp_utils::{
     borderless,
     codicons::{ICON_FOLDER, ICON_SETTINGS_GEAR},
     get_body_font_id, get_button_height,
+    persistent_window::PersistentWindow,
     ui_font_selector::UiFontSelector,
-    widgets::simple_widgets::{auto_selectable, frameless_btn, get_theme_button, theme_button},
+    widgets::{
+        nav_list::NavList,
+        simple_widgets::{auto_selectable, frameless_btn, get_theme_button, theme_button},
+    },
 };
-use eframe::egui::{self, Color32, Event, Key, PopupCloseBehavior, UiBuilder, Vec2};
+use eframe::egui::{self, Color32, PopupCloseBehavior, UiBuilder, Vec2};
 
 #[derive(PartialEq, Eq)]
 enum RunMode {
@@ -26,6 +30,7 @@ pub struct App {
     cwd: Option<String>,
     remembered_args: RememberedArgs,
     selector: UiFontSelector,
+    window: PersistentWindow,
 }
 
 impl App {
@@ -69,6 +74,7 @@ impl App {
             cwd,
             remembered_args,
             selector,
+            window: PersistentWindow::new(cc),
         };
 
         this.rebuild_fonts(&cc.egui_ctx);
@@ -86,10 +92,6 @@ impl App {
         self.loader.script_list.get_mut(*script_index)
     }
 
-    fn get_cur_script_len(&self) -> usize {
-        self.get_filtered_indices().len()
-    }
-
     fn get_filtered_indices(&self) -> Vec<usize> {
         let mut indices = Vec::new();
 
@@ -119,47 +121,6 @@ impl App {
         indices
     }
 
-    fn next_script(&mut self) {
-        let len = self.get_cur_script_len();
-        if len > 0 {
-            self.cur_sel_script = (self.cur_sel_script + 1) % len;
-        }
-    }
-
-    fn prev_script(&mut self) {
-        let len = self.get_cur_script_len();
-        if len > 0 {
-            self.cur_sel_script = (self.cur_sel_script + len - 1) % len;
-        }
-    }
-
-    fn select_script_by_letter(&mut self, letter: char) -> bool {
-        let indices = self.get_filtered_indices();
-        if indices.is_empty() {
-            return false;
-        }
-
-        let search_letter = letter.to_ascii_lowercase();
-
-        let start_index = (self.cur_sel_script + 1) % indices.len();
-        let mut found = false;
-
-        for i in 0..indices.len() {
-            let index = (start_index + i) % indices.len();
-            let script_index = indices[index];
-            let script = &self.loader.script_list[script_index];
-            if let Some(first_char) = script.command.name.chars().next()
-                && first_char.to_ascii_lowercase() == search_letter
-            {
-                self.cur_sel_script = index;
-                found = true;
-                break;
-            }
-        }
-
-        found
-    }
-
     fn prev_tag(&mut self) {
         if let Some(ref mut i) = self.cur_sel_tag {
             if *i == 0 {
@@ -278,7 +239,6 @@ impl App {
 
     fn ui_left_panel(&mut self, ui: &mut egui::Ui) {
         let mut t_changed = false; // tag
-        let mut s_changed = false; // script
 
         if ui.memory(|mem| mem.focused().is_none()) {
             if ui.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
@@ -290,30 +250,6 @@ impl App {
                 t_changed = true;
                 self.next_tag();
             }
-
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
-                s_changed = true;
-                self.prev_script();
-            }
-
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
-                s_changed = true;
-                self.next_script();
-            }
-
-            ui.input(|i| {
-                if let Some(Event::Key { key, .. }) = i.events.iter().find(|event| {
-                    matches!(
-                        event,
-                        Event::Key { key, pressed: true, .. }
-                        if *key >= Key::A && *key <= Key::Z
-                    )
-                }) {
-                    let i = *key as u8 - Key::A as u8;
-                    let letter = (b'a' + i) as char;
-                    s_changed = s_changed || self.select_script_by_letter(letter)
-                }
-            });
         }
 
         ui.add(
@@ -336,26 +272,12 @@ impl App {
             });
         });
 
-        egui::ScrollArea::vertical().show(ui, |ui| {
-            ui.with_layout(egui::Layout::top_down_justified(egui::Align::LEFT), |ui| {
-                let indices = self.get_filtered_indices();
-
-                if indices.is_empty() {
-                    return;
-                }
-
-                for (display_index, &script_index) in indices.iter().enumerate() {
-                    let script = &self.loader.script_list[script_index];
-                    auto_selectable(
-                        ui,
-                        &mut self.cur_sel_script,
-                        display_index,
-                        &script.command.name,
-                        s_changed,
-                    );
-                }
-            })
-        });
+        let indices = self.get_filtered_indices();
+        let script_list = &self.loader.script_list;
+        NavList::new("script_list", indices.len(), |i| {
+            script_list[indices[i]].command.name.clone()
+        })
+        .show(ui, &mut self.cur_sel_script);
     }
 
     fn ui_right_panel(&mut self, ui: &mut egui::Ui) {
@@ -455,6 +377,7 @@ impl eframe::App for App {
 
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, UiFontSelector::KEY, &self.selector);
+        self.window.save(storage);
         eframe::set_value(storage, "info_json_path", &self.info_json_path);
         eframe::set_value(
             storage,
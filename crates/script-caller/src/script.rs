@@ -9,7 +9,7 @@ use eframe::egui::{
     text::{CCursor, CCursorRange},
 };
 use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     path::Path,
@@ -61,12 +61,38 @@ pub struct Arg {
 
     #[serde(default)]
     pub existing_path: bool,
+
+    /// Shown only if all of them are met, either one condition or a list of them
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub visible_when: Vec<Condition>,
 }
 
 fn default_as_true() -> bool {
     true
 }
 
+/// Met if the arg named `arg` is shown and passed, and its value is `equals`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    pub arg: String,
+    pub equals: String,
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Condition>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(Condition),
+        Many(Vec<Condition>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(condition) => vec![condition],
+        OneOrMany::Many(conditions) => conditions,
+    })
+}
+
 impl Arg {
     pub fn optional_and_disabled(&self) -> bool {
         self.optional && !self.enabled
@@ -76,6 +102,11 @@ impl Arg {
         self.optional && self.enabled
     }
 
+    /// Whether it's called `name`, with or without the leading dashes
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name == name || self.name.trim_start_matches('-') == name.trim_start_matches('-')
+    }
+
     pub fn show_ui(&mut self, ui: &mut egui::Ui) {
         let name = if self.name.starts_with("--") {
             &self.name[2..]
@@ -406,21 +437,96 @@ pub struct Command {
 
 impl Command {
     pub fn show_ui(&mut self, ui: &mut egui::Ui) {
-        self.args.iter_mut().for_each(|arg| arg.show_ui(ui));
+        let visible = self.visible_args();
+        self.args
+            .iter_mut()
+            .zip(visible)
+            .filter(|(_, visible)| *visible)
+            .for_each(|(arg, _)| arg.show_ui(ui));
     }
 
+    /// Hidden args are left out, even if they have values
     pub fn generate_args(&self) -> Vec<String> {
         let mut value_vec = Vec::new();
 
         self.args
             .iter()
-            .filter(|arg| !arg.optional_and_disabled())
-            .for_each(|arg| value_vec.extend(arg.get_value_formatted()));
+            .zip(self.visible_args())
+            .filter(|(arg, visible)| *visible && !arg.optional_and_disabled())
+            .for_each(|(arg, _)| value_vec.extend(arg.get_value_formatted()));
 
         value_vec
     }
 
+    /// Whether each arg is shown by its conditions, evaluated on the current values
+    pub fn visible_args(&self) -> Vec<bool> {
+        let mut visible = vec![None; self.args.len()];
+        for idx in 0..self.args.len() {
+            self.resolve_visible(idx, &mut visible, &mut Vec::new());
+        }
+        visible.into_iter().map(|v| v.unwrap_or(false)).collect()
+    }
+
+    /// An arg whose conditions depend on itself is hidden
+    fn resolve_visible(
+        &self,
+        idx: usize,
+        visible: &mut [Option<bool>],
+        visiting: &mut Vec<usize>,
+    ) -> bool {
+        if let Some(v) = visible[idx] {
+            return v;
+        }
+        if visiting.contains(&idx) {
+            return false;
+        }
+
+        visiting.push(idx);
+        let met = self.args[idx].visible_when.iter().all(|condition| {
+            let Some(dep) = self
+                .args
+                .iter()
+                .position(|arg| arg.is_named(&condition.arg))
+            else {
+                return true;
+            };
+
+            let arg = &self.args[dep];
+            self.resolve_visible(dep, visible, visiting)
+                && !arg.optional_and_disabled()
+                && arg.get_value() == condition.equals
+        });
+        visiting.pop();
+
+        visible[idx] = Some(met);
+        met
+    }
+
+    /// Drops the conditions on unknown args, so that the args are shown as without them
+    fn check_conditions(&mut self) {
+        let names: Vec<_> = self.args.iter().map(|arg| arg.name.clone()).collect();
+        for arg in &mut self.args {
+            arg.visible_when.retain(|condition| {
+                let known = names.iter().any(|name| {
+                    name.trim_start_matches('-') == condition.arg.trim_start_matches('-')
+                });
+                if !known {
+                    log::warn!(
+                        "condition of '{}' in '{}' on unknown arg '{}' is ignored",
+                        arg.name,
+                        self.name,
+                        condition.arg
+                    );
+                }
+                known
+            });
+        }
+    }
+
     pub fn initialize(&mut self, prefix: &str, remembered_args: &RememberedArgs) {
+        self.check_conditions();
+
+        // hidden args are restored as well, they may be shown later
         self.args.iter_mut().for_each(|arg| {
             arg.initialize_value();
 
@@ -565,7 +671,7 @@ pub struct Loader {
     pub script_path: String,
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Default, Debug)]
 #[serde(default)]
 pub struct RememberedArg {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -702,3 +808,117 @@ pub fn args_to_escaped_string(args: &[String]) -> String {
         .collect::<Vec<String>>()
         .join(" ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(json: &str) -> Command {
+        let mut command: Command = serde_json::from_str(json).unwrap();
+        command.initialize("", &RememberedArgs::new());
+        command
+    }
+
+    const CHAIN: &str = r#"{
+        "name": "deploy",
+        "args": [
+            { "name": "--provider", "type": "choices", "choices": ["local", "aws"] },
+            {
+                "name": "--region", "type": "choices", "choices": ["us", "eu"],
+                "visible_when": { "arg": "--provider", "equals": "aws" }
+            },
+            {
+                "name": "--zone", "type": "one_line", "default": "a",
+                "visible_when": [{ "arg": "region", "equals": "eu" }]
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn condition_round_trip() {
+        let condition = Condition {
+            arg: "--provider".to_owned(),
+            equals: "aws".to_owned(),
+        };
+        let json = serde_json::to_string(&condition).unwrap();
+        assert_eq!(serde_json::from_str::<Condition>(&json).unwrap(), condition);
+
+        let command = command(CHAIN);
+        assert_eq!(command.args[1].visible_when, [condition]);
+        assert_eq!(command.args[2].visible_when.len(), 1);
+        assert!(command.args[0].visible_when.is_empty());
+    }
+
+    #[test]
+    fn conditions_chain() {
+        let mut command = command(CHAIN);
+        assert_eq!(command.visible_args(), [true, false, false]);
+        assert_eq!(command.generate_args(), ["--provider", "local"]);
+
+        command.args[0].set_value(Some("aws".to_owned()));
+        assert_eq!(command.visible_args(), [true, true, false]);
+
+        command.args[1].set_value(Some("eu".to_owned()));
+        assert_eq!(command.visible_args(), [true, true, true]);
+        assert_eq!(
+            command.generate_args(),
+            ["--provider", "aws", "--region", "eu", "--zone", "a"]
+        );
+
+        // hiding the first one hides the whole chain, though the values are kept
+        command.args[0].set_value(Some("local".to_owned()));
+        assert_eq!(command.visible_args(), [true, false, false]);
+        assert_eq!(command.generate_args(), ["--provider", "local"]);
+        assert_eq!(command.args[2].get_value(), "a");
+    }
+
+    #[test]
+    fn unknown_and_cyclic_conditions() {
+        let command = command(
+            r#"{
+                "name": "cmd",
+                "args": [
+                    {
+                        "name": "--a", "type": "store_true",
+                        "visible_when": { "arg": "--missing", "equals": "x" }
+                    },
+                    {
+                        "name": "--b", "type": "store_true",
+                        "visible_when": { "arg": "--c", "equals": "false" }
+                    },
+                    {
+                        "name": "--c", "type": "store_true",
+                        "visible_when": { "arg": "--b", "equals": "false" }
+                    }
+                ]
+            }"#,
+        );
+
+        assert!(command.args[0].visible_when.is_empty());
+        assert_eq!(command.visible_args(), [true, false, false]);
+    }
+
+    #[test]
+    fn hidden_args_are_restored() {
+        let mut command: Command = serde_json::from_str(CHAIN).unwrap();
+        let key = unique_name!("", "deploy", "--region");
+        let remembered = RememberedArgs::from([(
+            key.clone(),
+            RememberedArg {
+                value: Some("eu".to_owned()),
+                enabled: false,
+            },
+        )]);
+        command.initialize("", &remembered);
+
+        assert_eq!(command.visible_args(), [true, false, false]);
+        assert_eq!(command.args[1].get_value(), "eu");
+        assert_eq!(
+            command.get_remembered_args("").get(&key),
+            remembered.get(&key)
+        );
+
+        command.args[0].set_value(Some("aws".to_owned()));
+        assert_eq!(command.visible_args(), [true, true, true]);
+    }
+}
@@ -1,20 +1,37 @@
-use crate::sync::{self, ItemCmd, Syncer};
+use crate::{
+    schedule::{Pair, Scheduler},
+    sync::{self, ConflictPolicy, ItemCmd, Syncer},
+};
 use eapp_utils::{
     borderless,
-    codicons::{ICON_FOLDER, ICON_SETTINGS_GEAR},
+    codicons::{ICON_ADD, ICON_FOLDER, ICON_SETTINGS_GEAR, ICON_TRASH, ICON_WARNING},
     get_body_font_id, get_button_height,
+    persistent_window::PersistentWindow,
     ui_font_selector::UiFontSelector,
-    widgets::simple_widgets::{frameless_btn, get_theme_button, theme_button, toggle_ui},
+    waker::{WakeType, Waker},
+    widgets::{
+        simple_widgets::{frameless_btn, get_theme_button, theme_button, toggle_ui},
+        unit_drag::unit_drag,
+    },
 };
 use eframe::egui::{self, Color32, PopupCloseBehavior, RichText, UiBuilder, Vec2, Widget};
 use serde::{Deserialize, Serialize};
-use std::thread::JoinHandle;
+use std::{
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
 pub struct App {
     state: State,
     syncer: Option<Syncer>,
     handle: Option<JoinHandle<()>>,
     selector: UiFontSelector,
+    window: PersistentWindow,
+    scheduler: Scheduler,
+    waker: Waker,
+
+    /// The earliest scheduled run the waker is requested for
+    next_wake: Option<Instant>,
 }
 
 #[derive(Deserialize, Serialize, Default)]
@@ -38,6 +55,12 @@ pub struct State {
     #[serde(skip)]
     pub items: Vec<sync::Item>,
 
+    /// Pairs synced automatically while the app is running
+    pub pairs: Vec<Pair>,
+
+    /// Conflicts found by automatic runs, left for the user to review
+    pub deferred_conflicts: Vec<String>,
+
     /// Message shown in the status bar
     #[serde(skip)]
     pub msg: String,
@@ -51,11 +74,28 @@ impl State {
             &mut self.items,
             self.only_sync,
             self.allow_delete,
+            ConflictPolicy::Abort,
         ) {
             self.msg = err.to_string();
             self.items.clear();
         }
     }
+
+    /// The scheduled pair of the current source and target directories
+    pub fn current_pair(&self) -> Option<usize> {
+        self.pairs
+            .iter()
+            .position(|pair| pair.source == self.source && pair.target == self.target)
+    }
+
+    /// Resets the schedule of the pair being synced manually
+    pub fn reschedule_current_pair(&mut self) {
+        if let Some(idx) = self.current_pair()
+            && self.pairs[idx].enabled
+        {
+            self.pairs[idx].reschedule();
+        }
+    }
 }
 
 impl App {
@@ -82,6 +122,10 @@ impl App {
             syncer,
             handle,
             selector,
+            window: PersistentWindow::new(cc),
+            scheduler: Scheduler::default(),
+            waker: Waker::new(cc.egui_ctx.clone(), WakeType::Independent),
+            next_wake: None,
         };
 
         this.rebuild_fonts(&cc.egui_ctx);
@@ -91,6 +135,27 @@ impl App {
 
     fn update_syncer(&mut self) {
         let syncer = self.syncer.as_mut().unwrap();
+
+        if let Some(job) = &mut self.scheduler.job {
+            while let Some(result) = syncer.update_once(&mut job.items) {
+                match result {
+                    Ok(true) => {
+                        let target = &self.state.pairs[job.pair].target;
+                        if self.state.allow_delete
+                            && let Err(err) = sync::remove_empty_dirs(target)
+                        {
+                            job.errors.push(err.to_string());
+                        }
+                        self.scheduler.finish(&mut self.state.pairs);
+                        break;
+                    }
+                    Ok(false) => (),
+                    Err(err) => job.errors.push(err),
+                }
+            }
+            return;
+        }
+
         while let Some(result) = syncer.update_once(&mut self.state.items) {
             match result {
                 Ok(true) => {
@@ -109,6 +174,173 @@ impl App {
 }
 
 impl App {
+    /// Queues due pairs and starts the next one when the syncer is idle
+    fn update_scheduler(&mut self) {
+        self.scheduler.poll(&mut self.state.pairs);
+
+        let syncer = self.syncer.as_mut().unwrap();
+        if !syncer.synchronizing()
+            && self.scheduler.job.is_none()
+            && let Some(conflicts) = self
+                .scheduler
+                .start_next(&mut self.state.pairs, self.state.allow_delete)
+        {
+            let job = self.scheduler.job.as_ref().unwrap();
+            let pair = &self.state.pairs[job.pair];
+            for conflict in conflicts {
+                let conflict = format!("{} => {}: {conflict}", pair.source, pair.target);
+                if !self.state.deferred_conflicts.contains(&conflict) {
+                    self.state.deferred_conflicts.push(conflict);
+                }
+            }
+
+            if job.items.iter().any(|item| item.should_sync()) {
+                syncer.sync(&job.items);
+            } else {
+                self.scheduler.finish(&mut self.state.pairs);
+            }
+        }
+
+        let next_wake = Scheduler::next_wake(&self.state.pairs);
+        if next_wake != self.next_wake {
+            self.next_wake = next_wake;
+            if let Some(next_wake) = next_wake {
+                self.waker.wake_at(next_wake);
+            }
+        }
+    }
+
+    fn ui_pairs(&mut self, ui: &mut egui::Ui) {
+        let can_add = !self.state.source.is_empty()
+            && !self.state.target.is_empty()
+            && self.state.current_pair().is_none();
+
+        let header = format!("scheduled pairs ({})", self.state.pairs.len());
+        egui::CollapsingHeader::new(header)
+            .id_salt("scheduled_pairs")
+            .show(ui, |ui| {
+                if ui
+                    .add_enabled(
+                        can_add,
+                        egui::Button::new(format!("{ICON_ADD} add current")),
+                    )
+                    .on_hover_text("Sync the current source and target directories on a schedule")
+                    .clicked()
+                {
+                    self.state.pairs.push(Pair {
+                        source: self.state.source.clone(),
+                        target: self.state.target.clone(),
+                        ..Default::default()
+                    });
+                }
+
+                let mut pair_to_remove = None;
+
+                egui::Grid::new("pairs_grid")
+                    .num_columns(6)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (idx, pair) in self.state.pairs.iter_mut().enumerate() {
+                            let running = self.scheduler.job.as_ref().filter(|j| j.pair == idx);
+
+                            toggle_ui(ui, &mut pair.enabled).on_hover_text("Enable schedule");
+
+                            let label = format!("{} => {}", pair.source, pair.target);
+                            if ui
+                                .selectable_label(
+                                    pair.source == self.state.source
+                                        && pair.target == self.state.target,
+                                    label,
+                                )
+                                .on_hover_text("Load into the source and target directories")
+                                .clicked()
+                            {
+                                self.state.source = pair.source.clone();
+                                self.state.target = pair.target.clone();
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.label("every");
+                                if unit_drag(ui, &mut pair.interval_mins, "min", 1..=7 * 24 * 60)
+                                    && pair.enabled
+                                {
+                                    pair.reschedule();
+                                }
+                            });
+
+                            ui.checkbox(&mut pair.require_target, "target present")
+                                .on_hover_text("Skip runs while the target directory is missing");
+
+                            ui.horizontal(|ui| {
+                                if let Some(failure) = &pair.failure
+                                    && ui
+                                        .button(
+                                            RichText::new(format!("{ICON_WARNING} failed"))
+                                                .color(ui.visuals().error_fg_color),
+                                        )
+                                        .on_hover_text(format!("{failure}\n\nClick to acknowledge"))
+                                        .clicked()
+                                {
+                                    pair.failure = None;
+                                }
+
+                                ui.label(pair_status(
+                                    pair,
+                                    running.map(|j| {
+                                        let total =
+                                            j.items.iter().filter(|i| i.should_sync()).count();
+                                        let done =
+                                            j.items.iter().filter(|i| i.progress == 1.0).count();
+                                        (done, total)
+                                    }),
+                                    self.scheduler.is_queued(idx),
+                                ));
+                            });
+
+                            if ui
+                                .add_enabled(
+                                    running.is_none(),
+                                    egui::Button::new(ICON_TRASH.to_string()).frame(false),
+                                )
+                                .on_hover_text("Remove")
+                                .clicked()
+                            {
+                                pair_to_remove = Some(idx);
+                            }
+
+                            ui.end_row();
+                        }
+                    });
+
+                if let Some(idx) = pair_to_remove {
+                    self.scheduler.remove(idx);
+                    self.state.pairs.remove(idx);
+                }
+
+                if !self.state.deferred_conflicts.is_empty() {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "{} conflicts deferred by automatic runs",
+                            self.state.deferred_conflicts.len()
+                        ));
+                        if ui.button("clear").clicked() {
+                            self.state.deferred_conflicts.clear();
+                        }
+                    });
+
+                    egui::ScrollArea::vertical()
+                        .id_salt("deferred_conflicts")
+                        .max_height(120.0)
+                        .show(ui, |ui| {
+                            for conflict in &self.state.deferred_conflicts {
+                                ui.label(RichText::new(conflict).color(ui.visuals().warn_fg_color));
+                            }
+                        });
+                }
+            });
+    }
+
     fn ui_title_bar(&mut self, ui: &mut egui::Ui, title_bar_rect: egui::Rect) {
         borderless::title_bar(ui, title_bar_rect, |ui| {
             ui.add_space(8.0);
@@ -206,16 +438,21 @@ impl App {
                     let synchronizing = syncer.synchronizing();
 
                     btn!(0, "refresh", !synchronizing, self.state.get_items());
-                    btn!(1, "sync", !synchronizing, syncer.sync(&self.state.items));
+                    btn!(1, "sync", !synchronizing, {
+                        syncer.sync(&self.state.items);
+                        self.state.reschedule_current_pair();
+                    });
 
                     let synchronizing = syncer.synchronizing();
                     btn!(2, "cancel", synchronizing, syncer.cancel());
                 });
 
+                self.ui_pairs(ui);
+
                 ui.separator();
 
                 let synchronizing = self.syncer.as_ref().unwrap().synchronizing();
-                if synchronizing {
+                if synchronizing && self.scheduler.job.is_none() {
                     ui.label(format!(
                         "Synchronizing: {} / {}",
                         self.state
@@ -254,7 +491,11 @@ impl App {
                 let item = &self.state.items[i];
 
                 if ui.button("show").clicked() {
-                    eapp_utils::open_in_explorer(item.get_path().to_string_lossy().as_ref());
+                    if let Err(err) =
+                        eapp_utils::open_in_explorer(item.get_path().to_string_lossy().as_ref())
+                    {
+                        log::error!("open in explorer fails: {err}");
+                    }
                 }
 
                 if synchronizing && item.should_sync() {
@@ -299,6 +540,45 @@ impl App {
     }
 }
 
+fn pair_status(pair: &Pair, running: Option<(usize, usize)>, queued: bool) -> String {
+    let mut status = if let Some((done, total)) = running {
+        format!("syncing {done} / {total}")
+    } else if queued {
+        "queued".to_owned()
+    } else if let Some(last_run) = &pair.last_run {
+        let result = match &last_run.outcome {
+            _ if last_run.skipped => "skipped, target missing".to_owned(),
+            Ok(count) => format!("{count} synced in {}", format_duration(last_run.duration)),
+            Err(_) => format!("failed after {}", format_duration(last_run.duration)),
+        };
+        format!(
+            "{result}, {} ago",
+            format_duration(last_run.finished.elapsed())
+        )
+    } else {
+        "not run yet".to_owned()
+    };
+
+    if let Some(next_run) = pair.next_run
+        && running.is_none()
+        && !queued
+    {
+        let delay = next_run.saturating_duration_since(Instant::now());
+        status += &format!(", next in {}", format_duration(delay));
+    }
+
+    status
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{:.1}s", duration.as_secs_f32()),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
 impl eframe::App for App {
     fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
         egui::Rgba::TRANSPARENT.to_array()
@@ -325,6 +605,7 @@ impl eframe::App for App {
             .shrink2(Vec2::new(1.5, 1.0));
 
             self.update_syncer();
+            self.update_scheduler();
 
             self.ui_title_bar(ui, title_bar_rect);
 
@@ -337,6 +618,7 @@ impl eframe::App for App {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
         eframe::set_value(storage, eframe::APP_KEY, &self.state);
         eframe::set_value(storage, UiFontSelector::KEY, &self.selector);
+        self.window.save(storage);
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
@@ -1,10 +1,11 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 pub(crate) mod app;
+pub(crate) mod schedule;
 pub(crate) mod sync;
 
 fn main() {
-    eapp_utils::setup_loggers("syncer.log").unwrap();
+    eapp_utils::setup_loggers("syncer.log", eapp_utils::LogOptions::from_env()).unwrap();
 
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
@@ -18,6 +19,7 @@ fn main() {
                 ))
                 .unwrap(),
             ),
+        persist_window: false,
         ..Default::default()
     };
 
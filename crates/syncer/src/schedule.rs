@@ -0,0 +1,214 @@
+use crate::sync::{self, ConflictPolicy, Item};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+/// A source and target directory synced automatically
+#[derive(Deserialize, Serialize)]
+#[serde(default)]
+pub struct Pair {
+    pub source: String,
+    pub target: String,
+
+    pub enabled: bool,
+    pub interval_mins: u32,
+
+    /// Skip runs while the target directory is missing, e.g. its drive is unplugged
+    pub require_target: bool,
+
+    /// Failure of the last automatic run, shown until acknowledged
+    pub failure: Option<String>,
+
+    #[serde(skip)]
+    pub last_run: Option<RunResult>,
+
+    #[serde(skip)]
+    pub next_run: Option<Instant>,
+}
+
+impl Default for Pair {
+    fn default() -> Self {
+        Self {
+            source: String::new(),
+            target: String::new(),
+            enabled: true,
+            interval_mins: 30,
+            require_target: true,
+            failure: None,
+            last_run: None,
+            next_run: None,
+        }
+    }
+}
+
+impl Pair {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(u64::from(self.interval_mins.max(1)) * 60)
+    }
+
+    /// Schedules the next run one interval from now
+    pub fn reschedule(&mut self) {
+        self.next_run = Some(Instant::now() + self.interval());
+    }
+
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.enabled && self.next_run.is_some_and(|next_run| next_run <= now)
+    }
+}
+
+pub struct RunResult {
+    pub finished: Instant,
+    pub duration: Duration,
+
+    /// Number of synced items, or why the run failed
+    pub outcome: Result<usize, String>,
+
+    /// Whether the run was skipped because the target is missing
+    pub skipped: bool,
+}
+
+/// An automatic sync of a pair, in progress
+pub struct Job {
+    pub pair: usize,
+    pub started: Instant,
+    pub items: Vec<Item>,
+    pub errors: Vec<String>,
+}
+
+/// Queues due pairs, so that only one sync runs at a time
+#[derive(Default)]
+pub struct Scheduler {
+    queue: VecDeque<usize>,
+    pub job: Option<Job>,
+}
+
+impl Scheduler {
+    /// Schedules newly enabled pairs and queues pairs which are due
+    pub fn poll(&mut self, pairs: &mut [Pair]) {
+        let now = Instant::now();
+
+        for (idx, pair) in pairs.iter_mut().enumerate() {
+            if !pair.enabled {
+                pair.next_run = None;
+                continue;
+            }
+
+            if pair.next_run.is_none() {
+                pair.reschedule();
+            }
+
+            let busy =
+                self.queue.contains(&idx) || self.job.as_ref().is_some_and(|j| j.pair == idx);
+            if pair.is_due(now) && !busy {
+                self.queue.push_back(idx);
+            }
+        }
+    }
+
+    /// The earliest scheduled run of all pairs
+    pub fn next_wake(pairs: &[Pair]) -> Option<Instant> {
+        pairs.iter().filter_map(|pair| pair.next_run).min()
+    }
+
+    pub fn is_queued(&self, idx: usize) -> bool {
+        self.queue.contains(&idx)
+    }
+
+    /// Starts the next queued pair which can run, returns the deferred conflicts of
+    /// its listing if one is started, items of the job are left for the syncer
+    pub fn start_next(&mut self, pairs: &mut [Pair], allow_delete: bool) -> Option<Vec<String>> {
+        assert!(self.job.is_none());
+
+        while let Some(idx) = self.queue.pop_front() {
+            let pair = &mut pairs[idx];
+            pair.reschedule();
+
+            if pair.require_target && !Path::new(&pair.target).is_dir() {
+                pair.last_run = Some(RunResult {
+                    finished: Instant::now(),
+                    duration: Duration::ZERO,
+                    outcome: Ok(0),
+                    skipped: true,
+                });
+                continue;
+            }
+
+            let started = Instant::now();
+            let mut items = Vec::new();
+            match sync::get_items(
+                &pair.source,
+                &pair.target,
+                &mut items,
+                true,
+                allow_delete,
+                ConflictPolicy::Defer,
+            ) {
+                Ok(conflicts) => {
+                    self.job = Some(Job {
+                        pair: idx,
+                        started,
+                        items,
+                        errors: Vec::new(),
+                    });
+                    return Some(conflicts);
+                }
+                Err(err) => {
+                    let err = err.to_string();
+                    pair.failure = Some(err.clone());
+                    pair.last_run = Some(RunResult {
+                        finished: Instant::now(),
+                        duration: started.elapsed(),
+                        outcome: Err(err),
+                        skipped: false,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Records the end of the current job
+    pub fn finish(&mut self, pairs: &mut [Pair]) {
+        let Some(job) = self.job.take() else {
+            return;
+        };
+
+        let pair = &mut pairs[job.pair];
+        let outcome = if job.errors.is_empty() {
+            Ok(job.items.iter().filter(|item| item.should_sync()).count())
+        } else {
+            let err = job.errors.join("\n");
+            pair.failure = Some(err.clone());
+            Err(err)
+        };
+
+        pair.last_run = Some(RunResult {
+            finished: Instant::now(),
+            duration: job.started.elapsed(),
+            outcome,
+            skipped: false,
+        });
+    }
+
+    /// Forgets pair `idx`, which is being removed
+    pub fn remove(&mut self, idx: usize) {
+        assert!(self.job.as_ref().is_none_or(|job| job.pair != idx));
+
+        self.queue.retain(|i| *i != idx);
+        for i in self.queue.iter_mut() {
+            if *i > idx {
+                *i -= 1;
+            }
+        }
+
+        if let Some(job) = &mut self.job
+            && job.pair > idx
+        {
+            job.pair -= 1;
+        }
+    }
+}
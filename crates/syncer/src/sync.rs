@@ -15,6 +15,16 @@ pub enum ItemCmd {
     Keep,
 }
 
+/// What to do with items which cannot be compared safely
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    /// Fail the whole listing, so that the user looks into it
+    Abort,
+
+    /// Leave the item out and report it, so that unattended runs are not blocked
+    Defer,
+}
+
 pub enum SyncCmd {
     Sync(Vec<Option<SyncItem>>),
     Cancel,
@@ -248,15 +258,31 @@ impl Syncer {
     }
 }
 
+/// Lists items of `source` to sync into `target`, returns conflicts deferred by `policy`
 pub fn get_items(
     source: &str,
     target: &str,
     items: &mut Vec<Item>,
     only_sync: bool,
     allow_delete: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+    policy: ConflictPolicy,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     items.clear();
 
+    let mut conflicts = Vec::new();
+    macro_rules! conflict {
+        ($($arg:tt)*) => {{
+            let msg = format!($($arg)*);
+            match policy {
+                ConflictPolicy::Abort => return Err(msg.into()),
+                ConflictPolicy::Defer => {
+                    conflicts.push(msg);
+                    continue;
+                }
+            }
+        }};
+    }
+
     let source_dir_path = Path::new(source);
     let target_dir_path = Path::new(target);
 
@@ -269,11 +295,10 @@ pub fn get_items(
 
         let target_path = target_dir_path.join(source_path.strip_prefix(source_dir_path)?);
         if target_path.exists() && !target_path.is_file() {
-            return Err(format!(
+            conflict!(
                 "Got same name item, but which is not file '{}'",
                 target_path.display()
-            )
-            .into());
+            );
         }
 
         let filename = source_path
@@ -299,11 +324,10 @@ pub fn get_items(
                 Ordering::Less => ItemCmd::Keep,
                 Ordering::Equal => {
                     if source_meta.len() != target_meta.len() {
-                        return Err(format!(
+                        conflict!(
                             "Files with the same modification time but different sizes: '{}'",
                             target_path.display()
-                        )
-                        .into());
+                        );
                     }
 
                     ItemCmd::Keep
@@ -360,7 +384,7 @@ pub fn get_items(
         }
     }
 
-    Ok(())
+    Ok(conflicts)
 }
 
 pub fn remove_empty_dirs(path: impl AsRef<Path>) -> std::io::Result<()> {
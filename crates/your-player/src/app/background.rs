@@ -22,6 +22,11 @@ impl super::App {
         };
 
         if self.player.state().play_state == PlayState::Stop || playing_no_cover_audio {
+            // The error of the failed media is shown instead
+            if state.failed_media.is_some() {
+                return;
+            }
+
             ui.painter().text(
                 rect.center(),
                 Align2::CENTER_CENTER,
@@ -57,7 +62,12 @@ impl super::App {
     fn ui_show_danmu(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
         let playback_time = self.player.state().playback_time;
 
-        let elapsed_time = playback_time - self.state.last_playback_time;
+        // the frozen danmu are still painted while paused
+        let elapsed_time = if self.player.state().play_state.is_playing() {
+            playback_time - self.state.last_playback_time
+        } else {
+            0.0
+        };
 
         self.state.last_playback_time = playback_time;
         self.danmu.render(ui, rect, elapsed_time);
@@ -0,0 +1,189 @@
+use super::saved_window::SavedWindow;
+use crate::mpv::{self, player::PlayState};
+use eapp_utils::{
+    codicons::{
+        ICON_ARROW_CIRCLE_LEFT, ICON_ARROW_CIRCLE_RIGHT, ICON_CHROME_CLOSE, ICON_DEBUG_PAUSE,
+        ICON_MUTE, ICON_PLAY_CIRCLE, ICON_SCREEN_FULL, ICON_UNMUTE,
+    },
+    widgets::{
+        progress_bar::{ProgressBar, value_from_x},
+        simple_widgets::PlainButton,
+    },
+};
+use eframe::egui::{
+    self, CornerRadius, Frame, Id, Sense, UiBuilder, ViewportCommand, Widget as _, WindowLevel,
+    vec2,
+};
+
+impl super::App {
+    pub const COMPACT_SIZE: egui::Vec2 = vec2(360.0, 120.0);
+
+    pub fn toggle_compact(&mut self, ctx: &egui::Context) {
+        if self.state.compact.is_some() {
+            self.exit_compact(ctx);
+        } else {
+            self.enter_compact(ctx);
+        }
+    }
+
+    pub fn enter_compact(&mut self, ctx: &egui::Context) {
+        if self.state.compact.is_some() || self.state.pip.is_some() {
+            return;
+        }
+
+        self.state.compact = Some(SavedWindow::take(ctx));
+
+        self.state.volume_popup_open = false;
+        self.state.chapters_popup_open = false;
+        self.state.setting_popup_open = false;
+        self.state.long_setting_popup_open = false;
+
+        Self::apply_compact_viewport(ctx);
+    }
+
+    pub fn exit_compact(&mut self, ctx: &egui::Context) {
+        let Some(compact) = self.state.compact.take() else {
+            return;
+        };
+
+        compact.restore(ctx);
+    }
+
+    /// Shrinks the window and keeps it on top, the window level is not restored by eframe
+    pub fn apply_compact_viewport(ctx: &egui::Context) {
+        ctx.send_viewport_cmd(ViewportCommand::MinInnerSize(Self::COMPACT_SIZE));
+        ctx.send_viewport_cmd(ViewportCommand::InnerSize(Self::COMPACT_SIZE));
+        ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::AlwaysOnTop));
+    }
+
+    pub fn ui_compact_contents(&mut self, ui: &mut egui::Ui) {
+        egui::CentralPanel::default()
+            .frame(Frame::NONE)
+            .show_inside(ui, |ui| {
+                let app_rect = ui.max_rect();
+                self.state.content_rect = app_rect;
+
+                let response = ui.interact(app_rect, Id::new("compact_drag"), Sense::click());
+                if response.double_clicked() {
+                    self.exit_compact(ui.ctx());
+                    return;
+                }
+                if response.is_pointer_button_down_on() {
+                    ui.ctx().send_viewport_cmd(ViewportCommand::StartDrag);
+                }
+
+                ui.scope_builder(UiBuilder::new().max_rect(app_rect.shrink(8.0)), |ui| {
+                    ui.visuals_mut().override_text_color = Some(ui.visuals().strong_text_color());
+                    self.ui_compact_items(ui);
+                });
+            });
+    }
+
+    fn ui_compact_items(&mut self, ui: &mut egui::Ui) {
+        let hover_color = ui.visuals().selection.bg_fill;
+        let new_button = |size: f32, font_size, str| {
+            PlainButton::new(vec2(size, size), str)
+                .font_size(font_size)
+                .corner_radius(CornerRadius::same(2))
+                .hover(hover_color)
+        };
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui
+                    .add(new_button(24.0, 14.0, ICON_CHROME_CLOSE.to_string()))
+                    .clicked()
+                {
+                    ui.ctx().send_viewport_cmd(ViewportCommand::Close);
+                }
+
+                if ui
+                    .add(new_button(24.0, 14.0, ICON_SCREEN_FULL.to_string()))
+                    .on_hover_text("Exit compact mode (C)")
+                    .clicked()
+                {
+                    self.exit_compact(ui.ctx());
+                }
+
+                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                    ui.add(
+                        egui::Label::new(&self.player.state().media_title)
+                            .wrap_mode(egui::TextWrapMode::Truncate),
+                    );
+                });
+            });
+        });
+
+        let duration = self.player.state().duration;
+        let response = ProgressBar::new(self.player.state().playback_time, duration)
+            .height(12.0)
+            .knob_radius(4.0)
+            .ui(ui);
+
+        if response.dragged()
+            && let Some(pointer) = response.interact_pointer_pos()
+        {
+            self.player
+                .seek(value_from_x(duration, response.rect, pointer.x as _), false);
+        }
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+
+            if ui
+                .add(new_button(32.0, 22.0, ICON_ARROW_CIRCLE_LEFT.to_string()))
+                .clicked()
+                && let Some(new_media) = self.playlist.prev_item()
+            {
+                self.set_media(&new_media);
+            }
+
+            let is_pause = !self.player.state().play_state.is_playing();
+            let icon = if is_pause {
+                ICON_PLAY_CIRCLE
+            } else {
+                ICON_DEBUG_PAUSE
+            }
+            .to_string();
+            if ui.add(new_button(32.0, 22.0, icon)).clicked() {
+                self.player.set_play_state(if is_pause {
+                    PlayState::Play
+                } else {
+                    PlayState::Pause
+                });
+            }
+
+            if ui
+                .add(new_button(32.0, 22.0, ICON_ARROW_CIRCLE_RIGHT.to_string()))
+                .clicked()
+                && let Some(new_media) = self.playlist.next_item()
+            {
+                self.set_media(&new_media);
+            }
+
+            let mute = self.player.state().mute;
+            let icon = if mute { ICON_MUTE } else { ICON_UNMUTE }.to_string();
+            if ui.add(new_button(32.0, 18.0, icon)).clicked() {
+                self.player.set_mute(!mute);
+            }
+
+            ui.spacing_mut().slider_width = 72.0;
+            let mut volume = self.player.state().volume;
+            if ui
+                .add(egui::Slider::new(&mut volume, 0..=130).show_value(false))
+                .changed()
+            {
+                self.player.set_volume(volume);
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.label(format!(
+                    "{} / {}",
+                    mpv::make_time_string(self.player.state().playback_time),
+                    mpv::make_time_string(duration)
+                ));
+            });
+        });
+    }
+}
@@ -1,3 +1,4 @@
+use super::shortcuts::Action;
 use crate::mpv::{self, player::PlayState};
 use eapp_utils::{
     borderless,
@@ -15,6 +16,16 @@ use eframe::egui::{
 
 impl super::App {
     pub fn ui_contents(&mut self, ui: &mut egui::Ui) {
+        if self.state.compact.is_some() {
+            self.ui_compact_contents(ui);
+            return;
+        }
+
+        if self.state.pip.is_some() {
+            self.ui_pip_contents(ui);
+            return;
+        }
+
         egui::CentralPanel::default()
             .frame(Frame::NONE)
             .show_inside(ui, |ui| {
@@ -27,6 +38,8 @@ impl super::App {
                     rect.max.y = rect.min.y + title_bar_height;
                     rect
                 };
+                self.ui_gestures(ui, app_rect);
+                self.ui_playback_overlay(ui, app_rect);
                 borderless::title_bar_animated(ui, title_bar_rect);
 
                 let size = 20.0;
@@ -151,7 +164,7 @@ impl super::App {
                         let (_, rect) = ui.allocate_space(size);
                         if !self.player.state().is_audio
                             && let Some(tex) = self.preview.get(hover_time)
-                            && let Some(tex_id) = self.tex_register.get(*tex)
+                            && let Some(tex_id) = self.tex_register.get(tex)
                         {
                             egui::Image::from_texture(SizedTexture::new(tex_id, size))
                                 .corner_radius(4)
@@ -200,7 +213,7 @@ impl super::App {
         painter.text(
             pos2(rect.left(), rect.bottom() - btn_size),
             Align2::LEFT_CENTER,
-            format!("{playback_time} / {duration}"),
+            format!("{playback_time} / {duration}{}", self.sleep_indicators(ui)),
             get_body_font_id(ui),
             ui.visuals().text_color(),
         );
@@ -292,7 +305,7 @@ impl super::App {
             });
 
             let right_btns_rect = {
-                let width = btn_size * 5.0;
+                let width = btn_size * 8.0;
                 Rect::from_center_size(
                     pos2(rect.right() - width / 2.0, rect.bottom() - btn_size),
                     vec2(width, btn_size),
@@ -322,6 +335,8 @@ impl super::App {
                         };
                     }
 
+                    simple_popup!(ICON_WATCH.to_string(), sleep_popup_open, ui_sleep_popup);
+
                     simple_popup!(
                         ICON_REFERENCES.to_string(),
                         chapters_popup_open,
@@ -340,6 +355,28 @@ impl super::App {
                         ui_long_setting_popup
                     );
 
+                    if ui
+                        .add(new_button(16.0, ICON_MULTIPLE_WINDOWS.to_string()))
+                        .on_hover_text(format!(
+                            "Compact mode ({})",
+                            self.shortcuts.format(ui.ctx(), Action::Compact)
+                        ))
+                        .clicked()
+                    {
+                        self.enter_compact(ui.ctx());
+                    }
+
+                    if ui
+                        .add(new_button(16.0, ICON_PIN.to_string()))
+                        .on_hover_text(format!(
+                            "Picture-in-picture, a small window kept on top ({})",
+                            self.shortcuts.format(ui.ctx(), Action::Pip)
+                        ))
+                        .clicked()
+                    {
+                        self.enter_pip(ui.ctx());
+                    }
+
                     if ui.add(new_button(16.0, ICON_INSPECT.to_string())).clicked()
                         && self.tex_register.get(*self.player.texture()).is_some()
                     {
@@ -0,0 +1,190 @@
+use crate::mpv::{self, player::PlayState};
+use eapp_utils::get_body_font_id;
+use eframe::egui::{self, Align2, Id, Sense, ViewportCommand};
+
+/// What a drag on the video does, decided once it moved far enough
+#[derive(Clone, Copy, PartialEq)]
+enum Gesture {
+    /// Vertical drag on the right third
+    Volume { start: i64 },
+
+    /// Vertical drag on the left third
+    Brightness { start: i64 },
+
+    /// Horizontal drag anywhere
+    Seek { start: f64, delta: f64 },
+
+    /// Started from a place that gestures don't handle
+    Ignore,
+}
+
+#[derive(Default)]
+pub struct GestureState {
+    gesture: Option<Gesture>,
+
+    /// Text of the overlay and the time to hide it
+    overlay: Option<(String, f64)>,
+}
+
+impl super::App {
+    /// Drag distance before a drag is committed to a gesture
+    const GESTURE_MIN_DISTANCE: f32 = 16.0;
+
+    /// Seconds to seek when dragging across the whole width
+    const GESTURE_SEEK_RANGE: f64 = 180.0;
+
+    const GESTURE_OVERLAY_SECS: f64 = 0.8;
+
+    /// Handles gestures on the video, must be called before other widgets in `rect`
+    /// so that they are hit first
+    pub fn ui_gestures(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        if !self.state.enable_gestures {
+            return;
+        }
+
+        let response = ui.interact(rect, Id::new("video_gestures"), Sense::click_and_drag());
+        let no_focus = ui.memory(|m| m.focused().is_none());
+
+        if response.double_clicked() && no_focus {
+            let is_fullscreen = ui.input(|i| i.viewport().fullscreen.unwrap_or(false));
+            ui.ctx()
+                .send_viewport_cmd(ViewportCommand::Fullscreen(!is_fullscreen));
+        }
+
+        if response.drag_started() {
+            // Near the window edges the drag is a resize
+            let near_edge = ui
+                .input(|i| i.pointer.press_origin())
+                .is_none_or(|pos| !ui.ctx().screen_rect().shrink(8.0).contains(pos));
+            self.state.gesture.gesture =
+                (!no_focus || near_edge || self.player.state().play_state == PlayState::Stop)
+                    .then_some(Gesture::Ignore);
+        }
+
+        if response.dragged()
+            && let Some(origin) = ui.input(|i| i.pointer.press_origin())
+            && let Some(pos) = response.interact_pointer_pos()
+        {
+            let delta = pos - origin;
+            if self.state.gesture.gesture.is_none() && delta.length() >= Self::GESTURE_MIN_DISTANCE
+            {
+                self.state.gesture.gesture = Some(self.begin_gesture(rect, origin, delta));
+            }
+
+            if let Some(gesture) = &mut self.state.gesture.gesture {
+                let text = match gesture {
+                    Gesture::Volume { start } => {
+                        let volume =
+                            (*start - (delta.y / rect.height() * 100.0) as i64).clamp(0, 130);
+                        if volume != self.player.state().volume {
+                            self.player.set_volume(volume);
+                        }
+                        Some(format!("Volume {volume}%"))
+                    }
+                    Gesture::Brightness { start } => {
+                        let brightness =
+                            (*start - (delta.y / rect.height() * 200.0) as i64).clamp(-100, 100);
+                        if brightness != self.player.state().brightness {
+                            self.player.set_brightness(brightness);
+                        }
+                        Some(format!("Brightness {brightness}"))
+                    }
+                    Gesture::Seek { start, delta: seek } => {
+                        let duration = self.player.state().duration;
+                        *seek = (delta.x / rect.width()) as f64 * Self::GESTURE_SEEK_RANGE;
+                        *seek = seek.clamp(-*start, (duration - *start).max(0.0));
+                        Some(format!(
+                            "{} ({})",
+                            make_delta_string(*seek),
+                            mpv::make_time_string(*start + *seek)
+                        ))
+                    }
+                    Gesture::Ignore => None,
+                };
+
+                if let Some(text) = text {
+                    let hide_time = ui.input(|i| i.time) + Self::GESTURE_OVERLAY_SECS;
+                    self.state.gesture.overlay = Some((text, hide_time));
+                }
+            }
+        }
+
+        if response.drag_stopped() {
+            if let Some(Gesture::Seek { delta, .. }) = self.state.gesture.gesture
+                && delta != 0.0
+            {
+                self.player.seek(delta, true);
+            }
+            self.state.gesture.gesture = None;
+        }
+
+        self.ui_gesture_overlay(ui, rect);
+    }
+
+    fn begin_gesture(&self, rect: egui::Rect, origin: egui::Pos2, delta: egui::Vec2) -> Gesture {
+        let third = rect.width() / 3.0;
+        if delta.x.abs() > delta.y.abs() {
+            Gesture::Seek {
+                start: self.player.state().playback_time,
+                delta: 0.0,
+            }
+        } else if origin.x < rect.left() + third {
+            Gesture::Brightness {
+                start: self.player.state().brightness,
+            }
+        } else if origin.x > rect.right() - third {
+            Gesture::Volume {
+                start: self.player.state().volume,
+            }
+        } else {
+            Gesture::Ignore
+        }
+    }
+
+    fn ui_gesture_overlay(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        let Some((text, hide_time)) = &self.state.gesture.overlay else {
+            return;
+        };
+
+        let now = ui.input(|i| i.time);
+        let dragging = self
+            .state
+            .gesture
+            .gesture
+            .is_some_and(|g| g != Gesture::Ignore);
+        if !dragging && now >= *hide_time {
+            self.state.gesture.overlay = None;
+            return;
+        }
+        ui.ctx()
+            .request_repaint_after_secs((*hide_time - now).max(0.0) as f32);
+
+        let painter = ui.painter();
+        let galley = painter.layout_no_wrap(
+            text.clone(),
+            get_body_font_id(ui),
+            ui.visuals().strong_text_color(),
+        );
+        let bg_rect = egui::Rect::from_center_size(rect.center(), galley.size())
+            .expand2(egui::vec2(16.0, 8.0));
+        painter.rect_filled(
+            bg_rect,
+            8,
+            ui.visuals().extreme_bg_color.gamma_multiply(0.8),
+        );
+        painter.galley(
+            Align2::CENTER_CENTER
+                .align_size_within_rect(galley.size(), bg_rect)
+                .min,
+            galley,
+            ui.visuals().strong_text_color(),
+        );
+    }
+}
+
+/// e.g. `+01:05`, `-12:30`
+fn make_delta_string(seconds: f64) -> String {
+    let sign = if seconds < 0.0 { '-' } else { '+' };
+    let seconds = seconds.abs() as i64;
+    format!("{sign}{:02}:{:02}", seconds / 60, seconds % 60)
+}
@@ -2,21 +2,37 @@ use crate::danmu;
 use crate::{
     mpv::{self, player::PlayState},
     playlist::Playlist,
+    subtitle::Subtitles,
     tex_register::TexRegister,
 };
-use eapp_utils::ui_font_selector::UiFontSelector;
 use eapp_utils::{
     borderless,
+    debounced::Debounced,
+    dnd::{DropCategory, DropHandler, DroppedItem},
+    shortcuts::ShortcutMap,
     waker::{WakeType, Waker},
 };
+use eapp_utils::{persistent_window::PersistentWindow, ui_font_selector::UiFontSelector};
 use eframe::egui::{self, CornerRadius, ViewportCommand};
 use serde::{Deserialize, Serialize};
 
 mod background;
+mod compact;
 mod contents;
+mod gesture;
+mod open_url;
 mod opts_highlight;
+mod pip;
+mod playback_overlay;
 mod playlist;
 mod popups;
+mod repaint;
+mod saved_window;
+mod shortcuts;
+mod sleep_timer;
+mod subtitles;
+
+use shortcuts::Action;
 
 pub struct App {
     state: State,
@@ -26,7 +42,15 @@ pub struct App {
     preview: mpv::preview::Preview,
     tex_register: TexRegister,
     danmu: danmu::Manager,
+    subtitles: Subtitles,
     selector: UiFontSelector,
+    window: PersistentWindow,
+    drop_handler: DropHandler,
+    shortcuts: ShortcutMap<Action>,
+    repaint_stats: repaint::RepaintStats,
+    playlist_matches: playlist::PlaylistMatches,
+    /// List being renamed and the name typed, kept while its menu is open
+    list_rename: Option<(String, String)>,
 }
 
 #[derive(Deserialize, Serialize)]
@@ -45,6 +69,8 @@ pub struct State {
     pub setting_popup_open: bool,
     #[serde(skip)]
     pub long_setting_popup_open: bool,
+    #[serde(skip)]
+    pub sleep_popup_open: bool,
 
     /// how the setting to show
     #[serde(skip)]
@@ -58,16 +84,51 @@ pub struct State {
     #[serde(skip)]
     pub long_setting_type: LongSettingType,
 
+    /// profile shown in the profile editor
+    #[serde(skip)]
+    pub cur_profile: Option<String>,
+
+    #[serde(skip)]
+    pub profile_renaming: bool,
+
     /// filter keywords
     pub playlist_key: String,
 
+    /// `playlist_key` once typing pauses
+    #[serde(skip)]
+    pub playlist_filter: Debounced<String>,
+
     /// current selectd media by user
     #[serde(skip)]
     pub playlist_cur_sel: Option<(String, String)>,
 
+    /// scroll the playlist to the current play on next frame
+    #[serde(skip)]
+    pub scroll_to_current: bool,
+
+    /// filter keywords of subtitle cues
+    pub subtitle_key: String,
+
+    /// scroll the subtitle cues to the current one while playing
+    pub subtitle_follow: bool,
+
+    /// the current cue scrolled to
+    #[serde(skip)]
+    pub subtitle_last_cue: Option<usize>,
+
     /// how to do when media end reached
     pub end_reached: EndReached,
 
+    /// goes idle when the current media ends regardless `end_reached`, only once
+    #[serde(skip)]
+    pub stop_after_current: bool,
+
+    #[serde(skip)]
+    pub sleep_timer: sleep_timer::SleepTimer,
+
+    /// how to do when the sleep timer expires
+    pub sleep_action: sleep_timer::SleepAction,
+
     /// playback time of the simulation, for smoother danmu movement
     #[serde(skip)]
     pub last_playback_time: f64,
@@ -79,9 +140,6 @@ pub struct State {
     #[serde(skip)]
     pub last_prevent_sleep_time: f64,
 
-    #[serde(skip)]
-    pub last_playing_time: f64,
-
     #[serde(skip)]
     pub was_playing: bool,
 
@@ -97,25 +155,63 @@ pub struct State {
     pub danmu_font_path: String,
 
     pub enable_danmu: bool,
+
+    /// the window before compact mode, `Some` while in compact mode
+    pub compact: Option<saved_window::SavedWindow>,
+
+    /// the window before picture-in-picture, `Some` while in it, which is not kept across restarts
+    #[serde(skip)]
+    pub pip: Option<saved_window::SavedWindow>,
+
+    /// compact mode is only kept across restarts for audio
+    pub last_media_is_audio: bool,
+
+    /// double-click and drag gestures on the video
+    pub enable_gestures: bool,
+
+    #[serde(skip)]
+    pub gesture: gesture::GestureState,
+
+    /// longest side of the seek previews
+    pub preview_size: i64,
+
+    /// most seek previews kept in GPU memory
+    pub preview_capacity: usize,
+
+    /// recently played network streams, the latest first
+    pub url_history: Vec<String>,
+
+    #[serde(skip)]
+    pub url_dialog_open: bool,
+
+    #[serde(skip)]
+    pub url_input: String,
+
+    /// frames per second and the cause of the last repaint over the video, for debugging
+    pub show_repaint_stats: bool,
 }
 
 #[derive(PartialEq)]
 pub enum SettingType {
     Play,
     Color,
+    Audio,
     Danmu,
 }
 
 #[derive(PartialEq)]
 pub enum LongSettingType {
     MpvOptions,
+    Profiles,
     DanmuFonts,
+    Shortcuts,
 }
 
 #[derive(PartialEq)]
 pub enum PlaylistType {
     Playlist,
     Danmu,
+    Subtitles,
 }
 
 #[derive(PartialEq, Deserialize, Serialize, Clone, Copy)]
@@ -140,22 +236,43 @@ impl Default for State {
             chapters_popup_open: false,
             setting_popup_open: false,
             long_setting_popup_open: false,
+            sleep_popup_open: false,
             setting_type: SettingType::Play,
             playlist_type: PlaylistType::Playlist,
             long_setting_type: LongSettingType::MpvOptions,
+            cur_profile: None,
+            profile_renaming: false,
             playlist_key: String::default(),
+            playlist_filter: Debounced::new(String::default()).with_delay(App::FILTER_DELAY),
             playlist_cur_sel: None,
+            scroll_to_current: false,
+            subtitle_key: String::default(),
+            subtitle_follow: true,
+            subtitle_last_cue: None,
             end_reached: EndReached::Idle,
+            stop_after_current: false,
+            sleep_timer: Default::default(),
+            sleep_action: Default::default(),
             last_playback_time: 0.0,
             content_rect: egui::Rect::ZERO,
-            last_prevent_sleep_time: 0.0,
-            last_playing_time: 0.0,
+            last_prevent_sleep_time: f64::NEG_INFINITY,
             was_playing: true,
             danmu_regex_str: String::default(),
             danmu_regex: None,
             danmu_regex_err_str: None,
             danmu_font_path: String::default(),
             enable_danmu: true,
+            compact: None,
+            pip: None,
+            last_media_is_audio: false,
+            enable_gestures: true,
+            gesture: Default::default(),
+            preview_size: 200,
+            preview_capacity: mpv::preview::Preview::DEFAULT_CAPACITY,
+            url_history: Vec::new(),
+            url_dialog_open: false,
+            url_input: String::new(),
+            show_repaint_stats: false,
         }
     }
 }
@@ -166,6 +283,10 @@ impl App {
     pub const PLAYLIST_KEY: &'static str = "playlist_state";
     pub const DANMU_KEY: &'static str = "danmu_state";
 
+    pub const MIN_INNER_SIZE: egui::Vec2 = egui::vec2(640.0, 480.0);
+
+    pub const FILTER_DELAY: std::time::Duration = std::time::Duration::from_millis(250);
+
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
         cc.egui_ctx.style_mut(|style| style.animation_time = 0.11);
 
@@ -174,6 +295,7 @@ impl App {
         } else {
             State::default()
         };
+        state.playlist_filter.set_now(state.playlist_key.clone());
 
         let mpv_state = if let Some(storage) = cc.storage {
             eframe::get_value(storage, Self::MPV_KEY).unwrap_or_default()
@@ -203,7 +325,8 @@ impl App {
         };
 
         let tex_register = TexRegister::default();
-        let preview = mpv::preview::Preview::new(200, cc).unwrap();
+        let mut preview = mpv::preview::Preview::new(state.preview_size, cc).unwrap();
+        preview.set_capacity(state.preview_capacity);
 
         let danmu_state = if let Some(storage) = cc.storage {
             eframe::get_value(storage, Self::DANMU_KEY).unwrap_or_default()
@@ -222,7 +345,8 @@ impl App {
             };
         }
 
-        let waker = Waker::new(cc.egui_ctx.clone(), WakeType::WakeOnLongestDeadLine);
+        // each frame asks again for what it still needs, so the earliest of them is enough
+        let waker = Waker::new(cc.egui_ctx.clone(), WakeType::WakeOnEarliestDeadLine);
 
         let selector = if let Some(storage) = cc.storage {
             eframe::get_value(storage, UiFontSelector::KEY).unwrap_or_default()
@@ -230,6 +354,12 @@ impl App {
             UiFontSelector::default()
         };
 
+        let shortcuts = Self::shortcut_map(if let Some(storage) = cc.storage {
+            eframe::get_value(storage, Self::SHORTCUT_KEY).unwrap_or_default()
+        } else {
+            ShortcutMap::default()
+        });
+
         let mut this = Self {
             state,
             waker,
@@ -238,17 +368,43 @@ impl App {
             preview,
             tex_register,
             danmu,
+            subtitles: Subtitles::default(),
             selector,
+            window: PersistentWindow::new(cc),
+            drop_handler: DropHandler::new(&[
+                DropCategory::Media,
+                DropCategory::Subtitle,
+                DropCategory::Directory,
+            ])
+            .extensions(
+                DropCategory::Media,
+                &[&mpv::VIDEO_FORMATS[..], &mpv::AUDIO_FORMATS[..]].concat(),
+            ),
+            shortcuts,
+            repaint_stats: Default::default(),
+            playlist_matches: Default::default(),
+            list_rename: None,
         };
 
+        eapp_utils::diagnostics::remember_renderer(cc);
         this.rebuild_fonts(&cc.egui_ctx);
         this.selector.apply_text_style(&cc.egui_ctx);
 
-        if let Some(path_str) = std::env::args().nth(1)
-            && std::path::Path::new(&path_str).is_file()
-        {
-            this.set_media(&path_str);
-            this.playlist.set_current_play(None);
+        if this.state.compact.is_some() {
+            if this.state.last_media_is_audio {
+                Self::apply_compact_viewport(&cc.egui_ctx);
+            } else {
+                this.exit_compact(&cc.egui_ctx);
+            }
+        }
+
+        if let Some(path_str) = std::env::args().nth(1) {
+            if mpv::is_url(&path_str) {
+                this.open_url(&path_str);
+            } else if std::path::Path::new(&path_str).is_file() {
+                this.set_media(&path_str);
+                this.playlist.set_current_play(None);
+            }
         }
 
         this
@@ -256,8 +412,21 @@ impl App {
 
     /// set media to player and preview, regardless playlist
     pub fn set_media(&mut self, media_path: &str) {
+        self.player
+            .apply_profile(self.playlist.profile_for(media_path));
         self.player.set_media(media_path);
-        if !self.player.state().is_audio {
+        self.state.last_media_is_audio = self.player.state().is_audio;
+
+        // streams are not seeked for previews, and have no danmu beside them
+        if mpv::is_url(media_path) {
+            self.preview.unload();
+            self.danmu.clear();
+            return;
+        }
+
+        if self.player.state().is_audio {
+            self.preview.unload();
+        } else {
             self.preview.set_media(media_path);
         }
 
@@ -274,9 +443,20 @@ impl App {
         self.danmu.clear();
     }
 
+    /// Applies the profile of the current media again, once profiles are edited
+    pub fn reapply_profile(&mut self) {
+        let media_path = &self.player.state().media_path;
+        if media_path.is_empty() {
+            return;
+        }
+
+        let options = self.playlist.profile_for(media_path).map(str::to_owned);
+        self.player.apply_profile(options.as_deref());
+    }
+
     fn adjust(&self, corner_radius: CornerRadius) -> CornerRadius {
         let mut corner_radius = corner_radius;
-        if self.state.playlist_open {
+        if self.state.playlist_open && self.state.pip.is_none() {
             corner_radius.nw = 0;
             corner_radius.sw = 0;
         }
@@ -292,28 +472,40 @@ impl App {
     }
 
     fn process_inputs(&mut self, ui: &mut egui::Ui) {
+        if self.shortcuts.consume(ui, Action::OpenUrl) {
+            self.state.url_dialog_open = true;
+        }
+
         if ui.memory(|mem| mem.focused().is_none()) {
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
+            if self.shortcuts.consume(ui, Action::SeekBackward) {
                 self.player.seek(-0.5, true);
             }
 
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowRight)) {
+            if self.shortcuts.consume(ui, Action::SeekForward) {
                 self.player.seek(0.5, true);
             }
 
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
+            if self.shortcuts.consume(ui, Action::VolumeUp) {
                 self.player.set_volume(self.player.state().volume + 5);
             }
 
-            if ui.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
+            if self.shortcuts.consume(ui, Action::VolumeDown) {
                 self.player.set_volume(self.player.state().volume - 5);
             }
 
-            if ui.input(|i| i.key_pressed(egui::Key::M)) {
+            if self.shortcuts.consume(ui, Action::Mute) {
                 self.player.set_mute(!self.player.state().mute);
             }
 
-            if ui.input(|i| i.key_pressed(egui::Key::Space)) {
+            if self.shortcuts.consume(ui, Action::Compact) {
+                self.toggle_compact(ui.ctx());
+            }
+
+            if self.shortcuts.consume(ui, Action::Pip) {
+                self.toggle_pip(ui.ctx());
+            }
+
+            if self.shortcuts.consume(ui, Action::PlayPause) {
                 self.player
                     .set_play_state(if self.player.state().play_state.is_playing() {
                         PlayState::Pause
@@ -330,22 +522,29 @@ impl App {
                     .send_viewport_cmd(ViewportCommand::Fullscreen(false));
             }
 
-            let mut opt_path = None;
-            ui.ctx().input(|i| {
-                if !i.raw.dropped_files.is_empty()
-                    && let Some(path) = &i.raw.dropped_files.first().unwrap().path
-                {
-                    opt_path = Some(path.to_string_lossy().into_owned());
-                }
-            });
-
-            // we should be careful for deadlock
-            if let Some(path) = opt_path {
-                if std::path::Path::new(&path).is_file() {
-                    self.set_media(&path);
-                    self.playlist.set_current_play(None);
-                } else {
-                    self.playlist.add_list(path);
+            // the first media is played, the subtitles are added to it
+            let mut media_set = false;
+            let mut items = self.drop_handler.show(ui.ctx());
+            items.sort_by_key(|item| !matches!(item, DroppedItem::Path(DropCategory::Media, _)));
+            for item in items {
+                match item {
+                    DroppedItem::Path(DropCategory::Media, path) if !media_set => {
+                        self.set_media(&path.to_string_lossy());
+                        self.playlist.set_current_play(None);
+                        media_set = true;
+                    }
+                    DroppedItem::Path(DropCategory::Subtitle, path) => {
+                        self.player.add_subtitle(&path.to_string_lossy());
+                    }
+                    DroppedItem::Path(DropCategory::Directory, path) => {
+                        self.playlist.add_list(path.to_string_lossy().into_owned());
+                    }
+                    DroppedItem::Path(_, path) | DroppedItem::Unsupported(path) => {
+                        log::warn!("dropped file '{}' is ignored", path.display());
+                    }
+                    DroppedItem::Bytes { name, .. } => {
+                        log::warn!("dropped file '{name}' without a path is ignored");
+                    }
                 }
             }
         }
@@ -356,6 +555,13 @@ impl App {
             return;
         }
 
+        // stopped so that the end isn't reached again on the next frame
+        if self.state.stop_after_current {
+            self.state.stop_after_current = false;
+            self.player.set_play_state(PlayState::Stop);
+            return;
+        }
+
         match self.state.end_reached {
             EndReached::Idle => (),
             EndReached::Repeat => self.player.set_play_state(PlayState::Play),
@@ -374,16 +580,22 @@ impl App {
 
         self.state.was_playing = self.player.state().play_state.is_playing();
 
+        // Paused or stopped right now
+        if !self.state.was_playing {
+            self.state.last_prevent_sleep_time = f64::NEG_INFINITY;
+            eapp_utils::platform::allow_sleep();
+            // one more frame to show the stopped state, no change of the playback time follows
+            ui.ctx().request_repaint();
+            return;
+        }
+
         let now = ui.ctx().input(|i| i.time);
+        // No repaint is requested here, mpv wakes the app up on each change of the
+        // observed playback time while playing
         if now - self.state.last_prevent_sleep_time >= 120.0 {
             self.state.last_prevent_sleep_time = now;
             eapp_utils::platform::prevent_sleep();
         }
-
-        if now - self.state.last_playing_time >= 1.0 {
-            self.state.last_playing_time = now;
-            self.waker.request_repaint_after_secs(3.0);
-        }
     }
 
     fn rebuild_fonts(&mut self, ctx: &egui::Context) {
@@ -404,6 +616,11 @@ impl eframe::App for App {
         eframe::set_value(storage, Self::PLAYLIST_KEY, &self.playlist);
         eframe::set_value(storage, Self::DANMU_KEY, &self.danmu.state());
         eframe::set_value(storage, UiFontSelector::KEY, &self.selector);
+        eframe::set_value(storage, Self::SHORTCUT_KEY, &self.shortcuts);
+        // the window before picture-in-picture is kept, which is the last one saved
+        if self.state.pip.is_none() {
+            self.window.save(storage);
+        }
     }
 
     fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
@@ -414,22 +631,31 @@ impl eframe::App for App {
         borderless::window_frame(ctx, Some(ctx.style().visuals.extreme_bg_color)).show(ctx, |ui| {
             borderless::handle_resize(ui);
 
+            self.process_sleep_timer(ui);
             self.keep_state_if_media_playing(ui);
 
             let gl = frame.gl().unwrap();
 
             self.player.update(gl);
+            self.update_subtitles();
             if !self.player.state().is_audio {
                 self.preview.update(gl);
             }
 
+            let playback_time = self.player.state().playback_time;
+            if danmu::is_seek(self.state.last_playback_time, playback_time) {
+                self.danmu
+                    .on_seek(playback_time, self.state.danmu_regex.as_ref());
+                self.state.last_playback_time = playback_time;
+            }
+
             self.ui_background(ui);
 
             if self.player.state().play_state.is_playing()
                 && self.state.enable_danmu
                 && !self.danmu.danmu().is_empty()
             {
-                ctx.request_repaint();
+                self.request_danmu_frame(ctx);
 
                 let playback_time = self.player.state().playback_time;
                 self.danmu.push_pending(
@@ -442,9 +668,15 @@ impl eframe::App for App {
 
             self.ui_playlist(ui);
             self.ui_contents(ui);
+            self.ui_open_url_dialog(ui);
 
             self.process_inputs(ui);
 
+            self.repaint_stats.record(ctx);
+            if self.state.show_repaint_stats {
+                self.ui_repaint_stats(ui);
+            }
+
             self.tex_register.register_native_tex_if_any(frame);
         });
     }
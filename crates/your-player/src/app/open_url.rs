@@ -0,0 +1,211 @@
+use eapp_utils::codicons::{ICON_GLOBE, ICON_TRASH};
+use eframe::egui::{self, Color32};
+
+use crate::mpv;
+
+/// Most URLs kept in the history
+const MAX_URL_HISTORY: usize = 20;
+
+impl super::App {
+    /// Plays a network stream, regardless playlist
+    pub fn open_url(&mut self, url: &str) {
+        let url = url.trim();
+        if url.is_empty() {
+            return;
+        }
+
+        eapp_utils::push_recent(&mut self.state.url_history, url.to_owned(), MAX_URL_HISTORY);
+        self.set_media(url);
+        self.playlist.set_current_play(None);
+    }
+
+    pub fn ui_open_url_dialog(&mut self, ui: &mut egui::Ui) {
+        if !self.state.url_dialog_open {
+            return;
+        }
+
+        let mut url_to_open = None;
+        let mut url_to_remove = None;
+
+        let modal = egui::Modal::new(egui::Id::new("open_url")).show(ui.ctx(), |ui| {
+            ui.set_width(420.0);
+            ui.label(format!("{ICON_GLOBE} Open URL"));
+
+            let res = ui.add(
+                egui::TextEdit::singleline(&mut self.state.url_input)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("https://..."),
+            );
+            if ui.memory(|mem| mem.focused().is_none()) {
+                res.request_focus();
+            }
+
+            let entered = res.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            ui.horizontal(|ui| {
+                let valid = mpv::is_url(self.state.url_input.trim());
+                if ui.add_enabled(valid, egui::Button::new("Open")).clicked() || (entered && valid)
+                {
+                    url_to_open = Some(self.state.url_input.clone());
+                }
+
+                if ui.button("Cancel").clicked() {
+                    self.state.url_dialog_open = false;
+                }
+            });
+
+            if self.state.url_history.is_empty() {
+                return;
+            }
+
+            ui.separator();
+            ui.weak("Recent, double-click to open");
+
+            egui::ScrollArea::vertical()
+                .max_height(200.0)
+                .show(ui, |ui| {
+                    for url in &self.state.url_history {
+                        ui.horizontal(|ui| {
+                            let text = egui::RichText::new(ICON_TRASH.to_string())
+                                .color(Color32::LIGHT_RED);
+                            if ui.small_button(text).on_hover_text("Remove").clicked() {
+                                url_to_remove = Some(url.clone());
+                            }
+
+                            let res = ui
+                                .add(egui::Label::new(url).truncate().sense(egui::Sense::click()));
+                            if res.clicked() {
+                                self.state.url_input = url.clone();
+                            }
+                            if res.double_clicked() {
+                                url_to_open = Some(url.clone());
+                            }
+                        });
+                    }
+                });
+        });
+
+        if modal.should_close() {
+            self.state.url_dialog_open = false;
+        }
+
+        if let Some(url) = url_to_remove {
+            self.state.url_history.retain(|v| *v != url);
+        }
+
+        if let Some(url) = url_to_open {
+            self.state.url_dialog_open = false;
+            self.state.url_input.clear();
+            self.open_url(&url);
+        }
+    }
+
+    /// The group of recently played URLs, on top of the playlist
+    pub fn ui_playlist_network(&mut self, ui: &mut egui::Ui, key: &str) {
+        let media_path = &self.player.state().media_path;
+        let urls: Vec<_> = self
+            .state
+            .url_history
+            .iter()
+            .filter(|url| key.is_empty() || url.to_ascii_lowercase().contains(key))
+            .cloned()
+            .collect();
+
+        if urls.is_empty() {
+            return;
+        }
+
+        let mut url_to_open = None;
+        let mut url_to_remove = None;
+
+        egui::CollapsingHeader::new(format!("{ICON_GLOBE} Network"))
+            .id_salt("playlist_network")
+            .default_open(true)
+            .show(ui, |ui| {
+                for url in &urls {
+                    let is_cur_play = url == media_path;
+
+                    // the title is only known for the one loaded
+                    let title = &self.player.state().media_title;
+                    let text = if is_cur_play && !title.is_empty() && title != "None" {
+                        title.as_str()
+                    } else {
+                        url.as_str()
+                    };
+
+                    ui.scope(|ui| {
+                        if is_cur_play {
+                            ui.visuals_mut().override_text_color =
+                                Some(ui.visuals().strong_text_color());
+                        }
+
+                        ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+                        let res = ui.selectable_label(is_cur_play, text).on_hover_text(url);
+
+                        if res.triple_clicked() {
+                            url_to_open = Some(url.clone());
+                        }
+
+                        res.context_menu(|ui| {
+                            if ui.button("Copy URL").clicked() {
+                                ui.ctx().copy_text(url.clone());
+                                ui.close();
+                            }
+
+                            let text = egui::RichText::new("Remove").color(Color32::LIGHT_RED);
+                            if ui.button(text).clicked() {
+                                url_to_remove = Some(url.clone());
+                                ui.close();
+                            }
+                        });
+                    });
+                }
+            });
+
+        if let Some(url) = url_to_remove {
+            self.state.url_history.retain(|v| *v != url);
+        }
+
+        if let Some(url) = url_to_open {
+            self.open_url(&url);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eapp_utils::push_recent;
+
+    #[test]
+    fn history_is_deduplicated_and_bounded() {
+        let mut history = Vec::new();
+        for i in 0..MAX_URL_HISTORY + 5 {
+            push_recent(
+                &mut history,
+                format!("https://example.com/{i}"),
+                MAX_URL_HISTORY,
+            );
+        }
+        assert_eq!(history.len(), MAX_URL_HISTORY);
+        assert_eq!(
+            history[0],
+            format!("https://example.com/{}", MAX_URL_HISTORY + 4)
+        );
+
+        push_recent(
+            &mut history,
+            "https://example.com/10".to_owned(),
+            MAX_URL_HISTORY,
+        );
+        assert_eq!(history[0], "https://example.com/10");
+        assert_eq!(
+            history
+                .iter()
+                .filter(|url| *url == "https://example.com/10")
+                .count(),
+            1
+        );
+        assert_eq!(history.len(), MAX_URL_HISTORY);
+    }
+}
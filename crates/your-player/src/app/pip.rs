@@ -0,0 +1,157 @@
+use super::{saved_window::SavedWindow, shortcuts::Action};
+use crate::mpv::player::PlayState;
+use eapp_utils::{
+    borderless,
+    codicons::{ICON_CHROME_CLOSE, ICON_DEBUG_PAUSE, ICON_PLAY_CIRCLE},
+    widgets::simple_widgets::PlainButton,
+};
+use eframe::egui::{
+    self, CornerRadius, Frame, Id, Rect, Sense, UiBuilder, ViewportCommand, WindowLevel, vec2,
+};
+
+impl super::App {
+    pub const PIP_WIDTH: f32 = 400.0;
+    pub const PIP_MIN_SIZE: egui::Vec2 = vec2(192.0, 108.0);
+
+    pub fn toggle_pip(&mut self, ctx: &egui::Context) {
+        if self.state.pip.is_some() {
+            self.exit_pip(ctx);
+        } else {
+            self.enter_pip(ctx);
+        }
+    }
+
+    /// Shrinks the window to the video in a corner-sized window kept on top,
+    /// not in compact mode which is for audio
+    pub fn enter_pip(&mut self, ctx: &egui::Context) {
+        if self.state.pip.is_some() || self.state.compact.is_some() {
+            return;
+        }
+
+        self.state.pip = Some(SavedWindow::take(ctx));
+
+        self.state.volume_popup_open = false;
+        self.state.chapters_popup_open = false;
+        self.state.setting_popup_open = false;
+        self.state.long_setting_popup_open = false;
+        self.state.sleep_popup_open = false;
+
+        let (width, height) = self.player.state().media_size;
+        let aspect = if width > 0 && height > 0 {
+            height as f32 / width as f32
+        } else {
+            9.0 / 16.0
+        };
+        let size = vec2(Self::PIP_WIDTH, Self::PIP_WIDTH * aspect).max(Self::PIP_MIN_SIZE);
+
+        ctx.send_viewport_cmd(ViewportCommand::MinInnerSize(Self::PIP_MIN_SIZE));
+        ctx.send_viewport_cmd(ViewportCommand::InnerSize(size));
+        ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::AlwaysOnTop));
+    }
+
+    pub fn exit_pip(&mut self, ctx: &egui::Context) {
+        let Some(pip) = self.state.pip.take() else {
+            return;
+        };
+
+        pip.restore(ctx);
+    }
+
+    /// The video only, with a strip of controls shown while hovering the top of it
+    pub fn ui_pip_contents(&mut self, ui: &mut egui::Ui) {
+        egui::CentralPanel::default()
+            .frame(Frame::NONE)
+            .show_inside(ui, |ui| {
+                let app_rect = ui.max_rect();
+                self.state.content_rect = app_rect;
+
+                let response = ui.interact(app_rect, Id::new("pip_drag"), Sense::click());
+                if response.double_clicked() {
+                    self.exit_pip(ui.ctx());
+                    return;
+                }
+                if response.is_pointer_button_down_on() {
+                    ui.ctx().send_viewport_cmd(ViewportCommand::StartDrag);
+                }
+
+                let strip_rect = {
+                    let mut rect = app_rect;
+                    rect.set_bottom(rect.top() + 28.0);
+                    rect
+                };
+                let sense_rect = {
+                    let mut rect = app_rect;
+                    rect.set_bottom(rect.top() + app_rect.height() * 0.5);
+                    rect
+                };
+                self.ui_pip_strip(ui, strip_rect, sense_rect);
+            });
+    }
+
+    fn ui_pip_strip(&mut self, ui: &mut egui::Ui, rect: Rect, sense_rect: Rect) {
+        let opacity = ui.ctx().animate_bool(
+            Id::new("pip_strip_hover_area"),
+            borderless::rect_contains_pointer(ui, sense_rect),
+        );
+
+        if opacity == 0.0 {
+            return;
+        }
+
+        ui.set_opacity(opacity);
+        ui.painter().rect_filled(
+            rect,
+            CornerRadius {
+                nw: 8,
+                ne: 8,
+                ..CornerRadius::ZERO
+            },
+            ui.visuals().extreme_bg_color.gamma_multiply(0.8),
+        );
+
+        let hover_color = ui.visuals().selection.bg_fill;
+        let new_button = |str| {
+            PlainButton::new(vec2(24.0, 24.0), str)
+                .font_size(16.0)
+                .corner_radius(CornerRadius::same(2))
+                .hover(hover_color)
+        };
+
+        ui.scope_builder(
+            UiBuilder::new().max_rect(rect.shrink2(vec2(4.0, 2.0))),
+            |ui| {
+                ui.visuals_mut().override_text_color = Some(ui.visuals().strong_text_color());
+                ui.horizontal_centered(|ui| {
+                    ui.spacing_mut().item_spacing.x = 0.0;
+
+                    let is_pause = !self.player.state().play_state.is_playing();
+                    let icon = if is_pause {
+                        ICON_PLAY_CIRCLE
+                    } else {
+                        ICON_DEBUG_PAUSE
+                    };
+                    if ui.add(new_button(icon.to_string())).clicked() {
+                        self.player.set_play_state(if is_pause {
+                            PlayState::Play
+                        } else {
+                            PlayState::Pause
+                        });
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui
+                            .add(new_button(ICON_CHROME_CLOSE.to_string()))
+                            .on_hover_text(format!(
+                                "Exit picture-in-picture ({})",
+                                self.shortcuts.format(ui.ctx(), Action::Pip)
+                            ))
+                            .clicked()
+                        {
+                            self.exit_pip(ui.ctx());
+                        }
+                    });
+                });
+            },
+        );
+    }
+}
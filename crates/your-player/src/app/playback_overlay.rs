@@ -0,0 +1,104 @@
+use eapp_utils::{
+    codicons::{ICON_DEBUG_STEP_OVER, ICON_ERROR, ICON_REFRESH},
+    get_body_font_id, get_body_text_size,
+};
+use eframe::egui::{self, Align, Frame, Layout, UiBuilder, vec2};
+
+impl super::App {
+    /// Shows the buffering progress, or the error of the media failed to play
+    /// with buttons to retry or skip it
+    ///
+    /// Only the buttons sense clicks, so that widgets around the center are not covered
+    pub fn ui_playback_overlay(&mut self, ui: &mut egui::Ui, rect: egui::Rect) {
+        let state = self.player.state();
+        if state.is_buffering() {
+            let text = format!("Buffering... {}%", state.cache_buffering);
+            self.ui_buffering(ui, rect, text);
+        } else if let Some((path, message)) = state.failed_media.clone() {
+            self.ui_failed_media(ui, rect, &path, &message);
+        }
+    }
+
+    fn ui_buffering(&self, ui: &mut egui::Ui, rect: egui::Rect, text: String) {
+        let painter = ui.painter();
+        let galley =
+            painter.layout_no_wrap(text, get_body_font_id(ui), ui.visuals().strong_text_color());
+
+        let spinner_size = get_body_text_size(ui);
+        let spacing = 8.0;
+        let content_size = vec2(
+            spinner_size + spacing + galley.size().x,
+            galley.size().y.max(spinner_size),
+        );
+        let content_rect = egui::Rect::from_center_size(rect.center(), content_size);
+
+        painter.rect_filled(
+            content_rect.expand2(vec2(16.0, 8.0)),
+            8,
+            ui.visuals().extreme_bg_color.gamma_multiply(0.8),
+        );
+
+        let spinner_rect = egui::Rect::from_center_size(
+            egui::pos2(
+                content_rect.left() + spinner_size / 2.0,
+                content_rect.center().y,
+            ),
+            vec2(spinner_size, spinner_size),
+        );
+        egui::Spinner::new()
+            .size(spinner_size)
+            .paint_at(ui, spinner_rect);
+
+        painter.galley(
+            egui::pos2(
+                spinner_rect.right() + spacing,
+                content_rect.center().y - galley.size().y / 2.0,
+            ),
+            galley,
+            ui.visuals().strong_text_color(),
+        );
+    }
+
+    fn ui_failed_media(&mut self, ui: &mut egui::Ui, rect: egui::Rect, path: &str, message: &str) {
+        let card_rect =
+            egui::Rect::from_center_size(rect.center(), vec2(rect.width().min(420.0), 0.0))
+                .expand2(vec2(0.0, 60.0));
+
+        ui.scope_builder(
+            UiBuilder::new()
+                .max_rect(card_rect)
+                .layout(Layout::top_down(Align::Center)),
+            |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(
+                        egui::RichText::new(format!("{ICON_ERROR} Failed to play"))
+                            .color(ui.visuals().error_fg_color),
+                    );
+                    ui.add(egui::Label::new(path).wrap_mode(egui::TextWrapMode::Truncate))
+                        .on_hover_text(path);
+                    ui.add(
+                        egui::Label::new(egui::RichText::new(message).weak())
+                            .wrap_mode(egui::TextWrapMode::Wrap),
+                    );
+
+                    ui.horizontal(|ui| {
+                        if ui.button(format!("{ICON_REFRESH} Retry")).clicked() {
+                            self.set_media(path);
+                        }
+
+                        if ui
+                            .button(format!("{ICON_DEBUG_STEP_OVER} Skip"))
+                            .on_hover_text("Play the next one in the playlist")
+                            .clicked()
+                        {
+                            match self.playlist.next_item() {
+                                Some(next) => self.set_media(&next),
+                                None => self.player.clear_failed_media(),
+                            }
+                        }
+                    });
+                });
+            },
+        );
+    }
+}
@@ -1,9 +1,93 @@
 use super::PlaylistType;
-use eapp_utils::widgets::simple_widgets::{get_theme_button, theme_button};
-use eframe::egui::{self, Color32, CornerRadius, Frame};
-use std::path::Path;
+use eapp_utils::{
+    codicons::{ICON_GLOBE, ICON_INFO},
+    widgets::simple_widgets::{frameless_btn, get_theme_button, theme_button},
+};
+use eframe::egui::{
+    self, Color32, CornerRadius, Frame, ahash::HashMap, collapsing_header::CollapsingState,
+};
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{danmu::DanmuPtr, playlist::Playlist};
+
+/// Items of each list matching the playlist filter, found again only once the filter or the lists change
+#[derive(Default)]
+pub(super) struct PlaylistMatches {
+    generation: Option<u64>,
+
+    /// indices of the matching items, `None` when the whole list matches
+    lists: HashMap<String, Option<Vec<usize>>>,
+}
+
+impl PlaylistMatches {
+    fn new(playlist: &Playlist, key: &str) -> Self {
+        let lists = playlist
+            .inner_map()
+            .iter()
+            .map(|(list_name, list)| {
+                let group_matched = playlist.name(list_name).to_ascii_lowercase().contains(key);
+                let indices = (!key.is_empty() && !group_matched).then(|| {
+                    list.iter()
+                        .enumerate()
+                        .filter(|(_, media)| {
+                            Path::new(media)
+                                .file_name()
+                                .unwrap()
+                                .to_str()
+                                .unwrap()
+                                .to_ascii_lowercase()
+                                .contains(key)
+                        })
+                        .map(|(idx, _)| idx)
+                        .collect()
+                });
+                (list_name.clone(), indices)
+            })
+            .collect();
+
+        Self {
+            generation: Some(playlist.generation()),
+            lists,
+        }
+    }
+}
 
-use crate::danmu::DanmuPtr;
+enum GroupAction {
+    PlayFromHere,
+    Reveal,
+    MoveUp,
+    MoveDown,
+    Rename(String),
+    SetProfile(Option<String>),
+    Remove,
+}
+
+/// Choices of the profile to assign, returns the chosen one, `Some(None)` for no profile
+pub(super) fn profile_menu(
+    ui: &mut egui::Ui,
+    profiles: &BTreeMap<String, String>,
+    assigned: Option<&str>,
+) -> Option<Option<String>> {
+    if profiles.is_empty() {
+        ui.weak("No profile, add one in the settings");
+        return None;
+    }
+
+    let mut chosen = None;
+    if ui.radio(assigned.is_none(), "None").clicked() {
+        chosen = Some(None);
+    }
+    for name in profiles.keys() {
+        if ui.radio(assigned == Some(name.as_str()), name).clicked() {
+            chosen = Some(Some(name.clone()));
+        }
+    }
+
+    if chosen.is_some() {
+        ui.close();
+    }
+    chosen
+}
 
 impl super::App {
     pub fn ui_playlist(&mut self, ui: &mut egui::Ui) {
@@ -22,60 +106,123 @@ impl super::App {
                 )),
             )
             .width_range(200.0..=max_width)
-            .show_animated_inside(ui, self.state.playlist_open, |ui| {
-                ui.horizontal(|ui| {
-                    if theme_button(ui, get_theme_button(ui)).clicked() {
-                        self.selector.apply_text_style(ui.ctx());
-                    }
+            .show_animated_inside(
+                ui,
+                self.state.playlist_open
+                    && self.state.compact.is_none()
+                    && self.state.pip.is_none(),
+                |ui| {
+                    ui.horizontal(|ui| {
+                        if theme_button(ui, get_theme_button(ui)).clicked() {
+                            self.selector.apply_text_style(ui.ctx());
+                        }
 
-                    if self.selector.ui_and_should_rebuild_fonts(ui) {
-                        self.rebuild_fonts(ui.ctx());
-                    }
+                        if self.selector.ui_and_should_rebuild_fonts(ui) {
+                            self.rebuild_fonts(ui.ctx());
+                        }
 
-                    #[allow(clippy::single_element_loop)]
-                    for (v, str) in [
-                        (PlaylistType::Playlist, "Playlist"),
-                        (PlaylistType::Danmu, "Danmu"),
-                    ]
-                    .into_iter()
-                    {
-                        ui.selectable_value(&mut self.state.playlist_type, v, str);
-                    }
-                });
+                        for (v, str) in [
+                            (PlaylistType::Playlist, "Playlist"),
+                            (PlaylistType::Danmu, "Danmu"),
+                            (PlaylistType::Subtitles, "Subtitles"),
+                        ]
+                        .into_iter()
+                        {
+                            ui.selectable_value(&mut self.state.playlist_type, v, str);
+                        }
+
+                        if frameless_btn(ui, ICON_GLOBE.to_string())
+                            .on_hover_text("Open URL (Ctrl+U)")
+                            .clicked()
+                        {
+                            self.state.url_dialog_open = true;
+                        }
 
-                let max_width = ui.available_width();
-
-                match self.state.playlist_type {
-                    PlaylistType::Playlist => {
-                        ui.add(
-                            egui::TextEdit::singleline(&mut self.state.playlist_key)
-                                .desired_width(f32::INFINITY)
-                                .hint_text("Search keywords"),
-                        );
-                        egui::ScrollArea::both()
-                            .auto_shrink([false, true])
-                            .show(ui, |ui| {
-                                self.ui_playlist_playlist(ui, max_width);
+                        let response =
+                            frameless_btn(ui, ICON_INFO.to_string()).on_hover_text("About");
+                        egui::Popup::menu(&response)
+                            .close_behavior(egui::PopupCloseBehavior::CloseOnClickOutside)
+                            .show(|ui| {
+                                eapp_utils::diagnostics::about_popup(
+                                    ui,
+                                    "your-player",
+                                    env!("CARGO_PKG_VERSION"),
+                                )
                             });
+                    });
+
+                    let max_width = ui.available_width();
+
+                    match self.state.playlist_type {
+                        PlaylistType::Playlist => {
+                            let now = ui.input(|i| i.time);
+                            if ui
+                                .add(
+                                    egui::TextEdit::singleline(&mut self.state.playlist_key)
+                                        .desired_width(f32::INFINITY)
+                                        .hint_text("Search keywords"),
+                                )
+                                .changed()
+                            {
+                                self.state
+                                    .playlist_filter
+                                    .set(self.state.playlist_key.clone(), now);
+                            }
+                            if let Some(secs) = self.state.playlist_filter.remaining(now) {
+                                ui.ctx().request_repaint_after_secs(secs as f32);
+                            }
+
+                            egui::ScrollArea::both()
+                                .auto_shrink([false, true])
+                                .show(ui, |ui| {
+                                    self.ui_playlist_playlist(ui, max_width);
+                                });
+                        }
+                        PlaylistType::Danmu => {
+                            self.ui_playlist_danmu(ui);
+                        }
+                        PlaylistType::Subtitles => {
+                            self.ui_playlist_subtitles(ui);
+                        }
                     }
-                    PlaylistType::Danmu => {
-                        self.ui_playlist_danmu(ui);
-                    }
-                }
-            });
+                },
+            );
     }
 
     fn ui_playlist_playlist(&mut self, ui: &mut egui::Ui, max_width: f32) {
-        let key_empty = self.state.playlist_key.is_empty();
-        let key = self.state.playlist_key.to_ascii_lowercase();
+        let now = ui.input(|i| i.time);
+        let filter_changed = self.state.playlist_filter.changed(now);
+        let key = self.state.playlist_filter.get(now).to_ascii_lowercase();
+        let key_empty = key.is_empty();
+
+        if filter_changed || self.playlist_matches.generation != Some(self.playlist.generation()) {
+            self.playlist_matches = PlaylistMatches::new(&self.playlist, &key);
+        }
+
+        if self.playlist.consume_current_changed_flag()
+            && let Some((list, _)) = self.playlist.current_play()
+        {
+            let list = list.to_owned();
+            self.playlist.set_expanded(&list, true);
+            self.state.scroll_to_current = true;
+        }
 
         let mut current_play = self
             .playlist
             .current_play()
             .map(|(list, media)| (list.to_owned(), media.to_owned()));
 
+        if current_play.is_none() {
+            self.state.scroll_to_current = false;
+        }
+
+        self.ui_playlist_network(ui, &key);
+
         let popup_id: egui::Id = "playlist_popup_id".into();
         let mut popup_res: Option<egui::Response> = None;
+        let mut group_action: Option<(String, GroupAction)> = None;
+        let mut toggled_groups = Vec::new();
+        let mut rename_shown = false;
 
         macro_rules! tuple_as_ref {
             ($value:expr) => {
@@ -86,79 +233,251 @@ impl super::App {
             };
         }
 
-        for (list_name, list) in self.playlist.inner_map() {
-            let (iter, len): (Box<dyn Iterator<Item = &String>>, usize) = if key_empty {
-                (Box::new(list.iter()), list.0.len())
-            } else {
-                let iter = list.iter().filter(|v| {
-                    Path::new(v)
-                        .file_name()
-                        .unwrap()
-                        .to_str()
-                        .unwrap()
-                        .to_ascii_lowercase()
-                        .contains(&key)
+        let lists = self.playlist.lists();
+        let lists_len = lists.len();
+        for (list_idx, list_name) in lists.into_iter().enumerate() {
+            let list = &self.playlist.inner_map()[list_name];
+            let list_filename = self.playlist.name(list_name);
+            let indices = self
+                .playlist_matches
+                .lists
+                .get(list_name)
+                .and_then(Option::as_deref);
+            let len = indices.map_or(list.len(), <[usize]>::len);
+            let item = |row: usize| &list.0[indices.map_or(row, |indices| indices[row])];
+
+            if !key_empty && len == 0 {
+                continue;
+            }
+
+            let is_cur_list = current_play
+                .as_ref()
+                .is_some_and(|(cur_list, _)| cur_list == list_name);
+
+            // rows out of view are not laid out, so the current one is scrolled to by its offset
+            let cur_row = current_play
+                .as_ref()
+                .filter(|_| self.state.scroll_to_current && is_cur_list)
+                .and_then(|(_, cur_media)| list.search(cur_media).ok())
+                .and_then(|idx| match indices {
+                    Some(indices) => indices.binary_search(&idx).ok(),
+                    None => Some(idx),
                 });
-                (Box::new(iter.clone()), iter.count())
-            };
 
-            let list_filename = Path::new(list_name).file_name().unwrap().to_str().unwrap();
+            // groups are kept expanded while filtering, without touching what's persisted
+            let open = !key_empty || self.playlist.is_expanded(list_name);
+            let id = ui.make_persistent_id(("playlist_group", list_name));
+            let mut state = CollapsingState::load_with_default_open(ui.ctx(), id, open);
+            state.set_open(open);
+
+            let (toggle_res, header_res, _) = state
+                .show_header(ui, |ui| {
+                    let text = if is_cur_list {
+                        egui::RichText::new(list_filename).color(ui.visuals().strong_text_color())
+                    } else {
+                        egui::RichText::new(list_filename)
+                    };
+
+                    let res = ui
+                        .add(
+                            egui::Label::new(text)
+                                .selectable(false)
+                                .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(list_name);
+
+                    egui::Popup::context_menu(&res).show(|ui| {
+                        for (action, text) in [
+                            (GroupAction::PlayFromHere, "Play from here"),
+                            (GroupAction::Reveal, "Reveal in explorer"),
+                        ] {
+                            if frameless_btn(ui, text).clicked() {
+                                group_action = Some((list_name.to_owned(), action));
+                            }
+                        }
 
-            egui::CollapsingHeader::new(list_filename)
-                .default_open(false)
-                .show(ui, |ui| {
-                    let row_height = ui.text_style_height(&egui::TextStyle::Body);
-                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+                        ui.menu_button("Rename", |ui| {
+                            rename_shown = true;
+                            if self
+                                .list_rename
+                                .as_ref()
+                                .is_none_or(|(list, _)| list != list_name)
+                            {
+                                self.list_rename =
+                                    Some((list_name.to_owned(), list_filename.to_owned()));
+                            }
+                            let (_, name) = self.list_rename.as_mut().unwrap();
 
-                    egui::ScrollArea::both()
-                        .auto_shrink([false, true])
-                        .show_rows(ui, row_height, len, |ui, range| {
-                            for media_name in iter.skip(range.start).take(range.len()) {
-                                let media_filename =
-                                    Path::new(media_name).file_name().unwrap().to_str().unwrap();
-
-                                ui.scope(|ui| {
-                                    if tuple_as_ref!(current_play) == Some((list_name, media_name))
-                                    {
-                                        ui.visuals_mut().override_text_color =
-                                            Some(ui.visuals().strong_text_color());
-                                    }
-
-                                    let res = ui
-                                        .selectable_label(
-                                            tuple_as_ref!(self.state.playlist_cur_sel)
-                                                == Some((list_name, media_name)),
-                                            media_filename,
-                                        )
-                                        .on_hover_text(media_name);
-
-                                    if res.clicked() {
-                                        egui::Popup::close_id(ui.ctx(), popup_id);
-                                    }
-
-                                    if res.clicked() || res.secondary_clicked() {
-                                        self.state.playlist_cur_sel =
-                                            Some((list_name.to_owned(), media_name.to_owned()));
-                                    }
-
-                                    if res.triple_clicked() {
-                                        current_play =
-                                            Some((list_name.to_owned(), media_name.to_owned()));
-                                    }
-
-                                    if res.secondary_clicked() {
-                                        egui::Popup::open_id(ui.ctx(), popup_id);
-                                    }
-
-                                    if tuple_as_ref!(self.state.playlist_cur_sel)
-                                        == Some((list_name, media_name))
-                                    {
-                                        popup_res = Some(res);
-                                    }
-                                });
+                            // an emptied name is not taken, so that a new one can be typed
+                            if ui.text_edit_singleline(name).changed() && !name.trim().is_empty() {
+                                group_action =
+                                    Some((list_name.to_owned(), GroupAction::Rename(name.clone())));
+                            }
+
+                            if frameless_btn(ui, "Reset to folder name").clicked() {
+                                // the folder name is shown once it's reset
+                                self.list_rename = None;
+                                group_action = Some((
+                                    list_name.to_owned(),
+                                    GroupAction::Rename(String::new()),
+                                ));
+                            }
+                        });
+
+                        ui.menu_button("Profile", |ui| {
+                            let assigned = self.playlist.assigned_profile(list_name);
+                            if let Some(name) = profile_menu(ui, self.playlist.profiles(), assigned)
+                            {
+                                group_action =
+                                    Some((list_name.to_owned(), GroupAction::SetProfile(name)));
                             }
                         });
+
+                        ui.separator();
+
+                        if ui
+                            .add_enabled(list_idx > 0, egui::Button::new("Move up").frame(false))
+                            .clicked()
+                        {
+                            group_action = Some((list_name.to_owned(), GroupAction::MoveUp));
+                        }
+                        if ui
+                            .add_enabled(
+                                list_idx + 1 < lists_len,
+                                egui::Button::new("Move down").frame(false),
+                            )
+                            .clicked()
+                        {
+                            group_action = Some((list_name.to_owned(), GroupAction::MoveDown));
+                        }
+
+                        ui.separator();
+
+                        let text = egui::RichText::new("Remove group").color(Color32::LIGHT_RED);
+                        if frameless_btn(ui, text).clicked() {
+                            group_action = Some((list_name.to_owned(), GroupAction::Remove));
+                        }
+                    });
+
+                    res
+                })
+                .body(|ui| {
+                    let row_height = ui.text_style_height(&egui::TextStyle::Body);
+                    ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Extend);
+
+                    let mut scroll_area = egui::ScrollArea::both().auto_shrink([false, true]);
+                    if let Some(row) = cur_row {
+                        let row_height_with_spacing = row_height + ui.spacing().item_spacing.y;
+                        scroll_area = scroll_area
+                            .vertical_scroll_offset(row as f32 * row_height_with_spacing);
+                    }
+
+                    let output = scroll_area.show_rows(ui, row_height, len, |ui, range| {
+                        for media_name in range.map(item) {
+                            let media_filename =
+                                Path::new(media_name).file_name().unwrap().to_str().unwrap();
+
+                            ui.scope(|ui| {
+                                let is_cur_play =
+                                    tuple_as_ref!(current_play) == Some((list_name, media_name));
+
+                                if is_cur_play {
+                                    ui.visuals_mut().override_text_color =
+                                        Some(ui.visuals().strong_text_color());
+                                }
+
+                                let res = ui
+                                    .selectable_label(
+                                        tuple_as_ref!(self.state.playlist_cur_sel)
+                                            == Some((list_name, media_name)),
+                                        media_filename,
+                                    )
+                                    .on_hover_text(media_name);
+
+                                if res.clicked() {
+                                    egui::Popup::close_id(ui.ctx(), popup_id);
+                                }
+
+                                if res.clicked() || res.secondary_clicked() {
+                                    self.state.playlist_cur_sel =
+                                        Some((list_name.to_owned(), media_name.to_owned()));
+                                }
+
+                                if res.triple_clicked() {
+                                    current_play =
+                                        Some((list_name.to_owned(), media_name.to_owned()));
+                                }
+
+                                if res.secondary_clicked() {
+                                    egui::Popup::open_id(ui.ctx(), popup_id);
+                                }
+
+                                if tuple_as_ref!(self.state.playlist_cur_sel)
+                                    == Some((list_name, media_name))
+                                {
+                                    popup_res = Some(res);
+                                }
+                            });
+                        }
+                    });
+
+                    if cur_row.is_some() {
+                        self.state.scroll_to_current = false;
+                        ui.scroll_to_rect(output.inner_rect, Some(egui::Align::Center));
+                    }
                 });
+
+            if key_empty && (toggle_res.clicked() || header_res.inner.clicked()) {
+                toggled_groups.push((list_name.to_owned(), !open));
+            }
+        }
+
+        if !rename_shown {
+            self.list_rename = None;
+        }
+
+        for (list, expanded) in toggled_groups {
+            self.playlist.set_expanded(&list, expanded);
+        }
+
+        if let Some((list, action)) = group_action {
+            match action {
+                GroupAction::PlayFromHere => {
+                    if let Some(first) = self.playlist.inner_map()[&list].0.first() {
+                        current_play = Some((list, first.to_owned()));
+                    }
+                }
+                GroupAction::Reveal => {
+                    if let Err(err) = eapp_utils::open_in_explorer(&list) {
+                        log::error!("open in explorer fails: {err}");
+                    }
+                }
+                GroupAction::MoveUp => self.playlist.move_list(&list, -1),
+                GroupAction::MoveDown => self.playlist.move_list(&list, 1),
+                GroupAction::Rename(name) => self.playlist.set_name(&list, &name),
+                GroupAction::SetProfile(name) => {
+                    self.playlist.assign_profile(&list, name.as_deref());
+                    if Path::new(&self.player.state().media_path).starts_with(&list) {
+                        self.reapply_profile();
+                    }
+                }
+                GroupAction::Remove => {
+                    if current_play.as_ref().is_some_and(|(cur, _)| *cur == list) {
+                        current_play = None;
+                    }
+
+                    if self
+                        .state
+                        .playlist_cur_sel
+                        .as_ref()
+                        .is_some_and(|(sel, _)| *sel == list)
+                    {
+                        self.state.playlist_cur_sel = None;
+                    }
+
+                    self.playlist.remove_list(&list);
+                }
+            }
         }
 
         if self.playlist.current_play() != tuple_as_ref!(current_play) {
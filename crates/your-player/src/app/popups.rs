@@ -1,10 +1,18 @@
 use crate::{
-    app::{END_REACHED_LIST, opts_highlight},
-    mpv,
+    app::{END_REACHED_LIST, opts_highlight, playlist::profile_menu},
+    mpv::{
+        self,
+        audio_filter::{EQ_BANDS, EQ_PRESETS},
+        player::Player,
+    },
 };
 use eapp_utils::{
-    codicons::ICON_FOLDER,
-    widgets::simple_widgets::{frameless_btn, toggle_ui},
+    codicons::{ICON_ADD, ICON_FOLDER, ICON_TRASH},
+    widgets::{
+        color_picker::ColorButton,
+        simple_widgets::{editable_label, frameless_btn, toggle_ui},
+        unit_drag::UnitDrag,
+    },
 };
 use eframe::egui::{self, Color32};
 
@@ -45,7 +53,14 @@ impl super::App {
         ui.set_height(150.0);
         ui.set_width(350.0);
         ui.horizontal(|ui| {
-            for (v, str) in [(Play, "Play"), (Color, "Color"), (Danmu, "Danmu")].into_iter() {
+            for (v, str) in [
+                (Play, "Play"),
+                (Color, "Color"),
+                (Audio, "Audio"),
+                (Danmu, "Danmu"),
+            ]
+            .into_iter()
+            {
                 ui.selectable_value(&mut self.state.setting_type, v, str);
             }
         });
@@ -53,6 +68,11 @@ impl super::App {
         egui::ScrollArea::both()
             .auto_shrink([false, true])
             .show(ui, |ui| {
+                if self.state.setting_type == Audio {
+                    self.ui_equalizer(ui);
+                    return;
+                }
+
                 egui::Grid::new("setting_popup_grid")
                     .num_columns(2)
                     .spacing([20.0, 4.0])
@@ -114,6 +134,28 @@ impl super::App {
                 }
                 ui.end_row();
 
+                ui.label("subtitle colors");
+                ui.horizontal(|ui| {
+                    let mut sub_color = self.player.state().sub_color;
+                    if ui
+                        .add(ColorButton::new("sub_color", &mut sub_color))
+                        .on_hover_text("Text, ASS subtitles use their own styles")
+                        .changed()
+                    {
+                        self.player.set_sub_color(sub_color);
+                    }
+
+                    let mut sub_border_color = self.player.state().sub_border_color;
+                    if ui
+                        .add(ColorButton::new("sub_border_color", &mut sub_border_color))
+                        .on_hover_text("Border")
+                        .changed()
+                    {
+                        self.player.set_sub_border_color(sub_border_color);
+                    }
+                });
+                ui.end_row();
+
                 simple_combo!(
                     "audio track",
                     cur_audio_idx,
@@ -128,6 +170,28 @@ impl super::App {
                     self.player.state().subtitle_tracks
                 );
 
+                ui.label("audio languages");
+                let mut langs = self.player.state().preferred_audio_langs.clone();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut langs).hint_text("ja,jpn"))
+                    .on_hover_text("Comma separated language codes, tried in order")
+                    .changed()
+                {
+                    self.player.set_preferred_audio_langs(langs);
+                }
+                ui.end_row();
+
+                ui.label("subtitle languages");
+                let mut langs = self.player.state().preferred_sub_langs.clone();
+                if ui
+                    .add(egui::TextEdit::singleline(&mut langs).hint_text("zh,chi,chs"))
+                    .on_hover_text("Comma separated language codes, tried in order")
+                    .changed()
+                {
+                    self.player.set_preferred_sub_langs(langs);
+                }
+                ui.end_row();
+
                 simple_combo!(
                     "video aspect",
                     video_aspect,
@@ -154,6 +218,78 @@ impl super::App {
                 ui.end_row();
 
                 simple_slider!(speed, set_speed, 0.25..=4.0);
+
+                ui.label("hardware decoding");
+                let hwdec = self.player.state().hwdec.clone();
+                egui::ComboBox::from_id_salt("hwdec_combo")
+                    .height(80.0)
+                    .selected_text(hwdec.as_deref().unwrap_or("as options"))
+                    .show_ui(ui, |ui| {
+                        for &value in mpv::player::HWDEC_LIST {
+                            if ui
+                                .selectable_label(hwdec.as_deref() == Some(value), value)
+                                .clicked()
+                            {
+                                self.player.set_hwdec(value.to_owned());
+                            }
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("decoder");
+                let state = self.player.state();
+                if state.video_codec.is_empty() {
+                    ui.weak("None");
+                } else if state.is_hwdec_fallback() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("{}, software (backend unavailable)", state.video_codec),
+                    );
+                } else {
+                    let hwdec_current = match state.hwdec_current.as_str() {
+                        "" | "no" => "software",
+                        hwdec_current => hwdec_current,
+                    };
+                    ui.label(format!("{}, {hwdec_current}", state.video_codec));
+                }
+                ui.end_row();
+
+                ui.label("preview size");
+                if ui
+                    .add(UnitDrag::new(&mut self.state.preview_size, "px").range(100..=480))
+                    .on_hover_text(
+                        "Longest side of the previews shown when hovering the progress bar",
+                    )
+                    .changed()
+                {
+                    self.preview.set_max_size(self.state.preview_size);
+                }
+                ui.end_row();
+
+                ui.label("preview cache");
+                if ui
+                    .add(
+                        UnitDrag::new(&mut self.state.preview_capacity, "previews")
+                            .range(16..=1000),
+                    )
+                    .on_hover_text("Most previews kept in GPU memory, lower it for large previews")
+                    .changed()
+                {
+                    self.preview.set_capacity(self.state.preview_capacity);
+                }
+                ui.end_row();
+
+                ui.label("gestures");
+                toggle_ui(ui, &mut self.state.enable_gestures).on_hover_text(
+                    "Double-click for fullscreen, drag vertically on the right for volume, \
+                     on the left for brightness, drag horizontally to seek",
+                );
+                ui.end_row();
+
+                ui.label("repaint stats");
+                toggle_ui(ui, &mut self.state.show_repaint_stats)
+                    .on_hover_text("Show frames per second and what requested the last repaint");
+                ui.end_row();
             }
             Color => {
                 simple_slider!(brightness, set_brightness, -100..=100);
@@ -163,12 +299,69 @@ impl super::App {
                 simple_slider!(hue, set_hue, -100..=100);
                 simple_slider!(sharpen, set_sharpen, -4.0..=4.0);
             }
+            // the vertical sliders don't fit the grid, see `ui_equalizer`
+            Audio => {}
             Danmu => {
                 self.ui_setting_popup_contents_danmu(ui);
             }
         }
     }
 
+    fn ui_equalizer(&mut self, ui: &mut egui::Ui) {
+        let mut eq = self.player.state().equalizer.clone();
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("equalizer");
+            changed |= toggle_ui(ui, &mut eq.enabled).changed();
+
+            ui.add_space(10.0);
+            egui::ComboBox::from_id_salt("eq_preset_combo")
+                .height(80.0)
+                .selected_text(eq.preset().unwrap_or("Custom"))
+                .show_ui(ui, |ui| {
+                    for (name, gains) in EQ_PRESETS {
+                        if ui.selectable_label(eq.gains == gains, name).clicked() {
+                            eq.gains = gains;
+                            changed = true;
+                        }
+                    }
+                    // custom gains are made by moving the sliders
+                    let _ = ui.selectable_label(eq.preset().is_none(), "Custom");
+                });
+        });
+
+        ui.add_enabled_ui(eq.enabled, |ui| {
+            ui.spacing_mut().slider_width = 80.0;
+            ui.horizontal(|ui| {
+                let mut band = |ui: &mut egui::Ui, gain: &mut f32, text: &str| {
+                    ui.vertical(|ui| {
+                        let res = ui
+                            .add(
+                                egui::Slider::new(gain, mpv::audio_filter::EQ_GAIN_RANGE)
+                                    .vertical()
+                                    .step_by(0.5)
+                                    .show_value(false),
+                            )
+                            .on_hover_text(format!("{gain:+} dB"));
+                        changed |= res.changed();
+                        ui.small(text);
+                    });
+                };
+
+                band(ui, &mut eq.preamp, "pre");
+                ui.separator();
+                for ((_, text), gain) in EQ_BANDS.iter().zip(eq.gains.iter_mut()) {
+                    band(ui, gain, text);
+                }
+            });
+        });
+
+        if changed {
+            self.player.set_equalizer(&eq);
+        }
+    }
+
     fn ui_setting_popup_contents_danmu(&mut self, ui: &mut egui::Ui) {
         ui.label("danmu");
         toggle_ui(ui, &mut self.state.enable_danmu);
@@ -212,11 +405,7 @@ impl super::App {
 
         ui.label("danmu delay");
         if ui
-            .add(
-                egui::DragValue::new(&mut self.danmu.state_mut().delay)
-                    .speed(1.0)
-                    .suffix("s"),
-            )
+            .add(UnitDrag::new(&mut self.danmu.state_mut().delay, "s").max_decimals(1))
             .changed()
         {
             self.danmu.delay_danmu(self.danmu.state().delay);
@@ -238,7 +427,13 @@ impl super::App {
                     "Mpv options",
                     "Edit mpv option (effect on the next startup)",
                 ),
+                (
+                    Profiles,
+                    "Profiles",
+                    "Mpv options for some media only, assigned in the playlist",
+                ),
                 (DanmuFonts, "Danmu fonts", "Edit danmu fonts"),
+                (Shortcuts, "Shortcuts", "Rebind keyboard shortcuts"),
             ]
             .into_iter()
             {
@@ -258,6 +453,14 @@ impl super::App {
                     );
                 });
             }
+            Profiles => self.ui_profiles(ui),
+            Shortcuts => {
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        self.shortcuts.ui(ui);
+                    });
+            }
             DanmuFonts => {
                 let mut path_to_remove = None;
 
@@ -317,20 +520,120 @@ impl super::App {
         }
     }
 
+    fn ui_profiles(&mut self, ui: &mut egui::Ui) {
+        if self
+            .state
+            .cur_profile
+            .as_ref()
+            .is_none_or(|name| !self.playlist.profiles().contains_key(name))
+        {
+            self.state.cur_profile = self.playlist.profiles().keys().next().cloned();
+        }
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("profile_combo")
+                .selected_text(self.state.cur_profile.as_deref().unwrap_or("No profile"))
+                .show_ui(ui, |ui| {
+                    for name in self.playlist.profiles().keys() {
+                        ui.selectable_value(&mut self.state.cur_profile, Some(name.clone()), name);
+                    }
+                });
+
+            if frameless_btn(ui, ICON_ADD.to_string())
+                .on_hover_text("New profile")
+                .clicked()
+            {
+                self.state.cur_profile = Some(self.playlist.add_profile());
+                self.state.profile_renaming = true;
+            }
+
+            if let Some(name) = self.state.cur_profile.clone() {
+                let text = egui::RichText::new(ICON_TRASH.to_string()).color(Color32::LIGHT_RED);
+                if frameless_btn(ui, text)
+                    .on_hover_text("Remove profile")
+                    .clicked()
+                {
+                    self.playlist.remove_profile(&name);
+                    self.state.cur_profile = None;
+                    self.reapply_profile();
+                }
+            }
+        });
+
+        let Some(name) = self.state.cur_profile.clone() else {
+            ui.weak("Add a profile, then assign it to items or groups in the playlist");
+            return;
+        };
+
+        let mut new_name = name.clone();
+        if editable_label(ui, &mut new_name, &mut self.state.profile_renaming)
+            .on_hover_text("Double-click to rename")
+            .changed()
+            && self.playlist.rename_profile(&name, &new_name)
+        {
+            self.state.cur_profile = Some(new_name);
+            return;
+        }
+
+        let Some(options) = self.playlist.profile_options_mut(&name) else {
+            return;
+        };
+
+        let response = egui::ScrollArea::both()
+            .max_height(80.0)
+            .show(ui, |ui| {
+                ui.add(
+                    egui::TextEdit::multiline(options)
+                        .desired_rows(4)
+                        .code_editor()
+                        .hint_text("deinterlace=yes")
+                        .layouter(&mut opts_highlight::highlight),
+                )
+            })
+            .inner
+            .on_hover_text("Set when the media is loaded, back to the global ones after");
+
+        let (opts_before_init, _) = Player::parse_options(options);
+        if !opts_before_init.is_empty() {
+            let mut keys: Vec<_> = opts_before_init.into_keys().collect();
+            keys.sort_unstable();
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!("Only settable on startup, ignored: {}", keys.join(", ")),
+            );
+        }
+
+        if response.lost_focus() {
+            self.reapply_profile();
+        }
+    }
+
     pub fn ui_playlist_popup(&mut self, ui: &mut egui::Ui) {
-        let Some((list, media)) = self.state.playlist_cur_sel.as_ref() else {
+        let Some((list, media)) = self.state.playlist_cur_sel.clone() else {
             return;
         };
 
         if frameless_btn(ui, "Show in explorer").clicked() {
-            eapp_utils::open_in_explorer(media);
+            if let Err(err) = eapp_utils::open_in_explorer(&media) {
+                log::error!("open in explorer fails: {err}");
+            }
         }
 
+        ui.menu_button("Profile", |ui| {
+            let assigned = self.playlist.assigned_profile(&media);
+            if let Some(name) = profile_menu(ui, self.playlist.profiles(), assigned) {
+                self.playlist.assign_profile(&media, name.as_deref());
+                if self.player.state().media_path == media {
+                    self.reapply_profile();
+                }
+            }
+        });
+
         ui.visuals_mut().override_text_color = Some(egui::Color32::from_rgb(189, 21, 21));
 
         let text = egui::RichText::new("Delete the list").color(Color32::LIGHT_RED);
         if frameless_btn(ui, text).clicked() {
-            self.playlist.remove_list(list);
+            self.playlist.remove_list(&list);
         }
     }
 }
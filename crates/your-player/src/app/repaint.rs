@@ -0,0 +1,61 @@
+use eframe::egui::{self, Align2, Color32, FontId, vec2};
+use std::collections::VecDeque;
+
+/// Frames painted within the last second and what requested the last one,
+/// to notice repaints that are not needed
+#[derive(Default)]
+pub struct RepaintStats {
+    frame_times: VecDeque<f64>,
+    last_cause: String,
+}
+
+impl RepaintStats {
+    pub fn record(&mut self, ctx: &egui::Context) {
+        let now = ctx.input(|i| i.time);
+        self.frame_times.push_back(now);
+        while self
+            .frame_times
+            .front()
+            .is_some_and(|&time| now - time > 1.0)
+        {
+            self.frame_times.pop_front();
+        }
+
+        // nothing is requested for a frame painted on input
+        let causes = ctx.repaint_causes();
+        self.last_cause = if causes.is_empty() {
+            "input".to_owned()
+        } else {
+            causes
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+    }
+}
+
+impl super::App {
+    /// Repaints at most once per display refresh while danmu are moving, instead of
+    /// right away, which is coalesced with the repaints mpv requests for new video frames
+    pub fn request_danmu_frame(&self, ctx: &egui::Context) {
+        let interval = ctx.input(|i| i.predicted_dt);
+        ctx.request_repaint_after_secs(interval);
+    }
+
+    pub fn ui_repaint_stats(&self, ui: &egui::Ui) {
+        let stats = &self.repaint_stats;
+        let text = format!(
+            "{} fps\nlast repaint: {}",
+            stats.frame_times.len(),
+            stats.last_cause
+        );
+
+        let pos = self.state.content_rect.left_top() + vec2(8.0, 32.0);
+        let painter = ui.ctx().debug_painter();
+        let galley = painter.layout_no_wrap(text, FontId::monospace(12.0), Color32::WHITE);
+        let rect = Align2::LEFT_TOP.anchor_size(pos, galley.size()).expand(4.0);
+        painter.rect_filled(rect, 4.0, Color32::from_black_alpha(160));
+        painter.galley(rect.min + vec2(4.0, 4.0), galley, Color32::WHITE);
+    }
+}
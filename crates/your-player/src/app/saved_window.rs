@@ -0,0 +1,54 @@
+use eframe::egui::{self, ViewportCommand, WindowLevel};
+use serde::{Deserialize, Serialize};
+
+/// The window before it's shrunk for compact mode or picture-in-picture, to restore it when leaving
+#[derive(Deserialize, Serialize, Clone, Copy)]
+pub struct SavedWindow {
+    pub restore_pos: Option<egui::Pos2>,
+    pub restore_size: egui::Vec2,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl SavedWindow {
+    /// Takes the window as it is, and leaves fullscreen and maximized so that it can be resized.
+    /// Fullscreen is not restored, as the shrunk window is left to a normal one
+    pub fn take(ctx: &egui::Context) -> Self {
+        let (restore_pos, restore_size, is_fullscreen, maximized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.outer_rect.map(|rect| rect.min),
+                viewport.inner_rect.map(|rect| rect.size()),
+                viewport.fullscreen.unwrap_or(false),
+                viewport.maximized.unwrap_or(false),
+            )
+        });
+
+        if is_fullscreen {
+            ctx.send_viewport_cmd(ViewportCommand::Fullscreen(false));
+        }
+        if maximized {
+            ctx.send_viewport_cmd(ViewportCommand::Maximized(false));
+        }
+
+        Self {
+            restore_pos: restore_pos.filter(|_| !is_fullscreen),
+            restore_size: restore_size
+                .filter(|_| !is_fullscreen)
+                .unwrap_or(super::App::MIN_INNER_SIZE),
+            maximized: maximized && !is_fullscreen,
+        }
+    }
+
+    pub fn restore(self, ctx: &egui::Context) {
+        ctx.send_viewport_cmd(ViewportCommand::WindowLevel(WindowLevel::Normal));
+        ctx.send_viewport_cmd(ViewportCommand::MinInnerSize(super::App::MIN_INNER_SIZE));
+        ctx.send_viewport_cmd(ViewportCommand::InnerSize(self.restore_size));
+        if let Some(pos) = self.restore_pos {
+            ctx.send_viewport_cmd(ViewportCommand::OuterPosition(pos));
+        }
+        if self.maximized {
+            ctx.send_viewport_cmd(ViewportCommand::Maximized(true));
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use eapp_utils::shortcuts::ShortcutMap;
+use eframe::egui::{Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Action {
+    OpenUrl,
+    SeekBackward,
+    SeekForward,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+    Compact,
+    Pip,
+    PlayPause,
+}
+
+impl super::App {
+    pub const SHORTCUT_KEY: &'static str = ShortcutMap::<Action>::KEY;
+
+    /// Registers the default shortcuts to `shortcuts` loaded with the rebound ones
+    pub fn shortcut_map(mut shortcuts: ShortcutMap<Action>) -> ShortcutMap<Action> {
+        // the plain keys are pressed with any modifiers held, as they were before
+        shortcuts
+            .register(
+                Action::OpenUrl,
+                "Open URL",
+                KeyboardShortcut::new(Modifiers::COMMAND, Key::U),
+            )
+            .register_any_modifiers(Action::SeekBackward, "Seek backward", Key::ArrowLeft)
+            .register_any_modifiers(Action::SeekForward, "Seek forward", Key::ArrowRight)
+            .register_any_modifiers(Action::VolumeUp, "Volume up", Key::ArrowUp)
+            .register_any_modifiers(Action::VolumeDown, "Volume down", Key::ArrowDown)
+            .register_any_modifiers(Action::Mute, "Mute", Key::M)
+            .register_any_modifiers(Action::Compact, "Compact mode", Key::C)
+            .register_any_modifiers(Action::Pip, "Picture-in-picture", Key::P)
+            .register_any_modifiers(Action::PlayPause, "Play / pause", Key::Space);
+        shortcuts
+    }
+}
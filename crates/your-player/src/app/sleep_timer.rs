@@ -0,0 +1,160 @@
+use eapp_utils::codicons::{ICON_STOP_CIRCLE, ICON_WATCH};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::mpv::{self, player::PlayState};
+
+/// Minutes offered in the sleep timer popup
+const PRESET_MINUTES: [u32; 4] = [15, 30, 60, 90];
+
+#[derive(PartialEq, Deserialize, Serialize, Clone, Copy, Default)]
+pub enum SleepAction {
+    #[default]
+    Pause,
+    Stop,
+}
+
+/// Counts down to pausing or stopping the playback, in the time of the ui input
+pub struct SleepTimer {
+    deadline: Option<f64>,
+    pub custom_minutes: u32,
+}
+
+impl Default for SleepTimer {
+    fn default() -> Self {
+        Self {
+            deadline: None,
+            custom_minutes: 45,
+        }
+    }
+}
+
+impl SleepTimer {
+    pub fn arm(&mut self, now: f64, minutes: u32) {
+        self.deadline = Some(now + minutes as f64 * 60.0);
+    }
+
+    pub fn disarm(&mut self) {
+        self.deadline = None;
+    }
+
+    /// Seconds left, `None` if not armed
+    pub fn remaining(&self, now: f64) -> Option<f64> {
+        self.deadline.map(|deadline| (deadline - now).max(0.0))
+    }
+
+    /// Whether the deadline is passed, which disarms it
+    pub fn take_expired(&mut self, now: f64) -> bool {
+        if self.deadline.is_some_and(|deadline| now >= deadline) {
+            self.deadline = None;
+            return true;
+        }
+        false
+    }
+}
+
+impl super::App {
+    pub fn process_sleep_timer(&mut self, ui: &egui::Ui) {
+        let now = ui.input(|i| i.time);
+        if self.state.sleep_timer.take_expired(now) {
+            // playback is not kept awake once it's paused or stopped
+            match self.state.sleep_action {
+                SleepAction::Pause => self.player.set_play_state(PlayState::Pause),
+                SleepAction::Stop => self.player.set_play_state(PlayState::Stop),
+            }
+            return;
+        }
+
+        if let Some(remaining) = self.state.sleep_timer.remaining(now) {
+            self.waker.request_repaint_after_secs(remaining.min(1.0));
+        }
+    }
+
+    /// Small indicators of the sleep timer and "stop after current", beside the time
+    pub fn sleep_indicators(&self, ui: &egui::Ui) -> String {
+        let mut text = String::new();
+        let now = ui.input(|i| i.time);
+        if let Some(remaining) = self.state.sleep_timer.remaining(now) {
+            text += &format!("   {ICON_WATCH} {}", mpv::make_time_string(remaining));
+        }
+        if self.state.stop_after_current {
+            text += &format!("   {ICON_STOP_CIRCLE} after current");
+        }
+        text
+    }
+
+    pub fn ui_sleep_popup(&mut self, ui: &mut egui::Ui) {
+        ui.set_width(240.0);
+        let now = ui.input(|i| i.time);
+        let timer = &mut self.state.sleep_timer;
+
+        match timer.remaining(now) {
+            Some(remaining) => {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{ICON_WATCH} {} left",
+                        mpv::make_time_string(remaining)
+                    ));
+                    if ui.button("Cancel").clicked() {
+                        timer.disarm();
+                    }
+                });
+            }
+            None => {
+                ui.label(format!("{ICON_WATCH} Sleep timer"));
+            }
+        }
+
+        ui.horizontal(|ui| {
+            for minutes in PRESET_MINUTES {
+                if ui.button(format!("{minutes} min")).clicked() {
+                    timer.arm(now, minutes);
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut timer.custom_minutes)
+                    .range(1..=600)
+                    .suffix(" min"),
+            );
+            if ui.button("Start").clicked() {
+                timer.arm(now, timer.custom_minutes);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Then");
+            ui.selectable_value(&mut self.state.sleep_action, SleepAction::Pause, "Pause");
+            ui.selectable_value(&mut self.state.sleep_action, SleepAction::Stop, "Stop");
+        });
+
+        ui.separator();
+        ui.checkbox(&mut self.state.stop_after_current, "Stop after current")
+            .on_hover_text("Goes idle once the current media ends, only once");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sleep_timer_expires_once() {
+        let mut timer = SleepTimer::default();
+        assert_eq!(timer.remaining(0.0), None);
+        assert!(!timer.take_expired(1e9));
+
+        timer.arm(10.0, 15);
+        assert_eq!(timer.remaining(70.0), Some(840.0));
+        assert!(!timer.take_expired(909.0));
+        assert!(timer.take_expired(910.0));
+        assert!(!timer.take_expired(911.0));
+        assert_eq!(timer.remaining(911.0), None);
+
+        timer.arm(0.0, 1);
+        timer.disarm();
+        assert!(!timer.take_expired(60.0));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::subtitle::Cue;
+use eframe::egui;
+
+impl super::App {
+    /// Follows the media and the subtitle track, collecting the lines of embedded tracks
+    pub fn update_subtitles(&mut self) {
+        let state = self.player.state();
+        self.subtitles
+            .sync(&state.media_path, self.player.cur_subtitle_track());
+
+        if self.subtitles.is_embedded()
+            && state.play_state.is_playing()
+            && let Some((text, start, end)) = self.player.sub_text()
+        {
+            self.subtitles.sample(Cue {
+                start: *start,
+                end: *end,
+                text: text.clone(),
+            });
+        }
+    }
+
+    pub fn ui_playlist_subtitles(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.state.subtitle_follow, "Follow")
+                .on_hover_text("Scroll to the current line while playing");
+            if let Some(notice) = self.subtitles.notice() {
+                ui.weak(notice);
+            }
+        });
+
+        ui.add(
+            egui::TextEdit::singleline(&mut self.state.subtitle_key)
+                .desired_width(f32::INFINITY)
+                .hint_text("Filter lines"),
+        );
+
+        // cue times are the ones in the track, shown and seeked with the delay
+        let delay = self.player.state().sub_delay as f64;
+        let current = self
+            .subtitles
+            .current(self.player.state().playback_time - delay);
+
+        let key = self.state.subtitle_key.to_lowercase();
+        let cues = self.subtitles.cues();
+        let rows: Vec<usize> = (0..cues.len())
+            .filter(|&idx| key.is_empty() || cues[idx].text.to_lowercase().contains(&key))
+            .collect();
+
+        let text_style = egui::TextStyle::Body;
+        let row_height = ui.text_style_height(&text_style) + 4.0;
+
+        let mut scroll_area = egui::ScrollArea::both().auto_shrink([false, true]);
+        if self.state.subtitle_follow
+            && self.player.state().play_state.is_playing()
+            && current != self.state.subtitle_last_cue
+        {
+            self.state.subtitle_last_cue = current;
+            if let Some(current) = current
+                && let Ok(row) = rows.binary_search(&current)
+            {
+                let offset = row as f32 * row_height - ui.available_height() / 2.0;
+                scroll_area = scroll_area.vertical_scroll_offset(offset.max(0.0));
+            }
+        }
+
+        let mut seek_to = None;
+        scroll_area.show_rows(ui, row_height, rows.len(), |ui, row_range| {
+            egui::Grid::new("playlist_subtitles_grid")
+                .num_columns(2)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    for row in row_range {
+                        let idx = rows[row];
+                        let cue = &cues[idx];
+                        ui.label(crate::mpv::make_time_string(cue.start + delay));
+
+                        let res = ui
+                            .selectable_label(current == Some(idx), cue.text.replace('\n', " "))
+                            .on_hover_text(format!("{}\n\nRight-click to copy", cue.text));
+                        if res.clicked() {
+                            seek_to = Some(cue.start + delay);
+                        }
+                        if res.secondary_clicked() {
+                            ui.ctx().copy_text(cue.text.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some(playback_time) = seek_to {
+            self.player.seek(playback_time, false);
+        }
+    }
+}
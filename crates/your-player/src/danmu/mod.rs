@@ -89,6 +89,17 @@ pub enum DanmuType {
 
 pub type DanmuPtr = NonNull<DanmuData>;
 
+/// A jump of the playback time out of `0..=SEEK_THRESHOLD` between frames is a seek (in secs)
+pub const SEEK_THRESHOLD: f64 = 1.0;
+
+/// Danmu this close before the new position are shown again after a seek (in secs)
+pub const SEEK_REPLAY_WINDOW: f64 = 0.5;
+
+/// Whether the playback time went from `last_time` to `time` by seeking instead of playing
+pub fn is_seek(last_time: f64, time: f64) -> bool {
+    !(0.0..=SEEK_THRESHOLD).contains(&(time - last_time))
+}
+
 pub struct Manager {
     danmu: Vec<DanmuData>,
     emitted: HashSet<DanmuPtr>,
@@ -318,12 +329,28 @@ impl Manager {
     }
 
     pub fn clear(&mut self) {
+        self.reset_emitted();
+        self.danmu.clear();
+    }
+
+    /// Drops the emitted and pending danmu, so the ones around `new_time` are emitted again,
+    /// except the ones just before it which are pended right away
+    pub fn on_seek(&mut self, new_time: f64, regex: Option<&regex::Regex>) {
+        self.reset_emitted();
+
+        let start = (new_time - SEEK_REPLAY_WINDOW).max(0.0);
+        if start < new_time {
+            self.push_pending(start..new_time, regex);
+        }
+    }
+
+    fn reset_emitted(&mut self) {
         self.centered_emitted_map.clear();
         self.rolling_emitted_map.clear();
         self.rolling_pending.clear();
         self.centered_pending.clear();
         self.emitted.clear();
-        self.danmu.clear();
+        self.danmu.iter_mut().for_each(|d| d.emitted_data = None);
     }
 
     fn u32_to_rgb(color: u32) -> (u8, u8, u8) {
@@ -333,3 +360,70 @@ impl Manager {
         (r, g, b)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A rolling danmu every second from 0s to 9s
+    fn manager() -> Manager {
+        let mut manager = Manager::new(State::default());
+        manager.danmu = (0..10)
+            .map(|i| DanmuData {
+                playback_time_raw: i as f64,
+                playback_time: i as f64,
+                text: i.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        manager
+    }
+
+    fn pended(manager: &Manager) -> Vec<f64> {
+        manager
+            .danmu
+            .iter()
+            .filter(|d| d.emitted_data.is_some())
+            .map(|d| d.playback_time)
+            .collect()
+    }
+
+    #[test]
+    fn seek_detection() {
+        assert!(!is_seek(1.0, 1.0));
+        assert!(!is_seek(1.0, 1.5));
+        assert!(is_seek(1.0, 0.9));
+        assert!(is_seek(1.0, 2.5));
+    }
+
+    #[test]
+    fn backward_seek_replays_danmu() {
+        let mut manager = manager();
+        manager.push_pending(0.0..6.0, None);
+        assert_eq!(pended(&manager), [0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        manager.on_seek(2.2, None);
+        assert_eq!(pended(&manager), [2.0]);
+        assert_eq!(manager.rolling_pending.len(), 1);
+
+        manager.push_pending(3.0..3.1, None);
+        assert_eq!(pended(&manager), [2.0, 3.0]);
+    }
+
+    #[test]
+    fn forward_seek_skips_the_range() {
+        let mut manager = manager();
+        manager.push_pending(0.0..1.5, None);
+
+        manager.on_seek(7.0, None);
+        assert!(pended(&manager).is_empty());
+        assert!(manager.rolling_pending.is_empty());
+
+        manager.on_seek(7.3, None);
+        assert_eq!(pended(&manager), [7.0]);
+
+        let regex = regex::Regex::new("7").unwrap();
+        manager.on_seek(7.3, Some(&regex));
+        assert!(pended(&manager).is_empty());
+    }
+}
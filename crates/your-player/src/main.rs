@@ -4,16 +4,17 @@ pub(crate) mod app;
 pub(crate) mod danmu;
 pub(crate) mod mpv;
 pub(crate) mod playlist;
+pub(crate) mod subtitle;
 pub(crate) mod tex_register;
 
 fn main() {
-    eapp_utils::setup_loggers("your-player.log").unwrap();
+    eapp_utils::setup_loggers("your-player.log", eapp_utils::LogOptions::from_env()).unwrap();
 
     let options = eframe::NativeOptions {
         viewport: eframe::egui::ViewportBuilder::default()
             .with_decorations(false)
             .with_inner_size([1024.0, 576.0])
-            .with_min_inner_size([640.0, 480.0])
+            .with_min_inner_size(app::App::MIN_INNER_SIZE)
             .with_transparent(true)
             .with_icon(
                 eframe::icon_data::from_png_bytes(include_bytes!(
@@ -21,6 +22,7 @@ fn main() {
                 ))
                 .unwrap(),
             ),
+        persist_window: false,
         ..Default::default()
     };
 
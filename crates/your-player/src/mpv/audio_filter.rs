@@ -0,0 +1,170 @@
+//! Audio filters set by the player, each one is labeled in the mpv `af` chain,
+//! so they are changed without touching the others, including the ones in the options
+
+use serde::{Deserialize, Serialize};
+
+/// Center frequencies of the equalizer bands
+pub const EQ_BANDS: [(f32, &str); 10] = [
+    (31.0, "31"),
+    (62.0, "62"),
+    (125.0, "125"),
+    (250.0, "250"),
+    (500.0, "500"),
+    (1000.0, "1k"),
+    (2000.0, "2k"),
+    (4000.0, "4k"),
+    (8000.0, "8k"),
+    (16000.0, "16k"),
+];
+
+/// Range of the band gains and the preamp, in dB
+pub const EQ_GAIN_RANGE: std::ops::RangeInclusive<f32> = -12.0..=12.0;
+
+pub const EQ_PRESETS: [(&str, [f32; 10]); 3] = [
+    ("Flat", [0.0; 10]),
+    ("Rock", [5.0, 4.0, 3.0, 1.0, -1.0, -1.0, 1.0, 3.0, 4.0, 5.0]),
+    (
+        "Vocal",
+        [-3.0, -2.0, -1.0, 1.0, 3.0, 4.0, 4.0, 2.0, 0.0, -1.0],
+    ),
+];
+
+#[derive(PartialEq, Deserialize, Serialize, Clone, Debug)]
+#[serde(default)]
+pub struct EqSettings {
+    pub enabled: bool,
+    pub preamp: f32,
+    pub gains: [f32; 10],
+}
+
+impl Default for EqSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            preamp: 0.0,
+            gains: [0.0; 10],
+        }
+    }
+}
+
+impl EqSettings {
+    /// Name of the preset the gains are, `None` if they are custom
+    pub fn preset(&self) -> Option<&'static str> {
+        EQ_PRESETS
+            .iter()
+            .find(|(_, gains)| *gains == self.gains)
+            .map(|(name, _)| *name)
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.preamp == 0.0 && self.gains.iter().all(|gain| *gain == 0.0)
+    }
+
+    /// The filter of the settings, `None` if it changes nothing
+    pub fn filter(&self) -> Option<String> {
+        if !self.enabled || self.is_flat() {
+            return None;
+        }
+
+        let mut graph = Vec::with_capacity(EQ_BANDS.len() + 1);
+        if self.preamp != 0.0 {
+            graph.push(format!("volume={}dB", self.preamp));
+        }
+        for ((freq, _), gain) in EQ_BANDS.iter().zip(self.gains) {
+            if gain != 0.0 {
+                graph.push(format!("equalizer=f={freq}:t=o:w=1:g={gain}"));
+            }
+        }
+
+        Some(format!("lavfi=[{}]", graph.join(",")))
+    }
+}
+
+/// Filters the player added to the `af` chain, by label
+#[derive(Default)]
+pub struct FilterChain {
+    filters: Vec<(&'static str, String)>,
+}
+
+impl FilterChain {
+    /// Sets or removes the filter of `label`,
+    /// returns the arguments of the `af` command to run, `None` if it's not changed
+    pub fn set(&mut self, label: &'static str, filter: Option<String>) -> Option<Vec<String>> {
+        let idx = self.filters.iter().position(|(v, _)| *v == label);
+        match (idx, filter) {
+            (Some(idx), Some(filter)) => {
+                if self.filters[idx].1 == filter {
+                    return None;
+                }
+
+                // a filter with the same label is replaced in place
+                let args = vec!["add".to_owned(), format!("@{label}:{filter}")];
+                self.filters[idx].1 = filter;
+                Some(args)
+            }
+            (None, Some(filter)) => {
+                let args = vec!["add".to_owned(), format!("@{label}:{filter}")];
+                self.filters.push((label, filter));
+                Some(args)
+            }
+            (Some(idx), None) => {
+                self.filters.remove(idx);
+                Some(vec!["remove".to_owned(), format!("@{label}")])
+            }
+            (None, None) => None,
+        }
+    }
+
+    /// Forgets the filter of `label`, when applying it fails
+    pub fn forget(&mut self, label: &str) {
+        self.filters.retain(|(v, _)| *v != label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eq_filter_string() {
+        let mut eq = EqSettings::default();
+        assert_eq!(eq.filter(), None);
+
+        eq.gains[0] = 3.0;
+        eq.gains[9] = -1.5;
+        assert_eq!(eq.filter(), None);
+
+        eq.enabled = true;
+        eq.preamp = -2.0;
+        assert_eq!(
+            eq.filter().unwrap(),
+            "lavfi=[volume=-2dB,equalizer=f=31:t=o:w=1:g=3,equalizer=f=16000:t=o:w=1:g=-1.5]"
+        );
+        assert_eq!(eq.preset(), None);
+
+        eq.gains = EQ_PRESETS[1].1;
+        assert_eq!(eq.preset(), Some("Rock"));
+    }
+
+    #[test]
+    fn filter_chain_changes_only_its_label() {
+        let mut chain = FilterChain::default();
+        assert_eq!(chain.set("eq", None), None);
+
+        let args = chain.set("eq", Some("lavfi=[volume=1dB]".to_owned()));
+        assert_eq!(args.unwrap(), ["add", "@eq:lavfi=[volume=1dB]"]);
+        assert_eq!(chain.set("eq", Some("lavfi=[volume=1dB]".to_owned())), None);
+
+        let args = chain.set("loudnorm", Some("lavfi=[loudnorm]".to_owned()));
+        assert_eq!(args.unwrap(), ["add", "@loudnorm:lavfi=[loudnorm]"]);
+
+        let args = chain.set("eq", Some("lavfi=[volume=2dB]".to_owned()));
+        assert_eq!(args.unwrap(), ["add", "@eq:lavfi=[volume=2dB]"]);
+
+        assert_eq!(chain.set("eq", None).unwrap(), ["remove", "@eq"]);
+        assert_eq!(chain.set("eq", None), None);
+
+        chain.forget("loudnorm");
+        assert!(chain.set("loudnorm", None).is_none());
+    }
+}
@@ -11,6 +11,7 @@ use std::{
     },
 };
 
+pub(crate) mod audio_filter;
 pub(crate) mod player;
 pub(crate) mod preview;
 
@@ -38,6 +39,18 @@ pub fn get_ext_lowercase(filename: &Path) -> Option<String> {
         .map(|ext| ext.to_str().unwrap_or("").to_ascii_lowercase())
 }
 
+/// Whether `path` is a URL for mpv to stream, such as `https://...` or `ytdl://...`
+pub fn is_url(path: &str) -> bool {
+    path.split_once("://").is_some_and(|(scheme, _)| {
+        // a single letter is a drive of Windows
+        scheme.len() > 1
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    })
+}
+
 pub fn make_time_string(seconds: f64) -> String {
     let hour = (seconds / 3600.0) as i32;
     let min = (seconds / 60.0) as i32 % 60;
@@ -160,3 +173,18 @@ pub unsafe fn get_frame_buffer_with_texture(
         Ok((fbo, tex))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_and_paths() {
+        assert!(is_url("https://example.com/a.mp4"));
+        assert!(is_url("ytdl://abc"));
+        assert!(is_url("rtmp+tls://host/live"));
+        assert!(!is_url("C://videos/a.mp4"));
+        assert!(!is_url("/home/videos/a.mp4"));
+        assert!(!is_url("D:\\videos\\a://b.mp4"));
+    }
+}
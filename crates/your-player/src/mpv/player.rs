@@ -1,5 +1,12 @@
-use super::{AUDIO_FORMATS, get_ext_lowercase_from_str};
-use eframe::glow::{self, HasContext};
+use super::{
+    AUDIO_FORMATS,
+    audio_filter::{EqSettings, FilterChain},
+    get_ext_lowercase_from_str,
+};
+use eframe::{
+    egui::Color32,
+    glow::{self, HasContext},
+};
 use libmpv::Format;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -13,6 +20,20 @@ pub const VIDEO_ASPECT_LIST: [(&str, f64); 4] = [
     ("2.35:1", 2.35 / 1.0),
 ];
 
+/// Decoders for [`Player::set_hwdec`], the backends not on this platform are left out
+pub const HWDEC_LIST: &[&str] = &[
+    "auto",
+    "no",
+    #[cfg(windows)]
+    "d3d11va",
+    #[cfg(any(windows, target_os = "linux"))]
+    "nvdec",
+    #[cfg(target_os = "linux")]
+    "vaapi",
+    #[cfg(target_os = "macos")]
+    "videotoolbox",
+];
+
 pub type ListIdx = usize;
 
 #[derive(Clone, PartialEq, Debug)]
@@ -42,6 +63,25 @@ pub struct State {
     #[serde(skip)]
     pub is_audio: bool,
 
+    /// Path passed to [`Player::set_media`]
+    #[serde(skip)]
+    pub media_path: String,
+
+    /// Path and error message of the media failed to play, kept after it's stopped
+    #[serde(skip)]
+    pub failed_media: Option<(String, String)>,
+    #[serde(skip)]
+    last_error_log: Option<String>,
+
+    /// Playback is waiting for data
+    #[serde(skip)]
+    pub core_idle: bool,
+    #[serde(skip)]
+    pub paused_for_cache: bool,
+    /// Percentage of the cache filled to resume
+    #[serde(skip)]
+    pub cache_buffering: i64,
+
     pub video_rotate: ListIdx,
 
     #[serde(skip)]
@@ -51,9 +91,13 @@ pub struct State {
 
     pub sub_visibility: bool,
     pub sub_delay: i64,
+    /// Colors of the text subtitles, ASS ones use their own styles
+    pub sub_color: Color32,
+    pub sub_border_color: Color32,
     pub speed: f64,
     pub mute: bool,
     pub volume: i64,
+    pub equalizer: EqSettings,
     pub video_aspect: ListIdx,
 
     pub brightness: i64,
@@ -63,16 +107,37 @@ pub struct State {
     pub hue: i64,
     pub sharpen: f64,
 
+    /// Decoder chosen by user, `None` keeps the one in the options
+    pub hwdec: Option<String>,
+    /// Hardware decoder in use, `no` if it's decoded by software
+    #[serde(skip)]
+    pub hwdec_current: String,
+    #[serde(skip)]
+    pub video_codec: String,
+
+    /// Language codes tried in order when a media is loaded, comma separated, e.g. `ja,jpn`
+    pub preferred_audio_langs: String,
+    pub preferred_sub_langs: String,
+
     #[serde(skip)]
     pub chapters: Vec<(String, f64)>,
+    /// Title, id and language of each track
+    #[serde(skip)]
+    pub audio_tracks: Vec<(String, i64, String)>,
     #[serde(skip)]
-    pub audio_tracks: Vec<(String, i64)>,
+    pub subtitle_tracks: Vec<(String, i64, String)>,
+    /// File of each subtitle track, `None` if it's embedded in the media
     #[serde(skip)]
-    pub subtitle_tracks: Vec<(String, i64)>,
+    pub subtitle_files: Vec<Option<String>>,
     #[serde(skip)]
     pub cur_audio_idx: usize,
     #[serde(skip)]
     pub cur_subtitle_idx: usize,
+
+    /// Preferred languages are not applied to the loaded media yet,
+    /// once applied the tracks chosen by user are kept
+    #[serde(skip)]
+    langs_pending: bool,
 }
 
 impl Default for State {
@@ -82,14 +147,23 @@ impl Default for State {
             media_size: (0, 0),
             play_state: PlayState::Stop,
             is_audio: false,
+            media_path: String::default(),
+            failed_media: None,
+            last_error_log: None,
+            core_idle: false,
+            paused_for_cache: false,
+            cache_buffering: 0,
             video_rotate: 0,
             playback_time: 0.0,
             duration: 0.0,
             sub_visibility: true,
             sub_delay: 0,
+            sub_color: Color32::WHITE,
+            sub_border_color: Color32::BLACK,
             speed: 1.0,
             mute: false,
             volume: 50,
+            equalizer: EqSettings::default(),
             video_aspect: 0,
             brightness: 0,
             contrast: 0,
@@ -97,11 +171,18 @@ impl Default for State {
             gamma: 0,
             hue: 0,
             sharpen: 0.0,
+            hwdec: None,
+            hwdec_current: Default::default(),
+            video_codec: Default::default(),
+            preferred_audio_langs: Default::default(),
+            preferred_sub_langs: Default::default(),
             chapters: Default::default(),
             audio_tracks: Default::default(),
             subtitle_tracks: Default::default(),
+            subtitle_files: Default::default(),
             cur_audio_idx: 0,
             cur_subtitle_idx: 0,
+            langs_pending: false,
         }
     }
 }
@@ -119,7 +200,45 @@ impl State {
         self.cur_audio_idx = 0;
         self.cur_subtitle_idx = 0;
         self.is_audio = false;
+        self.langs_pending = false;
+        self.media_path.clear();
+        self.core_idle = false;
+        self.paused_for_cache = false;
+        self.cache_buffering = 0;
+        self.hwdec_current.clear();
+        self.video_codec.clear();
+    }
+
+    /// Whether it should be playing but is stalled
+    pub fn is_buffering(&self) -> bool {
+        self.paused_for_cache || (self.core_idle && self.play_state.is_playing())
     }
+
+    /// Whether the backend chosen by user isn't available for the current video,
+    /// it's decoded by software instead
+    pub fn is_hwdec_fallback(&self) -> bool {
+        let Some(hwdec) = self.hwdec.as_deref() else {
+            return false;
+        };
+
+        !matches!(hwdec, "auto" | "no")
+            && !self.is_audio
+            && !self.video_codec.is_empty()
+            && !self.hwdec_current.starts_with(hwdec)
+    }
+}
+
+/// Index of the first track matching `preferred_langs` in order, ignoring case
+fn find_preferred_track(tracks: &[(String, i64, String)], preferred_langs: &str) -> Option<usize> {
+    preferred_langs
+        .split(',')
+        .map(str::trim)
+        .filter(|lang| !lang.is_empty())
+        .find_map(|lang| {
+            tracks
+                .iter()
+                .position(|(_, _, track_lang)| track_lang.eq_ignore_ascii_case(lang))
+        })
 }
 
 pub struct Player {
@@ -127,6 +246,18 @@ pub struct Player {
     tex: glow::Texture,
     fbo: glow::Framebuffer,
     state: State,
+
+    /// Values of the options a profile changed, to set back when it's no longer applied
+    profile_revert: HashMap<String, String>,
+
+    /// Filters the player added to the `af` chain
+    audio_filters: FilterChain,
+
+    /// Subtitles to add once the loading media is loaded, `None` if it's not loading
+    pending_subtitles: Option<Vec<String>>,
+
+    /// The subtitle line shown now with its start and end time, read when it changes
+    sub_line: Option<(String, f64, f64)>,
 }
 
 impl Player {
@@ -161,6 +292,12 @@ impl Player {
         e.observe_property("eof-reached", Format::Flag, 0)?;
         e.observe_property("track-list", Format::Node, 0)?;
         e.observe_property("chapter-list", Format::Node, 0)?;
+        e.observe_property("core-idle", Format::Flag, 0)?;
+        e.observe_property("paused-for-cache", Format::Flag, 0)?;
+        e.observe_property("cache-buffering-state", Format::Int64, 0)?;
+        e.observe_property("hwdec-current", Format::String, 0)?;
+        e.observe_property("video-codec", Format::String, 0)?;
+        e.observe_property("sub-text", Format::String, 0)?;
 
         let state = state.clone();
 
@@ -172,6 +309,10 @@ impl Player {
                 tex,
                 fbo,
                 state,
+                profile_revert: HashMap::new(),
+                audio_filters: FilterChain::default(),
+                pending_subtitles: None,
+                sub_line: None,
             };
 
             this.apply_mpv_related_states();
@@ -191,16 +332,31 @@ impl Player {
                         text,
                         log_level: _,
                     } => match level {
-                        "fatal" | "error" => log::error!("[{prefix}][{level}]: {text}"),
+                        "fatal" | "error" => {
+                            log::error!("[{prefix}][{level}]: {text}");
+                            self.state.last_error_log = Some(text.trim().to_owned());
+                        }
                         "warn" => log::warn!("[{prefix}][{level}]: {text}"),
                         _ => log::info!("[{prefix}][{level}]: {text}"),
                     },
                     Event::EndFile(reason) => {
                         if reason == libmpv::mpv_end_file_reason::Error {
+                            let media_path = self.state.media_path.clone();
+                            let message = self
+                                .state
+                                .last_error_log
+                                .take()
+                                .unwrap_or_else(|| "Unknown error".to_owned());
                             self.set_play_state_internal(PlayState::Stop);
+                            self.state.failed_media = Some((media_path, message));
+                            self.pending_subtitles = None;
                         }
                     }
                     Event::FileLoaded => {
+                        for path in self.pending_subtitles.take().unwrap_or_default() {
+                            self.add_subtitle_now(&path);
+                        }
+
                         eapp_utils::capture_error!(
                             err => log::error!("mpv get property fails: {err}"),
                             {
@@ -252,6 +408,48 @@ impl Player {
                                     self.state.playback_time = value;
                                 }
                             }
+                            "core-idle" => {
+                                if let Flag(value) = change {
+                                    self.state.core_idle = value;
+                                }
+                            }
+                            "paused-for-cache" => {
+                                if let Flag(value) = change {
+                                    self.state.paused_for_cache = value;
+                                }
+                            }
+                            "cache-buffering-state" => {
+                                if let Int64(value) = change {
+                                    self.state.cache_buffering = value;
+                                }
+                            }
+                            "hwdec-current" => {
+                                if let Str(value) = change {
+                                    self.state.hwdec_current = value.to_owned();
+                                    if self.state.is_hwdec_fallback() {
+                                        log::warn!(
+                                            "hwdec '{}' is not available, fall back to '{value}'",
+                                            self.state.hwdec.as_deref().unwrap_or_default()
+                                        );
+                                    }
+                                }
+                            }
+                            "video-codec" => {
+                                if let Str(value) = change {
+                                    self.state.video_codec = value.to_owned();
+                                }
+                            }
+                            "sub-text" => {
+                                let handle = &self.mpv.handle;
+                                self.sub_line = match change {
+                                    Str(text) if !text.is_empty() => handle
+                                        .get_property::<f64>("sub-start")
+                                        .ok()
+                                        .zip(handle.get_property::<f64>("sub-end").ok())
+                                        .map(|(start, end)| (text.to_owned(), start, end)),
+                                    _ => None,
+                                };
+                            }
                             "eof-reached" => {
                                 if let Flag(end_reached) = change
                                     && end_reached
@@ -263,6 +461,7 @@ impl Player {
                                 if let Node(node) = change {
                                     self.state.audio_tracks.clear();
                                     self.state.subtitle_tracks.clear();
+                                    self.state.subtitle_files.clear();
 
                                     || -> Option<()> {
                                         for item in node.to_array()? {
@@ -272,23 +471,35 @@ impl Player {
                                             if let Some(str) = map.get("title") {
                                                 title = str.to_str()?;
                                             }
+                                            let mut lang = "";
+                                            if let Some(str) = map.get("lang") {
+                                                lang = str.to_str()?;
+                                            }
                                             let track_type = map.get("type")?.to_str()?;
                                             let id = map.get("id")?.to_i64()?;
 
+                                            let title = if lang.is_empty() {
+                                                title.to_owned()
+                                            } else {
+                                                format!("{title} [{lang}]")
+                                            };
+                                            let track = (title, id, lang.to_owned());
                                             if track_type == "audio" {
-                                                self.state
-                                                    .audio_tracks
-                                                    .push((title.to_owned(), id));
-                                            }
-                                            if track_type == "sub" {
-                                                self.state
-                                                    .subtitle_tracks
-                                                    .push((title.to_owned(), id));
+                                                self.state.audio_tracks.push(track);
+                                            } else if track_type == "sub" {
+                                                let file = match map.get("external-filename") {
+                                                    Some(str) => Some(str.to_str()?.to_owned()),
+                                                    None => None,
+                                                };
+                                                self.state.subtitle_tracks.push(track);
+                                                self.state.subtitle_files.push(file);
                                             }
                                         }
                                         Some(())
                                     }();
 
+                                    self.apply_preferred_langs();
+
                                     self.state.cur_audio_idx = self
                                         .state
                                         .cur_audio_idx
@@ -349,9 +560,12 @@ impl Player {
         self.set_video_rotate(self.state.video_rotate);
         self.set_sub_visibility(self.state.sub_visibility);
         self.set_sub_delay(self.state.sub_delay);
+        self.set_sub_color(self.state.sub_color);
+        self.set_sub_border_color(self.state.sub_border_color);
         self.set_speed(self.state.speed);
         self.set_mute(self.state.mute);
         self.set_volume(self.state.volume);
+        self.set_equalizer(&self.state.equalizer.clone());
         self.set_video_aspect(self.state.video_aspect);
         self.set_brightness(self.state.brightness);
         self.set_contrast(self.state.contrast);
@@ -359,9 +573,13 @@ impl Player {
         self.set_gamma(self.state.gamma);
         self.set_hue(self.state.hue);
         self.set_sharpen(self.state.sharpen);
+        if let Some(hwdec) = self.state.hwdec.clone() {
+            self.set_hwdec(hwdec);
+        }
     }
 
-    fn parse_options(options: &str) -> (HashMap<&str, &str>, HashMap<&str, &str>) {
+    /// Splits options into the ones only settable before init, and the others
+    pub fn parse_options(options: &str) -> (HashMap<&str, &str>, HashMap<&str, &str>) {
         let keys_before_init = HashSet::from([
             "config",
             "config-dir",
@@ -397,6 +615,12 @@ impl Player {
     }
 }
 
+/// The `r/g/b/a` form of mpv colors, each in `0.0..=1.0`
+fn mpv_color(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied().map(|v| v as f32 / 255.0);
+    format!("{r}/{g}/{b}/{a}")
+}
+
 macro_rules! simple_setter {
     ($fn_name:ident, $var_name:ident, $mpv_name:literal, $type:ty) => {
         pub fn $fn_name(&mut self, $var_name: $type) {
@@ -417,13 +641,57 @@ impl Player {
         match self.mpv.handle.command_async(0, &["loadfile", media_path]) {
             Ok(_) => {
                 self.set_play_state_internal(PlayState::Play);
+                self.state.langs_pending = true;
+                self.state.media_path = media_path.to_owned();
+                self.pending_subtitles = Some(Vec::new());
+                self.state.failed_media = None;
+                self.state.last_error_log = None;
                 self.state.is_audio = get_ext_lowercase_from_str(media_path)
                     .is_some_and(|ext| AUDIO_FORMATS.contains(&ext.as_str()));
             }
-            Err(err) => log::error!("set media '{media_path}' fails: {err}"),
+            Err(err) => {
+                log::error!("set media '{media_path}' fails: {err}");
+                self.state.failed_media = Some((media_path.to_owned(), err.to_string()));
+            }
         }
     }
 
+    /// Sets the options of a profile, after setting back the ones of the last applied profile,
+    /// call it before [`Player::set_media`] so that they take effect for the new media
+    pub fn apply_profile(&mut self, options: Option<&str>) {
+        for (key, value) in std::mem::take(&mut self.profile_revert) {
+            if let Err(err) = self.mpv.handle.set_property(&key, value.as_str()) {
+                log::error!("revert profile option '{key}' fails: {err}");
+            }
+        }
+
+        let Some(options) = options else {
+            return;
+        };
+
+        let (opts_before_init, opts) = Self::parse_options(options);
+        for key in opts_before_init.keys() {
+            log::warn!("profile option '{key}' is only settable on startup, ignored");
+        }
+
+        for (key, value) in opts {
+            let old_value = self.mpv.handle.get_property::<String>(key);
+            match self.mpv.handle.set_property(key, value) {
+                Ok(_) => {
+                    if let Ok(old_value) = old_value {
+                        self.profile_revert.insert(key.to_owned(), old_value);
+                    }
+                }
+                Err(err) => log::warn!("set profile option '{key}' fails: {err}"),
+            }
+        }
+    }
+
+    /// Hides the error of the media failed to play
+    pub fn clear_failed_media(&mut self) {
+        self.state.failed_media = None;
+    }
+
     pub fn set_play_state(&mut self, play_state: PlayState) {
         if self.state.play_state == PlayState::Stop || play_state == PlayState::EndReached {
             return;
@@ -496,6 +764,64 @@ impl Player {
         }
     }
 
+    pub fn set_sub_color(&mut self, sub_color: Color32) {
+        match self
+            .mpv
+            .handle
+            .set_property("sub-color", mpv_color(sub_color).as_str())
+        {
+            Ok(_) => self.state.sub_color = sub_color,
+            Err(err) => log::error!("set sub color fails: {err}"),
+        }
+    }
+
+    pub fn set_sub_border_color(&mut self, sub_border_color: Color32) {
+        match self
+            .mpv
+            .handle
+            .set_property("sub-border-color", mpv_color(sub_border_color).as_str())
+        {
+            Ok(_) => self.state.sub_border_color = sub_border_color,
+            Err(err) => log::error!("set sub border color fails: {err}"),
+        }
+    }
+
+    /// Replaces the equalizer in the `af` chain, the other filters are kept
+    pub fn set_equalizer(&mut self, equalizer: &EqSettings) {
+        self.state.equalizer = equalizer.clone();
+        self.set_audio_filter("eq", equalizer.filter());
+    }
+
+    /// Adds an external subtitle file to the media and selects it,
+    /// it's added after the media is loaded if it's still loading
+    pub fn add_subtitle(&mut self, path: &str) {
+        if self.state.media_path.is_empty() {
+            log::warn!("add subtitle '{path}' fails: no media is playing");
+        } else if let Some(pending) = &mut self.pending_subtitles {
+            pending.push(path.to_owned());
+        } else {
+            self.add_subtitle_now(path);
+        }
+    }
+
+    fn add_subtitle_now(&mut self, path: &str) {
+        if let Err(err) = self.mpv.handle.command("sub-add", &[path, "select"]) {
+            log::error!("add subtitle '{path}' fails: {err}");
+        }
+    }
+
+    fn set_audio_filter(&mut self, label: &'static str, filter: Option<String>) {
+        let Some(args) = self.audio_filters.set(label, filter) else {
+            return;
+        };
+
+        let args: Vec<_> = args.iter().map(String::as_str).collect();
+        if let Err(err) = self.mpv.handle.command("af", &args) {
+            log::error!("set audio filter '{label}' fails: {err}");
+            self.audio_filters.forget(label);
+        }
+    }
+
     pub fn set_video_aspect(&mut self, video_aspect: ListIdx) {
         match self
             .mpv
@@ -527,6 +853,44 @@ impl Player {
 
     simple_setter!(set_sharpen, sharpen, "sharpen", f64);
 
+    /// Switches the decoder, it also applies to the playing video
+    pub fn set_hwdec(&mut self, hwdec: String) {
+        match self.mpv.handle.set_property("hwdec", hwdec.as_str()) {
+            Ok(_) => self.state.hwdec = Some(hwdec),
+            Err(err) => log::error!("set hwdec fails: {err}"),
+        }
+    }
+
+    pub fn set_preferred_audio_langs(&mut self, langs: String) {
+        self.state.preferred_audio_langs = langs;
+    }
+
+    pub fn set_preferred_sub_langs(&mut self, langs: String) {
+        self.state.preferred_sub_langs = langs;
+    }
+
+    /// Picks the tracks by preferred languages once per media, the current ones are kept
+    /// if nothing matches
+    fn apply_preferred_langs(&mut self) {
+        if !self.state.langs_pending
+            || (self.state.audio_tracks.is_empty() && self.state.subtitle_tracks.is_empty())
+        {
+            return;
+        }
+        self.state.langs_pending = false;
+
+        if let Some(idx) =
+            find_preferred_track(&self.state.audio_tracks, &self.state.preferred_audio_langs)
+        {
+            self.state.cur_audio_idx = idx;
+        }
+        if let Some(idx) =
+            find_preferred_track(&self.state.subtitle_tracks, &self.state.preferred_sub_langs)
+        {
+            self.state.cur_subtitle_idx = idx;
+        }
+    }
+
     pub fn set_cur_audio_idx(&mut self, cur_audio_idx: usize) {
         if self.state.audio_tracks.is_empty() {
             return;
@@ -543,6 +907,23 @@ impl Player {
         }
     }
 
+    /// Id and file of the current subtitle track
+    pub fn cur_subtitle_track(&self) -> Option<(i64, Option<&str>)> {
+        let idx = self.state.cur_subtitle_idx;
+        let (_, id, _) = self.state.subtitle_tracks.get(idx)?;
+        let file = self
+            .state
+            .subtitle_files
+            .get(idx)
+            .and_then(|file| file.as_deref());
+        Some((*id, file))
+    }
+
+    /// The subtitle line shown now with its start and end time
+    pub fn sub_text(&self) -> Option<&(String, f64, f64)> {
+        self.sub_line.as_ref()
+    }
+
     pub fn set_cur_subtitle_idx(&mut self, cur_subtitle_idx: usize) {
         if self.state.subtitle_tracks.is_empty() {
             return;
@@ -569,4 +950,36 @@ mod tests {
         let opts = crate::mpv::DEFAULT_OPTS;
         println!("{:?}", Player::parse_options(opts));
     }
+
+    #[test]
+    fn preferred_track_follows_lang_order() {
+        let tracks = vec![
+            ("English".to_owned(), 1, "eng".to_owned()),
+            ("Unknown".to_owned(), 2, String::new()),
+            ("Japanese".to_owned(), 3, "JPN".to_owned()),
+        ];
+
+        assert_eq!(find_preferred_track(&tracks, "ja, jpn, eng"), Some(2));
+        assert_eq!(find_preferred_track(&tracks, "eng,jpn"), Some(0));
+        assert_eq!(find_preferred_track(&tracks, "zh,chi"), None);
+        assert_eq!(find_preferred_track(&tracks, ""), None);
+    }
+
+    #[test]
+    fn hwdec_fallback() {
+        let mut state = State {
+            hwdec: Some("nvdec".to_owned()),
+            video_codec: "h264".to_owned(),
+            hwdec_current: "no".to_owned(),
+            ..Default::default()
+        };
+        assert!(state.is_hwdec_fallback());
+
+        state.hwdec_current = "nvdec".to_owned();
+        assert!(!state.is_hwdec_fallback());
+
+        state.hwdec = Some("auto".to_owned());
+        state.hwdec_current = "no".to_owned();
+        assert!(!state.is_hwdec_fallback());
+    }
 }
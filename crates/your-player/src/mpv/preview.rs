@@ -1,24 +1,43 @@
 use crate::mpv::get_texture;
-use eframe::{
-    egui::ahash::{HashMap, HashMapExt},
-    glow::{self, HasContext},
-};
+use eframe::glow::{self, HasContext};
 use libmpv::Format;
+use std::collections::BTreeMap;
 
 pub struct Preview {
     mpv: super::BasicMpvWrapper,
     tex: glow::Texture,
     fbo: glow::Framebuffer,
+
+    /// Longest side of the previews
     max_size: i64,
+
+    /// Size of the video, zero until it is loaded
+    media_size: (i64, i64),
+
     size: (i64, i64),
-    preview: HashMap<u64, (bool, glow::Texture)>,
+
+    /// The render texture is reallocated on next update
+    size_changed: bool,
+
+    preview: Cache<glow::Texture>,
+
+    /// Textures of evicted previews, which are shrunk on next update
+    to_shrink: Vec<glow::Texture>,
+
+    /// Shrunk textures waiting to be reused
+    spare: Vec<glow::Texture>,
+
     update_idx: u64,
     cur_seek_idx: u64,
     interval: f64,
+
+    /// Whether a media is loaded, nothing is previewed after [`Preview::unload`]
+    loaded: bool,
 }
 
 impl Preview {
     const MAX_PREVIEW_LEN: f64 = 1000.0;
+    pub const DEFAULT_CAPACITY: usize = 120;
 
     pub fn new(
         max_size: i64,
@@ -32,7 +51,7 @@ impl Preview {
         mpv.event_ctx
             .observe_property("duration", Format::Double, 0)?;
 
-        let preview = HashMap::new();
+        let preview = Cache::new(Self::DEFAULT_CAPACITY);
         let size = (max_size, max_size);
         let update_idx = 0;
         let cur_seek_idx = 0;
@@ -47,11 +66,16 @@ impl Preview {
                 tex,
                 fbo,
                 max_size,
+                media_size: (0, 0),
                 size,
+                size_changed: false,
                 preview,
+                to_shrink: Vec::new(),
+                spare: Vec::new(),
                 update_idx,
                 cur_seek_idx,
                 interval,
+                loaded: false,
             })
         }
     }
@@ -60,9 +84,37 @@ impl Preview {
         self.interval = 5.0;
         self.cur_seek_idx = 0;
         self.update_idx = 0;
-        self.preview
-            .iter_mut()
-            .for_each(|(_, (ready, _))| *ready = false);
+        self.to_shrink.extend(self.preview.drain());
+    }
+
+    /// Sets the most previews kept, the least recently shown ones are dropped first
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.preview.capacity = capacity.max(1);
+    }
+
+    /// Sets the longest side of the previews, the cached ones are dropped
+    pub fn set_max_size(&mut self, max_size: i64) {
+        if max_size == self.max_size {
+            return;
+        }
+
+        self.max_size = max_size;
+        self.resize();
+        self.cur_seek_idx = 0;
+        self.to_shrink.extend(self.preview.drain());
+    }
+
+    fn resize(&mut self) {
+        let (width, height) = self.media_size;
+        if width <= 0 || height <= 0 {
+            self.size = (self.max_size, self.max_size);
+        } else {
+            let scale_factor =
+                (self.max_size as f64 / width as f64).min(self.max_size as f64 / height as f64);
+            self.size.0 = (width as f64 * scale_factor).round() as _;
+            self.size.1 = (height as f64 * scale_factor).round() as _;
+        }
+        self.size_changed = true;
     }
 
     pub fn update(&mut self, gl: &glow::Context) {
@@ -77,28 +129,8 @@ impl Preview {
                             {
                                 let width: i64 = self.mpv.handle.get_property("width")?;
                                 let height: i64 = self.mpv.handle.get_property("height")?;
-
-                                let scale_factor = (self.max_size as f64 / width as f64)
-                                    .min(self.max_size as f64 / height as f64);
-                                self.size.0 = (width as f64 * scale_factor).round() as _;
-                                self.size.1 = (height as f64 * scale_factor).round() as _;
-
-                                unsafe {
-                                    gl.bind_texture(glow::TEXTURE_2D, Some(self.tex));
-                                    gl.tex_image_2d(
-                                        glow::TEXTURE_2D,
-                                        0,
-                                        glow::SRGB8_ALPHA8 as _,
-                                        self.size.0 as _,
-                                        self.size.1 as _,
-                                        0,
-                                        glow::RGBA,
-                                        glow::UNSIGNED_BYTE,
-                                        glow::PixelUnpackData::Slice(None),
-                                    );
-                                    gl.bind_texture(glow::TEXTURE_2D, None);
-                                    eframe::egui_glow::check_for_gl_error!(gl);
-                                }
+                                self.media_size = (width, height);
+                                self.resize();
                             }
                         );
                     }
@@ -124,6 +156,46 @@ impl Preview {
             }
         }
 
+        if std::mem::take(&mut self.size_changed) {
+            unsafe {
+                gl.bind_texture(glow::TEXTURE_2D, Some(self.tex));
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    0,
+                    glow::SRGB8_ALPHA8 as _,
+                    self.size.0 as _,
+                    self.size.1 as _,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    glow::PixelUnpackData::Slice(None),
+                );
+                gl.bind_texture(glow::TEXTURE_2D, None);
+                eframe::egui_glow::check_for_gl_error!(gl);
+            }
+        }
+
+        self.to_shrink.extend(self.preview.evict_over_capacity());
+        for tex in std::mem::take(&mut self.to_shrink) {
+            // Textures are kept since they are registered to egui, only their memory is freed
+            unsafe {
+                gl.bind_texture(glow::TEXTURE_2D, Some(tex));
+                gl.tex_image_2d(
+                    glow::TEXTURE_2D,
+                    0,
+                    glow::SRGB8_ALPHA8 as _,
+                    1,
+                    1,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    glow::PixelUnpackData::Slice(None),
+                );
+                gl.bind_texture(glow::TEXTURE_2D, None);
+            }
+            self.spare.push(tex);
+        }
+
         if self.mpv.consume_need_update_flag() {
             if let Err(err) = self.mpv.render_ctx.render::<glow::Context>(
                 self.fbo.0.get() as _,
@@ -140,14 +212,15 @@ impl Preview {
 
             let idx = self.update_idx - 1;
 
-            self.preview
-                .entry(idx)
-                .or_insert_with(|| (false, unsafe { get_texture(gl).unwrap() }));
-
-            let (ready, tex) = self.preview.get_mut(&idx).unwrap();
+            let spare = &mut self.spare;
+            let thumbnail = self.preview.insert_with(idx, || {
+                spare
+                    .pop()
+                    .unwrap_or_else(|| unsafe { get_texture(gl).unwrap() })
+            });
 
             unsafe {
-                gl.bind_texture(glow::TEXTURE_2D, Some(*tex));
+                gl.bind_texture(glow::TEXTURE_2D, Some(thumbnail.value));
                 gl.tex_image_2d(
                     glow::TEXTURE_2D,
                     0,
@@ -174,7 +247,7 @@ impl Preview {
                 gl.bind_texture(glow::TEXTURE_2D, None);
             }
 
-            *ready = true;
+            thumbnail.ready = true;
         }
     }
 
@@ -188,32 +261,190 @@ impl Preview {
         {
             log::error!("preview set media '{media_path}' fails: {err}");
         }
+        self.loaded = true;
+    }
+
+    /// Stops the loaded media, for a media which is not previewed
+    pub fn unload(&mut self) {
+        self.clear();
+
+        if self.loaded
+            && let Err(err) = self.mpv.handle.command("stop", &[])
+        {
+            log::error!("preview stop fails: {err}");
+        }
+        self.loaded = false;
     }
 
     pub fn size(&self) -> (i64, i64) {
         self.size
     }
 
-    pub fn get(&mut self, playback_time: f64) -> Option<&glow::Texture> {
+    /// The preview at `playback_time`, or the nearest cached one while it is loading
+    pub fn get(&mut self, playback_time: f64) -> Option<glow::Texture> {
+        if !self.loaded {
+            return None;
+        }
+
         let idx = (playback_time / self.interval) as u64;
 
-        if let Some((true, tex)) = self.preview.get(&idx) {
+        if let Some(tex) = self.preview.get_ready(idx) {
             return Some(tex);
         }
 
-        if self.cur_seek_idx == idx + 1 {
-            return None;
+        if self.cur_seek_idx != idx + 1 {
+            self.cur_seek_idx = idx + 1;
+
+            if let Err(err) = self.mpv.handle.command_async(
+                self.cur_seek_idx,
+                &["seek", &playback_time.to_string(), "absolute"],
+            ) {
+                log::error!("preview seek {playback_time} fails: {err}");
+            }
+        }
+
+        self.preview.nearest_ready(idx)
+    }
+}
+
+struct Entry<T> {
+    value: T,
+    ready: bool,
+    last_used: u64,
+}
+
+/// Previews by the index of their interval, the least recently used ones are evicted first
+struct Cache<T> {
+    entries: BTreeMap<u64, Entry<T>>,
+    capacity: usize,
+
+    /// Increased by each use, to find the least recently used entry
+    clock: u64,
+}
+
+impl<T: Copy> Cache<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            capacity,
+            clock: 0,
+        }
+    }
+
+    fn get_ready(&mut self, idx: u64) -> Option<T> {
+        let entry = self.entries.get_mut(&idx).filter(|entry| entry.ready)?;
+        self.clock += 1;
+        entry.last_used = self.clock;
+        Some(entry.value)
+    }
+
+    /// The ready entry nearest to `idx`, the earlier one if both sides are as near
+    fn nearest_ready(&self, idx: u64) -> Option<T> {
+        let before = self
+            .entries
+            .range(..=idx)
+            .rev()
+            .find(|(_, entry)| entry.ready);
+        let after = self.entries.range(idx + 1..).find(|(_, entry)| entry.ready);
+
+        match (before, after) {
+            (Some((b, before)), Some((a, after))) => {
+                Some(if idx - b <= a - idx { before } else { after }.value)
+            }
+            (Some((_, entry)), None) | (None, Some((_, entry))) => Some(entry.value),
+            (None, None) => None,
         }
+    }
+
+    /// The entry of `idx`, a new one takes the value of the least recently used entry
+    /// if the cache is full, otherwise it's made by `make`
+    fn insert_with(&mut self, idx: u64, make: impl FnOnce() -> T) -> &mut Entry<T> {
+        self.clock += 1;
 
-        self.cur_seek_idx = idx + 1;
+        if !self.entries.contains_key(&idx) {
+            let value = if self.entries.len() >= self.capacity {
+                self.pop_least_recently_used().unwrap()
+            } else {
+                make()
+            };
 
-        if let Err(err) = self.mpv.handle.command_async(
-            self.cur_seek_idx,
-            &["seek", &playback_time.to_string(), "absolute"],
-        ) {
-            log::error!("preview seek {playback_time} fails: {err}");
+            self.entries.insert(
+                idx,
+                Entry {
+                    value,
+                    ready: false,
+                    last_used: 0,
+                },
+            );
         }
 
-        None
+        let entry = self.entries.get_mut(&idx).unwrap();
+        entry.last_used = self.clock;
+        entry
+    }
+
+    fn pop_least_recently_used(&mut self) -> Option<T> {
+        let idx = *self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)?
+            .0;
+        self.entries.remove(&idx).map(|entry| entry.value)
+    }
+
+    /// Values of the entries over the capacity
+    fn evict_over_capacity(&mut self) -> Vec<T> {
+        let over = self.entries.len().saturating_sub(self.capacity);
+        (0..over)
+            .filter_map(|_| self.pop_least_recently_used())
+            .collect()
+    }
+
+    fn drain(&mut self) -> impl Iterator<Item = T> + use<T> {
+        std::mem::take(&mut self.entries)
+            .into_values()
+            .map(|entry| entry.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ready(cache: &mut Cache<u32>, idx: u64, value: u32) {
+        cache.insert_with(idx, || value).ready = true;
+    }
+
+    #[test]
+    fn least_recently_used_is_reused() {
+        let mut cache = Cache::new(2);
+        ready(&mut cache, 1, 10);
+        ready(&mut cache, 2, 20);
+        assert_eq!(cache.get_ready(1), Some(10));
+
+        // Full, the value of 2 is taken instead of a new one
+        ready(&mut cache, 3, 30);
+        assert_eq!(cache.get_ready(3), Some(20));
+        assert_eq!(cache.get_ready(2), None);
+        assert_eq!(cache.get_ready(1), Some(10));
+
+        cache.capacity = 1;
+        assert_eq!(cache.evict_over_capacity(), [20]);
+        assert_eq!(cache.drain().collect::<Vec<_>>(), [10]);
+    }
+
+    #[test]
+    fn nearest_ready_while_loading() {
+        let mut cache = Cache::new(10);
+        ready(&mut cache, 10, 1);
+        ready(&mut cache, 20, 2);
+        cache.insert_with(14, || 3);
+
+        assert_eq!(cache.get_ready(14), None);
+        assert_eq!(cache.nearest_ready(14), Some(1));
+        assert_eq!(cache.nearest_ready(15), Some(1));
+        assert_eq!(cache.nearest_ready(16), Some(2));
+        assert_eq!(cache.nearest_ready(99), Some(2));
+        assert_eq!(Cache::<u32>::new(1).nearest_ready(0), None);
     }
 }
@@ -1,14 +1,39 @@
 use crate::mpv;
 use eapp_utils::natordset::NatOrdSet;
-use eframe::egui::ahash::HashMap;
+use eframe::egui::ahash::{HashMap, HashSet};
 use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
 use walkdir::WalkDir;
 
 #[derive(Deserialize, Serialize, Default, Debug)]
 pub struct Playlist {
     #[serde(skip)]
     current_play: Option<(String, String)>,
+    #[serde(skip)]
+    current_changed: bool,
+    #[serde(skip)]
+    generation: u64,
     map: HashMap<String, NatOrdSet>,
+
+    /// lists whose group is expanded in the playlist panel
+    #[serde(default)]
+    expanded: HashSet<String>,
+
+    /// lists in the order they are shown and played, see [`Playlist::lists`]
+    #[serde(default)]
+    order: Vec<String>,
+
+    /// names given to groups instead of their folder name
+    #[serde(default)]
+    names: HashMap<String, String>,
+
+    /// named blocks of mpv options, in the same syntax as the global ones
+    #[serde(default)]
+    profiles: BTreeMap<String, String>,
+
+    /// profiles assigned to items and lists, see [`Playlist::profile_for`]
+    #[serde(default)]
+    assigned_profiles: HashMap<String, String>,
 }
 
 impl Playlist {
@@ -17,7 +42,7 @@ impl Playlist {
             return;
         }
 
-        let mut set = NatOrdSet::new();
+        let mut items = Vec::new();
 
         eapp_utils::capture_error!(
             err => log::error!("playlist add list '{list}' fails: {err}"),
@@ -32,14 +57,16 @@ impl Playlist {
                             mpv::VIDEO_FORMATS.contains(&ext.as_str()) || mpv::AUDIO_FORMATS.contains(&ext.as_str())
                         });
                     if is_valid {
-                        set.push(item_path.to_string_lossy().into_owned());
+                        items.push(item_path.to_string_lossy().into_owned());
                     }
                 }
             }
         );
 
-        set.sort();
-        self.map.insert(list, set);
+        self.order.retain(|v| *v != list);
+        self.order.push(list.clone());
+        self.map.insert(list, NatOrdSet::from(items));
+        self.generation += 1;
     }
 
     pub fn remove_list(&mut self, list: &str) {
@@ -52,6 +79,69 @@ impl Playlist {
         }
 
         self.map.remove(list);
+        self.expanded.remove(list);
+        self.order.retain(|v| v != list);
+        self.names.remove(list);
+        self.generation += 1;
+        self.assigned_profiles
+            .retain(|path, _| !Path::new(path).starts_with(list));
+    }
+
+    /// Lists in order, the ones saved by older versions follow in name order
+    pub fn lists(&self) -> Vec<&str> {
+        let mut lists: Vec<&str> = self
+            .order
+            .iter()
+            .filter(|list| self.map.contains_key(*list))
+            .map(String::as_str)
+            .collect();
+
+        let mut rest: Vec<&str> = self
+            .map
+            .keys()
+            .filter(|list| !self.order.contains(list))
+            .map(String::as_str)
+            .collect();
+        rest.sort_unstable();
+
+        lists.extend(rest);
+        lists
+    }
+
+    /// Moves `list` by `delta` places in the order
+    pub fn move_list(&mut self, list: &str, delta: isize) {
+        self.order = self.lists().into_iter().map(str::to_owned).collect();
+        let Some(idx) = self.order.iter().position(|v| v == list) else {
+            return;
+        };
+
+        let new_idx = idx.saturating_add_signed(delta).min(self.order.len() - 1);
+        let list = self.order.remove(idx);
+        self.order.insert(new_idx, list);
+    }
+
+    /// The name of the group, which is the folder name unless it's renamed
+    pub fn name<'a>(&'a self, list: &'a str) -> &'a str {
+        self.names.get(list).map_or_else(
+            || {
+                Path::new(list)
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or(list)
+            },
+            String::as_str,
+        )
+    }
+
+    /// Renames the group, an empty name resets it to the folder name
+    pub fn set_name(&mut self, list: &str, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            self.names.remove(list);
+        } else if self.map.contains_key(list) {
+            self.names.insert(list.to_owned(), name.to_owned());
+        }
+        self.generation += 1;
     }
 
     pub fn set_current_play(&mut self, list_and_media: Option<(String, String)>) {
@@ -59,6 +149,9 @@ impl Playlist {
             if let Some(media_set) = self.map.get(&list)
                 && media_set.search(&media).is_ok()
             {
+                if self.current_play() != Some((&list, &media)) {
+                    self.current_changed = true;
+                }
                 self.current_play = Some((list, media));
             }
         } else {
@@ -72,42 +165,214 @@ impl Playlist {
             .map(|(list, media)| (list.as_str(), media.as_str()))
     }
 
+    /// The item after the current play, going on to the next group after the last one
     pub fn next_item(&mut self) -> Option<String> {
+        self.step_item(1)
+    }
+
+    /// The item before the current play, going back to the previous group before the first one
+    pub fn prev_item(&mut self) -> Option<String> {
+        self.step_item(-1)
+    }
+
+    fn step_item(&mut self, delta: isize) -> Option<String> {
         let (list, media) = self.current_play.clone()?;
-        let media_set = &self.map[&list];
 
-        let next_idx = match media_set.search(&media) {
-            Ok(media_idx) => (media_idx + 1) % media_set.0.len(),
-            _ => 0,
+        // all items of all lists in order, as (list, media)
+        let items: Vec<(&str, &String)> = self
+            .lists()
+            .into_iter()
+            .flat_map(|list| self.map[list].iter().map(move |media| (list, media)))
+            .collect();
+        if items.is_empty() {
+            return None;
+        }
+
+        let next_idx = match items.iter().position(|&(l, m)| l == list && *m == media) {
+            Some(idx) => (idx as isize + delta).rem_euclid(items.len() as isize) as usize,
+            None => 0,
         };
 
-        let next = media_set.0[next_idx].clone();
+        let (list, next) = items[next_idx];
+        let (list, next) = (list.to_owned(), next.to_owned());
 
         self.set_current_play(Some((list, next.clone())));
         Some(next)
     }
 
-    pub fn prev_item(&mut self) -> Option<String> {
-        let (list, media) = self.current_play.clone()?;
-        let media_set = &self.map[&list];
-
-        let prev_idx = match media_set.search(&media) {
-            Ok(media_idx) => {
-                if media_idx != 0 {
-                    media_idx - 1
-                } else {
-                    media_set.0.len() - 1
-                }
-            }
-            _ => 0,
-        };
-        let prev = media_set.0[prev_idx].clone();
+    /// Returns whether the current play changed since last call
+    pub fn consume_current_changed_flag(&mut self) -> bool {
+        std::mem::take(&mut self.current_changed)
+    }
+
+    pub fn is_expanded(&self, list: &str) -> bool {
+        self.expanded.contains(list)
+    }
+
+    pub fn set_expanded(&mut self, list: &str, expanded: bool) {
+        if expanded {
+            self.expanded.insert(list.to_owned());
+        } else {
+            self.expanded.remove(list);
+        }
+    }
 
-        self.set_current_play(Some((list, prev.clone())));
-        Some(prev)
+    /// Changes whenever a list is added, removed or renamed
+    pub fn generation(&self) -> u64 {
+        self.generation
     }
 
     pub fn inner_map(&self) -> &HashMap<String, NatOrdSet> {
         &self.map
     }
+
+    pub fn profiles(&self) -> &BTreeMap<String, String> {
+        &self.profiles
+    }
+
+    pub fn profile_options_mut(&mut self, name: &str) -> Option<&mut String> {
+        self.profiles.get_mut(name)
+    }
+
+    /// Adds an empty profile with an unused name, which is returned
+    pub fn add_profile(&mut self) -> String {
+        let name = (1..)
+            .map(|i| format!("Profile {i}"))
+            .find(|name| !self.profiles.contains_key(name))
+            .unwrap();
+        self.profiles.insert(name.clone(), String::new());
+        name
+    }
+
+    /// Renames the profile and where it's assigned, fails if `new` is taken
+    pub fn rename_profile(&mut self, old: &str, new: &str) -> bool {
+        if self.profiles.contains_key(new) {
+            return false;
+        }
+        let Some(options) = self.profiles.remove(old) else {
+            return false;
+        };
+
+        self.profiles.insert(new.to_owned(), options);
+        for name in self.assigned_profiles.values_mut() {
+            if name == old {
+                *name = new.to_owned();
+            }
+        }
+        true
+    }
+
+    /// Removes the profile, and unassigns it
+    pub fn remove_profile(&mut self, name: &str) {
+        self.profiles.remove(name);
+        self.assigned_profiles.retain(|_, v| v != name);
+    }
+
+    /// The profile assigned to the item or list at `path` itself
+    pub fn assigned_profile(&self, path: &str) -> Option<&str> {
+        self.assigned_profiles.get(path).map(String::as_str)
+    }
+
+    /// Assigns a profile to the item or list at `path`, `None` unassigns it
+    pub fn assign_profile(&mut self, path: &str, name: Option<&str>) {
+        match name {
+            Some(name) if self.profiles.contains_key(name) => {
+                self.assigned_profiles
+                    .insert(path.to_owned(), name.to_owned());
+            }
+            _ => {
+                self.assigned_profiles.remove(path);
+            }
+        }
+    }
+
+    /// The options of the profile for `media`, which is the one assigned to it,
+    /// or else to the nearest directory containing it
+    pub fn profile_for(&self, media: &str) -> Option<&str> {
+        let media = Path::new(media);
+        self.assigned_profiles
+            .iter()
+            .filter(|(path, _)| media.starts_with(path))
+            .max_by_key(|(path, _)| path.len())
+            .and_then(|(_, name)| self.profiles.get(name))
+            .map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playlist(lists: &[(&str, &[&str])]) -> Playlist {
+        let mut playlist = Playlist::default();
+        for (list, items) in lists {
+            let items: Vec<_> = items.iter().map(|item| format!("{list}/{item}")).collect();
+            playlist
+                .map
+                .insert(list.to_string(), NatOrdSet::from(items));
+            playlist.order.push(list.to_string());
+        }
+        playlist
+    }
+
+    #[test]
+    fn next_and_prev_go_across_groups() {
+        let mut playlist = playlist(&[("b", &["1", "2"]), ("empty", &[]), ("a", &["1"])]);
+        playlist.set_current_play(Some(("b".to_owned(), "b/2".to_owned())));
+
+        assert_eq!(playlist.next_item().as_deref(), Some("a/1"));
+        assert_eq!(playlist.current_play(), Some(("a", "a/1")));
+        assert_eq!(playlist.next_item().as_deref(), Some("b/1"));
+        assert_eq!(playlist.prev_item().as_deref(), Some("a/1"));
+    }
+
+    #[test]
+    fn profile_of_item_overrides_its_list() {
+        let mut playlist = playlist(&[("tv", &["1", "2"]), ("movie", &["1"])]);
+        playlist
+            .profiles
+            .insert("interlaced".to_owned(), "deinterlace=yes".to_owned());
+        playlist
+            .profiles
+            .insert("hdr".to_owned(), "tone-mapping=bt.2446a".to_owned());
+
+        playlist.assign_profile("tv", Some("interlaced"));
+        playlist.assign_profile("tv/2", Some("hdr"));
+        playlist.assign_profile("movie", Some("missing"));
+
+        assert_eq!(playlist.profile_for("tv/1"), Some("deinterlace=yes"));
+        assert_eq!(playlist.profile_for("tv/2"), Some("tone-mapping=bt.2446a"));
+        assert_eq!(playlist.profile_for("tvshow/1"), None);
+        assert_eq!(playlist.profile_for("movie/1"), None);
+
+        assert!(playlist.rename_profile("interlaced", "old tv"));
+        assert!(!playlist.rename_profile("old tv", "hdr"));
+        assert_eq!(playlist.assigned_profile("tv"), Some("old tv"));
+
+        playlist.remove_profile("hdr");
+        assert_eq!(playlist.profile_for("tv/2"), Some("deinterlace=yes"));
+
+        playlist.remove_list("tv");
+        assert_eq!(playlist.assigned_profile("tv"), None);
+    }
+
+    #[test]
+    fn lists_keep_their_order() {
+        let mut playlist = playlist(&[("b", &["1"]), ("a", &["1"])]);
+        playlist.map.insert("old".to_owned(), NatOrdSet::default());
+        assert_eq!(playlist.lists(), ["b", "a", "old"]);
+
+        playlist.move_list("old", -2);
+        assert_eq!(playlist.lists(), ["old", "b", "a"]);
+        playlist.move_list("old", 5);
+        assert_eq!(playlist.lists(), ["b", "a", "old"]);
+
+        playlist.set_name("a", "  Series  ");
+        assert_eq!(playlist.name("a"), "Series");
+        assert_eq!(playlist.name("/videos/b"), "b");
+
+        playlist.remove_list("a");
+        assert_eq!(playlist.lists(), ["b", "old"]);
+        assert_eq!(playlist.name("a"), "a");
+    }
 }
@@ -0,0 +1,332 @@
+//! Cues of the current subtitle track, listed in the playlist panel
+
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct Subtitles {
+    /// Media path and subtitle track id the cues are for
+    key: Option<(String, Option<i64>)>,
+
+    /// Sorted by start time
+    cues: Vec<Cue>,
+
+    /// Cues of embedded tracks can't be read ahead, they are collected while playing
+    embedded: bool,
+
+    notice: Option<String>,
+}
+
+impl Subtitles {
+    /// Loads the cues if the media or the track changed,
+    /// `track` is the id of the track and its file, which is `None` if it's embedded
+    pub fn sync(&mut self, media_path: &str, track: Option<(i64, Option<&str>)>) {
+        let key = (media_path.to_owned(), track.map(|(id, _)| id));
+        if self.key.as_ref() == Some(&key) {
+            return;
+        }
+        self.key = Some(key);
+        self.cues.clear();
+        self.embedded = false;
+        self.notice = None;
+
+        match track {
+            None => self.notice = Some("No subtitle track".to_owned()),
+            Some((_, Some(path))) => match load(path) {
+                Ok(cues) => {
+                    self.cues = cues;
+                    if self.cues.is_empty() {
+                        self.notice = Some("No text cues in the subtitle file".to_owned());
+                    }
+                }
+                Err(err) => {
+                    log::error!("load subtitle '{path}' fails: {err}");
+                    self.notice = Some(format!("Cannot load the subtitle file: {err}"));
+                }
+            },
+            Some((_, None)) => {
+                self.embedded = true;
+                self.notice = Some("Embedded track, lines are collected while playing".to_owned());
+            }
+        }
+    }
+
+    pub fn is_embedded(&self) -> bool {
+        self.embedded
+    }
+
+    /// Adds a cue sampled from the player, only for embedded tracks
+    pub fn sample(&mut self, cue: Cue) {
+        if !self.embedded || cue.text.is_empty() {
+            return;
+        }
+
+        let idx = self.cues.partition_point(|c| c.start < cue.start);
+        if self.cues[idx..]
+            .iter()
+            .take_while(|c| c.start == cue.start)
+            .any(|c| c.text == cue.text)
+        {
+            return;
+        }
+        self.cues.insert(idx, cue);
+    }
+
+    pub fn cues(&self) -> &[Cue] {
+        &self.cues
+    }
+
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_deref()
+    }
+
+    /// The cue shown at `time`, the latest started one if they overlap
+    pub fn current(&self, time: f64) -> Option<usize> {
+        let idx = self
+            .cues
+            .partition_point(|c| c.start <= time)
+            .checked_sub(1)?;
+        (time < self.cues[idx].end).then_some(idx)
+    }
+}
+
+/// Parses a subtitle file by its extension, `ass`/`ssa` or `srt`/`vtt`
+pub fn load(path: &str) -> std::io::Result<Vec<Cue>> {
+    let bytes = std::fs::read(path)?;
+    let content = String::from_utf8_lossy(&bytes);
+    let content = content.trim_start_matches('\u{feff}');
+
+    let ext = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase());
+
+    match ext.as_deref() {
+        Some("ass" | "ssa") => Ok(parse_ass(content)),
+        Some("srt" | "vtt") => Ok(parse_srt(content)),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "only srt, vtt, ass and ssa are supported",
+        )),
+    }
+}
+
+/// Parses SubRip, WebVTT is parsed as well since its cues look the same
+pub fn parse_srt(content: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut lines = content.lines().map(str::trim_end);
+
+    while let Some(line) = lines.next() {
+        let Some((start, end)) = line.split_once("-->") else {
+            continue;
+        };
+
+        // WebVTT puts cue settings after the end time
+        let end = end.split_whitespace().next().unwrap_or_default();
+        let (Some(start), Some(end)) = (parse_time(start.trim()), parse_time(end)) else {
+            continue;
+        };
+
+        let text: Vec<_> = lines
+            .by_ref()
+            .take_while(|line| !line.is_empty())
+            .map(strip_tags)
+            .collect();
+
+        cues.push(Cue {
+            start,
+            end,
+            text: text.join("\n"),
+        });
+    }
+
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    cues
+}
+
+/// Parses the `Dialogue` lines of the `[Events]` section of SubStation Alpha
+pub fn parse_ass(content: &str) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    let mut in_events = false;
+
+    // Default order of the fields in ASS, replaced by the `Format` line
+    let mut fields: Vec<String> = [
+        "layer", "start", "end", "style", "name", "marginl", "marginr", "marginv", "effect", "text",
+    ]
+    .map(str::to_owned)
+    .to_vec();
+
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+
+        let Some((kind, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        match kind.trim() {
+            "Format" => {
+                fields = value
+                    .split(',')
+                    .map(|field| field.trim().to_lowercase())
+                    .collect();
+            }
+            "Dialogue" => {
+                let values: Vec<_> = value.trim_start().splitn(fields.len(), ',').collect();
+                let field = |name: &str| {
+                    let idx = fields.iter().position(|field| field == name)?;
+                    values.get(idx).copied()
+                };
+
+                let (Some(start), Some(end), Some(text)) = (
+                    field("start").and_then(|v| parse_time(v.trim())),
+                    field("end").and_then(|v| parse_time(v.trim())),
+                    field("text"),
+                ) else {
+                    continue;
+                };
+
+                let text = strip_ass_overrides(text);
+                if !text.is_empty() {
+                    cues.push(Cue { start, end, text });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    cues
+}
+
+/// Seconds of `[[h:]m:]s[.,]fraction`
+fn parse_time(str: &str) -> Option<f64> {
+    let mut secs = 0.0;
+    for part in str.split(':') {
+        let part: f64 = part.replace(',', ".").parse().ok()?;
+        secs = secs * 60.0 + part;
+    }
+    Some(secs)
+}
+
+/// Removes html-like tags such as `<i>`, which srt allows
+fn strip_tags(line: &str) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => (),
+        }
+    }
+    text
+}
+
+/// Removes the `{...}` override blocks and turns the escapes into plain text
+fn strip_ass_overrides(text: &str) -> String {
+    let mut plain = String::with_capacity(text.len());
+    let mut in_block = false;
+    for c in text.chars() {
+        match c {
+            '{' => in_block = true,
+            '}' if in_block => in_block = false,
+            _ if !in_block => plain.push(c),
+            _ => (),
+        }
+    }
+
+    plain
+        .replace("\\N", "\n")
+        .replace("\\n", "\n")
+        .replace("\\h", " ")
+        .trim()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_srt_cues() {
+        let content = "1\r\n00:00:01,500 --> 00:00:03,000\r\n<i>Hello</i>\r\nworld\r\n\r\n\
+                       2\r\n00:01:02,000 --> 00:01:04,250\r\nBye\r\n";
+        let cues = parse_srt(content);
+
+        assert_eq!(
+            cues,
+            [
+                Cue {
+                    start: 1.5,
+                    end: 3.0,
+                    text: "Hello\nworld".to_owned()
+                },
+                Cue {
+                    start: 62.0,
+                    end: 64.25,
+                    text: "Bye".to_owned()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_vtt_cues() {
+        let content = "WEBVTT\n\n00:05.000 --> 00:06.000 align:start\nShort\n";
+        let cues = parse_srt(content);
+        assert_eq!(cues.len(), 1);
+        assert_eq!((cues[0].start, cues[0].end), (5.0, 6.0));
+        assert_eq!(cues[0].text, "Short");
+    }
+
+    #[test]
+    fn parse_ass_dialogues() {
+        let content = "[Script Info]\nTitle: test\n\n[Events]\n\
+             Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+             Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,ignored\n\
+             Dialogue: 0,0:00:10.50,0:00:12.00,Default,,0,0,0,,{\\i1}One, two{\\i0}\\Nthree\n\
+             Dialogue: 0,0:00:02.00,0:00:03.00,Default,,0,0,0,,{\\pos(1,1)}\n";
+        let cues = parse_ass(content);
+
+        assert_eq!(
+            cues,
+            [Cue {
+                start: 10.5,
+                end: 12.0,
+                text: "One, two\nthree".to_owned()
+            }]
+        );
+    }
+
+    #[test]
+    fn current_cue() {
+        let mut subtitles = Subtitles::default();
+        subtitles.sync("media", Some((1, None)));
+        for (start, end) in [(5.0, 7.0), (1.0, 2.0), (6.0, 8.0)] {
+            subtitles.sample(Cue {
+                start,
+                end,
+                text: "line".to_owned(),
+            });
+        }
+
+        assert_eq!(subtitles.cues().len(), 3);
+        assert_eq!(subtitles.current(0.5), None);
+        assert_eq!(subtitles.current(1.0), Some(0));
+        assert_eq!(subtitles.current(3.0), None);
+        assert_eq!(subtitles.current(6.5), Some(2));
+        assert_eq!(subtitles.current(8.0), None);
+    }
+}